@@ -25,7 +25,7 @@ fn create_evaluator(policy: &str, data: &str) -> ReapAstEvaluator {
 
 fn eval(evaluator: &ReapAstEvaluator, principal: &str) -> PolicyAction {
     let mut ctx = HashMap::new();
-    ctx.insert("principal".to_string(), principal.to_string());
+    ctx.insert("principal".to_string(), principal.into());
     evaluator
         .evaluate(&PolicyRequest {
             resource: "res".to_string(),
@@ -105,7 +105,7 @@ fn generate_data(num_entities: usize) -> String {
 
 fn make_request(principal: &str, action: &str, resource: &str) -> PolicyRequest {
     let mut ctx = HashMap::new();
-    ctx.insert("principal".to_string(), principal.to_string());
+    ctx.insert("principal".to_string(), principal.into());
     PolicyRequest {
         resource: resource.to_string(),
         action: action.to_string(),
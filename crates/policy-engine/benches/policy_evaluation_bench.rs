@@ -268,11 +268,8 @@ fn benchmark_realistic_workloads(c: &mut Criterion) {
             resource: resources[i % resources.len()].to_string(),
             action: actions[i % actions.len()].to_string(),
             context: HashMap::from([
-                (
-                    "service".to_string(),
-                    services[i % services.len()].to_string(),
-                ),
-                ("user_id".to_string(), format!("user-{}", i)),
+                ("service".to_string(), services[i % services.len()].into()),
+                ("user_id".to_string(), format!("user-{}", i).into()),
             ]),
 
             ..Default::default()
@@ -95,7 +95,7 @@ policy rebac_bench {
 
 fn request(principal: &str, resource: &str) -> PolicyRequest {
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), principal.to_string());
+    context.insert("principal".to_string(), principal.into());
     PolicyRequest {
         resource: resource.to_string(),
         action: "read".to_string(),
@@ -40,7 +40,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         let resource_id = format!("resource_{}", i % 100);
 
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), user_id);
+        context.insert("principal".to_string(), user_id.into());
 
         let request = PolicyRequest {
             resource: resource_id,
@@ -102,7 +102,7 @@ fn benchmark_scenario(name: &str, policy_path: &str, data_path: &str, iterations
             let user_idx = i % user_ids.len();
             let action_idx = i % actions.len();
             let mut ctx = HashMap::new();
-            ctx.insert("principal".to_string(), user_ids[user_idx].clone());
+            ctx.insert("principal".to_string(), user_ids[user_idx].clone().into());
             PolicyRequest {
                 resource: resource_id.to_string(),
                 action: actions[action_idx].to_string(),
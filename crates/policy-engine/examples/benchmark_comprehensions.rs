@@ -103,7 +103,7 @@ fn benchmark_set_comprehension(store: Arc<DataStore>, count: usize) -> u128 {
     let evaluator = policy.build_ast_evaluator(store);
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "test_user".to_string());
+    context.insert("principal".to_string(), "test_user".into());
 
     let request = PolicyRequest {
         resource: "resource1".to_string(),
@@ -154,7 +154,7 @@ fn benchmark_array_comprehension(store: Arc<DataStore>, count: usize) -> u128 {
     let evaluator = policy.build_ast_evaluator(store);
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "test_user".to_string());
+    context.insert("principal".to_string(), "test_user".into());
 
     let request = PolicyRequest {
         resource: "resource1".to_string(),
@@ -203,7 +203,7 @@ fn benchmark_object_comprehension(store: Arc<DataStore>, count: usize) -> u128 {
     let evaluator = policy.build_ast_evaluator(store);
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "test_user".to_string());
+    context.insert("principal".to_string(), "test_user".into());
 
     let request = PolicyRequest {
         resource: "resource1".to_string(),
@@ -34,7 +34,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut contexts = Vec::new();
     for role in &["admin", "user", "guest"] {
         let mut context = HashMap::new();
-        context.insert("role".to_string(), role.to_string());
+        context.insert("role".to_string(), (*role).into());
         contexts.push(context);
     }
 
@@ -250,7 +250,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         if let (Some(p_entity), Some(r_entity)) = (principal_entity, resource_entity) {
             // Build context with entity attributes
             let mut context = HashMap::new();
-            context.insert("principal".to_string(), principal.to_string());
+            context.insert("principal".to_string(), principal.into());
 
             // Add principal attributes to context
             for (key_id, value) in &p_entity.attributes {
@@ -263,7 +263,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     policy_engine::AttributeValue::Bool(b) => b.to_string(),
                     _ => continue,
                 };
-                context.insert(format!("principal.{}", key), value_str);
+                context.insert(format!("principal.{}", key), value_str.into());
             }
 
             // Add resource attributes to context
@@ -277,7 +277,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     policy_engine::AttributeValue::Bool(b) => b.to_string(),
                     _ => continue,
                 };
-                context.insert(format!("resource.{}", key), value_str);
+                context.insert(format!("resource.{}", key), value_str.into());
             }
 
             let request = PolicyRequest {
@@ -90,7 +90,7 @@ fn main() {
 
     // --- measured (2): the FIRST evaluation (nothing primed before it). -----
     let mut context = std::collections::HashMap::new();
-    context.insert("principal".to_string(), "alice".to_string());
+    context.insert("principal".to_string(), "alice".into());
     let request = PolicyRequest {
         resource: "doc-1".to_string(),
         action: "read".to_string(),
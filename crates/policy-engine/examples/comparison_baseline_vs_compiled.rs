@@ -74,9 +74,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Create test request
     let mut context = HashMap::new();
-    context.insert("role".to_string(), "user".to_string());
-    context.insert("department".to_string(), "eng".to_string());
-    context.insert("active".to_string(), "true".to_string());
+    context.insert("role".to_string(), "user".into());
+    context.insert("department".to_string(), "eng".into());
+    context.insert("active".to_string(), "true".into());
 
     let request = PolicyRequest {
         resource: "/api/resource_5".to_string(),
@@ -157,8 +157,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Provide static context for partial evaluation
     let mut static_context = HashMap::new();
-    static_context.insert("department".to_string(), "eng".to_string());
-    static_context.insert("active".to_string(), "true".to_string());
+    static_context.insert("department".to_string(), "eng".into());
+    static_context.insert("active".to_string(), "true".into());
 
     let optimized_evaluator = CompiledPolicyEvaluator::compile(&policy, Some(&static_context))?;
     let optimized_evaluator = Arc::new(optimized_evaluator) as Arc<dyn PolicyEvaluator>;
@@ -226,7 +226,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("Evaluating access for Alice (role: {}):", role);
 
     let mut context = HashMap::new();
-    context.insert("role".to_string(), role.to_string());
+    context.insert("role".to_string(), role.to_string().into());
 
     let request = PolicyRequest {
         resource: "admin/dashboard".to_string(),
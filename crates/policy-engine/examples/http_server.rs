@@ -135,7 +135,7 @@ async fn handle_evaluate(
 
     // Build request with principal in context
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), req.principal);
+    context.insert("principal".to_string(), req.principal.into());
 
     let policy_req = PolicyRequest {
         resource: req.resource,
@@ -189,7 +189,7 @@ async fn handle_batch_evaluate(
 
         // Build request with principal in context
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), req.principal);
+        context.insert("principal".to_string(), req.principal.into());
 
         let policy_req = PolicyRequest {
             resource: req.resource,
@@ -314,7 +314,7 @@ async fn handle_socket(mut socket: WebSocket, state: Arc<AppState>) {
 
             // Build request with principal in context
             let mut context = HashMap::new();
-            context.insert("principal".to_string(), req.principal);
+            context.insert("principal".to_string(), req.principal.into());
 
             let policy_req = PolicyRequest {
                 resource: req.resource,
@@ -254,7 +254,7 @@ fn run_test(
     let mut context = HashMap::new();
     context.insert(
         "principal".to_string(),
-        format!("user_{}", entity_count / 4),
+        format!("user_{}", entity_count / 4).into(),
     );
     let request = PolicyRequest {
         resource: format!("doc_{}", entity_count / 4),
@@ -279,7 +279,7 @@ fn run_test(
         let doc_id = format!("doc_{}", (i * 3) % max_entities);
 
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), user_id);
+        context.insert("principal".to_string(), user_id.into());
 
         let request = PolicyRequest {
             resource: doc_id,
@@ -118,7 +118,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Test Cedar policy evaluation - basic access
     let mut cedar_context = HashMap::new();
-    cedar_context.insert("principal".to_string(), "alice".to_string());
+    cedar_context.insert("principal".to_string(), "alice".into());
 
     let cedar_request = PolicyRequest {
         resource: "document-123".to_string(),
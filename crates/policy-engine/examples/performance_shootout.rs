@@ -121,7 +121,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Benchmark Simple policy
     let iterations = 10000;
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "alice".to_string());
+    context.insert("principal".to_string(), "alice".into());
 
     let request = PolicyRequest {
         resource: "doc1".to_string(),
@@ -218,6 +218,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         }),
         decision: PolicyAction::Allow,
         message: None,
+        obligations: None,
     }];
 
     let reaper_evaluator = ReaperDSLEvaluator::new(store.clone(), reaper_rules, PolicyAction::Deny);
@@ -264,6 +265,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             }),
             decision: PolicyAction::Allow,
             message: None,
+            obligations: None,
         },
         Rule {
             name: "department_access".to_string(),
@@ -284,6 +286,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             ]),
             decision: PolicyAction::Allow,
             message: None,
+            obligations: None,
         },
         Rule {
             name: "clearance_check".to_string(),
@@ -296,6 +299,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             }),
             decision: PolicyAction::Deny,
             message: None,
+            obligations: None,
         },
     ];
 
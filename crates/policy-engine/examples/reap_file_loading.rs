@@ -113,7 +113,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Test 1: Admin accessing any document
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "alice".to_string());
+    context.insert("principal".to_string(), "alice".into());
 
     let request = PolicyRequest {
         resource: "doc1".to_string(),
@@ -134,7 +134,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Test 2: User accessing own document
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "bob".to_string());
+    context.insert("principal".to_string(), "bob".into());
 
     let request = PolicyRequest {
         resource: "doc1".to_string(),
@@ -201,7 +201,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Test: Bob accessing doc1 (clearance 2, requires 2)
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "bob".to_string());
+    context.insert("principal".to_string(), "bob".into());
 
     let request = PolicyRequest {
         resource: "doc1".to_string(),
@@ -250,7 +250,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Test bundle evaluator
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "alice".to_string());
+    context.insert("principal".to_string(), "alice".into());
 
     let request = PolicyRequest {
         resource: "doc1".to_string(),
@@ -33,7 +33,7 @@ fn benchmark_pattern(
         let (user_id, resource_id) = access_fn(i);
 
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), user_id);
+        context.insert("principal".to_string(), user_id.into());
 
         let request = PolicyRequest {
             resource: resource_id,
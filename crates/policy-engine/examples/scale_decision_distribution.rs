@@ -41,7 +41,7 @@ fn benchmark_scenario(
 
     for (user_id, resource_id, _expected) in test_cases {
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), user_id.clone());
+        context.insert("principal".to_string(), user_id.clone().into());
 
         let request = PolicyRequest {
             resource: resource_id.clone(),
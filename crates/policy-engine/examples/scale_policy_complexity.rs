@@ -62,7 +62,7 @@ fn benchmark_policy(
         let resource_id = format!("resource_{}", (i * 7) % 200);
 
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), user_id);
+        context.insert("principal".to_string(), user_id.into());
 
         let request = PolicyRequest {
             resource: resource_id,
@@ -392,7 +392,7 @@ policy enterprise_access {
         let resource = format!("resource_{}", resource_idx);
 
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), principal);
+        context.insert("principal".to_string(), principal.into());
 
         let request = PolicyRequest {
             resource,
@@ -66,7 +66,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("📋 Sample Test Cases:");
     for (principal, resource, expected) in &test_cases {
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), principal.to_string());
+        context.insert("principal".to_string(), (*principal).into());
 
         let request = PolicyRequest {
             resource: resource.to_string(),
@@ -98,7 +98,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         let doc_id = format!("doc_{}", i % 2000);
 
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), user_id);
+        context.insert("principal".to_string(), user_id.into());
 
         let request = PolicyRequest {
             resource: doc_id,
@@ -342,7 +342,7 @@ policy multi_source_access {
         let resource = format!("doc_{}", resource_idx);
 
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), principal);
+        context.insert("principal".to_string(), principal.into());
 
         let request = PolicyRequest {
             resource,
@@ -406,7 +406,7 @@ policy multi_source_access {
         let resource = format!("doc_{}", resource_idx);
 
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), principal);
+        context.insert("principal".to_string(), principal.into());
 
         let request = PolicyRequest {
             resource,
@@ -54,7 +54,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     for (scenario, user_id, resource_id, action) in &test_cases {
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), user_id.to_string());
+        context.insert("principal".to_string(), (*user_id).into());
 
         let request = PolicyRequest {
             resource: resource_id.to_string(),
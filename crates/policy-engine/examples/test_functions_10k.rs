@@ -45,7 +45,7 @@ fn run(evaluator: &dyn PolicyEvaluator, iterations: usize) -> (Vec<u128>, usize)
     let mut allow = 0;
     for i in 0..iterations {
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), format!("user_{}", i % 1000));
+        context.insert("principal".to_string(), format!("user_{}", i % 1000).into());
         let request = PolicyRequest {
             resource: format!("resource_{}", i % 2000),
             action: "read".to_string(),
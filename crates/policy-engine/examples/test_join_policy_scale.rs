@@ -347,7 +347,7 @@ policy join_based_access {
         let resource = format!("doc_{}", resource_idx);
 
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), principal);
+        context.insert("principal".to_string(), principal.into());
 
         let request = PolicyRequest {
             resource,
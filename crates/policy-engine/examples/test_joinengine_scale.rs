@@ -196,7 +196,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         let resource = format!("doc_{}", resource_idx);
 
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), principal);
+        context.insert("principal".to_string(), principal.into());
 
         let request = PolicyRequest {
             resource,
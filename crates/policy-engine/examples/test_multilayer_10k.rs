@@ -113,7 +113,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             let resource_id = (scenario.resource_pattern)(i);
 
             let mut context = HashMap::new();
-            context.insert("principal".to_string(), user_id);
+            context.insert("principal".to_string(), user_id.into());
 
             let request = PolicyRequest {
                 resource: resource_id,
@@ -66,7 +66,7 @@ policy test_nested {
             println!("Compilation succeeded");
 
             let mut context = HashMap::new();
-            context.insert("principal".to_string(), "user_hierarchical".to_string());
+            context.insert("principal".to_string(), "user_hierarchical".into());
 
             let request = PolicyRequest {
                 resource: "hierarchy_map".to_string(),
@@ -91,7 +91,7 @@ policy test_nested {
     let ast_eval = policy.build_ast_evaluator(store_arc.clone());
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "user_hierarchical".to_string());
+    context.insert("principal".to_string(), "user_hierarchical".into());
 
     let request = PolicyRequest {
         resource: "hierarchy_map".to_string(),
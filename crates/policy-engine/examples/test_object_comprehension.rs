@@ -108,7 +108,7 @@ policy test_comprehension {
             println!("Compilation succeeded");
 
             let mut context = HashMap::new();
-            context.insert("principal".to_string(), "user_map_data".to_string());
+            context.insert("principal".to_string(), "user_map_data".into());
 
             let request = PolicyRequest {
                 resource: "object_result".to_string(),
@@ -133,7 +133,7 @@ policy test_comprehension {
     let ast_eval = policy.build_ast_evaluator(store_arc.clone());
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "user_map_data".to_string());
+    context.insert("principal".to_string(), "user_map_data".into());
 
     let request = PolicyRequest {
         resource: "object_result".to_string(),
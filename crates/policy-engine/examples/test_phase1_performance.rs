@@ -64,12 +64,13 @@ fn test_baseline_performance() {
         }),
         decision: PolicyAction::Allow,
         message: None,
+        obligations: None,
     }];
 
     let evaluator = ReaperDSLEvaluator::new(store, rules, PolicyAction::Deny);
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "alice".to_string());
+    context.insert("principal".to_string(), "alice".into());
 
     let request = PolicyRequest {
         resource: "doc1".to_string(),
@@ -150,12 +151,13 @@ fn test_membership_set_performance() {
         },
         decision: PolicyAction::Allow,
         message: None,
+        obligations: None,
     }];
 
     let evaluator = ReaperDSLEvaluator::new(store, rules, PolicyAction::Deny);
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "alice".to_string());
+    context.insert("principal".to_string(), "alice".into());
 
     let request = PolicyRequest {
         resource: "doc1".to_string(),
@@ -237,12 +239,13 @@ fn test_membership_list_performance() {
         },
         decision: PolicyAction::Allow,
         message: None,
+        obligations: None,
     }];
 
     let evaluator = ReaperDSLEvaluator::new(store, rules, PolicyAction::Deny);
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "alice".to_string());
+    context.insert("principal".to_string(), "alice".into());
 
     let request = PolicyRequest {
         resource: "doc1".to_string(),
@@ -326,6 +329,7 @@ fn test_indexed_access_performance() {
             },
             decision: PolicyAction::Allow,
             message: None,
+            obligations: None,
         },
         Rule {
             name: "object_index".to_string(),
@@ -337,13 +341,14 @@ fn test_indexed_access_performance() {
             },
             decision: PolicyAction::Allow,
             message: None,
+            obligations: None,
         },
     ];
 
     let evaluator = ReaperDSLEvaluator::new(store, rules, PolicyAction::Deny);
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "alice".to_string());
+    context.insert("principal".to_string(), "alice".into());
 
     let request = PolicyRequest {
         resource: "doc1".to_string(),
@@ -424,12 +429,13 @@ fn test_variable_assignment_performance() {
         ]),
         decision: PolicyAction::Allow,
         message: None,
+        obligations: None,
     }];
 
     let evaluator = ReaperDSLEvaluator::new(store, rules, PolicyAction::Deny);
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "alice".to_string());
+    context.insert("principal".to_string(), "alice".into());
 
     let request = PolicyRequest {
         resource: "doc1".to_string(),
@@ -542,12 +548,13 @@ fn test_complex_policy_performance() {
         ]),
         decision: PolicyAction::Allow,
         message: None,
+        obligations: None,
     }];
 
     let evaluator = ReaperDSLEvaluator::new(store, rules, PolicyAction::Deny);
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "alice".to_string());
+    context.insert("principal".to_string(), "alice".into());
 
     let request = PolicyRequest {
         resource: "doc1".to_string(),
@@ -62,7 +62,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("📋 Sample Test Cases:");
     for (principal, resource, expected) in &test_cases {
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), principal.to_string());
+        context.insert("principal".to_string(), (*principal).into());
 
         let request = PolicyRequest {
             resource: resource.to_string(),
@@ -94,7 +94,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         let resource_id = format!("resource_{}", i % 2000);
 
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), user_id);
+        context.insert("principal".to_string(), user_id.into());
 
         let request = PolicyRequest {
             resource: resource_id,
@@ -39,7 +39,7 @@ policy test {
 
     // Test viewer + write (should DENY)
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "user_viewer".to_string());
+    context.insert("principal".to_string(), "user_viewer".into());
 
     let request = PolicyRequest {
         resource: "/api/test".to_string(),
@@ -78,13 +78,14 @@ fn test_wildcard_small_list() {
         },
         decision: PolicyAction::Allow,
         message: None,
+        obligations: None,
     }];
 
     let evaluator = ReaperDSLEvaluator::new(store, rules, PolicyAction::Deny);
 
     // Warm up
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "user-alice".to_string());
+    context.insert("principal".to_string(), "user-alice".into());
     let request = PolicyRequest {
         resource: "doc1".to_string(),
         action: "read".to_string(),
@@ -157,13 +158,14 @@ fn test_wildcard_medium_list() {
         },
         decision: PolicyAction::Allow,
         message: None,
+        obligations: None,
     }];
 
     let evaluator = ReaperDSLEvaluator::new(store, rules, PolicyAction::Deny);
 
     // Warm up
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "user-alice".to_string());
+    context.insert("principal".to_string(), "user-alice".into());
     let request = PolicyRequest {
         resource: "doc1".to_string(),
         action: "read".to_string(),
@@ -235,13 +237,14 @@ fn test_wildcard_large_list() {
         },
         decision: PolicyAction::Allow,
         message: None,
+        obligations: None,
     }];
 
     let evaluator = ReaperDSLEvaluator::new(store, rules, PolicyAction::Deny);
 
     // Warm up
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "user-alice".to_string());
+    context.insert("principal".to_string(), "user-alice".into());
     let request = PolicyRequest {
         resource: "doc1".to_string(),
         action: "read".to_string(),
@@ -314,13 +317,14 @@ fn test_wildcard_set() {
         },
         decision: PolicyAction::Allow,
         message: None,
+        obligations: None,
     }];
 
     let evaluator = ReaperDSLEvaluator::new(store, rules, PolicyAction::Deny);
 
     // Warm up
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "user-alice".to_string());
+    context.insert("principal".to_string(), "user-alice".into());
     let request = PolicyRequest {
         resource: "doc1".to_string(),
         action: "read".to_string(),
@@ -389,12 +393,13 @@ fn test_wildcard_first_match() {
         },
         decision: PolicyAction::Allow,
         message: None,
+        obligations: None,
     }];
 
     let evaluator = ReaperDSLEvaluator::new(store, rules, PolicyAction::Deny);
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "user-alice".to_string());
+    context.insert("principal".to_string(), "user-alice".into());
     let request = PolicyRequest {
         resource: "doc1".to_string(),
         action: "read".to_string(),
@@ -468,12 +473,13 @@ fn test_wildcard_last_match() {
         },
         decision: PolicyAction::Allow,
         message: None,
+        obligations: None,
     }];
 
     let evaluator = ReaperDSLEvaluator::new(store, rules, PolicyAction::Deny);
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "user-alice".to_string());
+    context.insert("principal".to_string(), "user-alice".into());
     let request = PolicyRequest {
         resource: "doc1".to_string(),
         action: "read".to_string(),
@@ -165,7 +165,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Step 3: Warm-up run
     println!("3️⃣  Warming up (1000 iterations)...");
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "user_50".to_string());
+    context.insert("principal".to_string(), "user_50".into());
 
     let request = PolicyRequest {
         resource: "doc_150".to_string(),
@@ -202,7 +202,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             let doc_id = format!("doc_{}", (i * 3) % 500);
 
             let mut context = HashMap::new();
-            context.insert("principal".to_string(), user_id);
+            context.insert("principal".to_string(), user_id.into());
 
             let request = PolicyRequest {
                 resource: doc_id,
@@ -305,7 +305,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             };
 
             let mut context = HashMap::new();
-            context.insert("principal".to_string(), user_id);
+            context.insert("principal".to_string(), user_id.into());
 
             let request = PolicyRequest {
                 resource: doc_id,
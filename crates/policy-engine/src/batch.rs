@@ -408,7 +408,7 @@ mod tests {
 
     fn make_request(principal: &str, action: &str, resource: &str) -> PolicyRequest {
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), principal.to_string());
+        context.insert("principal".to_string(), principal.into());
         PolicyRequest {
             action: action.to_string(),
             resource: resource.to_string(),
@@ -6,7 +6,10 @@
 //!
 //! - `REAPER_CACHE_ENABLED` - Enable/disable cache ("true"/"false", default: "true")
 //! - `REAPER_CACHE_CAPACITY` - Maximum cache entries (default: 10000)
-//! - `REAPER_CACHE_TTL_SECS` - Time-to-live in seconds (default: 300, 0 = no TTL)
+//! - `REAPER_CACHE_TTL_SECS` - Time-to-live in seconds (default: 10, 0 = no TTL)
+//!
+//! The TTL does not bound staleness against expiring relationship edges; the
+//! agent bypasses the cache while any are loaded (see [`DecisionCache`]).
 //!
 //! # Usage
 //! ```text
@@ -200,7 +200,7 @@ impl CompiledPolicyEvaluator {
             Condition::LessThan(key, value) => {
                 // Try to parse as numbers for comparison
                 if let Some(context_value) = request.context.get(key) {
-                    if let (Ok(cv), Ok(v)) = (context_value.parse::<i64>(), value.parse::<i64>()) {
+                    if let (Some(cv), Ok(v)) = (context_int(context_value), value.parse::<i64>()) {
                         return cv < v;
                     }
                 }
@@ -209,7 +209,7 @@ impl CompiledPolicyEvaluator {
             Condition::GreaterThan(key, value) => {
                 // Try to parse as numbers for comparison
                 if let Some(context_value) = request.context.get(key) {
-                    if let (Ok(cv), Ok(v)) = (context_value.parse::<i64>(), value.parse::<i64>()) {
+                    if let (Some(cv), Ok(v)) = (context_int(context_value), value.parse::<i64>()) {
                         return cv > v;
                     }
                 }
@@ -257,6 +257,14 @@ impl CompiledPolicyEvaluator {
     }
 }
 
+/// A context value as an integer: a JSON integer, or a string that parses
+/// as one (the pre-typed encoding).
+fn context_int(value: &serde_json::Value) -> Option<i64> {
+    value
+        .as_i64()
+        .or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+}
+
 impl PolicyEvaluator for CompiledPolicyEvaluator {
     fn evaluate(&self, request: &PolicyRequest) -> std::result::Result<PolicyAction, ReaperError> {
         Ok(self.evaluate_fast(request))
@@ -323,7 +331,7 @@ mod tests {
         let evaluator = CompiledPolicyEvaluator::compile(&policy, None).unwrap();
 
         let mut context = HashMap::new();
-        context.insert("role".to_string(), "admin".to_string());
+        context.insert("role".to_string(), "admin".into());
 
         let request = PolicyRequest {
             resource: "/api/users".to_string(),
@@ -352,7 +360,7 @@ mod tests {
         let evaluator = CompiledPolicyEvaluator::compile(&policy, None).unwrap();
 
         let mut context = HashMap::new();
-        context.insert("role".to_string(), "user".to_string());
+        context.insert("role".to_string(), "user".into());
 
         let request = PolicyRequest {
             resource: "/api/users".to_string(),
@@ -380,7 +388,7 @@ mod tests {
 
         // Provide static context for department
         let mut static_context = HashMap::new();
-        static_context.insert("department".to_string(), "eng".to_string());
+        static_context.insert("department".to_string(), "eng".into());
 
         let evaluator = CompiledPolicyEvaluator::compile(&policy, Some(&static_context)).unwrap();
 
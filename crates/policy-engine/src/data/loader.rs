@@ -7,12 +7,14 @@
 //! automatically converted to Sets for O(1) membership tests.
 
 use super::entity::{AttributeValue, EntityBuilder};
+use super::schema::EntitySchema;
 use super::store::DataStore;
 use crate::clock::Stopwatch;
 use reaper_core::ReaperError;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Duration;
 
 /// Supported data formats
@@ -61,12 +63,33 @@ impl LoadStats {
 #[derive(Clone)]
 pub struct DataLoader {
     store: DataStore,
+    schema: Option<Arc<EntitySchema>>,
 }
 
 impl DataLoader {
     /// Create a new data loader
     pub fn new(store: DataStore) -> Self {
-        Self { store }
+        Self {
+            store,
+            schema: None,
+        }
+    }
+
+    /// Check every entity document against `schema` before it is inserted.
+    /// A non-conforming document fails the load; whole-document loads
+    /// ([`Self::load_json`], [`Self::load_json_batch`],
+    /// [`Self::load_json_values`]) check everything first, so a rejected
+    /// load inserts nothing.
+    pub fn with_schema(mut self, schema: Arc<EntitySchema>) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    fn conform(&self, doc: &EntityDocument) -> Result<(), ReaperError> {
+        match &self.schema {
+            Some(schema) => schema.check_entity(doc),
+            None => Ok(()),
+        }
     }
 
     /// Load data from a JSON string
@@ -149,6 +172,12 @@ impl DataLoader {
         let mut stats = LoadStats::new();
         let interner = self.store.interner();
 
+        if self.schema.is_some() {
+            for entity_value in &entities {
+                self.conform(&self.parse_entity_from_value(entity_value)?)?;
+            }
+        }
+
         for entity_value in entities {
             // Parse entity document
             let entity_doc = self.parse_entity_from_value(&entity_value)?;
@@ -207,6 +236,13 @@ impl DataLoader {
 
     /// Load a data document using batch insert for better locality
     fn load_document(&self, doc: DataDocument) -> Result<usize, ReaperError> {
+        for entity_doc in &doc.entities {
+            self.conform(entity_doc)?;
+        }
+        // A malformed caveat fails the load before any entity or edge lands.
+        for entity_doc in &doc.entities {
+            self.compile_caveats(entity_doc)?;
+        }
         let interner = self.store.interner();
         let mut entities = Vec::with_capacity(doc.entities.len());
 
@@ -228,17 +264,7 @@ impl DataLoader {
 
             // ReBAC edges: `id #relation @subject` into the relationship graph
             // (forward + reverse indexed at write time).
-            for (relation, subjects) in &entity_doc.relationships {
-                // Relations are a bounded vocabulary — pin them. Subjects are
-                // high-cardinality (entity ids), so count them: the graph
-                // releases one per live edge when the carrier is detached, so
-                // subject churn (and ids used only as subjects) is reclaimed.
-                let relation_id = interner.intern(relation);
-                for subject in subjects {
-                    let subject_id = interner.intern_counted(subject);
-                    self.store.add_relationship(id, relation_id, subject_id);
-                }
-            }
+            self.add_relationships(id, &entity_doc.relationships, interner)?;
 
             entities.push(builder.build());
         }
@@ -256,6 +282,7 @@ impl DataLoader {
         entity_doc: EntityDocument,
         interner: &super::interning::StringInterner,
     ) -> Result<(), ReaperError> {
+        self.compile_caveats(&entity_doc)?;
         let id = interner.intern_counted(&entity_doc.id);
         let entity_type = interner.intern(&entity_doc.entity_type);
         let mut builder = EntityBuilder::new(id, entity_type);
@@ -270,17 +297,59 @@ impl DataLoader {
             builder = builder.with_parent(interner.intern_counted(&parent));
         }
 
-        for (relation, subjects) in &entity_doc.relationships {
-            // Relations pinned (bounded vocabulary); subjects counted so a
-            // high-cardinality subject churn is reclaimed on detach.
-            let relation_id = interner.intern(relation);
-            for subject in subjects {
+        self.add_relationships(id, &entity_doc.relationships, interner)?;
+
+        self.store.insert(builder.build());
+        Ok(())
+    }
+
+    /// Compile every caveat `doc` declares into the graph's cache, so a
+    /// malformed one rejects the document before any of its edges is added.
+    fn compile_caveats(&self, doc: &EntityDocument) -> Result<(), ReaperError> {
+        for subject in doc.relationships.values().flatten() {
+            if let Some(caveat) = subject.caveat() {
                 self.store
-                    .add_relationship(id, relation_id, interner.intern_counted(subject));
+                    .relationships()
+                    .compile_caveat(caveat)
+                    .map_err(|e| ReaperError::InvalidPolicy {
+                        reason: format!("entity '{}': {e}", doc.id),
+                    })?;
             }
         }
+        Ok(())
+    }
 
-        self.store.insert(builder.build());
+    /// Add the ReBAC edges `doc` declares, carried by `id`. Relations are a
+    /// bounded vocabulary — pinned. Subjects are high-cardinality (entity
+    /// ids), so they are counted: the graph releases one per live edge when
+    /// the carrier is detached, so subject churn (and ids used only as
+    /// subjects) is reclaimed.
+    fn add_relationships(
+        &self,
+        id: super::EntityId,
+        relationships: &HashMap<String, Vec<RelationshipSubject>>,
+        interner: &super::interning::StringInterner,
+    ) -> Result<(), ReaperError> {
+        for (relation, subjects) in relationships {
+            let relation_id = interner.intern(relation);
+            for subject in subjects {
+                let subject_id = interner.intern_counted(subject.id());
+                match subject {
+                    RelationshipSubject::Id(_) => {
+                        self.store.add_relationship(id, relation_id, subject_id)
+                    }
+                    RelationshipSubject::Conditional(c) => {
+                        self.store.relationships().add_conditional_edge(
+                            id,
+                            relation_id,
+                            subject_id,
+                            c.expires_at.map(|t| t.timestamp()),
+                            c.caveat.as_deref(),
+                        )?
+                    }
+                }
+            }
+        }
         Ok(())
     }
 
@@ -299,84 +368,19 @@ impl DataLoader {
     /// decision-equivalent to [`Self::load_json`] (same `ingest_entity_doc`
     /// per entity); it trades a little parse throughput for a much lower peak.
     pub fn load_json_streaming(&self, json: &str) -> Result<usize, ReaperError> {
-        use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
-        use std::fmt;
-
-        // Visitor over the `{"entities": [...]}` wrapper object.
-        struct DocVisitor<'a> {
-            loader: &'a DataLoader,
-        }
-        impl<'de> Visitor<'de> for DocVisitor<'_> {
-            type Value = usize;
-            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
-                f.write_str("a data document object containing an 'entities' array")
-            }
-            fn visit_map<M: MapAccess<'de>>(self, mut map: M) -> Result<usize, M::Error> {
-                let mut count = 0usize;
-                let mut seen = false;
-                while let Some(key) = map.next_key::<String>()? {
-                    if key == "entities" {
-                        count = map.next_value_seed(EntitiesSeed {
-                            loader: self.loader,
-                        })?;
-                        seen = true;
-                    } else {
-                        // Ignore unknown top-level keys without materializing them.
-                        let _: de::IgnoredAny = map.next_value()?;
-                    }
-                }
-                if !seen {
-                    return Err(de::Error::custom("missing 'entities' array"));
-                }
-                Ok(count)
-            }
-        }
-
-        // Seed that streams the entities array element-by-element.
-        struct EntitiesSeed<'a> {
-            loader: &'a DataLoader,
-        }
-        impl<'de> DeserializeSeed<'de> for EntitiesSeed<'_> {
-            type Value = usize;
-            fn deserialize<D: Deserializer<'de>>(self, d: D) -> Result<usize, D::Error> {
-                d.deserialize_seq(EntitiesVisitor {
-                    loader: self.loader,
-                })
-            }
-        }
-        struct EntitiesVisitor<'a> {
-            loader: &'a DataLoader,
-        }
-        impl<'de> Visitor<'de> for EntitiesVisitor<'_> {
-            type Value = usize;
-            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
-                f.write_str("an array of entity documents")
-            }
-            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<usize, A::Error> {
-                let interner = self.loader.store.interner();
-                let mut count = 0usize;
-                // Each next_element parses exactly one EntityDocument; we insert
-                // it and drop it before the next — the DOM never accumulates.
-                while let Some(doc) = seq.next_element::<EntityDocument>()? {
-                    self.loader
-                        .ingest_entity_doc(doc, interner)
-                        .map_err(de::Error::custom)?;
-                    count += 1;
-                }
-                Ok(count)
-            }
-        }
-
-        let mut de = serde_json::Deserializer::from_str(json);
-        let count = de
-            .deserialize_map(DocVisitor { loader: self })
-            .map_err(|e| ReaperError::InvalidPolicy {
-                reason: format!("Failed to parse JSON (streaming): {}", e),
+        // With a schema, a first streaming pass only checks: a non-conforming
+        // entity (or malformed caveat) late in the document must not leave the
+        // earlier ones loaded.
+        if let Some(schema) = &self.schema {
+            stream_entities(json, |doc| {
+                schema.check_entity(&doc)?;
+                self.compile_caveats(&doc)
             })?;
-        de.end().map_err(|e| ReaperError::InvalidPolicy {
-            reason: format!("Trailing data after data document: {}", e),
-        })?;
-        Ok(count)
+        }
+        let interner = self.store.interner();
+        // Each entity is inserted and dropped before the next is read — the
+        // DOM never accumulates.
+        stream_entities(json, |doc| self.ingest_entity_doc(doc, interner))
     }
 
     /// UPSERT one entity document (delta-sync primitive): replaces the
@@ -387,6 +391,8 @@ impl DataLoader {
             serde_json::from_value(doc.clone()).map_err(|e| ReaperError::InvalidPolicy {
                 reason: format!("invalid entity document: {e}"),
             })?;
+        self.conform(&entity_doc)?;
+        self.compile_caveats(&entity_doc)?;
         let interner = self.store.interner();
         // Counted before the upsert; store.upsert() then releases the OLD
         // entity's counted strings via remove(). Building first keeps the count
@@ -406,14 +412,7 @@ impl DataLoader {
         // upsert() detaches previously carried edges (releasing their counted
         // subjects); re-add the current set with freshly counted subjects.
         self.store.upsert(builder.build());
-        for (relation, subjects) in &entity_doc.relationships {
-            let relation_id = interner.intern(relation);
-            for subject in subjects {
-                self.store
-                    .add_relationship(id, relation_id, interner.intern_counted(subject));
-            }
-        }
-        Ok(())
+        self.add_relationships(id, &entity_doc.relationships, interner)
     }
 
     /// DELETE one entity by id (delta-sync tombstone): idempotent, cascades
@@ -433,6 +432,106 @@ impl DataLoader {
     }
 }
 
+/// Pull-parse a `{"entities": [...]}` document, handing each entity to
+/// `sink` as soon as it is parsed. Returns the number of entities.
+fn stream_entities<F>(json: &str, mut sink: F) -> Result<usize, ReaperError>
+where
+    F: FnMut(EntityDocument) -> Result<(), ReaperError>,
+{
+    use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+    use std::fmt;
+
+    // Visitor over the `{"entities": [...]}` wrapper object.
+    struct DocVisitor<'a, F> {
+        sink: &'a mut F,
+    }
+    impl<'de, F> Visitor<'de> for DocVisitor<'_, F>
+    where
+        F: FnMut(EntityDocument) -> Result<(), String>,
+    {
+        type Value = usize;
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a data document object containing an 'entities' array")
+        }
+        fn visit_map<M: MapAccess<'de>>(self, mut map: M) -> Result<usize, M::Error> {
+            let mut count = 0usize;
+            let mut seen = false;
+            while let Some(key) = map.next_key::<String>()? {
+                if key == "entities" {
+                    count = map.next_value_seed(EntitiesSeed {
+                        sink: &mut *self.sink,
+                    })?;
+                    seen = true;
+                } else {
+                    // Ignore unknown top-level keys without materializing them.
+                    let _: de::IgnoredAny = map.next_value()?;
+                }
+            }
+            if !seen {
+                return Err(de::Error::custom("missing 'entities' array"));
+            }
+            Ok(count)
+        }
+    }
+
+    // Seed that streams the entities array element-by-element.
+    struct EntitiesSeed<'a, F> {
+        sink: &'a mut F,
+    }
+    impl<'de, F> DeserializeSeed<'de> for EntitiesSeed<'_, F>
+    where
+        F: FnMut(EntityDocument) -> Result<(), String>,
+    {
+        type Value = usize;
+        fn deserialize<D: Deserializer<'de>>(self, d: D) -> Result<usize, D::Error> {
+            d.deserialize_seq(EntitiesVisitor { sink: self.sink })
+        }
+    }
+    struct EntitiesVisitor<'a, F> {
+        sink: &'a mut F,
+    }
+    impl<'de, F> Visitor<'de> for EntitiesVisitor<'_, F>
+    where
+        F: FnMut(EntityDocument) -> Result<(), String>,
+    {
+        type Value = usize;
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("an array of entity documents")
+        }
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<usize, A::Error> {
+            let mut count = 0usize;
+            // Each next_element parses exactly one EntityDocument.
+            while let Some(doc) = seq.next_element::<EntityDocument>()? {
+                (self.sink)(doc).map_err(de::Error::custom)?;
+                count += 1;
+            }
+            Ok(count)
+        }
+    }
+
+    // A sink error is reported as itself, not as a JSON syntax error.
+    let mut rejected = None;
+    let mut guarded = |doc| {
+        sink(doc).map_err(|e| {
+            let message = e.to_string();
+            rejected = Some(e);
+            message
+        })
+    };
+    let mut de = serde_json::Deserializer::from_str(json);
+    let parsed = de.deserialize_map(DocVisitor { sink: &mut guarded });
+    if let Some(e) = rejected {
+        return Err(e);
+    }
+    let count = parsed.map_err(|e| ReaperError::InvalidPolicy {
+        reason: format!("Failed to parse JSON (streaming): {}", e),
+    })?;
+    de.end().map_err(|e| ReaperError::InvalidPolicy {
+        reason: format!("Trailing data after data document: {}", e),
+    })?;
+    Ok(count)
+}
+
 /// Convert JSON value to AttributeValue
 pub(crate) fn json_value_to_attribute(
     value: JsonValue,
@@ -497,10 +596,48 @@ pub(crate) struct EntityDocument {
     pub attributes: HashMap<String, JsonValue>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub parent: Option<String>,
-    /// ReBAC edges this entity declares: relation -> subject entity ids,
-    /// e.g. {"owner": ["alice"], "parent": ["folder-eng"]}.
+    /// ReBAC edges this entity declares: relation -> subjects, e.g.
+    /// {"owner": ["alice"], "parent": ["folder-eng"]}.
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
-    pub relationships: HashMap<String, Vec<String>>,
+    pub relationships: HashMap<String, Vec<RelationshipSubject>>,
+}
+
+/// One subject of a declared relation: a bare entity id, or an object that
+/// makes the edge conditional — `{"subject": "alice", "expires_at":
+/// "2026-06-01T00:00:00Z", "caveat": "context.ip_in_office == true"}`.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub(crate) enum RelationshipSubject {
+    Id(String),
+    Conditional(ConditionalSubject),
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ConditionalSubject {
+    pub subject: String,
+    /// RFC 3339; the edge is invisible from this instant on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// A `.reap` condition over `context`, evaluated per request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caveat: Option<String>,
+}
+
+impl RelationshipSubject {
+    pub(crate) fn id(&self) -> &str {
+        match self {
+            Self::Id(id) => id,
+            Self::Conditional(c) => &c.subject,
+        }
+    }
+
+    fn caveat(&self) -> Option<&str> {
+        match self {
+            Self::Id(_) => None,
+            Self::Conditional(c) => c.caveat.as_deref(),
+        }
+    }
 }
 
 /// Convenience function to create a DataStore from JSON
@@ -0,0 +1,571 @@
+//! ReBAC queries outside a policy: lookup-resources, lookup-subjects and
+//! expand.
+//!
+//! A policy asks "does alice hold `viewer` on doc1?". A sharing UI asks the
+//! inverse questions — "which documents can alice view?", "who can edit
+//! doc1?" — and "why?". These answer them over the same
+//! [`RelationshipGraph`](super::RelationshipGraph) with the semantics of
+//! `rebac::check` ([`RelationshipGraph::check`](super::RelationshipGraph::check)):
+//!
+//! - [`lookup_resources`] walks the reverse index up from the subject,
+//!   through every installed rewrite that reads what it reached, to a
+//!   superset of the objects that may hold the relation;
+//! - [`lookup_subjects`] walks the forward index down from the object the
+//!   same way;
+//! - both then verify each candidate with `check`, under a fresh
+//!   per-evaluation traversal budget, so a page holds exactly the ids a
+//!   policy would grant. A check that runs out of budget leaves its id out,
+//!   as evaluation would deny it;
+//! - [`expand`] returns the definition tree of `object#relation` down to
+//!   stored subjects, optionally pruned to the branches naming one subject.
+//!
+//! The candidate walks and the expand tree are each capped at
+//! [`EVAL_TRAVERSAL_BUDGET`] nodes; hitting the cap is reported as
+//! `truncated`, never passed off as a complete answer.
+
+use super::relationships::{reset_traversal_budget, EVAL_TRAVERSAL_BUDGET, MAX_REWRITE_DEPTH};
+use super::rewrites::{RelationRewrites, Rewrite};
+use super::{DataStore, EntityId, EntityType, InternedString};
+use rustc_hash::FxHashSet;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+/// Pagination for one lookup.
+#[derive(Debug, Clone)]
+pub struct LookupOptions {
+    /// Keyset cursor: return only ids sorting strictly after this one.
+    pub after: Option<String>,
+    /// Page size.
+    pub limit: usize,
+}
+
+/// One page of a lookup.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LookupPage {
+    /// Ids holding the relation, in ascending order.
+    pub ids: Vec<String>,
+    /// Pass as `after` to fetch the next page; `None` on the last page.
+    pub next_cursor: Option<String>,
+    /// Candidates the walk found (the verified set).
+    pub candidates: usize,
+    /// The candidate walk hit the traversal budget: ids it did not reach
+    /// are missing from every page.
+    pub truncated: bool,
+    /// Store epoch the page was computed at.
+    pub data_epoch: u64,
+}
+
+/// Which objects of `resource_type` does `subject` hold `relation` on?
+pub fn lookup_resources(
+    store: &DataStore,
+    subject: &str,
+    relation: &str,
+    resource_type: &str,
+    options: &LookupOptions,
+) -> LookupPage {
+    let interner = store.interner();
+    let mut page = LookupPage::empty(store.data_epoch());
+    // Never interned: nothing carries it.
+    let (Some(subject), Some(relation), Some(resource_type)) = (
+        interner.lookup(subject),
+        interner.lookup(relation),
+        interner.lookup(resource_type),
+    ) else {
+        return page;
+    };
+    let graph = store.relationships();
+    let type_of = |id: EntityId| store.get(id).map(|e| e.entity_type);
+    let rewrites = graph.rewrites();
+    let rules = rules(rewrites.as_deref());
+
+    let mut walk = Walk::default();
+    for (rel, objects) in graph.subject_edges(subject) {
+        for object in objects {
+            walk.push(object, rel);
+        }
+    }
+    let mut candidates = FxHashSet::default();
+    while let Some((object, rel)) = walk.queue.pop_front() {
+        let object_type = type_of(object);
+        if rel == relation && object_type == Some(resource_type) {
+            candidates.insert(object);
+        }
+        // Every rewrite that reads `object#rel` may put its own relation on
+        // `object` (a computed name) or on whatever points at `object`
+        // through the tupleset (`rel from tupleset`).
+        for (defined_on, defined, leaves) in &rules {
+            for leaf in leaves {
+                match leaf {
+                    Rewrite::Computed(name) if *name == rel && object_type == Some(*defined_on) => {
+                        walk.push(object, *defined);
+                    }
+                    Rewrite::TupleToUserset {
+                        relation: name,
+                        tupleset,
+                    } if *name == rel => {
+                        for parent in graph.related_to(object, *tupleset) {
+                            if type_of(parent) == Some(*defined_on) {
+                                walk.push(parent, *defined);
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    page.truncated = walk.truncated;
+    page.fill(store, candidates, options, |object| {
+        graph.check(object, relation, subject, &type_of)
+    });
+    page
+}
+
+/// Which subjects (of `subject_type`, if given) hold `relation` on `object`?
+pub fn lookup_subjects(
+    store: &DataStore,
+    object: &str,
+    relation: &str,
+    subject_type: Option<&str>,
+    options: &LookupOptions,
+) -> LookupPage {
+    let interner = store.interner();
+    let mut page = LookupPage::empty(store.data_epoch());
+    let (Some(object), Some(relation)) = (interner.lookup(object), interner.lookup(relation))
+    else {
+        return page;
+    };
+    let subject_type = match subject_type {
+        Some(name) => match interner.lookup(name) {
+            Some(ty) => Some(ty),
+            None => return page,
+        },
+        None => None,
+    };
+    let graph = store.relationships();
+    let type_of = |id: EntityId| store.get(id).map(|e| e.entity_type);
+    let rewrites = graph.rewrites();
+
+    let mut walk = Walk::default();
+    walk.push(object, relation);
+    let mut candidates = FxHashSet::default();
+    while let Some((node, rel)) = walk.queue.pop_front() {
+        let rewrite = rewrites
+            .as_deref()
+            .zip(type_of(node))
+            .and_then(|(rewrites, ty)| rewrites.get(ty, rel));
+        let Some(rewrite) = rewrite else {
+            candidates.extend(graph.related(node, rel));
+            continue;
+        };
+        for leaf in rewrite.leaves() {
+            match leaf {
+                Rewrite::This => candidates.extend(graph.related(node, rel)),
+                Rewrite::Computed(name) => walk.push(node, *name),
+                Rewrite::TupleToUserset {
+                    relation: name,
+                    tupleset,
+                } => {
+                    for target in graph.related(node, *tupleset) {
+                        walk.push(target, *name);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    if let Some(ty) = subject_type {
+        candidates.retain(|subject| type_of(*subject) == Some(ty));
+    }
+    page.truncated = walk.truncated;
+    page.fill(store, candidates, options, |subject| {
+        graph.check(object, relation, subject, &type_of)
+    });
+    page
+}
+
+/// One node of an [`expand`] tree.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ExpandNode {
+    /// `object#relation`'s stored subjects.
+    Direct {
+        object: String,
+        relation: String,
+        subjects: Vec<String>,
+    },
+    /// `object#relation` as its type's rewrite defines it.
+    Rewrite {
+        object: String,
+        relation: String,
+        definition: Box<ExpandNode>,
+    },
+    /// `relation from tupleset`: `relation` on each entity the object's
+    /// `tupleset` edges point at.
+    TupleToUserset {
+        tupleset: String,
+        relation: String,
+        children: Vec<ExpandNode>,
+    },
+    Union {
+        children: Vec<ExpandNode>,
+    },
+    Intersection {
+        children: Vec<ExpandNode>,
+    },
+    /// Members of `base` that are not members of `subtract`.
+    Exclusion {
+        base: Box<ExpandNode>,
+        subtract: Box<ExpandNode>,
+    },
+    /// Not expanded: `reason` is `cycle` (already being expanded further
+    /// up), `depth` or `budget`.
+    Truncated {
+        object: String,
+        relation: String,
+        reason: String,
+    },
+}
+
+/// What [`expand`] returns.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExpandTree {
+    pub tree: ExpandNode,
+    /// With a subject: whether `check` grants it the relation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub granted: Option<bool>,
+    /// Some branch stopped at the depth or traversal budget.
+    pub truncated: bool,
+    /// Store epoch the tree was built at.
+    pub data_epoch: u64,
+}
+
+/// The definition tree of `object#relation`. With `subject`, the tree keeps
+/// only the branches that name it — the path(s) that grant the relation, or
+/// that would have but for an exclusion — and reports the `check` verdict.
+pub fn expand(
+    store: &DataStore,
+    object: &str,
+    relation: &str,
+    subject: Option<&str>,
+) -> ExpandTree {
+    let interner = store.interner();
+    let data_epoch = store.data_epoch();
+    let graph = store.relationships();
+    let (Some(object_id), Some(relation_id)) = (interner.lookup(object), interner.lookup(relation))
+    else {
+        let tree = ExpandNode::Direct {
+            object: object.to_string(),
+            relation: relation.to_string(),
+            subjects: Vec::new(),
+        };
+        return ExpandTree {
+            tree,
+            granted: subject.map(|_| false),
+            truncated: false,
+            data_epoch,
+        };
+    };
+    let mut expander = Expander {
+        store,
+        rewrites: graph.rewrites(),
+        path: FxHashSet::default(),
+        remaining: EVAL_TRAVERSAL_BUDGET,
+        truncated: false,
+    };
+    let tree = expander.relation(object_id, relation_id, 0);
+    let (tree, granted) = match subject {
+        None => (tree, None),
+        Some(name) => {
+            let type_of = |id: EntityId| store.get(id).map(|e| e.entity_type);
+            let granted = interner.lookup(name).is_some_and(|subject| {
+                reset_traversal_budget();
+                graph.check(object_id, relation_id, subject, &type_of)
+            });
+            let tree = prune(tree, name).unwrap_or(ExpandNode::Union {
+                children: Vec::new(),
+            });
+            (tree, Some(granted))
+        }
+    };
+    ExpandTree {
+        tree,
+        granted,
+        truncated: expander.truncated,
+        data_epoch,
+    }
+}
+
+impl LookupPage {
+    fn empty(data_epoch: u64) -> Self {
+        Self {
+            ids: Vec::new(),
+            next_cursor: None,
+            candidates: 0,
+            truncated: false,
+            data_epoch,
+        }
+    }
+
+    /// Verify `candidates` in id order past the cursor until the page is
+    /// full. Each check is its own evaluation: a fresh traversal budget.
+    fn fill(
+        &mut self,
+        store: &DataStore,
+        candidates: FxHashSet<EntityId>,
+        options: &LookupOptions,
+        holds: impl Fn(EntityId) -> bool,
+    ) {
+        self.candidates = candidates.len();
+        let interner = store.interner();
+        let mut names: Vec<(String, EntityId)> = candidates
+            .into_iter()
+            .filter_map(|id| interner.resolve_str(id).map(|name| (name, id)))
+            .filter(|(name, _)| {
+                options
+                    .after
+                    .as_deref()
+                    .is_none_or(|after| name.as_str() > after)
+            })
+            .collect();
+        names.sort_unstable();
+
+        let limit = options.limit.max(1);
+        let mut remaining = names.into_iter();
+        for (name, id) in remaining.by_ref() {
+            reset_traversal_budget();
+            if holds(id) {
+                self.ids.push(name);
+                if self.ids.len() == limit {
+                    break;
+                }
+            }
+        }
+        if self.ids.len() == limit && remaining.next().is_some() {
+            self.next_cursor = self.ids.last().cloned();
+        }
+    }
+}
+
+/// Each rewrite's `(entity type, relation)` with the leaves it reads.
+fn rules(rewrites: Option<&RelationRewrites>) -> Vec<(EntityType, InternedString, Vec<&Rewrite>)> {
+    rewrites
+        .into_iter()
+        .flat_map(RelationRewrites::iter)
+        .map(|(&(ty, relation), rewrite)| (ty, relation, rewrite.leaves()))
+        .collect()
+}
+
+/// Breadth-first walk over `(entity, relation)` pairs, each visited once,
+/// capped at [`EVAL_TRAVERSAL_BUDGET`] pairs.
+#[derive(Default)]
+struct Walk {
+    seen: FxHashSet<(EntityId, InternedString)>,
+    queue: VecDeque<(EntityId, InternedString)>,
+    truncated: bool,
+}
+
+impl Walk {
+    fn push(&mut self, entity: EntityId, relation: InternedString) {
+        if self.seen.contains(&(entity, relation)) {
+            return;
+        }
+        if self.seen.len() >= EVAL_TRAVERSAL_BUDGET {
+            self.truncated = true;
+            return;
+        }
+        self.seen.insert((entity, relation));
+        self.queue.push_back((entity, relation));
+    }
+}
+
+/// Builds an [`ExpandNode`] tree: the shape of
+/// [`RelationshipGraph::check`](super::RelationshipGraph::check)'s
+/// expansion, for every subject at once.
+struct Expander<'a> {
+    store: &'a DataStore,
+    rewrites: Option<Arc<RelationRewrites>>,
+    /// `(object, relation)` pairs on the current expansion path.
+    path: FxHashSet<(EntityId, InternedString)>,
+    remaining: usize,
+    truncated: bool,
+}
+
+impl Expander<'_> {
+    fn name(&self, id: InternedString) -> String {
+        self.store.interner().resolve_str(id).unwrap_or_default()
+    }
+
+    fn truncated(
+        &mut self,
+        object: EntityId,
+        relation: InternedString,
+        reason: &str,
+    ) -> ExpandNode {
+        if reason != "cycle" {
+            self.truncated = true;
+        }
+        ExpandNode::Truncated {
+            object: self.name(object),
+            relation: self.name(relation),
+            reason: reason.to_string(),
+        }
+    }
+
+    fn direct(&self, object: EntityId, relation: InternedString) -> ExpandNode {
+        let mut subjects: Vec<String> = self
+            .store
+            .relationships()
+            .related(object, relation)
+            .into_iter()
+            .filter_map(|id| self.store.interner().resolve_str(id))
+            .collect();
+        subjects.sort_unstable();
+        ExpandNode::Direct {
+            object: self.name(object),
+            relation: self.name(relation),
+            subjects,
+        }
+    }
+
+    fn relation(&mut self, object: EntityId, relation: InternedString, depth: usize) -> ExpandNode {
+        if self.remaining == 0 {
+            return self.truncated(object, relation, "budget");
+        }
+        self.remaining -= 1;
+        let rewrites = self.rewrites.clone();
+        let rewrite = rewrites.as_deref().and_then(|rewrites| {
+            let ty = self.store.get(object)?.entity_type;
+            rewrites.get(ty, relation)
+        });
+        let Some(rewrite) = rewrite else {
+            return self.direct(object, relation);
+        };
+        if depth >= MAX_REWRITE_DEPTH {
+            return self.truncated(object, relation, "depth");
+        }
+        if !self.path.insert((object, relation)) {
+            return self.truncated(object, relation, "cycle");
+        }
+        let definition = self.rewrite(rewrite, object, relation, depth);
+        self.path.remove(&(object, relation));
+        ExpandNode::Rewrite {
+            object: self.name(object),
+            relation: self.name(relation),
+            definition: Box::new(definition),
+        }
+    }
+
+    fn rewrite(
+        &mut self,
+        rewrite: &Rewrite,
+        object: EntityId,
+        relation: InternedString,
+        depth: usize,
+    ) -> ExpandNode {
+        match rewrite {
+            Rewrite::This => self.direct(object, relation),
+            Rewrite::Computed(other) => self.relation(object, *other, depth + 1),
+            Rewrite::TupleToUserset {
+                relation: target_relation,
+                tupleset,
+            } => {
+                let children = self
+                    .store
+                    .relationships()
+                    .related(object, *tupleset)
+                    .into_iter()
+                    .map(|target| self.relation(target, *target_relation, depth + 1))
+                    .collect();
+                ExpandNode::TupleToUserset {
+                    tupleset: self.name(*tupleset),
+                    relation: self.name(*target_relation),
+                    children,
+                }
+            }
+            Rewrite::Union(items) => ExpandNode::Union {
+                children: items
+                    .iter()
+                    .map(|item| self.rewrite(item, object, relation, depth))
+                    .collect(),
+            },
+            Rewrite::Intersection(items) => ExpandNode::Intersection {
+                children: items
+                    .iter()
+                    .map(|item| self.rewrite(item, object, relation, depth))
+                    .collect(),
+            },
+            Rewrite::Exclusion(base, subtract) => ExpandNode::Exclusion {
+                base: Box::new(self.rewrite(base, object, relation, depth)),
+                subtract: Box::new(self.rewrite(subtract, object, relation, depth)),
+            },
+        }
+    }
+}
+
+/// `node` with only the branches naming `subject`; `None` if none does. An
+/// intersection needs every operand, an exclusion its base (the subtracted
+/// side is kept whole, to show what was taken away). Branches cut off at the
+/// depth or budget are kept: they might name it.
+fn prune(node: ExpandNode, subject: &str) -> Option<ExpandNode> {
+    let prune_all = |children: Vec<ExpandNode>| -> Vec<ExpandNode> {
+        children
+            .into_iter()
+            .filter_map(|child| prune(child, subject))
+            .collect()
+    };
+    match node {
+        ExpandNode::Direct {
+            object,
+            relation,
+            subjects,
+        } => subjects
+            .iter()
+            .any(|s| s == subject)
+            .then(|| ExpandNode::Direct {
+                object,
+                relation,
+                subjects: vec![subject.to_string()],
+            }),
+        ExpandNode::Rewrite {
+            object,
+            relation,
+            definition,
+        } => prune(*definition, subject).map(|definition| ExpandNode::Rewrite {
+            object,
+            relation,
+            definition: Box::new(definition),
+        }),
+        ExpandNode::TupleToUserset {
+            tupleset,
+            relation,
+            children,
+        } => {
+            let children = prune_all(children);
+            (!children.is_empty()).then_some(ExpandNode::TupleToUserset {
+                tupleset,
+                relation,
+                children,
+            })
+        }
+        ExpandNode::Union { children } => {
+            let children = prune_all(children);
+            (!children.is_empty()).then_some(ExpandNode::Union { children })
+        }
+        ExpandNode::Intersection { children } => {
+            let operands = children.len();
+            let children = prune_all(children);
+            (children.len() == operands).then_some(ExpandNode::Intersection { children })
+        }
+        ExpandNode::Exclusion { base, subtract } => {
+            prune(*base, subject).map(|base| ExpandNode::Exclusion {
+                base: Box::new(base),
+                subtract,
+            })
+        }
+        // A cycle contributes nothing; a cut-off branch is unknown.
+        ExpandNode::Truncated { reason, .. } if reason == "cycle" => None,
+        truncated @ ExpandNode::Truncated { .. } => Some(truncated),
+    }
+}
@@ -18,9 +18,12 @@ pub mod indexes;
 pub mod interning;
 pub mod join;
 pub mod loader;
+pub mod lookup;
 pub mod rbac;
 pub mod relationships;
+pub mod rewrites;
 pub mod router;
+pub mod schema;
 pub mod store;
 pub mod streaming;
 pub mod views;
@@ -34,9 +37,16 @@ pub use indexes::{IndexManager, IndexStats};
 pub use interning::{InternedString, StringInterner};
 pub use join::{EntitySource, JoinConfig, JoinEngine, JoinKey, JoinResult, SecondarySource};
 pub use loader::{DataFormat, DataLoader, LoadStats};
+pub use lookup::{
+    expand, lookup_resources, lookup_subjects, ExpandNode, ExpandTree, LookupOptions, LookupPage,
+};
 pub use rbac::{DataStoreRBACExt, RBACViewBuilder};
-pub use relationships::{EdgeList, RelationshipGraph};
+pub use relationships::{
+    validate_caveat, CaveatContextCell, CaveatScope, EdgeList, RelationshipGraph,
+};
+pub use rewrites::{RelationRewrites, Userset};
 pub use router::{PerformanceTier, QueryPattern, QueryResult, QueryRouter, RouterStats};
+pub use schema::{AttributeSchema, AttributeType, EntitySchema, EntityTypeSchema, RequestSchema};
 pub use store::{DataStore, DataStoreConfig, IndexStrategy, QueryBuilder};
 pub use streaming::{JsonStreamReader, StreamingLoader, StreamingStats};
 pub use views::{MaterializedView, ViewManager, ViewQuery, ViewStats, ViewStrategy};
@@ -27,14 +27,35 @@
 //! - [`RelationshipGraph::has_relation_inherited`] — object-side ancestor
 //!   walk: the relation holds on the object or any ancestor along `up` edges
 //!   (e.g. folder hierarchies)
+//! - [`RelationshipGraph::check`] — schema expansion: the relation as the
+//!   installed [`rewrites`](super::rewrites) define it for the object's type
+//!   (unions, intersections, exclusions, `viewer from parent`), charged
+//!   against the same traversal budget
+//!
+//! An edge may be *conditional* ([`RelationshipGraph::add_conditional_edge`]):
+//! it carries an expiry, a caveat over the request context, or both, and
+//! every check above treats it as absent once it has expired or while its
+//! caveat does not hold. Expiry is read from the clock at check time, so a
+//! temporary grant lapses without a data republish. Caveats read the context
+//! installed by [`RelationshipGraph::caveat_scope`] — the policy evaluators
+//! and filter narrowing install the request's — and fail closed outside one
+//! (lookups).
 
 use crate::data::interning::{InternedString, StringInterner};
-use crate::data::EntityId;
+use crate::data::rewrites::{RelationRewrites, Rewrite};
+use crate::data::schema::EntitySchema;
+use crate::data::{EntityId, EntityType};
+use crate::reap::Caveat;
+use arc_swap::ArcSwapOption;
 use dashmap::DashMap;
+use reaper_core::ReaperError;
 use rustc_hash::FxHashSet;
 use smallvec::SmallVec;
 use std::cell::{Cell, RefCell};
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
 /// Adjacency list — inline up to 4 edges, sorted for binary-search membership.
 pub type EdgeList = SmallVec<[EntityId; 4]>;
@@ -53,6 +74,11 @@ const TRAVERSAL_NODE_BUDGET: usize = 4_096;
 /// `max_candidate_policies × EVAL_TRAVERSAL_BUDGET` nodes.
 pub const EVAL_TRAVERSAL_BUDGET: usize = 4 * TRAVERSAL_NODE_BUDGET;
 
+/// Deepest chain of rewrites one `check` follows (each computed relation and
+/// `from` hop is a level). Deeper schemas fail closed, like an exhausted
+/// budget; the clamp matches the explicit traversals' `max_depth`.
+pub(crate) const MAX_REWRITE_DEPTH: usize = 16;
+
 /// Reusable BFS traversal scratch: (visited set, queue of `(node, depth)`).
 type BfsScratch = (FxHashSet<EntityId>, VecDeque<(EntityId, usize)>);
 
@@ -65,8 +91,14 @@ thread_local! {
 
     /// Remaining per-evaluation traversal budget on this thread.
     static EVAL_BUDGET: Cell<usize> = const { Cell::new(EVAL_TRAVERSAL_BUDGET) };
+
+    /// Request context the caveats of the running evaluation read (see
+    /// [`RelationshipGraph::caveat_scope`]). `None`: no caveat holds.
+    static CAVEAT_CONTEXT: RefCell<Option<Rc<CaveatContext>>> = const { RefCell::new(None) };
 }
 
+type CaveatContext = HashMap<String, serde_json::Value>;
+
 /// Reset the per-evaluation traversal budget. Called by the policy engine at
 /// the start of each policy evaluation so the budget spans all ReBAC
 /// conditions within that evaluation, rather than resetting per condition.
@@ -74,6 +106,13 @@ pub fn reset_traversal_budget() {
     EVAL_BUDGET.with(|b| b.set(EVAL_TRAVERSAL_BUDGET));
 }
 
+/// Check that `source` is a valid edge caveat — what
+/// [`RelationshipGraph::add_conditional_edge`] would accept — without a graph
+/// (writers validating a grant before storing it).
+pub fn validate_caveat(source: &str) -> Result<(), ReaperError> {
+    Caveat::compile(source).map(|_| ())
+}
+
 /// Concurrent, doubly-indexed relationship graph.
 #[derive(Debug)]
 pub struct RelationshipGraph {
@@ -101,6 +140,70 @@ pub struct RelationshipGraph {
     /// (duplicate edge, missing removal target) still bumps, which can only
     /// cost an unnecessary re-specialization, never a stale read.
     epoch: std::sync::Arc<std::sync::atomic::AtomicU64>,
+    /// Relation definitions [`Self::check`] expands, from the deployed
+    /// schema. Configuration, not data: [`Self::clear`] keeps them.
+    rewrites: ArcSwapOption<RelationRewrites>,
+    /// `(declaring entity, relation, subject)` -> when that edge holds, for
+    /// the edges added with an expiry or caveat. Most graphs have none.
+    conditions: DashMap<(EntityId, InternedString, EntityId), Arc<EdgeCondition>>,
+    /// `conditions.len()`, kept alongside so unconditional graphs skip the
+    /// lookup on every edge a traversal crosses.
+    conditional: AtomicUsize,
+    /// Compiled caveats by source: a caveat shared by many edges (the usual
+    /// case — one condition per grant policy) is parsed once.
+    caveats: DashMap<String, Arc<Caveat>>,
+}
+
+/// When a conditional edge holds.
+#[derive(Debug)]
+struct EdgeCondition {
+    /// Unix seconds; the edge is gone from this instant on.
+    expires_at: Option<i64>,
+    caveat: Option<Arc<Caveat>>,
+}
+
+impl EdgeCondition {
+    fn holds(&self) -> bool {
+        if let Some(expires_at) = self.expires_at {
+            if unix_now() >= expires_at {
+                return false;
+            }
+        }
+        match &self.caveat {
+            None => true,
+            Some(caveat) => match CAVEAT_CONTEXT.with(|c| c.borrow().clone()) {
+                Some(context) => caveat.holds(&context),
+                None => false,
+            },
+        }
+    }
+}
+
+/// Through chrono, which has a clock on wasm32 (`wasmbind`); `SystemTime`
+/// does not.
+fn unix_now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+/// One evaluation's copy of its request context for caveats, made on the
+/// first [`RelationshipGraph::caveat_scope_in`] and shared by every later
+/// ReBAC condition of the same evaluation.
+#[derive(Debug, Clone, Default)]
+pub struct CaveatContextCell(std::cell::OnceCell<Rc<CaveatContext>>);
+
+/// Installs a request context for caveats on this thread until dropped
+/// (restoring the previous one). See [`RelationshipGraph::caveat_scope`].
+#[must_use = "the context is uninstalled when the scope drops"]
+pub struct CaveatScope {
+    previous: Option<Option<Rc<CaveatContext>>>,
+}
+
+impl Drop for CaveatScope {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            CAVEAT_CONTEXT.with(|c| *c.borrow_mut() = previous);
+        }
+    }
 }
 
 impl RelationshipGraph {
@@ -132,9 +235,31 @@ impl RelationshipGraph {
             subject_rels: DashMap::new(),
             interner,
             epoch,
+            rewrites: ArcSwapOption::empty(),
+            conditions: DashMap::new(),
+            conditional: AtomicUsize::new(0),
+            caveats: DashMap::new(),
         }
     }
 
+    /// Install the relation definitions `schema` declares (or remove them
+    /// with `None`); [`Self::check`] expands them from here on.
+    pub fn set_schema(&self, schema: Option<&EntitySchema>) -> Result<(), ReaperError> {
+        let rewrites = match schema {
+            Some(schema) => Some(RelationRewrites::compile(schema, &self.interner)?),
+            None => None,
+        };
+        self.rewrites
+            .store(rewrites.filter(|r| !r.is_empty()).map(std::sync::Arc::new));
+        self.bump_epoch();
+        Ok(())
+    }
+
+    /// The installed relation definitions, if any.
+    pub fn rewrites(&self) -> Option<std::sync::Arc<RelationRewrites>> {
+        self.rewrites.load_full()
+    }
+
     fn bump_epoch(&self) {
         self.epoch
             .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
@@ -148,6 +273,128 @@ impl RelationshipGraph {
     /// invariant is **exactly one counted subject reference per live forward
     /// edge**, balanced by the release in every removal path below.
     pub fn add_edge(&self, from: EntityId, relation: InternedString, to: EntityId) {
+        self.insert_edge(from, relation, to);
+        // Re-adding an edge unconditionally lifts any expiry or caveat.
+        self.drop_condition(from, relation, to);
+        self.bump_epoch();
+    }
+
+    /// Record `from #relation @to` holding only until `expires_at` (unix
+    /// seconds) and only while `caveat` — a `.reap` condition over `context`
+    /// — holds for the evaluating request. With neither, this is
+    /// [`Self::add_edge`]. Re-adding the edge replaces its condition. A
+    /// caveat that does not parse is an error and adds nothing.
+    pub fn add_conditional_edge(
+        &self,
+        from: EntityId,
+        relation: InternedString,
+        to: EntityId,
+        expires_at: Option<i64>,
+        caveat: Option<&str>,
+    ) -> Result<(), ReaperError> {
+        if expires_at.is_none() && caveat.is_none() {
+            self.add_edge(from, relation, to);
+            return Ok(());
+        }
+        let caveat = match caveat {
+            Some(source) => Some(self.compile_caveat(source)?),
+            None => None,
+        };
+        self.insert_edge(from, relation, to);
+        let condition = Arc::new(EdgeCondition { expires_at, caveat });
+        if self
+            .conditions
+            .insert((from, relation, to), condition)
+            .is_none()
+        {
+            self.conditional.fetch_add(1, Ordering::Relaxed);
+        }
+        self.bump_epoch();
+        Ok(())
+    }
+
+    /// The compiled caveat for `source`, parsed on first use.
+    pub(crate) fn compile_caveat(&self, source: &str) -> Result<Arc<Caveat>, ReaperError> {
+        if let Some(caveat) = self.caveats.get(source) {
+            return Ok(caveat.clone());
+        }
+        let caveat = Arc::new(Caveat::compile(source)?);
+        Ok(self
+            .caveats
+            .entry(source.to_string())
+            .or_insert(caveat)
+            .clone())
+    }
+
+    fn drop_condition(&self, from: EntityId, relation: InternedString, to: EntityId) {
+        if self.conditional.load(Ordering::Relaxed) != 0
+            && self.conditions.remove(&(from, relation, to)).is_some()
+        {
+            self.conditional.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Does the stored edge `from #relation @to` hold right now? Always for
+    /// an unconditional edge.
+    #[inline]
+    fn visible(&self, from: EntityId, relation: InternedString, to: EntityId) -> bool {
+        if self.conditional.load(Ordering::Relaxed) == 0 {
+            return true;
+        }
+        // Cloned out so no shard guard is held while a caveat evaluates.
+        let condition = self
+            .conditions
+            .get(&(from, relation, to))
+            .map(|c| c.clone());
+        condition.is_none_or(|c| c.holds())
+    }
+
+    /// Make `context` the request context caveats read on this thread until
+    /// the returned scope drops. `action`, when given, is visible as
+    /// `context.action` (the interpreter already folds it into its context).
+    /// Copies the context only if the graph holds a conditional edge.
+    pub fn caveat_scope(
+        &self,
+        context: &HashMap<String, serde_json::Value>,
+        action: Option<&str>,
+    ) -> CaveatScope {
+        self.caveat_scope_in(&CaveatContextCell::default(), context, action)
+    }
+
+    /// [`Self::caveat_scope`] for a condition inside an evaluation: the
+    /// context is copied into `cell` once, so an evaluation that runs many
+    /// ReBAC conditions pays for one copy, not one per condition.
+    pub fn caveat_scope_in(
+        &self,
+        cell: &CaveatContextCell,
+        context: &HashMap<String, serde_json::Value>,
+        action: Option<&str>,
+    ) -> CaveatScope {
+        if self.conditional.load(Ordering::Relaxed) == 0 {
+            return CaveatScope { previous: None };
+        }
+        let context = cell
+            .0
+            .get_or_init(|| {
+                let mut context = context.clone();
+                if let Some(action) = action {
+                    context.insert("action".to_string(), action.into());
+                }
+                Rc::new(context)
+            })
+            .clone();
+        let previous = CAVEAT_CONTEXT.with(|c| c.borrow_mut().replace(context));
+        CaveatScope {
+            previous: Some(previous),
+        }
+    }
+
+    /// Number of edges carrying an expiry or caveat (diagnostics).
+    pub fn conditional_len(&self) -> usize {
+        self.conditional.load(Ordering::Relaxed)
+    }
+
+    fn insert_edge(&self, from: EntityId, relation: InternedString, to: EntityId) {
         let is_new = insert_sorted(
             self.forward
                 .entry((from, relation))
@@ -165,7 +412,6 @@ impl RelationshipGraph {
             // Duplicate edge — the loader's extra counted reference is redundant.
             self.interner.release(to);
         }
-        self.bump_epoch();
     }
 
     /// Remove one edge (both directions). Idempotent: removing a missing
@@ -173,6 +419,7 @@ impl RelationshipGraph {
     pub fn remove_edge(&self, from: EntityId, relation: InternedString, to: EntityId) {
         let removed = remove_from_list(&self.forward, (from, relation), to);
         remove_from_list(&self.reverse, (to, relation), from);
+        self.drop_condition(from, relation, to);
         if removed {
             // Balances the one counted reference this live edge held on `to`.
             self.interner.release(to);
@@ -190,6 +437,7 @@ impl RelationshipGraph {
                 if let Some((_, targets)) = self.forward.remove(&(entity, rel)) {
                     for target in targets {
                         remove_from_list(&self.reverse, (target, rel), entity);
+                        self.drop_condition(entity, rel, target);
                         // Each removed forward edge held one count on its subject.
                         self.interner.release(target);
                     }
@@ -208,6 +456,7 @@ impl RelationshipGraph {
             for rel in rels {
                 if let Some((_, carriers)) = self.reverse.remove(&(entity, rel)) {
                     for carrier in carriers {
+                        self.drop_condition(carrier, rel, entity);
                         // `entity` is this edge's subject; release its count
                         // only if the forward edge was actually present.
                         if remove_from_list(&self.forward, (carrier, rel), entity) {
@@ -224,17 +473,31 @@ impl RelationshipGraph {
 
     /// Subjects of `object #relation` (direct, one lookup).
     pub fn related(&self, object: EntityId, relation: InternedString) -> EdgeList {
-        self.forward
-            .get(&(object, relation))
-            .map(|e| e.clone())
-            .unwrap_or_default()
+        let mut subjects = self.stored(object, relation);
+        if self.conditional.load(Ordering::Relaxed) != 0 {
+            subjects.retain(|subject| self.visible(object, relation, *subject));
+        }
+        subjects
     }
 
     /// Objects that declare `#relation @subject` (reverse lookup).
     pub fn related_to(&self, subject: EntityId, relation: InternedString) -> EdgeList {
-        self.reverse
+        let mut objects = self
+            .reverse
             .get(&(subject, relation))
             .map(|e| e.clone())
+            .unwrap_or_default();
+        if self.conditional.load(Ordering::Relaxed) != 0 {
+            objects.retain(|object| self.visible(*object, relation, subject));
+        }
+        objects
+    }
+
+    /// Stored subjects of `object #relation`, conditional edges included.
+    fn stored(&self, object: EntityId, relation: InternedString) -> EdgeList {
+        self.forward
+            .get(&(object, relation))
+            .map(|e| e.clone())
             .unwrap_or_default()
     }
 
@@ -247,10 +510,12 @@ impl RelationshipGraph {
         relation: InternedString,
         subject: EntityId,
     ) -> bool {
-        self.forward
+        let stored = self
+            .forward
             .get(&(object, relation))
             .map(|edges| edges.binary_search(&subject).is_ok())
-            .unwrap_or(false)
+            .unwrap_or(false);
+        stored && self.visible(object, relation, subject)
     }
 
     /// Subject-side expansion: does `subject` hold `relation` on `object`
@@ -275,11 +540,10 @@ impl RelationshipGraph {
         // Copy out in a single statement so the shard read-guard drops before
         // the BFS re-enters the map (never hold a lock across traversal —
         // a queued writer on the same shard must not be able to wedge us).
-        let Some(holders): Option<EdgeList> =
-            self.forward.get(&(object, relation)).map(|e| e.clone())
-        else {
+        let holders = self.related(object, relation);
+        if holders.is_empty() {
             return false;
-        };
+        }
 
         self.bfs_reaches(subject, via, max_depth, |node| {
             holders.binary_search(&node).is_ok()
@@ -306,6 +570,43 @@ impl RelationshipGraph {
         })
     }
 
+    /// Schema check: does `subject` hold `relation` on `object` as the
+    /// installed rewrites define it for the object's type (`type_of`)? A
+    /// relation without a rewrite — or any relation when none are installed —
+    /// is its stored tuples, exactly [`Self::has_relation`].
+    ///
+    /// Every `(object, relation)` expanded is charged against the traversal
+    /// budget, and a relation met again on its own expansion path
+    /// contributes nothing (the least fixed point). Running out of budget or
+    /// depth, or a cycle inside an exclusion's subtracted side, fails the
+    /// whole check closed, so an unfinished exclusion never grants.
+    pub fn check(
+        &self,
+        object: EntityId,
+        relation: InternedString,
+        subject: EntityId,
+        type_of: &dyn Fn(EntityId) -> Option<EntityType>,
+    ) -> bool {
+        let Some(rewrites) = self.rewrites.load_full() else {
+            return self.has_relation(object, relation, subject);
+        };
+        let cap = EVAL_BUDGET.with(|b| b.get()).min(TRAVERSAL_NODE_BUDGET);
+        if cap == 0 {
+            return false;
+        }
+        let mut expansion = Expansion {
+            graph: self,
+            rewrites: &rewrites,
+            type_of,
+            subject,
+            path: FxHashSet::default(),
+            remaining: cap,
+        };
+        let found = expansion.relation(object, relation, 0, false);
+        EVAL_BUDGET.with(|b| b.set(b.get().saturating_sub(cap - expansion.remaining)));
+        found == Found::Yes
+    }
+
     /// Bounded, cycle-safe BFS from `start` along `edge` (forward direction),
     /// returning true as soon as `hit` matches a visited node (start excluded
     /// from the first check only via the caller's predicate when needed).
@@ -377,6 +678,9 @@ impl RelationshipGraph {
             let nexts: Option<EdgeList> = self.forward.get(&(node, edge)).map(|e| e.clone());
             if let Some(nexts) = nexts {
                 for next in nexts {
+                    if !visited.contains(&next) && !self.visible(node, edge, next) {
+                        continue;
+                    }
                     if visited.insert(next) {
                         if hit(next) {
                             return true;
@@ -399,9 +703,42 @@ impl RelationshipGraph {
         self.reverse.clear();
         self.carrier_rels.clear();
         self.subject_rels.clear();
+        self.conditions.clear();
+        self.conditional.store(0, Ordering::Relaxed);
+        self.caveats.clear();
         self.bump_epoch();
     }
 
+    /// Every relation `entity` carries, with its subjects (inspection —
+    /// `reaper-cli repl`; never on the evaluation path). Stored edges:
+    /// expired and caveated ones are listed too.
+    pub fn carried_edges(&self, entity: EntityId) -> Vec<(InternedString, EdgeList)> {
+        let rels: SmallVec<[InternedString; 4]> = self
+            .carrier_rels
+            .get(&entity)
+            .map(|r| r.clone())
+            .unwrap_or_default();
+        rels.into_iter()
+            .map(|rel| (rel, self.stored(entity, rel)))
+            .collect()
+    }
+
+    /// Every relation `entity` is a subject of, with the entities declaring
+    /// it (inspection, like [`Self::carried_edges`]).
+    pub fn subject_edges(&self, entity: EntityId) -> Vec<(InternedString, EdgeList)> {
+        let rels: SmallVec<[InternedString; 4]> = self
+            .subject_rels
+            .get(&entity)
+            .map(|r| r.clone())
+            .unwrap_or_default();
+        rels.into_iter()
+            .map(|rel| {
+                let objects = self.reverse.get(&(entity, rel)).map(|e| e.clone());
+                (rel, objects.unwrap_or_default())
+            })
+            .collect()
+    }
+
     /// Total number of forward edge lists (diagnostics).
     pub fn len(&self) -> usize {
         self.forward.len()
@@ -412,6 +749,134 @@ impl RelationshipGraph {
     }
 }
 
+/// What expanding part of a rewrite established.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Found {
+    Yes,
+    No,
+    /// Budget or depth ran out, or a cycle crossed an exclusion: unknown,
+    /// which the check reports as "no".
+    Exhausted,
+}
+
+impl From<bool> for Found {
+    fn from(held: bool) -> Self {
+        if held {
+            Found::Yes
+        } else {
+            Found::No
+        }
+    }
+}
+
+/// One [`RelationshipGraph::check`] in progress.
+struct Expansion<'a> {
+    graph: &'a RelationshipGraph,
+    rewrites: &'a RelationRewrites,
+    type_of: &'a dyn Fn(EntityId) -> Option<EntityType>,
+    subject: EntityId,
+    /// `(object, relation)` pairs on the current expansion path.
+    path: FxHashSet<(EntityId, InternedString)>,
+    remaining: usize,
+}
+
+impl Expansion<'_> {
+    /// `subject ∈ object#relation`, rewritten if the object's type defines
+    /// the relation. `negated` is set below an exclusion's subtracted side.
+    fn relation(
+        &mut self,
+        object: EntityId,
+        relation: InternedString,
+        depth: usize,
+        negated: bool,
+    ) -> Found {
+        if self.remaining == 0 {
+            return Found::Exhausted;
+        }
+        self.remaining -= 1;
+        let rewrites = self.rewrites;
+        let Some(rewrite) = (self.type_of)(object).and_then(|t| rewrites.get(t, relation)) else {
+            return self
+                .graph
+                .has_relation(object, relation, self.subject)
+                .into();
+        };
+        if depth >= MAX_REWRITE_DEPTH {
+            return Found::Exhausted;
+        }
+        if !self.path.insert((object, relation)) {
+            return if negated { Found::Exhausted } else { Found::No };
+        }
+        let found = self.rewrite(rewrite, object, relation, depth, negated);
+        self.path.remove(&(object, relation));
+        found
+    }
+
+    fn rewrite(
+        &mut self,
+        rewrite: &Rewrite,
+        object: EntityId,
+        relation: InternedString,
+        depth: usize,
+        negated: bool,
+    ) -> Found {
+        match rewrite {
+            Rewrite::This => self
+                .graph
+                .has_relation(object, relation, self.subject)
+                .into(),
+            Rewrite::Computed(other) => self.relation(object, *other, depth + 1, negated),
+            Rewrite::TupleToUserset {
+                relation: target_relation,
+                tupleset,
+            } => {
+                // Copied out so no shard guard is held across the expansion.
+                let mut found = Found::No;
+                for target in self.graph.related(object, *tupleset) {
+                    match self.relation(target, *target_relation, depth + 1, negated) {
+                        Found::Yes => return Found::Yes,
+                        Found::Exhausted => found = Found::Exhausted,
+                        Found::No => {}
+                    }
+                }
+                found
+            }
+            Rewrite::Union(items) => {
+                let mut found = Found::No;
+                for item in items {
+                    match self.rewrite(item, object, relation, depth, negated) {
+                        Found::Yes => return Found::Yes,
+                        Found::Exhausted => found = Found::Exhausted,
+                        Found::No => {}
+                    }
+                }
+                found
+            }
+            Rewrite::Intersection(items) => {
+                let mut found = Found::Yes;
+                for item in items {
+                    match self.rewrite(item, object, relation, depth, negated) {
+                        Found::No => return Found::No,
+                        Found::Exhausted => found = Found::Exhausted,
+                        Found::Yes => {}
+                    }
+                }
+                found
+            }
+            Rewrite::Exclusion(base, subtract) => {
+                match self.rewrite(base, object, relation, depth, negated) {
+                    Found::Yes => match self.rewrite(subtract, object, relation, depth, true) {
+                        Found::Yes => Found::No,
+                        Found::No => Found::Yes,
+                        Found::Exhausted => Found::Exhausted,
+                    },
+                    other => other,
+                }
+            }
+        }
+    }
+}
+
 /// Insert into the sorted list, returning `true` if the value was new (the
 /// list changed) and `false` if it was already present. Callers use the flag
 /// to keep the interner's subject refcount balanced against idempotent adds.
@@ -646,6 +1111,138 @@ mod tests {
         );
     }
 
+    /// A Drive-style schema: docs inherit viewers from parent folders,
+    /// editors view, group viewers expand to their members, and banned
+    /// users are excluded.
+    fn drive_schema() -> EntitySchema {
+        EntitySchema::from_json(
+            r#"{"entity_types": {
+                "User": {},
+                "Group": {"relations": {"member": ["User"]}},
+                "Folder": {
+                    "relations": {"viewer": ["User"], "parent": ["Folder"]},
+                    "rewrites": {"viewer": "viewer + viewer from parent"}
+                },
+                "Doc": {
+                    "relations": {"editor": ["User"], "viewer": ["User", "Group"],
+                                  "banned": ["User"], "parent": ["Folder"]},
+                    "rewrites": {
+                        "viewer": "(viewer + editor + viewer from parent + member from viewer) - banned"
+                    }
+                }
+            }}"#,
+        )
+        .unwrap()
+    }
+
+    /// Entity types for the check: by id prefix, as the store would answer.
+    fn type_of(i: &StringInterner) -> impl Fn(EntityId) -> Option<EntityType> + '_ {
+        move |id| {
+            let name = i.resolve_str(id)?;
+            let ty = ["doc", "folder", "group"]
+                .into_iter()
+                .find(|p| name.starts_with(p))
+                .map_or("User", |p| match p {
+                    "doc" => "Doc",
+                    "folder" => "Folder",
+                    _ => "Group",
+                });
+            Some(i.intern(ty))
+        }
+    }
+
+    #[test]
+    fn check_expands_rewrites() {
+        let (g, i) = graph();
+        g.set_schema(Some(&drive_schema())).unwrap();
+        let rel = |name: &str| i.intern(name);
+        let (doc, root, sub, eng) = (
+            i.intern("doc1"),
+            i.intern("folder-root"),
+            i.intern("folder-sub"),
+            i.intern("group-eng"),
+        );
+        let [alice, bob, carol, dave, erin] =
+            ["alice", "bob", "carol", "dave", "erin"].map(|u| i.intern(u));
+        g.add_edge(doc, rel("parent"), sub);
+        g.add_edge(sub, rel("parent"), root);
+        g.add_edge(root, rel("viewer"), alice); // two folders up
+        g.add_edge(doc, rel("editor"), bob); // editors view
+        g.add_edge(doc, rel("viewer"), eng);
+        g.add_edge(eng, rel("member"), carol); // through the group
+        g.add_edge(doc, rel("editor"), dave);
+        g.add_edge(doc, rel("banned"), dave); // excluded despite editing
+
+        reset_traversal_budget();
+        let types = type_of(&i);
+        let check = |subject| g.check(doc, rel("viewer"), subject, &types);
+        assert!(check(alice), "viewer from parent, twice");
+        assert!(check(bob), "editor");
+        assert!(check(carol), "member from viewer");
+        assert!(check(eng), "the group itself is a stored viewer");
+        assert!(!check(dave), "banned is subtracted");
+        assert!(!check(erin));
+        // A relation without a rewrite is its stored tuples.
+        assert!(g.check(doc, rel("editor"), bob, &types));
+        assert!(!g.check(doc, rel("editor"), alice, &types));
+
+        // Without a schema, check is the direct relation.
+        g.set_schema(None).unwrap();
+        assert!(!g.check(doc, rel("viewer"), alice, &types));
+        assert!(g.check(doc, rel("viewer"), eng, &types));
+    }
+
+    #[test]
+    fn check_survives_cycles_and_fails_closed() {
+        let (g, i) = graph();
+        g.set_schema(Some(&drive_schema())).unwrap();
+        let rel = |name: &str| i.intern(name);
+        let (a, b, doc, alice) = (
+            i.intern("folder-a"),
+            i.intern("folder-b"),
+            i.intern("doc1"),
+            i.intern("alice"),
+        );
+        g.add_edge(a, rel("parent"), b);
+        g.add_edge(b, rel("parent"), a); // folder cycle
+        reset_traversal_budget();
+        let types = type_of(&i);
+        assert!(!g.check(a, rel("viewer"), alice, &types));
+        g.add_edge(b, rel("viewer"), alice);
+        assert!(g.check(a, rel("viewer"), alice, &types));
+
+        // An exhausted budget denies even a stored viewer — and never lets
+        // an unfinished `- banned` grant.
+        g.add_edge(doc, rel("viewer"), alice);
+        EVAL_BUDGET.with(|b| b.set(1));
+        assert!(!g.check(doc, rel("viewer"), alice, &types));
+        reset_traversal_budget();
+        assert!(g.check(doc, rel("viewer"), alice, &types));
+    }
+
+    #[test]
+    fn check_depth_is_bounded() {
+        let (g, i) = graph();
+        g.set_schema(Some(&drive_schema())).unwrap();
+        let (parent, viewer, alice) = (i.intern("parent"), i.intern("viewer"), i.intern("alice"));
+        let folders: Vec<_> = (0..=MAX_REWRITE_DEPTH + 1)
+            .map(|k| i.intern(&format!("folder-{k}")))
+            .collect();
+        for w in folders.windows(2) {
+            g.add_edge(w[0], parent, w[1]);
+        }
+        let types = type_of(&i);
+        reset_traversal_budget();
+        g.add_edge(folders[MAX_REWRITE_DEPTH - 1], viewer, alice);
+        assert!(g.check(folders[0], viewer, alice, &types));
+        g.remove_edge(folders[MAX_REWRITE_DEPTH - 1], viewer, alice);
+        g.add_edge(folders[MAX_REWRITE_DEPTH + 1], viewer, alice);
+        assert!(
+            !g.check(folders[0], viewer, alice, &types),
+            "past the rewrite depth the check fails closed"
+        );
+    }
+
     // --- Subject refcount balance (white-box) --------------------------------
     //
     // These mirror the loader's contract exactly: a subject is `intern_counted`
@@ -0,0 +1,459 @@
+//! Declarative relation definitions: Zanzibar-style userset rewrites.
+//!
+//! An entity type in an [`EntitySchema`] may define a relation in terms of
+//! other relations instead of (or on top of) stored tuples:
+//!
+//! ```json
+//! "Document": {
+//!   "relations": {"owner": ["User"], "editor": ["User"],
+//!                 "viewer": ["User", "Group"], "parent": ["Folder"]},
+//!   "rewrites": {
+//!     "editor": "editor + owner",
+//!     "viewer": "viewer + editor + viewer from parent + member from viewer"
+//!   }
+//! }
+//! ```
+//!
+//! A rewrite is an expression over relation names on the same object:
+//!
+//! - `name` — the relation `name`, itself rewritten if it has a rewrite. The
+//!   defining relation's own name means its stored tuples (Zanzibar's
+//!   `_this`), so `"editor": "editor + owner"` extends rather than recurses.
+//! - `rel from tupleset` — tuple-to-userset: `rel` on every entity the
+//!   object's `tupleset` edges point at (`viewer from parent` walks up to
+//!   the parent folder; `member from viewer` expands group viewers).
+//! - `a + b` union, `a & b` intersection, `a - b` exclusion, `( … )`
+//!   grouping. `&` binds tighter than `+` and `-`, which associate left.
+//!
+//! Rewrites are parsed and checked when the schema is (see
+//! [`EntitySchema::validate`]) and interned once when installed on a
+//! [`RelationshipGraph`](super::RelationshipGraph), which expands them for
+//! `rebac::check`.
+
+use super::interning::{InternedString, StringInterner};
+use super::schema::EntitySchema;
+use super::EntityType;
+use reaper_core::ReaperError;
+use rustc_hash::FxHashMap;
+
+/// A parsed rewrite expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Userset {
+    /// The defining relation's stored tuples.
+    This,
+    /// Another relation on the same object.
+    Computed(String),
+    /// `relation` on every entity the object's `tupleset` edges point at.
+    TupleToUserset {
+        relation: String,
+        tupleset: String,
+    },
+    Union(Vec<Userset>),
+    Intersection(Vec<Userset>),
+    /// Members of the first set that are not members of the second.
+    Exclusion(Box<Userset>, Box<Userset>),
+}
+
+impl Userset {
+    /// Parse the rewrite of relation `defining` (which is what a bare
+    /// `defining` inside `source` refers to).
+    pub fn parse(defining: &str, source: &str) -> Result<Self, String> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            pos: 0,
+            defining,
+        };
+        let userset = parser.union()?;
+        match parser.peek() {
+            None => Ok(userset),
+            Some(token) => Err(format!("unexpected {}", token.describe())),
+        }
+    }
+
+    /// Every `(relation, tupleset)` the expression names: `(name, None)` for
+    /// a computed relation, `(rel, Some(tupleset))` for tuple-to-userset.
+    pub fn references(&self) -> Vec<(&str, Option<&str>)> {
+        let mut out = Vec::new();
+        self.collect_references(&mut out);
+        out
+    }
+
+    fn collect_references<'a>(&'a self, out: &mut Vec<(&'a str, Option<&'a str>)>) {
+        match self {
+            Userset::This => {}
+            Userset::Computed(name) => out.push((name, None)),
+            Userset::TupleToUserset { relation, tupleset } => out.push((relation, Some(tupleset))),
+            Userset::Union(items) | Userset::Intersection(items) => {
+                for item in items {
+                    item.collect_references(out);
+                }
+            }
+            Userset::Exclusion(base, subtract) => {
+                base.collect_references(out);
+                subtract.collect_references(out);
+            }
+        }
+    }
+
+    /// Whether the expression reads the defining relation's stored tuples.
+    pub fn reads_this(&self) -> bool {
+        match self {
+            Userset::This => true,
+            Userset::Computed(_) | Userset::TupleToUserset { .. } => false,
+            Userset::Union(items) | Userset::Intersection(items) => {
+                items.iter().any(Userset::reads_this)
+            }
+            Userset::Exclusion(base, subtract) => base.reads_this() || subtract.reads_this(),
+        }
+    }
+}
+
+/// A rewrite with every name interned.
+#[derive(Debug, Clone)]
+pub(crate) enum Rewrite {
+    This,
+    Computed(InternedString),
+    TupleToUserset {
+        relation: InternedString,
+        tupleset: InternedString,
+    },
+    Union(Vec<Rewrite>),
+    Intersection(Vec<Rewrite>),
+    Exclusion(Box<Rewrite>, Box<Rewrite>),
+}
+
+impl Rewrite {
+    fn intern(userset: &Userset, interner: &StringInterner) -> Self {
+        match userset {
+            Userset::This => Rewrite::This,
+            Userset::Computed(name) => Rewrite::Computed(interner.intern(name)),
+            Userset::TupleToUserset { relation, tupleset } => Rewrite::TupleToUserset {
+                relation: interner.intern(relation),
+                tupleset: interner.intern(tupleset),
+            },
+            Userset::Union(items) => {
+                Rewrite::Union(items.iter().map(|i| Self::intern(i, interner)).collect())
+            }
+            Userset::Intersection(items) => {
+                Rewrite::Intersection(items.iter().map(|i| Self::intern(i, interner)).collect())
+            }
+            Userset::Exclusion(base, subtract) => Rewrite::Exclusion(
+                Box::new(Self::intern(base, interner)),
+                Box::new(Self::intern(subtract, interner)),
+            ),
+        }
+    }
+
+    /// The `This`, `Computed` and `TupleToUserset` nodes of the expression,
+    /// on both sides of every operator — what a walk that over-approximates
+    /// the relation (the lookups) follows.
+    pub(crate) fn leaves(&self) -> Vec<&Rewrite> {
+        let mut out = Vec::new();
+        self.collect_leaves(&mut out);
+        out
+    }
+
+    fn collect_leaves<'a>(&'a self, out: &mut Vec<&'a Rewrite>) {
+        match self {
+            Rewrite::This | Rewrite::Computed(_) | Rewrite::TupleToUserset { .. } => out.push(self),
+            Rewrite::Union(items) | Rewrite::Intersection(items) => {
+                for item in items {
+                    item.collect_leaves(out);
+                }
+            }
+            Rewrite::Exclusion(base, subtract) => {
+                base.collect_leaves(out);
+                subtract.collect_leaves(out);
+            }
+        }
+    }
+}
+
+/// Every rewrite a schema declares, keyed by interned `(entity type,
+/// relation)`.
+#[derive(Debug, Default)]
+pub struct RelationRewrites {
+    rules: FxHashMap<(EntityType, InternedString), Rewrite>,
+}
+
+impl RelationRewrites {
+    /// Intern the rewrites `schema` declares. Type and relation names are
+    /// pinned (a bounded vocabulary, like relations the loader interns).
+    pub fn compile(schema: &EntitySchema, interner: &StringInterner) -> Result<Self, ReaperError> {
+        let mut rules = FxHashMap::default();
+        for (type_name, entity_type) in &schema.entity_types {
+            for (relation, source) in &entity_type.rewrites {
+                let userset =
+                    Userset::parse(relation, source).map_err(|e| ReaperError::InvalidPolicy {
+                        reason: format!(
+                            "invalid entity schema: rewrite {type_name}.{relation}: {e}"
+                        ),
+                    })?;
+                rules.insert(
+                    (interner.intern(type_name), interner.intern(relation)),
+                    Rewrite::intern(&userset, interner),
+                );
+            }
+        }
+        Ok(Self { rules })
+    }
+
+    /// The rewrite of `relation` on entities of `entity_type`, if declared.
+    pub(crate) fn get(
+        &self,
+        entity_type: EntityType,
+        relation: InternedString,
+    ) -> Option<&Rewrite> {
+        self.rules.get(&(entity_type, relation))
+    }
+
+    /// Every `((entity type, relation), rewrite)`, in no particular order.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&(EntityType, InternedString), &Rewrite)> {
+        self.rules.iter()
+    }
+
+    /// Number of declared rewrites.
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Name(String),
+    From,
+    Plus,
+    Minus,
+    Amp,
+    Open,
+    Close,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Name(name) => format!("`{name}`"),
+            Token::From => "`from`".to_string(),
+            Token::Plus => "`+`".to_string(),
+            Token::Minus => "`-`".to_string(),
+            Token::Amp => "`&`".to_string(),
+            Token::Open => "`(`".to_string(),
+            Token::Close => "`)`".to_string(),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        let token = match c {
+            c if c.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '&' => Token::Amp,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut end = start;
+                while let Some(&(i, c)) = chars.peek() {
+                    if !(c.is_ascii_alphanumeric() || c == '_') {
+                        break;
+                    }
+                    end = i + c.len_utf8();
+                    chars.next();
+                }
+                tokens.push(match &source[start..end] {
+                    "from" => Token::From,
+                    name => Token::Name(name.to_string()),
+                });
+                continue;
+            }
+            other => return Err(format!("unexpected character {other:?}")),
+        };
+        chars.next();
+        tokens.push(token);
+    }
+    if tokens.is_empty() {
+        return Err("empty rewrite".to_string());
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    defining: &'a str,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<&Token> {
+        let token = self.tokens.get(self.pos);
+        self.pos += 1;
+        token
+    }
+
+    /// `intersection (('+' | '-') intersection)*`, left-associative.
+    fn union(&mut self) -> Result<Userset, String> {
+        let mut left = self.intersection()?;
+        loop {
+            match self.peek() {
+                Some(Token::Plus) => {
+                    self.pos += 1;
+                    let right = self.intersection()?;
+                    left = match left {
+                        Userset::Union(mut items) => {
+                            items.push(right);
+                            Userset::Union(items)
+                        }
+                        left => Userset::Union(vec![left, right]),
+                    };
+                }
+                Some(Token::Minus) => {
+                    self.pos += 1;
+                    let right = self.intersection()?;
+                    left = Userset::Exclusion(Box::new(left), Box::new(right));
+                }
+                _ => return Ok(left),
+            }
+        }
+    }
+
+    /// `operand ('&' operand)*`.
+    fn intersection(&mut self) -> Result<Userset, String> {
+        let first = self.operand()?;
+        let mut items = vec![first];
+        while self.peek() == Some(&Token::Amp) {
+            self.pos += 1;
+            items.push(self.operand()?);
+        }
+        Ok(if items.len() == 1 {
+            items.remove(0)
+        } else {
+            Userset::Intersection(items)
+        })
+    }
+
+    /// `name ['from' name] | '(' union ')'`.
+    fn operand(&mut self) -> Result<Userset, String> {
+        match self.next().cloned() {
+            Some(Token::Open) => {
+                let inner = self.union()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(inner),
+                    Some(token) => Err(format!("expected `)`, found {}", token.describe())),
+                    None => Err("expected `)`".to_string()),
+                }
+            }
+            Some(Token::Name(name)) => {
+                if self.peek() != Some(&Token::From) {
+                    return Ok(if name == self.defining {
+                        Userset::This
+                    } else {
+                        Userset::Computed(name)
+                    });
+                }
+                self.pos += 1;
+                match self.next() {
+                    Some(Token::Name(tupleset)) => Ok(Userset::TupleToUserset {
+                        relation: name,
+                        tupleset: tupleset.clone(),
+                    }),
+                    Some(token) => Err(format!(
+                        "expected a relation after `from`, found {}",
+                        token.describe()
+                    )),
+                    None => Err("expected a relation after `from`".to_string()),
+                }
+            }
+            Some(token) => Err(format!(
+                "expected a relation name, found {}",
+                token.describe()
+            )),
+            None => Err("expected a relation name".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> Userset {
+        Userset::parse("viewer", source).unwrap()
+    }
+
+    fn computed(name: &str) -> Userset {
+        Userset::Computed(name.to_string())
+    }
+
+    #[test]
+    fn own_name_means_stored_tuples() {
+        assert_eq!(
+            parse("viewer + editor"),
+            Userset::Union(vec![Userset::This, computed("editor")])
+        );
+        assert_eq!(
+            parse("viewer from parent"),
+            Userset::TupleToUserset {
+                relation: "viewer".to_string(),
+                tupleset: "parent".to_string()
+            },
+            "`from` names the relation on the target, never `this`"
+        );
+    }
+
+    #[test]
+    fn precedence_and_grouping() {
+        // `&` binds tighter than `+`; `-` associates left with `+`.
+        assert_eq!(
+            parse("a + b & c - d"),
+            Userset::Exclusion(
+                Box::new(Userset::Union(vec![
+                    computed("a"),
+                    Userset::Intersection(vec![computed("b"), computed("c")]),
+                ])),
+                Box::new(computed("d")),
+            )
+        );
+        assert_eq!(
+            parse("a - (b + c)"),
+            Userset::Exclusion(
+                Box::new(computed("a")),
+                Box::new(Userset::Union(vec![computed("b"), computed("c")])),
+            )
+        );
+        assert_eq!(
+            parse("(a + b) + c"),
+            Userset::Union(vec![computed("a"), computed("b"), computed("c")])
+        );
+    }
+
+    #[test]
+    fn malformed_rewrites_are_rejected() {
+        for (source, expected) in [
+            ("", "empty rewrite"),
+            ("a +", "expected a relation name"),
+            ("a b", "unexpected `b`"),
+            ("a from", "expected a relation after `from`"),
+            ("from parent", "found `from`"),
+            ("(a + b", "expected `)`"),
+            ("a | b", "unexpected character '|'"),
+        ] {
+            let err = Userset::parse("viewer", source).unwrap_err();
+            assert!(err.contains(expected), "{source:?}: {err}");
+        }
+    }
+}
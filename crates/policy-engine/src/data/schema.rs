@@ -0,0 +1,549 @@
+//! Declared entity schemas
+//!
+//! An [`EntitySchema`] names the entity types a deployment's data holds, the
+//! attributes each type carries (type and optionality) and the relations it
+//! may declare, and says which types a request's `user`, `resource` and
+//! `actor` refer to. Two consumers read it:
+//!
+//! - the data loaders ([`DataLoader::with_schema`](super::DataLoader::with_schema))
+//!   reject non-conforming entity documents before anything is inserted;
+//! - the `.reap` type checker (`reap::typecheck`) resolves every attribute
+//!   path in a policy against it at compile/deploy time, so a typo'd
+//!   attribute is a build error instead of a `null` that never matches.
+//!
+//! Schemas are written as JSON:
+//!
+//! ```json
+//! {
+//!   "entity_types": {
+//!     "User": {
+//!       "attributes": {
+//!         "department": {"type": "string"},
+//!         "level": {"type": "number", "required": false},
+//!         "roles": {"type": "array", "items": "string"}
+//!       },
+//!       "relations": {"member_of": ["Group"]}
+//!     },
+//!     "Group": {},
+//!     "Document": {
+//!       "attributes": {"owner": {"type": "string"}},
+//!       "relations": {"viewer": ["User", "Group"]}
+//!     }
+//!   },
+//!   "request": {"user": ["User"], "resource": ["Document"]},
+//!   "context": {"ip": {"type": "string"}}
+//! }
+//! ```
+//!
+//! An entity type may also define relations in terms of others with
+//! `"rewrites"` (see [`rewrites`](super::rewrites)).
+//!
+//! Declared attribute sets are closed: an entity (or an `object` attribute
+//! with declared `attributes`) may not carry anything the schema does not
+//! list. A request slot with no types, or a schema without `context`, leaves
+//! that part unchecked.
+
+use super::loader::EntityDocument;
+use super::rewrites::Userset;
+use reaper_core::ReaperError;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Attribute value types. `number` covers integers and floats; `any` opts an
+/// attribute out of type checking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AttributeType {
+    String,
+    Number,
+    Bool,
+    Array,
+    Object,
+    Any,
+}
+
+impl AttributeType {
+    /// The type name as written in a schema.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AttributeType::String => "string",
+            AttributeType::Number => "number",
+            AttributeType::Bool => "bool",
+            AttributeType::Array => "array",
+            AttributeType::Object => "object",
+            AttributeType::Any => "any",
+        }
+    }
+
+    /// The type of a JSON value; `None` for `null`.
+    pub fn of_json(value: &JsonValue) -> Option<Self> {
+        match value {
+            JsonValue::Null => None,
+            JsonValue::Bool(_) => Some(AttributeType::Bool),
+            JsonValue::Number(_) => Some(AttributeType::Number),
+            JsonValue::String(_) => Some(AttributeType::String),
+            JsonValue::Array(_) => Some(AttributeType::Array),
+            JsonValue::Object(_) => Some(AttributeType::Object),
+        }
+    }
+
+    fn admits(self, value: &JsonValue) -> bool {
+        self == AttributeType::Any || AttributeType::of_json(value) == Some(self)
+    }
+}
+
+fn required_by_default() -> bool {
+    true
+}
+
+/// One attribute's declaration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AttributeSchema {
+    #[serde(rename = "type")]
+    pub ty: AttributeType,
+    /// Whether every entity of the type carries the attribute with a
+    /// non-null value. Defaults to `true`.
+    #[serde(default = "required_by_default")]
+    pub required: bool,
+    /// Element type of an `array` attribute; `None` leaves elements unchecked.
+    #[serde(default)]
+    pub items: Option<AttributeType>,
+    /// Fields of an `object` attribute. Empty leaves the object open.
+    #[serde(default)]
+    pub attributes: BTreeMap<String, AttributeSchema>,
+}
+
+/// One entity type's declaration.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EntityTypeSchema {
+    #[serde(default)]
+    pub attributes: BTreeMap<String, AttributeSchema>,
+    /// Relations entities of this type may carry (`relationships` in an
+    /// entity document), each with the entity types its subjects may have.
+    /// An empty list admits any subject type.
+    #[serde(default)]
+    pub relations: BTreeMap<String, Vec<String>>,
+    /// Relations defined in terms of others (`"viewer": "viewer + editor +
+    /// viewer from parent"`), expanded by `rebac::check`. See
+    /// [`rewrites`](super::rewrites) for the syntax.
+    #[serde(default)]
+    pub rewrites: BTreeMap<String, String>,
+}
+
+impl EntityTypeSchema {
+    /// Whether the type stores or rewrites a relation called `name`.
+    pub fn declares(&self, name: &str) -> bool {
+        self.relations.contains_key(name) || self.rewrites.contains_key(name)
+    }
+}
+
+/// Which entity types each request slot refers to. An empty list leaves the
+/// slot unchecked.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RequestSchema {
+    #[serde(default)]
+    pub user: Vec<String>,
+    #[serde(default)]
+    pub resource: Vec<String>,
+    #[serde(default)]
+    pub actor: Vec<String>,
+}
+
+impl RequestSchema {
+    /// Types bound to the slot named `slot` (`user`, `resource`, `actor`);
+    /// empty for anything else.
+    pub fn types_for(&self, slot: &str) -> &[String] {
+        match slot {
+            "user" => &self.user,
+            "resource" => &self.resource,
+            "actor" => &self.actor,
+            _ => &[],
+        }
+    }
+}
+
+/// A declared schema of entity types. See the module docs for the format.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EntitySchema {
+    #[serde(default)]
+    pub entity_types: BTreeMap<String, EntityTypeSchema>,
+    #[serde(default)]
+    pub request: RequestSchema,
+    /// Request-context attributes; `None` leaves `context.*` unchecked.
+    #[serde(default)]
+    pub context: Option<BTreeMap<String, AttributeSchema>>,
+}
+
+impl EntitySchema {
+    /// Parse a schema from JSON and check it is self-consistent.
+    pub fn from_json(json: &str) -> Result<Self, ReaperError> {
+        let schema: Self = serde_json::from_str(json).map_err(|e| ReaperError::InvalidPolicy {
+            reason: format!("invalid entity schema: {e}"),
+        })?;
+        schema.validate()?;
+        Ok(schema)
+    }
+
+    /// Read and parse a schema file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, ReaperError> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path).map_err(|e| ReaperError::InvalidPolicy {
+            reason: format!("Failed to read schema file {}: {e}", path.display()),
+        })?;
+        Self::from_json(&json)
+    }
+
+    /// Check that every type the schema refers to is declared and that
+    /// `items` / nested `attributes` only appear on array / object
+    /// attributes.
+    pub fn validate(&self) -> Result<(), ReaperError> {
+        let invalid = |reason: String| ReaperError::InvalidPolicy {
+            reason: format!("invalid entity schema: {reason}"),
+        };
+        let known = |name: &String, what: String| {
+            if self.entity_types.contains_key(name) {
+                Ok(())
+            } else {
+                Err(invalid(format!(
+                    "{what} names undeclared entity type '{name}'"
+                )))
+            }
+        };
+        for slot in ["user", "resource", "actor"] {
+            for name in self.request.types_for(slot) {
+                known(name, format!("request.{slot}"))?;
+            }
+        }
+        for (type_name, entity_type) in &self.entity_types {
+            for (relation, subjects) in &entity_type.relations {
+                for name in subjects {
+                    known(name, format!("relation {type_name}.{relation}"))?;
+                }
+            }
+            check_declarations(&entity_type.attributes, type_name).map_err(invalid)?;
+            for (relation, source) in &entity_type.rewrites {
+                self.check_rewrite(type_name, entity_type, relation, source)
+                    .map_err(|e| invalid(format!("rewrite {type_name}.{relation}: {e}")))?;
+            }
+        }
+        if let Some(context) = &self.context {
+            check_declarations(context, "context").map_err(invalid)?;
+        }
+        Ok(())
+    }
+
+    /// Check one rewrite: it parses, its own name is a stored relation if it
+    /// reads one, every relation it names exists on the type, and every
+    /// `rel from tupleset` follows a stored relation to a type declaring `rel`.
+    fn check_rewrite(
+        &self,
+        type_name: &str,
+        entity_type: &EntityTypeSchema,
+        relation: &str,
+        source: &str,
+    ) -> Result<(), String> {
+        let userset = Userset::parse(relation, source)?;
+        if userset.reads_this() && !entity_type.relations.contains_key(relation) {
+            return Err(format!(
+                "`{relation}` reads its own stored tuples, but {type_name} declares no relation `{relation}`"
+            ));
+        }
+        for (name, tupleset) in userset.references() {
+            let Some(tupleset) = tupleset else {
+                if !entity_type.declares(name) {
+                    return Err(format!("{type_name} declares no relation `{name}`"));
+                }
+                continue;
+            };
+            let Some(targets) = entity_type.relations.get(tupleset) else {
+                return Err(format!(
+                    "`{name} from {tupleset}` needs a stored relation `{tupleset}` on {type_name}"
+                ));
+            };
+            // A tupleset admitting any subject type is checked at evaluation.
+            let declared = targets.is_empty()
+                || targets
+                    .iter()
+                    .filter_map(|t| self.entity_type(t))
+                    .any(|t| t.declares(name));
+            if !declared {
+                return Err(format!(
+                    "`{name} from {tupleset}`: none of {} declares `{name}`",
+                    targets.join(", ")
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// The declaration of entity type `name`.
+    pub fn entity_type(&self, name: &str) -> Option<&EntityTypeSchema> {
+        self.entity_types.get(name)
+    }
+
+    /// Check one entity document against the schema.
+    pub(crate) fn check_entity(&self, doc: &EntityDocument) -> Result<(), ReaperError> {
+        let fail = |reason: String| ReaperError::InvalidPolicy {
+            reason: format!(
+                "entity '{}' ({}) does not match the schema: {reason}",
+                doc.id, doc.entity_type
+            ),
+        };
+        let entity_type = self
+            .entity_type(&doc.entity_type)
+            .ok_or_else(|| fail("undeclared entity type".to_string()))?;
+        check_fields(&entity_type.attributes, &doc.attributes, "").map_err(fail)?;
+        for relation in doc.relationships.keys() {
+            if !entity_type.relations.contains_key(relation) {
+                return Err(fail(format!("undeclared relation '{relation}'")));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn check_declarations(
+    attributes: &BTreeMap<String, AttributeSchema>,
+    owner: &str,
+) -> Result<(), String> {
+    for (name, attr) in attributes {
+        if attr.items.is_some() && attr.ty != AttributeType::Array {
+            return Err(format!("{owner}.{name}: `items` needs type array"));
+        }
+        if !attr.attributes.is_empty() {
+            if attr.ty != AttributeType::Object {
+                return Err(format!("{owner}.{name}: `attributes` needs type object"));
+            }
+            check_declarations(&attr.attributes, &format!("{owner}.{name}"))?;
+        }
+    }
+    Ok(())
+}
+
+/// Check `fields` against the declared `attributes` (a closed set).
+/// `prefix` is the dotted path of the enclosing object, for messages.
+fn check_fields<'a>(
+    attributes: &BTreeMap<String, AttributeSchema>,
+    fields: impl IntoIterator<Item = (&'a String, &'a JsonValue)> + Clone,
+    prefix: &str,
+) -> Result<(), String> {
+    for (name, value) in fields.clone() {
+        let path = format!("{prefix}{name}");
+        let attr = attributes
+            .get(name)
+            .ok_or_else(|| format!("undeclared attribute '{path}'"))?;
+        check_value(attr, value, &path)?;
+    }
+    for (name, attr) in attributes {
+        let present = fields
+            .clone()
+            .into_iter()
+            .any(|(field, value)| field == name && !value.is_null());
+        if attr.required && !present {
+            return Err(format!("missing required attribute '{prefix}{name}'"));
+        }
+    }
+    Ok(())
+}
+
+fn check_value(attr: &AttributeSchema, value: &JsonValue, path: &str) -> Result<(), String> {
+    if value.is_null() {
+        return if attr.required {
+            Err(format!("attribute '{path}' is required but null"))
+        } else {
+            Ok(())
+        };
+    }
+    if !attr.ty.admits(value) {
+        return Err(format!(
+            "attribute '{path}' must be {}, got {}",
+            attr.ty.as_str(),
+            AttributeType::of_json(value).map_or("null", AttributeType::as_str)
+        ));
+    }
+    match value {
+        JsonValue::Array(items) => {
+            if let Some(item_ty) = attr.items {
+                if let Some(bad) = items.iter().find(|item| !item_ty.admits(item)) {
+                    return Err(format!(
+                        "attribute '{path}' must hold {} elements, got {}",
+                        item_ty.as_str(),
+                        AttributeType::of_json(bad).map_or("null", AttributeType::as_str)
+                    ));
+                }
+            }
+        }
+        JsonValue::Object(map) if !attr.attributes.is_empty() => {
+            check_fields(&attr.attributes, map, &format!("{path}."))?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> EntitySchema {
+        EntitySchema::from_json(
+            r#"{
+                "entity_types": {
+                    "User": {
+                        "attributes": {
+                            "department": {"type": "string"},
+                            "level": {"type": "number", "required": false},
+                            "roles": {"type": "array", "items": "string"},
+                            "profile": {
+                                "type": "object",
+                                "required": false,
+                                "attributes": {"team": {"type": "string"}}
+                            }
+                        },
+                        "relations": {"member_of": ["Group"]}
+                    },
+                    "Group": {}
+                },
+                "request": {"user": ["User"]}
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn doc(value: JsonValue) -> EntityDocument {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_conforming_entity_passes() {
+        let user = doc(json!({
+            "id": "alice", "type": "User",
+            "attributes": {"department": "eng", "roles": ["admin"], "profile": {"team": "core"}},
+            "relationships": {"member_of": ["eng"]}
+        }));
+        assert!(schema().check_entity(&user).is_ok());
+    }
+
+    #[test]
+    fn test_violations_are_named() {
+        let cases = [
+            (
+                json!({"department": "eng", "roles": [], "departmnet": "x"}),
+                "undeclared attribute 'departmnet'",
+            ),
+            (
+                json!({"roles": []}),
+                "missing required attribute 'department'",
+            ),
+            (
+                json!({"department": 7, "roles": []}),
+                "'department' must be string, got number",
+            ),
+            (
+                json!({"department": "eng", "roles": [1]}),
+                "must hold string elements",
+            ),
+            (
+                json!({"department": "eng", "roles": [], "profile": {}}),
+                "missing required attribute 'profile.team'",
+            ),
+            (json!({"department": "eng", "roles": [], "level": null}), ""),
+        ];
+        for (attributes, expected) in cases {
+            let result = schema().check_entity(&doc(json!({
+                "id": "alice", "type": "User", "attributes": attributes
+            })));
+            match result {
+                Ok(()) => assert!(expected.is_empty(), "expected error {expected}"),
+                Err(e) => assert!(e.to_string().contains(expected), "{e}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_undeclared_types_and_relations_are_rejected() {
+        let err = schema()
+            .check_entity(&doc(json!({"id": "d", "type": "Doc", "attributes": {}})))
+            .unwrap_err();
+        assert!(err.to_string().contains("undeclared entity type"));
+        let err = schema()
+            .check_entity(&doc(json!({
+                "id": "g", "type": "Group", "attributes": {},
+                "relationships": {"owner": ["alice"]}
+            })))
+            .unwrap_err();
+        assert!(err.to_string().contains("undeclared relation 'owner'"));
+    }
+
+    #[test]
+    fn test_inconsistent_schemas_are_rejected() {
+        for (json, expected) in [
+            (
+                r#"{"request": {"user": ["User"]}}"#,
+                "undeclared entity type 'User'",
+            ),
+            (
+                r#"{"entity_types": {"U": {"attributes": {"a": {"type": "string", "items": "string"}}}}}"#,
+                "`items` needs type array",
+            ),
+            (
+                r#"{"entity_types": {"U": {"attributes": {"a": {"type": "text"}}}}}"#,
+                "unknown variant",
+            ),
+            (r#"{"entity_types": {}, "requests": {}}"#, "unknown field"),
+        ] {
+            let err = EntitySchema::from_json(json).unwrap_err();
+            assert!(err.to_string().contains(expected), "{err}");
+        }
+    }
+
+    #[test]
+    fn test_rewrites_must_name_declared_relations() {
+        let schema = |rewrites: &str| {
+            EntitySchema::from_json(&format!(
+                r#"{{"entity_types": {{
+                    "User": {{}},
+                    "Group": {{"relations": {{"member": ["User"]}}}},
+                    "Folder": {{"relations": {{"viewer": ["User"]}}}},
+                    "Doc": {{
+                        "relations": {{"owner": ["User"], "viewer": ["User", "Group"], "parent": ["Folder"]}},
+                        "rewrites": {rewrites}
+                    }}
+                }}}}"#
+            ))
+        };
+        assert!(schema(
+            r#"{"edit": "owner", "viewer": "viewer + edit + viewer from parent + member from viewer"}"#
+        )
+        .is_ok());
+        for (rewrites, expected) in [
+            (r#"{"edit": "edit + owner"}"#, "declares no relation `edit`"),
+            (
+                r#"{"viewer": "viewer + editor"}"#,
+                "declares no relation `editor`",
+            ),
+            (
+                r#"{"viewer": "viewer from owner_of"}"#,
+                "needs a stored relation `owner_of`",
+            ),
+            (
+                r#"{"viewer": "owner from parent"}"#,
+                "none of Folder declares `owner`",
+            ),
+            (
+                r#"{"viewer": "viewer +"}"#,
+                "rewrite Doc.viewer: expected a relation name",
+            ),
+        ] {
+            let err = schema(rewrites).unwrap_err();
+            assert!(err.to_string().contains(expected), "{rewrites}: {err}");
+        }
+    }
+}
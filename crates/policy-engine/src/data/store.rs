@@ -372,6 +372,36 @@ impl DataStore {
             .unwrap_or_default()
     }
 
+    /// Ids of every entity of `entity_type`, without touching the entities
+    /// themselves (the list-authorization candidate universe).
+    pub fn ids_by_type(&self, entity_type: EntityType) -> Vec<EntityId> {
+        self.type_index
+            .get(&entity_type)
+            .map(|entry| entry.value().iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Ids under one composite-index key. `None` when the composite index is
+    /// disabled, so a caller narrowing by it cannot mistake "not indexed" for
+    /// "no match". The set may include stale ids (a plain `insert` over an
+    /// existing entity leaves its old values indexed): it is a superset.
+    pub fn ids_by_type_and_attribute(
+        &self,
+        entity_type: EntityType,
+        attr_key: InternedString,
+        attr_value: InternedString,
+    ) -> Option<HashSet<EntityId>> {
+        if !self.config.index_composite {
+            return None;
+        }
+        Some(
+            self.composite_index
+                .get(&(entity_type, attr_key, attr_value))
+                .map(|entry| entry.value().clone())
+                .unwrap_or_default(),
+        )
+    }
+
     /// Remove an entity by ID
     /// UPSERT: replace an entity, cleaning the indexes its OLD attribute
     /// values occupied (a plain insert leaves stale attribute/composite
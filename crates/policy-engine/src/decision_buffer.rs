@@ -979,13 +979,17 @@ impl DecisionBuffer {
             return Prepared::Skipped;
         }
 
-        // Check if we should log this decision type
+        // Check if we should log this decision type. Shadow-divergence records
+        // are not served decisions and are rare by construction: they bypass
+        // the per-outcome gates whenever logging is enabled.
         let is_allow = entry.decision == "allow";
-        if is_allow && !self.config.log_allows {
-            return Prepared::Skipped;
-        }
-        if !is_allow && !self.config.log_denies {
-            return Prepared::Skipped;
+        if entry.shadow.is_none() {
+            if is_allow && !self.config.log_allows {
+                return Prepared::Skipped;
+            }
+            if !is_allow && !self.config.log_denies {
+                return Prepared::Skipped;
+            }
         }
 
         // Strip context if configured
@@ -1265,6 +1269,9 @@ pub struct DecisionFilter {
     pub decision: Option<String>,
     pub policy_id: Option<String>,
     pub since: Option<String>, // ISO 8601 timestamp
+    /// `Some(true)`: only shadow-divergence records; `Some(false)`: only
+    /// ordinary decisions.
+    pub shadow: Option<bool>,
 }
 
 impl DecisionFilter {
@@ -1302,6 +1309,11 @@ impl DecisionFilter {
         self
     }
 
+    pub fn with_shadow(mut self, shadow: bool) -> Self {
+        self.shadow = Some(shadow);
+        self
+    }
+
     fn matches(&self, entry: &DecisionLogEntry) -> bool {
         if let Some(ref p) = self.principal {
             if &entry.principal != p {
@@ -1333,6 +1345,11 @@ impl DecisionFilter {
                 return false;
             }
         }
+        if let Some(shadow) = self.shadow {
+            if entry.shadow.is_some() != shadow {
+                return false;
+            }
+        }
         true
     }
 }
@@ -1387,6 +1404,36 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_shadow_records_bypass_outcome_gates_and_filter() {
+        let config = DecisionLogConfig {
+            enabled: true,
+            privacy_profile: Some(PrivacyProfile::Raw),
+            log_allows: false,
+            ..Default::default()
+        };
+        let buffer = DecisionBuffer::new(config).unwrap();
+        buffer.log(test_entry("allow"));
+        buffer.log(test_entry("deny"));
+        buffer.log(
+            test_entry("allow").with_shadow(crate::decision_log::ShadowOutcome {
+                decision: "deny".to_string(),
+                policy_name: "test-policy".to_string(),
+                policy_version: Some("2".to_string()),
+                matched_rule: None,
+            }),
+        );
+
+        // The plain allow was gated out; the divergence record was not.
+        assert_eq!(buffer.get_recent(10).len(), 2);
+        let shadow = buffer.query(DecisionFilter::new().with_shadow(true), 10);
+        assert_eq!(shadow.len(), 1);
+        assert_eq!(shadow[0].decision, "allow");
+        let live = buffer.query(DecisionFilter::new().with_shadow(false), 10);
+        assert_eq!(live.len(), 1);
+        assert_eq!(live[0].decision, "deny");
+    }
+
     #[test]
     fn test_protection_applies_to_ring_and_file_sink() {
         // With protection configured, neither the query ring nor the file sink
@@ -22,6 +22,11 @@
 //! - **Policy-scoped**: the cache key includes a caller-supplied `scope` hash
 //!   (the resolved policy-id set) so decisions for different policies never
 //!   collide on the same `(principal, action, resource)`.
+//! - **Clock-blind**: nothing bumps the generation when time alone changes a
+//!   decision. A relationship edge with an expiry lapses without a data
+//!   change, so callers must not cache while the graph holds conditional
+//!   edges (`RelationshipGraph::conditional_len() != 0`) — the agent skips
+//!   the cache then; the TTL is not a bound (0 means none).
 //! - **Collision-safe**: keys are a 128-bit fingerprint (two independent
 //!   hashes). The map is keyed by the first 64 bits; the second 64 bits are
 //!   verified on read, so an unrelated request that hashes to the same slot is
@@ -406,7 +411,7 @@ mod tests {
 
     fn make_request(principal: &str, action: &str, resource: &str) -> PolicyRequest {
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), principal.to_string());
+        context.insert("principal".to_string(), principal.into());
         PolicyRequest {
             action: action.to_string(),
             resource: resource.to_string(),
@@ -596,14 +601,14 @@ mod tests {
         // therefore different iteration orders) must produce the same
         // fingerprint — the fold is commutative, no sorting involved.
         let mut ctx_a = HashMap::new();
-        ctx_a.insert("principal".to_string(), "alice".to_string());
-        ctx_a.insert("dept".to_string(), "eng".to_string());
-        ctx_a.insert("region".to_string(), "eu".to_string());
+        ctx_a.insert("principal".to_string(), "alice".into());
+        ctx_a.insert("dept".to_string(), "eng".into());
+        ctx_a.insert("region".to_string(), "eu".into());
 
         let mut ctx_b = HashMap::new();
-        ctx_b.insert("region".to_string(), "eu".to_string());
-        ctx_b.insert("dept".to_string(), "eng".to_string());
-        ctx_b.insert("principal".to_string(), "alice".to_string());
+        ctx_b.insert("region".to_string(), "eu".into());
+        ctx_b.insert("dept".to_string(), "eng".into());
+        ctx_b.insert("principal".to_string(), "alice".into());
 
         let r_a = PolicyRequest {
             action: "read".to_string(),
@@ -624,7 +629,7 @@ mod tests {
 
         // And a differing value must change the fingerprint.
         let mut ctx_c = r_b.context.clone();
-        ctx_c.insert("dept".to_string(), "sales".to_string());
+        ctx_c.insert("dept".to_string(), "sales".into());
         let r_c = PolicyRequest {
             action: "read".to_string(),
             resource: "doc1".to_string(),
@@ -60,6 +60,12 @@ pub struct DecisionLogEntry {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub matched_rule: Option<String>,
 
+    /// Obligations and advice served with the decision, so an audit shows
+    /// what the enforcement point was told to do (mask, step up), not just
+    /// the verdict.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub obligations: Option<crate::DecisionObligations>,
+
     /// "Explain" snapshot: the resolved principal/resource entity attributes the
     /// decision branched on (e.g. `{"principal": {...}, "resource": {...}}`).
     /// Present only when the explain tier is enabled (heavier; opt-in, typically
@@ -79,6 +85,13 @@ pub struct DecisionLogEntry {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub replay_input: Option<serde_json::Value>,
 
+    /// Shadow-divergence record: what a shadow-deployed candidate decided for
+    /// this same request when it disagreed with the live decision above.
+    /// Present only on divergence records, which the agent writes off the
+    /// response path; ordinary decisions never carry it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shadow: Option<ShadowOutcome>,
+
     /// Data-plane provenance: the datastore version this decision evaluated
     /// against (audits can pin exactly what data a decision saw).
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -125,6 +138,21 @@ pub struct DecisionLogEntry {
     pub entry_hash: String,
 }
 
+/// The shadow side of a divergence record (see [`DecisionLogEntry::shadow`]).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShadowOutcome {
+    /// Decision the shadow candidate reached: "allow", "deny", or "log".
+    pub decision: String,
+    /// Name of the deciding shadow policy (empty on a default deny).
+    pub policy_name: String,
+    /// Version of the deciding shadow policy.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub policy_version: Option<String>,
+    /// Rule that decided the shadow outcome, when one matched.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matched_rule: Option<String>,
+}
+
 /// A hash-chain verification failure, naming the record where it broke.
 #[derive(Debug, Clone)]
 pub struct ChainError {
@@ -942,6 +970,8 @@ impl DecisionLogEntry {
             matched_rule: None,
             input_data: None,
             replay_input: None,
+            shadow: None,
+            obligations: None,
             data_version: None,
             data_checksum: None,
             model_version: None,
@@ -985,6 +1015,18 @@ impl DecisionLogEntry {
         self
     }
 
+    /// Attach the obligations and advice served with the decision.
+    pub fn with_obligations(mut self, obligations: crate::DecisionObligations) -> Self {
+        self.obligations = Some(obligations);
+        self
+    }
+
+    /// Mark this as a shadow-divergence record.
+    pub fn with_shadow(mut self, shadow: ShadowOutcome) -> Self {
+        self.shadow = Some(shadow);
+        self
+    }
+
     /// Set the trace ID for OpenTelemetry correlation
     pub fn with_trace_id(mut self, trace_id: String) -> Self {
         self.trace_id = Some(trace_id);
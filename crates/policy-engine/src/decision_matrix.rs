@@ -61,26 +61,20 @@ pub struct DecisionKey {
     pub action: String,
     /// Resource being accessed
     pub resource: String,
-    /// Additional context (sorted by key for consistency)
+    /// Additional context as `(key, JSON text)`, sorted by key for
+    /// consistency. JSON text keeps `"1"` and `1` distinct.
     pub context: Vec<(String, String)>,
 }
 
 impl DecisionKey {
     /// Create a new decision key from a policy request
     pub fn from_request(request: &PolicyRequest, principal: &str) -> Self {
-        let mut context: Vec<(String, String)> = request
-            .context
-            .iter()
-            .map(|(k, v)| (k.clone(), v.clone()))
-            .collect();
-        context.sort(); // Ensure consistent ordering for hashing
-
-        Self {
-            principal: principal.to_string(),
-            action: request.action.clone(),
-            resource: request.resource.clone(),
-            context,
-        }
+        Self::new(
+            principal.to_string(),
+            request.action.clone(),
+            request.resource.clone(),
+            request.context.clone(),
+        )
     }
 
     /// Create a new decision key from components
@@ -88,10 +82,13 @@ impl DecisionKey {
         principal: String,
         action: String,
         resource: String,
-        context: HashMap<String, String>,
+        context: HashMap<String, serde_json::Value>,
     ) -> Self {
-        let mut context_vec: Vec<(String, String)> = context.into_iter().collect();
-        context_vec.sort();
+        let mut context_vec: Vec<(String, String)> = context
+            .into_iter()
+            .map(|(k, v)| (k, v.to_string()))
+            .collect();
+        context_vec.sort(); // Ensure consistent ordering for hashing
 
         Self {
             principal,
@@ -166,7 +163,7 @@ impl DecisionMatrix {
         principals: Vec<String>,
         resources: Vec<String>,
         actions: Vec<String>,
-        contexts: Vec<HashMap<String, String>>,
+        contexts: Vec<HashMap<String, serde_json::Value>>,
     ) -> Result<usize> {
         info!(
             "Precomputing decision matrix for policy {} (principals: {}, resources: {}, actions: {}, contexts: {})",
@@ -349,12 +346,12 @@ mod tests {
     #[test]
     fn test_decision_key_consistency() {
         let mut context1 = HashMap::new();
-        context1.insert("role".to_string(), "admin".to_string());
-        context1.insert("dept".to_string(), "eng".to_string());
+        context1.insert("role".to_string(), "admin".into());
+        context1.insert("dept".to_string(), "eng".into());
 
         let mut context2 = HashMap::new();
-        context2.insert("dept".to_string(), "eng".to_string());
-        context2.insert("role".to_string(), "admin".to_string());
+        context2.insert("dept".to_string(), "eng".into());
+        context2.insert("role".to_string(), "admin".into());
 
         let key1 = DecisionKey::new(
             "alice".to_string(),
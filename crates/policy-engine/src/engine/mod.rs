@@ -24,11 +24,12 @@ mod tests;
 
 // Re-export all public types
 pub use policy::EnhancedPolicy;
+pub(crate) use types::context_root;
 pub use types::{
-    AllPoliciesEvaluationResult, DenyInfo, PackageEvaluationResult, PackageInfo, PolicyAction,
-    PolicyDecision, PolicyEngineStats, PolicyLanguage, PolicyRequest, PolicyRule, PolicySource,
-    PolicySourceMetadata, PolicyVersion, PruningIndexStats, SetEvalOutcome, SimpleAction,
-    SimpleRule, StagedPackage, TrustLevel,
+    AllPoliciesEvaluationResult, DecisionObligations, DenyInfo, PackageEvaluationResult,
+    PackageInfo, PolicyAction, PolicyDecision, PolicyEngineStats, PolicyLanguage, PolicyRequest,
+    PolicyRule, PolicySource, PolicySourceMetadata, PolicyVersion, PruningIndexStats,
+    SetEvalOutcome, SimpleAction, SimpleRule, StagedPackage, TrustLevel,
 };
 
 use arc_swap::ArcSwap;
@@ -527,6 +528,23 @@ impl PolicyEngine {
         info!("Default policy updated");
     }
 
+    /// Trace `request` through a policy: the decision [`Self::evaluate`]
+    /// would return, with every rule visited and each condition's resolved
+    /// operands. Slow by design; for debugging, never the serving path.
+    pub fn explain(
+        &self,
+        policy_id: &PolicyId,
+        request: &PolicyRequest,
+    ) -> Result<crate::reap::EvaluationTrace> {
+        let policy = self
+            .get_policy(policy_id)
+            .or_else(|| self.default_policy.read().clone())
+            .ok_or_else(|| ReaperError::PolicyNotFound {
+                policy_id: policy_id.to_string(),
+            })?;
+        policy.get_evaluator()?.explain(request)
+    }
+
     /// Evaluate a request against a policy.
     ///
     /// Optimized for sub-microsecond latency:
@@ -567,6 +585,7 @@ impl PolicyEngine {
 
         let named = evaluator.evaluate_named(request)?;
         let matched_rule_name = named.rule_name.map(str::to_string);
+        let obligations = named.obligations.cloned();
         let decision = named.decision;
         let evaluation_time_ns = start_time.elapsed_ns();
 
@@ -589,6 +608,7 @@ impl PolicyEngine {
             evaluation_time_ns,
             matched_rule,
             matched_rule_name,
+            obligations,
         })
     }
 
@@ -606,12 +626,31 @@ impl PolicyEngine {
     ///   evaluation with Deny.
     /// - **First allow wins** (among matched allows) — sets the attribution, but
     ///   a later matched deny still overrides it.
+    /// - **Obligations follow the decision.** A deny carries only the denying
+    ///   rule's obligations; an allow carries the union over every matched
+    ///   allow (the attributed policy's win on key conflicts), so an
+    ///   obligation such as a field mask is never dropped because another
+    ///   policy also allowed.
     /// - **Log matches don't decide.**
     /// - **No policy matched ⇒ default deny** (nil policy id).
     /// - **Errors deny** (fail closed) and stop evaluation.
     /// - **Unknown policy id ⇒ deny** (fail closed) with the error surfaced — a
     ///   candidate id with no live policy is an inconsistency, not a skip.
     pub fn evaluate_set(&self, policy_ids: &[PolicyId], request: &PolicyRequest) -> SetEvalOutcome {
+        self.evaluate_set_with(policy_ids, request, |_| None)
+    }
+
+    /// [`evaluate_set`](Self::evaluate_set) with per-policy substitution: each
+    /// live policy for which `substitute` returns a candidate is evaluated as
+    /// that candidate instead, with identical combination semantics. This is
+    /// how a shadow deployment answers "what would this request get if the
+    /// candidate were live" without touching the active set.
+    pub fn evaluate_set_with(
+        &self,
+        policy_ids: &[PolicyId],
+        request: &PolicyRequest,
+        substitute: impl Fn(&EnhancedPolicy) -> Option<Arc<EnhancedPolicy>>,
+    ) -> SetEvalOutcome {
         let mut outcome = SetEvalOutcome {
             decision: PolicyAction::Deny,
             policy_id: PolicyId::nil(),
@@ -619,6 +658,7 @@ impl PolicyEngine {
             policy_version: 0,
             matched_rule: None,
             matched_rule_name: None,
+            obligations: None,
             total_eval_time_ns: 0,
             error: None,
         };
@@ -646,6 +686,7 @@ impl PolicyEngine {
                 );
                 return outcome;
             };
+            let policy = substitute(&policy).unwrap_or(policy);
 
             let evaluator = match policy.get_evaluator() {
                 Ok(e) => e,
@@ -667,6 +708,7 @@ impl PolicyEngine {
                     PolicyAction::Deny => {
                         outcome.decision = PolicyAction::Deny;
                         Self::attribute(&mut outcome, &policy, request, named.rule_name);
+                        outcome.obligations = named.obligations.cloned();
                         return outcome;
                     }
                     PolicyAction::Allow => {
@@ -674,6 +716,12 @@ impl PolicyEngine {
                             any_allow = true;
                             outcome.decision = PolicyAction::Allow;
                             Self::attribute(&mut outcome, &policy, request, named.rule_name);
+                            outcome.obligations = named.obligations.cloned();
+                        } else if let Some(more) = named.obligations {
+                            outcome
+                                .obligations
+                                .get_or_insert_with(Default::default)
+                                .merge_missing(more);
                         }
                     }
                     PolicyAction::Log => {}
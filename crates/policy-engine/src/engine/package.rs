@@ -110,6 +110,7 @@ impl PolicyEngine {
                     evaluation_time_ns: eval_time,
                     matched_rule: None,
                     matched_rule_name: None,
+                    obligations: None,
                 };
 
                 // Security-first: any deny = overall deny
@@ -270,6 +270,7 @@ async fn test_bundle_deployment_with_version_tracking() {
         default_decision: Decision::Deny,
         rules: vec![ReapRule {
             message: None,
+            obligations: None,
             name: "allow-admins".to_string(),
             decision: Decision::Allow,
             condition: ReapCondition::True,
@@ -516,6 +517,7 @@ async fn test_atomic_package_stage_and_commit() {
         default_decision: Decision::Deny,
         rules: vec![ReapRule {
             message: None,
+            obligations: None,
             name: "allow-admin".to_string(),
             decision: Decision::Allow,
             condition: ReapCondition::True,
@@ -774,7 +776,7 @@ policy restart_test {
     engine.deploy_policy(policy).unwrap();
 
     let mut ctx = std::collections::HashMap::new();
-    ctx.insert("principal".to_string(), "alice".to_string());
+    ctx.insert("principal".to_string(), "alice".into());
     let allow_req = crate::PolicyRequest {
         resource: "/doc".to_string(),
         action: "read".to_string(),
@@ -914,3 +916,49 @@ fn test_evaluate_set_production_semantics() {
     let out = engine.evaluate_set(&[], &request("/a"));
     assert_eq!(out.decision, PolicyAction::Deny);
 }
+
+#[test]
+fn test_evaluate_set_with_substitutes_candidates() {
+    // Shadow evaluation: a substituted policy is evaluated in place of the
+    // live one, with the same combination rules; the active set is untouched.
+    let engine = PolicyEngine::new();
+    let live = EnhancedPolicy::new(
+        "docs".to_string(),
+        "".to_string(),
+        vec![PolicyRule {
+            action: PolicyAction::Allow,
+            resource: "/a".to_string(),
+            conditions: vec![],
+        }],
+    );
+    let live_id = live.id;
+    engine.deploy_policy(live).unwrap();
+    let candidate = Arc::new(EnhancedPolicy::new(
+        "docs".to_string(),
+        "".to_string(),
+        vec![PolicyRule {
+            action: PolicyAction::Deny,
+            resource: "/a".to_string(),
+            conditions: vec![],
+        }],
+    ));
+
+    let request = PolicyRequest {
+        resource: "/a".to_string(),
+        action: "read".to_string(),
+        ..Default::default()
+    };
+    let out = engine.evaluate_set_with(&[live_id], &request, |p| {
+        (p.name == "docs").then(|| candidate.clone())
+    });
+    assert_eq!(out.decision, PolicyAction::Deny);
+    assert_eq!(out.policy_name, "docs");
+
+    // No substitute -> the live decision.
+    let out = engine.evaluate_set_with(&[live_id], &request, |_| None);
+    assert_eq!(out.decision, PolicyAction::Allow);
+    assert_eq!(
+        engine.evaluate_set(&[live_id], &request).decision,
+        PolicyAction::Allow
+    );
+}
@@ -232,6 +232,12 @@ pub enum TrustLevel {
 
 /// Policy evaluation request
 ///
+/// `context` values are full JSON (strings, numbers, bools, arrays,
+/// objects), so `context.amount > 1000` and `"x" in context.tags` compare
+/// natively. Values are not coerced: a number sent as a string (`"1500"`)
+/// compares as a string, so `context.amount > 1000` no longer holds for it
+/// (the compiled path used to parse such strings; see CHANGELOG).
+///
 /// `actor` and `context_provenance` are the F1 agentic extensions — both
 /// optional and `serde(default)`, so every pre-F1 wire payload and stored
 /// replay input deserializes unchanged (absent = today's single-principal,
@@ -243,24 +249,81 @@ pub struct PolicyRequest {
     #[serde(default)]
     pub action: String,
     #[serde(default)]
-    pub context: std::collections::HashMap<String, String>,
+    pub context: std::collections::HashMap<String, serde_json::Value>,
     /// The non-human actor wielding this request on behalf of the principal
     /// (the DSL's `actor` entity binding). `None` = no delegation in play.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub actor: Option<String>,
-    /// Per-key trust labels for `context` (taint mode). `None` = taint mode
-    /// off: every key behaves as platform-trusted, preserving pre-F1
-    /// semantics. `Some(map)` = taint mode on: unlabeled keys are `Llm`.
+    /// Per-key trust labels for `context` (taint mode), keyed by TOP-LEVEL
+    /// context key: a label on `order` covers `context.order.total`. `None`
+    /// = taint mode off: every key behaves as platform-trusted, preserving
+    /// pre-F1 semantics. `Some(map)` = taint mode on: unlabeled keys are
+    /// `Llm`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub context_provenance: Option<std::collections::HashMap<String, TrustLevel>>,
 }
 
 impl PolicyRequest {
     /// Effective trust of one context key under the fail-untrusted rule.
+    /// A dotted path (`order.total`) takes its top-level key's label unless
+    /// the full path is itself a labeled key.
     pub fn context_trust(&self, key: &str) -> TrustLevel {
         match &self.context_provenance {
             None => TrustLevel::Platform,
-            Some(map) => map.get(key).copied().unwrap_or(TrustLevel::Llm),
+            Some(map) => map
+                .get(key)
+                .or_else(|| map.get(context_root(key)))
+                .copied()
+                .unwrap_or(TrustLevel::Llm),
+        }
+    }
+
+    /// A context value that is a JSON string (`principal` and every
+    /// pre-typed key). `None` if absent or not a string.
+    pub fn context_str(&self, key: &str) -> Option<&str> {
+        self.context.get(key).and_then(serde_json::Value::as_str)
+    }
+}
+
+/// The top-level context key a dotted context path reads through — the
+/// granularity taint provenance is labeled at.
+pub(crate) fn context_root(path: &str) -> &str {
+    path.split('.').next().unwrap_or(path)
+}
+
+/// Structured obligations and advice a matching rule attaches to its
+/// decision (`allow with obligations {...} with advice {...}`).
+///
+/// Obligations are directives the enforcement point must carry out to honor
+/// the decision (mask these fields, require step-up); advice is best-effort
+/// and may be ignored. Both are static JSON objects declared in the policy.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DecisionObligations {
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub obligations: serde_json::Map<String, serde_json::Value>,
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub advice: serde_json::Map<String, serde_json::Value>,
+}
+
+impl DecisionObligations {
+    /// True when the rule declared neither obligations nor advice.
+    pub fn is_empty(&self) -> bool {
+        self.obligations.is_empty() && self.advice.is_empty()
+    }
+
+    /// Fold `other` in without overriding keys already present: when several
+    /// policies allow a request, the first allow keeps attribution and its
+    /// obligations win on conflict, but the others' obligations still apply.
+    pub fn merge_missing(&mut self, other: &DecisionObligations) {
+        for (key, value) in &other.obligations {
+            self.obligations
+                .entry(key.clone())
+                .or_insert_with(|| value.clone());
+        }
+        for (key, value) in &other.advice {
+            self.advice
+                .entry(key.clone())
+                .or_insert_with(|| value.clone());
         }
     }
 }
@@ -281,6 +344,10 @@ pub struct PolicyDecision {
     /// skipped when absent — wire-compatible.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub matched_rule_name: Option<String>,
+    /// Obligations and advice of the deciding rule; `None` when it declared
+    /// none or the per-policy default decided.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub obligations: Option<DecisionObligations>,
 }
 
 /// Outcome of evaluating one request against a SET of policies with the
@@ -297,6 +364,10 @@ pub struct SetEvalOutcome {
     /// once, only for the single decisive policy — same discipline as
     /// `policy_name`.
     pub matched_rule_name: Option<String>,
+    /// Obligations and advice attached to the decision. A deny carries the
+    /// denying rule's; an allow carries those of every matched allow, the
+    /// attributed policy's winning on key conflicts.
+    pub obligations: Option<DecisionObligations>,
     /// Sum of per-policy evaluation times.
     pub total_eval_time_ns: u64,
     /// Set when an evaluation errored (the decision is then Deny — fail closed).
@@ -9,11 +9,9 @@ use super::{EvaluatorMetadata, PolicyEvaluator};
 use crate::{PolicyAction, PolicyRequest};
 use cedar_policy::{
     Authorizer, Context, Decision, Entities, EntityTypeName, EntityUid, PolicySet, Request,
-    RestrictedExpression,
 };
 use reaper_core::ReaperError;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 use std::str::FromStr;
 
 /// Cedar policy evaluator providing AWS-compatible authorization
@@ -106,10 +104,9 @@ impl CedarPolicyEvaluator {
         // - Resource: Resource::"<resource>"
 
         let principal_id = request
-            .context
-            .get("principal")
-            .cloned()
-            .unwrap_or_else(|| "anonymous".to_string());
+            .context_str("principal")
+            .unwrap_or("anonymous")
+            .to_string();
 
         let principal = EntityUid::from_type_name_and_id(
             EntityTypeName::from_str("User").map_err(|e| ReaperError::EvaluationError {
@@ -146,30 +143,20 @@ impl CedarPolicyEvaluator {
                 })?,
         );
 
-        // Build context from request.context
-        let mut context_map = HashMap::new();
-        for (key, value) in &request.context {
-            if key != "principal" {
-                // Skip principal as it's handled separately
-                // Convert string value to RestrictedExpression
-                // For now, we'll treat everything as strings
-                // In production, you'd want proper type conversion
-                let expr =
-                    RestrictedExpression::from_str(&format!("\"{}\"", value)).map_err(|e| {
-                        ReaperError::EvaluationError {
-                            reason: format!("Failed to convert context value for {}: {}", key, e),
-                        }
-                    })?;
-                context_map.insert(key.clone(), expr);
-            }
-        }
-
-        let context =
-            Context::from_pairs(context_map).map_err(|e| ReaperError::EvaluationError {
+        // Build context from request.context (principal is handled
+        // separately). Values are JSON, so Cedar sees longs, bools, sets and
+        // records rather than their string renderings; null reads as absent.
+        let context_json: serde_json::Map<String, serde_json::Value> = request
+            .context
+            .iter()
+            .filter(|(key, value)| key.as_str() != "principal" && !value.is_null())
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        let context = Context::from_json_value(serde_json::Value::Object(context_json), None)
+            .map_err(|e| ReaperError::EvaluationError {
                 reason: format!("Failed to build context: {}", e),
             })?;
 
-        // Build the Cedar request
         Request::new(principal, action, resource, context, None).map_err(|e| {
             ReaperError::EvaluationError {
                 reason: format!("Failed to build Cedar request: {}", e),
@@ -283,7 +270,7 @@ mod tests {
         let evaluator = CedarPolicyEvaluator::new(policy.to_string()).unwrap();
 
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), "alice".to_string());
+        context.insert("principal".to_string(), "alice".into());
 
         let request = PolicyRequest {
             resource: "document1".to_string(),
@@ -306,7 +293,7 @@ mod tests {
         let evaluator = CedarPolicyEvaluator::new(policy.to_string()).unwrap();
 
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), "bob".to_string());
+        context.insert("principal".to_string(), "bob".into());
 
         let request = PolicyRequest {
             resource: "document1".to_string(),
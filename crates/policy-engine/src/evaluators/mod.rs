@@ -31,12 +31,15 @@ pub use simple::SimplePolicyEvaluator;
 /// Outcome of [`PolicyEvaluator::evaluate_named`]: the decision, whether a
 /// rule actually matched (vs the per-policy default), and — when the policy
 /// language has named rules — the deciding rule's name, borrowed from the
-/// evaluator so the eval loop allocates nothing.
+/// evaluator so the eval loop allocates nothing. Obligations are borrowed the
+/// same way.
 #[derive(Debug, Clone)]
 pub struct NamedOutcome<'a> {
     pub decision: PolicyAction,
     pub matched: bool,
     pub rule_name: Option<&'a str>,
+    /// Obligations and advice declared by the deciding rule.
+    pub obligations: Option<&'a crate::DecisionObligations>,
 }
 
 /// Core trait for policy evaluation across different languages
@@ -105,6 +108,7 @@ pub trait PolicyEvaluator: Send + Sync + Debug {
             decision,
             matched,
             rule_name: None,
+            obligations: None,
         })
     }
 
@@ -159,6 +163,42 @@ pub trait PolicyEvaluator: Send + Sync + Debug {
         })
     }
 
+    /// Evaluate `request` and record how the decision was reached: every
+    /// rule visited and the resolved operands of each condition (see
+    /// [`crate::reap::trace`]). Off the serving path; callers opt in.
+    ///
+    /// The default errs for the same reason as [`Self::check_with_input`].
+    fn explain(
+        &self,
+        request: &PolicyRequest,
+    ) -> Result<crate::reap::EvaluationTrace, ReaperError> {
+        let _ = request;
+        Err(ReaperError::EvaluationError {
+            reason: format!(
+                "explain is not supported by the '{}' evaluator (Reaper DSL policies only)",
+                self.evaluator_type()
+            ),
+        })
+    }
+
+    /// List authorization (FILTER_COMPILATION.md §4): this policy's rules
+    /// over an unknown resource, with everything `request` fixes
+    /// (principal, action, context, actor) evaluated now. `request.resource`
+    /// is ignored.
+    ///
+    /// `Ok(None)` = this evaluator cannot residualize; filter callers then
+    /// verify every candidate (ids) or fail closed (SQL / IR). `Err` is an
+    /// evaluation error the point path would hit for every resource, such
+    /// as an unknown principal. The default is `Ok(None)`: only the compiled
+    /// `.reap` evaluator residualizes.
+    fn residualize(
+        &self,
+        request: &PolicyRequest,
+    ) -> Result<Option<crate::filter::PolicyResidual>, ReaperError> {
+        let _ = request;
+        Ok(None)
+    }
+
     /// The finite set of resource strings this policy can match, or `None` if
     /// its match set is unbounded (wildcards, attribute/prefix/negation/dynamic
     /// resource predicates). `Some(v)` is a PROMISE: the evaluator matches NO
@@ -0,0 +1,96 @@
+//! Compiled evaluation of infix-arithmetic comparisons.
+//!
+//! The arithmetic is [`crate::reap::arith`]'s, shared with the interpreter's
+//! `Expr::BinaryOp` arm. What this module mirrors is the interpreter's
+//! operand reads and comparison rules:
+//!
+//! 1. An attribute, input or context value that is missing, null or non-numeric makes
+//!    the whole side undefined; so does overflow, a zero divisor, a float
+//!    `%` or a non-finite result.
+//! 2. An undefined side fails every operator — `!=` included.
+//! 3. `==`/`!=` are type-strict like `values_equal`: an integer never equals
+//!    a float, floats compare within EPSILON.
+//! 4. Ordered operators compare as f64, like `compare_numeric`.
+//! 5. JSON numbers map like `json_to_eval_value`: i64-representable ⇒
+//!    integer, else float.
+
+use super::context_eval::json_number;
+use super::types::{CompiledArithExpr, CompiledArithOperand, EntityBindings, NumericOp};
+use super::{entity_helpers::get_entity_for_type, EvalContext};
+use crate::data::AttributeValue;
+use crate::reap::arith::{self, Number};
+
+/// Evaluate a compiled `ArithCompare`.
+pub(super) fn eval_arith_compare(
+    left: &CompiledArithExpr,
+    op: &NumericOp,
+    right: &CompiledArithExpr,
+    bindings: EntityBindings<'_>,
+    context: &EvalContext<'_>,
+) -> bool {
+    let (Some(l), Some(r)) = (
+        eval_arith_expr(left, bindings, context),
+        eval_arith_expr(right, bindings, context),
+    ) else {
+        return false;
+    };
+    let equal = match (l, r) {
+        (Number::Int(a), Number::Int(b)) => a == b,
+        (Number::Float(a), Number::Float(b)) => (a - b).abs() < f64::EPSILON,
+        _ => false,
+    };
+    let (a, b) = (l.as_f64(), r.as_f64());
+    match op {
+        NumericOp::Equal => equal,
+        NumericOp::NotEqual => !equal,
+        NumericOp::Greater => a > b,
+        NumericOp::GreaterEqual => a >= b,
+        NumericOp::Less => a < b,
+        NumericOp::LessEqual => a <= b,
+    }
+}
+
+fn eval_arith_expr(
+    expr: &CompiledArithExpr,
+    bindings: EntityBindings<'_>,
+    context: &EvalContext<'_>,
+) -> Option<Number> {
+    match expr {
+        CompiledArithExpr::Operand(operand) => read_operand(operand, bindings, context),
+        CompiledArithExpr::Binary { op, left, right } => arith::apply(
+            *op,
+            eval_arith_expr(left, bindings, context)?,
+            eval_arith_expr(right, bindings, context)?,
+        ),
+    }
+}
+
+fn read_operand(
+    operand: &CompiledArithOperand,
+    bindings: EntityBindings<'_>,
+    context: &EvalContext<'_>,
+) -> Option<Number> {
+    match operand {
+        CompiledArithOperand::Int(i) => Some(Number::Int(*i)),
+        CompiledArithOperand::Float(f) => Some(Number::Float(*f)),
+        CompiledArithOperand::Attribute {
+            entity_type,
+            attribute,
+        } => match get_entity_for_type(entity_type, bindings)?.get_attribute(*attribute)? {
+            AttributeValue::Int(i) => Some(Number::Int(*i)),
+            AttributeValue::Float(f) => Some(Number::Float(*f)),
+            _ => None,
+        },
+        CompiledArithOperand::Input(path) => {
+            let number = match path.resolve(context.input?)? {
+                serde_json::Value::Number(n) => n,
+                _ => return None,
+            };
+            match number.as_i64() {
+                Some(i) => Some(Number::Int(i)),
+                None => number.as_f64().map(Number::Float),
+            }
+        }
+        CompiledArithOperand::Context(path) => context.read(path).and_then(json_number),
+    }
+}
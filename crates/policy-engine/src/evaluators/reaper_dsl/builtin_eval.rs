@@ -0,0 +1,137 @@
+//! Compiled evaluation of pure builtins (`base64::`, `hex::`, `url::`,
+//! `glob::match`, `semver::`, `to_number`, `to_string`).
+//!
+//! The functions are [`crate::reap::builtins`]'s, shared with the
+//! interpreter's `builtin_functions/pure.rs`. What this module mirrors is
+//! the interpreter's argument reads and comparison rules:
+//!
+//! 1. A missing attribute, input path or context key reads as null, which
+//!    every builtin treats as a wrong-typed argument (null or false result).
+//! 2. Entity attributes convert like the interpreter's values: sets come out
+//!    sorted, so `to_string` renders them identically.
+//! 3. Against a `null` literal, `==`/`!=` are a presence check; otherwise a
+//!    null result fails every operator, `!=` included.
+//! 4. `==`/`!=` are type-strict like `values_equal`: an integer never equals
+//!    a float, floats compare within EPSILON. Ordered operators are numeric.
+
+use super::context_eval::ContextRead;
+use super::types::{CompiledBuiltinArg, CompiledBuiltinCall, EntityBindings, NumericOp};
+use super::{entity_helpers::get_entity_for_type, EvalContext};
+use crate::data::StringInterner;
+use crate::reap::builtins::{self, Builtin};
+use serde_json::Value;
+
+/// Evaluate a call to its JSON result.
+pub(super) fn eval_builtin_call(
+    call: &CompiledBuiltinCall,
+    bindings: EntityBindings<'_>,
+    context: &EvalContext<'_>,
+    interner: &StringInterner,
+) -> Value {
+    let args: Vec<Value> = call
+        .args
+        .iter()
+        .map(|arg| read_arg(arg, bindings, context, interner))
+        .collect();
+    match (&call.glob, call.function) {
+        (Some(re), Builtin::GlobMatch) => {
+            let path = args.get(1).unwrap_or(&Value::Null);
+            Value::Bool(
+                re.as_ref()
+                    .is_some_and(|re| builtins::glob_matches(re, path)),
+            )
+        }
+        _ => call.function.call(&args),
+    }
+}
+
+/// `BuiltinTest`: true only on a `true` result.
+pub(super) fn eval_builtin_test(
+    call: &CompiledBuiltinCall,
+    bindings: EntityBindings<'_>,
+    context: &EvalContext<'_>,
+    interner: &StringInterner,
+) -> bool {
+    eval_builtin_call(call, bindings, context, interner) == Value::Bool(true)
+}
+
+/// `BuiltinCompare`: the result against a literal.
+pub(super) fn eval_builtin_compare(
+    call: &CompiledBuiltinCall,
+    op: &NumericOp,
+    literal: &Value,
+    bindings: EntityBindings<'_>,
+    context: &EvalContext<'_>,
+    interner: &StringInterner,
+) -> bool {
+    let result = eval_builtin_call(call, bindings, context, interner);
+    if literal.is_null() {
+        // Lowering admits only `==`/`!=` against null.
+        return match op {
+            NumericOp::Equal => result.is_null(),
+            NumericOp::NotEqual => !result.is_null(),
+            _ => false,
+        };
+    }
+    if result.is_null() {
+        return false;
+    }
+    let number = |v: &Value| v.as_number().and_then(|n| n.as_f64());
+    let ordered = |cmp: fn(f64, f64) -> bool| match (number(&result), number(literal)) {
+        (Some(a), Some(b)) => cmp(a, b),
+        _ => false,
+    };
+    match op {
+        NumericOp::Equal => strict_equal(&result, literal),
+        NumericOp::NotEqual => !strict_equal(&result, literal),
+        NumericOp::Greater => ordered(|a, b| a > b),
+        NumericOp::GreaterEqual => ordered(|a, b| a >= b),
+        NumericOp::Less => ordered(|a, b| a < b),
+        NumericOp::LessEqual => ordered(|a, b| a <= b),
+    }
+}
+
+/// `values_equal` over scalar JSON: integers and floats never mix.
+fn strict_equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => match (a.as_i64(), b.as_i64()) {
+            (Some(a), Some(b)) => a == b,
+            (None, None) => match (a.as_f64(), b.as_f64()) {
+                (Some(a), Some(b)) => (a - b).abs() < f64::EPSILON,
+                _ => false,
+            },
+            _ => false,
+        },
+        (Value::String(a), Value::String(b)) => a == b,
+        (Value::Bool(a), Value::Bool(b)) => a == b,
+        _ => false,
+    }
+}
+
+fn read_arg(
+    arg: &CompiledBuiltinArg,
+    bindings: EntityBindings<'_>,
+    context: &EvalContext<'_>,
+    interner: &StringInterner,
+) -> Value {
+    match arg {
+        CompiledBuiltinArg::Literal(value) => value.clone(),
+        CompiledBuiltinArg::Attribute {
+            entity_type,
+            attribute,
+        } => get_entity_for_type(entity_type, bindings)
+            .and_then(|entity| entity.get_attribute(*attribute))
+            .map(|value| super::attr_value_to_interpolation_json(value, interner))
+            .unwrap_or(Value::Null),
+        CompiledBuiltinArg::Input(path) => context
+            .input
+            .and_then(|doc| path.resolve(doc))
+            .cloned()
+            .unwrap_or(Value::Null),
+        CompiledBuiltinArg::Context(path) => match context.read(path) {
+            Some(ContextRead::Text(s)) => Value::String(s.to_string()),
+            Some(ContextRead::Json(value)) => value.clone(),
+            None => Value::Null,
+        },
+    }
+}
@@ -7,8 +7,8 @@
 //! - Membership values: Pre-compute AttributeValue for membership tests
 
 use super::types::{
-    ChainMethod, CompareTarget, Condition, ExprIndexType, ExprType, LiteralValue,
-    UncompiledIterationSource, UncompiledOutput,
+    ArithExpr, ArithOperand, BuiltinArg, BuiltinCall, ChainMethod, CompareTarget, Condition,
+    ExprIndexType, ExprType, LiteralValue, UncompiledIterationSource, UncompiledOutput,
 };
 use crate::data::{AttributeValue, InternedString, StringInterner};
 use rustc_hash::FxHashMap;
@@ -16,11 +16,9 @@ use rustc_hash::FxHashMap;
 /// Recursively collect and compile regex patterns from a condition
 pub fn collect_regex_patterns(condition: &Condition, cache: &mut FxHashMap<String, regex::Regex>) {
     match condition {
-        Condition::RegexMatches { pattern, .. } => {
-            if !cache.contains_key(pattern) {
-                if let Ok(re) = regex::Regex::new(pattern) {
-                    cache.insert(pattern.clone(), re);
-                }
+        Condition::RegexMatches { pattern, .. } if !cache.contains_key(pattern) => {
+            if let Ok(re) = regex::Regex::new(pattern) {
+                cache.insert(pattern.clone(), re);
             }
         }
         Condition::And(conditions) | Condition::Or(conditions) => {
@@ -28,7 +26,7 @@ pub fn collect_regex_patterns(condition: &Condition, cache: &mut FxHashMap<Strin
                 collect_regex_patterns(c, cache);
             }
         }
-        Condition::Not(inner) => {
+        Condition::Not(inner) | Condition::Quantifier { body: inner, .. } => {
             collect_regex_patterns(inner, cache);
         }
         _ => {} // Other conditions don't have regex patterns
@@ -155,6 +153,38 @@ pub fn collect_strings_for_interning(
         Condition::RegexMatches { attribute, .. } => {
             intern(attribute);
         }
+        Condition::NetMatch { attribute, op, .. } => {
+            use super::types::{NetOp, NetRanges};
+            intern(attribute);
+            if let NetOp::ContainedIn(
+                NetRanges::Attribute { attribute, .. } | NetRanges::AnyOf { attribute, .. },
+            )
+            | NetOp::Overlaps(
+                NetRanges::Attribute { attribute, .. } | NetRanges::AnyOf { attribute, .. },
+            ) = op
+            {
+                intern(attribute);
+            }
+        }
+        Condition::ArithCompare { left, right, .. } => {
+            fn walk(expr: &ArithExpr, intern: &mut impl FnMut(&String)) {
+                match expr {
+                    ArithExpr::Operand(ArithOperand::Attribute { attribute, .. }) => {
+                        intern(attribute)
+                    }
+                    ArithExpr::Operand(_) => {}
+                    ArithExpr::Binary { left, right, .. } => {
+                        walk(left, intern);
+                        walk(right, intern);
+                    }
+                }
+            }
+            walk(left, &mut intern);
+            walk(right, &mut intern);
+        }
+        Condition::BuiltinTest(call) | Condition::BuiltinCompare { call, .. } => {
+            intern_builtin_args(call, &mut intern)
+        }
         Condition::ObjectHasKey { attribute, key, .. } => {
             intern(attribute);
             intern(key);
@@ -408,6 +438,24 @@ pub fn collect_strings_for_interning(
         Condition::Not(inner) => {
             collect_strings_for_interning(inner, cache, interner);
         }
+        Condition::Quantifier {
+            index,
+            variable,
+            source,
+            body,
+            ..
+        } => {
+            if let Some(index) = index {
+                intern(index);
+            }
+            intern(variable);
+            match source {
+                UncompiledIterationSource::EntityAttr { attribute, .. } => intern(attribute),
+                UncompiledIterationSource::Variable { variable: v } => intern(v),
+                UncompiledIterationSource::Input { .. } => {}
+            }
+            collect_strings_for_interning(body, cache, interner);
+        }
         Condition::Always => {}
     }
 }
@@ -562,6 +610,17 @@ fn collect_expr_type_strings(
         // Input reads navigate the raw JSON document by string key; values
         // materialize with TRANSIENT interning at eval — nothing to pin.
         ExprType::InputRead { .. } => {}
+        ExprType::Builtin(call) => intern_builtin_args(call, &mut intern),
+    }
+}
+
+/// Builtin arguments intern only their attribute names; literals and
+/// request paths are raw request-side strings.
+fn intern_builtin_args(call: &BuiltinCall, intern: &mut impl FnMut(&String)) {
+    for arg in &call.args {
+        if let BuiltinArg::Attribute { attribute, .. } = arg {
+            intern(attribute);
+        }
     }
 }
 
@@ -608,15 +667,13 @@ pub fn collect_membership_values(
     interner: &StringInterner,
 ) {
     match condition {
+        // Only pre-compute String values (Int/Bool are Copy types)
         Condition::MembershipTest {
             value: LiteralValue::String(s),
             ..
-        } => {
-            // Only pre-compute String values (Int/Bool are Copy types)
-            if !cache.contains_key(s) {
-                let interned = interner.intern(s);
-                cache.insert(s.clone(), AttributeValue::String(interned));
-            }
+        } if !cache.contains_key(s) => {
+            let interned = interner.intern(s);
+            cache.insert(s.clone(), AttributeValue::String(interned));
         }
         Condition::MembershipTest { .. } => {
             // Int/Bool are Copy types, no pre-computation needed
@@ -626,7 +683,7 @@ pub fn collect_membership_values(
                 collect_membership_values(c, cache, interner);
             }
         }
-        Condition::Not(inner) => {
+        Condition::Not(inner) | Condition::Quantifier { body: inner, .. } => {
             collect_membership_values(inner, cache, interner);
         }
         _ => {} // Other conditions don't have membership tests
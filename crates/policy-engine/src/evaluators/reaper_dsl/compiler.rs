@@ -11,7 +11,17 @@
 //! - etc.
 
 use super::types::{
+    // Uncompiled types
+    ArithExpr,
+    ArithOperand,
+    Builtin,
+    BuiltinArg,
+    BuiltinCall,
     // Compiled types
+    CompiledArithExpr,
+    CompiledArithOperand,
+    CompiledBuiltinArg,
+    CompiledBuiltinCall,
     CompiledCompareTarget,
     CompiledComprehension,
     CompiledCondition,
@@ -24,7 +34,6 @@ use super::types::{
     CompiledRegexMatch,
     CompiledRule,
     ComprehensionType,
-    // Uncompiled types
     Condition,
     EntityType,
     LiteralValue,
@@ -40,6 +49,70 @@ use super::expr_compiler::compile_expr_type;
 // Re-export collection utilities from collect module for backward compatibility
 pub use super::collect::{collect_membership_values, collect_regex_patterns};
 
+fn compile_arith_expr(expr: &ArithExpr, interner: &StringInterner) -> CompiledArithExpr {
+    match expr {
+        ArithExpr::Operand(operand) => CompiledArithExpr::Operand(match operand {
+            ArithOperand::Attribute {
+                entity_type,
+                attribute,
+            } => CompiledArithOperand::Attribute {
+                entity_type: entity_type.clone(),
+                attribute: interner.intern(attribute),
+            },
+            ArithOperand::Input(path) => CompiledArithOperand::Input(path.clone()),
+            ArithOperand::Context(path) => CompiledArithOperand::Context(path.clone()),
+            ArithOperand::Int(i) => CompiledArithOperand::Int(*i),
+            ArithOperand::Float(f) => CompiledArithOperand::Float(*f),
+        }),
+        ArithExpr::Binary { op, left, right } => CompiledArithExpr::Binary {
+            op: *op,
+            left: Box::new(compile_arith_expr(left, interner)),
+            right: Box::new(compile_arith_expr(right, interner)),
+        },
+    }
+}
+
+/// Compile a builtin call: attribute names intern, literals parse, and a
+/// `glob::match` with literal pattern and delimiters compiles its regex once.
+pub(super) fn compile_builtin_call(
+    call: &BuiltinCall,
+    interner: &StringInterner,
+) -> CompiledBuiltinCall {
+    let args: Vec<CompiledBuiltinArg> = call
+        .args
+        .iter()
+        .map(|arg| match arg {
+            // Lowering wrote the text; unparseable text can only come from a
+            // hand-edited serialized condition and reads as null.
+            BuiltinArg::Literal(json) => CompiledBuiltinArg::Literal(
+                serde_json::from_str(json).unwrap_or(serde_json::Value::Null),
+            ),
+            BuiltinArg::Attribute {
+                entity_type,
+                attribute,
+            } => CompiledBuiltinArg::Attribute {
+                entity_type: entity_type.clone(),
+                attribute: interner.intern(attribute),
+            },
+            BuiltinArg::Input(path) => CompiledBuiltinArg::Input(path.clone()),
+            BuiltinArg::Context(path) => CompiledBuiltinArg::Context(path.clone()),
+        })
+        .collect();
+    let glob = match (call.function, args.first(), args.get(2)) {
+        (
+            Builtin::GlobMatch,
+            Some(CompiledBuiltinArg::Literal(pattern)),
+            Some(CompiledBuiltinArg::Literal(delimiters)),
+        ) => Some(crate::reap::builtins::glob_regex(pattern, delimiters)),
+        _ => None,
+    };
+    CompiledBuiltinCall {
+        function: call.function,
+        args,
+        glob,
+    }
+}
+
 /// Compile a condition with pre-interned strings for zero-lookup evaluation.
 /// This is called once at construction time, not during evaluation.
 pub fn compile_condition(condition: &Condition, interner: &StringInterner) -> CompiledCondition {
@@ -187,6 +260,70 @@ pub fn compile_condition(condition: &Condition, interner: &StringInterner) -> Co
             variable: interner.intern(variable),
         },
 
+        // ============ net:: CIDR / IP checks ============
+        // Range literals were validated at lowering; a malformed one cannot
+        // reach here, so the filter_map only ever drops nothing.
+        Condition::NetMatch {
+            entity_type,
+            attribute,
+            op,
+        } => {
+            use super::types::{CompiledNetOp, CompiledNetRanges, NetOp, NetRanges};
+            let ranges = |r: &NetRanges| match r {
+                NetRanges::Literal(items) => CompiledNetRanges::Literal(
+                    items
+                        .iter()
+                        .filter_map(|s| crate::net::IpNet::parse(s))
+                        .collect(),
+                ),
+                NetRanges::Attribute {
+                    entity_type,
+                    attribute,
+                } => CompiledNetRanges::Attribute {
+                    entity_type: entity_type.clone(),
+                    attribute: interner.intern(attribute),
+                },
+                NetRanges::AnyOf {
+                    entity_type,
+                    attribute,
+                } => CompiledNetRanges::AnyOf {
+                    entity_type: entity_type.clone(),
+                    attribute: interner.intern(attribute),
+                },
+            };
+            CompiledCondition::NetMatch {
+                entity_type: entity_type.clone(),
+                attribute: interner.intern(attribute),
+                op: match op {
+                    NetOp::ContainedIn(r) => CompiledNetOp::ContainedIn(ranges(r)),
+                    NetOp::Overlaps(r) => CompiledNetOp::Overlaps(ranges(r)),
+                    NetOp::IsIp => CompiledNetOp::IsIp,
+                    NetOp::IsIpv4 => CompiledNetOp::IsIpv4,
+                    NetOp::IsIpv6 => CompiledNetOp::IsIpv6,
+                    NetOp::IsCidr => CompiledNetOp::IsCidr,
+                },
+            }
+        }
+
+        // Arithmetic comparison: operand attribute names intern; input
+        // paths were pre-parsed at lowering.
+        Condition::ArithCompare { left, op, right } => CompiledCondition::ArithCompare {
+            left: compile_arith_expr(left, interner),
+            op: *op,
+            right: compile_arith_expr(right, interner),
+        },
+
+        // Pure builtins: argument attribute names intern; the comparison
+        // literal parses once.
+        Condition::BuiltinTest(call) => {
+            CompiledCondition::BuiltinTest(compile_builtin_call(call, interner))
+        }
+        Condition::BuiltinCompare { call, op, value } => CompiledCondition::BuiltinCompare {
+            call: compile_builtin_call(call, interner),
+            op: *op,
+            value: serde_json::from_str(value).unwrap_or(serde_json::Value::Null),
+        },
+
         // ============ Regex Match ============
         Condition::RegexMatches {
             entity_type,
@@ -535,26 +672,29 @@ pub fn compile_condition(condition: &Condition, interner: &StringInterner) -> Co
         Condition::Not(inner) => {
             CompiledCondition::Not(Box::new(compile_condition(inner, interner)))
         }
+
+        Condition::Quantifier {
+            kind,
+            index,
+            variable,
+            source,
+            body,
+        } => CompiledCondition::Quantifier {
+            kind: *kind,
+            index: index.as_deref().map(|i| interner.intern(i)),
+            variable: interner.intern(variable),
+            source: compile_iteration_source(source, interner),
+            body: Box::new(compile_condition(body, interner)),
+        },
     }
 }
 
-/// Compile comprehension with pre-interned strings
-fn compile_comprehension(
-    comp_type: &UncompiledComprehensionType,
-    iterator_var: &str,
-    iterator_source: &UncompiledIterationSource,
-    filters: &[Condition],
-    output: &Option<UncompiledOutput>,
-    key_output: &Option<UncompiledOutput>,
+/// Compile an iteration source with pre-interned strings
+fn compile_iteration_source(
+    source: &UncompiledIterationSource,
     interner: &StringInterner,
-) -> CompiledComprehension {
-    let compiled_type = match comp_type {
-        UncompiledComprehensionType::Set => ComprehensionType::Set,
-        UncompiledComprehensionType::Array => ComprehensionType::Array,
-        UncompiledComprehensionType::Object => ComprehensionType::Object,
-    };
-
-    let compiled_source = match iterator_source {
+) -> CompiledIterationSource {
+    match source {
         UncompiledIterationSource::EntityAttr {
             entity_type,
             attribute,
@@ -569,8 +709,27 @@ fn compile_comprehension(
         UncompiledIterationSource::Input { path } => {
             CompiledIterationSource::Input { path: path.clone() }
         }
+    }
+}
+
+/// Compile comprehension with pre-interned strings
+fn compile_comprehension(
+    comp_type: &UncompiledComprehensionType,
+    iterator_var: &str,
+    iterator_source: &UncompiledIterationSource,
+    filters: &[Condition],
+    output: &Option<UncompiledOutput>,
+    key_output: &Option<UncompiledOutput>,
+    interner: &StringInterner,
+) -> CompiledComprehension {
+    let compiled_type = match comp_type {
+        UncompiledComprehensionType::Set => ComprehensionType::Set,
+        UncompiledComprehensionType::Array => ComprehensionType::Array,
+        UncompiledComprehensionType::Object => ComprehensionType::Object,
     };
 
+    let compiled_source = compile_iteration_source(iterator_source, interner);
+
     let compiled_filters: Vec<CompiledCondition> = filters
         .iter()
         .map(|f| compile_condition(f, interner))
@@ -816,7 +975,14 @@ pub enum LeafStaticness {
 /// [`binds_variables`]. Classification is conservative: when in doubt a leaf
 /// is `Dynamic`, which can only cost a missed optimization, never a stale
 /// answer.
-pub fn leaf_staticness(cond: &CompiledCondition) -> LeafStaticness {
+///
+/// `conditional_edges` says whether the relationship graph holds any
+/// expiring or caveated edge ([`RelationshipGraph::conditional_len`]); such
+/// a graph answers ReBAC checks from the clock and the request context, so
+/// none of its checks is static.
+///
+/// [`RelationshipGraph::conditional_len`]: crate::data::RelationshipGraph::conditional_len
+pub fn leaf_staticness(cond: &CompiledCondition, conditional_edges: bool) -> LeafStaticness {
     use CompiledCondition as C;
     use LeafStaticness::{Dynamic, Static, StaticContext};
 
@@ -832,7 +998,10 @@ pub fn leaf_staticness(cond: &CompiledCondition) -> LeafStaticness {
     match cond {
         // -- The one provably data-static shape today: ReBAC with BOTH refs
         // literal. Every RebacKind (direct / reachable / inherited) reads
-        // only the relationship graph, whose mutations bump the data epoch.
+        // only the relationship graph, whose mutations bump the data epoch —
+        // unless the graph holds conditional edges, whose expiry and caveats
+        // read the clock and the context: those are never folded.
+        C::RebacCheck { .. } if conditional_edges => Dynamic,
         C::RebacCheck {
             subject, object, ..
         } => match (subject, object) {
@@ -859,6 +1028,31 @@ pub fn leaf_staticness(cond: &CompiledCondition) -> LeafStaticness {
         // compile time — no clock read at eval time, so the anchor decides.
         C::TimeOp(c) => by_entity(&c.entity_type),
         C::RegexMatch(c) => by_entity(&c.entity_type),
+        C::NetMatch {
+            entity_type, op, ..
+        } => {
+            use super::types::{CompiledNetOp, CompiledNetRanges};
+            let ranges = match op {
+                CompiledNetOp::ContainedIn(r) | CompiledNetOp::Overlaps(r) => Some(r),
+                _ => None,
+            };
+            match ranges {
+                Some(
+                    CompiledNetRanges::Attribute {
+                        entity_type: range_entity,
+                        ..
+                    }
+                    | CompiledNetRanges::AnyOf {
+                        entity_type: range_entity,
+                        ..
+                    },
+                ) => match (by_entity(entity_type), by_entity(range_entity)) {
+                    (StaticContext, StaticContext) => StaticContext,
+                    _ => Dynamic,
+                },
+                _ => by_entity(entity_type),
+            }
+        }
         C::CrossEntityCompare(c) => match (by_entity(&c.left_entity), by_entity(&c.right_entity)) {
             (StaticContext, StaticContext) => StaticContext,
             _ => Dynamic,
@@ -889,6 +1083,10 @@ pub fn leaf_staticness(cond: &CompiledCondition) -> LeafStaticness {
         C::TaintTrusted { .. } => Dynamic,
         // The input document is request-scoped by definition.
         C::InputCompare { .. } => Dynamic,
+        // Operands are entity attributes or input/context paths.
+        C::ArithCompare { .. } => Dynamic,
+        // Builtin arguments read request data or entity attributes.
+        C::BuiltinTest(_) | C::BuiltinCompare { .. } => Dynamic,
         // Reads a rule-scoped variable: eval-time dependent.
         C::VariableAttrStringOp { .. } => Dynamic,
         C::VariableAttrMembershipTest { .. } => Dynamic,
@@ -926,6 +1124,8 @@ pub fn leaf_staticness(cond: &CompiledCondition) -> LeafStaticness {
         | C::VariableAttrEqualsNull { .. }
         | C::VariableAttrNotEqualsNull { .. }
         | C::VariableAttrContains { .. } => Dynamic,
+        // Binds its own variables per element; the body is never walked.
+        C::Quantifier { .. } => Dynamic,
 
         // -- Constants and structure: no specialization opportunity in the
         // node itself. (`Always` is tier-1's business; And/Or/Not are walked
@@ -995,26 +1195,27 @@ fn leaf_count(cond: &CompiledCondition) -> usize {
 fn substitute_static_leaves(
     cond: &CompiledCondition,
     include_context: bool,
+    conditional_edges: bool,
     truth: bool,
 ) -> CompiledCondition {
     match cond {
         CompiledCondition::And(children) => CompiledCondition::And(
             children
                 .iter()
-                .map(|c| substitute_static_leaves(c, include_context, truth))
+                .map(|c| substitute_static_leaves(c, include_context, conditional_edges, truth))
                 .collect(),
         ),
         CompiledCondition::Or(children) => CompiledCondition::Or(
             children
                 .iter()
-                .map(|c| substitute_static_leaves(c, include_context, truth))
+                .map(|c| substitute_static_leaves(c, include_context, conditional_edges, truth))
                 .collect(),
         ),
         CompiledCondition::Not(inner) => CompiledCondition::Not(Box::new(
-            substitute_static_leaves(inner, include_context, truth),
+            substitute_static_leaves(inner, include_context, conditional_edges, truth),
         )),
         leaf => {
-            let qualifies = match leaf_staticness(leaf) {
+            let qualifies = match leaf_staticness(leaf, conditional_edges) {
                 LeafStaticness::Static => true,
                 LeafStaticness::StaticContext => include_context,
                 LeafStaticness::Dynamic => false,
@@ -1036,7 +1237,11 @@ fn substitute_static_leaves(
 /// True iff SOME truth assignment (all-true or all-false — static leaves in
 /// one rule share few enough shapes that mixed assignments add nothing the
 /// bound needs) folds to fewer non-constant leaves than the rule has today.
-fn condition_shortens(cond: &CompiledCondition, include_context: bool) -> bool {
+fn condition_shortens(
+    cond: &CompiledCondition,
+    include_context: bool,
+    conditional_edges: bool,
+) -> bool {
     let baseline = leaf_count(cond);
     if baseline == 0 {
         return false;
@@ -1045,24 +1250,29 @@ fn condition_shortens(cond: &CompiledCondition, include_context: bool) -> bool {
         leaf_count(&fold_condition(substitute_static_leaves(
             cond,
             include_context,
+            conditional_edges,
             truth,
         ))) < baseline
     })
 }
 
 /// Tally one condition's leaves into (total, static, static-context).
-fn tally_leaves(cond: &CompiledCondition, tally: &mut (usize, usize, usize)) {
+fn tally_leaves(
+    cond: &CompiledCondition,
+    conditional_edges: bool,
+    tally: &mut (usize, usize, usize),
+) {
     match cond {
         CompiledCondition::And(children) | CompiledCondition::Or(children) => {
             for child in children {
-                tally_leaves(child, tally);
+                tally_leaves(child, conditional_edges, tally);
             }
         }
-        CompiledCondition::Not(inner) => tally_leaves(inner, tally),
+        CompiledCondition::Not(inner) => tally_leaves(inner, conditional_edges, tally),
         CompiledCondition::Always => {}
         leaf => {
             tally.0 += 1;
-            match leaf_staticness(leaf) {
+            match leaf_staticness(leaf, conditional_edges) {
                 LeafStaticness::Static => tally.1 += 1,
                 LeafStaticness::StaticContext => tally.2 += 1,
                 LeafStaticness::Dynamic => {}
@@ -1073,24 +1283,28 @@ fn tally_leaves(cond: &CompiledCondition, tally: &mut (usize, usize, usize)) {
 
 /// Measure the tier-2 fitness of a set of compiled rules (design §6). Pure
 /// analysis: consults no DataStore, mutates nothing, safe to run anywhere.
-pub fn specialization_fitness(rules: &[CompiledRule]) -> SpecializationFitness {
+/// `conditional_edges` is as for [`leaf_staticness`].
+pub fn specialization_fitness(
+    rules: &[CompiledRule],
+    conditional_edges: bool,
+) -> SpecializationFitness {
     let mut fitness = SpecializationFitness {
         total_rules: rules.len(),
         ..Default::default()
     };
     for rule in rules {
         let mut tally = (0usize, 0usize, 0usize);
-        tally_leaves(&rule.condition, &mut tally);
+        tally_leaves(&rule.condition, conditional_edges, &mut tally);
         fitness.total_leaves += tally.0;
         fitness.static_leaves += tally.1;
         fitness.static_context_leaves += tally.2;
         if tally.1 > 0 {
             fitness.rules_with_static_leaf += 1;
         }
-        if condition_shortens(&rule.condition, false) {
+        if condition_shortens(&rule.condition, false, conditional_edges) {
             fitness.rules_shortenable += 1;
         }
-        if condition_shortens(&rule.condition, true) {
+        if condition_shortens(&rule.condition, true, conditional_edges) {
             fitness.rules_shortenable_with_static_context += 1;
         }
     }
@@ -1553,6 +1767,7 @@ mod tests {
             condition: cond,
             decision: PolicyAction::Allow,
             message: None,
+            obligations: None,
         }
     }
 
@@ -1561,7 +1776,10 @@ mod tests {
         let i = StringInterner::new();
 
         // Literal-literal ReBAC: the only Static shape today.
-        assert_eq!(leaf_staticness(&static_rebac(&i)), LeafStaticness::Static);
+        assert_eq!(
+            leaf_staticness(&static_rebac(&i), false),
+            LeafStaticness::Static
+        );
 
         // ReBAC with a request-bound ref: dynamic.
         let dynamic_rebac = CompiledCondition::RebacCheck {
@@ -1572,12 +1790,15 @@ mod tests {
             via: None,
             max_depth: 1,
         };
-        assert_eq!(leaf_staticness(&dynamic_rebac), LeafStaticness::Dynamic);
+        assert_eq!(
+            leaf_staticness(&dynamic_rebac, false),
+            LeafStaticness::Dynamic
+        );
 
         // Context-anchored comparison against a literal: static only under a
         // declared static context.
         assert_eq!(
-            leaf_staticness(&context_leaf(&i)),
+            leaf_staticness(&context_leaf(&i), false),
             LeafStaticness::StaticContext
         );
 
@@ -1592,16 +1813,44 @@ mod tests {
                 attribute: i.intern("region"),
             },
         });
-        assert_eq!(leaf_staticness(&ctx_vs_user), LeafStaticness::Dynamic);
+        assert_eq!(
+            leaf_staticness(&ctx_vs_user, false),
+            LeafStaticness::Dynamic
+        );
 
         // Request-intrinsic leaves.
-        assert_eq!(leaf_staticness(&leaf(&i, "r")), LeafStaticness::Dynamic);
         assert_eq!(
-            leaf_staticness(&CompiledCondition::TaintTrusted { key: "k".into() }),
+            leaf_staticness(&leaf(&i, "r"), false),
+            LeafStaticness::Dynamic
+        );
+        assert_eq!(
+            leaf_staticness(&CompiledCondition::TaintTrusted { key: "k".into() }, false),
             LeafStaticness::Dynamic
         );
         // Bindings are never static (side effects).
-        assert_eq!(leaf_staticness(&binding(&i)), LeafStaticness::Dynamic);
+        assert_eq!(
+            leaf_staticness(&binding(&i), false),
+            LeafStaticness::Dynamic
+        );
+    }
+
+    #[test]
+    fn test_conditional_graph_makes_rebac_dynamic() {
+        // Expiring or caveated edges answer from the clock and the request
+        // context: even a literal-literal check must not be folded.
+        let i = StringInterner::new();
+        assert_eq!(
+            leaf_staticness(&static_rebac(&i), true),
+            LeafStaticness::Dynamic
+        );
+        let fitness = specialization_fitness(&[rule(static_rebac(&i))], true);
+        assert_eq!(fitness.static_leaves, 0);
+        assert_eq!(fitness.rules_shortenable, 0);
+        // Non-ReBAC classification does not depend on the graph.
+        assert_eq!(
+            leaf_staticness(&context_leaf(&i), true),
+            LeafStaticness::StaticContext
+        );
     }
 
     #[test]
@@ -1627,7 +1876,7 @@ mod tests {
             leaf(&i, "doc4"),
         ]));
 
-        let fitness = specialization_fitness(&[r1, r2, r3]);
+        let fitness = specialization_fitness(&[r1, r2, r3], false);
         assert_eq!(fitness.total_rules, 3);
         assert_eq!(fitness.total_leaves, 6);
         assert_eq!(fitness.static_leaves, 1);
@@ -1651,14 +1900,14 @@ mod tests {
 
         // Direct check of the unsound direction: false-substitution + fold
         // must keep the binding.
-        let false_sub = fold_condition(substitute_static_leaves(&cond, false, false));
+        let false_sub = fold_condition(substitute_static_leaves(&cond, false, false, false));
         let CompiledCondition::And(children) = &false_sub else {
             panic!("binding conjunction must survive false-substitution");
         };
         assert!(binds_variables(&children[0]));
 
         // And the aggregate still reports it shortenable (via true).
-        let fitness = specialization_fitness(&[rule(cond)]);
+        let fitness = specialization_fitness(&[rule(cond)], false);
         assert_eq!(fitness.rules_shortenable, 1);
     }
 
@@ -1668,7 +1917,7 @@ mod tests {
         // constant: leaf_count 1 → 0. That is the biggest win available and
         // must register as shortening.
         let i = StringInterner::new();
-        let fitness = specialization_fitness(&[rule(static_rebac(&i))]);
+        let fitness = specialization_fitness(&[rule(static_rebac(&i))], false);
         assert_eq!(fitness.total_leaves, 1);
         assert_eq!(fitness.rules_shortenable, 1);
     }
@@ -1678,10 +1927,13 @@ mod tests {
         let i = StringInterner::new();
         // static || dynamic: false-substitution drops the disjunct;
         // true-substitution folds the Or to true. Either way shorter.
-        let fitness = specialization_fitness(&[rule(CompiledCondition::Or(vec![
-            static_rebac(&i),
-            leaf(&i, "doc"),
-        ]))]);
+        let fitness = specialization_fitness(
+            &[rule(CompiledCondition::Or(vec![
+                static_rebac(&i),
+                leaf(&i, "doc"),
+            ]))],
+            false,
+        );
         assert_eq!(fitness.rules_shortenable, 1);
     }
 }
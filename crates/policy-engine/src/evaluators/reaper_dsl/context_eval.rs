@@ -0,0 +1,169 @@
+//! Compiled reads of the typed request `context`.
+//!
+//! Context values are JSON. The interpreter converts each one with
+//! `json_to_eval_value` and compares it with `values_equal` /
+//! `compare_numeric` / `check_membership`; this module mirrors those rules
+//! directly over `serde_json::Value`, without materializing anything:
+//!
+//! 1. Strings are strings: `"1500"` never equals or orders against `1500`
+//!    (the compiled path used to parse numeric-looking strings; the
+//!    interpreter never did).
+//! 2. `context.a.b` navigates objects. A key that literally contains the
+//!    dot is looked up first (flat string maps used such keys before).
+//! 3. JSON `null` reads as absent: only `== null` / `!= null` see it.
+//! 4. `==` is type-strict and existential over arrays; ordered operators
+//!    compare numbers only.
+//!
+//! Request values are never interned: they are unbounded-cardinality.
+
+use super::types::{CompiledCompareTarget, CompiledLiteralValue, NumericOp};
+use super::EvalContext;
+use crate::data::StringInterner;
+use crate::reap::arith::Number;
+use serde_json::Value;
+
+/// One resolved `context.*` read.
+#[derive(Debug, Clone, Copy)]
+pub(super) enum ContextRead<'a> {
+    /// A string: `action`, `resource`, or a JSON string value.
+    Text(&'a str),
+    /// Any other non-null JSON value.
+    Json(&'a Value),
+}
+
+impl<'a> EvalContext<'a> {
+    /// Resolve `path` against the request. `None` = absent or null.
+    pub(super) fn read(&self, path: &str) -> Option<ContextRead<'a>> {
+        match path {
+            "action" => return Some(ContextRead::Text(self.action)),
+            "resource" => return Some(ContextRead::Text(self.resource)),
+            _ => {}
+        }
+        let value = match self.context.get(path) {
+            Some(value) => value,
+            None => {
+                let (root, rest) = path.split_once('.')?;
+                rest.split('.')
+                    .try_fold(self.context.get(root)?, |node, key| node.get(key))?
+            }
+        };
+        match value {
+            Value::Null => None,
+            Value::String(s) => Some(ContextRead::Text(s)),
+            other => Some(ContextRead::Json(other)),
+        }
+    }
+}
+
+/// `context.x <op> literal` for a present value.
+pub(super) fn compare_literal(
+    read: ContextRead<'_>,
+    op: NumericOp,
+    target: &CompiledCompareTarget,
+    interner: &StringInterner,
+) -> bool {
+    let value = match read {
+        ContextRead::Json(value) => value,
+        ContextRead::Text(s) => {
+            let equal = match target {
+                CompiledCompareTarget::LiteralString(id) => {
+                    interner.with_resolved(*id, |e| e == s).unwrap_or(false)
+                }
+                _ => false,
+            };
+            return match op {
+                NumericOp::Equal => equal,
+                NumericOp::NotEqual => !equal,
+                _ => false,
+            };
+        }
+    };
+    match op {
+        NumericOp::Equal => equals_literal(value, target, interner),
+        NumericOp::NotEqual => !equals_literal(value, target, interner),
+        _ => match (value.as_f64(), target) {
+            (Some(n), CompiledCompareTarget::LiteralNum(t)) => match op {
+                NumericOp::Greater => n > *t,
+                NumericOp::GreaterEqual => n >= *t,
+                NumericOp::Less => n < *t,
+                NumericOp::LessEqual => n <= *t,
+                NumericOp::Equal | NumericOp::NotEqual => unreachable!("handled above"),
+            },
+            _ => false,
+        },
+    }
+}
+
+fn equals_literal(
+    value: &Value,
+    target: &CompiledCompareTarget,
+    interner: &StringInterner,
+) -> bool {
+    match (value, target) {
+        (Value::Array(items), _) => items
+            .iter()
+            .any(|item| equals_literal(item, target, interner)),
+        (Value::String(s), CompiledCompareTarget::LiteralString(id)) => {
+            interner.with_resolved(*id, |e| e == s).unwrap_or(false)
+        }
+        (Value::Number(n), CompiledCompareTarget::LiteralNum(t)) => {
+            n.as_f64().is_some_and(|n| (n - t).abs() < f64::EPSILON)
+        }
+        (Value::Bool(b), CompiledCompareTarget::LiteralBool(e)) => b == e,
+        _ => false,
+    }
+}
+
+/// `literal in context.x`: array elements by type-strict equality, object
+/// keys by presence. Anything else holds nothing.
+pub(super) fn json_contains(
+    collection: &Value,
+    value: &CompiledLiteralValue,
+    interner: &StringInterner,
+) -> bool {
+    match collection {
+        Value::Array(items) => items.iter().any(|item| match (item, value) {
+            (Value::String(s), CompiledLiteralValue::String(id)) => {
+                interner.with_resolved(*id, |e| e == s).unwrap_or(false)
+            }
+            (Value::Number(n), CompiledLiteralValue::Int(i)) => n.as_i64() == Some(*i),
+            (Value::Bool(b), CompiledLiteralValue::Bool(e)) => b == e,
+            _ => false,
+        }),
+        Value::Object(map) => match value {
+            CompiledLiteralValue::String(id) => interner
+                .with_resolved(*id, |k| map.contains_key(k))
+                .unwrap_or(false),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// An arithmetic operand: JSON numbers only, i64-first.
+pub(super) fn json_number(read: ContextRead<'_>) -> Option<Number> {
+    let ContextRead::Json(Value::Number(n)) = read else {
+        return None;
+    };
+    match n.as_i64() {
+        Some(i) => Some(Number::Int(i)),
+        None => n.as_f64().map(Number::Float),
+    }
+}
+
+/// `eval_value_to_message` over a context value. Objects render as JSON
+/// (the interpreter debug-formats its own value type; outside the parity
+/// contract, like object-valued bound variables).
+pub(super) fn json_to_message(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => i.to_string(),
+            None => n.as_f64().map(|f| f.to_string()).unwrap_or_default(),
+        },
+        Value::Bool(b) => b.to_string(),
+        Value::Null => String::new(),
+        Value::Array(items) => items.iter().map(json_to_message).collect(),
+        Value::Object(_) => value.to_string(),
+    }
+}
@@ -32,6 +32,11 @@ pub(super) fn compile_expr_type(
             path: super::InputPath::from_dotted(path),
         },
 
+        // Pure builtin: same argument compilation as the condition forms.
+        ExprType::Builtin(call) => {
+            CompiledExprType::Builtin(super::compiler::compile_builtin_call(call, interner))
+        }
+
         ExprType::StringLower {
             entity_type,
             attribute,
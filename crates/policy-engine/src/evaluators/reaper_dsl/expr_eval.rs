@@ -637,6 +637,8 @@ pub fn evaluate_compiled_expr_type(
         // is unreachable by construction. None fails closed if that ever
         // changes.
         CompiledExprType::InputRead { .. } => None,
+        // Same for builtin calls, which read request data too.
+        CompiledExprType::Builtin(_) => None,
 
         CompiledExprType::StringLower {
             entity_type,
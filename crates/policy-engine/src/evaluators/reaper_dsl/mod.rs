@@ -10,18 +10,23 @@
 //! - `entity_helpers`: Entity access helpers for zero-overhead abstractions
 //! - `variable_eval`: Variable condition evaluation
 
+mod arith_eval;
+mod builtin_eval;
 mod chain_method_eval;
 mod collect;
 mod collection_eval;
 mod comparison_eval;
 mod compiler;
 mod comprehension_eval;
+mod context_eval;
 pub mod entity_helpers;
 mod expr_compiler;
 mod expr_eval;
 #[cfg(test)]
 mod expr_eval_tests;
 mod input_eval;
+mod net_eval;
+mod residual;
 mod string_eval;
 #[cfg(test)]
 mod tests;
@@ -165,13 +170,17 @@ type InputCollectionMemo =
 pub(crate) struct EvalContext<'a> {
     action: &'a str,
     resource: &'a str,
-    context: &'a std::collections::HashMap<String, String>,
+    /// Typed request context; read through [`EvalContext::read`].
+    context: &'a std::collections::HashMap<String, serde_json::Value>,
     /// The request's structured `input` document (R4-01 B.1) — raw JSON,
     /// navigated by pre-parsed `InputPath`s. `None` on the ordinary
     /// entity-request path; `Some` only via the `*_with_input` entries.
     input: Option<&'a serde_json::Value>,
     /// See [`InputCollectionMemo`].
     input_collections: &'a InputCollectionMemo,
+    /// The context ReBAC conditions install for edge caveats, copied once
+    /// per evaluation.
+    caveats: crate::data::CaveatContextCell,
 }
 
 impl<'a> EvalContext<'a> {
@@ -179,7 +188,7 @@ impl<'a> EvalContext<'a> {
     fn new(
         action: &'a str,
         resource: &'a str,
-        context: &'a std::collections::HashMap<String, String>,
+        context: &'a std::collections::HashMap<String, serde_json::Value>,
         input: Option<&'a serde_json::Value>,
         input_collections: &'a InputCollectionMemo,
     ) -> Self {
@@ -189,15 +198,16 @@ impl<'a> EvalContext<'a> {
             context,
             input,
             input_collections,
+            caveats: Default::default(),
         }
     }
 
+    /// String-valued reads only; typed values go through `read`.
     #[inline]
-    fn get(&self, key: &str) -> Option<&str> {
-        match key {
-            "action" => Some(self.action),
-            "resource" => Some(self.resource),
-            _ => self.context.get(key).map(|s| s.as_str()),
+    fn get(&self, key: &str) -> Option<&'a str> {
+        match self.read(key)? {
+            context_eval::ContextRead::Text(s) => Some(s),
+            context_eval::ContextRead::Json(_) => None,
         }
     }
 }
@@ -239,6 +249,9 @@ pub struct ReaperDSLEvaluator {
     /// Pre-computed AttributeValue objects for membership tests
     #[allow(dead_code)]
     membership_cache: Arc<FxHashMap<String, AttributeValue>>,
+    /// The `.reap` AST this evaluator was compiled from, kept for
+    /// [`PolicyEvaluator::explain`]; `None` for rules built directly.
+    source: Option<Arc<crate::reap::Policy>>,
 }
 
 impl ReaperDSLEvaluator {
@@ -285,18 +298,14 @@ impl ReaperDSLEvaluator {
                 message: rule.message.as_ref().map(|m| match m {
                     Message::Literal(s) => CompiledMessage::Literal(s.clone()),
                     Message::Variable(v) => CompiledMessage::Variable(interner.intern(v)),
-                    Message::Concat(parts) => CompiledMessage::Concat(
-                        parts
-                            .iter()
-                            .map(|p| match p {
-                                MessagePart::Literal(s) => CompiledMessagePart::Literal(s.clone()),
-                                MessagePart::Variable(v) => {
-                                    CompiledMessagePart::Variable(interner.intern(v))
-                                }
-                            })
-                            .collect(),
-                    ),
+                    Message::Concat(parts) => {
+                        CompiledMessage::Concat(compile_message_parts(parts, interner))
+                    }
+                    Message::Interpolated(parts) => {
+                        CompiledMessage::Interpolated(compile_message_parts(parts, interner))
+                    }
                 }),
+                obligations: rule.obligations,
                 name: rule.name,
                 decision: rule.decision.clone(),
             };
@@ -319,9 +328,17 @@ impl ReaperDSLEvaluator {
             actor_type_id,
             regex_cache: Arc::new(regex_cache),
             membership_cache: Arc::new(membership_cache),
+            source: None,
         }
     }
 
+    /// Keep the policy this evaluator was compiled from, so requests can be
+    /// traced against it.
+    pub(crate) fn with_source(mut self, policy: Arc<crate::reap::Policy>) -> Self {
+        self.source = Some(policy);
+        self
+    }
+
     /// Evaluate a compiled condition against entities
     ///
     /// This is the FAST PATH - all strings are pre-interned at construction time.
@@ -401,6 +418,11 @@ impl ReaperDSLEvaluator {
                     return false;
                 };
                 let graph = self.store.relationships();
+                let _caveats = graph.caveat_scope_in(
+                    &_context.caveats,
+                    _context.context,
+                    Some(_context.action),
+                );
                 match kind {
                     RebacKind::Direct => graph.has_relation(object_id, *relation, subject_id),
                     RebacKind::Reachable => graph.has_relation_reachable(
@@ -417,6 +439,9 @@ impl ReaperDSLEvaluator {
                         via.expect("inherited always compiles with via"),
                         *max_depth as usize,
                     ),
+                    RebacKind::Check => graph.check(object_id, *relation, subject_id, &|id| {
+                        self.store.get(id).map(|e| e.entity_type)
+                    }),
                 }
             }
 
@@ -469,8 +494,9 @@ impl ReaperDSLEvaluator {
                 if !needs_ctx {
                     comparison_eval::eval_cross_entity_comparison(comp, bindings, interner)
                 } else {
-                    // Ok(v)  = a concrete AttributeValue (entity attr, parsed
-                    //          number, or an already-interned request string).
+                    // Ok(v)  = a concrete AttributeValue (entity attr, typed
+                    //          request value, or an already-interned request
+                    //          string).
                     // Err(s) = a request string that is not interned, so it
                     //          content-matches nothing already in the store.
                     let resolve = |etype: &EntityType,
@@ -478,11 +504,17 @@ impl ReaperDSLEvaluator {
                      -> Option<Result<AttributeValue, Arc<str>>> {
                         if matches!(etype, EntityType::Context) {
                             let name = interner.resolve(attr)?;
-                            // EvalContext::get special-cases "action"/"resource".
-                            let raw: &str = _context.get(name.as_ref())?;
-                            if let Ok(n) = raw.parse::<f64>() {
-                                Some(Ok(AttributeValue::Float(n)))
-                            } else if let Some(id) = interner.lookup(raw) {
+                            // EvalContext::read special-cases "action"/"resource".
+                            // Typed values materialize transiently, like `input`.
+                            let raw: &str = match _context.read(name.as_ref())? {
+                                context_eval::ContextRead::Text(raw) => raw,
+                                context_eval::ContextRead::Json(v) => {
+                                    return Some(Ok(input_eval::json_to_attribute_transient(
+                                        v, interner,
+                                    )));
+                                }
+                            };
+                            if let Some(id) = interner.lookup(raw) {
                                 Some(Ok(AttributeValue::String(id)))
                             } else {
                                 Some(Err(Arc::from(raw)))
@@ -539,6 +571,26 @@ impl ReaperDSLEvaluator {
                 string_eval::eval_regex_match(m, bindings, interner)
             }
 
+            CompiledCondition::NetMatch {
+                entity_type,
+                attribute,
+                op,
+            } => {
+                net_eval::eval_net_match(entity_type, *attribute, op, bindings, _context, interner)
+            }
+
+            CompiledCondition::ArithCompare { left, op, right } => {
+                arith_eval::eval_arith_compare(left, op, right, bindings, _context)
+            }
+
+            CompiledCondition::BuiltinTest(call) => {
+                builtin_eval::eval_builtin_test(call, bindings, _context, interner)
+            }
+
+            CompiledCondition::BuiltinCompare { call, op, value } => {
+                builtin_eval::eval_builtin_compare(call, op, value, bindings, _context, interner)
+            }
+
             CompiledCondition::ObjectHasKey {
                 entity_type,
                 attribute,
@@ -656,6 +708,22 @@ impl ReaperDSLEvaluator {
                 }
             }
 
+            // `"x" in context.tags`: the collection is a request value.
+            CompiledCondition::MembershipTest {
+                value,
+                entity_type: EntityType::Context,
+                attribute,
+                index: None,
+            } => interner
+                .resolve(*attribute)
+                .and_then(|name| _context.read(&name))
+                .is_some_and(|read| match read {
+                    context_eval::ContextRead::Json(v) => {
+                        context_eval::json_contains(v, value, interner)
+                    }
+                    context_eval::ContextRead::Text(_) => false,
+                }),
+
             CompiledCondition::MembershipTest {
                 value,
                 entity_type,
@@ -1219,6 +1287,17 @@ impl ReaperDSLEvaluator {
                     false
                 }
             }
+
+            // ============ Quantifier Block ============
+            CompiledCondition::Quantifier {
+                kind,
+                index,
+                variable,
+                source,
+                body,
+            } => self.evaluate_quantifier(
+                *kind, *index, *variable, source, body, bindings, _context, variables,
+            ),
         }
     }
 
@@ -1244,7 +1323,7 @@ impl ReaperDSLEvaluator {
         // the catch-all and always returned false (`context.ticket != null`
         // denied on the fast path while the AST evaluator allowed; caught by
         // the policy-library parity suite).
-        let ctx_val_opt = context.get(&attr_name);
+        let ctx_val_opt = context.read(&attr_name);
         if matches!(&comp.target, CompiledCompareTarget::LiteralNull) {
             let is_null = ctx_val_opt.is_none();
             return match comp.op {
@@ -1254,48 +1333,9 @@ impl ReaperDSLEvaluator {
             };
         }
 
-        // Get the context value
-        let ctx_val = match ctx_val_opt {
-            Some(v) => v,
-            None => return false,
-        };
-
-        // Compare based on target type
-        match &comp.target {
-            CompiledCompareTarget::LiteralString(expected) => {
-                if let Some(expected_str) = interner.resolve(*expected) {
-                    match comp.op {
-                        NumericOp::Equal => ctx_val == &*expected_str,
-                        NumericOp::NotEqual => ctx_val != &*expected_str,
-                        _ => false,
-                    }
-                } else {
-                    false
-                }
-            }
-            CompiledCompareTarget::LiteralNum(threshold) => {
-                if let Ok(num) = ctx_val.parse::<f64>() {
-                    match comp.op {
-                        NumericOp::Equal => (num - threshold).abs() < f64::EPSILON,
-                        NumericOp::NotEqual => (num - threshold).abs() >= f64::EPSILON,
-                        NumericOp::Greater => num > *threshold,
-                        NumericOp::GreaterEqual => num >= *threshold,
-                        NumericOp::Less => num < *threshold,
-                        NumericOp::LessEqual => num <= *threshold,
-                    }
-                } else {
-                    false
-                }
-            }
-            CompiledCompareTarget::LiteralBool(expected) => {
-                let expected_str = if *expected { "true" } else { "false" };
-                match comp.op {
-                    NumericOp::Equal => ctx_val == expected_str,
-                    NumericOp::NotEqual => ctx_val != expected_str,
-                    _ => false,
-                }
-            }
-            _ => false, // EntityAttr and Variable not supported for context
+        match ctx_val_opt {
+            Some(read) => context_eval::compare_literal(read, comp.op, &comp.target, interner),
+            None => false,
         }
     }
 
@@ -1322,6 +1362,12 @@ impl ReaperDSLEvaluator {
                 None => AttributeValue::Null,
             });
         }
+        // Builtin calls read request data the same way; a null result binds
+        // Null and the assignment succeeds.
+        if let CompiledExprType::Builtin(call) = expr_type {
+            let value = builtin_eval::eval_builtin_call(call, bindings, context, interner);
+            return Some(input_eval::json_to_attribute_transient(&value, interner));
+        }
         expr_eval::evaluate_compiled_expr_type(expr_type, bindings, variables, interner)
     }
 
@@ -1518,6 +1564,109 @@ impl ReaperDSLEvaluator {
         }
     }
 
+    /// Evaluate `some`/`every` over a collection. Same contract as the
+    /// interpreter: each element is checked against a copy of `variables`
+    /// (nothing the body binds escapes), the index is the array position,
+    /// the object key (keys in sorted order) or the set element itself, and
+    /// a missing or non-collection source is false for both kinds.
+    #[allow(clippy::too_many_arguments)]
+    fn evaluate_quantifier(
+        &self,
+        kind: crate::reap::QuantifierKind,
+        index: Option<InternedString>,
+        variable: InternedString,
+        source: &CompiledIterationSource,
+        body: &CompiledCondition,
+        bindings: EntityBindings<'_>,
+        context: &EvalContext<'_>,
+        variables: &std::collections::HashMap<String, AttributeValue>,
+    ) -> bool {
+        use crate::reap::QuantifierKind;
+        let interner = self.store.interner();
+        let entries = |value: &AttributeValue| -> Option<Vec<(AttributeValue, AttributeValue)>> {
+            match value {
+                AttributeValue::List(items) => Some(
+                    items
+                        .iter()
+                        .enumerate()
+                        .map(|(i, item)| (AttributeValue::Int(i as i64), item.clone()))
+                        .collect(),
+                ),
+                AttributeValue::Set(items) => Some(
+                    items
+                        .iter()
+                        .map(|item| (item.clone(), item.clone()))
+                        .collect(),
+                ),
+                AttributeValue::Object(map) => {
+                    let mut keyed: Vec<_> = map
+                        .iter()
+                        .map(|(k, v)| (interner.resolve(*k).unwrap_or_default(), *k, v))
+                        .collect();
+                    keyed.sort_by(|a, b| a.0.cmp(&b.0));
+                    Some(
+                        keyed
+                            .into_iter()
+                            .map(|(_, k, v)| (AttributeValue::String(k), v.clone()))
+                            .collect(),
+                    )
+                }
+                _ => None,
+            }
+        };
+        let items = match source {
+            CompiledIterationSource::EntityAttr {
+                entity_type,
+                attribute,
+            } => {
+                let entity = match entity_type {
+                    EntityType::User => Some(bindings.user),
+                    EntityType::Resource => Some(bindings.resource),
+                    EntityType::Actor => bindings.actor,
+                    EntityType::Context => None,
+                };
+                entity
+                    .and_then(|e| e.get_attribute(*attribute))
+                    .and_then(entries)
+            }
+            CompiledIterationSource::Variable { variable } => interner
+                .resolve(*variable)
+                .and_then(|name| variables.get(&*name))
+                .and_then(entries),
+            CompiledIterationSource::Input { path } => context
+                .input
+                .and_then(|d| path.resolve(d))
+                .filter(|node| node.is_array() || node.is_object())
+                .map(|node| input_eval::json_to_attribute_transient(node, interner))
+                .and_then(|value| entries(&value)),
+        };
+        let Some(items) = items else {
+            return false;
+        };
+
+        let index_name = index
+            .and_then(|i| interner.resolve(i))
+            .map(|s| s.to_string());
+        let variable_name = interner
+            .resolve(variable)
+            .map(|s| s.to_string())
+            .unwrap_or_default();
+        for (position, item) in items {
+            let mut local_vars = variables.clone();
+            if let Some(index_name) = &index_name {
+                local_vars.insert(index_name.clone(), position);
+            }
+            local_vars.insert(variable_name.clone(), item);
+            let holds = self.evaluate_compiled_condition(body, bindings, context, &mut local_vars);
+            match kind {
+                QuantifierKind::Some if holds => return true,
+                QuantifierKind::Every if !holds => return false,
+                _ => {}
+            }
+        }
+        kind == QuantifierKind::Every
+    }
+
     /// Evaluate filters for object comprehension (returns bool indicating pass/fail)
     fn evaluate_object_comprehension_filters(
         &self,
@@ -1702,7 +1851,7 @@ impl ReaperDSLEvaluator {
     pub(crate) fn evaluate_with_match(
         &self,
         request: &PolicyRequest,
-    ) -> Result<(PolicyAction, bool, Option<&str>), reaper_core::ReaperError> {
+    ) -> Result<(PolicyAction, bool, Option<&CompiledRule>), reaper_core::ReaperError> {
         self.evaluate_with_match_input(request, None, false)
     }
 
@@ -1721,7 +1870,7 @@ impl ReaperDSLEvaluator {
         request: &PolicyRequest,
         input: Option<&serde_json::Value>,
         relax_principal: bool,
-    ) -> Result<(PolicyAction, bool, Option<&str>), reaper_core::ReaperError> {
+    ) -> Result<(PolicyAction, bool, Option<&CompiledRule>), reaper_core::ReaperError> {
         self.with_eval_env(
             request,
             input,
@@ -1738,7 +1887,7 @@ impl ReaperDSLEvaluator {
                         eval_context,
                         &mut variables,
                     ) {
-                        return (PolicyAction::Deny, true, Some(rule.name.as_str()));
+                        return (PolicyAction::Deny, true, Some(rule));
                     }
                     variables.clear();
                 }
@@ -1749,7 +1898,7 @@ impl ReaperDSLEvaluator {
                         eval_context,
                         &mut variables,
                     ) {
-                        return (PolicyAction::Allow, true, Some(rule.name.as_str()));
+                        return (PolicyAction::Allow, true, Some(rule));
                     }
                     variables.clear();
                 }
@@ -1856,7 +2005,7 @@ impl ReaperDSLEvaluator {
         // cardinality (many principals / resources) — and, when a principal is a
         // loaded entity, would pin that entity's id and defeat the data-plane's
         // refcounted reclamation. `lookup` only reads an already-interned id.
-        let principal = request.context.get("principal");
+        let principal = request.context_str("principal");
         // A principal that was never interned cannot be a loaded entity.
         let user_id = principal.and_then(|p| interner.lookup(p));
 
@@ -2008,17 +2157,17 @@ impl ReaperDSLEvaluator {
 }
 
 /// A message variable resolved the way the interpreter resolves
-/// `Expr::Variable`: rule-bound variables first, then the request's string
+/// `Expr::Variable`: rule-bound variables first, then the request's
 /// context map, else an "Undefined variable" error.
 enum ResolvedMsgVar<'a> {
     Bound(&'a AttributeValue),
-    RequestContext(&'a str),
+    RequestContext(&'a serde_json::Value),
 }
 
 fn resolve_message_var<'a>(
     id: crate::data::InternedString,
     variables: &'a std::collections::HashMap<String, AttributeValue>,
-    request_context: &'a std::collections::HashMap<String, String>,
+    request_context: &'a std::collections::HashMap<String, serde_json::Value>,
     interner: &crate::data::StringInterner,
 ) -> Result<ResolvedMsgVar<'a>, reaper_core::ReaperError> {
     let name = interner
@@ -2029,7 +2178,7 @@ fn resolve_message_var<'a>(
         Ok(ResolvedMsgVar::Bound(value))
     } else if let Some(s) = request_context.get(&name) {
         // The interpreter's Expr::Variable eval falls back to the request
-        // context (a string map) before erroring — mirror it.
+        // context before erroring — mirror it.
         Ok(ResolvedMsgVar::RequestContext(s))
     } else {
         Err(reaper_core::ReaperError::InvalidPolicy {
@@ -2051,7 +2200,7 @@ fn resolve_message_var<'a>(
 fn render_message(
     message: &CompiledMessage,
     variables: &std::collections::HashMap<String, AttributeValue>,
-    request_context: &std::collections::HashMap<String, String>,
+    request_context: &std::collections::HashMap<String, serde_json::Value>,
     interner: &crate::data::StringInterner,
 ) -> Result<String, reaper_core::ReaperError> {
     match message {
@@ -2059,7 +2208,7 @@ fn render_message(
         CompiledMessage::Variable(id) => {
             match resolve_message_var(*id, variables, request_context, interner)? {
                 ResolvedMsgVar::Bound(value) => Ok(attr_value_to_message(value, interner)),
-                ResolvedMsgVar::RequestContext(s) => Ok(s.to_string()),
+                ResolvedMsgVar::RequestContext(v) => Ok(context_eval::json_to_message(v)),
             }
         }
         CompiledMessage::Concat(parts) => {
@@ -2082,13 +2231,15 @@ fn render_message(
             for (part, value) in parts.iter().zip(resolved) {
                 match (part, value) {
                     (CompiledMessagePart::Literal(s), _) => out.push_str(s),
-                    (_, Some(ResolvedMsgVar::RequestContext(s))) => out.push_str(s),
+                    (_, Some(ResolvedMsgVar::RequestContext(serde_json::Value::String(s)))) => {
+                        out.push_str(s)
+                    }
                     (_, Some(ResolvedMsgVar::Bound(AttributeValue::String(id)))) => {
                         if let Some(s) = interner.resolve(*id) {
                             out.push_str(&s);
                         }
                     }
-                    (_, Some(ResolvedMsgVar::Bound(_))) => {
+                    (_, Some(ResolvedMsgVar::Bound(_) | ResolvedMsgVar::RequestContext(_))) => {
                         return Err(reaper_core::ReaperError::InvalidPolicy {
                             reason: "concat() requires string arguments".to_string(),
                         })
@@ -2098,6 +2249,73 @@ fn render_message(
             }
             Ok(out)
         }
+        // Holes evaluate in order (an unbound variable errors at its
+        // position) and render any value, like the interpreter.
+        CompiledMessage::Interpolated(parts) => {
+            let mut out = String::new();
+            for part in parts {
+                match part {
+                    CompiledMessagePart::Literal(s) => out.push_str(s),
+                    CompiledMessagePart::Variable(id) => {
+                        let json =
+                            match resolve_message_var(*id, variables, request_context, interner)? {
+                                ResolvedMsgVar::Bound(value) => {
+                                    attr_value_to_interpolation_json(value, interner)
+                                }
+                                ResolvedMsgVar::RequestContext(v) => v.clone(),
+                            };
+                        out.push_str(&crate::reap::interpolate::render(&json));
+                    }
+                }
+            }
+            Ok(out)
+        }
+    }
+}
+
+/// Pre-intern the variable parts of a lowered message.
+fn compile_message_parts(
+    parts: &[MessagePart],
+    interner: &crate::data::StringInterner,
+) -> Vec<CompiledMessagePart> {
+    parts
+        .iter()
+        .map(|p| match p {
+            MessagePart::Literal(s) => CompiledMessagePart::Literal(s.clone()),
+            MessagePart::Variable(v) => CompiledMessagePart::Variable(interner.intern(v)),
+        })
+        .collect()
+}
+
+/// An `AttributeValue` as the JSON an f-string hole renders, sets sorted —
+/// the interpreter's `strings::render` over the same value.
+fn attr_value_to_interpolation_json(
+    value: &AttributeValue,
+    interner: &crate::data::StringInterner,
+) -> serde_json::Value {
+    match value {
+        AttributeValue::List(items) => serde_json::Value::Array(
+            items
+                .iter()
+                .map(|v| attr_value_to_interpolation_json(v, interner))
+                .collect(),
+        ),
+        AttributeValue::Set(items) => crate::reap::interpolate::sorted_set(
+            items
+                .iter()
+                .map(|v| attr_value_to_interpolation_json(v, interner))
+                .collect(),
+        ),
+        AttributeValue::Object(map) => serde_json::Value::Object(
+            map.iter()
+                .filter_map(|(k, v)| {
+                    interner
+                        .resolve(*k)
+                        .map(|k| (k.to_string(), attr_value_to_interpolation_json(v, interner)))
+                })
+                .collect(),
+        ),
+        scalar => scalar.to_json(interner),
     }
 }
 
@@ -2143,17 +2361,19 @@ impl PolicyEvaluator for ReaperDSLEvaluator {
             .map(|(action, matched, _)| (action, matched))
     }
 
-    /// Allow-path explainability (F1-s4): surface the deciding rule's name,
-    /// borrowed from the compiled rule — zero allocation on the eval loop.
+    /// Allow-path explainability (F1-s4): surface the deciding rule's name
+    /// and obligations, borrowed from the compiled rule — zero allocation on
+    /// the eval loop.
     fn evaluate_named(
         &self,
         request: &PolicyRequest,
     ) -> Result<crate::evaluators::NamedOutcome<'_>, reaper_core::ReaperError> {
-        let (decision, matched, rule_name) = self.evaluate_with_match(request)?;
+        let (decision, matched, rule) = self.evaluate_with_match(request)?;
         Ok(crate::evaluators::NamedOutcome {
             decision,
             matched,
-            rule_name,
+            rule_name: rule.map(|r| r.name.as_str()),
+            obligations: rule.and_then(|r| r.obligations.as_ref()),
         })
     }
 
@@ -2165,6 +2385,30 @@ impl PolicyEvaluator for ReaperDSLEvaluator {
         ReaperDSLEvaluator::check_with_input(self, request, input)
     }
 
+    /// Replays the request through the interpreter over the source policy;
+    /// the compiled evaluation runs first so explain fails exactly where
+    /// serving would.
+    fn explain(
+        &self,
+        request: &PolicyRequest,
+    ) -> Result<crate::reap::EvaluationTrace, reaper_core::ReaperError> {
+        let served = self.evaluate_named(request)?;
+        let Some(source) = &self.source else {
+            return Err(reaper_core::ReaperError::EvaluationError {
+                reason: "explain: this evaluator was not compiled from a .reap policy".to_string(),
+            });
+        };
+        crate::reap::trace::replay(self.store.clone(), source, "reaper_dsl", served, request)
+    }
+
+    /// List authorization: every compiled rule residualizes (see `residual`).
+    fn residualize(
+        &self,
+        request: &PolicyRequest,
+    ) -> Result<Option<crate::filter::PolicyResidual>, reaper_core::ReaperError> {
+        self.compiled_residual(request).map(Some)
+    }
+
     fn validate(&self) -> Result<(), reaper_core::ReaperError> {
         // Validation happens at construction time
         // Check we have at least one rule
@@ -2293,16 +2537,20 @@ impl ReaperDSLEvaluator {
         input: Option<&serde_json::Value>,
     ) -> Result<(PolicyAction, Option<&str>), reaper_core::ReaperError> {
         self.evaluate_with_match_input(request, input, true)
-            .map(|(action, _, name)| (action, name))
+            .map(|(action, _, rule)| (action, rule.map(|r| r.name.as_str())))
     }
 
     /// Tier-2 specialization fitness of this evaluator's compiled rules
-    /// (R3 Plan 06 F.3 dry run — design §6). Pure measurement: consults no
-    /// data, rewrites nothing.
+    /// (R3 Plan 06 F.3 dry run — design §6). Pure measurement: reads only
+    /// whether the relationship graph holds conditional edges, rewrites
+    /// nothing.
     pub fn specialization_fitness(&self) -> compiler::SpecializationFitness {
-        let mut fitness = compiler::specialization_fitness(&self.compiled_deny_rules);
+        let conditional_edges = self.store.relationships().conditional_len() != 0;
+        let mut fitness =
+            compiler::specialization_fitness(&self.compiled_deny_rules, conditional_edges);
         fitness.merge(&compiler::specialization_fitness(
             &self.compiled_allow_rules,
+            conditional_edges,
         ));
         fitness
     }
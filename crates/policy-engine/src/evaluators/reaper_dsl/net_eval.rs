@@ -0,0 +1,133 @@
+//! Compiled evaluation of `net::` CIDR / IP-address checks.
+//!
+//! Parsing and matching are [`crate::net`]'s, shared with the interpreter's
+//! `builtin_functions/net.rs`, so the two paths agree by construction. What
+//! this module mirrors is the interpreter's VALUE handling:
+//!
+//! 1. The operand and any attribute-sourced range must be strings; a
+//!    missing attribute, a non-string, or a malformed address ⇒ false.
+//! 2. `context.*` reads the request's typed context (never interned —
+//!    request values are unbounded-cardinality): a string for the operand
+//!    and single ranges, a JSON array for `ip_in_any`.
+//! 3. A list/set range source (`ip_in_any`) skips non-string and malformed
+//!    elements, exactly like the interpreter.
+
+use super::context_eval::ContextRead;
+use super::types::{CompiledNetOp, CompiledNetRanges, EntityBindings, EntityType};
+use super::{entity_helpers::get_entity_for_type, EvalContext};
+use crate::data::{AttributeValue, InternedString, StringInterner};
+use crate::net::{self, IpFamily, IpNet};
+use serde_json::Value;
+
+/// Evaluate a compiled `NetMatch`.
+pub(super) fn eval_net_match(
+    entity_type: &EntityType,
+    attribute: InternedString,
+    op: &CompiledNetOp,
+    bindings: EntityBindings<'_>,
+    context: &EvalContext<'_>,
+    interner: &StringInterner,
+) -> bool {
+    let read = |f: fn(&str) -> bool| {
+        with_string_attr(entity_type, attribute, bindings, context, interner, f).unwrap_or(false)
+    };
+    // Parse the operand before touching the ranges: the interner guard is
+    // released before any attribute-sourced range is resolved.
+    let operand = || {
+        with_string_attr(
+            entity_type,
+            attribute,
+            bindings,
+            context,
+            interner,
+            IpNet::parse,
+        )
+        .flatten()
+    };
+    match op {
+        CompiledNetOp::IsIp => read(|s| net::is_ip(s, None)),
+        CompiledNetOp::IsIpv4 => read(|s| net::is_ip(s, Some(IpFamily::V4))),
+        CompiledNetOp::IsIpv6 => read(|s| net::is_ip(s, Some(IpFamily::V6))),
+        CompiledNetOp::IsCidr => read(net::is_cidr),
+        CompiledNetOp::ContainedIn(ranges) => match operand() {
+            Some(inner) => any_range(ranges, bindings, context, interner, |r| r.contains(&inner)),
+            None => false,
+        },
+        CompiledNetOp::Overlaps(ranges) => match operand() {
+            Some(other) => any_range(ranges, bindings, context, interner, |r| r.overlaps(&other)),
+            None => false,
+        },
+    }
+}
+
+/// True if `pred` holds for any range of the source.
+fn any_range(
+    ranges: &CompiledNetRanges,
+    bindings: EntityBindings<'_>,
+    context: &EvalContext<'_>,
+    interner: &StringInterner,
+    pred: impl Fn(&IpNet) -> bool,
+) -> bool {
+    match ranges {
+        CompiledNetRanges::Literal(nets) => nets.iter().any(pred),
+        CompiledNetRanges::Attribute {
+            entity_type,
+            attribute,
+        } => with_string_attr(entity_type, *attribute, bindings, context, interner, |s| {
+            IpNet::parse(s).is_some_and(|r| pred(&r))
+        })
+        .unwrap_or(false),
+        CompiledNetRanges::AnyOf {
+            entity_type,
+            attribute,
+        } => {
+            if matches!(entity_type, EntityType::Context) {
+                let Some(name) = interner.resolve(*attribute) else {
+                    return false;
+                };
+                return match context.read(name.as_ref()) {
+                    Some(ContextRead::Json(Value::Array(items))) => items.iter().any(|item| {
+                        item.as_str()
+                            .and_then(IpNet::parse)
+                            .is_some_and(|r| pred(&r))
+                    }),
+                    _ => false,
+                };
+            }
+            let Some(entity) = get_entity_for_type(entity_type, bindings) else {
+                return false;
+            };
+            let matches = |item: &AttributeValue| match item {
+                AttributeValue::String(id) => interner
+                    .with_resolved(*id, |s| IpNet::parse(s).is_some_and(|r| pred(&r)))
+                    .unwrap_or(false),
+                _ => false,
+            };
+            match entity.get_attribute(*attribute) {
+                Some(AttributeValue::List(items)) => items.iter().any(matches),
+                Some(AttributeValue::Set(items)) => items.iter().any(matches),
+                _ => false,
+            }
+        }
+    }
+}
+
+/// Run `f` over a string attribute of an entity or the request context.
+/// `None` if the attribute is missing or not a string.
+fn with_string_attr<R>(
+    entity_type: &EntityType,
+    attribute: InternedString,
+    bindings: EntityBindings<'_>,
+    context: &EvalContext<'_>,
+    interner: &StringInterner,
+    f: impl FnOnce(&str) -> R,
+) -> Option<R> {
+    if matches!(entity_type, EntityType::Context) {
+        let name = interner.resolve(attribute)?;
+        return context.get(name.as_ref()).map(f);
+    }
+    match get_entity_for_type(entity_type, bindings)?.get_attribute(attribute) {
+        Some(AttributeValue::String(id)) => interner.with_resolved(*id, f),
+        _ => None,
+    }
+}
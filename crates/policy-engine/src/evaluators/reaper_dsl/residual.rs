@@ -0,0 +1,599 @@
+//! Compiled-rule residualization for list authorization
+//! (`docs/development/FILTER_COMPILATION.md` §4).
+//!
+//! The request fixes principal, action, context and actor; the resource is
+//! the unknown. Every subtree that does not read the resource is evaluated
+//! now, with the same `evaluate_compiled_condition` the decision path uses,
+//! and folds to a constant. What is left are resource leaves, translated to
+//! [`ResidualAtom`]s with the request-side operand already grounded.
+//!
+//! Soundness rule: a leaf is only ever translated when the atom's meaning
+//! is exactly the leaf's truth for every resource. Anything else — a shape
+//! the IR has no atom for, a resource read through `context.resource`, a
+//! rule that touches the variables map — becomes `Opaque`, which every
+//! consumer treats as "unknown, verify".
+
+use super::entity_helpers::get_nested_attr;
+use super::types::{
+    CompiledArithExpr, CompiledArithOperand, CompiledBuiltinArg, CompiledBuiltinCall,
+    CompiledCompareTarget, CompiledCondition, CompiledLiteralValue, CompiledNetOp,
+    CompiledNetRanges, CompiledRebacRef, CompiledRule, EntityBindings, EntityType, NumericOp,
+};
+use super::{EvalContext, ReaperDSLEvaluator};
+use crate::data::{AttributeValue, InternedString, StringInterner};
+use crate::filter::{PolicyResidual, Residual, ResidualAtom, ResidualValue};
+use crate::PolicyRequest;
+
+/// What a compiled condition's truth depends on, for residualization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dependence {
+    /// Fixed by the request: evaluate now.
+    Known,
+    /// Reads the unknown resource somewhere.
+    Resource,
+    /// Touches the rule-scoped variables map. Bindings made by one leaf
+    /// feed later leaves, so no part of the rule can be evaluated alone.
+    Variables,
+}
+
+impl Dependence {
+    fn join(self, other: Dependence) -> Dependence {
+        use Dependence::{Known, Resource, Variables};
+        match (self, other) {
+            (Variables, _) | (_, Variables) => Variables,
+            (Resource, _) | (_, Resource) => Resource,
+            (Known, Known) => Known,
+        }
+    }
+
+    fn reads(resource: bool) -> Dependence {
+        if resource {
+            Dependence::Resource
+        } else {
+            Dependence::Known
+        }
+    }
+}
+
+/// Does `entity.attr` read the resource? `context.resource` does:
+/// `EvalContext::get` answers it from the request's resource string.
+fn reads_resource(entity: &EntityType, attr: InternedString, interner: &StringInterner) -> bool {
+    match entity {
+        EntityType::Resource => true,
+        EntityType::Context => interner
+            .resolve(attr)
+            .is_none_or(|name| &*name == "resource"),
+        EntityType::User | EntityType::Actor => false,
+    }
+}
+
+fn arith_reads_resource(expr: &CompiledArithExpr) -> bool {
+    match expr {
+        CompiledArithExpr::Operand(CompiledArithOperand::Attribute { entity_type, .. }) => {
+            matches!(entity_type, EntityType::Resource)
+        }
+        CompiledArithExpr::Operand(CompiledArithOperand::Context(path)) => path == "resource",
+        CompiledArithExpr::Operand(
+            CompiledArithOperand::Input(_)
+            | CompiledArithOperand::Int(_)
+            | CompiledArithOperand::Float(_),
+        ) => false,
+        CompiledArithExpr::Binary { left, right, .. } => {
+            arith_reads_resource(left) || arith_reads_resource(right)
+        }
+    }
+}
+
+fn builtin_reads_resource(call: &CompiledBuiltinCall) -> bool {
+    call.args.iter().any(|arg| match arg {
+        CompiledBuiltinArg::Attribute { entity_type, .. } => {
+            matches!(entity_type, EntityType::Resource)
+        }
+        CompiledBuiltinArg::Context(path) => path == "resource",
+        CompiledBuiltinArg::Input(_) | CompiledBuiltinArg::Literal(_) => false,
+    })
+}
+
+/// Classify a condition subtree. EXHAUSTIVE ON PURPOSE (no `_` arm), the
+/// same pin discipline as `leaf_staticness`: a new `CompiledCondition`
+/// variant must decide here whether it reads the resource. A wrong `Known`
+/// would evaluate a resource read against the probe's empty resource and
+/// bake that answer into every row, so when in doubt answer `Resource`
+/// (which at worst costs an `Opaque`).
+fn dependence(cond: &CompiledCondition, interner: &StringInterner) -> Dependence {
+    use CompiledCondition as C;
+    use Dependence::{Known, Resource, Variables};
+    let reads = |entity: &EntityType, attr: InternedString| reads_resource(entity, attr, interner);
+
+    match cond {
+        C::And(children) | C::Or(children) => children
+            .iter()
+            .fold(Known, |acc, c| acc.join(dependence(c, interner))),
+        C::Not(inner) => dependence(inner, interner),
+
+        C::AttributeCompare(c) => match &c.target {
+            CompiledCompareTarget::Variable(_) => Variables,
+            CompiledCompareTarget::EntityAttr {
+                entity_type,
+                attribute,
+            } => Dependence::reads(
+                reads(&c.entity_type, c.attribute) || reads(entity_type, *attribute),
+            ),
+            CompiledCompareTarget::LiteralString(_)
+            | CompiledCompareTarget::LiteralNum(_)
+            | CompiledCompareTarget::LiteralBool(_)
+            | CompiledCompareTarget::LiteralNull => {
+                Dependence::reads(reads(&c.entity_type, c.attribute))
+            }
+        },
+        C::StringOp(c) => Dependence::reads(reads(&c.entity_type, c.attribute)),
+        C::CountOp(c) => Dependence::reads(reads(&c.entity_type, c.attribute)),
+        C::TimeOp(c) => Dependence::reads(reads(&c.entity_type, c.attribute)),
+        C::RegexMatch(c) => Dependence::reads(reads(&c.entity_type, c.attribute)),
+        C::CrossEntityCompare(c) => Dependence::reads(
+            reads(&c.left_entity, c.left_attr) || reads(&c.right_entity, c.right_attr),
+        ),
+        C::WildcardCompare(c) => Dependence::reads(
+            reads(&c.collection_entity, c.collection_attr)
+                || reads(&c.scalar_entity, c.scalar_attr),
+        ),
+        C::NetMatch {
+            entity_type,
+            attribute,
+            op,
+        } => {
+            let range_reads = match op {
+                CompiledNetOp::ContainedIn(ranges) | CompiledNetOp::Overlaps(ranges) => {
+                    match ranges {
+                        CompiledNetRanges::Attribute {
+                            entity_type,
+                            attribute,
+                        }
+                        | CompiledNetRanges::AnyOf {
+                            entity_type,
+                            attribute,
+                        } => reads(entity_type, *attribute),
+                        CompiledNetRanges::Literal(_) => false,
+                    }
+                }
+                CompiledNetOp::IsIp
+                | CompiledNetOp::IsIpv4
+                | CompiledNetOp::IsIpv6
+                | CompiledNetOp::IsCidr => false,
+            };
+            Dependence::reads(reads(entity_type, *attribute) || range_reads)
+        }
+        C::ArithCompare { left, right, .. } => {
+            Dependence::reads(arith_reads_resource(left) || arith_reads_resource(right))
+        }
+        C::BuiltinTest(call) | C::BuiltinCompare { call, .. } => {
+            Dependence::reads(builtin_reads_resource(call))
+        }
+        C::SameEntityAttrCompare {
+            entity_type,
+            left_attr,
+            right_attr,
+            ..
+        } => Dependence::reads(reads(entity_type, *left_attr) || reads(entity_type, *right_attr)),
+        C::ObjectHasKey {
+            entity_type,
+            attribute,
+            ..
+        }
+        | C::CollectionAny {
+            entity_type,
+            attribute,
+        }
+        | C::CollectionAll {
+            entity_type,
+            attribute,
+        }
+        | C::MembershipTest {
+            entity_type,
+            attribute,
+            ..
+        }
+        | C::IndexedEquals {
+            entity_type,
+            attribute,
+            ..
+        }
+        | C::IsString {
+            entity_type,
+            attribute,
+        }
+        | C::IsNumber {
+            entity_type,
+            attribute,
+        }
+        | C::IsBool {
+            entity_type,
+            attribute,
+        }
+        | C::SetIntersectionCountGreater {
+            entity_type,
+            attribute,
+            ..
+        }
+        | C::MapKeyExists {
+            entity_type,
+            attribute,
+            ..
+        }
+        | C::ComprehensionCountGreaterEqual {
+            entity_type,
+            attribute,
+            ..
+        }
+        | C::ComprehensionCountEqual {
+            entity_type,
+            attribute,
+            ..
+        } => Dependence::reads(reads(entity_type, *attribute)),
+
+        C::RebacCheck {
+            subject, object, ..
+        } => Dependence::reads(
+            matches!(subject, CompiledRebacRef::ResourceId)
+                || matches!(object, CompiledRebacRef::ResourceId),
+        ),
+        C::ResourceIdEquals { .. } => Resource,
+
+        // Request-intrinsic: fixed by the filter query.
+        C::Always | C::ActionEquals { .. } | C::TaintTrusted { .. } | C::InputCompare { .. } => {
+            Known
+        }
+
+        C::Assignment { .. }
+        | C::ExpressionAssignment { .. }
+        | C::ExprCompareAssignment { .. }
+        | C::ComparisonAssignment { .. }
+        | C::NullComparisonAssignment { .. }
+        | C::VarAttrNullCompareAssignment { .. }
+        | C::ComprehensionAssignment { .. }
+        | C::EqualsVariable { .. }
+        | C::VariableStringOp(_)
+        | C::VariableEqualsLiteral { .. }
+        | C::VariableNotEqualsLiteral { .. }
+        | C::VariableCompare { .. }
+        | C::VariableIsNull { .. }
+        | C::VariableIsNotNull { .. }
+        | C::VariableMembershipTest { .. }
+        | C::VariableIsString { .. }
+        | C::VariableIsNumber { .. }
+        | C::VariableIsBool { .. }
+        | C::VariableIsTruthy { .. }
+        | C::VariableEqualsVariable { .. }
+        | C::VariableNotEqualsVariable { .. }
+        | C::VariableMethodWithLiteralArray { .. }
+        | C::VariableMethodCompare { .. }
+        | C::VariableChainedMethodCompare { .. }
+        | C::VariableAttrEqualsLiteral { .. }
+        | C::VariableAttrNotEqualsLiteral { .. }
+        | C::VariableAttrCompare { .. }
+        | C::VariableAttrEqualsNull { .. }
+        | C::VariableAttrNotEqualsNull { .. }
+        | C::VariableAttrContains { .. }
+        | C::VariableAttrStringOp { .. }
+        | C::VariableAttrMembershipTest { .. }
+        | C::Quantifier { .. } => Variables,
+    }
+}
+
+/// `a op b` ⇔ `b flip(op) a`.
+fn flip(op: NumericOp) -> NumericOp {
+    match op {
+        NumericOp::Equal => NumericOp::Equal,
+        NumericOp::NotEqual => NumericOp::NotEqual,
+        NumericOp::Less => NumericOp::Greater,
+        NumericOp::LessEqual => NumericOp::GreaterEqual,
+        NumericOp::Greater => NumericOp::Less,
+        NumericOp::GreaterEqual => NumericOp::LessEqual,
+    }
+}
+
+/// A number literal as the IR carries it: integral values in `i64` range
+/// stay integers, so SQL binds them against integer columns unchanged.
+fn number_value(n: f64) -> ResidualValue {
+    if n.fract() == 0.0 && n >= i64::MIN as f64 && n < i64::MAX as f64 {
+        ResidualValue::Int(n as i64)
+    } else {
+        ResidualValue::Float(n)
+    }
+}
+
+/// A known scalar as an IR value; `None` for missing, null and collection
+/// values, which no type-strict comparison accepts.
+fn scalar_value(
+    value: Option<&AttributeValue>,
+    interner: &StringInterner,
+) -> Option<ResidualValue> {
+    match value? {
+        AttributeValue::String(s) => interner.resolve_str(*s).map(ResidualValue::String),
+        AttributeValue::Int(n) => Some(ResidualValue::Int(*n)),
+        AttributeValue::Float(f) => Some(ResidualValue::Float(*f)),
+        AttributeValue::Bool(b) => Some(ResidualValue::Bool(*b)),
+        _ => None,
+    }
+}
+
+impl ReaperDSLEvaluator {
+    /// Residualize every rule over the unknown resource. Errors exactly
+    /// where the decision path would for any resource (unknown principal).
+    pub(super) fn compiled_residual(
+        &self,
+        request: &PolicyRequest,
+    ) -> Result<PolicyResidual, reaper_core::ReaperError> {
+        self.with_eval_env(request, None, false, |this, bindings, eval_context| {
+            let rules = |rules: &[CompiledRule]| {
+                Residual::or(
+                    rules
+                        .iter()
+                        .map(|rule| this.rule_residual(rule, bindings, eval_context)),
+                )
+            };
+            PolicyResidual {
+                deny: rules(&this.compiled_deny_rules),
+                allow: rules(&this.compiled_allow_rules),
+            }
+        })
+    }
+
+    fn rule_residual(
+        &self,
+        rule: &CompiledRule,
+        bindings: EntityBindings<'_>,
+        eval_context: &EvalContext<'_>,
+    ) -> Residual {
+        self.condition_residual(&rule.condition, &rule.name, bindings, eval_context)
+    }
+
+    fn condition_residual(
+        &self,
+        cond: &CompiledCondition,
+        rule: &str,
+        bindings: EntityBindings<'_>,
+        eval_context: &EvalContext<'_>,
+    ) -> Residual {
+        let interner = self.store.interner();
+        match dependence(cond, interner) {
+            Dependence::Known => {
+                let mut variables = std::collections::HashMap::new();
+                Residual::constant(self.evaluate_compiled_condition(
+                    cond,
+                    bindings,
+                    eval_context,
+                    &mut variables,
+                ))
+            }
+            Dependence::Variables => Residual::opaque(format!("rule '{rule}' binds variables")),
+            Dependence::Resource => match cond {
+                CompiledCondition::And(children) => Residual::and(
+                    children
+                        .iter()
+                        .map(|c| self.condition_residual(c, rule, bindings, eval_context)),
+                ),
+                CompiledCondition::Or(children) => Residual::or(
+                    children
+                        .iter()
+                        .map(|c| self.condition_residual(c, rule, bindings, eval_context)),
+                ),
+                CompiledCondition::Not(inner) => {
+                    Residual::not(self.condition_residual(inner, rule, bindings, eval_context))
+                }
+                leaf => self
+                    .resource_leaf(leaf, bindings, interner)
+                    .unwrap_or_else(|| {
+                        Residual::opaque(format!(
+                            "rule '{rule}' has a resource condition outside the residual IR"
+                        ))
+                    }),
+            },
+        }
+    }
+
+    /// Translate one resource-reading leaf, or `None` when no atom means
+    /// exactly what it does.
+    fn resource_leaf(
+        &self,
+        leaf: &CompiledCondition,
+        bindings: EntityBindings<'_>,
+        interner: &StringInterner,
+    ) -> Option<Residual> {
+        let known = |entity: &EntityType| matches!(entity, EntityType::User | EntityType::Actor);
+        match leaf {
+            CompiledCondition::ResourceIdEquals { value } => {
+                Some(Residual::atom(ResidualAtom::IdEquals {
+                    id: interner.resolve_str(*value)?,
+                }))
+            }
+
+            CompiledCondition::AttributeCompare(c) => match (&c.entity_type, &c.target) {
+                (
+                    EntityType::Resource,
+                    CompiledCompareTarget::EntityAttr {
+                        entity_type,
+                        attribute,
+                    },
+                ) if known(entity_type) => {
+                    let value = get_nested_attr(entity_type, *attribute, bindings, interner);
+                    self.grounded_compare(c.attribute, c.op, value.as_ref())
+                }
+                (
+                    entity_type,
+                    CompiledCompareTarget::EntityAttr {
+                        entity_type: EntityType::Resource,
+                        attribute,
+                    },
+                ) if known(entity_type) => {
+                    let value = get_nested_attr(entity_type, c.attribute, bindings, interner);
+                    self.grounded_compare(*attribute, flip(c.op), value.as_ref())
+                }
+                (EntityType::Resource, target) => {
+                    let value = match target {
+                        CompiledCompareTarget::LiteralString(s) => {
+                            ResidualValue::String(interner.resolve_str(*s)?)
+                        }
+                        CompiledCompareTarget::LiteralNum(n) => number_value(*n),
+                        CompiledCompareTarget::LiteralBool(b) => ResidualValue::Bool(*b),
+                        CompiledCompareTarget::LiteralNull => ResidualValue::Null,
+                        CompiledCompareTarget::EntityAttr { .. }
+                        | CompiledCompareTarget::Variable(_) => return None,
+                    };
+                    Some(Residual::atom(ResidualAtom::AttrCompare {
+                        attr: interner.resolve_str(c.attribute)?,
+                        op: c.op,
+                        value,
+                    }))
+                }
+                _ => None,
+            },
+
+            CompiledCondition::CrossEntityCompare(c) => {
+                match (&c.left_entity, &c.right_entity) {
+                    (EntityType::Resource, right) if known(right) => {
+                        let value = get_nested_attr(right, c.right_attr, bindings, interner);
+                        self.grounded_compare(c.left_attr, c.op, value.as_ref())
+                    }
+                    (left, EntityType::Resource) if known(left) => {
+                        let value = get_nested_attr(left, c.left_attr, bindings, interner);
+                        self.grounded_compare(c.right_attr, flip(c.op), value.as_ref())
+                    }
+                    // Context operands are parsed from request text with
+                    // their own coercions; leave them to verification.
+                    _ => None,
+                }
+            }
+
+            CompiledCondition::StringOp(c) if matches!(c.entity_type, EntityType::Resource) => {
+                Some(Residual::atom(ResidualAtom::AttrString {
+                    attr: interner.resolve_str(c.attribute)?,
+                    op: c.op,
+                    value: c.value.clone(),
+                }))
+            }
+
+            CompiledCondition::MembershipTest {
+                value,
+                entity_type: EntityType::Resource,
+                attribute,
+                index: None,
+            } => {
+                let value = match value {
+                    CompiledLiteralValue::String(s) => {
+                        ResidualValue::String(interner.resolve_str(*s)?)
+                    }
+                    CompiledLiteralValue::Int(n) => ResidualValue::Int(*n),
+                    CompiledLiteralValue::Bool(b) => ResidualValue::Bool(*b),
+                };
+                Some(Residual::atom(ResidualAtom::AttrContains {
+                    attr: interner.resolve_str(*attribute)?,
+                    value,
+                }))
+            }
+
+            // Existential `==` (the `!=` form quantifies differently and is
+            // left to verification).
+            CompiledCondition::WildcardCompare(c) if !c.negated => {
+                match (&c.collection_entity, &c.scalar_entity) {
+                    (EntityType::Resource, scalar) if known(scalar) => {
+                        let value = get_nested_attr(scalar, c.scalar_attr, bindings, interner);
+                        let Some(value) = scalar_value(value.as_ref(), interner) else {
+                            // No present scalar: the wildcard cannot hold.
+                            return Some(Residual::False);
+                        };
+                        Some(Residual::atom(ResidualAtom::AttrContains {
+                            attr: interner.resolve_str(c.collection_attr)?,
+                            value,
+                        }))
+                    }
+                    (collection, EntityType::Resource) if known(collection) => {
+                        let items: Vec<AttributeValue> = match get_nested_attr(
+                            collection,
+                            c.collection_attr,
+                            bindings,
+                            interner,
+                        ) {
+                            Some(AttributeValue::List(items)) => items,
+                            Some(AttributeValue::Set(items)) => items.into_iter().collect(),
+                            _ => return Some(Residual::False),
+                        };
+                        let attr = interner.resolve_str(c.scalar_attr)?;
+                        // Element equality here is `AttributeValue ==`; only
+                        // strings mean the same thing as an `AttrCompare`.
+                        let mut alternatives = Vec::with_capacity(items.len());
+                        for item in items {
+                            let AttributeValue::String(s) = item else {
+                                return None;
+                            };
+                            alternatives.push(Residual::atom(ResidualAtom::AttrCompare {
+                                attr: attr.clone(),
+                                op: NumericOp::Equal,
+                                value: ResidualValue::String(interner.resolve_str(s)?),
+                            }));
+                        }
+                        Some(Residual::or(alternatives))
+                    }
+                    _ => None,
+                }
+            }
+
+            CompiledCondition::RebacCheck {
+                kind,
+                subject,
+                relation,
+                object: CompiledRebacRef::ResourceId,
+                via,
+                max_depth,
+            } => {
+                let subject = match subject {
+                    CompiledRebacRef::Principal => bindings.user.id,
+                    CompiledRebacRef::Literal(id) => *id,
+                    // Absent actor: the check fails closed, as when evaluated.
+                    CompiledRebacRef::Actor => match bindings.actor {
+                        Some(actor) => actor.id,
+                        None => return Some(Residual::False),
+                    },
+                    CompiledRebacRef::ResourceId => return None,
+                };
+                // A never-interned subject (the sentinel id) holds nothing.
+                let Some(subject) = interner.resolve_str(subject) else {
+                    return Some(Residual::False);
+                };
+                let via = match via {
+                    Some(via) => Some(interner.resolve_str(*via)?),
+                    None => None,
+                };
+                Some(Residual::atom(ResidualAtom::RelatedTo {
+                    subject,
+                    relation: interner.resolve_str(*relation)?,
+                    kind: *kind,
+                    via,
+                    max_depth: *max_depth,
+                }))
+            }
+
+            _ => None,
+        }
+    }
+
+    /// `resource.<attr> op value` with the request side already read.
+    /// Mirrors `compare_attr_values`: a missing, null or collection operand
+    /// satisfies no operator.
+    fn grounded_compare(
+        &self,
+        attr: InternedString,
+        op: NumericOp,
+        value: Option<&AttributeValue>,
+    ) -> Option<Residual> {
+        let interner = self.store.interner();
+        let Some(value) = scalar_value(value, interner) else {
+            return Some(Residual::False);
+        };
+        Some(Residual::atom(ResidualAtom::AttrCompare {
+            attr: interner.resolve_str(attr)?,
+            op,
+            value,
+        }))
+    }
+}
@@ -37,13 +37,14 @@ fn test_reaper_dsl_simple_rule() {
         }),
         decision: PolicyAction::Allow,
         message: None,
+        obligations: None,
     }];
 
     let evaluator = ReaperDSLEvaluator::new(store, rules, PolicyAction::Deny);
 
     // Test evaluation
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "alice".to_string());
+    context.insert("principal".to_string(), "alice".into());
 
     let request = PolicyRequest {
         resource: "doc1".to_string(),
@@ -94,12 +95,13 @@ fn test_reaper_dsl_complex_rule() {
         }),
         decision: PolicyAction::Allow,
         message: None,
+        obligations: None,
     }];
 
     let evaluator = ReaperDSLEvaluator::new(store, rules, PolicyAction::Deny);
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "bob".to_string());
+    context.insert("principal".to_string(), "bob".into());
 
     let request = PolicyRequest {
         resource: "doc2".to_string(),
@@ -164,6 +166,7 @@ fn allow_rule(name: &str, condition: Condition) -> Rule {
         condition,
         decision: PolicyAction::Allow,
         message: None,
+        obligations: None,
     }
 }
 
@@ -295,6 +298,7 @@ fn test_ridx_deny_rule_literal_bounded() {
             condition: resource_eq("secret"),
             decision: PolicyAction::Deny,
             message: None,
+            obligations: None,
         }],
         PolicyAction::Allow,
     );
@@ -326,6 +330,7 @@ fn test_ridx_soundness_differential() {
             condition: resource_eq("secret"),
             decision: PolicyAction::Deny,
             message: None,
+            obligations: None,
         }],
         vec![
             allow_rule("dead", Condition::Not(Box::new(Condition::Always))),
@@ -357,7 +362,7 @@ fn test_ridx_soundness_differential() {
                 continue;
             }
             let mut context = HashMap::new();
-            context.insert("principal".to_string(), "alice".to_string());
+            context.insert("principal".to_string(), "alice".into());
             let request = PolicyRequest {
                 resource: probe.to_string(),
                 action: "read".to_string(),
@@ -658,6 +663,7 @@ fn test_pruning_soundness_differential_two_tier() {
             condition: resource_type_eq("secretkind"),
             decision: PolicyAction::Deny,
             message: None,
+            obligations: None,
         }],
     ];
 
@@ -690,7 +696,7 @@ fn test_pruning_soundness_differential_two_tier() {
                 continue; // the index would keep this policy as a candidate
             }
             let mut context = HashMap::new();
-            context.insert("principal".to_string(), "alice".to_string());
+            context.insert("principal".to_string(), "alice".into());
             let request = PolicyRequest {
                 resource: probe.to_string(),
                 action: "read".to_string(),
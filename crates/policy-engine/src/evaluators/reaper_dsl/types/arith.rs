@@ -0,0 +1,65 @@
+//! Compiled infix-arithmetic types (`user.used + input.requested <= user.quota`).
+//!
+//! The numeric rules themselves live in [`crate::reap::arith`], shared with
+//! the interpreter; these types only describe where each operand is read
+//! from. Operand sources are the ones whose values both paths read the same
+//! way: flat entity attributes, dotted `input` and `context` paths and
+//! number literals. Anything else (bound variables, method calls) keeps its
+//! per-rule AST fallback.
+
+use super::core::EntityType;
+use super::input::InputPath;
+use crate::data::InternedString;
+use crate::reap::ArithOp;
+use serde::{Deserialize, Serialize};
+
+/// One leaf of an arithmetic expression.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ArithOperand {
+    /// A single-segment attribute of `user`, `resource` or `actor`.
+    Attribute {
+        entity_type: EntityType,
+        attribute: String,
+    },
+    /// `input.<dotted.path>`, pre-parsed at lowering.
+    Input(InputPath),
+    /// `context.<key>` or `context.<dotted.path>`, read from the request.
+    Context(String),
+    Int(i64),
+    Float(f64),
+}
+
+/// An arithmetic expression tree; precedence is already applied.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ArithExpr {
+    Operand(ArithOperand),
+    Binary {
+        op: ArithOp,
+        left: Box<ArithExpr>,
+        right: Box<ArithExpr>,
+    },
+}
+
+/// Compiled [`ArithOperand`]: attribute names interned.
+#[derive(Debug, Clone)]
+pub enum CompiledArithOperand {
+    Attribute {
+        entity_type: EntityType,
+        attribute: InternedString,
+    },
+    Input(InputPath),
+    Context(String),
+    Int(i64),
+    Float(f64),
+}
+
+/// Compiled [`ArithExpr`].
+#[derive(Debug, Clone)]
+pub enum CompiledArithExpr {
+    Operand(CompiledArithOperand),
+    Binary {
+        op: ArithOp,
+        left: Box<CompiledArithExpr>,
+        right: Box<CompiledArithExpr>,
+    },
+}
@@ -0,0 +1,59 @@
+//! Compiled pure-builtin types (`base64::decode(context.auth)`,
+//! `glob::match(..)`, `semver::compare(..)`, `to_number(..)`).
+//!
+//! The functions themselves live in [`crate::reap::builtins`], shared with
+//! the interpreter; these types only describe where each argument is read
+//! from. Argument sources match [`super::arith::ArithOperand`]'s — flat
+//! entity attributes and dotted `input`/`context` paths — plus literals.
+//! Bound variables keep their per-rule AST fallback.
+
+use super::core::EntityType;
+use super::input::InputPath;
+use crate::data::InternedString;
+pub use crate::reap::builtins::Builtin;
+use serde::{Deserialize, Serialize};
+
+/// One argument of a builtin call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BuiltinArg {
+    /// A literal, as JSON text so serialized conditions stay format-agnostic.
+    Literal(String),
+    /// A single-segment attribute of `user`, `resource` or `actor`.
+    Attribute {
+        entity_type: EntityType,
+        attribute: String,
+    },
+    /// `input.<dotted.path>`, pre-parsed at lowering.
+    Input(InputPath),
+    /// `context.<key>` or `context.<dotted.path>`, read from the request.
+    Context(String),
+}
+
+/// A pure builtin applied to its arguments.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuiltinCall {
+    pub function: Builtin,
+    pub args: Vec<BuiltinArg>,
+}
+
+/// Compiled [`BuiltinArg`]: literals parsed, attribute names interned.
+#[derive(Debug, Clone)]
+pub enum CompiledBuiltinArg {
+    Literal(serde_json::Value),
+    Attribute {
+        entity_type: EntityType,
+        attribute: InternedString,
+    },
+    Input(InputPath),
+    Context(String),
+}
+
+/// Compiled [`BuiltinCall`].
+#[derive(Debug, Clone)]
+pub struct CompiledBuiltinCall {
+    pub function: Builtin,
+    pub args: Vec<CompiledBuiltinArg>,
+    /// `glob::match` with a literal pattern and delimiters: the regex,
+    /// compiled once (`None` inside when the pattern is malformed).
+    pub glob: Option<Option<regex::Regex>>,
+}
@@ -314,6 +314,44 @@ pub enum CompiledCondition {
         variable: InternedString,
         attribute: InternedString,
     },
+
+    /// `net::` address / CIDR check; literal ranges are pre-parsed. The
+    /// request-side address is parsed per evaluation and never interned.
+    NetMatch {
+        entity_type: EntityType,
+        attribute: InternedString,
+        op: super::net::CompiledNetOp,
+    },
+
+    /// Arithmetic comparison; both sides are evaluated per request and any
+    /// undefined or non-numeric result is a non-match.
+    ArithCompare {
+        left: super::arith::CompiledArithExpr,
+        op: super::operators::NumericOp,
+        right: super::arith::CompiledArithExpr,
+    },
+
+    /// `some`/`every` block; the body sees `index` and `variable` bound
+    /// and its own bindings never leave the block.
+    Quantifier {
+        kind: crate::reap::QuantifierKind,
+        index: Option<InternedString>,
+        variable: InternedString,
+        source: super::comprehension::CompiledIterationSource,
+        body: Box<CompiledCondition>,
+    },
+
+    /// Boolean pure builtin; true only when it returns `true`.
+    BuiltinTest(super::builtin::CompiledBuiltinCall),
+
+    /// Pure builtin result against a literal, with the interpreter's
+    /// comparison rules (null literal ⇒ presence check, a null result
+    /// otherwise fails, equality type-strict, ordering numeric).
+    BuiltinCompare {
+        call: super::builtin::CompiledBuiltinCall,
+        op: super::operators::NumericOp,
+        value: serde_json::Value,
+    },
 }
 
 // ============================================================================
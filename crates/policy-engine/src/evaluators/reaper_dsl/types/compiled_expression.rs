@@ -212,6 +212,10 @@ pub enum CompiledExprType {
     InputRead {
         path: super::input::InputPath,
     },
+
+    /// Pure builtin call; reads request data, so like `InputRead` it is
+    /// evaluated in the context-aware wrapper.
+    Builtin(super::builtin::CompiledBuiltinCall),
 }
 
 /// Compiled index type for indexed access expressions
@@ -35,6 +35,9 @@ pub enum RebacKind {
     Reachable,
     /// relation holds on object or an ancestor along `up` edges
     Inherited,
+    /// relation as the schema's rewrites define it for the object's type.
+    /// Appended so serialized conditions keep their encoding.
+    Check,
 }
 
 /// How a rebac argument resolves at evaluation time.
@@ -59,7 +62,8 @@ pub enum Condition {
     /// Always true
     Always,
 
-    /// ReBAC relationship check: rebac::related / reachable / inherited.
+    /// ReBAC relationship check: rebac::related / reachable / inherited /
+    /// check.
     /// Compiled to pure interned-id graph lookups (no strings at runtime).
     RebacCheck {
         kind: RebacKind,
@@ -400,4 +404,49 @@ pub enum Condition {
         variable: String,
         attribute: String,
     },
+
+    /// `net::cidr_contains` / `cidr_overlaps` / `ip_in_any` / `is_ip`-family
+    /// over an entity or context attribute. Appended after the original
+    /// variants so serialized conditions keep their encoding.
+    NetMatch {
+        entity_type: EntityType,
+        attribute: String,
+        op: super::net::NetOp,
+    },
+
+    /// Numeric comparison with infix arithmetic on at least one side
+    /// (`user.used + input.requested <= user.quota`). Appended after the
+    /// original variants so serialized conditions keep their encoding.
+    ArithCompare {
+        left: super::arith::ArithExpr,
+        op: super::operators::NumericOp,
+        right: super::arith::ArithExpr,
+    },
+
+    /// `some i, x in coll { body }` / `every x in coll { body }`. The body
+    /// runs once per element with `index` and `variable` bound, in a copy of
+    /// the rule's variables. Appended after the original variants so
+    /// serialized conditions keep their encoding.
+    Quantifier {
+        kind: crate::reap::QuantifierKind,
+        index: Option<String>,
+        variable: String,
+        source: UncompiledIterationSource,
+        body: Box<Condition>,
+    },
+
+    /// A boolean pure builtin as a bare condition (`glob::match(..)`,
+    /// `semver::satisfies(..)`). Appended after the original variants so
+    /// serialized conditions keep their encoding.
+    BuiltinTest(super::builtin::BuiltinCall),
+
+    /// A pure builtin's result compared with a literal
+    /// (`semver::compare(input.version, "2.0.0") >= 0`); the literal is JSON
+    /// text. Appended after the original variants so serialized conditions
+    /// keep their encoding.
+    BuiltinCompare {
+        call: super::builtin::BuiltinCall,
+        op: super::operators::NumericOp,
+        value: String,
+    },
 }
@@ -38,7 +38,11 @@ impl EntityBindings<'_> {
     pub fn context_trust(&self, key: &str) -> crate::TrustLevel {
         match self.provenance {
             None => crate::TrustLevel::Platform,
-            Some(map) => map.get(key).copied().unwrap_or(crate::TrustLevel::Llm),
+            Some(map) => map
+                .get(key)
+                .or_else(|| map.get(crate::engine::context_root(key)))
+                .copied()
+                .unwrap_or(crate::TrustLevel::Llm),
         }
     }
 }
@@ -58,6 +62,10 @@ pub struct Rule {
     /// their encoding.
     #[serde(default)]
     pub message: Option<Message>,
+    /// Obligations and advice returned when this rule decides. `None` = the
+    /// rule declares neither.
+    #[serde(default)]
+    pub obligations: Option<crate::DecisionObligations>,
 }
 
 /// A lowered check-mode message (uncompiled). The three shapes carry
@@ -79,6 +87,10 @@ pub enum Message {
     /// must be bound to a STRING at render, else the render errors like
     /// the interpreter's `concat()`.
     Concat(Vec<MessagePart>),
+    /// `with message f"..."` or `strings::format(...)` with a literal
+    /// template — ordered parts; variable parts render leniently, the way
+    /// an f-string hole renders any value.
+    Interpolated(Vec<MessagePart>),
 }
 
 /// One lowered piece of a check-mode `concat(...)` message (uncompiled).
@@ -101,6 +113,8 @@ pub struct CompiledRule {
     pub decision: PolicyAction,
     /// Check-mode message, variables pre-interned (R4-01 B.3).
     pub message: Option<CompiledMessage>,
+    /// Obligations and advice, handed out by reference when the rule decides.
+    pub obligations: Option<crate::DecisionObligations>,
 }
 
 /// Compiled check-mode message. Shape semantics mirror [`Message`]:
@@ -114,6 +128,9 @@ pub enum CompiledMessage {
     Variable(crate::data::InternedString),
     /// Ordered `concat(...)` parts; variable parts must hold strings.
     Concat(Vec<CompiledMessagePart>),
+    /// Ordered f-string / `strings::format` parts; variable parts render
+    /// any value.
+    Interpolated(Vec<CompiledMessagePart>),
 }
 
 /// One compiled `concat(...)` message piece.
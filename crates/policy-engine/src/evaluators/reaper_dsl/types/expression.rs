@@ -215,6 +215,12 @@ pub enum ExprType {
     InputRead {
         path: String,
     },
+
+    /// A pure builtin call (`x := base64::decode(context.auth)`). A null
+    /// result binds `Null` and the assignment succeeds, like the
+    /// interpreter. Appended after the original variants so serialized
+    /// expressions keep their encoding.
+    Builtin(super::builtin::BuiltinCall),
 }
 
 /// Index type for indexed access expressions
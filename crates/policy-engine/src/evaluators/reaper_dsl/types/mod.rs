@@ -9,8 +9,13 @@
 //! - `compiled_expression`: Compiled expression types
 //! - `comprehension`: Comprehension types
 //! - `compiled_literal`: CompiledLiteralValue
+//! - `net`: `net::` builtin ops and range sources
+//! - `arith`: infix arithmetic expression trees
+//! - `builtin`: pure builtin calls (`base64::`, `glob::`, `semver::`, ...)
 //! - `v2`: V2 consolidated types for reduced enum explosion
 
+mod arith;
+mod builtin;
 mod compiled_condition;
 mod compiled_expression;
 mod compiled_literal;
@@ -19,6 +24,7 @@ mod condition;
 mod core;
 mod expression;
 mod input;
+mod net;
 mod operators;
 mod v2;
 
@@ -39,9 +45,12 @@ pub use core::{
 pub use operators::{AttrCompareOp, ComprehensionFilterOp, CountOp, NumericOp, StringOp};
 
 // Condition types
+pub use arith::{ArithExpr, ArithOperand, CompiledArithExpr, CompiledArithOperand};
+pub use builtin::{Builtin, BuiltinArg, BuiltinCall, CompiledBuiltinArg, CompiledBuiltinCall};
 pub use compiled_condition::CompiledCondition;
 pub use condition::Condition;
 pub use input::{InputLiteral, InputPath, InputPathSeg};
+pub use net::{CompiledNetOp, CompiledNetRanges, NetOp, NetRanges};
 
 // Expression types
 pub use compiled_expression::{CompiledChainMethod, CompiledExprIndexType, CompiledExprType};
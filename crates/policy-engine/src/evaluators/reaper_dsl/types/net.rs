@@ -0,0 +1,68 @@
+//! Compiled `net::` builtin types (CIDR / IP-address checks).
+//!
+//! The uncompiled form keeps range literals as text so serialized conditions
+//! stay readable; the compiled form carries them pre-parsed into
+//! [`crate::net::IpNet`], so evaluation is one parse of the request-side
+//! address plus a mask-and-compare per range.
+
+use super::core::EntityType;
+use crate::data::InternedString;
+use crate::net::IpNet;
+use serde::{Deserialize, Serialize};
+
+/// Where the ranges of a containment / overlap check come from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum NetRanges {
+    /// Literal range strings, validated at lowering.
+    Literal(Vec<String>),
+    /// One range read from a string attribute (`net::cidr_contains(resource.cidr, ..)`).
+    Attribute {
+        entity_type: EntityType,
+        attribute: String,
+    },
+    /// A list/set attribute of range strings (`net::ip_in_any(.., resource.ranges)`).
+    AnyOf {
+        entity_type: EntityType,
+        attribute: String,
+    },
+}
+
+/// The check a `NetMatch` condition performs on its operand.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum NetOp {
+    /// Operand (address or subnet) lies inside any of the ranges.
+    ContainedIn(NetRanges),
+    /// Operand range shares an address with any of the ranges.
+    Overlaps(NetRanges),
+    /// Operand parses as an address of either family.
+    IsIp,
+    IsIpv4,
+    IsIpv6,
+    /// Operand parses as an explicit `addr/prefix`.
+    IsCidr,
+}
+
+/// Compiled [`NetRanges`]: literals pre-parsed, attribute names interned.
+#[derive(Debug, Clone)]
+pub enum CompiledNetRanges {
+    Literal(Vec<IpNet>),
+    Attribute {
+        entity_type: EntityType,
+        attribute: InternedString,
+    },
+    AnyOf {
+        entity_type: EntityType,
+        attribute: InternedString,
+    },
+}
+
+/// Compiled [`NetOp`].
+#[derive(Debug, Clone)]
+pub enum CompiledNetOp {
+    ContainedIn(CompiledNetRanges),
+    Overlaps(CompiledNetRanges),
+    IsIp,
+    IsIpv4,
+    IsIpv6,
+    IsCidr,
+}
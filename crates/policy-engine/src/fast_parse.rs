@@ -67,27 +67,7 @@ pub fn parse_policy_request(bytes: &[u8]) -> Result<PolicyRequest, ReaperError>
         })?
         .to_string();
 
-    // Parse context HashMap
-    let context = if let Some(ctx) = value.get("context") {
-        if ctx.is_object() {
-            let obj = ctx.as_object().unwrap();
-            let mut map = HashMap::with_capacity(obj.len());
-            for (k, v) in obj.iter() {
-                if let Some(s) = v.as_str() {
-                    map.insert(k.to_string(), s.to_string());
-                } else if v.is_i64() {
-                    map.insert(k.to_string(), v.as_i64().unwrap().to_string());
-                } else if let Some(b) = v.as_bool() {
-                    map.insert(k.to_string(), b.to_string());
-                }
-            }
-            map
-        } else {
-            HashMap::new()
-        }
-    } else {
-        HashMap::new()
-    };
+    let context = parse_context(&value, 0)?;
 
     Ok(PolicyRequest {
         resource,
@@ -98,6 +78,27 @@ pub fn parse_policy_request(bytes: &[u8]) -> Result<PolicyRequest, ReaperError>
     })
 }
 
+/// The request's `context` object, values kept as full JSON. A missing or
+/// non-object `context` is empty; `reserve` leaves room for keys the caller
+/// inserts (the principal).
+#[cfg(not(target_arch = "wasm32"))]
+fn parse_context(
+    value: &sonic_rs::Value,
+    reserve: usize,
+) -> Result<HashMap<String, serde_json::Value>, ReaperError> {
+    let Some(obj) = value.get("context").and_then(|ctx| ctx.as_object()) else {
+        return Ok(HashMap::with_capacity(reserve));
+    };
+    let mut map = HashMap::with_capacity(obj.len() + reserve);
+    for (k, v) in obj.iter() {
+        let v = serde_json::to_value(v).map_err(|e| ReaperError::InvalidPolicy {
+            reason: format!("Invalid context value for '{}': {}", k, e),
+        })?;
+        map.insert(k.to_string(), v);
+    }
+    Ok(map)
+}
+
 /// WASM fallback - uses serde_json
 #[cfg(target_arch = "wasm32")]
 pub fn parse_policy_request(bytes: &[u8]) -> Result<PolicyRequest, ReaperError> {
@@ -145,29 +146,10 @@ pub fn parse_evaluate_request(bytes: &[u8]) -> Result<PolicyRequest, ReaperError
         .to_string();
 
     // Parse context and add principal
-    let mut context = if let Some(ctx) = value.get("context") {
-        if ctx.is_object() {
-            let obj = ctx.as_object().unwrap();
-            let mut map = HashMap::with_capacity(obj.len() + 1);
-            for (k, v) in obj.iter() {
-                if let Some(s) = v.as_str() {
-                    map.insert(k.to_string(), s.to_string());
-                } else if v.is_i64() {
-                    map.insert(k.to_string(), v.as_i64().unwrap().to_string());
-                } else if let Some(b) = v.as_bool() {
-                    map.insert(k.to_string(), b.to_string());
-                }
-            }
-            map
-        } else {
-            HashMap::with_capacity(1)
-        }
-    } else {
-        HashMap::with_capacity(1)
-    };
+    let mut context = parse_context(&value, 1)?;
 
     // Always insert principal into context
-    context.insert("principal".to_string(), principal);
+    context.insert("principal".to_string(), principal.into());
 
     Ok(PolicyRequest {
         resource,
@@ -189,7 +171,7 @@ pub fn parse_evaluate_request(bytes: &[u8]) -> Result<PolicyRequest, ReaperError
         resource: String,
         action: String,
         #[serde(default)]
-        context: HashMap<String, String>,
+        context: HashMap<String, serde_json::Value>,
     }
 
     let req: EvalRequest =
@@ -198,7 +180,7 @@ pub fn parse_evaluate_request(bytes: &[u8]) -> Result<PolicyRequest, ReaperError
         })?;
 
     let mut context = req.context;
-    context.insert("principal".to_string(), req.principal);
+    context.insert("principal".to_string(), req.principal.into());
 
     Ok(PolicyRequest {
         resource: req.resource,
@@ -248,7 +230,7 @@ pub fn parse_batch_requests(bytes: &[u8]) -> Result<Vec<PolicyRequest>, ReaperEr
         resource: String,
         action: String,
         #[serde(default)]
-        context: HashMap<String, String>,
+        context: HashMap<String, serde_json::Value>,
     }
 
     let requests: Vec<EvalRequest> =
@@ -260,7 +242,7 @@ pub fn parse_batch_requests(bytes: &[u8]) -> Result<Vec<PolicyRequest>, ReaperEr
         .into_iter()
         .map(|req| {
             let mut context = req.context;
-            context.insert("principal".to_string(), req.principal);
+            context.insert("principal".to_string(), req.principal.into());
             PolicyRequest {
                 resource: req.resource,
                 action: req.action,
@@ -283,7 +265,7 @@ mod tests {
 
         assert_eq!(result.resource, "document_123");
         assert_eq!(result.action, "read");
-        assert_eq!(result.context.get("key"), Some(&"value".to_string()));
+        assert_eq!(result.context.get("key"), Some(&"value".into()));
     }
 
     #[test]
@@ -293,11 +275,8 @@ mod tests {
 
         assert_eq!(result.resource, "doc_789");
         assert_eq!(result.action, "write");
-        assert_eq!(
-            result.context.get("principal"),
-            Some(&"user_456".to_string())
-        );
-        assert_eq!(result.context.get("dept"), Some(&"eng".to_string()));
+        assert_eq!(result.context.get("principal"), Some(&"user_456".into()));
+        assert_eq!(result.context.get("dept"), Some(&"eng".into()));
     }
 
     #[test]
@@ -307,16 +286,21 @@ mod tests {
 
         assert_eq!(result.resource, "r1");
         assert_eq!(result.action, "a1");
-        assert_eq!(result.context.get("principal"), Some(&"u1".to_string()));
+        assert_eq!(result.context.get("principal"), Some(&"u1".into()));
     }
 
     #[test]
-    fn test_parse_with_numeric_context() {
-        let json = br#"{"principal":"user","resource":"res","action":"act","context":{"count":42,"flag":true}}"#;
+    fn test_parse_with_typed_context() {
+        let json = br#"{"principal":"user","resource":"res","action":"act","context":{"count":42,"flag":true,"tags":["a"],"order":{"total":9.5}}}"#;
         let result = parse_evaluate_request(json).unwrap();
 
-        assert_eq!(result.context.get("count"), Some(&"42".to_string()));
-        assert_eq!(result.context.get("flag"), Some(&"true".to_string()));
+        assert_eq!(result.context.get("count"), Some(&serde_json::json!(42)));
+        assert_eq!(result.context.get("flag"), Some(&serde_json::json!(true)));
+        assert_eq!(result.context.get("tags"), Some(&serde_json::json!(["a"])));
+        assert_eq!(
+            result.context.get("order"),
+            Some(&serde_json::json!({"total": 9.5}))
+        );
     }
 
     #[test]
@@ -336,7 +320,7 @@ mod tests {
         let result = parse_batch_requests(json).unwrap();
 
         assert_eq!(result.len(), 2);
-        assert_eq!(result[0].context.get("principal"), Some(&"u1".to_string()));
-        assert_eq!(result[1].context.get("principal"), Some(&"u2".to_string()));
+        assert_eq!(result[0].context.get("principal"), Some(&"u1".into()));
+        assert_eq!(result[1].context.get("principal"), Some(&"u2".into()));
     }
 }
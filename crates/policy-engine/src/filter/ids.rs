@@ -0,0 +1,241 @@
+//! In-engine ids backend (design §6): index narrowing, then verification.
+//!
+//! Phase 1 reads the allow residual as set algebra over entity ids and only
+//! ever narrows from a superset: an atom with no index behind it, an
+//! `Opaque`, or anything under `Not` contributes "every resource of the
+//! type". Phase 2 point-evaluates each surviving candidate with the served
+//! path's own set evaluation, so the page is exactly the permitted ids no
+//! matter how coarse phase 1 was.
+
+use super::{narrowing_residual, FilterError, FilterQuery, FilterScope, Residual, ResidualAtom};
+use crate::data::{DataStore, EntityId, EntityType};
+use crate::evaluators::reaper_dsl::{NumericOp, RebacKind};
+use crate::filter::ResidualValue;
+use crate::{PolicyAction, PolicyEngine};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Pagination and budget for one ids query.
+#[derive(Debug, Clone)]
+pub struct IdsOptions {
+    /// Keyset cursor: return only ids sorting strictly after this one.
+    pub after: Option<String>,
+    /// Page size.
+    pub limit: usize,
+    /// Phase-2 budget: more narrowed candidates than this is an error.
+    pub max_candidates: usize,
+}
+
+/// One page of permitted resource ids.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IdsPage {
+    /// Permitted ids, in ascending order.
+    pub ids: Vec<String>,
+    /// Pass as `after` to fetch the next page; `None` on the last page.
+    pub next_cursor: Option<String>,
+    /// Candidates left after index narrowing (the phase-2 set).
+    pub candidates: usize,
+    /// Store epoch the page was computed at.
+    pub data_epoch: u64,
+    /// The store changed while the page was computed, twice in a row: the
+    /// page may mix two snapshots.
+    pub epoch_moved: bool,
+}
+
+/// Answer `query` from `store`: one page of permitted resource ids.
+///
+/// The store epoch is read before and after; if it moved the page is
+/// recomputed once, and a second move is reported as `epoch_moved`.
+pub fn filter_ids(
+    engine: &PolicyEngine,
+    store: &DataStore,
+    query: &FilterQuery,
+    options: &IdsOptions,
+) -> Result<IdsPage, FilterError> {
+    let page = page_once(engine, store, query, options)?;
+    if page.data_epoch == store.data_epoch() {
+        return Ok(page);
+    }
+    let mut page = page_once(engine, store, query, options)?;
+    page.epoch_moved = page.data_epoch != store.data_epoch();
+    Ok(page)
+}
+
+fn page_once(
+    engine: &PolicyEngine,
+    store: &DataStore,
+    query: &FilterQuery,
+    options: &IdsOptions,
+) -> Result<IdsPage, FilterError> {
+    let data_epoch = store.data_epoch();
+    let interner = store.interner();
+    let mut page = IdsPage {
+        ids: Vec::new(),
+        next_cursor: None,
+        candidates: 0,
+        data_epoch,
+        epoch_moved: false,
+    };
+    // A type that was never interned has no entities.
+    let Some(resource_type) = interner.lookup(&query.resource_type) else {
+        return Ok(page);
+    };
+
+    let residual = narrowing_residual(engine, query);
+    let universe = store.ids_by_type(resource_type);
+    let narrowed = {
+        // Caveated edges narrow under the context phase 2 evaluates with;
+        // outside a scope they read as absent and would drop resources the
+        // verify step allows.
+        let graph = store.relationships();
+        let _caveats = (graph.conditional_len() != 0).then(|| {
+            let request = query.request_for("");
+            graph.caveat_scope(&request.context, Some(&query.action))
+        });
+        narrow(&residual, store, resource_type)
+    };
+    let candidates: Vec<EntityId> = match narrowed {
+        None => universe,
+        Some(set) => universe.into_iter().filter(|id| set.contains(id)).collect(),
+    };
+    if candidates.len() > options.max_candidates {
+        return Err(FilterError::CandidatesExceeded {
+            candidates: candidates.len(),
+            max: options.max_candidates,
+        });
+    }
+    page.candidates = candidates.len();
+
+    let mut names: Vec<String> = candidates
+        .into_iter()
+        .filter_map(|id| interner.resolve_str(id))
+        .filter(|name| {
+            options
+                .after
+                .as_deref()
+                .is_none_or(|after| name.as_str() > after)
+        })
+        .collect();
+    names.sort_unstable();
+
+    let limit = options.limit.max(1);
+    let mut remaining = names.into_iter();
+    for name in remaining.by_ref() {
+        if permitted(engine, store, query, &name) {
+            page.ids.push(name);
+            if page.ids.len() == limit {
+                break;
+            }
+        }
+    }
+    if page.ids.len() == limit && remaining.next().is_some() {
+        page.next_cursor = page.ids.last().cloned();
+    }
+    Ok(page)
+}
+
+/// Phase 2: the served decision for one candidate.
+fn permitted(
+    engine: &PolicyEngine,
+    store: &DataStore,
+    query: &FilterQuery,
+    resource: &str,
+) -> bool {
+    let request = query.request_for(resource);
+    let outcome = match &query.scope {
+        FilterScope::Policies(ids) => engine.evaluate_set(ids, &request),
+        FilterScope::All {
+            max_candidate_policies,
+            use_pruning_index,
+        } => {
+            let ids: Vec<_> = if *use_pruning_index {
+                let resource_type = store.resource_type_attr(resource);
+                engine.candidate_policy_ids(resource, resource_type.as_deref())
+            } else {
+                engine.list_policies().iter().map(|p| p.id).collect()
+            };
+            if ids.len() > *max_candidate_policies {
+                return false;
+            }
+            engine.evaluate_set(&ids, &request)
+        }
+    };
+    outcome.decision == PolicyAction::Allow
+}
+
+/// Phase 1: a superset of the ids `residual` can hold for, or `None` for
+/// "no narrowing".
+fn narrow(
+    residual: &Residual,
+    store: &DataStore,
+    resource_type: EntityType,
+) -> Option<HashSet<EntityId>> {
+    match residual {
+        Residual::True => None,
+        Residual::False => Some(HashSet::new()),
+        Residual::Atom(atom) => narrow_atom(atom, store, resource_type),
+        Residual::And(children) => children
+            .iter()
+            .filter_map(|child| narrow(child, store, resource_type))
+            .reduce(|a, b| a.intersection(&b).copied().collect()),
+        Residual::Or(children) => {
+            let mut union = HashSet::new();
+            for child in children {
+                union.extend(narrow(child, store, resource_type)?);
+            }
+            Some(union)
+        }
+        // Complement against a live population is a moving target; the
+        // verify phase decides negations.
+        Residual::Not(_) => None,
+    }
+}
+
+fn narrow_atom(
+    atom: &ResidualAtom,
+    store: &DataStore,
+    resource_type: EntityType,
+) -> Option<HashSet<EntityId>> {
+    let interner = store.interner();
+    match atom {
+        ResidualAtom::IdEquals { id } => Some(interner.lookup(id).into_iter().collect()),
+        // The composite index holds top-level string attributes only; a
+        // dotted path navigates nested values it never saw.
+        ResidualAtom::AttrCompare {
+            attr,
+            op: NumericOp::Equal,
+            value: ResidualValue::String(value),
+        } if !attr.contains('.') => {
+            let (Some(attr), Some(value)) = (interner.lookup(attr), interner.lookup(value)) else {
+                // Never interned: no entity carries it.
+                return Some(HashSet::new());
+            };
+            store.ids_by_type_and_attribute(resource_type, attr, value)
+        }
+        ResidualAtom::RelatedTo {
+            subject,
+            relation,
+            kind: RebacKind::Direct,
+            ..
+        } => {
+            let (Some(subject), Some(relation)) =
+                (interner.lookup(subject), interner.lookup(relation))
+            else {
+                return Some(HashSet::new());
+            };
+            Some(
+                store
+                    .relationships()
+                    .related_to(subject, relation)
+                    .iter()
+                    .copied()
+                    .collect(),
+            )
+        }
+        ResidualAtom::AttrCompare { .. }
+        | ResidualAtom::AttrContains { .. }
+        | ResidualAtom::AttrString { .. }
+        | ResidualAtom::RelatedTo { .. }
+        | ResidualAtom::Opaque { .. } => None,
+    }
+}
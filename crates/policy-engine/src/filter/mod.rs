@@ -0,0 +1,206 @@
+//! List authorization: which resources of a type may this principal act on?
+//! (design: `docs/development/FILTER_COMPILATION.md`).
+//!
+//! A filter query fixes principal, action and context and leaves the
+//! resource unknown. Each policy's compiled rules are residualized over that
+//! resource ([`crate::PolicyEvaluator::residualize`]) and the per-policy
+//! residuals compose with the engine's set semantics
+//! ([`crate::PolicyEngine::evaluate_set`]):
+//!
+//! ```text
+//! Permitted(r) = ¬ ∨ deny_p(r)  ∧  ∨ allow_p(r)
+//! ```
+//!
+//! The residual is then answered by one of two backends:
+//!
+//! - [`filter_ids`] — in-engine, against the agent's `DataStore`: the
+//!   residual narrows candidates through the type / composite / reverse
+//!   relationship indexes, and every candidate is verified with the real
+//!   point evaluation, so the page is exact however coarse the narrowing.
+//! - [`to_sql`] — a parameterized `WHERE` clause for resources that live in
+//!   the application's own database, widened to a superset wherever the
+//!   dialect or column mapping cannot express an atom (`exact: false`).
+//!
+//! Only the compiled `.reap` evaluator residualizes. The ids backend treats
+//! any other policy as "no narrowing" and still verifies; the SQL and IR
+//! outputs fail closed with [`FilterError::NotResidualizable`].
+
+mod ids;
+mod residual;
+mod sql;
+
+pub use ids::{filter_ids, IdsOptions, IdsPage};
+pub use residual::{PolicyResidual, Residual, ResidualAtom, ResidualValue};
+pub use sql::{filter_sql, to_sql, ParamStyle, SqlFilter, SqlOptions};
+
+use crate::{PolicyEngine, PolicyRequest, TrustLevel};
+use reaper_core::PolicyId;
+use std::collections::HashMap;
+
+/// Which policies a filter query is answered against — the same two shapes
+/// the agent's point-evaluation endpoints serve.
+#[derive(Debug, Clone)]
+pub enum FilterScope {
+    /// Exactly these policies (a named or id-addressed request).
+    Policies(Vec<PolicyId>),
+    /// Evaluate-all: per resource, the pruning index's candidate policies
+    /// (or every policy with `use_pruning_index` off), with the served
+    /// path's cap — a resource whose candidate set exceeds
+    /// `max_candidate_policies` is denied, as `/api/v1/messages` would.
+    All {
+        max_candidate_policies: usize,
+        use_pruning_index: bool,
+    },
+}
+
+/// One list-authorization query.
+#[derive(Debug, Clone)]
+pub struct FilterQuery {
+    pub principal: String,
+    pub action: String,
+    /// Entity type of the resources to list (the data document's `type`).
+    pub resource_type: String,
+    pub context: HashMap<String, serde_json::Value>,
+    pub actor: Option<String>,
+    pub context_provenance: Option<HashMap<String, TrustLevel>>,
+    pub scope: FilterScope,
+}
+
+impl FilterQuery {
+    /// The point-evaluation request this query asks about `resource`.
+    pub fn request_for(&self, resource: &str) -> PolicyRequest {
+        let mut context = self.context.clone();
+        context.insert("principal".to_string(), self.principal.clone().into());
+        PolicyRequest {
+            resource: resource.to_string(),
+            action: self.action.clone(),
+            context,
+            actor: self.actor.clone(),
+            context_provenance: self.context_provenance.clone(),
+        }
+    }
+
+    /// The policies whose residuals compose into this query's answer,
+    /// sorted for a deterministic residual.
+    fn policy_ids(&self, engine: &PolicyEngine) -> Vec<PolicyId> {
+        match &self.scope {
+            FilterScope::Policies(ids) => ids.clone(),
+            FilterScope::All { .. } => {
+                let mut ids: Vec<PolicyId> = engine.list_policies().iter().map(|p| p.id).collect();
+                ids.sort();
+                ids
+            }
+        }
+    }
+}
+
+/// Why a filter query could not be answered.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterError {
+    /// These policies have no residual (not compiled `.reap`, or their
+    /// evaluator is unavailable); only the ids backend can serve them.
+    NotResidualizable { policies: Vec<String> },
+    /// Index narrowing left more candidates than the verify budget allows.
+    CandidatesExceeded { candidates: usize, max: usize },
+    /// A policy failed to evaluate for the query's principal.
+    Evaluation { policy: String, reason: String },
+    /// A request-supplied SQL identifier is not a plain column name.
+    InvalidColumn { column: String },
+}
+
+impl FilterError {
+    /// Stable machine-readable code for API responses.
+    pub fn code(&self) -> &'static str {
+        match self {
+            FilterError::NotResidualizable { .. } => "not_residualizable",
+            FilterError::CandidatesExceeded { .. } => "candidates_exceeded",
+            FilterError::Evaluation { .. } => "evaluation_error",
+            FilterError::InvalidColumn { .. } => "invalid_column",
+        }
+    }
+}
+
+impl std::fmt::Display for FilterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FilterError::NotResidualizable { policies } => write!(
+                f,
+                "policies cannot be residualized (compiled .reap only): {}",
+                policies.join(", ")
+            ),
+            FilterError::CandidatesExceeded { candidates, max } => write!(
+                f,
+                "{candidates} candidates exceed the verify budget of {max}; \
+                 push the filter down (mode=sql) or narrow the query"
+            ),
+            FilterError::Evaluation { policy, reason } => {
+                write!(f, "policy '{policy}' failed to evaluate: {reason}")
+            }
+            FilterError::InvalidColumn { column } => {
+                write!(f, "'{column}' is not a valid SQL column name")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FilterError {}
+
+/// Residualize `query` over its policies and compose the set decision.
+///
+/// Strict: any policy without a residual is an error naming it, since a
+/// residual that silently skipped a policy could leak (a skipped deny) or
+/// drop (a skipped allow) rows. The ids backend uses the lenient
+/// [`narrowing_residual`] instead.
+pub fn residualize(engine: &PolicyEngine, query: &FilterQuery) -> Result<Residual, FilterError> {
+    let probe = query.request_for("");
+    let mut denies = Vec::new();
+    let mut allows = Vec::new();
+    let mut missing = Vec::new();
+    for id in query.policy_ids(engine) {
+        let Some(policy) = engine.get_policy(&id) else {
+            missing.push(id.to_string());
+            continue;
+        };
+        let residual = match policy.get_evaluator() {
+            Ok(evaluator) => evaluator.residualize(&probe),
+            Err(_) => Ok(None),
+        };
+        match residual {
+            Ok(Some(residual)) => {
+                denies.push(residual.deny);
+                allows.push(residual.allow);
+            }
+            Ok(None) => missing.push(policy.name.clone()),
+            Err(e) => {
+                return Err(FilterError::Evaluation {
+                    policy: policy.name.clone(),
+                    reason: e.to_string(),
+                })
+            }
+        }
+    }
+    if !missing.is_empty() {
+        return Err(FilterError::NotResidualizable { policies: missing });
+    }
+    Ok(Residual::and([
+        Residual::not(Residual::or(denies)),
+        Residual::or(allows),
+    ]))
+}
+
+/// The allow side of the composition only, with every policy that cannot be
+/// residualized (or fails to) contributing `Opaque`. A superset of the
+/// permitted resources by construction: a permitted resource needs at least
+/// one matched allow. Deny residuals are left to the verify phase.
+pub(crate) fn narrowing_residual(engine: &PolicyEngine, query: &FilterQuery) -> Residual {
+    let probe = query.request_for("");
+    Residual::or(query.policy_ids(engine).into_iter().map(|id| {
+        match engine
+            .get_policy(&id)
+            .map(|p| p.get_evaluator().map(|e| e.residualize(&probe)))
+        {
+            Some(Ok(Ok(Some(residual)))) => residual.allow,
+            _ => Residual::opaque(format!("policy {id} is verified only")),
+        }
+    }))
+}
@@ -0,0 +1,211 @@
+//! The residual IR (design §5): a small closed predicate language over one
+//! unknown resource.
+//!
+//! Strings are carried resolved, not interned — the IR is what `mode=ir`
+//! returns verbatim and what the SQL printer reads, so it must stand on its
+//! own outside the store's interner. The constructors fold constants as they
+//! build, so a residual never carries a `True`/`False` below the root.
+
+use crate::evaluators::reaper_dsl::{NumericOp, RebacKind, StringOp};
+use serde::{Deserialize, Serialize};
+
+/// A constant a residual atom compares against. Serialized as the bare JSON
+/// scalar.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ResidualValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+}
+
+/// One resource-dependent leaf, with everything known about the request
+/// already substituted in.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum ResidualAtom {
+    /// `resource == "x"` — the resource id itself.
+    IdEquals { id: String },
+    /// `resource.<attr> <op> <value>`, with the engine's type-strict
+    /// comparison rules: a missing or null attribute satisfies no operator
+    /// except `== null`.
+    AttrCompare {
+        attr: String,
+        op: NumericOp,
+        value: ResidualValue,
+    },
+    /// `<value> in resource.<attr>` over a list or set attribute.
+    AttrContains { attr: String, value: ResidualValue },
+    /// A string method on `resource.<attr>` (`startswith`, `lower() == …`).
+    AttrString {
+        attr: String,
+        op: StringOp,
+        value: String,
+    },
+    /// The resource is an object `subject` holds `relation` on. `kind`,
+    /// `via` and `max_depth` are the original check's traversal shape.
+    RelatedTo {
+        subject: String,
+        relation: String,
+        kind: RebacKind,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        via: Option<String>,
+        max_depth: u32,
+    },
+    /// A resource-dependent condition the IR cannot express. Never an
+    /// error: consumers treat it as "unknown" and verify the candidates.
+    Opaque { reason: String },
+}
+
+/// A residual predicate over the unknown resource.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Residual {
+    True,
+    False,
+    Atom(ResidualAtom),
+    And(Vec<Residual>),
+    Or(Vec<Residual>),
+    Not(Box<Residual>),
+}
+
+impl Residual {
+    /// A single atom.
+    pub fn atom(atom: ResidualAtom) -> Self {
+        Residual::Atom(atom)
+    }
+
+    /// An `Opaque` atom with the given reason.
+    pub fn opaque(reason: impl Into<String>) -> Self {
+        Residual::Atom(ResidualAtom::Opaque {
+            reason: reason.into(),
+        })
+    }
+
+    /// Constant truth value.
+    pub fn constant(value: bool) -> Self {
+        if value {
+            Residual::True
+        } else {
+            Residual::False
+        }
+    }
+
+    /// Conjunction: `False` absorbs, `True` drops out, nested `And`s flatten.
+    pub fn and(children: impl IntoIterator<Item = Residual>) -> Self {
+        let mut out = Vec::new();
+        for child in children {
+            match child {
+                Residual::False => return Residual::False,
+                Residual::True => {}
+                Residual::And(inner) => out.extend(inner),
+                other => out.push(other),
+            }
+        }
+        match out.len() {
+            0 => Residual::True,
+            1 => out.pop().expect("one child"),
+            _ => Residual::And(out),
+        }
+    }
+
+    /// Disjunction: `True` absorbs, `False` drops out, nested `Or`s flatten.
+    pub fn or(children: impl IntoIterator<Item = Residual>) -> Self {
+        let mut out = Vec::new();
+        for child in children {
+            match child {
+                Residual::True => return Residual::True,
+                Residual::False => {}
+                Residual::Or(inner) => out.extend(inner),
+                other => out.push(other),
+            }
+        }
+        match out.len() {
+            0 => Residual::False,
+            1 => out.pop().expect("one child"),
+            _ => Residual::Or(out),
+        }
+    }
+
+    /// Negation: constants flip, double negation cancels.
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(inner: Residual) -> Self {
+        match inner {
+            Residual::True => Residual::False,
+            Residual::False => Residual::True,
+            Residual::Not(inner) => *inner,
+            other => Residual::Not(Box::new(other)),
+        }
+    }
+
+    /// Does any `Opaque` atom remain?
+    pub fn has_opaque(&self) -> bool {
+        match self {
+            Residual::Atom(ResidualAtom::Opaque { .. }) => true,
+            Residual::True | Residual::False | Residual::Atom(_) => false,
+            Residual::And(children) | Residual::Or(children) => {
+                children.iter().any(Residual::has_opaque)
+            }
+            Residual::Not(inner) => inner.has_opaque(),
+        }
+    }
+}
+
+/// One policy's residual, split the way the evaluator composes it: a
+/// resource is denied by the policy when `deny` holds and allowed when
+/// `allow` holds and `deny` does not. Whether the policy matched at all
+/// (`deny ∨ allow`) is what set evaluation keys on; the per-policy default
+/// never enters the set decision.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PolicyResidual {
+    pub deny: Residual,
+    pub allow: Residual,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(s: &str) -> Residual {
+        Residual::atom(ResidualAtom::IdEquals { id: s.to_string() })
+    }
+
+    #[test]
+    fn constructors_fold_constants_and_flatten() {
+        assert_eq!(Residual::and([Residual::True, id("a")]), id("a"));
+        assert_eq!(Residual::and([id("a"), Residual::False]), Residual::False);
+        assert_eq!(Residual::and(Vec::new()), Residual::True);
+        assert_eq!(Residual::or([Residual::False, id("a")]), id("a"));
+        assert_eq!(Residual::or([id("a"), Residual::True]), Residual::True);
+        assert_eq!(Residual::or(Vec::new()), Residual::False);
+        assert_eq!(Residual::not(Residual::not(id("a"))), id("a"));
+        assert_eq!(
+            Residual::and([Residual::and([id("a"), id("b")]), id("c")]),
+            Residual::And(vec![id("a"), id("b"), id("c")])
+        );
+    }
+
+    #[test]
+    fn serializes_as_tagged_json() {
+        let residual = Residual::and([
+            Residual::atom(ResidualAtom::AttrCompare {
+                attr: "owner".into(),
+                op: NumericOp::Equal,
+                value: ResidualValue::String("alice".into()),
+            }),
+            Residual::not(Residual::opaque("rule 'r' binds variables")),
+        ]);
+        let json = serde_json::to_value(&residual).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"and": [
+                {"atom": {"type": "attr_compare", "attr": "owner", "op": "Equal", "value": "alice"}},
+                {"not": {"atom": {"type": "opaque", "reason": "rule 'r' binds variables"}}}
+            ]})
+        );
+        let back: Residual = serde_json::from_value(json).unwrap();
+        assert_eq!(back, residual);
+    }
+}
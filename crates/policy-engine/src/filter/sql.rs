@@ -0,0 +1,493 @@
+//! Pushdown backend (design §7): print a residual as a parameterized SQL
+//! `WHERE` clause.
+//!
+//! The output is a pre-filter plus a verify contract. Values only ever
+//! travel as placeholders; identifiers come from the caller's column map and
+//! must be plain (optionally table-qualified) names. Anything the printer
+//! cannot express — an unmapped attribute, a collection test, a traversal
+//! relation, an `Opaque` — is *widened*: replaced by `TRUE` under an even
+//! number of negations and `FALSE` under an odd number, which always yields
+//! a superset of the permitted rows. `exact` is set only when nothing was
+//! widened and every printed atom means in SQL exactly what it means in the
+//! engine; otherwise the caller must verify the rows it gets back.
+//!
+//! Columns are assumed typed like the attribute they map. Comparisons under
+//! an odd number of negations print as `COALESCE(…, FALSE)`: the engine's
+//! comparisons are two-valued (a missing attribute is simply false), and a
+//! bare `NOT (col = $1)` would drop the NULL rows the engine keeps.
+
+use super::{
+    residualize, FilterError, FilterQuery, FilterScope, Residual, ResidualAtom, ResidualValue,
+};
+use crate::data::DataStore;
+use crate::evaluators::reaper_dsl::{NumericOp, RebacKind, StringOp};
+use crate::PolicyEngine;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Placeholder syntax.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParamStyle {
+    /// `$1, $2, …` (PostgreSQL, SQLite).
+    #[default]
+    Dollar,
+    /// `?` (MySQL, SQLite).
+    Question,
+}
+
+/// How residual atoms map onto the caller's table.
+#[derive(Debug, Clone)]
+pub struct SqlOptions {
+    /// Column holding the resource id.
+    pub id_column: String,
+    /// Resource attribute → column. Unmapped attributes widen.
+    pub columns: HashMap<String, String>,
+    pub param_style: ParamStyle,
+    /// Largest relationship id set emitted as an `IN (…)` list; a larger
+    /// one widens.
+    pub max_in_list: usize,
+}
+
+/// A printed filter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SqlFilter {
+    /// Boolean SQL expression, ready to drop into a `WHERE`.
+    pub sql: String,
+    /// Placeholder values, in order.
+    pub params: Vec<serde_json::Value>,
+    /// `true` ⇒ the rows matched are exactly the permitted ones; `false` ⇒
+    /// they are a superset and each must be verified.
+    pub exact: bool,
+    /// Atoms widened out of the filter or printed with looser SQL semantics.
+    pub unverified_atoms: usize,
+    /// Store epoch the filter was computed at.
+    pub data_epoch: u64,
+}
+
+/// Residualize `query` and print it. Fails closed when any policy in scope
+/// has no residual.
+pub fn filter_sql(
+    engine: &PolicyEngine,
+    store: &DataStore,
+    query: &FilterQuery,
+    options: &SqlOptions,
+) -> Result<SqlFilter, FilterError> {
+    let data_epoch = store.data_epoch();
+    let residual = residualize(engine, query)?;
+    let mut filter = to_sql(&residual, store, options)?;
+    filter.data_epoch = data_epoch;
+    // Evaluate-all denies a resource whose candidate set is over the cap;
+    // the composed residual cannot see that, so only a set that can never
+    // exceed it stays exact.
+    if let FilterScope::All {
+        max_candidate_policies,
+        ..
+    } = query.scope
+    {
+        if engine.list_policies().len() > max_candidate_policies {
+            filter.exact = false;
+        }
+    }
+    Ok(filter)
+}
+
+/// Print `residual` as SQL. `store` resolves relationship atoms to id sets.
+pub fn to_sql(
+    residual: &Residual,
+    store: &DataStore,
+    options: &SqlOptions,
+) -> Result<SqlFilter, FilterError> {
+    for column in std::iter::once(&options.id_column).chain(options.columns.values()) {
+        if !is_identifier(column) {
+            return Err(FilterError::InvalidColumn {
+                column: column.clone(),
+            });
+        }
+    }
+    let mut printer = Printer {
+        store,
+        options,
+        params: Vec::new(),
+        widened: 0,
+        loose: 0,
+    };
+    let sql = match printer.print(residual, false) {
+        Sql::Const(true) => "TRUE".to_string(),
+        Sql::Const(false) => "FALSE".to_string(),
+        Sql::Expr(sql) => sql,
+    };
+    Ok(SqlFilter {
+        sql,
+        params: printer.params,
+        exact: printer.widened == 0 && printer.loose == 0,
+        unverified_atoms: printer.widened + printer.loose,
+        data_epoch: store.data_epoch(),
+    })
+}
+
+/// `name` or `table.name`, ASCII word characters only.
+fn is_identifier(column: &str) -> bool {
+    let part = |p: &str| {
+        let mut chars = p.chars();
+        chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+    };
+    match column.split_once('.') {
+        Some((table, name)) => part(table) && part(name),
+        None => part(column),
+    }
+}
+
+enum Sql {
+    Const(bool),
+    Expr(String),
+}
+
+struct Printer<'a> {
+    store: &'a DataStore,
+    options: &'a SqlOptions,
+    params: Vec<serde_json::Value>,
+    /// Atoms replaced by a constant.
+    widened: usize,
+    /// Atoms printed whose SQL meaning is only a superset (LIKE collation).
+    loose: usize,
+}
+
+impl Printer<'_> {
+    fn print(&mut self, residual: &Residual, negated: bool) -> Sql {
+        match residual {
+            Residual::True => Sql::Const(true),
+            Residual::False => Sql::Const(false),
+            Residual::Atom(atom) => self.atom(atom, negated),
+            Residual::And(children) => self.junction(children, negated, " AND ", false),
+            Residual::Or(children) => self.junction(children, negated, " OR ", true),
+            Residual::Not(inner) => match self.print(inner, !negated) {
+                Sql::Const(value) => Sql::Const(!value),
+                Sql::Expr(sql) if sql.starts_with('(') => Sql::Expr(format!("NOT {sql}")),
+                Sql::Expr(sql) => Sql::Expr(format!("NOT ({sql})")),
+            },
+        }
+    }
+
+    /// `AND` (`absorbing = false`) or `OR` (`absorbing = true`).
+    fn junction(
+        &mut self,
+        children: &[Residual],
+        negated: bool,
+        sep: &str,
+        absorbing: bool,
+    ) -> Sql {
+        let mut parts = Vec::new();
+        for child in children {
+            match self.print(child, negated) {
+                Sql::Const(value) if value == absorbing => return Sql::Const(absorbing),
+                Sql::Const(_) => {}
+                Sql::Expr(sql) => parts.push(sql),
+            }
+        }
+        match parts.len() {
+            0 => Sql::Const(!absorbing),
+            1 => Sql::Expr(parts.pop().expect("one part")),
+            _ => Sql::Expr(format!("({})", parts.join(sep))),
+        }
+    }
+
+    fn widen(&mut self, negated: bool) -> Sql {
+        self.widened += 1;
+        Sql::Const(!negated)
+    }
+
+    fn param(&mut self, value: serde_json::Value) -> String {
+        self.params.push(value);
+        match self.options.param_style {
+            ParamStyle::Dollar => format!("${}", self.params.len()),
+            ParamStyle::Question => "?".to_string(),
+        }
+    }
+
+    /// A comparison, two-valued under negation.
+    fn compare(&mut self, column: &str, op: &str, value: serde_json::Value, negated: bool) -> Sql {
+        let placeholder = self.param(value);
+        if negated {
+            Sql::Expr(format!("COALESCE({column} {op} {placeholder}, FALSE)"))
+        } else {
+            Sql::Expr(format!("{column} {op} {placeholder}"))
+        }
+    }
+
+    fn atom(&mut self, atom: &ResidualAtom, negated: bool) -> Sql {
+        match atom {
+            ResidualAtom::IdEquals { id } => {
+                let column = self.options.id_column.clone();
+                self.compare(&column, "=", id.clone().into(), negated)
+            }
+            ResidualAtom::AttrCompare { attr, op, value } => {
+                let Some(column) = self.options.columns.get(attr).cloned() else {
+                    return self.widen(negated);
+                };
+                let sql_op = match op {
+                    NumericOp::Equal => "=",
+                    NumericOp::NotEqual => "<>",
+                    NumericOp::Greater => ">",
+                    NumericOp::GreaterEqual => ">=",
+                    NumericOp::Less => "<",
+                    NumericOp::LessEqual => "<=",
+                };
+                let ordered = !matches!(op, NumericOp::Equal | NumericOp::NotEqual);
+                let value = match value {
+                    ResidualValue::Null => {
+                        return match op {
+                            NumericOp::Equal => Sql::Expr(format!("{column} IS NULL")),
+                            NumericOp::NotEqual => Sql::Expr(format!("{column} IS NOT NULL")),
+                            // Null has no order: false for every row.
+                            _ => Sql::Const(false),
+                        };
+                    }
+                    // Strings and booleans have no order in the engine.
+                    ResidualValue::String(_) | ResidualValue::Bool(_) if ordered => {
+                        return Sql::Const(false)
+                    }
+                    ResidualValue::String(s) => serde_json::Value::from(s.clone()),
+                    ResidualValue::Bool(b) => serde_json::Value::from(*b),
+                    ResidualValue::Int(i) => serde_json::Value::from(*i),
+                    ResidualValue::Float(f) => serde_json::Value::from(*f),
+                };
+                self.compare(&column, sql_op, value, negated)
+            }
+            ResidualAtom::AttrString { attr, op, value } => {
+                // LIKE and LOWER() follow the database's collation, which may
+                // match more than the engine does: safe only where a larger
+                // match set widens, i.e. never under negation.
+                let Some(column) = self.options.columns.get(attr).cloned() else {
+                    return self.widen(negated);
+                };
+                if negated {
+                    return self.widen(negated);
+                }
+                self.loose += 1;
+                let escaped = escape_like(value);
+                let (lhs, sql_op, param) = match op {
+                    StringOp::Contains => (column, "LIKE", format!("%{escaped}%")),
+                    StringOp::StartsWith => (column, "LIKE", format!("{escaped}%")),
+                    StringOp::EndsWith => (column, "LIKE", format!("%{escaped}")),
+                    StringOp::LowerEquals => (format!("LOWER({column})"), "=", value.clone()),
+                    StringOp::UpperEquals => (format!("UPPER({column})"), "=", value.clone()),
+                    StringOp::LowerNotEquals => (format!("LOWER({column})"), "<>", value.clone()),
+                    StringOp::UpperNotEquals => (format!("UPPER({column})"), "<>", value.clone()),
+                };
+                let placeholder = self.param(param.into());
+                let escape = if sql_op == "LIKE" { " ESCAPE '!'" } else { "" };
+                Sql::Expr(format!("{lhs} {sql_op} {placeholder}{escape}"))
+            }
+            ResidualAtom::RelatedTo {
+                subject,
+                relation,
+                kind: RebacKind::Direct,
+                ..
+            } => {
+                let interner = self.store.interner();
+                let (Some(subject), Some(relation)) =
+                    (interner.lookup(subject), interner.lookup(relation))
+                else {
+                    return Sql::Const(false);
+                };
+                let objects = self.store.relationships().related_to(subject, relation);
+                if objects.is_empty() {
+                    return Sql::Const(false);
+                }
+                if objects.len() > self.options.max_in_list {
+                    return self.widen(negated);
+                }
+                let mut ids: Vec<String> = objects
+                    .iter()
+                    .filter_map(|id| interner.resolve_str(*id))
+                    .collect();
+                ids.sort_unstable();
+                let placeholders: Vec<String> =
+                    ids.into_iter().map(|id| self.param(id.into())).collect();
+                Sql::Expr(format!(
+                    "{} IN ({})",
+                    self.options.id_column,
+                    placeholders.join(", ")
+                ))
+            }
+            ResidualAtom::AttrContains { .. }
+            | ResidualAtom::RelatedTo { .. }
+            | ResidualAtom::Opaque { .. } => self.widen(negated),
+        }
+    }
+}
+
+/// Escape `LIKE` wildcards with `!` (the one escape character every dialect
+/// reads the same way inside a string literal).
+fn escape_like(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '!' | '%' | '_') {
+            out.push('!');
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn options() -> SqlOptions {
+        SqlOptions {
+            id_column: "id".to_string(),
+            columns: HashMap::from([
+                ("owner".to_string(), "owner_id".to_string()),
+                ("level".to_string(), "docs.level".to_string()),
+                ("title".to_string(), "title".to_string()),
+            ]),
+            param_style: ParamStyle::Dollar,
+            max_in_list: 2,
+        }
+    }
+
+    fn cmp(attr: &str, op: NumericOp, value: ResidualValue) -> Residual {
+        Residual::atom(ResidualAtom::AttrCompare {
+            attr: attr.to_string(),
+            op,
+            value,
+        })
+    }
+
+    #[test]
+    fn prints_parameterized_and_exact() {
+        let store = DataStore::new();
+        let residual = Residual::and([
+            cmp(
+                "owner",
+                NumericOp::Equal,
+                ResidualValue::String("alice".into()),
+            ),
+            Residual::or([
+                cmp("level", NumericOp::LessEqual, ResidualValue::Int(3)),
+                Residual::atom(ResidualAtom::IdEquals { id: "doc-1".into() }),
+            ]),
+        ]);
+        let filter = to_sql(&residual, &store, &options()).unwrap();
+        assert_eq!(
+            filter.sql,
+            "(owner_id = $1 AND (docs.level <= $2 OR id = $3))"
+        );
+        assert_eq!(
+            filter.params,
+            vec![json!("alice"), json!(3), json!("doc-1")]
+        );
+        assert!(filter.exact);
+        assert_eq!(filter.unverified_atoms, 0);
+    }
+
+    #[test]
+    fn widens_toward_a_superset_by_negation_parity() {
+        let store = DataStore::new();
+        let unmapped = cmp(
+            "region",
+            NumericOp::Equal,
+            ResidualValue::String("eu".into()),
+        );
+        let owner = cmp(
+            "owner",
+            NumericOp::Equal,
+            ResidualValue::String("bob".into()),
+        );
+
+        // Positive: the unmapped conjunct becomes TRUE.
+        let filter = to_sql(
+            &Residual::and([owner.clone(), unmapped.clone()]),
+            &store,
+            &options(),
+        )
+        .unwrap();
+        assert_eq!(filter.sql, "owner_id = $1");
+        assert!(!filter.exact);
+        assert_eq!(filter.unverified_atoms, 1);
+
+        // Negated: it becomes FALSE inside the NOT, so the NOT keeps every
+        // row the unmapped atom might have excluded.
+        let filter = to_sql(
+            &Residual::not(Residual::or([owner, unmapped])),
+            &store,
+            &options(),
+        )
+        .unwrap();
+        assert_eq!(filter.sql, "NOT (COALESCE(owner_id = $1, FALSE))");
+        assert!(!filter.exact);
+
+        let filter = to_sql(&Residual::opaque("x"), &store, &options()).unwrap();
+        assert_eq!(filter.sql, "TRUE");
+        let filter = to_sql(&Residual::not(Residual::opaque("x")), &store, &options()).unwrap();
+        assert_eq!(filter.sql, "TRUE");
+    }
+
+    #[test]
+    fn string_ops_escape_and_never_print_under_negation() {
+        let store = DataStore::new();
+        let starts = Residual::atom(ResidualAtom::AttrString {
+            attr: "title".into(),
+            op: StringOp::StartsWith,
+            value: "50%_off!".into(),
+        });
+        let mut opts = options();
+        opts.param_style = ParamStyle::Question;
+        let filter = to_sql(&starts, &store, &opts).unwrap();
+        assert_eq!(filter.sql, "title LIKE ? ESCAPE '!'");
+        assert_eq!(filter.params, vec![json!("50!%!_off!!%")]);
+        assert!(!filter.exact);
+        let filter = to_sql(&Residual::not(starts), &store, &opts).unwrap();
+        assert_eq!(filter.sql, "TRUE");
+    }
+
+    #[test]
+    fn related_to_emits_an_in_list_under_the_cap() {
+        let store = DataStore::new();
+        let interner = store.interner();
+        let (alice, viewer) = (interner.intern("alice"), interner.intern("viewer"));
+        store.add_relationship(interner.intern("doc-2"), viewer, alice);
+        store.add_relationship(interner.intern("doc-1"), viewer, alice);
+        let related = |subject: &str| {
+            Residual::atom(ResidualAtom::RelatedTo {
+                subject: subject.into(),
+                relation: "viewer".into(),
+                kind: RebacKind::Direct,
+                via: None,
+                max_depth: 0,
+            })
+        };
+        let filter = to_sql(&related("alice"), &store, &options()).unwrap();
+        assert_eq!(filter.sql, "id IN ($1, $2)");
+        assert_eq!(filter.params, vec![json!("doc-1"), json!("doc-2")]);
+        assert!(filter.exact);
+
+        assert_eq!(
+            to_sql(&related("carol"), &store, &options()).unwrap().sql,
+            "FALSE"
+        );
+
+        store.add_relationship(interner.intern("doc-3"), viewer, alice);
+        let filter = to_sql(&related("alice"), &store, &options()).unwrap();
+        assert_eq!(filter.sql, "TRUE");
+        assert!(!filter.exact);
+    }
+
+    #[test]
+    fn rejects_non_identifier_columns() {
+        let store = DataStore::new();
+        let mut opts = options();
+        opts.columns
+            .insert("owner".to_string(), "owner_id; DROP TABLE docs".to_string());
+        assert_eq!(
+            to_sql(&Residual::True, &store, &opts).unwrap_err().code(),
+            "invalid_column"
+        );
+    }
+}
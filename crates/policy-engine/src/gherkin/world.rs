@@ -91,7 +91,7 @@ impl TestContext {
         let resource = self.resource.as_ref().ok_or("No resource set")?;
 
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), principal.clone());
+        context.insert("principal".to_string(), principal.clone().into());
 
         let request = PolicyRequest {
             resource: resource.clone(),
@@ -11,7 +11,9 @@ pub mod decision_matrix;
 mod engine;
 mod evaluators;
 pub mod fast_parse;
+pub mod filter;
 pub mod gherkin;
+pub mod net;
 pub mod optimizer;
 pub mod partial_evaluation;
 pub mod policy_compilation;
@@ -20,10 +22,11 @@ pub mod regex_cache;
 
 pub use engine::PolicyVersion as EngineVersion;
 pub use engine::{
-    AllPoliciesEvaluationResult, DenyInfo, EnhancedPolicy, PackageEvaluationResult, PackageInfo,
-    PolicyAction, PolicyDecision, PolicyEngine, PolicyEngineStats, PolicyLanguage, PolicyRequest,
-    PolicyRule, PolicySource, PolicySourceMetadata, PruningIndexStats, SetEvalOutcome,
-    SimpleAction, SimpleRule, StagedPackage, TrustLevel,
+    AllPoliciesEvaluationResult, DecisionObligations, DenyInfo, EnhancedPolicy,
+    PackageEvaluationResult, PackageInfo, PolicyAction, PolicyDecision, PolicyEngine,
+    PolicyEngineStats, PolicyLanguage, PolicyRequest, PolicyRule, PolicySource,
+    PolicySourceMetadata, PruningIndexStats, SetEvalOutcome, SimpleAction, SimpleRule,
+    StagedPackage, TrustLevel,
 };
 
 #[cfg(feature = "cedar")]
@@ -96,7 +99,7 @@ pub use decision_buffer::{
 };
 #[cfg(feature = "audit-buffer")]
 pub use decision_export::ExportFormat;
-pub use decision_log::{DecisionLogConfig, DecisionLogEntry, PrivacyProfile};
+pub use decision_log::{DecisionLogConfig, DecisionLogEntry, PrivacyProfile, ShadowOutcome};
 #[cfg(feature = "decision-privacy")]
 pub use decision_privacy::{
     decrypt_input_data, generate_encryption_key_hex, pseudonymize, pseudonymize_domain,
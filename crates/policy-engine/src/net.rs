@@ -0,0 +1,316 @@
+//! IP address and CIDR matching shared by both `.reap` evaluators.
+//!
+//! The `net::` builtins (`cidr_contains`, `cidr_overlaps`, `ip_in_any`,
+//! `is_ip`/`is_ipv4`/`is_ipv6`/`is_cidr`) evaluate on the AST interpreter
+//! AND the compiled path; both call into this module so the two can never
+//! disagree on what an address or a range is.
+//!
+//! Semantics (pinned by the builtin differential oracle against `std::net`):
+//! - Addresses are IPv4 dotted-quad or IPv6 text as accepted by
+//!   [`std::net::IpAddr`]. Zone ids (`fe80::1%eth0`), surrounding whitespace
+//!   and ports are rejected — a malformed address never matches.
+//! - A range is `addr/prefix` or a bare address (a single-host range).
+//!   Host bits below the prefix are ignored (`10.1.2.3/8` ≡ `10.0.0.0/8`),
+//!   matching OPA's `net.cidr_contains`.
+//! - IPv4-mapped IPv6 (`::ffff:10.0.0.1`, `::ffff:0:0/96` and narrower) is
+//!   normalized to IPv4, so a dual-stack listener's view of a v4 client
+//!   matches v4 ranges. Otherwise the families never match each other.
+//!
+//! Everything here is total: parse failures surface as `None`, and the
+//! evaluators turn them into a non-match (fail closed), never an error.
+
+use std::net::IpAddr;
+
+/// Address family of a parsed address or range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IpFamily {
+    V4,
+    V6,
+}
+
+impl IpFamily {
+    #[inline]
+    fn bits(self) -> u8 {
+        match self {
+            IpFamily::V4 => 32,
+            IpFamily::V6 => 128,
+        }
+    }
+}
+
+/// A parsed CIDR range (or a single address as a full-length prefix). The
+/// address is stored pre-masked, so containment is one mask + compare.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpNet {
+    family: IpFamily,
+    /// Network address, host bits cleared. IPv4 lives in the low 32 bits.
+    addr: u128,
+    prefix: u8,
+}
+
+impl IpNet {
+    /// Parse `addr/prefix` or a bare address (single host).
+    pub fn parse(s: &str) -> Option<Self> {
+        let (addr_part, prefix_part) = match s.split_once('/') {
+            Some((a, p)) => (a, Some(p)),
+            None => (s, None),
+        };
+        let ip: IpAddr = addr_part.parse().ok()?;
+        let (family, raw, mapped_v4) = split_ip(ip);
+        let declared_bits = match ip {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        let prefix = match prefix_part {
+            None => declared_bits,
+            Some(p) => {
+                // Digits only: "+8", " 8", "08" with a sign etc. are malformed.
+                if p.is_empty() || p.len() > 3 || !p.bytes().all(|b| b.is_ascii_digit()) {
+                    return None;
+                }
+                let n: u8 = p.parse().ok()?;
+                if n > declared_bits {
+                    return None;
+                }
+                n
+            }
+        };
+        let prefix = if mapped_v4 {
+            // ::ffff:a.b.c.d/N with N >= 96 is the v4 range a.b.c.d/(N-96);
+            // anything wider spans non-mapped v6 space and stays v6.
+            if prefix < 96 {
+                return Some(Self::new(IpFamily::V6, ipv6_bits(ip), prefix));
+            }
+            prefix - 96
+        } else {
+            prefix
+        };
+        Some(Self::new(family, raw, prefix))
+    }
+
+    fn new(family: IpFamily, addr: u128, prefix: u8) -> Self {
+        Self {
+            family,
+            addr: addr & mask(family, prefix),
+            prefix,
+        }
+    }
+
+    /// Address family of the range.
+    pub fn family(&self) -> IpFamily {
+        self.family
+    }
+
+    /// Prefix length in bits (after IPv4-mapped normalization).
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix
+    }
+
+    /// True if `other` (an address or a range) lies entirely inside `self`.
+    #[inline]
+    pub fn contains(&self, other: &IpNet) -> bool {
+        self.family == other.family
+            && self.prefix <= other.prefix
+            && (other.addr & mask(self.family, self.prefix)) == self.addr
+    }
+
+    /// True if the two ranges share at least one address.
+    #[inline]
+    pub fn overlaps(&self, other: &IpNet) -> bool {
+        if self.family != other.family {
+            return false;
+        }
+        let m = mask(self.family, self.prefix.min(other.prefix));
+        (self.addr & m) == (other.addr & m)
+    }
+}
+
+/// Split an address into (family, bits, was-IPv4-mapped), normalizing
+/// `::ffff:a.b.c.d` to IPv4.
+fn split_ip(ip: IpAddr) -> (IpFamily, u128, bool) {
+    match ip {
+        IpAddr::V4(v4) => (IpFamily::V4, u32::from(v4) as u128, false),
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => (IpFamily::V4, u32::from(v4) as u128, true),
+            None => (IpFamily::V6, u128::from(v6), false),
+        },
+    }
+}
+
+fn ipv6_bits(ip: IpAddr) -> u128 {
+    match ip {
+        IpAddr::V4(v4) => u128::from(v4.to_ipv6_mapped()),
+        IpAddr::V6(v6) => u128::from(v6),
+    }
+}
+
+#[inline]
+fn mask(family: IpFamily, prefix: u8) -> u128 {
+    let bits = u32::from(family.bits());
+    let prefix = u32::from(prefix);
+    if prefix == 0 {
+        return 0;
+    }
+    let ones = u128::MAX >> (128 - bits);
+    let host = if prefix >= bits {
+        0
+    } else {
+        (1u128 << (bits - prefix)) - 1
+    };
+    ones & !host
+}
+
+/// Parse a single address (no prefix). `None` if `s` is not an address.
+pub fn parse_ip(s: &str) -> Option<IpNet> {
+    if s.contains('/') {
+        return None;
+    }
+    IpNet::parse(s)
+}
+
+/// Parse an explicit CIDR (`addr/prefix`). Bare addresses are rejected here —
+/// use [`IpNet::parse`] where a single host is acceptable.
+pub fn parse_cidr(s: &str) -> Option<IpNet> {
+    if !s.contains('/') {
+        return None;
+    }
+    IpNet::parse(s)
+}
+
+/// `net::cidr_contains(cidr, addr_or_cidr)`.
+pub fn cidr_contains(cidr: &str, addr: &str) -> bool {
+    match (IpNet::parse(cidr), IpNet::parse(addr)) {
+        (Some(net), Some(inner)) => net.contains(&inner),
+        _ => false,
+    }
+}
+
+/// `net::cidr_overlaps(a, b)`.
+pub fn cidr_overlaps(a: &str, b: &str) -> bool {
+    match (IpNet::parse(a), IpNet::parse(b)) {
+        (Some(a), Some(b)) => a.overlaps(&b),
+        _ => false,
+    }
+}
+
+/// True if `addr` is inside any of the pre-parsed `ranges`.
+#[inline]
+pub fn contained_in_any(ranges: &[IpNet], addr: &str) -> bool {
+    match IpNet::parse(addr) {
+        Some(inner) => ranges.iter().any(|r| r.contains(&inner)),
+        None => false,
+    }
+}
+
+/// True if `cidr` overlaps any of the pre-parsed `ranges`.
+#[inline]
+pub fn overlaps_any(ranges: &[IpNet], cidr: &str) -> bool {
+    match IpNet::parse(cidr) {
+        Some(other) => ranges.iter().any(|r| r.overlaps(&other)),
+        None => false,
+    }
+}
+
+/// `net::is_ip(s)` / `net::is_ipv4(s)` / `net::is_ipv6(s)`: `family = None`
+/// accepts either family. IPv4-mapped IPv6 text counts as IPv4.
+pub fn is_ip(s: &str, family: Option<IpFamily>) -> bool {
+    match parse_ip(s) {
+        Some(ip) => family.is_none_or(|f| ip.family == f),
+        None => false,
+    }
+}
+
+/// `net::is_cidr(s)`: an explicit `addr/prefix` range.
+pub fn is_cidr(s: &str) -> bool {
+    parse_cidr(s).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ipv4_containment() {
+        assert!(cidr_contains("10.0.0.0/8", "10.255.1.2"));
+        assert!(!cidr_contains("10.0.0.0/8", "11.0.0.1"));
+        assert!(cidr_contains("192.168.1.0/24", "192.168.1.255"));
+        assert!(!cidr_contains("192.168.1.0/24", "192.168.2.0"));
+        // Not on an octet boundary — what string-prefix hacks get wrong.
+        assert!(cidr_contains("172.16.0.0/12", "172.31.255.255"));
+        assert!(!cidr_contains("172.16.0.0/12", "172.32.0.0"));
+        assert!(cidr_contains("0.0.0.0/0", "8.8.8.8"));
+        assert!(cidr_contains("1.2.3.4/32", "1.2.3.4"));
+        assert!(!cidr_contains("1.2.3.4/32", "1.2.3.5"));
+    }
+
+    #[test]
+    fn host_bits_are_ignored_and_bare_address_is_single_host() {
+        assert!(cidr_contains("10.1.2.3/8", "10.9.9.9"));
+        assert!(cidr_contains("10.1.2.3", "10.1.2.3"));
+        assert!(!cidr_contains("10.1.2.3", "10.1.2.4"));
+    }
+
+    #[test]
+    fn subnet_containment_and_overlap() {
+        assert!(cidr_contains("10.0.0.0/8", "10.1.0.0/16"));
+        assert!(!cidr_contains("10.1.0.0/16", "10.0.0.0/8"));
+        assert!(cidr_overlaps("10.1.0.0/16", "10.0.0.0/8"));
+        assert!(cidr_overlaps("10.0.0.0/8", "10.1.0.0/16"));
+        assert!(!cidr_overlaps("10.0.0.0/16", "10.1.0.0/16"));
+    }
+
+    #[test]
+    fn ipv6_and_mapped_addresses() {
+        assert!(cidr_contains("2001:db8::/32", "2001:db8:ffff::1"));
+        assert!(!cidr_contains("2001:db8::/32", "2001:db9::1"));
+        assert!(cidr_contains("::/0", "::1"));
+        assert!(cidr_contains("10.0.0.0/8", "::ffff:10.2.3.4"));
+        assert!(cidr_contains("::ffff:10.0.0.0/104", "10.2.3.4"));
+        // Families never cross (outside the mapped range).
+        assert!(!cidr_contains("0.0.0.0/0", "2001:db8::1"));
+        assert!(!cidr_contains("::/0", "10.0.0.1"));
+    }
+
+    #[test]
+    fn malformed_input_never_matches() {
+        for bad in [
+            "",
+            "10.0.0",
+            "10.0.0.256",
+            "10.0.0.0/33",
+            "10.0.0.0/",
+            "10.0.0.0/x",
+            "10.0.0.0/+8",
+            " 10.0.0.1",
+            "fe80::1%eth0",
+            "2001:db8::/129",
+            "10.0.0.1:80",
+        ] {
+            assert!(!cidr_contains("0.0.0.0/0", bad), "{bad:?} must not match");
+            assert!(
+                !cidr_contains(bad, "10.0.0.1"),
+                "{bad:?} as a range must not match"
+            );
+        }
+    }
+
+    #[test]
+    fn type_predicates() {
+        assert!(is_ip("10.0.0.1", None));
+        assert!(is_ip("10.0.0.1", Some(IpFamily::V4)));
+        assert!(!is_ip("10.0.0.1", Some(IpFamily::V6)));
+        assert!(is_ip("::1", Some(IpFamily::V6)));
+        assert!(!is_ip("10.0.0.0/8", None));
+        assert!(is_cidr("10.0.0.0/8"));
+        assert!(!is_cidr("10.0.0.1"));
+    }
+
+    #[test]
+    fn mask_edges() {
+        assert_eq!(mask(IpFamily::V4, 0), 0);
+        assert_eq!(mask(IpFamily::V4, 32), 0xffff_ffff);
+        assert_eq!(mask(IpFamily::V4, 8), 0xff00_0000);
+        assert_eq!(mask(IpFamily::V6, 128), u128::MAX);
+        assert_eq!(mask(IpFamily::V6, 1), 1u128 << 127);
+    }
+}
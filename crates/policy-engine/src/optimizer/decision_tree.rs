@@ -120,6 +120,7 @@ impl DecisionTree {
             evaluation_time_ns,
             matched_rule,
             matched_rule_name: None,
+            obligations: None,
         })
     }
 
@@ -183,13 +184,18 @@ impl DecisionTree {
         match attribute {
             "action" => Some(request.action.clone()),
             "resource" => Some(request.resource.clone()),
-            "principal" => request.context.get("principal").cloned(),
+            "principal" => request.context_str("principal").map(str::to_string),
             _ => {
                 // Check context
-                context
-                    .get(attribute)
-                    .cloned()
-                    .or_else(|| request.context.get(attribute).cloned())
+                context.get(attribute).cloned().or_else(|| {
+                    // Tree conditions are string-valued: scalars compare
+                    // by their text, as they did before context was typed.
+                    request
+                        .context
+                        .get(attribute)
+                        .filter(|v| !v.is_null())
+                        .map(|v| v.as_str().map_or_else(|| v.to_string(), str::to_string))
+                })
             }
         }
     }
@@ -0,0 +1,760 @@
+//! Source-level analysis of `.reap` text for editor tooling (`reaper-lsp`).
+//!
+//! The parser proper lowers straight to a span-free AST. This module re-walks
+//! the pest tree to keep byte spans for the things an editor points at —
+//! rule, `func` and import declarations, builtin calls, method calls and
+//! entity attribute paths — and layers the engine's own checks on top:
+//!
+//! - [`check_source`] runs the same parse, `func`/import validation and
+//!   import resolution a file load runs, reporting each failure with a span
+//!   (pest errors carry one; semantic errors are anchored on the identifier
+//!   they name), plus calls to builtins that do not exist.
+//! - [`rule_compile_modes`] compiles each rule alone, exactly as
+//!   `build_preferred`'s per-rule split does, and reports which rules will be
+//!   served by the AST interpreter and why.
+//! - [`BUILTIN_FUNCTIONS`] / [`BUILTIN_METHODS`] document every builtin the
+//!   interpreter dispatches.
+//!
+//! Nothing here evaluates a policy or touches a live data store.
+
+use super::ast::{MethodName, Policy};
+use super::parser::{ReapParser, Rule};
+use super::{compiler, functions, limits, resolve_imports, ReaperPolicy};
+use crate::data::DataStore;
+use pest::iterators::Pair;
+use pest::Parser as PestParser;
+use reaper_core::ReaperError;
+use std::path::Path;
+use std::sync::Arc;
+
+/// A byte range into the analyzed source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceSpan {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl SourceSpan {
+    fn of(pair: &Pair<'_, Rule>) -> Self {
+        let span = pair.as_span();
+        Self {
+            start: span.start(),
+            end: span.end(),
+        }
+    }
+
+    /// Whether `offset` falls inside the span (end inclusive, so a cursor
+    /// just past an identifier still hits it).
+    pub fn contains(&self, offset: usize) -> bool {
+        self.start <= offset && offset <= self.end
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+}
+
+/// One problem found in the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceDiagnostic {
+    pub span: SourceSpan,
+    pub severity: DiagnosticSeverity,
+    pub message: String,
+}
+
+impl SourceDiagnostic {
+    fn error(span: SourceSpan, message: impl Into<String>) -> Self {
+        Self {
+            span,
+            severity: DiagnosticSeverity::Error,
+            message: message.into(),
+        }
+    }
+}
+
+/// A declaration: the policy/library itself, a rule, or a `func`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Declaration {
+    pub name: String,
+    /// The declared identifier.
+    pub name_span: SourceSpan,
+    /// The whole declaration.
+    pub span: SourceSpan,
+}
+
+/// `import "path" as alias`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportRef {
+    pub path: String,
+    pub alias: String,
+    /// The quoted path, quotes included.
+    pub path_span: SourceSpan,
+    pub alias_span: SourceSpan,
+}
+
+/// A function call: `ns::name(...)` or `name(...)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallRef {
+    pub namespace: Option<String>,
+    pub function: String,
+    pub namespace_span: Option<SourceSpan>,
+    pub function_span: SourceSpan,
+}
+
+/// A method call's name: the `lower` in `user.name.lower()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MethodRef {
+    pub name: String,
+    pub span: SourceSpan,
+}
+
+/// An entity attribute path: `user.profile.tier` is entity `user`, path
+/// `["profile", "tier"]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttributeRef {
+    pub entity: String,
+    pub path: Vec<String>,
+    pub span: SourceSpan,
+}
+
+/// Everything [`outline`] locates in a syntactically valid source.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceOutline {
+    /// `library name { ... }` rather than `policy name { ... }`.
+    pub is_library: bool,
+    pub name: Option<Declaration>,
+    pub imports: Vec<ImportRef>,
+    pub rules: Vec<Declaration>,
+    pub functions: Vec<Declaration>,
+    pub calls: Vec<CallRef>,
+    pub methods: Vec<MethodRef>,
+    pub attributes: Vec<AttributeRef>,
+}
+
+/// How a rule will be served by `build_preferred`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleCompileMode {
+    pub rule: String,
+    /// `None` = compiled; `Some(reason)` = the compiler rejected the rule and
+    /// it falls back to the AST interpreter.
+    pub fallback: Option<String>,
+}
+
+/// Whether the source is a library file (`library name { ... }`).
+pub(super) fn is_library_source(source: &str) -> bool {
+    let mut rest = source.trim_start();
+    // Skip leading comments the grammar would also skip.
+    loop {
+        if let Some(after) = rest.strip_prefix("//") {
+            rest = after.split_once('\n').map_or("", |(_, r)| r).trim_start();
+        } else if let Some(after) = rest.strip_prefix("/*") {
+            rest = after.split_once("*/").map_or("", |(_, r)| r).trim_start();
+        } else {
+            break;
+        }
+    }
+    rest.starts_with("library")
+        && !rest["library".len()..].starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Parse `source` and locate its declarations and references. A syntax error
+/// comes back as the diagnostic [`check_source`] would report first.
+pub fn outline(source: &str) -> Result<SourceOutline, SourceDiagnostic> {
+    let whole = SourceSpan {
+        start: 0,
+        end: source.len(),
+    };
+    limits::enforce_source_nesting(source)
+        .map_err(|e| SourceDiagnostic::error(whole, reason(&e)))?;
+
+    let is_library = is_library_source(source);
+    let top = if is_library {
+        Rule::library
+    } else {
+        Rule::policy
+    };
+    let pairs = <ReapParser as PestParser<Rule>>::parse(top, source).map_err(|e| {
+        let span = match e.location {
+            pest::error::InputLocation::Pos(p) => SourceSpan { start: p, end: p },
+            pest::error::InputLocation::Span((start, end)) => SourceSpan { start, end },
+        };
+        SourceDiagnostic::error(span, syntax_message(&e))
+    })?;
+
+    let mut out = SourceOutline {
+        is_library,
+        ..Default::default()
+    };
+    for pair in pairs {
+        walk(pair, &mut out);
+    }
+    Ok(out)
+}
+
+/// The pest error without its rendered source excerpt (the editor shows the
+/// location itself).
+fn syntax_message(e: &pest::error::Error<Rule>) -> String {
+    let e = e.clone().renamed_rules(|r| match r {
+        Rule::EOI => "end of input".to_string(),
+        other => format!("{other:?}"),
+    });
+    format!("syntax error: {}", e.variant.message())
+}
+
+fn walk(pair: Pair<'_, Rule>, out: &mut SourceOutline) {
+    match pair.as_rule() {
+        Rule::policy | Rule::library => {
+            let span = SourceSpan::of(&pair);
+            for inner in pair.into_inner() {
+                if inner.as_rule() == Rule::ident && out.name.is_none() {
+                    out.name = Some(Declaration {
+                        name: inner.as_str().to_string(),
+                        name_span: SourceSpan::of(&inner),
+                        span,
+                    });
+                } else {
+                    walk(inner, out);
+                }
+            }
+            return;
+        }
+        Rule::import_stmt => {
+            let mut inner = pair.into_inner();
+            if let (Some(path), Some(alias)) = (inner.next(), inner.next()) {
+                out.imports.push(ImportRef {
+                    path: path
+                        .as_str()
+                        .trim_start_matches('r')
+                        .trim_matches('"')
+                        .to_string(),
+                    alias: alias.as_str().to_string(),
+                    path_span: SourceSpan::of(&path),
+                    alias_span: SourceSpan::of(&alias),
+                });
+            }
+            return;
+        }
+        Rule::rule | Rule::func_def => {
+            let span = SourceSpan::of(&pair);
+            let is_rule = pair.as_rule() == Rule::rule;
+            let mut inner = pair.into_inner();
+            if let Some(name) = inner.next() {
+                let decl = Declaration {
+                    name: name.as_str().to_string(),
+                    name_span: SourceSpan::of(&name),
+                    span,
+                };
+                if is_rule {
+                    out.rules.push(decl);
+                } else {
+                    out.functions.push(decl);
+                }
+            }
+            for rest in inner {
+                walk(rest, out);
+            }
+            return;
+        }
+        Rule::comp_function_call => {
+            let idents: Vec<Pair<'_, Rule>> = pair
+                .clone()
+                .into_inner()
+                .take_while(|p| p.as_rule() == Rule::ident)
+                .collect();
+            match idents.as_slice() {
+                [ns, function] => out.calls.push(CallRef {
+                    namespace: Some(ns.as_str().to_string()),
+                    function: function.as_str().to_string(),
+                    namespace_span: Some(SourceSpan::of(ns)),
+                    function_span: SourceSpan::of(function),
+                }),
+                [function] => out.calls.push(CallRef {
+                    namespace: None,
+                    function: function.as_str().to_string(),
+                    namespace_span: None,
+                    function_span: SourceSpan::of(function),
+                }),
+                _ => {}
+            }
+        }
+        Rule::comp_single_method_call => {
+            if let Some(name) = pair.clone().into_inner().next() {
+                out.methods.push(MethodRef {
+                    name: name.as_str().to_string(),
+                    span: SourceSpan::of(&name),
+                });
+            }
+        }
+        Rule::entity_attr | Rule::entity_method_call => {
+            let mut inner = pair.clone().into_inner();
+            if let Some(entity) = inner.next() {
+                let path: Vec<String> = inner
+                    .take_while(|p| p.as_rule() == Rule::ident)
+                    .map(|p| p.as_str().to_string())
+                    .collect();
+                if !path.is_empty() {
+                    out.attributes.push(AttributeRef {
+                        entity: entity.as_str().to_string(),
+                        path,
+                        span: SourceSpan::of(&pair),
+                    });
+                }
+            }
+        }
+        // Inside call arguments and comprehensions `user.name` is a plain
+        // `ident.ident` access rather than an `entity_attr`.
+        Rule::comp_dot_access | Rule::comp_dot_access_with_methods => {
+            let mut inner = pair.clone().into_inner();
+            if let (Some(entity), Some(attr)) = (inner.next(), inner.next()) {
+                if matches!(
+                    entity.as_str(),
+                    "user" | "actor" | "resource" | "context" | "input"
+                ) && attr.as_rule() == Rule::ident
+                {
+                    out.attributes.push(AttributeRef {
+                        entity: entity.as_str().to_string(),
+                        path: vec![attr.as_str().to_string()],
+                        span: SourceSpan::of(&pair),
+                    });
+                }
+            }
+        }
+        _ => {}
+    }
+    for inner in pair.into_inner() {
+        walk(inner, out);
+    }
+}
+
+/// Everything wrong with `source`, as a file load would see it. `base_dir`
+/// is the directory imports resolve against; without one, imports are
+/// reported the way a string-parsed policy reports them.
+pub fn check_source(source: &str, base_dir: Option<&Path>) -> Vec<SourceDiagnostic> {
+    let outline = match outline(source) {
+        Ok(outline) => outline,
+        Err(diagnostic) => return vec![diagnostic],
+    };
+
+    let mut diagnostics = unknown_builtin_calls(&outline);
+    let loaded = if outline.is_library {
+        ReapParser::parse_library(source).and_then(|(_, funcs)| {
+            // A library's functions are validated as a policy would see them
+            // once imported: names, params, DAG and depth.
+            let policy = Policy {
+                name: String::new(),
+                metadata: Default::default(),
+                default_decision: super::ast::Decision::Deny,
+                rules: Vec::new(),
+                functions: funcs,
+                imports: Vec::new(),
+            };
+            functions::validate_policy_functions(&policy, limits::configured_max_nesting_depth())
+        })
+    } else {
+        load(source, base_dir).map(|_| ())
+    };
+    if let Err(e) = loaded {
+        let message = reason(&e);
+        let span = anchor(&message, source, &outline);
+        // A call already flagged above is the same failure, reported twice.
+        if !diagnostics.iter().any(|d| d.span.contains(span.start)) {
+            diagnostics.push(SourceDiagnostic::error(span, message));
+        }
+    }
+    diagnostics
+}
+
+/// Parse and, when a base directory is known, resolve imports — the AST a
+/// file load would produce.
+fn load(source: &str, base_dir: Option<&Path>) -> Result<Policy, ReaperError> {
+    let policy = match base_dir {
+        Some(dir) => {
+            let mut policy = ReaperPolicy::parse_source(source)?;
+            if !policy.ast.imports.is_empty() {
+                resolve_imports(&mut policy.ast, dir)?;
+            }
+            policy
+        }
+        None => source.parse::<ReaperPolicy>()?,
+    };
+    Ok(policy.ast)
+}
+
+/// Which rules will be compiled and which fall back to the interpreter.
+/// Mirrors `build_preferred`: a policy that compiles whole has no fallbacks;
+/// otherwise each rule is compiled alone, with the full function set.
+pub fn rule_compile_modes(
+    source: &str,
+    base_dir: Option<&Path>,
+) -> Result<Vec<RuleCompileMode>, ReaperError> {
+    let policy = load(source, base_dir)?;
+    let store = Arc::new(DataStore::new());
+    if compiler::compile_policy(policy.clone(), store.clone()).is_ok() {
+        return Ok(policy
+            .rules
+            .iter()
+            .map(|rule| RuleCompileMode {
+                rule: rule.name.clone(),
+                fallback: None,
+            })
+            .collect());
+    }
+    Ok(policy
+        .rules
+        .iter()
+        .map(|rule| {
+            let single = Policy {
+                rules: vec![rule.clone()],
+                ..policy.clone()
+            };
+            RuleCompileMode {
+                rule: rule.name.clone(),
+                fallback: compiler::compile_policy(single, store.clone())
+                    .err()
+                    .map(|e| reason(&e)),
+            }
+        })
+        .collect())
+}
+
+fn reason(e: &ReaperError) -> String {
+    match e {
+        ReaperError::InvalidPolicy { reason } => reason.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Calls into a builtin namespace (or to an un-namespaced builtin-looking
+/// name) that the interpreter would reject at evaluation time.
+fn unknown_builtin_calls(outline: &SourceOutline) -> Vec<SourceDiagnostic> {
+    let aliases: Vec<&str> = outline.imports.iter().map(|i| i.alias.as_str()).collect();
+    let locals: Vec<&str> = outline.functions.iter().map(|f| f.name.as_str()).collect();
+    outline
+        .calls
+        .iter()
+        .filter(|call| match call.namespace.as_deref() {
+            Some(ns) if functions::BUILTIN_NAMESPACES.contains(&ns) => {
+                builtin_function(Some(ns), &call.function).is_none()
+            }
+            Some(ns) => !aliases.contains(&ns),
+            None => {
+                builtin_function(None, &call.function).is_none()
+                    && !locals.contains(&call.function.as_str())
+            }
+        })
+        .map(|call| {
+            let span = match call.namespace_span {
+                Some(ns) => SourceSpan {
+                    start: ns.start,
+                    end: call.function_span.end,
+                },
+                None => call.function_span,
+            };
+            let message = match call.namespace.as_deref() {
+                Some(ns) if functions::BUILTIN_NAMESPACES.contains(&ns) => {
+                    format!("unknown builtin function '{ns}::{}'", call.function)
+                }
+                Some(ns) => format!("'{ns}' is neither a builtin namespace nor an import alias"),
+                None => format!("unknown function '{}'", call.function),
+            };
+            SourceDiagnostic::error(span, message)
+        })
+        .collect()
+}
+
+/// Anchor a span-less engine error on the first identifier it quotes that
+/// appears in the source, else on the policy name.
+fn anchor(message: &str, source: &str, outline: &SourceOutline) -> SourceSpan {
+    let fallback = outline
+        .name
+        .as_ref()
+        .map_or(SourceSpan { start: 0, end: 0 }, |decl| decl.name_span);
+    for quote in ['\'', '"'] {
+        for quoted in message.split(quote).skip(1).step_by(2) {
+            let needle = quoted.rsplit("::").next().unwrap_or(quoted);
+            if needle.is_empty() {
+                continue;
+            }
+            if let Some(start) = find_word(source, needle) {
+                return SourceSpan {
+                    start,
+                    end: start + needle.len(),
+                };
+            }
+        }
+    }
+    fallback
+}
+
+/// First occurrence of `word` not embedded in a longer identifier.
+fn find_word(source: &str, word: &str) -> Option<usize> {
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    source.match_indices(word).map(|(i, _)| i).find(|&i| {
+        let before = source[..i].chars().next_back();
+        let after = source[i + word.len()..].chars().next();
+        !before.is_some_and(is_ident) && !after.is_some_and(is_ident)
+    })
+}
+
+/// Reference entry for one builtin function.
+#[derive(Debug, Clone, Copy)]
+pub struct BuiltinFunction {
+    /// `None` for globals (`concat`, `is_string`, ...).
+    pub namespace: Option<&'static str>,
+    pub name: &'static str,
+    pub signature: &'static str,
+    pub doc: &'static str,
+}
+
+const fn f(
+    namespace: Option<&'static str>,
+    name: &'static str,
+    signature: &'static str,
+    doc: &'static str,
+) -> BuiltinFunction {
+    BuiltinFunction {
+        namespace,
+        name,
+        signature,
+        doc,
+    }
+}
+
+/// Every builtin function the interpreter dispatches.
+pub const BUILTIN_FUNCTIONS: &[BuiltinFunction] = &[
+    f(None, "concat", "concat(a, b, ...) -> string", "Concatenates strings; list arguments are flattened."),
+    f(None, "is_string", "is_string(v) -> bool", "True when `v` is a string."),
+    f(None, "is_number", "is_number(v) -> bool", "True when `v` is an integer or float."),
+    f(None, "is_bool", "is_bool(v) -> bool", "True when `v` is a boolean."),
+    f(None, "is_array", "is_array(v) -> bool", "True when `v` is an array."),
+    f(None, "is_set", "is_set(v) -> bool", "True when `v` is a set."),
+    f(None, "is_object", "is_object(v) -> bool", "True when `v` is an object."),
+    f(None, "is_null", "is_null(v) -> bool", "True when `v` is null or missing."),
+    f(None, "to_number", "to_number(v) -> number", "`v` as a number: numbers as-is, numeric strings parsed, booleans as 1/0; otherwise null."),
+    f(None, "to_string", "to_string(v) -> string", "`v` as text, rendered like an f-string hole: strings as-is, anything else as JSON."),
+    f(Some("time"), "now", "time::now() -> int", "Current Unix time in seconds."),
+    f(Some("time"), "now_secs", "time::now_secs() -> int", "Current Unix time in seconds — the unit of JWT `exp`/`nbf`/`iat`."),
+    f(Some("time"), "now_ms", "time::now_ms() -> int", "Current Unix time in milliseconds."),
+    f(Some("time"), "now_ns", "time::now_ns() -> int", "Current Unix time in nanoseconds."),
+    f(Some("time"), "parse_rfc3339", "time::parse_rfc3339(s) -> int", "Parses an RFC 3339 timestamp to Unix nanoseconds."),
+    f(Some("time"), "format_rfc3339", "time::format_rfc3339(ns) -> string", "Formats Unix nanoseconds as an RFC 3339 timestamp."),
+    f(Some("time"), "add_ns", "time::add_ns(t, d) -> int", "`t + d`, both in nanoseconds."),
+    f(Some("time"), "subtract_ns", "time::subtract_ns(t, d) -> int", "`t - d`, both in nanoseconds."),
+    f(Some("time"), "is_before", "time::is_before(t1, t2) -> bool", "True when `t1` is earlier than `t2`."),
+    f(Some("time"), "is_after", "time::is_after(t1, t2) -> bool", "True when `t1` is later than `t2`."),
+    f(Some("time"), "is_between", "time::is_between(t, start, end) -> bool", "True when `start <= t <= end`."),
+    f(Some("math"), "abs", "math::abs(x) -> number", "Absolute value."),
+    f(Some("math"), "round", "math::round(x) -> number", "Rounds to the nearest integer, halves away from zero."),
+    f(Some("math"), "floor", "math::floor(x) -> number", "Largest integer not greater than `x`."),
+    f(Some("math"), "ceil", "math::ceil(x) -> number", "Smallest integer not less than `x`."),
+    f(Some("math"), "sqrt", "math::sqrt(x) -> float", "Square root."),
+    f(Some("math"), "pow", "math::pow(base, exponent) -> number", "`base` raised to `exponent`."),
+    f(Some("math"), "min", "math::min(a, b) -> number", "The smaller of two numbers."),
+    f(Some("math"), "max", "math::max(a, b) -> number", "The larger of two numbers."),
+    f(Some("math"), "clamp", "math::clamp(x, lo, hi) -> number", "`x` limited to the range `[lo, hi]`."),
+    f(Some("regex"), "matches", "regex::matches(text, pattern) -> bool", "True when `pattern` matches anywhere in `text`. Patterns are compiled once and cached."),
+    f(Some("regex"), "replace", "regex::replace(text, pattern, replacement) -> string", "Replaces every match of `pattern` in `text`."),
+    f(Some("regex"), "split", "regex::split(text, pattern) -> array", "Splits `text` on every match of `pattern`."),
+    f(Some("regex"), "is_valid", "regex::is_valid(pattern) -> bool", "True when `pattern` compiles."),
+    f(Some("regex"), "escape", "regex::escape(s) -> string", "Escapes regex metacharacters so `s` matches literally."),
+    f(Some("json"), "parse", "json::parse(s) -> value", "Parses a JSON string."),
+    f(Some("json"), "stringify", "json::stringify(v) -> string", "Serializes a value as JSON."),
+    f(Some("json"), "is_valid", "json::is_valid(s) -> bool", "True when `s` is valid JSON."),
+    f(Some("strings"), "format", "strings::format(template, args...) -> string", "Fills each `{}` in `template` with the next argument, rendered like an f-string hole; `{{`/`}}` are literal braces."),
+    f(Some("jwt"), "decode", "jwt::decode(token) -> object", "The token's claims. **No signature verification** — verify at the trust boundary."),
+    f(Some("jwt"), "header", "jwt::header(token) -> object", "The token's JOSE header (`alg`, `kid`, `typ`, ...)."),
+    f(Some("rebac"), "related", "rebac::related(subject, relation, object) -> bool", "True when `subject` holds `relation` on `object` directly."),
+    f(Some("rebac"), "reachable", "rebac::reachable(subject, relation, object, via, max_depth) -> bool", "True when `subject` holds `relation` on `object` directly or through groups reached along its own `via` edges, up to `max_depth` hops. Bounded and cycle-safe."),
+    f(Some("rebac"), "inherited", "rebac::inherited(subject, relation, object, up, max_depth) -> bool", "True when `relation` holds on `object` or any ancestor reached along `up` edges, up to `max_depth` hops."),
+    f(Some("rebac"), "check", "rebac::check(subject, relation, object) -> bool", "True when `subject` holds `relation` on `object` as the schema's rewrites define it for the object's type (unions, intersections, exclusions, `viewer from parent`); a relation without a rewrite is its direct tuples. Budgeted and cycle-safe."),
+    f(Some("taint"), "level", "taint::level(key) -> string", "Provenance of a context key: `\"platform\"`, `\"verified\"` or `\"llm\"`. With taint mode on, unlabeled keys are `\"llm\"`."),
+    f(Some("taint"), "trusted", "taint::trusted(key) -> bool", "True when the context key is at least `verified` — never satisfied by an LLM-asserted value."),
+    f(Some("net"), "cidr_contains", "net::cidr_contains(cidr, addr) -> bool", "True when `addr` (an address or subnet) lies entirely inside `cidr`."),
+    f(Some("net"), "cidr_overlaps", "net::cidr_overlaps(a, b) -> bool", "True when the two ranges share an address."),
+    f(Some("net"), "ip_in_any", "net::ip_in_any(addr, ranges) -> bool", "True when `addr` lies inside any range of a list or set."),
+    f(Some("net"), "is_ip", "net::is_ip(s) -> bool", "True when `s` is an IPv4 or IPv6 address."),
+    f(Some("net"), "is_ipv4", "net::is_ipv4(s) -> bool", "True when `s` is an IPv4 address."),
+    f(Some("net"), "is_ipv6", "net::is_ipv6(s) -> bool", "True when `s` is an IPv6 address."),
+    f(Some("net"), "is_cidr", "net::is_cidr(s) -> bool", "True when `s` is an explicit `addr/prefix` range."),
+    f(Some("base64"), "encode", "base64::encode(s) -> string", "Standard, padded Base64 of the UTF-8 bytes of `s`."),
+    f(Some("base64"), "decode", "base64::decode(s) -> string", "Decodes standard Base64 (padding optional); null unless the result is UTF-8 text."),
+    f(Some("base64"), "url_encode", "base64::url_encode(s) -> string", "URL-safe, unpadded Base64 of `s`."),
+    f(Some("base64"), "url_decode", "base64::url_decode(s) -> string", "Decodes URL-safe Base64 (padding optional); null unless the result is UTF-8 text."),
+    f(Some("hex"), "encode", "hex::encode(s) -> string", "Lowercase hex of the UTF-8 bytes of `s`."),
+    f(Some("hex"), "decode", "hex::decode(s) -> string", "Decodes hex of either case; null unless the result is UTF-8 text."),
+    f(Some("url"), "encode", "url::encode(s) -> string", "Percent-encodes every byte outside `A-Z a-z 0-9 - . _ ~`."),
+    f(Some("url"), "decode", "url::decode(s) -> string", "Decodes `%XX` escapes (`+` stays literal); null on a malformed escape or non-UTF-8 result."),
+    f(Some("glob"), "match", "glob::match(pattern, path, delimiters) -> bool", "True when `path` matches `pattern`. `*` and `?` stop at the single-character `delimiters` (`[]` means `[\".\"]`, `null` means none); `**` crosses them. A malformed pattern is false."),
+    f(Some("semver"), "compare", "semver::compare(a, b) -> int", "-1, 0 or 1 by SemVer precedence (a leading `v` is allowed); null when either is not a version."),
+    f(Some("semver"), "satisfies", "semver::satisfies(version, requirement) -> bool", "True when `version` meets a Cargo-style requirement such as `^1.2`, `~1.4.0` or `>=1.0, <2`."),
+];
+
+/// Look up a builtin function.
+pub fn builtin_function(namespace: Option<&str>, name: &str) -> Option<&'static BuiltinFunction> {
+    BUILTIN_FUNCTIONS
+        .iter()
+        .find(|b| b.namespace == namespace && b.name == name)
+}
+
+/// Reference entry for one builtin method.
+#[derive(Debug, Clone, Copy)]
+pub struct BuiltinMethod {
+    pub name: &'static str,
+    pub signature: &'static str,
+    pub doc: &'static str,
+}
+
+const fn m(name: &'static str, signature: &'static str, doc: &'static str) -> BuiltinMethod {
+    BuiltinMethod {
+        name,
+        signature,
+        doc,
+    }
+}
+
+/// Every method the parser accepts, in [`MethodName`] order.
+pub const BUILTIN_METHODS: &[BuiltinMethod] = &[
+    m(
+        "count",
+        "collection.count() -> int",
+        "Number of elements (or characters of a string).",
+    ),
+    m(
+        "sum",
+        "collection.sum() -> number",
+        "Sum of the numeric elements.",
+    ),
+    m("max", "collection.max() -> number", "Largest element."),
+    m("min", "collection.min() -> number", "Smallest element."),
+    m(
+        "any",
+        "collection.any() -> bool",
+        "True when any element is true.",
+    ),
+    m(
+        "all",
+        "collection.all() -> bool",
+        "True when every element is true.",
+    ),
+    m("lower", "string.lower() -> string", "Lower-cased copy."),
+    m("upper", "string.upper() -> string", "Upper-cased copy."),
+    m(
+        "trim",
+        "string.trim() -> string",
+        "Copy without leading and trailing whitespace.",
+    ),
+    m(
+        "split",
+        "string.split(delimiter) -> array",
+        "Splits on a literal delimiter.",
+    ),
+    m(
+        "contains",
+        "value.contains(x) -> bool",
+        "Substring test on strings; membership test on arrays and sets.",
+    ),
+    m(
+        "startswith",
+        "string.startswith(prefix) -> bool",
+        "True when the string begins with `prefix`.",
+    ),
+    m(
+        "endswith",
+        "string.endswith(suffix) -> bool",
+        "True when the string ends with `suffix`.",
+    ),
+    m(
+        "matches",
+        "string.matches(pattern) -> bool",
+        "Regex match anywhere in the string.",
+    ),
+    m(
+        "find",
+        "string.find(pattern) -> string",
+        "First regex match, or null.",
+    ),
+    m(
+        "find_all",
+        "string.find_all(pattern) -> array",
+        "Every regex match.",
+    ),
+    m(
+        "replace",
+        "string.replace(pattern, replacement) -> string",
+        "Replaces every regex match.",
+    ),
+    m(
+        "union",
+        "set.union(other) -> set",
+        "Elements in either collection.",
+    ),
+    m(
+        "intersection",
+        "set.intersection(other) -> set",
+        "Elements in both collections.",
+    ),
+    m(
+        "difference",
+        "set.difference(other) -> set",
+        "Elements not in `other`.",
+    ),
+    m("first", "array.first() -> value", "First element, or null."),
+    m("last", "array.last() -> value", "Last element, or null."),
+    m(
+        "slice",
+        "array.slice(start, end) -> array",
+        "Elements `start..end`.",
+    ),
+    m(
+        "reverse",
+        "array.reverse() -> array",
+        "Elements in reverse order.",
+    ),
+    m(
+        "sort",
+        "array.sort() -> array",
+        "Elements in ascending order.",
+    ),
+    m(
+        "unique",
+        "array.unique() -> array",
+        "Elements with duplicates removed, first occurrence kept.",
+    ),
+    m("keys", "object.keys() -> array", "The object's keys."),
+    m("values", "object.values() -> array", "The object's values."),
+    m(
+        "has_key",
+        "object.has_key(key) -> bool",
+        "True when the object has `key`.",
+    ),
+];
+
+/// Look up a builtin method.
+pub fn builtin_method(name: &str) -> Option<&'static BuiltinMethod> {
+    MethodName::from_str(name)
+        .ok()
+        .and_then(|m| BUILTIN_METHODS.iter().find(|b| b.name == m.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reference_tables_track_the_dispatched_builtins() {
+        for ns in functions::BUILTIN_NAMESPACES {
+            assert!(
+                BUILTIN_FUNCTIONS.iter().any(|b| b.namespace == Some(ns)),
+                "no reference entry for namespace {ns}"
+            );
+        }
+        for name in functions::BUILTIN_GLOBALS {
+            assert!(builtin_function(None, name).is_some(), "{name}");
+        }
+        for method in BUILTIN_METHODS {
+            assert!(MethodName::from_str(method.name).is_ok(), "{}", method.name);
+        }
+    }
+}
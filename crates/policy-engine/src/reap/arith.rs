@@ -0,0 +1,139 @@
+//! Numeric semantics of infix arithmetic (`+ - * / %`), shared by the AST
+//! interpreter and the compiled evaluator so the two cannot drift.
+//!
+//! Type-strict and total:
+//! - integer op integer stays an integer; any float operand makes the result
+//!   a float;
+//! - `/` on integers is exact when it divides evenly (`10 / 5` is `2`) and a
+//!   float otherwise (`10 / 4` is `2.5`);
+//! - `%` is integer-only (truncated, like Rust and Go);
+//! - overflow, division or remainder by zero, a float `%` and any non-finite
+//!   float result are undefined (`None`), which every comparison treats as
+//!   a non-match.
+
+use super::ast::ArithOp;
+
+/// A numeric operand or result.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Number {
+    Int(i64),
+    Float(f64),
+}
+
+impl Number {
+    pub(crate) fn as_f64(self) -> f64 {
+        match self {
+            Number::Int(i) => i as f64,
+            Number::Float(f) => f,
+        }
+    }
+}
+
+/// Apply `op` to two numbers; `None` when the result is undefined.
+pub(crate) fn apply(op: ArithOp, left: Number, right: Number) -> Option<Number> {
+    match (left, right) {
+        (Number::Int(a), Number::Int(b)) => match op {
+            ArithOp::Add => a.checked_add(b).map(Number::Int),
+            ArithOp::Sub => a.checked_sub(b).map(Number::Int),
+            ArithOp::Mul => a.checked_mul(b).map(Number::Int),
+            ArithOp::Div => {
+                if b == 0 {
+                    None
+                } else if a.checked_rem(b)? == 0 {
+                    a.checked_div(b).map(Number::Int)
+                } else {
+                    finite(a as f64 / b as f64)
+                }
+            }
+            ArithOp::Rem => {
+                if b == 0 {
+                    None
+                } else {
+                    a.checked_rem(b).map(Number::Int)
+                }
+            }
+        },
+        _ => {
+            let (a, b) = (left.as_f64(), right.as_f64());
+            match op {
+                ArithOp::Add => finite(a + b),
+                ArithOp::Sub => finite(a - b),
+                ArithOp::Mul => finite(a * b),
+                ArithOp::Div if b == 0.0 => None,
+                ArithOp::Div => finite(a / b),
+                ArithOp::Rem => None,
+            }
+        }
+    }
+}
+
+fn finite(f: f64) -> Option<Number> {
+    f.is_finite().then_some(Number::Float(f))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integer_arithmetic_stays_integer() {
+        assert_eq!(
+            apply(ArithOp::Add, Number::Int(2), Number::Int(3)),
+            Some(Number::Int(5))
+        );
+        assert_eq!(
+            apply(ArithOp::Sub, Number::Int(2), Number::Int(3)),
+            Some(Number::Int(-1))
+        );
+        assert_eq!(
+            apply(ArithOp::Mul, Number::Int(4), Number::Int(3)),
+            Some(Number::Int(12))
+        );
+        assert_eq!(
+            apply(ArithOp::Rem, Number::Int(-7), Number::Int(3)),
+            Some(Number::Int(-1))
+        );
+    }
+
+    #[test]
+    fn division_is_exact_or_float() {
+        assert_eq!(
+            apply(ArithOp::Div, Number::Int(10), Number::Int(5)),
+            Some(Number::Int(2))
+        );
+        assert_eq!(
+            apply(ArithOp::Div, Number::Int(10), Number::Int(4)),
+            Some(Number::Float(2.5))
+        );
+        assert_eq!(
+            apply(ArithOp::Div, Number::Float(1.0), Number::Int(4)),
+            Some(Number::Float(0.25))
+        );
+    }
+
+    #[test]
+    fn undefined_results() {
+        assert_eq!(apply(ArithOp::Div, Number::Int(1), Number::Int(0)), None);
+        assert_eq!(apply(ArithOp::Rem, Number::Int(1), Number::Int(0)), None);
+        assert_eq!(
+            apply(ArithOp::Div, Number::Float(1.0), Number::Float(0.0)),
+            None
+        );
+        assert_eq!(
+            apply(ArithOp::Rem, Number::Float(5.0), Number::Int(2)),
+            None
+        );
+        assert_eq!(
+            apply(ArithOp::Add, Number::Int(i64::MAX), Number::Int(1)),
+            None
+        );
+        assert_eq!(
+            apply(ArithOp::Div, Number::Int(i64::MIN), Number::Int(-1)),
+            None
+        );
+        assert_eq!(
+            apply(ArithOp::Mul, Number::Float(f64::MAX), Number::Float(2.0)),
+            None
+        );
+    }
+}
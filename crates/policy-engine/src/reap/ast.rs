@@ -66,6 +66,35 @@ pub struct Rule {
     /// check mode. Decision-mode evaluation ignores it (zero cost).
     #[serde(default)]
     pub message: Option<Expr>,
+    /// Optional `with obligations {...}` / `with advice {...}` clauses,
+    /// returned with the decision whenever this rule decides. `None` = the
+    /// rule declares neither.
+    #[serde(default)]
+    pub obligations: Option<RuleObligations>,
+}
+
+/// A rule's obligation and advice clauses, as written. Values are static
+/// literals; [`RuleObligations::to_decision`] lowers them to the JSON the
+/// engine hands back with a decision.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RuleObligations {
+    pub obligations: Vec<(String, Value)>,
+    pub advice: Vec<(String, Value)>,
+}
+
+impl RuleObligations {
+    pub fn to_decision(&self) -> crate::DecisionObligations {
+        let lower = |pairs: &[(String, Value)]| {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.clone(), v.to_json()))
+                .collect::<serde_json::Map<_, _>>()
+        };
+        crate::DecisionObligations {
+            obligations: lower(&self.obligations),
+            advice: lower(&self.advice),
+        }
+    }
 }
 
 /// Decision type
@@ -101,6 +130,34 @@ pub enum Condition {
     Not(Box<Condition>),
     /// Expression that evaluates to boolean (e.g., function calls like is_string(x))
     Expr(Expr),
+    /// Quantifier block: `some i, x in resource.sites { ... }`,
+    /// `every x in input.containers { ... }`. `index` and `variable` (and any
+    /// variable the body binds) are scoped to the block.
+    Quantifier {
+        kind: QuantifierKind,
+        /// Position (arrays, sets) or key (objects) of each element.
+        index: Option<String>,
+        variable: String,
+        collection: IterationSource,
+        body: Box<Condition>,
+    },
+}
+
+/// `some` (at least one element satisfies the body) or `every` (all do).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QuantifierKind {
+    Some,
+    Every,
+}
+
+impl QuantifierKind {
+    /// The keyword as written in source.
+    pub fn keyword(self) -> &'static str {
+        match self {
+            QuantifierKind::Some => "some",
+            QuantifierKind::Every => "every",
+        }
+    }
 }
 
 /// Left side of comparison
@@ -222,6 +279,32 @@ pub enum Value {
     Set(Vec<Value>),
 }
 
+impl Value {
+    /// The literal as JSON. Sets become arrays; a non-finite float becomes
+    /// `null`.
+    pub fn to_json(&self) -> serde_json::Value {
+        use serde_json::Value as Json;
+        match self {
+            Value::String(s) => Json::String(s.clone()),
+            Value::Integer(i) => Json::from(*i),
+            Value::Float(f) => serde_json::Number::from_f64(*f)
+                .map(Json::Number)
+                .unwrap_or(Json::Null),
+            Value::Boolean(b) => Json::Bool(*b),
+            Value::Null => Json::Null,
+            Value::Array(items) | Value::Set(items) => {
+                Json::Array(items.iter().map(Value::to_json).collect())
+            }
+            Value::Object(pairs) => Json::Object(
+                pairs
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.to_json()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
 /// Comprehension expression for collecting and transforming data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Comprehension {
@@ -307,6 +390,82 @@ pub enum Expr {
         function: String,
         args: Vec<Expr>,
     },
+
+    /// Infix arithmetic: user.used + input.requested, (a - b) * 2
+    /// Numbers only; a non-numeric operand, overflow or division by zero
+    /// evaluates to null (a non-match in any comparison).
+    BinaryOp {
+        op: ArithOp,
+        left: Box<Expr>,
+        right: Box<Expr>,
+    },
+
+    /// Interpolated string: f"bucket {input.name} is public"
+    /// Each hole renders like a `strings::format` argument.
+    Interpolation(Vec<InterpolationPart>),
+}
+
+/// One piece of an interpolated string.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum InterpolationPart {
+    /// Literal text, with `{{`/`}}` and escapes already resolved.
+    Literal(String),
+    /// A `{expr}` hole.
+    Expr(Expr),
+}
+
+impl InterpolationPart {
+    /// The hole's expression, if this part is a hole.
+    pub fn expr(&self) -> Option<&Expr> {
+        match self {
+            InterpolationPart::Literal(_) => None,
+            InterpolationPart::Expr(e) => Some(e),
+        }
+    }
+}
+
+/// Infix arithmetic operator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ArithOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+impl ArithOp {
+    /// Binding strength: `*` `/` `%` bind tighter than `+` `-`.
+    pub fn precedence(self) -> u8 {
+        match self {
+            ArithOp::Add | ArithOp::Sub => 1,
+            ArithOp::Mul | ArithOp::Div | ArithOp::Rem => 2,
+        }
+    }
+
+    /// The operator as written in source.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            ArithOp::Add => "+",
+            ArithOp::Sub => "-",
+            ArithOp::Mul => "*",
+            ArithOp::Div => "/",
+            ArithOp::Rem => "%",
+        }
+    }
+}
+
+impl From<&str> for ArithOp {
+    fn from(s: &str) -> Self {
+        match s {
+            "+" => ArithOp::Add,
+            "-" => ArithOp::Sub,
+            "*" => ArithOp::Mul,
+            "/" => ArithOp::Div,
+            "%" => ArithOp::Rem,
+            _ => panic!("Invalid arithmetic operator: {}", s),
+        }
+    }
 }
 
 /// Method names for method call syntax
@@ -367,6 +526,19 @@ pub enum TypeName {
     Null,
 }
 
+impl Entity {
+    /// The entity keyword as written in source.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Entity::User => "user",
+            Entity::Actor => "actor",
+            Entity::Resource => "resource",
+            Entity::Context => "context",
+            Entity::Input => "input",
+        }
+    }
+}
+
 impl From<&str> for Entity {
     fn from(s: &str) -> Self {
         match s {
@@ -380,6 +552,21 @@ impl From<&str> for Entity {
     }
 }
 
+impl Operator {
+    /// The operator as written in source.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Operator::Equal => "==",
+            Operator::NotEqual => "!=",
+            Operator::GreaterThan => ">",
+            Operator::LessThan => "<",
+            Operator::GreaterEqual => ">=",
+            Operator::LessEqual => "<=",
+            Operator::In => "in",
+        }
+    }
+}
+
 impl From<&str> for Operator {
     fn from(s: &str) -> Self {
         match s {
@@ -395,6 +582,16 @@ impl From<&str> for Operator {
     }
 }
 
+impl Decision {
+    /// The decision keyword as written in source.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Decision::Allow => "allow",
+            Decision::Deny => "deny",
+        }
+    }
+}
+
 impl From<&str> for Decision {
     fn from(s: &str) -> Self {
         match s {
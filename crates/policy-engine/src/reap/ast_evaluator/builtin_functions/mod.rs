@@ -5,12 +5,18 @@
 //! - math: abs, round, floor, ceil, sqrt, pow, min, max, clamp
 //! - regex: escape (cache-dependent functions remain in evaluator)
 //! - json: parse, stringify, is_valid
+//! - net: cidr_contains, cidr_overlaps, ip_in_any, is_ip, is_ipv4, is_ipv6, is_cidr
+//! - strings: format
+//! - pure: base64, hex, url, glob, semver codecs and matchers; to_number, to_string
 //! - type_check: is_string, is_number, is_bool, is_array, is_set, is_object, is_null, concat
 
 pub(super) mod json;
 pub(super) mod jwt;
 pub(super) mod math;
+pub(super) mod net;
+pub(super) mod pure;
 pub(super) mod regex;
+pub(super) mod strings;
 pub(super) mod time;
 pub(super) mod type_check;
 
@@ -36,6 +42,9 @@ pub(super) use json::{
     is_valid as json_is_valid, parse as json_parse, stringify as json_stringify,
 };
 
+// Re-export string functions
+pub(super) use strings::format as strings_format;
+
 // Re-export type check functions
 pub(super) use type_check::{
     concat, is_array, is_bool, is_null, is_number, is_object, is_set, is_string,
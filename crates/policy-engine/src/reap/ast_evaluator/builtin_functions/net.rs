@@ -0,0 +1,68 @@
+//! Network functions for policy evaluation.
+//!
+//! This module provides IP address and CIDR operations:
+//! - cidr_contains(cidr, addr) - Address or subnet lies inside a range
+//! - cidr_overlaps(a, b) - Two ranges share at least one address
+//! - ip_in_any(addr, ranges) - Address lies inside any range of a collection
+//! - is_ip(), is_ipv4(), is_ipv6(), is_cidr() - Parse checks
+//!
+//! Parsing and matching live in [`crate::net`], shared with the compiled
+//! evaluator. A non-string or malformed operand is a non-match (`false`),
+//! never an error: addresses are request data, and a garbled `source_ip`
+//! must fail closed rather than abort the evaluation.
+
+use super::super::types::EvalValue;
+use crate::net::{self, IpFamily};
+
+/// net::cidr_contains(cidr, addr_or_cidr)
+#[inline]
+pub fn cidr_contains(cidr: &EvalValue, addr: &EvalValue) -> EvalValue {
+    match (cidr, addr) {
+        (EvalValue::String(c), EvalValue::String(a)) => {
+            EvalValue::Boolean(net::cidr_contains(c, a))
+        }
+        _ => EvalValue::Boolean(false),
+    }
+}
+
+/// net::cidr_overlaps(a, b)
+#[inline]
+pub fn cidr_overlaps(a: &EvalValue, b: &EvalValue) -> EvalValue {
+    match (a, b) {
+        (EvalValue::String(a), EvalValue::String(b)) => {
+            EvalValue::Boolean(net::cidr_overlaps(a, b))
+        }
+        _ => EvalValue::Boolean(false),
+    }
+}
+
+/// net::ip_in_any(addr, ranges) — `ranges` is an array or set of range
+/// strings; non-string or malformed elements never match.
+pub fn ip_in_any(addr: &EvalValue, ranges: &EvalValue) -> EvalValue {
+    let EvalValue::String(addr) = addr else {
+        return EvalValue::Boolean(false);
+    };
+    let (EvalValue::Array(items) | EvalValue::Set(items)) = ranges else {
+        return EvalValue::Boolean(false);
+    };
+    let Some(inner) = net::IpNet::parse(addr) else {
+        return EvalValue::Boolean(false);
+    };
+    let hit = items.iter().any(|item| match item {
+        EvalValue::String(r) => net::IpNet::parse(r).is_some_and(|range| range.contains(&inner)),
+        _ => false,
+    });
+    EvalValue::Boolean(hit)
+}
+
+/// net::is_ip(s) / net::is_ipv4(s) / net::is_ipv6(s)
+#[inline]
+pub fn is_ip(value: &EvalValue, family: Option<IpFamily>) -> EvalValue {
+    EvalValue::Boolean(matches!(value, EvalValue::String(s) if net::is_ip(s, family)))
+}
+
+/// net::is_cidr(s)
+#[inline]
+pub fn is_cidr(value: &EvalValue) -> EvalValue {
+    EvalValue::Boolean(matches!(value, EvalValue::String(s) if net::is_cidr(s)))
+}
@@ -0,0 +1,40 @@
+//! Pure value functions for policy evaluation.
+//!
+//! This module provides encoding, matching and conversion functions:
+//! - base64::encode/decode/url_encode/url_decode, hex::encode/decode,
+//!   url::encode/decode - Text codecs; undecodable input is null
+//! - glob::match(pattern, path, delimiters) - Separator-aware glob match
+//! - semver::compare(a, b), semver::satisfies(version, requirement)
+//! - to_number(v), to_string(v) - Explicit conversions
+//!
+//! The semantics live in [`crate::reap::builtins`], shared with the
+//! compiled evaluator; this module only converts values in and out.
+
+use super::super::types::EvalValue;
+use super::strings;
+use crate::reap::builtins::{self, Builtin};
+
+/// Apply `builtin` to already-evaluated arguments.
+pub fn call(builtin: Builtin, args: &[EvalValue]) -> EvalValue {
+    let args: Vec<serde_json::Value> = args.iter().map(strings::to_json).collect();
+    from_result(builtin.call(&args))
+}
+
+/// glob::match with an already-compiled pattern (see
+/// [`builtins::glob_regex_source`]); `None` is a malformed pattern.
+pub fn glob_match(re: Option<&regex::Regex>, path: &EvalValue) -> EvalValue {
+    EvalValue::Boolean(re.is_some_and(|re| builtins::glob_matches(re, &strings::to_json(path))))
+}
+
+/// Builtin results are scalars: strings, numbers, booleans or null.
+fn from_result(value: serde_json::Value) -> EvalValue {
+    match value {
+        serde_json::Value::String(s) => EvalValue::String(s),
+        serde_json::Value::Bool(b) => EvalValue::Boolean(b),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => EvalValue::Integer(i),
+            None => n.as_f64().map(EvalValue::Float).unwrap_or(EvalValue::Null),
+        },
+        _ => EvalValue::Null,
+    }
+}
@@ -0,0 +1,50 @@
+//! String functions for policy evaluation.
+//!
+//! This module provides string formatting:
+//! - format() - Fill `{}` placeholders positionally
+//! - render() - Render one value as interpolation text (f-string holes)
+
+use super::super::types::EvalValue;
+use crate::reap::interpolate;
+use reaper_core::ReaperError;
+
+/// Render a value the way an f-string hole or `strings::format` argument
+/// shows it.
+pub fn render(value: &EvalValue) -> String {
+    interpolate::render(&to_json(value))
+}
+
+/// strings::format(template, args...) - Fill `{}` placeholders in order
+pub fn format(values: &[EvalValue]) -> Result<EvalValue, ReaperError> {
+    let (template, args) = match values.split_first() {
+        Some((EvalValue::String(template), args)) => (template, args),
+        _ => {
+            return Err(ReaperError::InvalidPolicy {
+                reason: "strings::format() requires a string template as its first argument"
+                    .to_string(),
+            })
+        }
+    };
+    let args: Vec<String> = args.iter().map(render).collect();
+    interpolate::fill(template, &args).map(EvalValue::String)
+}
+
+/// A value as JSON, for the helpers shared with the compiled evaluator.
+/// Sets come out sorted.
+pub fn to_json(value: &EvalValue) -> serde_json::Value {
+    use serde_json::Value;
+    match value {
+        EvalValue::String(s) => Value::String(s.clone()),
+        EvalValue::Integer(i) => Value::from(*i),
+        EvalValue::Float(f) => serde_json::Number::from_f64(*f)
+            .map(Value::Number)
+            .unwrap_or(Value::Null),
+        EvalValue::Boolean(b) => Value::Bool(*b),
+        EvalValue::Null => Value::Null,
+        EvalValue::Array(items) => Value::Array(items.iter().map(to_json).collect()),
+        EvalValue::Set(items) => interpolate::sorted_set(items.iter().map(to_json).collect()),
+        EvalValue::Object(map) => {
+            Value::Object(map.iter().map(|(k, v)| (k.clone(), to_json(v))).collect())
+        }
+    }
+}
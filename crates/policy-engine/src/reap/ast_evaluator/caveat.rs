@@ -0,0 +1,78 @@
+//! Relationship caveats: a condition a ReBAC edge carries, evaluated against
+//! the request context whenever a traversal crosses the edge
+//! (`"member": [{"subject": "alice", "caveat": "context.ip_in_office == true"}]`).
+//!
+//! A caveat is parsed like a snippet — the condition of a throwaway rule —
+//! and interpreted by a [`ReapAstEvaluator`] over a shared, empty store: only
+//! `context.*` (and literals, builtins) carries meaning; `user`, `resource`
+//! and entity lookups read null, so a caveat can never reach back into the
+//! graph that is evaluating it.
+
+use super::types::EvalContext;
+use super::ReapAstEvaluator;
+use crate::data::DataStore;
+use crate::reap::ast::Policy;
+use crate::reap::parser::ReapParser;
+use reaper_core::ReaperError;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+/// The store every caveat evaluates over. Never written: caveats see no
+/// entities, and nothing a caveat interns outlives its (bounded) source.
+fn caveat_store() -> Arc<DataStore> {
+    static STORE: OnceLock<Arc<DataStore>> = OnceLock::new();
+    STORE.get_or_init(|| Arc::new(DataStore::new())).clone()
+}
+
+/// A compiled edge caveat.
+#[derive(Debug)]
+pub(crate) struct Caveat {
+    evaluator: ReapAstEvaluator,
+    nobody: crate::data::EntityId,
+}
+
+impl Caveat {
+    /// Parse `source` as a rule condition. Malformed caveats are rejected
+    /// here, at load time, rather than silently hiding the edge later.
+    pub(crate) fn compile(source: &str) -> Result<Self, ReaperError> {
+        let wrapped = format!(
+            "policy caveat {{\n    default: deny,\n    rule caveat {{\n        allow if {{\n{source}\n        }}\n    }}\n}}\n"
+        );
+        let policy: Policy =
+            ReapParser::parse(&wrapped).map_err(|e| ReaperError::InvalidPolicy {
+                reason: format!("invalid caveat `{source}`: {e}"),
+            })?;
+        if policy.rules.len() != 1 {
+            return Err(ReaperError::InvalidPolicy {
+                reason: format!("invalid caveat `{source}`: expected one condition"),
+            });
+        }
+        let store = caveat_store();
+        let nobody = store.interner().intern("");
+        Ok(Self {
+            evaluator: ReapAstEvaluator::new(store, policy),
+            nobody,
+        })
+    }
+
+    /// Does the caveat hold under `context`? An evaluation error (a type
+    /// mismatch, a missing function) is "no" — a caveat fails closed. Runs
+    /// inside the caller's evaluation, so the traversal budget is not reset.
+    pub(crate) fn holds(&self, context: &HashMap<String, serde_json::Value>) -> bool {
+        let mut eval = EvalContext {
+            variables: HashMap::new(),
+            user_id: self.nobody,
+            actor_id: None,
+            resource_id: self.nobody,
+            request_context: context.clone(),
+            context_provenance: None,
+            input: None,
+            calls: None,
+            caveats: Default::default(),
+        };
+        let condition = &self.evaluator.policy.rules[0].condition;
+        self.evaluator
+            .evaluate_condition(condition, &mut eval)
+            .unwrap_or(false)
+    }
+}
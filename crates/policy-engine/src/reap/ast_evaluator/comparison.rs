@@ -8,7 +8,7 @@
 
 use super::types::{EvalContext, EvalValue};
 use super::ReapAstEvaluator;
-use crate::reap::ast::{ComparisonLeft, ComparisonRight, Operator, Value};
+use crate::reap::ast::{ComparisonLeft, ComparisonRight, Expr, Operator, Value};
 use reaper_core::ReaperError;
 
 impl ReapAstEvaluator {
@@ -20,6 +20,17 @@ impl ReapAstEvaluator {
         right: &ComparisonRight,
         context: &EvalContext,
     ) -> Result<bool, ReaperError> {
+        let (left_value, right_value) = self.comparison_operands(left, right, context)?;
+        self.compare_operands(left, op, right, &left_value, &right_value)
+    }
+
+    /// Resolve both sides of a comparison.
+    pub(super) fn comparison_operands(
+        &self,
+        left: &ComparisonLeft,
+        right: &ComparisonRight,
+        context: &EvalContext,
+    ) -> Result<(EvalValue, EvalValue), ReaperError> {
         // Get left value
         let left_value = match left {
             ComparisonLeft::EntityAttr(attr) => self.get_entity_attribute(attr, context)?,
@@ -41,7 +52,19 @@ impl ReapAstEvaluator {
             }
             ComparisonRight::Expr(expr) => self.evaluate_expr(expr, context)?,
         };
+        Ok((left_value, right_value))
+    }
 
+    /// Apply `op` to resolved operands; `left` and `right` are the operand
+    /// nodes they came from.
+    pub(super) fn compare_operands(
+        &self,
+        left: &ComparisonLeft,
+        op: Operator,
+        right: &ComparisonRight,
+        left_value: &EvalValue,
+        right_value: &EvalValue,
+    ) -> Result<bool, ReaperError> {
         // NULL SEMANTICS (specified; enforced by the differential oracle):
         // a missing attribute/path evaluates to Null, and Null satisfies NO
         // comparison except an EXPLICIT presence check against a null literal
@@ -64,20 +87,33 @@ impl ReapAstEvaluator {
             // Any other comparison involving Null fails the rule (never errors).
             return Ok(false);
         }
+        // ARITHMETIC COMPARISONS are numeric-only: with `+ - * / %` on
+        // either side, a non-numeric other side is a non-match for every
+        // operator — `!=` included — rather than a type-strict inequality.
+        let is_arith = |e: Option<&Expr>| matches!(e, Some(Expr::BinaryOp { .. }));
+        let arith = is_arith(match left {
+            ComparisonLeft::Expr(e) => Some(e),
+            _ => None,
+        }) || is_arith(match right {
+            ComparisonRight::Expr(e) => Some(e),
+            _ => None,
+        });
+        let is_number = |v: &EvalValue| matches!(v, EvalValue::Integer(_) | EvalValue::Float(_));
+        if arith && !(is_number(left_value) && is_number(right_value)) {
+            return Ok(false);
+        }
 
         // Perform comparison based on operator
         match op {
-            Operator::Equal => Ok(Self::values_equal(&left_value, &right_value)),
-            Operator::NotEqual => Ok(!Self::values_equal(&left_value, &right_value)),
-            Operator::GreaterThan => self.compare_numeric(&left_value, &right_value, |a, b| a > b),
-            Operator::LessThan => self.compare_numeric(&left_value, &right_value, |a, b| a < b),
-            Operator::GreaterEqual => {
-                self.compare_numeric(&left_value, &right_value, |a, b| a >= b)
-            }
-            Operator::LessEqual => self.compare_numeric(&left_value, &right_value, |a, b| a <= b),
+            Operator::Equal => Ok(Self::values_equal(left_value, right_value)),
+            Operator::NotEqual => Ok(!Self::values_equal(left_value, right_value)),
+            Operator::GreaterThan => self.compare_numeric(left_value, right_value, |a, b| a > b),
+            Operator::LessThan => self.compare_numeric(left_value, right_value, |a, b| a < b),
+            Operator::GreaterEqual => self.compare_numeric(left_value, right_value, |a, b| a >= b),
+            Operator::LessEqual => self.compare_numeric(left_value, right_value, |a, b| a <= b),
             // For "in" operator: "value in collection" is parsed as left=collection, right=value
             // So we need to check if right_value is in left_value (collection)
-            Operator::In => self.check_membership(&left_value, &right_value),
+            Operator::In => self.check_membership(left_value, right_value),
         }
     }
 
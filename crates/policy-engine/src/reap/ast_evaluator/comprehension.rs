@@ -4,11 +4,15 @@
 //! - Set comprehension: `{expr | var := collection; filters}`
 //! - Array comprehension: `[expr | var := collection; filters]`
 //! - Object comprehension: `{key: value | var := collection; filters}`
+//!
+//! and of quantifier blocks (`some i, x in collection { ... }`,
+//! `every x in collection { ... }`), which share the iteration sources.
 
 use super::types::{EvalContext, EvalValue};
 use super::ReapAstEvaluator;
 use crate::reap::ast::{
     AssignmentValue, Comprehension, ComprehensionIterator, Condition, Expr, IterationSource,
+    QuantifierKind,
 };
 use reaper_core::ReaperError;
 use std::collections::{HashMap, HashSet};
@@ -180,27 +184,87 @@ impl ReapAstEvaluator {
         Ok(EvalValue::Object(result))
     }
 
+    /// Evaluate a quantifier block. Each element is tried in a copy of the
+    /// context with `index` (array position, object key, or the set element
+    /// itself) and `variable` bound, so nothing the body binds escapes.
+    ///
+    /// `some` over an empty collection is false and `every` is true. A
+    /// collection that is missing or not a collection makes both false:
+    /// `every` over absent input must not pass vacuously.
+    pub(super) fn evaluate_quantifier(
+        &self,
+        kind: QuantifierKind,
+        index: Option<&str>,
+        variable: &str,
+        collection: &IterationSource,
+        body: &Condition,
+        context: &EvalContext,
+    ) -> Result<bool, ReaperError> {
+        let entries: Vec<(EvalValue, EvalValue)> = match self.resolve_source(collection, context)? {
+            EvalValue::Array(items) => items
+                .into_iter()
+                .enumerate()
+                .map(|(i, item)| (EvalValue::Integer(i as i64), item))
+                .collect(),
+            EvalValue::Set(items) => items.into_iter().map(|item| (item.clone(), item)).collect(),
+            EvalValue::Object(map) => {
+                let mut entries: Vec<_> = map.into_iter().collect();
+                entries.sort_by(|a, b| a.0.cmp(&b.0));
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (EvalValue::String(k), v))
+                    .collect()
+            }
+            _ => return Ok(false),
+        };
+
+        for (position, item) in entries {
+            let mut item_context = context.clone();
+            if let Some(index) = index {
+                item_context.variables.insert(index.to_string(), position);
+            }
+            item_context.variables.insert(variable.to_string(), item);
+            let holds = self.evaluate_condition(body, &mut item_context)?;
+            match kind {
+                QuantifierKind::Some if holds => return Ok(true),
+                QuantifierKind::Every if !holds => return Ok(false),
+                _ => {}
+            }
+        }
+        Ok(kind == QuantifierKind::Every)
+    }
+
+    /// The value an iteration source names.
+    fn resolve_source(
+        &self,
+        source: &IterationSource,
+        context: &EvalContext,
+    ) -> Result<EvalValue, ReaperError> {
+        match source {
+            IterationSource::EntityAttr(entity_attr) => {
+                self.get_entity_attribute(entity_attr, context)
+            }
+            IterationSource::VarAttr(var_attr) => self.get_var_attribute(var_attr, context),
+            IterationSource::IndexedVariable { variable, index } => {
+                let var_value =
+                    context
+                        .variables
+                        .get(variable)
+                        .ok_or_else(|| ReaperError::InvalidPolicy {
+                            reason: format!("Undefined variable in iteration: {}", variable),
+                        })?;
+                self.apply_index(var_value, index)
+            }
+        }
+    }
+
     /// Get items from an iterator collection
     pub(super) fn get_iterator_items(
         &self,
         iterator: &ComprehensionIterator,
         context: &EvalContext,
     ) -> Result<Vec<EvalValue>, ReaperError> {
-        let collection =
-            match &iterator.collection {
-                IterationSource::EntityAttr(entity_attr) => {
-                    self.get_entity_attribute(entity_attr, context)?
-                }
-                IterationSource::VarAttr(var_attr) => self.get_var_attribute(var_attr, context)?,
-                IterationSource::IndexedVariable { variable, index } => {
-                    let var_value = context.variables.get(variable).ok_or_else(|| {
-                        ReaperError::InvalidPolicy {
-                            reason: format!("Undefined variable in iteration: {}", variable),
-                        }
-                    })?;
-                    self.apply_index(var_value, index)?
-                }
-            };
+        let collection = self.resolve_source(&iterator.collection, context)?;
 
         match collection {
             EvalValue::Array(arr) | EvalValue::Set(arr) => Ok(arr),
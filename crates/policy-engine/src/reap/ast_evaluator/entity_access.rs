@@ -17,23 +17,15 @@ impl ReapAstEvaluator {
         attr: &EntityAttr,
         context: &EvalContext,
     ) -> Result<EvalValue, ReaperError> {
-        // Handle context entity specially - it's not stored in DataStore
+        // Handle context entity specially - it's not stored in DataStore.
+        // Values are typed JSON; dotted paths navigate nested objects.
         if attr.entity == Entity::Context {
-            let attr_parts: Vec<&str> = attr.attribute.split('.').collect();
-            let value = context.request_context.get(attr_parts[0]);
-
-            if attr_parts.len() == 1 {
-                return Ok(value
-                    .map(|s| EvalValue::String(s.clone()))
-                    .unwrap_or(EvalValue::Null));
+            let value = context.context_value(&attr.attribute)?;
+            return if let Some(index) = &attr.index {
+                self.apply_index(&value, index)
             } else {
-                return Err(ReaperError::InvalidPolicy {
-                    reason: format!(
-                        "Chained context attributes not yet supported: context.{}",
-                        attr.attribute
-                    ),
-                });
-            }
+                Ok(value)
+            };
         }
 
         // `input` is the structured request document, not a DataStore entity:
@@ -0,0 +1,210 @@
+//! Traced evaluation (see [`crate::reap::trace`]).
+//!
+//! Mirrors `decide` and `evaluate_condition` node for node, reusing the
+//! same operand resolution and comparison code, so a trace can only differ
+//! from the real evaluation in what it records.
+
+use super::types::{EvalContext, EvalValue};
+use super::ReapAstEvaluator;
+use crate::reap::ast::{Condition, Decision, Operator};
+use crate::reap::format;
+use crate::reap::trace::{ConditionTrace, EvaluationTrace, RuleTrace};
+use crate::PolicyRequest;
+use reaper_core::ReaperError;
+use serde_json::Value as JsonValue;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+impl ReapAstEvaluator {
+    /// Evaluate `request` like [`Self::evaluate_with_input_named`], recording
+    /// an [`EvaluationTrace`] of every rule visited.
+    pub fn explain_with_input(
+        &self,
+        request: &PolicyRequest,
+        input: Option<&serde_json::Value>,
+    ) -> Result<EvaluationTrace, ReaperError> {
+        crate::data::relationships::reset_traversal_budget();
+        let mut context = self.eval_context(request, input)?;
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        context.calls = Some(calls.clone());
+
+        let mut rules = Vec::with_capacity(self.policy.rules.len());
+        let mut decided: Option<usize> = None;
+        for effect in [Decision::Deny, Decision::Allow] {
+            for (i, rule) in self.policy.rules.iter().enumerate() {
+                if rule.decision != effect {
+                    continue;
+                }
+                let condition = match decided {
+                    Some(_) => None,
+                    None => Some(self.trace_condition(&rule.condition, &mut context)?),
+                };
+                let matched = condition.as_ref().is_some_and(ConditionTrace::result);
+                if matched {
+                    decided = Some(i);
+                }
+                rules.push(RuleTrace {
+                    name: rule.name.clone(),
+                    effect: effect.clone(),
+                    matched,
+                    condition,
+                });
+            }
+        }
+
+        let decision = match decided {
+            Some(i) => self.policy.rules[i].decision.clone().into(),
+            None => self.policy.default_decision.clone().into(),
+        };
+        Ok(EvaluationTrace {
+            policy: self.policy.name.clone(),
+            evaluator: crate::evaluators::PolicyEvaluator::evaluator_type(self).to_string(),
+            decision,
+            decided_by: decided.map(|i| self.policy.rules[i].name.clone()),
+            rules,
+            calls: calls.take(),
+        })
+    }
+
+    fn trace_condition(
+        &self,
+        condition: &Condition,
+        context: &mut EvalContext,
+    ) -> Result<ConditionTrace, ReaperError> {
+        Ok(match condition {
+            Condition::True => ConditionTrace::Literal { result: true },
+            Condition::False => ConditionTrace::Literal { result: false },
+
+            Condition::Comparison { left, op, right } => {
+                let (left_value, right_value) = self.comparison_operands(left, right, context)?;
+                let result = self.compare_operands(left, *op, right, &left_value, &right_value)?;
+                // `x in coll` parses with the collection on the left.
+                let (left_value, right_value) = match op {
+                    Operator::In => (right_value, left_value),
+                    _ => (left_value, right_value),
+                };
+                ConditionTrace::Comparison {
+                    source: format::condition(condition),
+                    left: trace_value(&left_value),
+                    op: op.as_str().to_string(),
+                    right: trace_value(&right_value),
+                    result,
+                }
+            }
+
+            Condition::Assignment { variable, value } => {
+                let eval_value = self.evaluate_assignment_value(value, context)?;
+                let traced = trace_value(&eval_value);
+                context.variables.insert(variable.clone(), eval_value);
+                ConditionTrace::Assignment {
+                    variable: variable.clone(),
+                    value: traced,
+                }
+            }
+
+            Condition::And(conditions) | Condition::Or(conditions) => {
+                let all = matches!(condition, Condition::And(_));
+                let mut operands = Vec::new();
+                for cond in conditions {
+                    let traced = self.trace_condition(cond, context)?;
+                    let stop = traced.result() != all;
+                    operands.push(traced);
+                    if stop {
+                        break;
+                    }
+                }
+                let skipped = conditions.len() - operands.len();
+                let result = if all {
+                    operands.iter().all(ConditionTrace::result)
+                } else {
+                    operands.iter().any(ConditionTrace::result)
+                };
+                if all {
+                    ConditionTrace::All {
+                        result,
+                        operands,
+                        skipped,
+                    }
+                } else {
+                    ConditionTrace::Any {
+                        result,
+                        operands,
+                        skipped,
+                    }
+                }
+            }
+
+            Condition::Not(cond) => {
+                let operand = self.trace_condition(cond, context)?;
+                ConditionTrace::Not {
+                    result: !operand.result(),
+                    operand: Box::new(operand),
+                }
+            }
+
+            Condition::Quantifier {
+                kind,
+                index,
+                variable,
+                collection,
+                body,
+            } => {
+                let result = self.evaluate_quantifier(
+                    *kind,
+                    index.as_deref(),
+                    variable,
+                    collection,
+                    body,
+                    context,
+                )?;
+                ConditionTrace::Predicate {
+                    source: format::condition(condition),
+                    value: serde_json::Value::Bool(result),
+                    result,
+                }
+            }
+
+            Condition::Expr(expr) => {
+                let value = self.evaluate_expr(expr, context)?;
+                let result = match value {
+                    EvalValue::Boolean(b) => b,
+                    EvalValue::Null => false,
+                    _ => {
+                        return Err(ReaperError::InvalidPolicy {
+                            reason: format!(
+                                "Expression in condition must evaluate to boolean, got: {:?}",
+                                value
+                            ),
+                        })
+                    }
+                };
+                ConditionTrace::Predicate {
+                    source: format::condition(condition),
+                    value: trace_value(&value),
+                    result,
+                }
+            }
+        })
+    }
+}
+
+/// An evaluated value as JSON. Non-finite floats, which JSON cannot carry,
+/// are rendered as strings.
+fn trace_value(value: &EvalValue) -> JsonValue {
+    match value {
+        EvalValue::String(s) => JsonValue::String(s.clone()),
+        EvalValue::Integer(i) => JsonValue::from(*i),
+        EvalValue::Float(f) => serde_json::Number::from_f64(*f)
+            .map_or_else(|| JsonValue::String(f.to_string()), JsonValue::Number),
+        EvalValue::Boolean(b) => JsonValue::Bool(*b),
+        EvalValue::Null => JsonValue::Null,
+        EvalValue::Array(items) | EvalValue::Set(items) => {
+            JsonValue::Array(items.iter().map(trace_value).collect())
+        }
+        EvalValue::Object(map) => JsonValue::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), trace_value(v)))
+                .collect(),
+        ),
+    }
+}
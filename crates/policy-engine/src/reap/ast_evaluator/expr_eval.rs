@@ -2,6 +2,7 @@
 //!
 //! This module handles evaluation of expressions:
 //! - Literals
+//! - Interpolated strings
 //! - Variable references
 //! - Attribute access
 //! - Indexed access
@@ -10,7 +11,8 @@
 
 use super::types::{EvalContext, EvalValue};
 use super::ReapAstEvaluator;
-use crate::reap::ast::{Entity, EntityAttr, Expr};
+use crate::reap::arith::{self, Number};
+use crate::reap::ast::{Entity, EntityAttr, Expr, InterpolationPart};
 use reaper_core::ReaperError;
 
 impl ReapAstEvaluator {
@@ -23,6 +25,21 @@ impl ReapAstEvaluator {
         match expr {
             Expr::Literal(val) => Ok(self.value_to_eval_value(val)),
 
+            Expr::Interpolation(parts) => {
+                let mut out = String::new();
+                for part in parts {
+                    match part {
+                        InterpolationPart::Literal(text) => out.push_str(text),
+                        InterpolationPart::Expr(expr) => {
+                            out.push_str(&super::builtin_functions::strings::render(
+                                &self.evaluate_expr(expr, context)?,
+                            ))
+                        }
+                    }
+                }
+                Ok(EvalValue::String(out))
+            }
+
             Expr::Variable(var_name) => {
                 // Check if this is a pseudo-entity reference like "user.name" from entity method calls
                 if var_name.starts_with("user.")
@@ -50,7 +67,7 @@ impl ReapAstEvaluator {
                 if let Some(val) = context.variables.get(var_name) {
                     Ok(val.clone())
                 } else if let Some(val) = context.request_context.get(var_name) {
-                    Ok(EvalValue::String(val.clone()))
+                    super::builtin_functions::json::json_to_eval_value(val)
                 } else {
                     Err(ReaperError::InvalidPolicy {
                         reason: format!("Undefined variable: {}", var_name),
@@ -82,14 +99,7 @@ impl ReapAstEvaluator {
                         // Get resource entity attribute
                         self.get_entity_attr_by_name(context.resource_id, attribute)
                     }
-                    "context" => {
-                        // Request context values are flat strings.
-                        Ok(context
-                            .request_context
-                            .get(attribute)
-                            .map(|s| EvalValue::String(s.clone()))
-                            .unwrap_or(EvalValue::Null))
-                    }
+                    "context" => context.context_value(attribute),
                     // The structured request document: navigate without
                     // cloning the whole tree (expressions like
                     // jwt::decode(input.token) hit this path).
@@ -197,6 +207,28 @@ impl ReapAstEvaluator {
                 function,
                 args,
             } => self.evaluate_function_call(namespace.as_deref(), function, args, context),
+
+            Expr::BinaryOp { op, left, right } => {
+                let left = self.evaluate_expr(left, context)?;
+                let right = self.evaluate_expr(right, context)?;
+                Ok(match (eval_number(&left), eval_number(&right)) {
+                    (Some(l), Some(r)) => match arith::apply(*op, l, r) {
+                        Some(Number::Int(i)) => EvalValue::Integer(i),
+                        Some(Number::Float(f)) => EvalValue::Float(f),
+                        None => EvalValue::Null,
+                    },
+                    // Non-numeric (or null) operand: undefined, not an error.
+                    _ => EvalValue::Null,
+                })
+            }
         }
     }
 }
+
+fn eval_number(value: &EvalValue) -> Option<Number> {
+    match value {
+        EvalValue::Integer(i) => Some(Number::Int(*i)),
+        EvalValue::Float(f) => Some(Number::Float(*f)),
+        _ => None,
+    }
+}
@@ -7,11 +7,14 @@
 //! - Math namespace: abs, round, floor, ceil, sqrt, pow, min, max, clamp
 //! - Regex namespace: is_valid, escape, matches, replace, split
 //! - JSON namespace: parse, stringify, is_valid
+//! - Net namespace: cidr_contains, cidr_overlaps, ip_in_any, is_ip, is_ipv4, is_ipv6, is_cidr
+//! - Pure value functions: base64, hex, url, glob, semver namespaces; to_number, to_string
 
 use super::builtin_functions;
 use super::types::{EvalContext, EvalValue};
 use super::ReapAstEvaluator;
 use crate::reap::ast::Expr;
+use crate::reap::trace::FuncCallTrace;
 use reaper_core::ReaperError;
 
 /// Runtime guard against function-call recursion. The call-graph DAG check
@@ -69,6 +72,10 @@ impl ReapAstEvaluator {
             return self.call_user_function(i, args, context);
         }
 
+        if let Some(builtin) = crate::reap::builtins::Builtin::lookup(namespace, function) {
+            return self.call_pure_builtin(builtin, args, context);
+        }
+
         match (namespace, function) {
             // Type checking functions (using builtin_functions)
             (None, "is_string") => {
@@ -327,10 +334,11 @@ impl ReapAstEvaluator {
                     // Unbound actor argument: the check is false, never an error.
                     return Ok(EvalValue::Boolean(false));
                 };
+                let graph = self.store.relationships();
+                let _caveats =
+                    graph.caveat_scope_in(&context.caveats, &context.request_context, None);
                 Ok(EvalValue::Boolean(
-                    self.store
-                        .relationships()
-                        .has_relation(object, relation, subject),
+                    graph.has_relation(object, relation, subject),
                 ))
             }
             // rebac::reachable(subject, relation, object, via, max_depth) -> bool
@@ -344,11 +352,12 @@ impl ReapAstEvaluator {
                     return Ok(EvalValue::Boolean(false));
                 };
                 let (via, max) = self.rebac_via_max(args, context, "reachable")?;
-                Ok(EvalValue::Boolean(
-                    self.store
-                        .relationships()
-                        .has_relation_reachable(object, relation, subject, via, max),
-                ))
+                let graph = self.store.relationships();
+                let _caveats =
+                    graph.caveat_scope_in(&context.caveats, &context.request_context, None);
+                Ok(EvalValue::Boolean(graph.has_relation_reachable(
+                    object, relation, subject, via, max,
+                )))
             }
             // rebac::inherited(subject, relation, object, up, max_depth) -> bool
             // relation holds on the object or any ancestor along `up` edges.
@@ -360,11 +369,38 @@ impl ReapAstEvaluator {
                     return Ok(EvalValue::Boolean(false));
                 };
                 let (up, max) = self.rebac_via_max(args, context, "inherited")?;
-                Ok(EvalValue::Boolean(
-                    self.store
-                        .relationships()
-                        .has_relation_inherited(object, relation, subject, up, max),
-                ))
+                let graph = self.store.relationships();
+                let _caveats =
+                    graph.caveat_scope_in(&context.caveats, &context.request_context, None);
+                Ok(EvalValue::Boolean(graph.has_relation_inherited(
+                    object, relation, subject, up, max,
+                )))
+            }
+            // rebac::check(subject, relation, object) -> bool
+            // relation as the deployed schema's rewrites define it.
+            (Some("rebac"), "check") => {
+                if args.len() != 3 {
+                    return Err(ReaperError::InvalidPolicy {
+                        reason: format!(
+                            "rebac::check requires (subject, relation, object), got {} args",
+                            args.len()
+                        ),
+                    });
+                }
+                let Some((subject, relation, object)) = self.rebac_ids_3(args, context, "check")?
+                else {
+                    // Unbound actor argument: the check is false, never an error.
+                    return Ok(EvalValue::Boolean(false));
+                };
+                let graph = self.store.relationships();
+                let _caveats =
+                    graph.caveat_scope_in(&context.caveats, &context.request_context, None);
+                Ok(EvalValue::Boolean(graph.check(
+                    object,
+                    relation,
+                    subject,
+                    &|id| self.store.get(id).map(|e| e.entity_type),
+                )))
             }
 
             // ===== Taint / context provenance (F1 agentic authz) =====
@@ -621,6 +657,73 @@ impl ReapAstEvaluator {
                 builtin_functions::json_is_valid(&value)
             }
 
+            // String formatting (using builtin_functions::strings)
+            (Some("strings"), "format") => {
+                let values: Result<Vec<EvalValue>, _> = args
+                    .iter()
+                    .map(|arg| self.evaluate_expr(arg, context))
+                    .collect();
+                builtin_functions::strings_format(&values?)
+            }
+
+            // Net functions (using builtin_functions::net). Malformed or
+            // non-string operands are a non-match, never an error.
+            (Some("net"), "cidr_contains") => {
+                if args.len() != 2 {
+                    return Err(ReaperError::InvalidPolicy {
+                        reason:
+                            "net::cidr_contains() requires exactly two arguments (cidr, address)"
+                                .to_string(),
+                    });
+                }
+                let cidr = self.evaluate_expr(&args[0], context)?;
+                let addr = self.evaluate_expr(&args[1], context)?;
+                Ok(builtin_functions::net::cidr_contains(&cidr, &addr))
+            }
+
+            (Some("net"), "cidr_overlaps") => {
+                if args.len() != 2 {
+                    return Err(ReaperError::InvalidPolicy {
+                        reason: "net::cidr_overlaps() requires exactly two arguments (cidr, cidr)"
+                            .to_string(),
+                    });
+                }
+                let a = self.evaluate_expr(&args[0], context)?;
+                let b = self.evaluate_expr(&args[1], context)?;
+                Ok(builtin_functions::net::cidr_overlaps(&a, &b))
+            }
+
+            (Some("net"), "ip_in_any") => {
+                if args.len() != 2 {
+                    return Err(ReaperError::InvalidPolicy {
+                        reason: "net::ip_in_any() requires exactly two arguments (address, ranges)"
+                            .to_string(),
+                    });
+                }
+                let addr = self.evaluate_expr(&args[0], context)?;
+                let ranges = self.evaluate_expr(&args[1], context)?;
+                Ok(builtin_functions::net::ip_in_any(&addr, &ranges))
+            }
+
+            (Some("net"), name @ ("is_ip" | "is_ipv4" | "is_ipv6" | "is_cidr")) => {
+                if args.len() != 1 {
+                    return Err(ReaperError::InvalidPolicy {
+                        reason: format!("net::{name}() requires exactly one argument"),
+                    });
+                }
+                let value = self.evaluate_expr(&args[0], context)?;
+                Ok(match name {
+                    "is_ip" => builtin_functions::net::is_ip(&value, None),
+                    "is_ipv4" => {
+                        builtin_functions::net::is_ip(&value, Some(crate::net::IpFamily::V4))
+                    }
+                    "is_ipv6" => {
+                        builtin_functions::net::is_ip(&value, Some(crate::net::IpFamily::V6))
+                    }
+                    _ => builtin_functions::net::is_cidr(&value),
+                })
+            }
+
             _ => Err(ReaperError::InvalidPolicy {
                 reason: format!(
                     "Unknown function: {}",
@@ -634,6 +737,36 @@ impl ReapAstEvaluator {
 }
 
 impl super::ReapAstEvaluator {
+    /// Evaluate a pure value builtin (using builtin_functions::pure).
+    /// Malformed or wrong-typed operands are null/false, never an error;
+    /// only a wrong argument count is.
+    fn call_pure_builtin(
+        &self,
+        builtin: crate::reap::builtins::Builtin,
+        args: &[Expr],
+        context: &EvalContext,
+    ) -> Result<EvalValue, ReaperError> {
+        if args.len() != builtin.arity() {
+            return Err(ReaperError::InvalidPolicy {
+                reason: builtin.arity_error(),
+            });
+        }
+        let values = args
+            .iter()
+            .map(|arg| self.evaluate_expr(arg, context))
+            .collect::<Result<Vec<_>, _>>()?;
+        if builtin == crate::reap::builtins::Builtin::GlobMatch {
+            // Compiled globs share the evaluator's regex cache.
+            let re = crate::reap::builtins::glob_regex_source(
+                &builtin_functions::strings::to_json(&values[0]),
+                &builtin_functions::strings::to_json(&values[2]),
+            )
+            .and_then(|source| self.get_cached_regex(&source).ok());
+            return Ok(builtin_functions::pure::glob_match(re.as_ref(), &values[1]));
+        }
+        Ok(builtin_functions::pure::call(builtin, &values))
+    }
+
     /// Evaluate the common (subject, relation, object) prefix of a rebac::*
     /// call into interned ids. Args are expressions, so any string-producing
     /// value works: the `user`/`resource` pseudo-variables, bound variables,
@@ -804,6 +937,12 @@ impl ReapAstEvaluator {
         let mut func_ctx = context.clone();
         func_ctx.variables = vars;
         let matched = self.evaluate_condition(&def.body, &mut func_ctx)?;
+        if let Some(calls) = &context.calls {
+            calls.borrow_mut().push(FuncCallTrace {
+                function: crate::reap::functions::qualified(def),
+                result: matched,
+            });
+        }
         Ok(EvalValue::Boolean(matched))
     }
 }
@@ -13,15 +13,21 @@
 
 pub mod builtin_functions;
 pub mod builtin_methods;
+mod caveat;
 mod comparison;
 mod comprehension;
 mod entity_access;
+mod explain;
 mod expr_eval;
 mod function_dispatch;
 mod method_dispatch;
 mod regex_methods;
+mod snippet;
 mod types;
 
+pub(crate) use caveat::Caveat;
+pub use snippet::SnippetScope;
+
 use types::{EvalContext, EvalValue};
 
 use super::ast::*;
@@ -54,15 +60,29 @@ pub struct ReapAstEvaluator {
     /// Compiled regex patterns are expensive, cache them by pattern string
     /// Uses parking_lot::Mutex for efficient thread-safe access
     regex_cache: Mutex<HashMap<String, regex::Regex>>,
+    /// Each rule's obligations lowered to JSON once, indexed like
+    /// `policy.rules`, so a decision borrows them instead of re-lowering.
+    obligations: Vec<Option<crate::DecisionObligations>>,
 }
 
 impl ReapAstEvaluator {
     /// Create a new AST evaluator
     pub fn new(store: Arc<DataStore>, policy: Policy) -> Self {
+        let obligations = policy
+            .rules
+            .iter()
+            .map(|rule| {
+                rule.obligations
+                    .as_ref()
+                    .map(|o| o.to_decision())
+                    .filter(|o| !o.is_empty())
+            })
+            .collect();
         Self {
             store,
             policy,
             regex_cache: Mutex::new(HashMap::new()),
+            obligations,
         }
     }
 
@@ -95,64 +115,41 @@ impl ReapAstEvaluator {
         request: &PolicyRequest,
         input: Option<&serde_json::Value>,
     ) -> Result<(PolicyAction, Option<&str>), ReaperError> {
+        self.decide(request, input)
+            .map(|(action, rule)| (action, rule.map(|i| self.policy.rules[i].name.as_str())))
+    }
+
+    /// First-match evaluation reporting the deciding rule's index into
+    /// `policy.rules`; `None` = the per-policy default decided.
+    fn decide(
+        &self,
+        request: &PolicyRequest,
+        input: Option<&serde_json::Value>,
+    ) -> Result<(PolicyAction, Option<usize>), ReaperError> {
         // One evaluation = one ReBAC traversal budget, shared across every
         // condition this policy checks (Plan 08 Phase E).
         crate::data::relationships::reset_traversal_budget();
-
-        // Get user and resource IDs from the DataStore
-        let interner = self.store.interner();
-        let user_id = interner.intern(
-            request
-                .context
-                .get("principal")
-                .map(String::as_str)
-                .unwrap_or(""),
-        );
-        let resource_id = interner.intern(&request.resource);
-        // Actor (F1): intern the request's actor id so `actor.*` resolves the
-        // loaded actor entity, exactly as `user` resolves the principal.
-        let actor_id = request.actor.as_deref().map(|a| interner.intern(a));
-
-        // Create evaluation context
-        let mut request_context = request.context.clone();
-        // Add action to context if not already present
-        request_context.insert("action".to_string(), request.action.clone());
-
-        // Convert the input document once per evaluation (rules then navigate
-        // the tree with zero re-parsing).
-        let input_value = input
-            .map(super::ast_evaluator::builtin_functions::json::json_to_eval_value)
-            .transpose()?;
-
-        let mut context = EvalContext {
-            variables: rebac_pseudo_vars(request),
-            user_id,
-            actor_id,
-            resource_id,
-            request_context,
-            context_provenance: request.context_provenance.clone(),
-            input: input_value,
-        };
+        let mut context = self.eval_context(request, input)?;
 
         // Security-first evaluation: Deny rules ALWAYS take precedence over Allow rules
         // This ensures explicit denies cannot be bypassed by subsequent allow rules
 
         // Phase 1: Evaluate all DENY rules first
-        for rule in &self.policy.rules {
+        for (i, rule) in self.policy.rules.iter().enumerate() {
             if matches!(rule.decision, super::ast::Decision::Deny)
                 && self.evaluate_condition(&rule.condition, &mut context)?
             {
                 // Explicit deny - return immediately, no allow can override this
-                return Ok((PolicyAction::Deny, Some(rule.name.as_str())));
+                return Ok((PolicyAction::Deny, Some(i)));
             }
         }
 
         // Phase 2: No deny matched, now evaluate ALLOW rules
-        for rule in &self.policy.rules {
+        for (i, rule) in self.policy.rules.iter().enumerate() {
             if matches!(rule.decision, super::ast::Decision::Allow)
                 && self.evaluate_condition(&rule.condition, &mut context)?
             {
-                return Ok((PolicyAction::Allow, Some(rule.name.as_str())));
+                return Ok((PolicyAction::Allow, Some(i)));
             }
         }
 
@@ -160,38 +157,31 @@ impl ReapAstEvaluator {
         Ok((self.policy.default_decision.clone().into(), None))
     }
 
-    /// Check-mode evaluation (the conftest/gatekeeper driver): evaluate EVERY
-    /// deny rule against the request + `input` document and collect all
-    /// matching rules as violations, with their `with message` text rendered
-    /// using the variables the rule bound. Decision-mode `evaluate()` is
-    /// untouched (first-match, sub-microsecond path).
-    ///
-    /// `allowed` is true when no deny rule matched AND the policy would allow
-    /// (an allow rule matches or the default is allow).
-    pub fn check_with_input(
+    /// The evaluation context for one request.
+    fn eval_context(
         &self,
         request: &PolicyRequest,
         input: Option<&serde_json::Value>,
-    ) -> Result<CheckResult, ReaperError> {
+    ) -> Result<EvalContext, ReaperError> {
+        // Get user and resource IDs from the DataStore
         let interner = self.store.interner();
-        let user_id = interner.intern(
-            request
-                .context
-                .get("principal")
-                .map(String::as_str)
-                .unwrap_or(""),
-        );
+        let user_id = interner.intern(request.context_str("principal").unwrap_or(""));
         let resource_id = interner.intern(&request.resource);
+        // Actor (F1): intern the request's actor id so `actor.*` resolves the
+        // loaded actor entity, exactly as `user` resolves the principal.
         let actor_id = request.actor.as_deref().map(|a| interner.intern(a));
 
         let mut request_context = request.context.clone();
-        request_context.insert("action".to_string(), request.action.clone());
+        // Add action to context if not already present
+        request_context.insert("action".to_string(), request.action.clone().into());
 
+        // Convert the input document once per evaluation (rules then navigate
+        // the tree with zero re-parsing).
         let input_value = input
             .map(super::ast_evaluator::builtin_functions::json::json_to_eval_value)
             .transpose()?;
 
-        let base = EvalContext {
+        Ok(EvalContext {
             variables: rebac_pseudo_vars(request),
             user_id,
             actor_id,
@@ -199,7 +189,25 @@ impl ReapAstEvaluator {
             request_context,
             context_provenance: request.context_provenance.clone(),
             input: input_value,
-        };
+            calls: None,
+            caveats: Default::default(),
+        })
+    }
+
+    /// Check-mode evaluation (the conftest/gatekeeper driver): evaluate EVERY
+    /// deny rule against the request + `input` document and collect all
+    /// matching rules as violations, with their `with message` text rendered
+    /// using the variables the rule bound. Decision-mode `evaluate()` is
+    /// untouched (first-match, sub-microsecond path).
+    ///
+    /// `allowed` is true when no deny rule matched AND the policy would allow
+    /// (an allow rule matches or the default is allow).
+    pub fn check_with_input(
+        &self,
+        request: &PolicyRequest,
+        input: Option<&serde_json::Value>,
+    ) -> Result<CheckResult, ReaperError> {
+        let base = self.eval_context(request, input)?;
 
         let mut violations = Vec::new();
         for rule in &self.policy.rules {
@@ -291,6 +299,21 @@ impl ReapAstEvaluator {
 
             Condition::Not(cond) => Ok(!self.evaluate_condition(cond, context)?),
 
+            Condition::Quantifier {
+                kind,
+                index,
+                variable,
+                collection,
+                body,
+            } => self.evaluate_quantifier(
+                *kind,
+                index.as_deref(),
+                variable,
+                collection,
+                body,
+                context,
+            ),
+
             Condition::Expr(expr) => {
                 // Evaluate the expression and convert to boolean
                 let value = self.evaluate_expr(expr, context)?;
@@ -356,11 +379,12 @@ impl crate::evaluators::PolicyEvaluator for ReapAstEvaluator {
         &self,
         request: &crate::PolicyRequest,
     ) -> Result<crate::evaluators::NamedOutcome<'_>, reaper_core::ReaperError> {
-        let (decision, rule_name) = self.evaluate_with_input_named(request, None)?;
+        let (decision, rule) = self.decide(request, None)?;
         Ok(crate::evaluators::NamedOutcome {
             decision,
             matched: true,
-            rule_name,
+            rule_name: rule.map(|i| self.policy.rules[i].name.as_str()),
+            obligations: rule.and_then(|i| self.obligations[i].as_ref()),
         })
     }
 
@@ -372,6 +396,13 @@ impl crate::evaluators::PolicyEvaluator for ReapAstEvaluator {
         ReapAstEvaluator::check_with_input(self, request, input)
     }
 
+    fn explain(
+        &self,
+        request: &crate::PolicyRequest,
+    ) -> Result<crate::reap::EvaluationTrace, reaper_core::ReaperError> {
+        self.explain_with_input(request, None)
+    }
+
     // D2 secondary: AST-side resource extraction is a follow-up. AST-fallback
     // policies are the uncommon case (constructs the compiler doesn't yet
     // support), so we keep the trait default `resource_index_terms() -> None`
@@ -487,7 +518,7 @@ mod tests {
         let evaluator = ReapAstEvaluator::new(store, policy);
 
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), "alice".to_string());
+        context.insert("principal".to_string(), "alice".into());
 
         let request = PolicyRequest {
             resource: "doc1".to_string(),
@@ -515,7 +546,7 @@ mod tests {
         let evaluator = ReapAstEvaluator::new(store, policy);
 
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), "bob".to_string());
+        context.insert("principal".to_string(), "bob".into());
 
         let request = PolicyRequest {
             resource: "doc1".to_string(),
@@ -544,7 +575,7 @@ mod tests {
 
         // Alice has 8 years - should allow
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), "alice".to_string());
+        context.insert("principal".to_string(), "alice".into());
         let request = PolicyRequest {
             resource: "doc1".to_string(),
             action: "read".to_string(),
@@ -557,7 +588,7 @@ mod tests {
 
         // Bob has 3 years - should deny
         let mut context2 = HashMap::new();
-        context2.insert("principal".to_string(), "bob".to_string());
+        context2.insert("principal".to_string(), "bob".into());
         let request2 = PolicyRequest {
             resource: "doc1".to_string(),
             action: "read".to_string(),
@@ -589,7 +620,7 @@ mod tests {
 
         // Alice: 8 years, active=true - should allow
         let mut context1 = HashMap::new();
-        context1.insert("principal".to_string(), "alice".to_string());
+        context1.insert("principal".to_string(), "alice".into());
         let request1 = PolicyRequest {
             resource: "doc1".to_string(),
             action: "read".to_string(),
@@ -604,7 +635,7 @@ mod tests {
 
         // Charlie: 6 years, active=false - should deny
         let mut context2 = HashMap::new();
-        context2.insert("principal".to_string(), "charlie".to_string());
+        context2.insert("principal".to_string(), "charlie".into());
         let request2 = PolicyRequest {
             resource: "doc1".to_string(),
             action: "read".to_string(),
@@ -640,7 +671,7 @@ mod tests {
         let evaluator = ReapAstEvaluator::new(store, policy);
 
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), "alice".to_string());
+        context.insert("principal".to_string(), "alice".into());
 
         let request = PolicyRequest {
             resource: "doc1".to_string(),
@@ -672,7 +703,7 @@ mod tests {
         let evaluator = ReapAstEvaluator::new(store, policy);
 
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), "alice".to_string());
+        context.insert("principal".to_string(), "alice".into());
 
         let request = PolicyRequest {
             resource: "doc1".to_string(),
@@ -706,7 +737,7 @@ mod tests {
         let evaluator = ReapAstEvaluator::new(store, policy);
 
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), "alice".to_string());
+        context.insert("principal".to_string(), "alice".into());
 
         let request = PolicyRequest {
             resource: "doc1".to_string(),
@@ -741,7 +772,7 @@ mod tests {
         let evaluator = ReapAstEvaluator::new(store, policy);
 
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), "alice".to_string());
+        context.insert("principal".to_string(), "alice".into());
 
         let request = PolicyRequest {
             resource: "doc1".to_string(),
@@ -775,7 +806,7 @@ mod tests {
         let evaluator = ReapAstEvaluator::new(store, policy);
 
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), "alice".to_string());
+        context.insert("principal".to_string(), "alice".into());
 
         let request = PolicyRequest {
             resource: "doc1".to_string(),
@@ -811,7 +842,7 @@ mod tests {
         let evaluator = ReapAstEvaluator::new(store, policy);
 
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), "alice".to_string());
+        context.insert("principal".to_string(), "alice".into());
 
         let request = PolicyRequest {
             resource: "doc1".to_string(),
@@ -844,7 +875,7 @@ mod tests {
         let evaluator = ReapAstEvaluator::new(store, policy);
 
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), "alice".to_string());
+        context.insert("principal".to_string(), "alice".into());
 
         let request = PolicyRequest {
             resource: "doc1".to_string(),
@@ -876,7 +907,7 @@ mod tests {
         let evaluator = ReapAstEvaluator::new(store, policy);
 
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), "alice".to_string());
+        context.insert("principal".to_string(), "alice".into());
 
         let request = PolicyRequest {
             resource: "doc1".to_string(),
@@ -908,7 +939,7 @@ mod tests {
         let evaluator = ReapAstEvaluator::new(store, policy);
 
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), "alice".to_string());
+        context.insert("principal".to_string(), "alice".into());
 
         let request = PolicyRequest {
             resource: "doc1".to_string(),
@@ -942,7 +973,7 @@ mod tests {
         let evaluator = ReapAstEvaluator::new(store, policy);
 
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), "alice".to_string());
+        context.insert("principal".to_string(), "alice".into());
 
         let request = PolicyRequest {
             resource: "doc1".to_string(),
@@ -970,10 +1001,9 @@ fn rebac_pseudo_vars(request: &PolicyRequest) -> HashMap<String, types::EvalValu
         "user".to_string(),
         types::EvalValue::String(
             request
-                .context
-                .get("principal")
-                .cloned()
-                .unwrap_or_default(),
+                .context_str("principal")
+                .unwrap_or_default()
+                .to_string(),
         ),
     );
     vars.insert(
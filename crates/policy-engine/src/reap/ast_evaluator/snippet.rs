@@ -0,0 +1,149 @@
+//! Standalone snippets (`reaper-cli repl`): one expression or condition,
+//! typed outside any rule, evaluated against a request with the policy's
+//! funcs in scope.
+//!
+//! A snippet is parsed by wrapping it in a throwaway rule, so it accepts
+//! exactly what a rule condition or a `:=` right-hand side accepts. Three
+//! forms, tried in order:
+//!
+//! - `name := value` binds `name` in the [`SnippetScope`] for later snippets;
+//! - a value (`user.groups.intersection(resource.groups).count()`, a
+//!   comprehension, a comparison) evaluates to that value;
+//! - anything else must be a condition (`a && !b`) and evaluates to a bool.
+
+use super::types::EvalValue;
+use super::ReapAstEvaluator;
+use crate::reap::ast::{Condition, Policy};
+use crate::reap::parser::ReapParser;
+use crate::PolicyRequest;
+use reaper_core::ReaperError;
+use std::collections::HashMap;
+
+/// Variables bound by earlier snippets.
+#[derive(Debug, Default, Clone)]
+pub struct SnippetScope {
+    variables: HashMap<String, EvalValue>,
+}
+
+impl SnippetScope {
+    /// Bound variable names, sorted.
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.variables.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// The value of one variable, as JSON.
+    pub fn get(&self, name: &str) -> Option<serde_json::Value> {
+        self.variables.get(name).map(to_json)
+    }
+
+    pub fn clear(&mut self) {
+        self.variables.clear();
+    }
+}
+
+impl ReapAstEvaluator {
+    /// Evaluate one snippet against `request` (see the module docs for the
+    /// accepted forms). Variables the snippet binds — `x := ...`, or
+    /// assignments inside a condition — are kept in `scope`. Sets come back
+    /// as JSON arrays.
+    pub fn evaluate_snippet(
+        &self,
+        source: &str,
+        request: &PolicyRequest,
+        input: Option<&serde_json::Value>,
+        scope: &mut SnippetScope,
+    ) -> Result<serde_json::Value, ReaperError> {
+        crate::data::relationships::reset_traversal_budget();
+        let snippet = parse_snippet(source)?;
+        let mut context = self.eval_context(request, input)?;
+        let pseudo: Vec<String> = context.variables.keys().cloned().collect();
+        context
+            .variables
+            .extend(scope.variables.iter().map(|(k, v)| (k.clone(), v.clone())));
+
+        let value = match &snippet {
+            Snippet::Bind(Condition::Assignment { variable, value }) => {
+                let value = self.evaluate_assignment_value(value, &context)?;
+                context.variables.insert(variable.clone(), value.clone());
+                value
+            }
+            Snippet::Value(Condition::Assignment { value, .. }) => {
+                self.evaluate_assignment_value(value, &context)?
+            }
+            Snippet::Bind(cond) | Snippet::Value(cond) | Snippet::Condition(cond) => {
+                EvalValue::Boolean(self.evaluate_condition(cond, &mut context)?)
+            }
+        };
+
+        for name in pseudo {
+            context.variables.remove(&name);
+        }
+        context.variables.remove(VALUE);
+        scope.variables = context.variables;
+        Ok(to_json(&value))
+    }
+}
+
+/// The variable a bare value is bound to while parsing.
+const VALUE: &str = "repl_value";
+
+enum Snippet {
+    /// `name := value`.
+    Bind(Condition),
+    /// A value, parsed as `repl_value := value`.
+    Value(Condition),
+    Condition(Condition),
+}
+
+fn parse_snippet(source: &str) -> Result<Snippet, ReaperError> {
+    let source = source.trim();
+    let condition = match parse_condition(source) {
+        Ok(Condition::Assignment { variable, value }) if variable != VALUE => {
+            return Ok(Snippet::Bind(Condition::Assignment { variable, value }));
+        }
+        other => other,
+    };
+    if let Ok(value) = parse_condition(&format!("{VALUE} := {source}")) {
+        if matches!(value, Condition::Assignment { .. }) {
+            return Ok(Snippet::Value(value));
+        }
+    }
+    condition
+        .map(Snippet::Condition)
+        .map_err(|_| ReaperError::InvalidPolicy {
+            reason: format!("not an expression or condition: {source}"),
+        })
+}
+
+/// `source` as the condition of a throwaway rule.
+fn parse_condition(source: &str) -> Result<Condition, ReaperError> {
+    let wrapped = format!(
+        "policy repl {{\n    default: deny,\n    rule repl {{\n        allow if {{\n{source}\n        }}\n    }}\n}}\n"
+    );
+    let Policy { mut rules, .. } = ReapParser::parse(&wrapped)?;
+    rules
+        .pop()
+        .map(|rule| rule.condition)
+        .ok_or_else(|| ReaperError::InvalidPolicy {
+            reason: "empty snippet".to_string(),
+        })
+}
+
+fn to_json(value: &EvalValue) -> serde_json::Value {
+    use serde_json::Value as J;
+    match value {
+        EvalValue::String(s) => J::String(s.clone()),
+        EvalValue::Integer(i) => J::from(*i),
+        EvalValue::Float(f) => serde_json::Number::from_f64(*f).map_or(J::Null, J::Number),
+        EvalValue::Boolean(b) => J::Bool(*b),
+        EvalValue::Null => J::Null,
+        EvalValue::Array(items) | EvalValue::Set(items) => {
+            J::Array(items.iter().map(to_json).collect())
+        }
+        EvalValue::Object(map) => {
+            J::Object(map.iter().map(|(k, v)| (k.clone(), to_json(v))).collect())
+        }
+    }
+}
@@ -5,8 +5,11 @@
 //! - EvalValue: Runtime value representation for policy expressions
 
 use crate::data::EntityId;
+use crate::reap::trace::FuncCallTrace;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
+use std::rc::Rc;
 
 /// Evaluation context holding variable bindings
 #[derive(Debug, Clone)]
@@ -21,8 +24,9 @@ pub(super) struct EvalContext {
     pub(super) actor_id: Option<EntityId>,
     /// Resource entity from request
     pub(super) resource_id: EntityId,
-    /// Request context (includes action and other attributes)
-    pub(super) request_context: HashMap<String, String>,
+    /// Request context (includes action and other attributes), raw JSON;
+    /// values convert to `EvalValue` as they are read.
+    pub(super) request_context: HashMap<String, serde_json::Value>,
     /// Per-key context provenance (F1 taint). `None` = taint mode off (every
     /// key platform-trusted); `Some(map)` = taint on, unlabeled keys are the
     /// `Llm` floor. Drives the `taint::level`/`taint::trusted` DSL builtins.
@@ -30,16 +34,41 @@ pub(super) struct EvalContext {
     /// Structured request document (`input`): arbitrary nested JSON converted
     /// once per evaluation. None when the request carries no document.
     pub(super) input: Option<EvalValue>,
+    /// Where `func` calls are recorded while an evaluation is being traced
+    /// (shared with the scopes func bodies run in). `None` otherwise.
+    pub(super) calls: Option<Rc<RefCell<Vec<FuncCallTrace>>>>,
+    /// `request_context` as ReBAC conditions install it for edge caveats,
+    /// copied once per evaluation.
+    pub(super) caveats: crate::data::CaveatContextCell,
 }
 
 impl EvalContext {
+    /// `context.<path>` as an `EvalValue`. A key that literally contains
+    /// the dots wins; otherwise the path navigates nested objects. Absent
+    /// reads are Null.
+    pub(super) fn context_value(&self, path: &str) -> Result<EvalValue, reaper_core::ReaperError> {
+        let value = self.request_context.get(path).or_else(|| {
+            let (root, rest) = path.split_once('.')?;
+            rest.split('.')
+                .try_fold(self.request_context.get(root)?, |node, key| node.get(key))
+        });
+        match value {
+            Some(v) => super::builtin_functions::json::json_to_eval_value(v),
+            None => Ok(EvalValue::Null),
+        }
+    }
+
     /// Effective trust of one context key under the fail-untrusted rule —
     /// the same semantics as [`crate::PolicyRequest::context_trust`], but on
     /// the eval-side provenance snapshot.
     pub(super) fn context_trust(&self, key: &str) -> crate::TrustLevel {
         match &self.context_provenance {
             None => crate::TrustLevel::Platform,
-            Some(map) => map.get(key).copied().unwrap_or(crate::TrustLevel::Llm),
+            Some(map) => map
+                .get(key)
+                .or_else(|| map.get(crate::engine::context_root(key)))
+                .copied()
+                .unwrap_or(crate::TrustLevel::Llm),
         }
     }
 }
@@ -0,0 +1,363 @@
+//! Pure value builtins — `base64::`, `hex::`, `url::`, `glob::match`,
+//! `semver::` and the `to_number`/`to_string` conversions — shared by the
+//! AST interpreter and the compiled evaluator so the two cannot drift.
+//!
+//! Like `net::`, every function is total: a wrong-typed or malformed
+//! argument (undecodable text, a bad glob or version) yields `null`, or
+//! `false` for the boolean functions, never an error. These functions read
+//! request data, and a garbled header must fail closed rather than abort the
+//! evaluation.
+
+use base64::engine::general_purpose::{GeneralPurpose, GeneralPurposeConfig};
+use base64::engine::DecodePaddingMode;
+use base64::{alphabet, Engine};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::Ordering;
+
+/// A pure builtin, resolved from its `(namespace, name)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Builtin {
+    Base64Encode,
+    Base64Decode,
+    Base64UrlEncode,
+    Base64UrlDecode,
+    HexEncode,
+    HexDecode,
+    UrlEncode,
+    UrlDecode,
+    GlobMatch,
+    SemverCompare,
+    SemverSatisfies,
+    ToNumber,
+    ToString,
+}
+
+impl Builtin {
+    pub(crate) fn lookup(namespace: Option<&str>, function: &str) -> Option<Self> {
+        Some(match (namespace, function) {
+            (Some("base64"), "encode") => Builtin::Base64Encode,
+            (Some("base64"), "decode") => Builtin::Base64Decode,
+            (Some("base64"), "url_encode") => Builtin::Base64UrlEncode,
+            (Some("base64"), "url_decode") => Builtin::Base64UrlDecode,
+            (Some("hex"), "encode") => Builtin::HexEncode,
+            (Some("hex"), "decode") => Builtin::HexDecode,
+            (Some("url"), "encode") => Builtin::UrlEncode,
+            (Some("url"), "decode") => Builtin::UrlDecode,
+            (Some("glob"), "match") => Builtin::GlobMatch,
+            (Some("semver"), "compare") => Builtin::SemverCompare,
+            (Some("semver"), "satisfies") => Builtin::SemverSatisfies,
+            (None, "to_number") => Builtin::ToNumber,
+            (None, "to_string") => Builtin::ToString,
+            _ => return None,
+        })
+    }
+
+    /// The qualified name, as written in policy source.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Builtin::Base64Encode => "base64::encode",
+            Builtin::Base64Decode => "base64::decode",
+            Builtin::Base64UrlEncode => "base64::url_encode",
+            Builtin::Base64UrlDecode => "base64::url_decode",
+            Builtin::HexEncode => "hex::encode",
+            Builtin::HexDecode => "hex::decode",
+            Builtin::UrlEncode => "url::encode",
+            Builtin::UrlDecode => "url::decode",
+            Builtin::GlobMatch => "glob::match",
+            Builtin::SemverCompare => "semver::compare",
+            Builtin::SemverSatisfies => "semver::satisfies",
+            Builtin::ToNumber => "to_number",
+            Builtin::ToString => "to_string",
+        }
+    }
+
+    pub(crate) fn arity(self) -> usize {
+        match self {
+            Builtin::GlobMatch => 3,
+            Builtin::SemverCompare | Builtin::SemverSatisfies => 2,
+            _ => 1,
+        }
+    }
+
+    /// Whether the result is always a boolean (usable as a bare condition).
+    pub(crate) fn returns_bool(self) -> bool {
+        matches!(self, Builtin::GlobMatch | Builtin::SemverSatisfies)
+    }
+
+    /// The arity error both evaluators report.
+    pub(crate) fn arity_error(self) -> String {
+        let n = self.arity();
+        format!(
+            "{}() requires exactly {n} argument{}",
+            self.name(),
+            if n == 1 { "" } else { "s" }
+        )
+    }
+
+    /// Apply the builtin. `args` must hold exactly [`Builtin::arity`] values.
+    pub(crate) fn call(self, args: &[Value]) -> Value {
+        let arg = |i: usize| args.get(i).unwrap_or(&Value::Null);
+        match self {
+            Builtin::Base64Encode => map_str(arg(0), |s| Some(STANDARD.encode(s))),
+            Builtin::Base64Decode => map_str(arg(0), |s| utf8(STANDARD.decode(s).ok()?)),
+            Builtin::Base64UrlEncode => map_str(arg(0), |s| Some(URL_SAFE.encode(s))),
+            Builtin::Base64UrlDecode => map_str(arg(0), |s| utf8(URL_SAFE.decode(s).ok()?)),
+            Builtin::HexEncode => map_str(arg(0), |s| Some(hex::encode(s))),
+            Builtin::HexDecode => map_str(arg(0), |s| utf8(hex::decode(s).ok()?)),
+            Builtin::UrlEncode => map_str(arg(0), |s| Some(url_encode(s))),
+            Builtin::UrlDecode => map_str(arg(0), url_decode),
+            Builtin::GlobMatch => {
+                Value::Bool(glob_regex(arg(0), arg(2)).is_some_and(|re| glob_matches(&re, arg(1))))
+            }
+            Builtin::SemverCompare => match (version(arg(0)), version(arg(1))) {
+                (Some(a), Some(b)) => Value::from(match precedence(&a, &b) {
+                    Ordering::Less => -1,
+                    Ordering::Equal => 0,
+                    Ordering::Greater => 1,
+                }),
+                _ => Value::Null,
+            },
+            Builtin::SemverSatisfies => Value::Bool(satisfies(arg(0), arg(1))),
+            Builtin::ToNumber => to_number(arg(0)),
+            Builtin::ToString => Value::String(match arg(0) {
+                Value::String(s) => s.clone(),
+                other => super::interpolate::render(&normalize_numbers(other)),
+            }),
+        }
+    }
+}
+
+/// Padded on encode; padding optional on decode.
+const STANDARD: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// URL-safe alphabet, unpadded on encode (as in JWTs); padding optional on
+/// decode.
+const URL_SAFE: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::URL_SAFE,
+    GeneralPurposeConfig::new()
+        .with_encode_padding(false)
+        .with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+fn map_str(value: &Value, f: impl FnOnce(&str) -> Option<String>) -> Value {
+    match value {
+        Value::String(s) => f(s).map(Value::String).unwrap_or(Value::Null),
+        _ => Value::Null,
+    }
+}
+
+/// Decoded bytes are only a value when they are text.
+fn utf8(bytes: Vec<u8>) -> Option<String> {
+    String::from_utf8(bytes).ok()
+}
+
+/// Percent-encode every byte outside the RFC 3986 unreserved set.
+fn url_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for &b in s.as_bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~') {
+            out.push(char::from(b));
+        } else {
+            out.push_str(&format!("%{b:02X}"));
+        }
+    }
+    out
+}
+
+/// Decode `%XX` escapes; `+` stays a literal plus. A malformed escape or a
+/// non-UTF-8 result is `None`.
+fn url_decode(s: &str) -> Option<String> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let (hi, lo) = (bytes.get(i + 1)?, bytes.get(i + 2)?);
+            let digit = |b: &u8| char::from(*b).to_digit(16);
+            out.push(u8::try_from(digit(hi)? * 16 + digit(lo)?).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    utf8(out)
+}
+
+/// Compile a glob to an anchored regex. `delimiters` is a list of
+/// single-character separators (`[]` means `["."]`) or `null` for none.
+/// `None` for a malformed pattern or delimiter list.
+///
+/// Syntax: `*` (any run without a separator), `**` (any run), `?` (one
+/// non-separator character), `[abc]`/`[a-z]`/`[!abc]`, `{a,b}` alternatives
+/// (nestable) and `\` escapes. Compiling to a regex keeps matching linear in
+/// the input however the pattern nests.
+pub(crate) fn glob_regex(pattern: &Value, delimiters: &Value) -> Option<regex::Regex> {
+    regex::Regex::new(&glob_regex_source(pattern, delimiters)?).ok()
+}
+
+/// The regex source [`glob_regex`] compiles.
+pub(crate) fn glob_regex_source(pattern: &Value, delimiters: &Value) -> Option<String> {
+    let Value::String(pattern) = pattern else {
+        return None;
+    };
+    let delimiters: Vec<char> = match delimiters {
+        Value::Null => Vec::new(),
+        Value::Array(items) if items.is_empty() => vec!['.'],
+        Value::Array(items) => items
+            .iter()
+            .map(|d| {
+                let mut chars = d.as_str()?.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Some(c),
+                    _ => None,
+                }
+            })
+            .collect::<Option<_>>()?,
+        _ => return None,
+    };
+    let single = if delimiters.is_empty() {
+        ".".to_string()
+    } else {
+        let class: String = delimiters
+            .iter()
+            .map(|c| format!("\\x{{{:x}}}", u32::from(*c)))
+            .collect();
+        format!("[^{class}]")
+    };
+
+    let mut out = String::from("(?s)^(?:");
+    let mut chars = pattern.chars().peekable();
+    let mut depth = 0usize;
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => push_literal(&mut out, chars.next()?),
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                out.push_str(".*");
+            }
+            '*' => {
+                out.push_str(&single);
+                out.push('*');
+            }
+            '?' => out.push_str(&single),
+            '[' => {
+                let negated = chars.peek() == Some(&'!');
+                if negated {
+                    chars.next();
+                }
+                out.push_str(if negated { "[^" } else { "[" });
+                let mut empty = true;
+                loop {
+                    let c = chars.next()?;
+                    if c == ']' && !empty {
+                        break;
+                    }
+                    let c = if c == '\\' { chars.next()? } else { c };
+                    out.push_str(&format!("\\x{{{:x}}}", u32::from(c)));
+                    if chars.peek() == Some(&'-') {
+                        chars.next();
+                        let hi = match chars.next()? {
+                            '\\' => chars.next()?,
+                            ']' => return None,
+                            hi => hi,
+                        };
+                        if hi < c {
+                            return None;
+                        }
+                        out.push_str(&format!("-\\x{{{:x}}}", u32::from(hi)));
+                    }
+                    empty = false;
+                }
+                out.push(']');
+            }
+            '{' => {
+                depth += 1;
+                out.push_str("(?:");
+            }
+            ',' if depth > 0 => out.push('|'),
+            '}' if depth > 0 => {
+                depth -= 1;
+                out.push(')');
+            }
+            c => push_literal(&mut out, c),
+        }
+    }
+    if depth > 0 {
+        return None;
+    }
+    out.push_str(")$");
+    Some(out)
+}
+
+fn push_literal(out: &mut String, c: char) {
+    out.push_str(&regex::escape(c.encode_utf8(&mut [0; 4])));
+}
+
+/// Match a compiled glob against a path; a non-string path never matches.
+pub(crate) fn glob_matches(re: &regex::Regex, path: &Value) -> bool {
+    path.as_str().is_some_and(|p| re.is_match(p))
+}
+
+/// A semantic version, with an optional leading `v`.
+fn version(value: &Value) -> Option<semver::Version> {
+    let s = value.as_str()?;
+    semver::Version::parse(s.strip_prefix('v').unwrap_or(s)).ok()
+}
+
+/// SemVer precedence: build metadata is ignored.
+fn precedence(a: &semver::Version, b: &semver::Version) -> Ordering {
+    (a.major, a.minor, a.patch, &a.pre).cmp(&(b.major, b.minor, b.patch, &b.pre))
+}
+
+/// Cargo-style requirements (`^1.2`, `~1.2.3`, `>=1.0, <2`, `*`).
+fn satisfies(version_value: &Value, requirement: &Value) -> bool {
+    match (version(version_value), requirement.as_str()) {
+        (Some(v), Some(req)) => semver::VersionReq::parse(req).is_ok_and(|req| req.matches(&v)),
+        _ => false,
+    }
+}
+
+/// Numbers pass through; strings parse as an integer, then as a finite
+/// float; booleans are 1/0; anything else is `null`.
+fn to_number(value: &Value) -> Value {
+    match value {
+        Value::Number(_) => normalize_numbers(value),
+        Value::String(s) => {
+            if let Ok(i) = s.parse::<i64>() {
+                Value::from(i)
+            } else {
+                s.parse::<f64>()
+                    .ok()
+                    .and_then(serde_json::Number::from_f64)
+                    .map(Value::Number)
+                    .unwrap_or(Value::Null)
+            }
+        }
+        Value::Bool(b) => Value::from(i64::from(*b)),
+        _ => Value::Null,
+    }
+}
+
+/// Numbers outside `i64` become floats, as they do when the interpreter
+/// reads them, so raw JSON from the compiled path renders identically.
+fn normalize_numbers(value: &Value) -> Value {
+    match value {
+        Value::Number(n) if n.as_i64().is_none() => n
+            .as_f64()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number)
+            .unwrap_or(Value::Null),
+        Value::Array(items) => Value::Array(items.iter().map(normalize_numbers).collect()),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), normalize_numbers(v)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
@@ -5,13 +5,14 @@
 //! The bundle format preserves the full Reaper Policy AST, allowing the compiled
 //! ReaperDSLEvaluator to be rebuilt at deployment time with full functionality.
 
-use super::ast::{Decision as ReapDecision, Policy};
-use super::compiler;
-use crate::data::DataStore;
+use super::ast::{Condition, Decision as ReapDecision, Expr, FuncDef, ImportDecl, Policy, Rule};
+use super::{compiler, typecheck};
+use crate::data::{AttributeSchema, DataStore, EntitySchema, EntityTypeSchema, RequestSchema};
 use crate::engine::{EnhancedPolicy, PolicyAction, PolicyLanguage, PolicyRule};
 use crate::evaluators::PolicyEvaluator;
 use reaper_core::ReaperError;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::sync::Arc;
 use std::time::SystemTime;
 use uuid::Uuid;
@@ -49,6 +50,12 @@ pub struct BundleFormat {
 pub struct PolicyBundle {
     pub metadata: BundleFormat,
     pub policy: Policy,
+    /// Declared entity schema the policy was type-checked against (wire
+    /// version 5, or 6 with relation rewrites). Deploys re-check the policy
+    /// against it, and agents validate data loads and expand `rebac::check`
+    /// with it.
+    #[serde(default)]
+    pub schema: Option<EntitySchema>,
 }
 
 /// The v2 wire shape of a policy — exactly the four fields v2 encoders wrote.
@@ -60,7 +67,53 @@ struct PolicyWireV2 {
     name: String,
     metadata: std::collections::HashMap<String, String>,
     default_decision: ReapDecision,
-    rules: Vec<super::ast::Rule>,
+    rules: Vec<RuleWireV3>,
+}
+
+/// The v3 wire shape of a policy: v2 plus `functions`/`imports`, with rules
+/// still in their pre-obligations shape.
+#[derive(Serialize, Deserialize)]
+struct PolicyWireV3 {
+    name: String,
+    metadata: std::collections::HashMap<String, String>,
+    default_decision: ReapDecision,
+    rules: Vec<RuleWireV3>,
+    functions: Vec<FuncDef>,
+    imports: Vec<ImportDecl>,
+}
+
+/// The v2/v3 wire shape of a rule — everything before `obligations`.
+#[derive(Serialize, Deserialize)]
+struct RuleWireV3 {
+    name: String,
+    decision: ReapDecision,
+    condition: Condition,
+    message: Option<Expr>,
+}
+
+impl From<RuleWireV3> for Rule {
+    fn from(r: RuleWireV3) -> Self {
+        Rule {
+            name: r.name,
+            decision: r.decision,
+            condition: r.condition,
+            message: r.message,
+            obligations: None,
+        }
+    }
+}
+
+/// Only used for policies whose rules declare no obligations (see
+/// [`wire_version`]), so nothing is dropped.
+impl From<Rule> for RuleWireV3 {
+    fn from(r: Rule) -> Self {
+        RuleWireV3 {
+            name: r.name,
+            decision: r.decision,
+            condition: r.condition,
+            message: r.message,
+        }
+    }
 }
 
 impl From<PolicyWireV2> for Policy {
@@ -69,7 +122,7 @@ impl From<PolicyWireV2> for Policy {
             name: p.name,
             metadata: p.metadata,
             default_decision: p.default_decision,
-            rules: p.rules,
+            rules: p.rules.into_iter().map(Rule::from).collect(),
             functions: Vec::new(),
             imports: Vec::new(),
         }
@@ -82,22 +135,152 @@ impl From<Policy> for PolicyWireV2 {
             name: p.name,
             metadata: p.metadata,
             default_decision: p.default_decision,
-            rules: p.rules,
+            rules: p.rules.into_iter().map(RuleWireV3::from).collect(),
+        }
+    }
+}
+
+impl From<PolicyWireV3> for Policy {
+    fn from(p: PolicyWireV3) -> Self {
+        Policy {
+            name: p.name,
+            metadata: p.metadata,
+            default_decision: p.default_decision,
+            rules: p.rules.into_iter().map(Rule::from).collect(),
+            functions: p.functions,
+            imports: p.imports,
         }
     }
 }
 
+impl From<Policy> for PolicyWireV3 {
+    fn from(p: Policy) -> Self {
+        PolicyWireV3 {
+            name: p.name,
+            metadata: p.metadata,
+            default_decision: p.default_decision,
+            rules: p.rules.into_iter().map(RuleWireV3::from).collect(),
+            functions: p.functions,
+            imports: p.imports,
+        }
+    }
+}
+
+/// The v5 wire shape of an entity schema: entity types before `rewrites`.
+#[derive(Serialize, Deserialize)]
+struct EntitySchemaWireV5 {
+    entity_types: BTreeMap<String, EntityTypeWireV5>,
+    request: RequestSchema,
+    context: Option<BTreeMap<String, AttributeSchema>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EntityTypeWireV5 {
+    attributes: BTreeMap<String, AttributeSchema>,
+    relations: BTreeMap<String, Vec<String>>,
+}
+
+impl From<EntitySchemaWireV5> for EntitySchema {
+    fn from(s: EntitySchemaWireV5) -> Self {
+        EntitySchema {
+            entity_types: s
+                .entity_types
+                .into_iter()
+                .map(|(name, t)| {
+                    let entity_type = EntityTypeSchema {
+                        attributes: t.attributes,
+                        relations: t.relations,
+                        rewrites: BTreeMap::new(),
+                    };
+                    (name, entity_type)
+                })
+                .collect(),
+            request: s.request,
+            context: s.context,
+        }
+    }
+}
+
+/// Only used for schemas that declare no rewrites (see
+/// [`schema_wire_version`]), so nothing is dropped.
+impl From<EntitySchema> for EntitySchemaWireV5 {
+    fn from(s: EntitySchema) -> Self {
+        EntitySchemaWireV5 {
+            entity_types: s
+                .entity_types
+                .into_iter()
+                .map(|(name, t)| {
+                    let entity_type = EntityTypeWireV5 {
+                        attributes: t.attributes,
+                        relations: t.relations,
+                    };
+                    (name, entity_type)
+                })
+                .collect(),
+            request: s.request,
+            context: s.context,
+        }
+    }
+}
+
+/// The oldest wire version that can carry `schema`: 6 if any entity type
+/// declares rewrites, else 5.
+fn schema_wire_version(schema: &EntitySchema) -> u32 {
+    if schema.entity_types.values().any(|t| !t.rewrites.is_empty()) {
+        6
+    } else {
+        5
+    }
+}
+
+/// The oldest wire version that can carry `policy` without loss: 4 if any
+/// rule declares obligations/advice, 3 if it uses functions or imports,
+/// else 2. The format only ratchets forward for policies that need it.
+fn wire_version(policy: &Policy) -> u32 {
+    if policy.rules.iter().any(|r| r.obligations.is_some()) {
+        4
+    } else if !policy.functions.is_empty() || !policy.imports.is_empty() {
+        3
+    } else {
+        2
+    }
+}
+
+/// Encode `policy` in the wire shape of `version` (from [`wire_version`]).
+fn encode_policy<M: Serialize>(
+    metadata: &M,
+    policy: &Policy,
+    version: u32,
+) -> Result<Vec<u8>, postcard::Error> {
+    match version {
+        2 => postcard::to_allocvec(&(metadata, PolicyWireV2::from(policy.clone()))),
+        3 => postcard::to_allocvec(&(metadata, PolicyWireV3::from(policy.clone()))),
+        _ => postcard::to_allocvec(&(metadata, policy)),
+    }
+}
+
+/// Decode a policy written in the wire shape of `version`.
+fn decode_policy(bytes: &[u8], version: u32) -> Result<Policy, postcard::Error> {
+    match version {
+        4.. => postcard::from_bytes(bytes),
+        3 => postcard::from_bytes::<PolicyWireV3>(bytes).map(Policy::from),
+        _ => postcard::from_bytes::<PolicyWireV2>(bytes).map(Policy::from),
+    }
+}
+
 impl PolicyBundle {
     const MAGIC_BYTES: &'static [u8; 4] = b"REAP";
-    /// Format version 3: the policy carries `functions`/`imports` (language
-    /// v3, R4-01 Phase C). Version 2 (postcard, replacing bincode v1.3 —
-    /// RUSTSEC-2025-0141) is still WRITTEN for policies that use neither, so
-    /// v2 engines keep loading function-free bundles; a v3-encoded bundle is
-    /// rejected by v2 engines on its wire version AND its `language_version`
-    /// metadata — fail closed twice over, never silently dropping functions.
-    const FORMAT_VERSION: u32 = 3;
-    /// The function-free wire encoding (see [`Self::FORMAT_VERSION`]).
-    const LEGACY_FORMAT_VERSION: u32 = 2;
+    /// Format version 6: the schema's entity types carry relation
+    /// `rewrites`. Version 5: a declared [`EntitySchema`] follows the policy.
+    /// Version 4: rules carry `obligations` (obligations/advice
+    /// clauses). Version 3 added `functions`/`imports` (language v3, R4-01
+    /// Phase C); version 2 (postcard, replacing bincode v1.3 —
+    /// RUSTSEC-2025-0141) has neither. Each bundle is WRITTEN at the oldest
+    /// version that carries it (see [`wire_version`]), so older engines keep
+    /// loading bundles that don't use newer constructs; a bundle that does is
+    /// rejected by older engines on its wire version — fail closed, never
+    /// silently dropping functions, obligations or the schema.
+    const FORMAT_VERSION: u32 = 6;
 
     /// Create a new bundle from a policy
     pub fn new(policy: Policy) -> Self {
@@ -115,7 +298,19 @@ impl PolicyBundle {
             source_checksum,
         };
 
-        Self { metadata, policy }
+        Self {
+            metadata,
+            policy,
+            schema: None,
+        }
+    }
+
+    /// Attach a declared entity schema, after type-checking the policy
+    /// against it (see [`typecheck`]).
+    pub fn with_schema(mut self, schema: EntitySchema) -> Result<Self, ReaperError> {
+        typecheck::ensure_well_typed(&self.policy, &schema)?;
+        self.schema = Some(schema);
+        Ok(self)
     }
 
     /// Serialize to bytes. Function-free policies encode as wire version 2 —
@@ -127,28 +322,32 @@ impl PolicyBundle {
         // Magic bytes
         bytes.extend_from_slice(Self::MAGIC_BYTES);
 
-        let legacy = self.policy.functions.is_empty() && self.policy.imports.is_empty();
         // Postcard encodes a tuple as its fields concatenated — identical
-        // bytes to the `PolicyBundle { metadata, policy }` struct encoding.
-        let bundle_bytes = if legacy {
-            let metadata = BundleFormat {
-                version: Self::LEGACY_FORMAT_VERSION,
-                ..self.metadata.clone()
-            };
-            postcard::to_allocvec(&(metadata, PolicyWireV2::from(self.policy.clone())))
-        } else {
-            let metadata = BundleFormat {
-                version: Self::FORMAT_VERSION,
-                ..self.metadata.clone()
-            };
-            postcard::to_allocvec(&(metadata, self.policy.clone()))
-        }
-        .map_err(|e| ReaperError::InvalidPolicy {
+        // bytes to a `{ metadata, policy }` struct encoding. A v5/v6
+        // bundle's schema is appended after the policy the same way.
+        let version = match &self.schema {
+            Some(schema) => schema_wire_version(schema),
+            None => wire_version(&self.policy),
+        };
+        let metadata = BundleFormat {
+            version,
+            ..self.metadata.clone()
+        };
+        let serialize_failed = |e: postcard::Error| ReaperError::InvalidPolicy {
             reason: format!("Failed to serialize bundle: {}", e),
-        })?;
-
+        };
+        let bundle_bytes =
+            encode_policy(&metadata, &self.policy, version).map_err(serialize_failed)?;
         bytes.extend_from_slice(&bundle_bytes);
 
+        if let Some(schema) = &self.schema {
+            let schema_bytes = match version {
+                5 => postcard::to_allocvec(&EntitySchemaWireV5::from(schema.clone())),
+                _ => postcard::to_allocvec(schema),
+            };
+            bytes.extend_from_slice(&schema_bytes.map_err(serialize_failed)?);
+        }
+
         Ok(bytes)
     }
 
@@ -179,19 +378,31 @@ impl PolicyBundle {
             });
         }
 
-        let policy: Policy = if metadata.version >= Self::FORMAT_VERSION {
-            postcard::from_bytes(rest).map_err(|e| ReaperError::InvalidPolicy {
-                reason: format!("Failed to deserialize bundle: {}", e),
-            })?
+        let deserialize_failed = |e: postcard::Error| ReaperError::InvalidPolicy {
+            reason: format!("Failed to deserialize bundle: {}", e),
+        };
+        let (policy, schema) = if metadata.version >= 5 {
+            let (policy, rest) =
+                postcard::take_from_bytes::<Policy>(rest).map_err(deserialize_failed)?;
+            let schema = match metadata.version {
+                5 => postcard::from_bytes::<EntitySchemaWireV5>(rest).map(EntitySchema::from),
+                _ => postcard::from_bytes::<EntitySchema>(rest),
+            }
+            .map_err(deserialize_failed)?;
+            // Rewrites are parsed when installed; a malformed one rejects the
+            // bundle here rather than failing after its policy is live.
+            schema.validate()?;
+            (policy, Some(schema))
         } else {
-            postcard::from_bytes::<PolicyWireV2>(rest)
-                .map_err(|e| ReaperError::InvalidPolicy {
-                    reason: format!("Failed to deserialize bundle: {}", e),
-                })?
-                .into()
+            let policy = decode_policy(rest, metadata.version).map_err(deserialize_failed)?;
+            (policy, None)
         };
 
-        let bundle = Self { metadata, policy };
+        let bundle = Self {
+            metadata,
+            policy,
+            schema,
+        };
 
         // DSL language-version check (round-3 Plan 04): the language version
         // rides in the compiled policy's metadata. A bundle whose policy targets
@@ -296,6 +507,12 @@ impl PolicyBundle {
         &self,
         store: Arc<DataStore>,
     ) -> Result<EnhancedPolicy, ReaperError> {
+        // A bundle that declares a schema is type-checked on every deploy,
+        // not only when it was built.
+        if let Some(schema) = &self.schema {
+            typecheck::ensure_well_typed(&self.policy, schema)?;
+        }
+
         // Compile the policy AST using the ReaperDSL compiler
         let evaluator = compiler::compile_policy(self.policy.clone(), store)?;
 
@@ -489,22 +706,42 @@ pub struct PackageMetadata {
     pub policy_count: usize,
 }
 
-/// The v2 wire shape of a package policy entry (see [`PolicyWireV2`]).
+/// A package policy entry in an older wire shape (`P` = [`PolicyWireV2`] or
+/// [`PolicyWireV3`]).
 #[derive(Serialize, Deserialize)]
-struct PolicyEntryWireV2 {
-    policy: PolicyWireV2,
+struct PolicyEntryWire<P> {
+    policy: P,
     priority: u32,
     package: String,
 }
 
+impl<P: From<Policy>> From<&PolicyEntry> for PolicyEntryWire<P> {
+    fn from(e: &PolicyEntry) -> Self {
+        PolicyEntryWire {
+            policy: e.policy.clone().into(),
+            priority: e.priority,
+            package: e.package.clone(),
+        }
+    }
+}
+
+impl<P: Into<Policy>> From<PolicyEntryWire<P>> for PolicyEntry {
+    fn from(e: PolicyEntryWire<P>) -> Self {
+        PolicyEntry {
+            policy: e.policy.into(),
+            priority: e.priority,
+            package: e.package,
+        }
+    }
+}
+
 impl PolicyPackage {
     const MAGIC_BYTES: &'static [u8; 4] = b"REPP"; // Reaper Policy Package
-    /// Format version 3: policies carry `functions`/`imports` (language v3,
-    /// R4-01 Phase C). Version 2 (postcard) is still written when no policy
-    /// in the package uses them, so v2 engines keep loading such packages.
-    const FORMAT_VERSION: u32 = 3;
-    /// The function-free wire encoding (see [`Self::FORMAT_VERSION`]).
-    const LEGACY_FORMAT_VERSION: u32 = 2;
+    /// Format version 4: rules carry obligations. As with single bundles
+    /// (see [`PolicyBundle`]), a package is written at the oldest version
+    /// that carries every policy in it, so older engines keep loading
+    /// packages that use none of the newer constructs.
+    const FORMAT_VERSION: u32 = 4;
 
     /// Create a new package from multiple policies
     ///
@@ -586,39 +823,37 @@ impl PolicyPackage {
         }
     }
 
-    /// Serialize to bytes. Packages whose policies are all function-free
-    /// encode as wire version 2 — loadable by v2 engines.
+    /// Serialize to bytes, at the oldest wire version that carries every
+    /// policy (e.g. all function- and obligation-free → version 2, loadable
+    /// by v2 engines).
     pub fn to_bytes(&self) -> Result<Vec<u8>, ReaperError> {
         let mut bytes = Vec::new();
         bytes.extend_from_slice(Self::MAGIC_BYTES);
 
-        let legacy = self
+        let version = self
             .policies
             .iter()
-            .all(|e| e.policy.functions.is_empty() && e.policy.imports.is_empty());
+            .map(|e| wire_version(&e.policy))
+            .max()
+            .unwrap_or(2);
+        let metadata = PackageMetadata {
+            format_version: version,
+            ..self.metadata.clone()
+        };
         // Tuple encoding == struct encoding under postcard (fields
         // concatenated), mirroring `PolicyBundle::to_bytes`.
-        let bundle_bytes = if legacy {
-            let metadata = PackageMetadata {
-                format_version: Self::LEGACY_FORMAT_VERSION,
-                ..self.metadata.clone()
-            };
-            let entries: Vec<PolicyEntryWireV2> = self
-                .policies
-                .iter()
-                .map(|e| PolicyEntryWireV2 {
-                    policy: e.policy.clone().into(),
-                    priority: e.priority,
-                    package: e.package.clone(),
-                })
-                .collect();
-            postcard::to_allocvec(&(metadata, entries, self.hints.clone()))
-        } else {
-            let metadata = PackageMetadata {
-                format_version: Self::FORMAT_VERSION,
-                ..self.metadata.clone()
-            };
-            postcard::to_allocvec(&(metadata, self.policies.clone(), self.hints.clone()))
+        let bundle_bytes = match version {
+            2 => {
+                let entries: Vec<PolicyEntryWire<PolicyWireV2>> =
+                    self.policies.iter().map(PolicyEntryWire::from).collect();
+                postcard::to_allocvec(&(metadata, entries, &self.hints))
+            }
+            3 => {
+                let entries: Vec<PolicyEntryWire<PolicyWireV3>> =
+                    self.policies.iter().map(PolicyEntryWire::from).collect();
+                postcard::to_allocvec(&(metadata, entries, &self.hints))
+            }
+            _ => postcard::to_allocvec(&(metadata, &self.policies, &self.hints)),
         }
         .map_err(|e| ReaperError::InvalidPolicy {
             reason: format!("Failed to serialize policy package: {}", e),
@@ -652,28 +887,21 @@ impl PolicyPackage {
             });
         }
 
-        let (policies, hints): (Vec<PolicyEntry>, PrecompilationHints) =
-            if metadata.format_version >= Self::FORMAT_VERSION {
-                postcard::from_bytes(rest).map_err(|e| ReaperError::InvalidPolicy {
-                    reason: format!("Failed to deserialize policy package: {}", e),
-                })?
-            } else {
-                let (entries, hints): (Vec<PolicyEntryWireV2>, PrecompilationHints) =
-                    postcard::from_bytes(rest).map_err(|e| ReaperError::InvalidPolicy {
-                        reason: format!("Failed to deserialize policy package: {}", e),
-                    })?;
-                (
-                    entries
-                        .into_iter()
-                        .map(|e| PolicyEntry {
-                            policy: e.policy.into(),
-                            priority: e.priority,
-                            package: e.package,
-                        })
-                        .collect(),
-                    hints,
-                )
-            };
+        fn legacy<P: Into<Policy>>(
+            (entries, hints): (Vec<PolicyEntryWire<P>>, PrecompilationHints),
+        ) -> (Vec<PolicyEntry>, PrecompilationHints) {
+            (entries.into_iter().map(PolicyEntry::from).collect(), hints)
+        }
+        let (policies, hints): (Vec<PolicyEntry>, PrecompilationHints) = match metadata
+            .format_version
+        {
+            4.. => postcard::from_bytes(rest),
+            3 => postcard::from_bytes::<(Vec<PolicyEntryWire<PolicyWireV3>>, _)>(rest).map(legacy),
+            _ => postcard::from_bytes::<(Vec<PolicyEntryWire<PolicyWireV2>>, _)>(rest).map(legacy),
+        }
+        .map_err(|e| ReaperError::InvalidPolicy {
+            reason: format!("Failed to deserialize policy package: {}", e),
+        })?;
 
         let bundle = Self {
             metadata,
@@ -910,6 +1138,10 @@ fn extract_hints_from_expr(
             strings.insert(variable.clone());
             strings.insert(attribute.clone());
         }
+        Expr::BinaryOp { left, right, .. } => {
+            extract_hints_from_expr(left, strings, regex_patterns);
+            extract_hints_from_expr(right, strings, regex_patterns);
+        }
         _ => {}
     }
 }
@@ -959,6 +1191,7 @@ mod tests {
             default_decision: Decision::Deny,
             rules: vec![Rule {
                 message: None,
+                obligations: None,
                 name: "admin".to_string(),
                 decision: Decision::Allow,
                 condition: Condition::True,
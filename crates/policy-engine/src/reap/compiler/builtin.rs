@@ -0,0 +1,154 @@
+//! Pure builtin compilation (`base64::`, `hex::`, `url::`, `glob::match`,
+//! `semver::`, `to_number`, `to_string`).
+//!
+//! Arguments compile only where the compiled read matches the interpreter's:
+//! single-segment `user`/`resource`/`actor` attributes, dotted `input` and
+//! `context` paths, and scalar or array literals. Every other argument
+//! rejects, so the rule takes its per-rule AST fallback.
+
+use super::comparison::operator_to_numeric_op;
+use crate::evaluators::reaper_dsl::{
+    Builtin, BuiltinArg, BuiltinCall, Condition as DslCondition, EntityType, InputPath,
+};
+use crate::reap::ast::{ComparisonRight, Expr, Operator, Value};
+use reaper_core::ReaperError;
+
+/// Lower a call to a pure builtin. A wrong argument count errors here and
+/// again, identically, on the interpreter.
+pub(super) fn lower_builtin_call(
+    function: Builtin,
+    args: &[Expr],
+) -> Result<BuiltinCall, ReaperError> {
+    if args.len() != function.arity() {
+        return Err(ReaperError::InvalidPolicy {
+            reason: function.arity_error(),
+        });
+    }
+    Ok(BuiltinCall {
+        function,
+        args: args.iter().map(lower_arg).collect::<Result<_, _>>()?,
+    })
+}
+
+/// A boolean builtin as a bare condition. A builtin that can return a
+/// non-boolean is an evaluation error there, which only the interpreter
+/// reports.
+pub(super) fn compile_builtin_test(
+    function: Builtin,
+    args: &[Expr],
+) -> Result<DslCondition, ReaperError> {
+    if !function.returns_bool() {
+        return Err(ReaperError::InvalidPolicy {
+            reason: format!(
+                "{}() is not a boolean condition; the rule runs on the AST evaluator",
+                function.name()
+            ),
+        });
+    }
+    Ok(DslCondition::BuiltinTest(lower_builtin_call(
+        function, args,
+    )?))
+}
+
+/// `builtin(...) <op> literal`.
+pub(super) fn compile_builtin_comparison(
+    function: Builtin,
+    args: &[Expr],
+    op: Operator,
+    right: ComparisonRight,
+) -> Result<DslCondition, ReaperError> {
+    let value = match right {
+        // Ordering against null is an evaluation error on the interpreter.
+        ComparisonRight::Value(Value::Null)
+            if !matches!(op, Operator::Equal | Operator::NotEqual) =>
+        {
+            None
+        }
+        ComparisonRight::Value(
+            v @ (Value::String(_)
+            | Value::Integer(_)
+            | Value::Float(_)
+            | Value::Boolean(_)
+            | Value::Null),
+        ) => literal_json(&v),
+        _ => None,
+    }
+    .ok_or_else(|| ReaperError::InvalidPolicy {
+        reason: format!(
+            "{}() comparison is compiled only against a scalar literal; the rule runs on \
+             the AST evaluator",
+            function.name()
+        ),
+    })?;
+    Ok(DslCondition::BuiltinCompare {
+        call: lower_builtin_call(function, args)?,
+        op: operator_to_numeric_op(&op)?,
+        value: value.to_string(),
+    })
+}
+
+fn lower_arg(expr: &Expr) -> Result<BuiltinArg, ReaperError> {
+    match expr {
+        Expr::Literal(v) => match literal_json(v) {
+            Some(json) => Ok(BuiltinArg::Literal(json.to_string())),
+            None => Err(not_compiled(format!("{v:?}"))),
+        },
+        // Function arguments parse as `AttributeAccess`; the pseudo-entity
+        // form (`Variable("input.image.tag")`) reads the same way.
+        Expr::AttributeAccess {
+            variable,
+            attribute,
+        } => lower_path(&format!("{variable}.{attribute}")),
+        Expr::Variable(name) => lower_path(name),
+        other => Err(not_compiled(format!("{other:?}"))),
+    }
+}
+
+fn lower_path(name: &str) -> Result<BuiltinArg, ReaperError> {
+    match name.split_once('.') {
+        Some(("input", path)) => Ok(BuiltinArg::Input(InputPath::from_dotted(path))),
+        // The compiled context reads `resource` from the request's
+        // resource id; the interpreter reads the context map.
+        Some(("context", "resource")) => Err(not_compiled(name.to_string())),
+        Some(("context", path)) => Ok(BuiltinArg::Context(path.to_string())),
+        // Entity attribute stores are flat: a dotted path navigates
+        // nested values on the interpreter only.
+        Some((entity, attribute)) if !attribute.contains('.') => {
+            let entity_type = match entity {
+                "user" => EntityType::User,
+                "resource" => EntityType::Resource,
+                "actor" => EntityType::Actor,
+                _ => return Err(not_compiled(name.to_string())),
+            };
+            Ok(BuiltinArg::Attribute {
+                entity_type,
+                attribute: attribute.to_string(),
+            })
+        }
+        _ => Err(not_compiled(name.to_string())),
+    }
+}
+
+/// Scalars and arrays of them; objects and sets stay on the interpreter.
+fn literal_json(value: &Value) -> Option<serde_json::Value> {
+    Some(match value {
+        Value::String(s) => serde_json::Value::String(s.clone()),
+        Value::Integer(i) => serde_json::Value::from(*i),
+        Value::Float(f) => serde_json::Value::Number(serde_json::Number::from_f64(*f)?),
+        Value::Boolean(b) => serde_json::Value::Bool(*b),
+        Value::Null => serde_json::Value::Null,
+        Value::Array(items) => {
+            serde_json::Value::Array(items.iter().map(literal_json).collect::<Option<_>>()?)
+        }
+        Value::Object(_) | Value::Set(_) => return None,
+    })
+}
+
+fn not_compiled(arg: String) -> ReaperError {
+    ReaperError::InvalidPolicy {
+        reason: format!(
+            "builtin argument {arg} is not compiled (only user/resource/actor attributes, \
+             input and context paths and literals); the rule runs on the AST evaluator"
+        ),
+    }
+}
@@ -0,0 +1,87 @@
+//! Arithmetic comparison compilation.
+//!
+//! Lowers `user.used + input.requested <= user.quota` to an `ArithCompare`.
+//! Operands compile only where the compiled read matches the interpreter's:
+//! single-segment `user`/`resource`/`actor` attributes, dotted `input` and
+//! `context` paths and number literals. Every other operand rejects, so the rule takes its
+//! per-rule AST fallback.
+
+use super::entity::operator_to_numeric_op;
+use crate::evaluators::reaper_dsl::{
+    ArithExpr, ArithOperand, Condition as DslCondition, EntityType, InputPath,
+};
+use crate::reap::ast::{ComparisonLeft, ComparisonRight, Expr, Operator, Value};
+use reaper_core::ReaperError;
+
+/// Does either side carry infix arithmetic?
+pub fn is_arith_comparison(left: &ComparisonLeft, right: &ComparisonRight) -> bool {
+    matches!(left, ComparisonLeft::Expr(Expr::BinaryOp { .. }))
+        || matches!(right, ComparisonRight::Expr(Expr::BinaryOp { .. }))
+}
+
+/// Compile an arithmetic comparison: `user.used + 1 <= user.quota`.
+pub fn compile_arith_comparison(
+    left: ComparisonLeft,
+    op: Operator,
+    right: ComparisonRight,
+) -> Result<DslCondition, ReaperError> {
+    let left = match left {
+        ComparisonLeft::Expr(e) => compile_arith_expr(&e)?,
+        other => return Err(not_compiled(format!("{other:?}"))),
+    };
+    let right = match right {
+        ComparisonRight::Expr(e) => compile_arith_expr(&e)?,
+        other => return Err(not_compiled(format!("{other:?}"))),
+    };
+    Ok(DslCondition::ArithCompare {
+        left,
+        op: operator_to_numeric_op(&op)?,
+        right,
+    })
+}
+
+fn compile_arith_expr(expr: &Expr) -> Result<ArithExpr, ReaperError> {
+    match expr {
+        Expr::BinaryOp { op, left, right } => Ok(ArithExpr::Binary {
+            op: *op,
+            left: Box::new(compile_arith_expr(left)?),
+            right: Box::new(compile_arith_expr(right)?),
+        }),
+        Expr::Literal(Value::Integer(i)) => Ok(ArithExpr::Operand(ArithOperand::Int(*i))),
+        Expr::Literal(Value::Float(f)) => Ok(ArithExpr::Operand(ArithOperand::Float(*f))),
+        // The parser's pseudo-entity form: "user.used", "input.request.cpu".
+        Expr::Variable(name) => {
+            let operand = match name.split_once('.') {
+                Some(("input", path)) => ArithOperand::Input(InputPath::from_dotted(path)),
+                Some(("context", path)) => ArithOperand::Context(path.to_string()),
+                // Entity attribute stores are flat: a dotted path navigates
+                // nested values on the interpreter only.
+                Some((entity, attribute)) if !attribute.contains('.') => {
+                    let entity_type = match entity {
+                        "user" => EntityType::User,
+                        "resource" => EntityType::Resource,
+                        "actor" => EntityType::Actor,
+                        _ => return Err(not_compiled(name.clone())),
+                    };
+                    ArithOperand::Attribute {
+                        entity_type,
+                        attribute: attribute.to_string(),
+                    }
+                }
+                _ => return Err(not_compiled(name.clone())),
+            };
+            Ok(ArithExpr::Operand(operand))
+        }
+        other => Err(not_compiled(format!("{other:?}"))),
+    }
+}
+
+fn not_compiled(operand: String) -> ReaperError {
+    ReaperError::InvalidPolicy {
+        reason: format!(
+            "arithmetic operand {operand} is not compiled (only user/resource/actor \
+             attributes, input and context paths and number literals); the rule runs on the AST \
+             evaluator"
+        ),
+    }
+}
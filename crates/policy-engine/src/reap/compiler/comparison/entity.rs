@@ -27,7 +27,9 @@ fn entity_to_type(entity: &Entity) -> Result<EntityType, ReaperError> {
 }
 
 /// Convert AST Operator to NumericOp
-fn operator_to_numeric_op(op: &Operator) -> Result<NumericOp, ReaperError> {
+pub(in crate::reap::compiler) fn operator_to_numeric_op(
+    op: &Operator,
+) -> Result<NumericOp, ReaperError> {
     match op {
         Operator::Equal => Ok(NumericOp::Equal),
         Operator::NotEqual => Ok(NumericOp::NotEqual),
@@ -104,6 +106,16 @@ pub fn compile_value_comparison(
     // collection to the literal, a compiled-only wrong Deny (caught by the
     // compiled-vs-AST differential when the actor cases extended it).
     if let Some(index) = &left.index {
+        // `context` has no entity binding, so the compiled IndexedEquals
+        // would read nothing and deny (`context.http.headers["x-tenant"]`).
+        // The interpreter walks the typed context value; let it.
+        if matches!(entity_type, EntityType::Context) {
+            return Err(ReaperError::InvalidPolicy {
+                reason: "indexed `context` access is not compiled; the rule runs on the \
+                         AST evaluator"
+                    .to_string(),
+            });
+        }
         return match (&op, &value) {
             (Operator::Equal, Value::String(s)) => Ok(DslCondition::IndexedEquals {
                 entity_type,
@@ -13,6 +13,8 @@ use crate::evaluators::reaper_dsl::{
     StringOperationCondition,
 };
 use crate::reap::ast::{ComparisonRight, Expr, MethodName, Operator, Value};
+use crate::reap::builtins::Builtin;
+use crate::reap::compiler::builtin::compile_builtin_comparison;
 use crate::reap::compiler::helpers::{extract_entity_attr, parse_entity_type};
 use reaper_core::ReaperError;
 
@@ -282,6 +284,18 @@ pub fn compile_expr_comparison(
         };
     }
 
+    // Pure builtins against a literal: semver::compare(input.v, "2.0.0") >= 0
+    if let Expr::FunctionCall {
+        namespace,
+        function,
+        args,
+    } = &expr
+    {
+        if let Some(builtin) = Builtin::lookup(namespace.as_deref(), function) {
+            return compile_builtin_comparison(builtin, args, op, right);
+        }
+    }
+
     Err(ReaperError::InvalidPolicy {
         reason:
             "Expression comparisons only support method calls like .count(), .lower(), .upper()"
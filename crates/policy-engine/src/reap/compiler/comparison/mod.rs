@@ -8,7 +8,9 @@
 //! - `variable`: Variable comparisons (x == "value", x.count() >= 5)
 //! - `expression`: Expression comparisons (user.skills.count() >= 5)
 //! - `membership`: Membership tests ("admin" in user.roles)
+//! - `arithmetic`: Infix arithmetic comparisons (user.used + 1 <= user.quota)
 
+mod arithmetic;
 mod entity;
 mod expression;
 mod membership;
@@ -24,6 +26,8 @@ use crate::reap::ast::{ComparisonLeft, ComparisonRight, Entity, Expr, Operator,
 use crate::reap::compiler::expression::compile_expr_compare_assignment;
 use reaper_core::ReaperError;
 
+pub use arithmetic::{compile_arith_comparison, is_arith_comparison};
+pub(super) use entity::operator_to_numeric_op;
 pub use entity::{compile_attr_comparison, compile_value_comparison};
 pub use expression::compile_expr_comparison;
 pub use membership::compile_membership_test;
@@ -42,6 +46,12 @@ pub fn compile_comparison(
     right: ComparisonRight,
     allow_var_compare: bool,
 ) -> Result<DslCondition, ReaperError> {
+    // Arithmetic on either side: every operand must be numeric, so none of
+    // the shape-specific paths below apply.
+    if is_arith_comparison(&left, &right) {
+        return compile_arith_comparison(left, op, right);
+    }
+
     // Special case: check if this is an "action" or "resource" variable comparison
     if let ComparisonLeft::Expr(Expr::Variable(var_name)) = &left {
         if var_name == "action" || var_name == "resource" {
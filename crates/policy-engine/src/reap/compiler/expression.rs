@@ -32,8 +32,17 @@ pub fn compile_expr_compare_assignment(
     op: Operator,
     right: ComparisonRight,
 ) -> Result<DslCondition, ReaperError> {
-    // Compile the expression to an ExprType
+    // Compile the expression to an ExprType. Builtin results compare with
+    // the interpreter's rules only through `BuiltinCompare`, not the
+    // literal comparison below.
     let expr_type = compile_expr_to_type(expr)?;
+    if matches!(expr_type, ExprType::Builtin(_)) {
+        return Err(ReaperError::InvalidPolicy {
+            reason: "builtin comparison assignments are not compiled; the rule runs on the AST \
+                     evaluator"
+                .to_string(),
+        });
+    }
 
     // Convert operator
     let attr_op = match op {
@@ -324,6 +333,15 @@ pub fn compile_expr_to_type(expr: Expr) -> Result<ExprType, ReaperError> {
             } else {
                 // Chained method call: could be variable.method() or expr.method()
                 let base = compile_expr_to_type(*receiver)?;
+                // Builtin calls read request data and are evaluated only as a
+                // direct assignment value, never as a chain base.
+                if matches!(base, ExprType::Builtin(_)) {
+                    return Err(ReaperError::InvalidPolicy {
+                        reason: "method calls on builtin results are not compiled; the rule \
+                                 runs on the AST evaluator"
+                            .to_string(),
+                    });
+                }
                 let chain_method = match method {
                     // String methods
                     MethodName::Lower => ChainMethod::Lower,
@@ -386,6 +404,11 @@ pub fn compile_expr_to_type(expr: Expr) -> Result<ExprType, ReaperError> {
             function,
             args,
         } => {
+            if let Some(builtin) =
+                crate::reap::builtins::Builtin::lookup(namespace.as_deref(), &function)
+            {
+                return super::builtin::lower_builtin_call(builtin, &args).map(ExprType::Builtin);
+            }
             let ns = namespace.as_deref().unwrap_or("");
             match (ns, function.as_str()) {
                 ("time", "now") => Ok(ExprType::TimeNow),
@@ -34,7 +34,8 @@
 
 use super::super::ast::{
     AssignmentValue, ComparisonLeft, ComparisonRight, Comprehension, ComprehensionIterator,
-    Condition, Entity, EntityAttr, Expr, FuncDef, Index, IterationSource, Policy, Rule, VarAttr,
+    Condition, Entity, EntityAttr, Expr, FuncDef, Index, InterpolationPart, IterationSource,
+    Policy, Rule, VarAttr,
 };
 use crate::reap::functions::{collect_bound_vars_condition, find_function, qualified};
 use reaper_core::ReaperError;
@@ -60,6 +61,7 @@ pub(super) fn inline_policy(policy: Policy) -> Result<Policy, ReaperError> {
             decision: rule.decision.clone(),
             condition,
             message: rule.message.clone(),
+            obligations: rule.obligations.clone(),
         });
     }
     let inlined = Policy {
@@ -186,6 +188,27 @@ impl Inliner<'_> {
                     value,
                 })
             }
+            Condition::Quantifier {
+                kind,
+                index,
+                variable,
+                collection,
+                body,
+            } => {
+                // Like comprehension filters: the body is a condition
+                // position with the block's names bound; its own bindings
+                // don't escape the block.
+                let mut block_bound = bound.clone();
+                block_bound.extend(index.iter().cloned());
+                block_bound.insert(variable.clone());
+                Ok(Condition::Quantifier {
+                    kind: *kind,
+                    index: index.clone(),
+                    variable: variable.clone(),
+                    collection: collection.clone(),
+                    body: Box::new(self.inline_condition(body, &mut block_bound)?),
+                })
+            }
         }
     }
 
@@ -380,6 +403,18 @@ fn reject_nested_user_calls(expr: &Expr, functions: &[FuncDef]) -> Result<(), Re
             }
             Ok(())
         }
+        Expr::BinaryOp { left, right, .. } => {
+            reject_nested_user_calls(left, functions)?;
+            reject_nested_user_calls(right, functions)
+        }
+        Expr::Interpolation(parts) => {
+            for part in parts {
+                if let InterpolationPart::Expr(e) = part {
+                    reject_nested_user_calls(e, functions)?;
+                }
+            }
+            Ok(())
+        }
         Expr::Literal(_)
         | Expr::Variable(_)
         | Expr::AttributeAccess { .. }
@@ -421,6 +456,22 @@ fn subst_condition(
             variable: rename_if_mapped(variable, subst)?,
             value: subst_assignment(value, subst)?,
         },
+        Condition::Quantifier {
+            kind,
+            index,
+            variable,
+            collection,
+            body,
+        } => Condition::Quantifier {
+            kind: *kind,
+            index: index
+                .as_deref()
+                .map(|i| rename_if_mapped(i, subst))
+                .transpose()?,
+            variable: rename_if_mapped(variable, subst)?,
+            collection: subst_source(collection, subst)?,
+            body: Box::new(subst_condition(body, subst)?),
+        },
     })
 }
 
@@ -507,6 +558,24 @@ fn subst_expr(expr: &Expr, subst: &HashMap<String, ArgSubst>) -> Result<Expr, Re
                 .map(|a| subst_expr(a, subst))
                 .collect::<Result<_, _>>()?,
         },
+        Expr::BinaryOp { op, left, right } => Expr::BinaryOp {
+            op: *op,
+            left: Box::new(subst_expr(left, subst)?),
+            right: Box::new(subst_expr(right, subst)?),
+        },
+        Expr::Interpolation(parts) => Expr::Interpolation(
+            parts
+                .iter()
+                .map(|part| {
+                    Ok(match part {
+                        InterpolationPart::Literal(_) => part.clone(),
+                        InterpolationPart::Expr(e) => {
+                            InterpolationPart::Expr(subst_expr(e, subst)?)
+                        }
+                    })
+                })
+                .collect::<Result<_, ReaperError>>()?,
+        ),
     })
 }
 
@@ -684,51 +753,7 @@ fn subst_comprehension(
     subst: &HashMap<String, ArgSubst>,
 ) -> Result<Comprehension, ReaperError> {
     let subst_iterator = |iterator: &ComprehensionIterator| -> Result<_, ReaperError> {
-        let collection = match &iterator.collection {
-            IterationSource::EntityAttr(_) => iterator.collection.clone(),
-            IterationSource::VarAttr(va) => match subst.get(&va.variable) {
-                None => iterator.collection.clone(),
-                Some(ArgSubst::Rename(to)) => IterationSource::VarAttr(VarAttr {
-                    variable: to.clone(),
-                    attribute: va.attribute.clone(),
-                    index: va.index.clone(),
-                }),
-                Some(ArgSubst::Splice(arg)) => {
-                    let (_, path) = input_path_join(arg, &va.attribute)?;
-                    IterationSource::EntityAttr(EntityAttr {
-                        entity: Entity::Input,
-                        attribute: path,
-                        index: va.index.clone(),
-                    })
-                }
-            },
-            IterationSource::IndexedVariable { variable, index } => match subst.get(variable) {
-                None => iterator.collection.clone(),
-                Some(ArgSubst::Rename(to)) => IterationSource::IndexedVariable {
-                    variable: to.clone(),
-                    index: index.clone(),
-                },
-                Some(ArgSubst::Splice(arg)) => {
-                    // Iterating a spliced path (`x := p[_]`): any entity-
-                    // rooted collection works — this is exactly the shape the
-                    // parser emits for `entity.attr[_]`.
-                    match expr_to_entity_attr(arg) {
-                        Some(mut ea) if ea.index.is_none() => {
-                            ea.index = Some(index.clone());
-                            IterationSource::EntityAttr(ea)
-                        }
-                        _ => {
-                            return Err(err(format!(
-                                "func body iterates parameter '{variable}' but its \
-                                 argument {arg:?} is not an un-indexed entity/input \
-                                 path; not compiled — the rule runs on the AST \
-                                 evaluator"
-                            )))
-                        }
-                    }
-                }
-            },
-        };
+        let collection = subst_source(&iterator.collection, subst)?;
         Ok(ComprehensionIterator {
             variable: rename_if_mapped(&iterator.variable, subst)?,
             collection,
@@ -775,3 +800,56 @@ fn subst_comprehension(
         },
     })
 }
+
+/// Substitute into an iteration source (comprehension iterators and
+/// `some`/`every` collections).
+fn subst_source(
+    source: &IterationSource,
+    subst: &HashMap<String, ArgSubst>,
+) -> Result<IterationSource, ReaperError> {
+    Ok(match source {
+        IterationSource::EntityAttr(_) => source.clone(),
+        IterationSource::VarAttr(va) => match subst.get(&va.variable) {
+            None => source.clone(),
+            Some(ArgSubst::Rename(to)) => IterationSource::VarAttr(VarAttr {
+                variable: to.clone(),
+                attribute: va.attribute.clone(),
+                index: va.index.clone(),
+            }),
+            Some(ArgSubst::Splice(arg)) => {
+                let (_, path) = input_path_join(arg, &va.attribute)?;
+                IterationSource::EntityAttr(EntityAttr {
+                    entity: Entity::Input,
+                    attribute: path,
+                    index: va.index.clone(),
+                })
+            }
+        },
+        IterationSource::IndexedVariable { variable, index } => match subst.get(variable) {
+            None => source.clone(),
+            Some(ArgSubst::Rename(to)) => IterationSource::IndexedVariable {
+                variable: to.clone(),
+                index: index.clone(),
+            },
+            Some(ArgSubst::Splice(arg)) => {
+                // Iterating a spliced path (`x := p[_]`): any entity-
+                // rooted collection works — this is exactly the shape the
+                // parser emits for `entity.attr[_]`.
+                match expr_to_entity_attr(arg) {
+                    Some(mut ea) if ea.index.is_none() => {
+                        ea.index = Some(index.clone());
+                        IterationSource::EntityAttr(ea)
+                    }
+                    _ => {
+                        return Err(err(format!(
+                            "func body iterates parameter '{variable}' but its \
+                             argument {arg:?} is not an un-indexed entity/input \
+                             path; not compiled — the rule runs on the AST \
+                             evaluator"
+                        )))
+                    }
+                }
+            }
+        },
+    })
+}
@@ -9,6 +9,7 @@
 //! - `expression`: Expression type compilation
 //! - `comparison`: Comparison compilation
 
+mod builtin;
 mod comparison;
 mod comprehension;
 mod expression;
@@ -23,7 +24,8 @@ use helpers::{extract_entity_attr, extract_int_literal, extract_string_literal};
 use methods::compile_method_call;
 
 use super::ast::{
-    AssignmentValue, Comprehension, Condition, Decision, Entity, Expr, Index, Policy, Rule,
+    AssignmentValue, Comprehension, ComprehensionIterator, Condition, Decision, Entity, Expr,
+    Index, InterpolationPart, IterationSource, Policy, Rule, Value,
 };
 use crate::evaluators::reaper_dsl::{
     Condition as DslCondition, EntityType as DslEntityType, ExprType,
@@ -51,6 +53,7 @@ pub fn compile_policy(
     // whose calls don't fit the substitution guards error here and take the
     // per-rule AST fallback (A.2), where call-by-value is interpreted
     // directly.
+    let source = Arc::new(policy.clone());
     let policy = inline::inline_policy(policy)?;
 
     // Convert default decision
@@ -65,7 +68,7 @@ pub fn compile_policy(
         rules.push(compile_rule(rule)?);
     }
 
-    let evaluator = ReaperDSLEvaluator::new(store, rules, default_decision);
+    let evaluator = ReaperDSLEvaluator::new(store, rules, default_decision).with_source(source);
     Ok(evaluator)
 }
 
@@ -98,7 +101,7 @@ fn compile_rule(rule: Rule) -> Result<DslRule, ReaperError> {
         let msg_vars: Vec<&String> = match msg {
             DslMessage::Literal(_) => Vec::new(),
             DslMessage::Variable(v) => vec![v],
-            DslMessage::Concat(parts) => parts
+            DslMessage::Concat(parts) | DslMessage::Interpolated(parts) => parts
                 .iter()
                 .filter_map(|p| match p {
                     DslMessagePart::Variable(v) => Some(v),
@@ -118,12 +121,18 @@ fn compile_rule(rule: Rule) -> Result<DslRule, ReaperError> {
         }
     }
     let condition = compile_condition_with(rule.condition, allow_var_compare)?;
+    let obligations = rule
+        .obligations
+        .as_ref()
+        .map(|o| o.to_decision())
+        .filter(|o| !o.is_empty());
 
     Ok(DslRule {
         name: rule.name,
         condition,
         decision,
         message,
+        obligations,
     })
 }
 
@@ -140,12 +149,18 @@ fn collect_assigned_vars(cond: &Condition, out: &mut std::collections::HashSet<S
             }
         }
         Condition::Not(inner) => collect_assigned_vars(inner, out),
-        Condition::True | Condition::False | Condition::Comparison { .. } | Condition::Expr(_) => {}
+        // Quantifier bindings are block-scoped: nothing escapes the body.
+        Condition::True
+        | Condition::False
+        | Condition::Comparison { .. }
+        | Condition::Expr(_)
+        | Condition::Quantifier { .. } => {}
     }
 }
 
 /// Lower a check-mode message expression to renderable parts: string
-/// literals, rule-variable references, and `concat(...)` of those.
+/// literals, rule-variable references, and `concat(...)`, f-strings and
+/// literal-template `strings::format(...)` of those.
 fn lower_message(expr: &Expr) -> Result<DslMessage, ReaperError> {
     match expr {
         Expr::Literal(super::ast::Value::String(s)) => Ok(DslMessage::Literal(s.clone())),
@@ -182,11 +197,64 @@ fn lower_message(expr: &Expr) -> Result<DslMessage, ReaperError> {
             }
             Ok(DslMessage::Concat(parts))
         }
+        Expr::Interpolation(holes) => {
+            let mut parts = Vec::with_capacity(holes.len());
+            for hole in holes {
+                parts.push(match hole {
+                    InterpolationPart::Literal(s) => DslMessagePart::Literal(s.clone()),
+                    InterpolationPart::Expr(e) => lower_interpolated_arg(e)?,
+                });
+            }
+            Ok(DslMessage::Interpolated(parts))
+        }
+        Expr::FunctionCall {
+            namespace: Some(ns),
+            function,
+            args,
+        } if ns == "strings" && function == "format" => {
+            // A bad template or a placeholder/argument mismatch errors at
+            // evaluation, after the arguments evaluate: leave that to the
+            // interpreter.
+            let pieces = match args.split_first() {
+                Some((Expr::Literal(super::ast::Value::String(template)), rest)) => {
+                    crate::reap::interpolate::split(template)
+                        .ok()
+                        .filter(|pieces| pieces.len() == rest.len() + 1)
+                }
+                _ => None,
+            }
+            .ok_or_else(|| ReaperError::InvalidPolicy {
+                reason: "check-mode strings::format() without a literal template matching \
+                         its arguments is not compiled; the rule runs on the AST evaluator"
+                    .to_string(),
+            })?;
+            let mut parts = vec![DslMessagePart::Literal(pieces[0].clone())];
+            for (arg, piece) in args[1..].iter().zip(&pieces[1..]) {
+                parts.push(lower_interpolated_arg(arg)?);
+                parts.push(DslMessagePart::Literal(piece.clone()));
+            }
+            Ok(DslMessage::Interpolated(parts))
+        }
         other => Err(ReaperError::InvalidPolicy {
             reason: format!(
                 "check-mode message expression {other:?} is not compiled (only string \
-                 literals, variables, and concat of those); the rule runs on the AST \
-                 evaluator"
+                 literals, variables, and concat, f-strings or strings::format of those); \
+                 the rule runs on the AST evaluator"
+            ),
+        }),
+    }
+}
+
+/// An f-string hole or `strings::format` argument in a lowered message:
+/// a string literal or a variable.
+fn lower_interpolated_arg(expr: &Expr) -> Result<DslMessagePart, ReaperError> {
+    match expr {
+        Expr::Literal(super::ast::Value::String(s)) => Ok(DslMessagePart::Literal(s.clone())),
+        Expr::Variable(v) => Ok(DslMessagePart::Variable(v.clone())),
+        other => Err(ReaperError::InvalidPolicy {
+            reason: format!(
+                "check-mode interpolated value {other:?} is not compiled (only string \
+                 literals and variables); the rule runs on the AST evaluator"
             ),
         }),
     }
@@ -204,6 +272,10 @@ fn lower_message(expr: &Expr) -> Result<DslMessage, ReaperError> {
 /// `Not` branches never escape the branch (the branch may be skipped at
 /// runtime). Any un-dominated use ⇒ `false` ⇒ the rule keeps its AST
 /// fallback (cheap and observable since per-rule fallback, A.2).
+///
+/// The same verdict gates quantifiers over a variable (`every r in roles`):
+/// the interpreter errors on an unbound source variable where the compiled
+/// shape would read it as absent.
 fn entity_var_compares_dominated(cond: &Condition) -> bool {
     use crate::reap::ast::ComparisonRight;
     fn walk(cond: &Condition, bound: &mut std::collections::HashSet<String>) -> bool {
@@ -226,6 +298,23 @@ fn entity_var_compares_dominated(cond: &Condition) -> bool {
                 let mut branch = bound.clone();
                 walk(inner, &mut branch)
             }
+            Condition::Quantifier {
+                index,
+                variable,
+                collection,
+                body,
+                ..
+            } => {
+                if let IterationSource::IndexedVariable { variable, .. } = collection {
+                    if !bound.contains(variable) {
+                        return false;
+                    }
+                }
+                let mut branch = bound.clone();
+                branch.extend(index.iter().cloned());
+                branch.insert(variable.clone());
+                walk(body, &mut branch)
+            }
             Condition::True | Condition::False | Condition::Expr(_) => true,
         }
     }
@@ -379,6 +468,56 @@ fn compile_condition_with(
             // Compile expression-based conditions (function calls, method calls)
             compile_expr_condition(expr)
         }
+
+        Condition::Quantifier {
+            kind,
+            index,
+            variable,
+            collection,
+            body,
+        } => {
+            compile_quantifier_source(&collection, allow_var_compare)?;
+            let (variable, source) = compile_iterator(ComprehensionIterator {
+                variable,
+                collection,
+            })?;
+            Ok(DslCondition::Quantifier {
+                kind,
+                index,
+                variable,
+                source,
+                body: Box::new(compile_condition_with(*body, allow_var_compare)?),
+            })
+        }
+    }
+}
+
+/// Quantifier sources the compiled evaluator reads exactly as the
+/// interpreter does: an undotted, unindexed `user`/`resource`/`actor`
+/// attribute, an `input` path, or a variable bound on every path to the
+/// block. Anything else keeps the rule on the AST evaluator.
+fn compile_quantifier_source(
+    collection: &IterationSource,
+    variables_dominated: bool,
+) -> Result<(), ReaperError> {
+    let compiled = match collection {
+        IterationSource::EntityAttr(attr) => match attr.entity {
+            Entity::Input => true,
+            Entity::Context => false,
+            Entity::User | Entity::Resource | Entity::Actor => {
+                attr.index.is_none() && !attr.attribute.contains('.')
+            }
+        },
+        IterationSource::VarAttr(_) => false,
+        IterationSource::IndexedVariable { .. } => variables_dominated,
+    };
+    if compiled {
+        Ok(())
+    } else {
+        Err(ReaperError::InvalidPolicy {
+            reason: "quantifier source is not compiled; the rule runs on the AST evaluator"
+                .to_string(),
+        })
     }
 }
 
@@ -418,13 +557,17 @@ fn compile_function_call(
     function: String,
     args: Vec<Expr>,
 ) -> Result<DslCondition, ReaperError> {
+    if let Some(builtin) = crate::reap::builtins::Builtin::lookup(namespace.as_deref(), &function) {
+        return builtin::compile_builtin_test(builtin, &args);
+    }
+
     let ns = namespace.as_deref().unwrap_or("");
 
     match (ns, function.as_str()) {
-        // rebac::related / reachable / inherited — compiled to interned graph
-        // lookups. Subject/object must be `user`/`resource` or literals here;
-        // dynamic (variable) ids run on the AST evaluator instead.
-        ("rebac", "related") | ("rebac", "reachable") | ("rebac", "inherited") => {
+        // rebac::related / reachable / inherited / check — compiled to
+        // interned graph lookups. Subject/object must be `user`/`resource` or
+        // literals here; dynamic (variable) ids run on the AST evaluator.
+        ("rebac", "related" | "reachable" | "inherited" | "check") => {
             compile_rebac_call(&function, args)
         }
 
@@ -441,6 +584,11 @@ fn compile_function_call(
             Ok(DslCondition::TaintTrusted { key })
         }
 
+        // net::cidr_contains / cidr_overlaps / ip_in_any / is_ip-family —
+        // the operand must be an entity or context attribute; variable
+        // operands and malformed range literals run on the AST evaluator.
+        ("net", _) => compile_net_call(&function, args),
+
         // regex::matches(entity.attribute, "pattern")
         ("regex", "matches") => {
             if args.len() != 2 {
@@ -589,7 +737,8 @@ fn compile_function_call(
             Err(ReaperError::InvalidPolicy {
                 reason: format!(
                     "Unsupported function call: {}{}. Supported functions: \
-                    regex::matches, time::is_after, time::is_before, is_string, is_number, is_bool",
+                    regex::matches, time::is_after, time::is_before, net::*, is_string, \
+                    is_number, is_bool",
                     fn_prefix, function
                 ),
             })
@@ -676,6 +825,110 @@ fn compile_comprehension_assignment(
     }
 }
 
+/// Lower a net::* function call into a NetMatch condition. Range literals
+/// are validated here so the compiled form can hold them pre-parsed; a
+/// malformed literal errors (the AST evaluator treats it as a non-match).
+fn compile_net_call(function: &str, args: Vec<Expr>) -> Result<DslCondition, ReaperError> {
+    use crate::evaluators::reaper_dsl::{NetOp, NetRanges};
+
+    let expected = match function {
+        "is_ip" | "is_ipv4" | "is_ipv6" | "is_cidr" => 1,
+        "cidr_contains" | "cidr_overlaps" | "ip_in_any" => 2,
+        _ => {
+            return Err(ReaperError::InvalidPolicy {
+                reason: format!("Unsupported function call: net::{function}"),
+            })
+        }
+    };
+    if args.len() != expected {
+        return Err(ReaperError::InvalidPolicy {
+            reason: format!(
+                "net::{function} requires {expected} argument{}, got {}",
+                if expected == 1 { "" } else { "s" },
+                args.len()
+            ),
+        });
+    }
+
+    // A single range: a literal, or a string attribute.
+    let single = |expr: &Expr| -> Result<NetRanges, ReaperError> {
+        if let Expr::Literal(Value::String(s)) = expr {
+            return Ok(NetRanges::Literal(vec![net_range_literal(s)?]));
+        }
+        let (entity_type, attribute) = net_attr(expr)?;
+        Ok(NetRanges::Attribute {
+            entity_type,
+            attribute,
+        })
+    };
+
+    let (operand, op) = match function {
+        "is_ip" => (&args[0], NetOp::IsIp),
+        "is_ipv4" => (&args[0], NetOp::IsIpv4),
+        "is_ipv6" => (&args[0], NetOp::IsIpv6),
+        "is_cidr" => (&args[0], NetOp::IsCidr),
+        "cidr_contains" => (&args[1], NetOp::ContainedIn(single(&args[0])?)),
+        // Symmetric: whichever side is not a literal is the operand.
+        "cidr_overlaps" => match &args[0] {
+            Expr::Literal(_) => (&args[1], NetOp::Overlaps(single(&args[0])?)),
+            _ => (&args[0], NetOp::Overlaps(single(&args[1])?)),
+        },
+        _ => {
+            let ranges = match &args[1] {
+                Expr::Literal(Value::Array(items)) => NetRanges::Literal(
+                    items
+                        .iter()
+                        .map(|v| match v {
+                            Value::String(s) => net_range_literal(s),
+                            other => Err(ReaperError::InvalidPolicy {
+                                reason: format!(
+                                    "net::ip_in_any range list must hold strings, got {other:?}"
+                                ),
+                            }),
+                        })
+                        .collect::<Result<_, _>>()?,
+                ),
+                other => {
+                    let (entity_type, attribute) = net_attr(other)?;
+                    NetRanges::AnyOf {
+                        entity_type,
+                        attribute,
+                    }
+                }
+            };
+            (&args[0], NetOp::ContainedIn(ranges))
+        }
+    };
+
+    let (entity_type, attribute) = net_attr(operand)?;
+    Ok(DslCondition::NetMatch {
+        entity_type,
+        attribute,
+        op,
+    })
+}
+
+/// A flat `entity.attribute` operand. Dotted paths stay on the AST
+/// evaluator, which reads them as a single literal key.
+fn net_attr(expr: &Expr) -> Result<(DslEntityType, String), ReaperError> {
+    let (entity_type, attribute) = extract_entity_attr(expr)?;
+    if attribute.contains('.') {
+        return Err(ReaperError::InvalidPolicy {
+            reason: format!("net:: operands must be flat attributes, got '{attribute}'"),
+        });
+    }
+    Ok((entity_type, attribute))
+}
+
+fn net_range_literal(s: &str) -> Result<String, ReaperError> {
+    match crate::net::IpNet::parse(s) {
+        Some(_) => Ok(s.to_string()),
+        None => Err(ReaperError::InvalidPolicy {
+            reason: format!("Invalid CIDR or IP address literal: {s:?}"),
+        }),
+    }
+}
+
 /// Lower a rebac::* function call into a RebacCheck condition. Only static
 /// argument shapes compile (the sub-microsecond path needs ids resolvable
 /// without variable state); anything else errors, which routes the policy to
@@ -686,9 +939,11 @@ fn compile_rebac_call(function: &str, args: Vec<Expr>) -> Result<DslCondition, R
     let kind = match function {
         "related" => RebacKind::Direct,
         "reachable" => RebacKind::Reachable,
+        "check" => RebacKind::Check,
         _ => RebacKind::Inherited,
     };
-    let expected = if kind == RebacKind::Direct { 3 } else { 5 };
+    let traverses = matches!(kind, RebacKind::Reachable | RebacKind::Inherited);
+    let expected = if traverses { 5 } else { 3 };
     if args.len() != expected {
         return Err(ReaperError::InvalidPolicy {
             reason: format!(
@@ -728,7 +983,7 @@ fn compile_rebac_call(function: &str, args: Vec<Expr>) -> Result<DslCondition, R
     let subject = rebac_ref(&args[0])?;
     let relation = literal_str(&args[1], "relation")?;
     let object = rebac_ref(&args[2])?;
-    let (via, max_depth) = if kind == RebacKind::Direct {
+    let (via, max_depth) = if !traverses {
         (None, 1)
     } else {
         let via = literal_str(&args[3], "edge")?;
@@ -772,6 +1027,7 @@ mod tests {
             default_decision: Decision::Deny,
             rules: vec![Rule {
                 message: None,
+                obligations: None,
                 name: "admin".to_string(),
                 decision: Decision::Allow,
                 condition: Condition::Comparison {
@@ -811,7 +1067,7 @@ mod tests {
 
         // Evaluate
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), "alice".to_string());
+        context.insert("principal".to_string(), "alice".into());
 
         let request = PolicyRequest {
             resource: "doc1".to_string(),
@@ -874,7 +1130,7 @@ policy test_expr {
         let evaluator = compile_policy(parsed, store).expect("Compile failed");
 
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), "user1".to_string());
+        context.insert("principal".to_string(), "user1".into());
 
         let request = PolicyRequest {
             resource: "res1".to_string(),
@@ -928,8 +1184,8 @@ policy test_context {
 
         // Test with matching context
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), "user1".to_string());
-        context.insert("env".to_string(), "production".to_string());
+        context.insert("principal".to_string(), "user1".into());
+        context.insert("env".to_string(), "production".into());
 
         let request = PolicyRequest {
             resource: "res1".to_string(),
@@ -948,8 +1204,8 @@ policy test_context {
 
         // Test with non-matching context
         let mut context2 = HashMap::new();
-        context2.insert("principal".to_string(), "user1".to_string());
-        context2.insert("env".to_string(), "development".to_string());
+        context2.insert("principal".to_string(), "user1".into());
+        context2.insert("env".to_string(), "development".into());
 
         let request2 = PolicyRequest {
             resource: "res1".to_string(),
@@ -1024,7 +1280,7 @@ policy test_comp {
         let evaluator = compile_policy(parsed, store).expect("Compile failed");
 
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), "user1".to_string());
+        context.insert("principal".to_string(), "user1".into());
 
         let request = PolicyRequest {
             resource: "res1".to_string(),
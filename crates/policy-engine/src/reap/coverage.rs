@@ -0,0 +1,605 @@
+//! Policy test coverage: which rules, condition outcomes and `func` helpers
+//! a set of evaluations exercised (`reaper-cli test-suite --coverage`).
+//!
+//! Coverage is accumulated from [`EvaluationTrace`]s, so it sees exactly
+//! what the reference interpreter evaluated, whichever evaluator serves the
+//! policy. Three things are counted per policy:
+//!
+//! - rules: how often each rule's condition was evaluated and matched;
+//! - branches: every comparison and boolean expression in a rule condition
+//!   (the leaves under `&&`, `||` and `!`) has two outcomes, true and false,
+//!   and each is covered once some evaluation produced it;
+//! - funcs: how often each helper was called, and with which results.
+//!
+//! [`CoverageReport`] renders the totals as text, JSON, LCOV or Cobertura.
+
+use super::analysis;
+use super::ast::{Condition, Decision, Policy};
+use super::format;
+use super::trace::{lowercase, ConditionTrace, EvaluationTrace};
+use super::ReaperPolicy;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+/// Coverage of one policy, accumulated over any number of evaluations.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PolicyCoverage {
+    pub policy: String,
+    /// Traces recorded.
+    pub evaluations: usize,
+    /// In declaration order.
+    pub rules: Vec<RuleCoverage>,
+    pub functions: Vec<FunctionCoverage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RuleCoverage {
+    pub name: String,
+    #[serde(serialize_with = "lowercase")]
+    pub effect: Decision,
+    /// 1-based source line, when the policy was read from `.reap` source.
+    pub line: Option<usize>,
+    /// Evaluations that reached this rule (an earlier match stops the rest).
+    pub evaluated: usize,
+    pub matched: usize,
+    /// The condition's leaves, in source order.
+    pub branches: Vec<BranchCoverage>,
+    #[serde(skip)]
+    shape: Shape,
+}
+
+/// The `&&` / `||` / `!` structure of a condition down to its branch
+/// leaves, for lining traces up with [`RuleCoverage::branches`].
+#[derive(Debug, Clone, PartialEq)]
+enum Shape {
+    Leaf,
+    Group(Vec<Shape>),
+    Not(Box<Shape>),
+    /// `true`, `false`, assignments: no branch.
+    Fixed,
+}
+
+/// One comparison or boolean expression and how often it held.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BranchCoverage {
+    pub source: String,
+    pub true_count: usize,
+    pub false_count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FunctionCoverage {
+    /// Qualified name (`ns::name` for imported helpers).
+    pub name: String,
+    pub line: Option<usize>,
+    pub calls: usize,
+    pub true_count: usize,
+    pub false_count: usize,
+}
+
+/// Covered / total counts; a percentage of an empty total is 100.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CoverageSummary {
+    pub rules: usize,
+    pub rules_matched: usize,
+    /// Two per branch.
+    pub branch_outcomes: usize,
+    pub branch_outcomes_covered: usize,
+    pub functions: usize,
+    pub functions_called: usize,
+}
+
+impl PolicyCoverage {
+    /// Empty coverage for `policy`. Source lines are filled in by
+    /// [`Self::with_source`].
+    pub fn new(policy: &ReaperPolicy) -> Self {
+        Self::for_ast(&policy.ast)
+    }
+
+    fn for_ast(policy: &Policy) -> Self {
+        let rules = policy
+            .rules
+            .iter()
+            .map(|rule| {
+                let mut branches = Vec::new();
+                let shape = shape(&rule.condition, &mut branches);
+                RuleCoverage {
+                    name: rule.name.clone(),
+                    effect: rule.decision.clone(),
+                    line: None,
+                    evaluated: 0,
+                    matched: 0,
+                    branches,
+                    shape,
+                }
+            })
+            .collect();
+        let functions = policy
+            .functions
+            .iter()
+            .map(|def| FunctionCoverage {
+                name: super::functions::qualified(def),
+                line: None,
+                calls: 0,
+                true_count: 0,
+                false_count: 0,
+            })
+            .collect();
+        PolicyCoverage {
+            policy: policy.name.clone(),
+            evaluations: 0,
+            rules,
+            functions,
+        }
+    }
+
+    /// Attach line numbers from the policy's `.reap` source. Declarations
+    /// are matched by name; a source that does not parse leaves lines unset.
+    pub fn with_source(mut self, source: &str) -> Self {
+        let Ok(outline) = analysis::outline(source) else {
+            return self;
+        };
+        let line_of = |offset: usize| source[..offset].matches('\n').count() + 1;
+        let mut rule_lines: HashMap<&str, Vec<usize>> = HashMap::new();
+        for decl in &outline.rules {
+            rule_lines
+                .entry(decl.name.as_str())
+                .or_default()
+                .push(line_of(decl.span.start));
+        }
+        // Duplicate names take their lines in declaration order.
+        let mut taken: HashMap<&str, usize> = HashMap::new();
+        for rule in &mut self.rules {
+            let n = taken.entry(rule.name.as_str()).or_default();
+            rule.line = rule_lines
+                .get(rule.name.as_str())
+                .and_then(|lines| lines.get(*n))
+                .copied();
+            *n += 1;
+        }
+        for func in &mut self.functions {
+            func.line = outline
+                .functions
+                .iter()
+                .find(|decl| decl.name == func.name)
+                .map(|decl| line_of(decl.span.start));
+        }
+        self
+    }
+
+    /// Add one evaluation. Rules are matched to the trace by name and
+    /// effect, in order, so a trace of another policy records nothing.
+    pub fn record(&mut self, trace: &EvaluationTrace) {
+        self.evaluations += 1;
+        let mut next: HashMap<(&str, bool), usize> = HashMap::new();
+        for traced in &trace.rules {
+            let deny = traced.effect == Decision::Deny;
+            let from = next.entry((traced.name.as_str(), deny)).or_default();
+            let Some(offset) = self.rules[*from..]
+                .iter()
+                .position(|r| r.name == traced.name && (r.effect == Decision::Deny) == deny)
+            else {
+                continue;
+            };
+            let index = *from + offset;
+            *from = index + 1;
+            let Some(condition) = &traced.condition else {
+                continue;
+            };
+            let rule = &mut self.rules[index];
+            rule.evaluated += 1;
+            rule.matched += usize::from(traced.matched);
+            let mut leaf = 0;
+            count(&rule.shape, condition, &mut rule.branches, &mut leaf);
+        }
+        for call in &trace.calls {
+            if let Some(func) = self.functions.iter_mut().find(|f| f.name == call.function) {
+                func.calls += 1;
+                if call.result {
+                    func.true_count += 1;
+                } else {
+                    func.false_count += 1;
+                }
+            }
+        }
+    }
+
+    pub fn summary(&self) -> CoverageSummary {
+        let branches = self.rules.iter().flat_map(|r| &r.branches);
+        CoverageSummary {
+            rules: self.rules.len(),
+            rules_matched: self.rules.iter().filter(|r| r.matched > 0).count(),
+            branch_outcomes: 2 * branches.clone().count(),
+            branch_outcomes_covered: branches
+                .map(|b| usize::from(b.true_count > 0) + usize::from(b.false_count > 0))
+                .sum(),
+            functions: self.functions.len(),
+            functions_called: self.functions.iter().filter(|f| f.calls > 0).count(),
+        }
+    }
+}
+
+impl CoverageSummary {
+    pub fn rule_percent(&self) -> f64 {
+        percent(self.rules_matched, self.rules)
+    }
+
+    pub fn branch_percent(&self) -> f64 {
+        percent(self.branch_outcomes_covered, self.branch_outcomes)
+    }
+
+    pub fn function_percent(&self) -> f64 {
+        percent(self.functions_called, self.functions)
+    }
+
+    /// The lowest of the three percentages.
+    pub fn min_percent(&self) -> f64 {
+        self.rule_percent()
+            .min(self.branch_percent())
+            .min(self.function_percent())
+    }
+
+    fn add(&mut self, other: &CoverageSummary) {
+        self.rules += other.rules;
+        self.rules_matched += other.rules_matched;
+        self.branch_outcomes += other.branch_outcomes;
+        self.branch_outcomes_covered += other.branch_outcomes_covered;
+        self.functions += other.functions;
+        self.functions_called += other.functions_called;
+    }
+}
+
+fn percent(covered: usize, total: usize) -> f64 {
+    if total == 0 {
+        100.0
+    } else {
+        covered as f64 * 100.0 / total as f64
+    }
+}
+
+/// The shape of `condition`, pushing its branch leaves in source order.
+fn shape(condition: &Condition, branches: &mut Vec<BranchCoverage>) -> Shape {
+    match condition {
+        // A quantifier is traced as one predicate: a single branch.
+        Condition::Comparison { .. } | Condition::Expr(_) | Condition::Quantifier { .. } => {
+            branches.push(BranchCoverage {
+                source: format::condition(condition),
+                true_count: 0,
+                false_count: 0,
+            });
+            Shape::Leaf
+        }
+        Condition::And(items) | Condition::Or(items) => {
+            Shape::Group(items.iter().map(|c| shape(c, branches)).collect())
+        }
+        Condition::Not(inner) => Shape::Not(Box::new(shape(inner, branches))),
+        Condition::True | Condition::False | Condition::Assignment { .. } => Shape::Fixed,
+    }
+}
+
+impl Shape {
+    fn leaves(&self) -> usize {
+        match self {
+            Shape::Leaf => 1,
+            Shape::Group(items) => items.iter().map(Shape::leaves).sum(),
+            Shape::Not(inner) => inner.leaves(),
+            Shape::Fixed => 0,
+        }
+    }
+}
+
+/// Count the outcomes in `trace` against `branches`, starting at leaf
+/// `*leaf`. The trace mirrors the condition node for node, except that
+/// operands an `&&` / `||` did not reach have no trace; their leaves are
+/// passed over.
+fn count(shape: &Shape, trace: &ConditionTrace, branches: &mut [BranchCoverage], leaf: &mut usize) {
+    match (shape, trace) {
+        (
+            Shape::Leaf,
+            ConditionTrace::Comparison { result, .. } | ConditionTrace::Predicate { result, .. },
+        ) => {
+            if let Some(branch) = branches.get_mut(*leaf) {
+                if *result {
+                    branch.true_count += 1;
+                } else {
+                    branch.false_count += 1;
+                }
+            }
+            *leaf += 1;
+        }
+        (
+            Shape::Group(items),
+            ConditionTrace::All { operands, .. } | ConditionTrace::Any { operands, .. },
+        ) => {
+            for (i, item) in items.iter().enumerate() {
+                match operands.get(i) {
+                    Some(operand) => count(item, operand, branches, leaf),
+                    None => *leaf += item.leaves(),
+                }
+            }
+        }
+        (Shape::Not(inner), ConditionTrace::Not { operand, .. }) => {
+            count(inner, operand, branches, leaf)
+        }
+        (shape, _) => *leaf += shape.leaves(),
+    }
+}
+
+/// Coverage of every policy a run exercised, keyed by policy file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CoverageReport {
+    pub files: Vec<FileCoverage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FileCoverage {
+    pub path: String,
+    #[serde(flatten)]
+    pub coverage: PolicyCoverage,
+}
+
+impl CoverageReport {
+    /// Coverage for `path`, created from `policy` (and `source`, for line
+    /// numbers) the first time the file is seen.
+    pub fn file(
+        &mut self,
+        path: &str,
+        policy: &ReaperPolicy,
+        source: Option<&str>,
+    ) -> &mut PolicyCoverage {
+        let index = match self.files.iter().position(|f| f.path == path) {
+            Some(index) => index,
+            None => {
+                let mut coverage = PolicyCoverage::new(policy);
+                if let Some(source) = source {
+                    coverage = coverage.with_source(source);
+                }
+                self.files.push(FileCoverage {
+                    path: path.to_string(),
+                    coverage,
+                });
+                self.files.len() - 1
+            }
+        };
+        &mut self.files[index].coverage
+    }
+
+    /// Totals over every file.
+    pub fn summary(&self) -> CoverageSummary {
+        let mut total = CoverageSummary::default();
+        for file in &self.files {
+            total.add(&file.coverage.summary());
+        }
+        total
+    }
+
+    /// JSON: every file with its rules, branches, funcs and summary, plus
+    /// the overall `summary`.
+    pub fn to_json(&self) -> serde_json::Value {
+        let files: Vec<serde_json::Value> = self
+            .files
+            .iter()
+            .map(|f| {
+                let mut file = serde_json::json!(f);
+                file["summary"] = serde_json::json!(f.coverage.summary());
+                file
+            })
+            .collect();
+        serde_json::json!({ "files": files, "summary": self.summary() })
+    }
+
+    /// A human summary: per-file percentages, then everything never
+    /// exercised.
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for file in &self.files {
+            let cov = &file.coverage;
+            let s = cov.summary();
+            let _ = writeln!(
+                out,
+                "{} (policy {}, {} evaluation(s))",
+                file.path, cov.policy, cov.evaluations
+            );
+            summary_lines(&mut out, &s);
+            for rule in cov.rules.iter().filter(|r| r.matched == 0) {
+                let _ = writeln!(out, "  never matched: rule {}{}", rule.name, at(rule.line));
+            }
+            for rule in &cov.rules {
+                for branch in &rule.branches {
+                    let never = match (branch.true_count > 0, branch.false_count > 0) {
+                        (true, true) => continue,
+                        (false, false) => "never evaluated",
+                        (false, true) => "never true",
+                        (true, false) => "never false",
+                    };
+                    let _ = writeln!(
+                        out,
+                        "  {never}: {} (rule {}{})",
+                        branch.source,
+                        rule.name,
+                        at(rule.line)
+                    );
+                }
+            }
+            for func in cov.functions.iter().filter(|f| f.calls == 0) {
+                let _ = writeln!(out, "  never called: func {}{}", func.name, at(func.line));
+            }
+            out.push('\n');
+        }
+        if self.files.len() > 1 {
+            let _ = writeln!(out, "total");
+            summary_lines(&mut out, &self.summary());
+        }
+        out
+    }
+
+    /// LCOV tracefile. Rules are lines (hit count = matches), each branch
+    /// leaf is a pair of LCOV branches (true, false) on its rule's line, and
+    /// funcs are functions (hit count = calls).
+    pub fn to_lcov(&self) -> String {
+        let mut out = String::new();
+        for file in &self.files {
+            let cov = &file.coverage;
+            let _ = writeln!(out, "TN:\nSF:{}", file.path);
+            for func in &cov.functions {
+                let _ = writeln!(out, "FN:{},{}", func.line.unwrap_or(1), func.name);
+            }
+            for func in &cov.functions {
+                let _ = writeln!(out, "FNDA:{},{}", func.calls, func.name);
+            }
+            let s = cov.summary();
+            let _ = writeln!(out, "FNF:{}\nFNH:{}", s.functions, s.functions_called);
+            for (block, rule) in cov.rules.iter().enumerate() {
+                let line = rule.line.unwrap_or(1);
+                for (i, branch) in rule.branches.iter().enumerate() {
+                    for (j, taken) in [branch.true_count, branch.false_count].iter().enumerate() {
+                        let taken = if rule.evaluated == 0 {
+                            "-".to_string()
+                        } else {
+                            taken.to_string()
+                        };
+                        let _ = writeln!(out, "BRDA:{line},{block},{},{taken}", 2 * i + j);
+                    }
+                }
+            }
+            let _ = writeln!(
+                out,
+                "BRF:{}\nBRH:{}",
+                s.branch_outcomes, s.branch_outcomes_covered
+            );
+            for rule in &cov.rules {
+                let _ = writeln!(out, "DA:{},{}", rule.line.unwrap_or(1), rule.matched);
+            }
+            let _ = writeln!(out, "LF:{}\nLH:{}\nend_of_record", s.rules, s.rules_matched);
+        }
+        out
+    }
+
+    /// Cobertura XML: one class per policy file, rules as lines (with their
+    /// branch condition coverage) and funcs as methods.
+    pub fn to_cobertura(&self) -> String {
+        let total = self.summary();
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let mut out = String::new();
+        let _ = writeln!(out, r#"<?xml version="1.0" ?>"#);
+        let _ = writeln!(
+            out,
+            r#"<!DOCTYPE coverage SYSTEM "http://cobertura.sourceforge.net/xml/coverage-04.dtd">"#
+        );
+        let _ = writeln!(
+            out,
+            r#"<coverage line-rate="{}" branch-rate="{}" lines-covered="{}" lines-valid="{}" branches-covered="{}" branches-valid="{}" complexity="0" version="{}" timestamp="{timestamp}">"#,
+            rate(total.rules_matched, total.rules),
+            rate(total.branch_outcomes_covered, total.branch_outcomes),
+            total.rules_matched,
+            total.rules,
+            total.branch_outcomes_covered,
+            total.branch_outcomes,
+            reaper_core::VERSION,
+        );
+        let _ = writeln!(out, "  <sources><source>.</source></sources>\n  <packages>");
+        let _ = writeln!(
+            out,
+            r#"    <package name="policies" line-rate="{}" branch-rate="{}" complexity="0">"#,
+            rate(total.rules_matched, total.rules),
+            rate(total.branch_outcomes_covered, total.branch_outcomes),
+        );
+        let _ = writeln!(out, "      <classes>");
+        for file in &self.files {
+            let cov = &file.coverage;
+            let s = cov.summary();
+            let _ = writeln!(
+                out,
+                r#"        <class name="{}" filename="{}" line-rate="{}" branch-rate="{}" complexity="0">"#,
+                xml_escape(&cov.policy),
+                xml_escape(&file.path),
+                rate(s.rules_matched, s.rules),
+                rate(s.branch_outcomes_covered, s.branch_outcomes),
+            );
+            let _ = writeln!(out, "          <methods>");
+            for func in &cov.functions {
+                let line = func.line.unwrap_or(1);
+                let _ = writeln!(
+                    out,
+                    r#"            <method name="{}" signature="" line-rate="{}" branch-rate="1" complexity="0"><lines><line number="{line}" hits="{}"/></lines></method>"#,
+                    xml_escape(&func.name),
+                    rate(usize::from(func.calls > 0), 1),
+                    func.calls,
+                );
+            }
+            let _ = writeln!(out, "          </methods>\n          <lines>");
+            for rule in &cov.rules {
+                let line = rule.line.unwrap_or(1);
+                if rule.branches.is_empty() {
+                    let _ = writeln!(
+                        out,
+                        r#"            <line number="{line}" hits="{}" branch="false"/>"#,
+                        rule.matched
+                    );
+                    continue;
+                }
+                let outcomes = 2 * rule.branches.len();
+                let covered: usize = rule
+                    .branches
+                    .iter()
+                    .map(|b| usize::from(b.true_count > 0) + usize::from(b.false_count > 0))
+                    .sum();
+                let _ = writeln!(
+                    out,
+                    r#"            <line number="{line}" hits="{}" branch="true" condition-coverage="{}% ({covered}/{outcomes})"/>"#,
+                    rule.matched,
+                    covered * 100 / outcomes,
+                );
+            }
+            let _ = writeln!(out, "          </lines>\n        </class>");
+        }
+        let _ = writeln!(
+            out,
+            "      </classes>\n    </package>\n  </packages>\n</coverage>"
+        );
+        out
+    }
+}
+
+fn summary_lines(out: &mut String, s: &CoverageSummary) {
+    let _ = writeln!(
+        out,
+        "  rules     {}/{} matched ({:.1}%)",
+        s.rules_matched,
+        s.rules,
+        s.rule_percent()
+    );
+    let _ = writeln!(
+        out,
+        "  branches  {}/{} outcomes ({:.1}%)",
+        s.branch_outcomes_covered,
+        s.branch_outcomes,
+        s.branch_percent()
+    );
+    let _ = writeln!(
+        out,
+        "  funcs     {}/{} called ({:.1}%)",
+        s.functions_called,
+        s.functions,
+        s.function_percent()
+    );
+}
+
+fn at(line: Option<usize>) -> String {
+    line.map(|l| format!(", line {l}")).unwrap_or_default()
+}
+
+/// A Cobertura rate: covered / total in [0, 1], 1 for an empty total.
+fn rate(covered: usize, total: usize) -> String {
+    format!("{:.4}", percent(covered, total) / 100.0)
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
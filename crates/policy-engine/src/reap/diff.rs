@@ -0,0 +1,468 @@
+//! Semantic diff of two versions of a policy (`reaper-cli bundle diff`, and
+//! the management service's change-request diff).
+//!
+//! Instead of comparing text, each version's decision is turned into a
+//! boolean formula over its conditions — deny overrides, then any allow,
+//! then the default — and the two formulas are compared symbolically. The
+//! result is the request classes whose outcome changes:
+//!
+//! ```text
+//! deny → allow when user.role == "contractor" && resource.classification == "internal"
+//! ```
+//!
+//! Conditions are compared in their canonical ([`format`](super::format))
+//! form, so reordering rules, renaming them, or reformatting leaves no
+//! changes. Atoms are the leaves of `&&` / `||` / `!`; a call to one of the
+//! policy's funcs is the same atom in both versions only when the func's
+//! body is unchanged. Rules that bind variables are compared as a whole.
+//!
+//! Like [`lint`](super::lint), the reasoning is conservative: comparisons of
+//! one operand against literals are understood (`x == "a"` rules out
+//! `x == "b"` and implies `x != "b"`), anything else is opaque. A reported
+//! class may occasionally be empty in practice; an unreported change is one
+//! whose atoms cannot be told apart textually.
+
+use super::ast::{
+    ComparisonLeft, ComparisonRight, Condition, Decision, Expr, Operator, Policy, Rule, Value,
+};
+use super::format;
+use super::functions::{collect_bound_vars_condition, qualified};
+use super::lint::{calls_condition, contradiction, holds, literal_eq, lower, number, upper};
+use serde::{Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+/// Most request classes reported per direction; beyond it the diff is
+/// marked truncated.
+pub const MAX_CLASSES: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffStatus {
+    /// Only in the new version.
+    Added,
+    /// Only in the old version.
+    Removed,
+    /// Some requests get a different outcome.
+    Changed,
+    /// Every request gets the same outcome (the text may still differ).
+    Unchanged,
+}
+
+impl DiffStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DiffStatus::Added => "added",
+            DiffStatus::Removed => "removed",
+            DiffStatus::Changed => "changed",
+            DiffStatus::Unchanged => "unchanged",
+        }
+    }
+}
+
+/// One class of requests whose outcome changes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OutcomeChange {
+    #[serde(serialize_with = "decision_name")]
+    pub from: Decision,
+    #[serde(serialize_with = "decision_name")]
+    pub to: Decision,
+    /// Conditions that together describe the class, in canonical form.
+    /// Empty: every request.
+    pub when: Vec<String>,
+}
+
+impl fmt::Display for OutcomeChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} → {}", name(&self.from), name(&self.to))?;
+        if self.when.is_empty() {
+            write!(f, " for every request")
+        } else {
+            write!(f, " when {}", self.when.join(" && "))
+        }
+    }
+}
+
+/// The outcome diff of one policy.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PolicyDiff {
+    pub policy: String,
+    pub status: DiffStatus,
+    /// `deny → allow` classes first, then `allow → deny`.
+    pub changes: Vec<OutcomeChange>,
+    /// More than [`MAX_CLASSES`] classes in one direction; `changes` lists
+    /// the first ones.
+    pub truncated: bool,
+}
+
+/// Compare two versions of one policy.
+pub fn diff_policies(old: &Policy, new: &Policy) -> PolicyDiff {
+    let mut atoms = Atoms::default();
+    let mut truncated = false;
+    let mut changes = Vec::new();
+    for (from, to, granted, revoked) in [
+        (Decision::Deny, Decision::Allow, new, old),
+        (Decision::Allow, Decision::Deny, old, new),
+    ] {
+        // Allowed by one version and not by the other.
+        let formula = Formula::And(vec![
+            allowed(granted, true, &mut atoms),
+            allowed(revoked, false, &mut atoms),
+        ]);
+        let mut classes = dnf(&formula, &atoms, &mut truncated);
+        let mut rendered: Vec<Vec<String>> =
+            classes.drain(..).map(|cube| atoms.render(&cube)).collect();
+        rendered.sort();
+        changes.extend(rendered.into_iter().map(|when| OutcomeChange {
+            from: from.clone(),
+            to: to.clone(),
+            when,
+        }));
+    }
+    PolicyDiff {
+        policy: new.name.clone(),
+        status: if changes.is_empty() {
+            DiffStatus::Unchanged
+        } else {
+            DiffStatus::Changed
+        },
+        changes,
+        truncated,
+    }
+}
+
+/// Compare two sets of policies (two bundles, two packages), matched by
+/// name: changed and unchanged policies in `new`'s order, then added, then
+/// removed. When each side holds exactly one policy the two are compared
+/// whatever their names, so a rename is a change rather than an
+/// add/remove pair.
+pub fn diff_sets(old: &[&Policy], new: &[&Policy]) -> Vec<PolicyDiff> {
+    if let ([old], [new]) = (old, new) {
+        return vec![diff_policies(old, new)];
+    }
+    let mut out = Vec::new();
+    let mut added = Vec::new();
+    for policy in new {
+        match old.iter().find(|p| p.name == policy.name) {
+            Some(before) => out.push(diff_policies(before, policy)),
+            None => added.push(only(policy, DiffStatus::Added)),
+        }
+    }
+    out.extend(added);
+    out.extend(
+        old.iter()
+            .filter(|p| !new.iter().any(|n| n.name == p.name))
+            .map(|p| only(p, DiffStatus::Removed)),
+    );
+    out
+}
+
+fn only(policy: &Policy, status: DiffStatus) -> PolicyDiff {
+    PolicyDiff {
+        policy: policy.name.clone(),
+        status,
+        changes: Vec::new(),
+        truncated: false,
+    }
+}
+
+fn name(decision: &Decision) -> &'static str {
+    match decision {
+        Decision::Allow => "allow",
+        Decision::Deny => "deny",
+    }
+}
+
+fn decision_name<S: Serializer>(decision: &Decision, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(name(decision))
+}
+
+// ---------------------------------------------------------------------------
+// Formulas
+// ---------------------------------------------------------------------------
+
+/// A condition in negation normal form over interned atoms.
+enum Formula {
+    Const(bool),
+    /// `(atom, holds)`.
+    Lit(usize, bool),
+    And(Vec<Formula>),
+    Or(Vec<Formula>),
+}
+
+/// A conjunction of literals: atom -> polarity.
+type Cube = BTreeMap<usize, bool>;
+
+/// When `policy` allows (`positive`) or denies (`!positive`) a request.
+fn allowed(policy: &Policy, positive: bool, atoms: &mut Atoms) -> Formula {
+    let denies: Vec<&Rule> = policy
+        .rules
+        .iter()
+        .filter(|r| r.decision == Decision::Deny)
+        .collect();
+    let allows: Vec<&Rule> = policy
+        .rules
+        .iter()
+        .filter(|r| r.decision == Decision::Allow)
+        .collect();
+    let mut rules = |rules: &[&Rule], matches: bool| -> Vec<Formula> {
+        rules
+            .iter()
+            .map(|r| rule(policy, r, matches, atoms))
+            .collect()
+    };
+    let default_allow = policy.default_decision == Decision::Allow;
+
+    if positive {
+        // no deny matches, and (default allow or some allow matches)
+        let mut all = rules(&denies, false);
+        if !default_allow {
+            all.push(Formula::Or(rules(&allows, true)));
+        }
+        Formula::And(all)
+    } else {
+        let mut any = rules(&denies, true);
+        if !default_allow {
+            any.push(Formula::And(rules(&allows, false)));
+        }
+        Formula::Or(any)
+    }
+}
+
+/// When `rule` matches (`positive`) or does not.
+fn rule(policy: &Policy, rule: &Rule, positive: bool, atoms: &mut Atoms) -> Formula {
+    let mut bound = HashSet::new();
+    collect_bound_vars_condition(&rule.condition, &mut bound);
+    if bound.is_empty() {
+        build(policy, &rule.condition, positive, atoms)
+    } else {
+        Formula::Lit(atoms.intern(policy, &rule.condition), positive)
+    }
+}
+
+fn build(policy: &Policy, cond: &Condition, positive: bool, atoms: &mut Atoms) -> Formula {
+    let mut each = |items: &[Condition]| -> Vec<Formula> {
+        items
+            .iter()
+            .map(|c| build(policy, c, positive, atoms))
+            .collect()
+    };
+    match cond {
+        Condition::True => Formula::Const(positive),
+        Condition::False => Formula::Const(!positive),
+        Condition::And(items) if positive => Formula::And(each(items)),
+        Condition::And(items) => Formula::Or(each(items)),
+        Condition::Or(items) if positive => Formula::Or(each(items)),
+        Condition::Or(items) => Formula::And(each(items)),
+        Condition::Not(inner) => build(policy, inner, !positive, atoms),
+        _ => Formula::Lit(atoms.intern(policy, cond), positive),
+    }
+}
+
+/// The satisfiable cubes of `formula`, minimal and without redundant
+/// literals. Sets `truncated` when more than [`MAX_CLASSES`] were found.
+fn dnf(formula: &Formula, atoms: &Atoms, truncated: &mut bool) -> Vec<Cube> {
+    let mut cubes = match formula {
+        Formula::Const(true) => vec![Cube::new()],
+        Formula::Const(false) => Vec::new(),
+        Formula::Lit(atom, holds) => vec![Cube::from([(*atom, *holds)])],
+        Formula::Or(items) => items
+            .iter()
+            .flat_map(|f| dnf(f, atoms, truncated))
+            .collect(),
+        Formula::And(items) => {
+            let mut acc = vec![Cube::new()];
+            for item in items {
+                let right = dnf(item, atoms, truncated);
+                let mut next = Vec::new();
+                for a in &acc {
+                    for b in &right {
+                        let merged = merge(a, b).and_then(|c| atoms.normalize(c));
+                        next.extend(merged);
+                    }
+                }
+                acc = minimal(next);
+                if acc.len() > MAX_CLASSES {
+                    acc.truncate(MAX_CLASSES);
+                    *truncated = true;
+                }
+                if acc.is_empty() {
+                    break;
+                }
+            }
+            acc
+        }
+    };
+    cubes = minimal(cubes);
+    if cubes.len() > MAX_CLASSES {
+        cubes.truncate(MAX_CLASSES);
+        *truncated = true;
+    }
+    cubes
+}
+
+/// `a && b`, or `None` when they fix one atom both ways.
+fn merge(a: &Cube, b: &Cube) -> Option<Cube> {
+    let mut out = a.clone();
+    for (atom, holds) in b {
+        if *out.entry(*atom).or_insert(*holds) != *holds {
+            return None;
+        }
+    }
+    Some(out)
+}
+
+/// Drop duplicates and cubes implied by a smaller one (`a` covers `a && b`).
+fn minimal(mut cubes: Vec<Cube>) -> Vec<Cube> {
+    cubes.sort_by_key(Cube::len);
+    let mut out: Vec<Cube> = Vec::new();
+    for cube in cubes {
+        let covered = out
+            .iter()
+            .any(|smaller| smaller.iter().all(|(a, h)| cube.get(a) == Some(h)));
+        if !covered {
+            out.push(cube);
+        }
+    }
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Atoms
+// ---------------------------------------------------------------------------
+
+#[derive(Default)]
+struct Atoms {
+    /// key -> index into `conds`.
+    keys: HashMap<String, usize>,
+    conds: Vec<Condition>,
+}
+
+impl Atoms {
+    /// The atom for `cond`: its canonical text, plus the definitions of the
+    /// policy's funcs it reaches, so a changed func body is a new atom.
+    fn intern(&mut self, policy: &Policy, cond: &Condition) -> usize {
+        let mut key = format::condition(cond);
+        let mut defs: Vec<String> = Vec::new();
+        let mut seen = HashSet::new();
+        let mut pending = Vec::new();
+        calls_condition(cond, &mut pending);
+        while let Some(call) = pending.pop() {
+            if !seen.insert(call) {
+                continue;
+            }
+            let (namespace, name) = call;
+            if let Some(func) = policy
+                .functions
+                .iter()
+                .find(|f| f.namespace.as_deref() == namespace && f.name == name)
+            {
+                defs.push(format!(
+                    "{}({}) := {}",
+                    qualified(func),
+                    func.params.join(", "),
+                    format::condition(&func.body)
+                ));
+                calls_condition(&func.body, &mut pending);
+            }
+        }
+        defs.sort();
+        for def in defs {
+            key.push('\n');
+            key.push_str(&def);
+        }
+        let next = self.conds.len();
+        *self.keys.entry(key).or_insert_with(|| {
+            self.conds.push(cond.clone());
+            next
+        })
+    }
+
+    /// `cube` with literals implied by the others dropped, or `None` when
+    /// its literals contradict each other.
+    fn normalize(&self, mut cube: Cube) -> Option<Cube> {
+        let positives: Vec<&Condition> = cube
+            .iter()
+            .filter(|(_, holds)| **holds)
+            .map(|(a, _)| &self.conds[*a])
+            .collect();
+        if contradiction(&positives).is_some() {
+            return None;
+        }
+        let mut redundant = Vec::new();
+        for (&atom, &holds) in &cube {
+            let implied = cube
+                .iter()
+                .filter(|(other, fixed)| **other != atom && **fixed)
+                .find_map(|(other, _)| implied(&self.conds[*other], &self.conds[atom]));
+            match implied {
+                Some(value) if value == holds => redundant.push(atom),
+                Some(_) => return None,
+                None => {}
+            }
+        }
+        for atom in redundant {
+            cube.remove(&atom);
+        }
+        Some(cube)
+    }
+
+    fn render(&self, cube: &Cube) -> Vec<String> {
+        cube.iter()
+            .map(|(atom, holds)| {
+                let cond = &self.conds[*atom];
+                if *holds {
+                    format::condition(cond)
+                } else {
+                    format::condition(&Condition::Not(Box::new(cond.clone())))
+                }
+            })
+            .collect()
+    }
+}
+
+/// The truth of `b` whenever `a` holds, when the two compare the same
+/// operand with literals: `x == "a"` pins `x`, `x >= 5` implies `x >= 3`,
+/// and a conflicting pair rules `b` out.
+fn implied(a: &Condition, b: &Condition) -> Option<bool> {
+    if contradiction(&[a, b]).is_some() {
+        return Some(false);
+    }
+    let (a_left, a_op, a_value) = literal_comparison(a)?;
+    let (b_left, b_op, b_value) = literal_comparison(b)?;
+    if a_left != b_left {
+        return None;
+    }
+    if a_op == Operator::Equal {
+        return match b_op {
+            Operator::Equal => literal_eq(a_value, b_value),
+            Operator::NotEqual => literal_eq(a_value, b_value).map(|eq| !eq),
+            Operator::In => None,
+            _ => number(a_value).map(|x| holds(x, b_op, b_value)),
+        };
+    }
+    // Same-direction bounds: the tighter one implies the looser.
+    let tighter = |a: Option<(f64, bool)>, b: Option<(f64, bool)>, above: bool| {
+        let ((x, x_strict), (y, y_strict)) = (a?, b?);
+        let beyond = if above { x > y } else { x < y };
+        (beyond || (x == y && (x_strict || !y_strict))).then_some(true)
+    };
+    tighter(lower(a_op, a_value), lower(b_op, b_value), true)
+        .or_else(|| tighter(upper(a_op, a_value), upper(b_op, b_value), false))
+}
+
+/// `(operand text, op, literal)` for `operand <op> literal`.
+fn literal_comparison(cond: &Condition) -> Option<(String, Operator, &Value)> {
+    let Condition::Comparison { left, op, right } = cond else {
+        return None;
+    };
+    if matches!(left, ComparisonLeft::Expr(Expr::Literal(_))) {
+        return None;
+    }
+    match right {
+        ComparisonRight::Value(v) | ComparisonRight::Expr(Expr::Literal(v)) => {
+            Some((format::comparison_left(left), *op, v))
+        }
+        _ => None,
+    }
+}
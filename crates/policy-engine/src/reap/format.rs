@@ -0,0 +1,1199 @@
+//! Canonical pretty-printer for `.reap` source (`reaper-cli fmt`).
+//!
+//! [`format_policy`] and [`format_library`] print an AST back to `.reap`
+//! text; [`format_source`] formats a whole file and keeps its comments. The
+//! layout is fixed, so two edits that mean the same thing produce the same
+//! text:
+//!
+//! - imports first, then `policy name {`, metadata sorted by key, `default`,
+//!   the `func`s and then the rules, each group in source order;
+//! - a rule whose condition is an `&&` / `||` chain takes the block form,
+//!   one operand per line; a nested group stays on its operand's line until
+//!   it passes [`MAX_WIDTH`], then breaks the same way inside `( ... )`, and
+//!   a `some`/`every` block breaks inside its own braces;
+//! - four-space indent, two spaces before a trailing comment.
+//!
+//! The AST has no comments, so they are re-attached from the source: a
+//! comment belongs to the declaration (or top-level condition operand) it
+//! precedes, or ends the line of. A comment deeper inside an operand moves
+//! to just above that operand.
+//!
+//! [`format_source`] re-parses its own output and refuses to return text
+//! whose AST differs from the input's, or that lost a comment — formatting
+//! never changes what a policy decides.
+
+use super::analysis::is_library_source;
+use super::ast::{
+    self, AssignmentValue, ComparisonLeft, ComparisonRight, Comprehension, ComprehensionIterator,
+    Condition, Decision, EntityAttr, Expr, FuncDef, Index, InterpolationPart, IterationSource,
+    Operator, Policy, QuantifierKind, VarAttr,
+};
+use super::parser::{ReapParser, Rule};
+use pest::iterators::Pair;
+use pest::Parser as PestParser;
+use reaper_core::ReaperError;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Column limit past which conditions break onto more lines.
+pub const MAX_WIDTH: usize = 100;
+
+const INDENT: &str = "    ";
+
+/// Print a policy in canonical form. Functions merged from imports
+/// (namespace `Some`) are not printed; their `import` line stands for them.
+pub fn format_policy(policy: &Policy) -> String {
+    let mut printer = Printer::default();
+    printer.policy(policy, &Notes::default());
+    printer.out
+}
+
+/// Print a library file (`library name { func ... }`) in canonical form.
+pub fn format_library(name: &str, functions: &[FuncDef]) -> String {
+    let mut printer = Printer::default();
+    printer.library(name, &BTreeMap::new(), functions, &Notes::default());
+    printer.out
+}
+
+/// Format a `.reap` policy or library file, keeping its comments.
+///
+/// Fails with the parser's error on invalid source, and with an
+/// `InvalidPolicy` error if the formatted text would not parse back to the
+/// same AST or would drop a comment (a formatter bug — the input is never
+/// rewritten in that case).
+pub fn format_source(source: &str) -> Result<String, ReaperError> {
+    let comments = scan_comments(source);
+    let comment_spans: Vec<(usize, usize)> = comments.iter().map(|c| (c.start, c.end)).collect();
+
+    let formatted = if is_library_source(source) {
+        let (name, functions) = ReapParser::parse_library(source)?;
+        let tree = source_tree(Rule::library, source, &comment_spans)?;
+        let counts: Vec<usize> = functions.iter().map(|f| operand_count(&f.body)).collect();
+        let notes = attach(source, &comments, &tree.items(&counts, &[]));
+
+        let mut printer = Printer::default();
+        printer.library(&name, &tree.metadata, &functions, &notes);
+        let reparsed = ReapParser::parse_library(&printer.out).map_err(reparse_failure)?;
+        ensure_same(&(name, functions), &reparsed)?;
+        printer.out
+    } else {
+        let policy = ReapParser::parse(source)?;
+        let tree = source_tree(Rule::policy, source, &comment_spans)?;
+        let func_counts: Vec<usize> = policy
+            .functions
+            .iter()
+            .map(|f| operand_count(&f.body))
+            .collect();
+        let rule_counts: Vec<usize> = policy
+            .rules
+            .iter()
+            .map(|r| operand_count(&r.condition))
+            .collect();
+        let notes = attach(source, &comments, &tree.items(&func_counts, &rule_counts));
+
+        let mut printer = Printer::default();
+        printer.policy(&policy, &notes);
+        let reparsed = ReapParser::parse(&printer.out).map_err(reparse_failure)?;
+        ensure_same(&policy, &reparsed)?;
+        printer.out
+    };
+
+    let mut before: Vec<&str> = comments.iter().map(|c| c.text.as_str()).collect();
+    let after_comments = scan_comments(&formatted);
+    let mut after: Vec<&str> = after_comments.iter().map(|c| c.text.as_str()).collect();
+    before.sort_unstable();
+    after.sort_unstable();
+    if before != after {
+        return Err(formatter_bug("a comment would be lost"));
+    }
+    Ok(formatted)
+}
+
+fn reparse_failure(e: ReaperError) -> ReaperError {
+    formatter_bug(&format!("the output does not parse ({e})"))
+}
+
+fn formatter_bug(what: &str) -> ReaperError {
+    ReaperError::InvalidPolicy {
+        reason: format!("formatter error: {what}; the file was left unformatted"),
+    }
+}
+
+fn ensure_same<T: Serialize>(before: &T, after: &T) -> Result<(), ReaperError> {
+    match (serde_json::to_value(before), serde_json::to_value(after)) {
+        (Ok(a), Ok(b)) if a == b => Ok(()),
+        _ => Err(formatter_bug(
+            "the output would change the policy's meaning",
+        )),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Layout
+// ---------------------------------------------------------------------------
+
+#[derive(Default)]
+struct Printer {
+    out: String,
+}
+
+impl Printer {
+    fn line(&mut self, depth: usize, text: &str) {
+        for _ in 0..depth {
+            self.out.push_str(INDENT);
+        }
+        self.out.push_str(text);
+        self.out.push('\n');
+    }
+
+    /// One empty line; never two in a row, never at the top of the file.
+    fn blank(&mut self) {
+        if !self.out.is_empty() && !self.out.ends_with("\n\n") {
+            self.out.push('\n');
+        }
+    }
+
+    /// Comments above an item. `separate` puts an empty line before them
+    /// when the source had one there.
+    fn leading(&mut self, depth: usize, comments: &[Comment], separate: bool) {
+        for (i, comment) in comments.iter().enumerate() {
+            if comment.blank_before && (i > 0 || separate) {
+                self.blank();
+            }
+            self.line(depth, &comment.text);
+        }
+        if comments.last().is_some_and(|c| c.blank_after) {
+            self.blank();
+        }
+    }
+
+    fn policy(&mut self, policy: &Policy, notes: &Notes) {
+        for (i, import) in policy.imports.iter().enumerate() {
+            let note = notes.get(&Slot::Import(i));
+            self.leading(0, &note.own.leading, i > 0);
+            let text = format!("import {} as {}", string(&import.path), import.alias);
+            self.line(0, &trail(text, &note.own.trailing));
+        }
+        if !policy.imports.is_empty() {
+            self.blank();
+        }
+
+        let metadata: BTreeMap<_, _> = policy
+            .metadata
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        self.open("policy", &policy.name, notes);
+        self.body(
+            &metadata,
+            Some(&policy.default_decision),
+            &policy.functions,
+            &policy.rules,
+            notes,
+        );
+        self.close(notes);
+    }
+
+    fn library(
+        &mut self,
+        name: &str,
+        metadata: &BTreeMap<String, String>,
+        functions: &[FuncDef],
+        notes: &Notes,
+    ) {
+        self.open("library", name, notes);
+        self.body(metadata, None, functions, &[], notes);
+        self.close(notes);
+    }
+
+    fn open(&mut self, keyword: &str, name: &str, notes: &Notes) {
+        let note = notes.get(&Slot::Header);
+        self.leading(0, &note.own.leading, false);
+        self.line(
+            0,
+            &trail(format!("{keyword} {name} {{"), &note.own.trailing),
+        );
+    }
+
+    fn close(&mut self, notes: &Notes) {
+        if let Some(first) = notes.dangling.first() {
+            if first.blank_before {
+                self.blank();
+            }
+        }
+        self.leading(1, &notes.dangling, false);
+        let note = notes.get(&Slot::Close);
+        self.line(0, &trail("}".to_string(), &note.own.trailing));
+        if let Some(first) = notes.trailer.first() {
+            if first.blank_before {
+                self.blank();
+            }
+        }
+        self.leading(0, &notes.trailer, false);
+    }
+
+    fn body(
+        &mut self,
+        metadata: &BTreeMap<String, String>,
+        default: Option<&Decision>,
+        functions: &[FuncDef],
+        rules: &[ast::Rule],
+        notes: &Notes,
+    ) {
+        let mut started = false;
+
+        let mut first = true;
+        for (key, value) in metadata {
+            let note = notes.get(&Slot::Meta(key.clone()));
+            self.leading(1, &note.own.leading, !first);
+            let text = format!("{key}: {},", string(value));
+            self.line(1, &trail(text, &note.own.trailing));
+            first = false;
+            started = true;
+        }
+        if let Some(decision) = default {
+            let note = notes.get(&Slot::Default);
+            self.leading(1, &note.own.leading, !first);
+            let text = format!("default: {},", decision.as_str());
+            self.line(1, &trail(text, &note.own.trailing));
+            started = true;
+        }
+
+        let mut first = true;
+        for (i, func) in functions.iter().enumerate() {
+            if func.namespace.is_some() {
+                continue;
+            }
+            if first && started {
+                self.blank();
+            }
+            self.func(func, notes.get(&Slot::Func(i)), !first);
+            first = false;
+            started = true;
+        }
+
+        for (i, rule) in rules.iter().enumerate() {
+            if started {
+                self.blank();
+            }
+            self.rule(rule, notes.get(&Slot::Rule(i)));
+            started = true;
+        }
+    }
+
+    fn func(&mut self, func: &FuncDef, note: &ItemNotes, separate: bool) {
+        self.leading(1, &note.own.leading, separate);
+        let head = format!("func {}({}) :=", func.name, func.params.join(", "));
+        let inline = format!("{head} {},", condition(&func.body));
+        match connective(&func.body) {
+            Some((items, joiner)) if !fits(1, &inline) || note.has_inner() => {
+                self.line(1, &format!("{head} {{"));
+                self.operands(2, items, joiner, &note.operands);
+                self.leading(2, &note.tail, false);
+                self.line(1, &trail("},".to_string(), &note.own.trailing));
+            }
+            _ => self.line(1, &trail(inline, &note.own.trailing)),
+        }
+    }
+
+    fn rule(&mut self, rule: &ast::Rule, note: &ItemNotes) {
+        self.leading(1, &note.own.leading, false);
+        self.line(1, &format!("rule {} {{", rule.name));
+
+        let mut head = rule.decision.as_str().to_string();
+        if let Some(message) = &rule.message {
+            head.push_str(" with message ");
+            head.push_str(&expr(message));
+        }
+        if let Some(directives) = &rule.obligations {
+            // `with obligations {}` alone still parses to Some(empty), so it
+            // is kept when neither clause has entries.
+            if !directives.obligations.is_empty() || directives.advice.is_empty() {
+                head.push_str(" with obligations ");
+                head.push_str(&object(&directives.obligations));
+            }
+            if !directives.advice.is_empty() {
+                head.push_str(" with advice ");
+                head.push_str(&object(&directives.advice));
+            }
+        }
+
+        let inline = format!("{head} if {}", condition(&rule.condition));
+        match connective(&rule.condition) {
+            Some((items, joiner)) => {
+                self.line(2, &format!("{head} if {{"));
+                self.operands(3, items, joiner, &note.operands);
+                self.leading(3, &note.tail, false);
+                self.line(2, "}");
+            }
+            None if fits(2, &inline) => self.line(2, &inline),
+            None if matches!(rule.condition, Condition::Quantifier { .. }) => {
+                self.line(2, &format!("{head} if {{"));
+                self.quantifier(3, &rule.condition, "", &[]);
+                self.line(2, "}");
+            }
+            None => {
+                self.line(2, &format!("{head} if {{"));
+                self.line(3, &condition(&rule.condition));
+                self.line(2, "}");
+            }
+        }
+        self.line(1, &trail("}".to_string(), &note.own.trailing));
+    }
+
+    /// One operand per line, joined by `&&` / `||` at line ends.
+    fn operands(&mut self, depth: usize, items: &[Condition], joiner: &str, notes: &[Attached]) {
+        let last = items.len().saturating_sub(1);
+        for (i, item) in items.iter().enumerate() {
+            let note = notes.get(i);
+            let trailing = note.map_or(&[][..], |n| &n.trailing[..]);
+            if let Some(n) = note {
+                self.leading(depth, &n.leading, false);
+            }
+            let sep = if i == last {
+                String::new()
+            } else {
+                format!(" {joiner}")
+            };
+            let text = format!("{}{sep}", operand(item));
+            match connective(item) {
+                Some((inner, inner_joiner)) if !fits(depth, &text) => {
+                    self.line(depth, "(");
+                    self.operands(depth + 1, inner, inner_joiner, &[]);
+                    self.line(depth, &trail(format!("){sep}"), trailing));
+                }
+                None if !fits(depth, &text) => self.quantifier(depth, item, &sep, trailing),
+                _ => self.line(depth, &trail(text, trailing)),
+            }
+        }
+    }
+
+    /// A `some`/`every` block over several lines: its body is laid out like a
+    /// rule's. Anything else prints on one line.
+    fn quantifier(&mut self, depth: usize, cond: &Condition, sep: &str, trailing: &[Comment]) {
+        let Condition::Quantifier {
+            kind,
+            index,
+            variable,
+            collection,
+            body,
+        } = cond
+        else {
+            self.line(depth, &trail(format!("{}{sep}", operand(cond)), trailing));
+            return;
+        };
+        self.line(
+            depth,
+            &format!(
+                "{} {{",
+                quantifier_head(*kind, index.as_deref(), variable, collection)
+            ),
+        );
+        match connective(body) {
+            Some((items, joiner)) => self.operands(depth + 1, items, joiner, &[]),
+            None => self.quantifier(depth + 1, body, "", &[]),
+        }
+        self.line(depth, &trail(format!("}}{sep}"), trailing));
+    }
+}
+
+fn fits(depth: usize, text: &str) -> bool {
+    depth * INDENT.len() + text.chars().count() <= MAX_WIDTH
+}
+
+fn trail(mut text: String, comments: &[Comment]) -> String {
+    for comment in comments {
+        text.push_str("  ");
+        text.push_str(&comment.text);
+    }
+    text
+}
+
+/// The operands and joiner of an `&&` / `||` chain.
+fn connective(cond: &Condition) -> Option<(&[Condition], &'static str)> {
+    match cond {
+        Condition::And(items) => Some((items, "&&")),
+        Condition::Or(items) => Some((items, "||")),
+        _ => None,
+    }
+}
+
+fn operand_count(cond: &Condition) -> usize {
+    connective(cond).map_or(0, |(items, _)| items.len())
+}
+
+// ---------------------------------------------------------------------------
+// Conditions and expressions (single line)
+// ---------------------------------------------------------------------------
+
+pub(crate) fn condition(cond: &Condition) -> String {
+    match cond {
+        Condition::True => "true".to_string(),
+        Condition::False => "false".to_string(),
+        Condition::Comparison { left, op, right } => comparison(left, *op, right, true),
+        Condition::Assignment { variable, value } => {
+            format!("{variable} := {}", assignment_value(value))
+        }
+        Condition::And(items) => join(items.iter().map(operand), " && "),
+        Condition::Or(items) => join(items.iter().map(operand), " || "),
+        Condition::Not(inner) => match inner.as_ref() {
+            Condition::Expr(_) | Condition::True | Condition::False | Condition::Not(_) => {
+                format!("!{}", condition(inner))
+            }
+            _ => format!("!({})", condition(inner)),
+        },
+        Condition::Expr(e) => expr(e),
+        Condition::Quantifier {
+            kind,
+            index,
+            variable,
+            collection,
+            body,
+        } => format!(
+            "{} {{ {} }}",
+            quantifier_head(*kind, index.as_deref(), variable, collection),
+            condition(body)
+        ),
+    }
+}
+
+/// `some i, x in coll`, the collection written without `[_]`.
+pub(crate) fn quantifier_head(
+    kind: QuantifierKind,
+    index: Option<&str>,
+    variable: &str,
+    collection: &IterationSource,
+) -> String {
+    let names = match index {
+        Some(index) => format!("{index}, {variable}"),
+        None => variable.to_string(),
+    };
+    let source = match collection {
+        IterationSource::EntityAttr(attr) => entity_attr(attr),
+        IterationSource::VarAttr(attr) => var_attr(attr),
+        IterationSource::IndexedVariable { variable, .. } => variable.clone(),
+    };
+    format!("{} {names} in {source}", kind.keyword())
+}
+
+/// A chain operand: nested chains keep their parentheses (they are what
+/// makes them nested), the rest print bare.
+fn operand(cond: &Condition) -> String {
+    match cond {
+        Condition::And(_) | Condition::Or(_) => format!("({})", condition(cond)),
+        _ => condition(cond),
+    }
+}
+
+/// `value_first` picks `"admin" in user.roles` over `user.roles in "admin"`
+/// (both parse to the same node); assignments only accept the latter.
+fn comparison(
+    left: &ComparisonLeft,
+    op: Operator,
+    right: &ComparisonRight,
+    value_first: bool,
+) -> String {
+    if let (true, Operator::In, ComparisonRight::Value(value)) = (value_first, op, right) {
+        let scalar = !matches!(
+            value,
+            ast::Value::Array(_) | ast::Value::Object(_) | ast::Value::Set(_)
+        );
+        let plain_left = match left {
+            ComparisonLeft::EntityAttr(_) | ComparisonLeft::VarAttr(_) => true,
+            ComparisonLeft::Expr(Expr::Variable(name)) => !name.contains('.'),
+            ComparisonLeft::Expr(_) => false,
+        };
+        if scalar && plain_left {
+            return format!("{} in {}", literal(value), comparison_left(left));
+        }
+    }
+    format!(
+        "{} {} {}",
+        comparison_left(left),
+        op.as_str(),
+        comparison_right(right)
+    )
+}
+
+pub(crate) fn comparison_left(left: &ComparisonLeft) -> String {
+    match left {
+        ComparisonLeft::EntityAttr(attr) => entity_attr(attr),
+        ComparisonLeft::VarAttr(attr) => var_attr(attr),
+        ComparisonLeft::Expr(e) => expr(e),
+    }
+}
+
+fn comparison_right(right: &ComparisonRight) -> String {
+    match right {
+        ComparisonRight::Value(value) => literal(value),
+        ComparisonRight::EntityAttr(attr) => entity_attr(attr),
+        ComparisonRight::Variable(name) => name.clone(),
+        ComparisonRight::VarAttr(attr) => var_attr(attr),
+        ComparisonRight::Expr(e) => expr(e),
+    }
+}
+
+fn assignment_value(value: &AssignmentValue) -> String {
+    match value {
+        AssignmentValue::EntityAttr(attr) => entity_attr(attr),
+        AssignmentValue::Value(v) => literal(v),
+        AssignmentValue::Variable(name) => name.clone(),
+        AssignmentValue::Comprehension(c) => comprehension(c),
+        AssignmentValue::Expr(e) => expr(e),
+        AssignmentValue::Comparison { left, op, right } => comparison(left, *op, right, false),
+    }
+}
+
+fn comprehension(c: &Comprehension) -> String {
+    match c {
+        Comprehension::Set {
+            output,
+            iterator,
+            filters,
+        } => format!(
+            "{{{} | {}{}}}",
+            expr(output),
+            comprehension_iterator(iterator),
+            comprehension_filters(filters)
+        ),
+        Comprehension::Array {
+            output,
+            iterator,
+            filters,
+        } => format!(
+            "[{} | {}{}]",
+            expr(output),
+            comprehension_iterator(iterator),
+            comprehension_filters(filters)
+        ),
+        Comprehension::Object {
+            key,
+            value,
+            iterator,
+            filters,
+        } => format!(
+            "{{{}: {} | {}{}}}",
+            expr(key),
+            expr(value),
+            comprehension_iterator(iterator),
+            comprehension_filters(filters)
+        ),
+    }
+}
+
+fn comprehension_iterator(iterator: &ComprehensionIterator) -> String {
+    let source = match &iterator.collection {
+        IterationSource::EntityAttr(attr) => entity_attr(attr),
+        IterationSource::VarAttr(attr) => var_attr(attr),
+        IterationSource::IndexedVariable { variable, index: i } => {
+            format!("{variable}{}", index(i))
+        }
+    };
+    format!("{} := {source}", iterator.variable)
+}
+
+fn comprehension_filters(filters: &[Condition]) -> String {
+    filters
+        .iter()
+        .map(|f| format!("; {}", condition(f)))
+        .collect()
+}
+
+fn expr(e: &Expr) -> String {
+    match e {
+        Expr::Literal(value) => literal(value),
+        Expr::Interpolation(parts) => fstring(parts),
+        Expr::Variable(name) => name.clone(),
+        Expr::AttributeAccess {
+            variable,
+            attribute,
+        } => format!("{variable}.{attribute}"),
+        Expr::IndexedAccess {
+            variable,
+            attribute,
+            index: i,
+        } if attribute.is_empty() => format!("{variable}{}", index(i)),
+        Expr::IndexedAccess {
+            variable,
+            attribute,
+            index: i,
+        } => format!("{variable}.{attribute}{}", index(i)),
+        Expr::MethodCall {
+            receiver,
+            method,
+            args,
+        } => format!(
+            "{}.{}({})",
+            expr(receiver),
+            method.as_str(),
+            join(args.iter().map(expr), ", ")
+        ),
+        Expr::FunctionCall {
+            namespace,
+            function,
+            args,
+        } => {
+            let args = join(args.iter().map(expr), ", ");
+            match namespace {
+                Some(ns) => format!("{ns}::{function}({args})"),
+                None => format!("{function}({args})"),
+            }
+        }
+        Expr::BinaryOp { op, left, right } => format!(
+            "{} {} {}",
+            arith_operand(left, op.precedence(), false),
+            op.symbol(),
+            arith_operand(right, op.precedence(), true)
+        ),
+    }
+}
+
+/// Parenthesize an arithmetic operand that binds looser than its parent,
+/// or as tightly on the right (operators are left-associative).
+fn arith_operand(e: &Expr, parent: u8, right: bool) -> String {
+    match e {
+        Expr::BinaryOp { op, .. }
+            if op.precedence() < parent || (right && op.precedence() == parent) =>
+        {
+            format!("({})", expr(e))
+        }
+        _ => expr(e),
+    }
+}
+
+fn entity_attr(attr: &EntityAttr) -> String {
+    let index = attr.index.as_ref().map(index).unwrap_or_default();
+    format!("{}.{}{index}", attr.entity.as_str(), attr.attribute)
+}
+
+fn var_attr(attr: &VarAttr) -> String {
+    let index = attr.index.as_ref().map(index).unwrap_or_default();
+    format!("{}.{}{index}", attr.variable, attr.attribute)
+}
+
+fn index(i: &Index) -> String {
+    match i {
+        Index::Number(n) => format!("[{n}]"),
+        Index::String(s) => format!("[{}]", string(s)),
+        Index::Wildcard => "[_]".to_string(),
+    }
+}
+
+fn literal(value: &ast::Value) -> String {
+    match value {
+        ast::Value::String(s) => string(s),
+        ast::Value::Integer(i) => i.to_string(),
+        ast::Value::Float(f) => {
+            // The grammar needs digits on both sides of the point.
+            let text = f.to_string();
+            if text.contains('.') {
+                text
+            } else {
+                format!("{text}.0")
+            }
+        }
+        ast::Value::Boolean(b) => b.to_string(),
+        ast::Value::Null => "null".to_string(),
+        ast::Value::Array(items) => format!("[{}]", join(items.iter().map(literal), ", ")),
+        ast::Value::Set(items) => format!("{{{}}}", join(items.iter().map(literal), ", ")),
+        ast::Value::Object(pairs) => object(pairs),
+    }
+}
+
+fn object(pairs: &[(String, ast::Value)]) -> String {
+    let pairs = pairs
+        .iter()
+        .map(|(k, v)| format!("{}: {}", string(k), literal(v)));
+    format!("{{{}}}", join(pairs, ", "))
+}
+
+/// A string literal that reads back as `s`. Text with a backslash and no
+/// quote prints raw, the way regex patterns read best (`r"^\d+$"`);
+/// anything else prints quoted with `\` and `"` escaped.
+fn string(s: &str) -> String {
+    if s.contains('\\') && !s.contains('"') {
+        format!("r\"{s}\"")
+    } else {
+        format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+/// An interpolated string: literal text escapes `\`, `"` and braces;
+/// holes print their expression.
+fn fstring(parts: &[InterpolationPart]) -> String {
+    let mut out = String::from("f\"");
+    for part in parts {
+        match part {
+            InterpolationPart::Literal(text) => {
+                for c in text.chars() {
+                    match c {
+                        '\\' | '"' => {
+                            out.push('\\');
+                            out.push(c);
+                        }
+                        '{' | '}' => {
+                            out.push(c);
+                            out.push(c);
+                        }
+                        _ => out.push(c),
+                    }
+                }
+            }
+            InterpolationPart::Expr(e) => {
+                out.push('{');
+                out.push_str(&expr(e));
+                out.push('}');
+            }
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// The parser's unescaping of a string literal's contents.
+fn unescape(inner: &str) -> String {
+    inner.replace("\\\"", "\"").replace("\\\\", "\\")
+}
+
+fn join(parts: impl Iterator<Item = String>, sep: &str) -> String {
+    parts.collect::<Vec<_>>().join(sep)
+}
+
+// ---------------------------------------------------------------------------
+// Comments
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+struct Comment {
+    start: usize,
+    end: usize,
+    text: String,
+    /// Nothing but whitespace before it on its line.
+    own_line: bool,
+    /// An empty line separates it from the code or comment above.
+    blank_before: bool,
+    /// An empty line separates it from what follows.
+    blank_after: bool,
+}
+
+/// Every `//` and `/* */` comment outside string literals.
+fn scan_comments(source: &str) -> Vec<Comment> {
+    let bytes = source.as_bytes();
+    let mut comments = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match (bytes[i], bytes.get(i + 1)) {
+            (b'"', _) => i = skip_string(bytes, i),
+            (b'r' | b'f', Some(b'"')) if !continues_ident(bytes, i) => i = skip_string(bytes, i),
+            (b'/', Some(b'/')) => {
+                let end = source[i..].find('\n').map_or(source.len(), |n| i + n);
+                comments.push(comment(source, i, end));
+                i = end;
+            }
+            (b'/', Some(b'*')) => {
+                let end = source[i + 2..]
+                    .find("*/")
+                    .map_or(source.len(), |n| i + 2 + n + 2);
+                comments.push(comment(source, i, end));
+                i = end;
+            }
+            _ => i += 1,
+        }
+    }
+    comments
+}
+
+/// Whether the byte before `i` is part of an identifier, so an `r"`/`f"`
+/// there is not a string prefix.
+fn continues_ident(bytes: &[u8], i: usize) -> bool {
+    i > 0 && (bytes[i - 1].is_ascii_alphanumeric() || bytes[i - 1] == b'_')
+}
+
+/// The index just past the string literal starting at `i`: a plain
+/// `"..."` with backslash escapes, a raw `r"..."` with none, or an
+/// `f"..."` whose `{...}` holes may hold strings of their own.
+fn skip_string(bytes: &[u8], mut i: usize) -> usize {
+    let prefix = bytes[i];
+    if prefix != b'"' {
+        i += 1;
+    }
+    i += 1;
+    let mut depth = 0usize;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' if depth == 0 => return i + 1,
+            b'\\' if prefix != b'r' && depth == 0 => i += 2,
+            b'{' | b'}' if prefix == b'f' && bytes.get(i + 1) == Some(&bytes[i]) && depth == 0 => {
+                i += 2
+            }
+            b'{' if prefix == b'f' => {
+                depth += 1;
+                i += 1;
+            }
+            b'}' if prefix == b'f' => {
+                depth = depth.saturating_sub(1);
+                i += 1;
+            }
+            b'"' => i = skip_string(bytes, i),
+            b'r' | b'f'
+                if depth > 0 && bytes.get(i + 1) == Some(&b'"') && !continues_ident(bytes, i) =>
+            {
+                i = skip_string(bytes, i)
+            }
+            _ => i += 1,
+        }
+    }
+    i
+}
+
+fn comment(source: &str, start: usize, end: usize) -> Comment {
+    let before = &source[..start];
+    let line_start = before.rfind('\n').map_or(0, |n| n + 1);
+    let gap_before = &before[before.trim_end().len()..];
+    let after = &source[end..];
+    let gap_after = &after[..after.len() - after.trim_start().len()];
+    Comment {
+        start,
+        end,
+        text: source[start..end].trim_end().to_string(),
+        own_line: source[line_start..start].trim().is_empty(),
+        blank_before: gap_before.matches('\n').count() >= 2,
+        blank_after: gap_after.matches('\n').count() >= 2,
+    }
+}
+
+/// What a comment can attach to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Slot {
+    Import(usize),
+    /// `policy name {` / `library name {`
+    Header,
+    Meta(String),
+    Default,
+    Func(usize),
+    Rule(usize),
+    /// The closing `}`
+    Close,
+}
+
+#[derive(Debug, Default)]
+struct Attached {
+    leading: Vec<Comment>,
+    trailing: Vec<Comment>,
+}
+
+#[derive(Debug, Default)]
+struct ItemNotes {
+    own: Attached,
+    /// Per top-level condition operand (funcs and rules).
+    operands: Vec<Attached>,
+    /// After the last operand, before the block closes.
+    tail: Vec<Comment>,
+}
+
+impl ItemNotes {
+    fn has_inner(&self) -> bool {
+        !self.tail.is_empty()
+            || self
+                .operands
+                .iter()
+                .any(|o| !o.leading.is_empty() || !o.trailing.is_empty())
+    }
+}
+
+#[derive(Debug, Default)]
+struct Notes {
+    items: HashMap<Slot, ItemNotes>,
+    /// Own-line comments before the closing `}`.
+    dangling: Vec<Comment>,
+    /// Comments after the closing `}`.
+    trailer: Vec<Comment>,
+}
+
+static NO_NOTES: ItemNotes = ItemNotes {
+    own: Attached {
+        leading: Vec::new(),
+        trailing: Vec::new(),
+    },
+    operands: Vec::new(),
+    tail: Vec::new(),
+};
+
+impl Notes {
+    fn get(&self, slot: &Slot) -> &ItemNotes {
+        self.items.get(slot).unwrap_or(&NO_NOTES)
+    }
+}
+
+/// A declaration's byte span in the source, with the spans of its
+/// condition's top-level operands.
+struct Item {
+    slot: Slot,
+    start: usize,
+    end: usize,
+    operands: Vec<(usize, usize)>,
+}
+
+/// The declarations of a parsed file, in source order.
+struct SourceTree {
+    header: (usize, usize),
+    close: usize,
+    imports: Vec<(usize, usize)>,
+    /// Body declarations; `Func` / `Rule` indices count funcs and rules
+    /// separately, matching the AST vectors.
+    body: Vec<Item>,
+    /// Library metadata (the library AST does not keep it).
+    metadata: BTreeMap<String, String>,
+}
+
+impl SourceTree {
+    /// All items, dropping operand spans that do not line up with the AST's
+    /// chain (e.g. `{ (a && b) }` is one operand in the tree, two in the
+    /// AST); their comments then attach to the item itself.
+    fn items(&self, func_counts: &[usize], rule_counts: &[usize]) -> Vec<Item> {
+        let mut items: Vec<Item> = self
+            .imports
+            .iter()
+            .enumerate()
+            .map(|(i, &(start, end))| Item {
+                slot: Slot::Import(i),
+                start,
+                end,
+                operands: Vec::new(),
+            })
+            .collect();
+        items.push(Item {
+            slot: Slot::Header,
+            start: self.header.0,
+            end: self.header.1,
+            operands: Vec::new(),
+        });
+        for item in &self.body {
+            let expected = match item.slot {
+                Slot::Func(i) => func_counts.get(i).copied(),
+                Slot::Rule(i) => rule_counts.get(i).copied(),
+                _ => None,
+            };
+            let operands = if expected == Some(item.operands.len()) {
+                item.operands.clone()
+            } else {
+                Vec::new()
+            };
+            items.push(Item {
+                slot: item.slot.clone(),
+                start: item.start,
+                end: item.end,
+                operands,
+            });
+        }
+        items.push(Item {
+            slot: Slot::Close,
+            start: self.close,
+            end: self.close + 1,
+            operands: Vec::new(),
+        });
+        items
+    }
+}
+
+fn source_tree(
+    top: Rule,
+    source: &str,
+    comments: &[(usize, usize)],
+) -> Result<SourceTree, ReaperError> {
+    let root = <ReapParser as PestParser<Rule>>::parse(top, source)
+        .map_err(|e| ReaperError::InvalidPolicy {
+            reason: format!("Parse error: {}", e),
+        })?
+        .next()
+        .ok_or_else(|| formatter_bug("empty parse tree"))?;
+
+    let keyword = if top == Rule::library {
+        "library"
+    } else {
+        "policy"
+    };
+    let mut tree = SourceTree {
+        header: (0, 0),
+        close: rfind_code(source, '}', comments).unwrap_or(source.len()),
+        imports: Vec::new(),
+        body: Vec::new(),
+        metadata: BTreeMap::new(),
+    };
+    let (mut funcs, mut rules) = (0, 0);
+    let mut items: Vec<Pair<'_, Rule>> = Vec::new();
+    for pair in root.into_inner() {
+        match pair.as_rule() {
+            Rule::import_stmt => tree
+                .imports
+                .push((pair.as_span().start(), pair.as_span().end())),
+            Rule::ident => {
+                let name = pair.as_span();
+                let start = source[..name.start()].rfind(keyword).unwrap_or(0);
+                let brace = find_code(source, name.end(), '{', comments).unwrap_or(name.end());
+                tree.header = (start, brace + 1);
+            }
+            Rule::policy_body => items.extend(pair.into_inner()),
+            _ => items.push(pair),
+        }
+    }
+
+    for pair in items {
+        let span = pair.as_span();
+        let (slot, operands) = match pair.as_rule() {
+            Rule::metadata_field => {
+                let mut inner = pair.into_inner();
+                let key = inner.next().map(|p| p.as_str().to_string());
+                let value = inner.next().map(|p| p.as_str());
+                let key = key.unwrap_or_default();
+                if let Some(literal) = value {
+                    let contents = match literal.strip_prefix('r') {
+                        Some(raw) => raw[1..raw.len() - 1].to_string(),
+                        None => unescape(&literal[1..literal.len() - 1]),
+                    };
+                    tree.metadata.insert(key.clone(), contents);
+                }
+                (Slot::Meta(key), Vec::new())
+            }
+            Rule::default_field => (Slot::Default, Vec::new()),
+            Rule::func_def => {
+                funcs += 1;
+                (Slot::Func(funcs - 1), operand_spans(pair))
+            }
+            Rule::rule => {
+                rules += 1;
+                (Slot::Rule(rules - 1), operand_spans(pair))
+            }
+            _ => continue,
+        };
+        tree.body.push(Item {
+            slot,
+            start: span.start(),
+            end: span.end(),
+            operands,
+        });
+    }
+    Ok(tree)
+}
+
+/// Spans of the top-level `&&` / `||` operands of a func's or rule's
+/// condition (its last `condition` child), or none for a single operand.
+fn operand_spans(decl: Pair<'_, Rule>) -> Vec<(usize, usize)> {
+    let Some(cond) = decl
+        .into_inner()
+        .filter(|p| p.as_rule() == Rule::condition)
+        .last()
+    else {
+        return Vec::new();
+    };
+    // condition > (condition_block >) condition_expr > or_expr
+    let mut node = cond;
+    while node.as_rule() != Rule::or_expr {
+        match node.into_inner().next() {
+            Some(next) => node = next,
+            None => return Vec::new(),
+        }
+    }
+    let spans = |pairs: Vec<Pair<'_, Rule>>| -> Vec<(usize, usize)> {
+        pairs
+            .iter()
+            .map(|p| (p.as_span().start(), p.as_span().end()))
+            .collect()
+    };
+    let ands: Vec<_> = node.into_inner().collect();
+    if ands.len() > 1 {
+        return spans(ands);
+    }
+    let nots: Vec<_> = ands
+        .into_iter()
+        .next()
+        .map(|and| and.into_inner().collect())
+        .unwrap_or_default();
+    if nots.len() > 1 {
+        spans(nots)
+    } else {
+        Vec::new()
+    }
+}
+
+fn in_comment(pos: usize, comments: &[(usize, usize)]) -> bool {
+    comments.iter().any(|&(s, e)| s <= pos && pos < e)
+}
+
+fn find_code(source: &str, from: usize, ch: char, comments: &[(usize, usize)]) -> Option<usize> {
+    source[from..]
+        .char_indices()
+        .map(|(i, c)| (from + i, c))
+        .find(|&(i, c)| c == ch && !in_comment(i, comments))
+        .map(|(i, _)| i)
+}
+
+fn rfind_code(source: &str, ch: char, comments: &[(usize, usize)]) -> Option<usize> {
+    source
+        .char_indices()
+        .rev()
+        .find(|&(i, c)| c == ch && !in_comment(i, comments))
+        .map(|(i, _)| i)
+}
+
+/// Decide where each comment goes: the operand or item it ends the line of,
+/// else the operand or item it precedes.
+fn attach(source: &str, comments: &[Comment], items: &[Item]) -> Notes {
+    let same_line = |a: usize, b: usize| !source[a.min(b)..a.max(b)].contains('\n');
+    let mut notes = Notes::default();
+
+    for c in comments {
+        let containing = items
+            .iter()
+            .find(|it| it.start < c.start && c.start < it.end);
+
+        if !c.own_line {
+            if let Some(item) = containing {
+                let ended = item
+                    .operands
+                    .iter()
+                    .rposition(|&(_, end)| end <= c.start && same_line(end, c.start));
+                if let Some(k) = ended {
+                    notes_for(&mut notes, item).operands[k]
+                        .trailing
+                        .push(c.clone());
+                    continue;
+                }
+            }
+            let before = items
+                .iter()
+                .rev()
+                .find(|it| it.end <= c.start && same_line(it.end, c.start));
+            if let Some(item) = before {
+                notes_for(&mut notes, item).own.trailing.push(c.clone());
+                continue;
+            }
+        }
+
+        if let Some(item) = containing {
+            let next = item.operands.iter().position(|&(_, end)| end > c.start);
+            let target = notes_for(&mut notes, item);
+            match next {
+                Some(k) => target.operands[k].leading.push(c.clone()),
+                None if !item.operands.is_empty() => target.tail.push(c.clone()),
+                None => target.own.leading.push(c.clone()),
+            }
+            continue;
+        }
+        match items.iter().find(|it| it.start >= c.end) {
+            Some(item) if item.slot == Slot::Close => notes.dangling.push(c.clone()),
+            Some(item) => notes_for(&mut notes, item).own.leading.push(c.clone()),
+            None => notes.trailer.push(c.clone()),
+        }
+    }
+    notes
+}
+
+fn notes_for<'n>(notes: &'n mut Notes, item: &Item) -> &'n mut ItemNotes {
+    let slot = notes.items.entry(item.slot.clone()).or_default();
+    if slot.operands.len() < item.operands.len() {
+        slot.operands
+            .resize_with(item.operands.len(), Attached::default);
+    }
+    slot
+}
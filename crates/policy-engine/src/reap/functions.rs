@@ -13,7 +13,7 @@
 
 use super::ast::{
     AssignmentValue, ComparisonLeft, ComparisonRight, Comprehension, Condition, Expr, FuncDef,
-    Policy,
+    InterpolationPart, Policy,
 };
 use reaper_core::ReaperError;
 use std::collections::HashSet;
@@ -21,8 +21,10 @@ use std::collections::HashSet;
 /// Builtin function namespaces. An import alias or imported-function
 /// namespace may not collide with these — `time::x(...)` must always mean the
 /// builtin namespace.
-pub(crate) const BUILTIN_NAMESPACES: &[&str] =
-    &["time", "math", "regex", "json", "jwt", "rebac", "taint"];
+pub(crate) const BUILTIN_NAMESPACES: &[&str] = &[
+    "time", "math", "regex", "json", "jwt", "rebac", "taint", "net", "strings", "base64", "hex",
+    "url", "glob", "semver",
+];
 
 /// Builtin global (un-namespaced) functions. A policy-local `func` may not
 /// take one of these names.
@@ -35,6 +37,8 @@ pub(crate) const BUILTIN_GLOBALS: &[&str] = &[
     "is_set",
     "is_object",
     "is_null",
+    "to_number",
+    "to_string",
 ];
 
 /// Identifiers that can never name a `func` or a parameter (entity keywords,
@@ -355,7 +359,7 @@ const PSEUDO_VARS: &[&str] = &["user", "resource", "actor"];
 /// Is `name` an entity reference rather than a variable? Covers both bare
 /// entity keywords and the parser's dotted pseudo-variable form for entity
 /// method calls (`Variable("user.email")`).
-fn is_entity_rooted_name(name: &str) -> bool {
+pub(crate) fn is_entity_rooted_name(name: &str) -> bool {
     ENTITY_KEYWORDS.contains(&name)
         || ENTITY_KEYWORDS
             .iter()
@@ -364,7 +368,7 @@ fn is_entity_rooted_name(name: &str) -> bool {
 
 /// Collect variable names a condition tree REFERENCES (reads), excluding
 /// entity-keyword-rooted accesses (those are entity references).
-fn collect_referenced_vars_condition(cond: &Condition, out: &mut HashSet<String>) {
+pub(crate) fn collect_referenced_vars_condition(cond: &Condition, out: &mut HashSet<String>) {
     fn from_expr(e: &Expr, out: &mut HashSet<String>) {
         match e {
             Expr::Variable(v) => {
@@ -384,6 +388,15 @@ fn collect_referenced_vars_condition(cond: &Condition, out: &mut HashSet<String>
                     from_expr(a, out);
                 }
             }
+            Expr::BinaryOp { left, right, .. } => {
+                from_expr(left, out);
+                from_expr(right, out);
+            }
+            Expr::Interpolation(parts) => {
+                for e in parts.iter().filter_map(InterpolationPart::expr) {
+                    from_expr(e, out);
+                }
+            }
             Expr::Literal(_) => {}
         }
     }
@@ -469,12 +482,26 @@ fn collect_referenced_vars_condition(cond: &Condition, out: &mut HashSet<String>
         }
         Condition::Not(inner) => collect_referenced_vars_condition(inner, out),
         Condition::Expr(e) => from_expr(e, out),
+        Condition::Quantifier {
+            collection, body, ..
+        } => {
+            match collection {
+                super::ast::IterationSource::VarAttr(va) => {
+                    out.insert(va.variable.clone());
+                }
+                super::ast::IterationSource::IndexedVariable { variable, .. } => {
+                    out.insert(variable.clone());
+                }
+                super::ast::IterationSource::EntityAttr(_) => {}
+            }
+            collect_referenced_vars_condition(body, out);
+        }
         Condition::True | Condition::False => {}
     }
 }
 
-/// Collect variable names a condition tree BINDS (assignments and
-/// comprehension iterator variables).
+/// Collect variable names a condition tree BINDS (assignments, comprehension
+/// iterator variables, and the names a `some`/`every` block binds).
 pub(crate) fn collect_bound_vars_condition(cond: &Condition, out: &mut HashSet<String>) {
     match cond {
         Condition::Assignment { variable, value } => {
@@ -489,6 +516,16 @@ pub(crate) fn collect_bound_vars_condition(cond: &Condition, out: &mut HashSet<S
             }
         }
         Condition::Not(inner) => collect_bound_vars_condition(inner, out),
+        Condition::Quantifier {
+            index,
+            variable,
+            body,
+            ..
+        } => {
+            out.extend(index.iter().cloned());
+            out.insert(variable.clone());
+            collect_bound_vars_condition(body, out);
+        }
         Condition::True | Condition::False | Condition::Comparison { .. } | Condition::Expr(_) => {}
     }
 }
@@ -555,7 +592,7 @@ impl MeasureCtx<'_> {
                     max = max.max(self.measure_condition(c, depth + 1, sites)?);
                 }
             }
-            Condition::Not(inner) => {
+            Condition::Not(inner) | Condition::Quantifier { body: inner, .. } => {
                 max = max.max(self.measure_condition(inner, depth + 1, sites)?);
             }
             Condition::Expr(e) => {
@@ -696,6 +733,15 @@ impl MeasureCtx<'_> {
                     max = max.max(self.measure_expr_at(a, depth + 1, sites)?);
                 }
             }
+            Expr::BinaryOp { left, right, .. } => {
+                max = max.max(self.measure_expr_at(left, depth + 1, sites)?);
+                max = max.max(self.measure_expr_at(right, depth + 1, sites)?);
+            }
+            Expr::Interpolation(parts) => {
+                for e in parts.iter().filter_map(InterpolationPart::expr) {
+                    max = max.max(self.measure_expr_at(e, depth + 1, sites)?);
+                }
+            }
             Expr::Literal(_)
             | Expr::Variable(_)
             | Expr::AttributeAccess { .. }
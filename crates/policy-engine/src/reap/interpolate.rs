@@ -0,0 +1,100 @@
+//! Rendering of interpolated strings (`f"..."`) and `strings::format`,
+//! shared by the AST interpreter and the compiled evaluator so the two
+//! cannot drift.
+//!
+//! A hole renders its value as text:
+//! - strings render as-is (no quotes);
+//! - everything else renders as compact JSON (`3`, `2.5`, `true`, `null`,
+//!   `["a","b"]`, `{"k":1}`), objects with sorted keys;
+//! - sets render as arrays sorted by their rendered elements, so the text
+//!   never depends on storage order;
+//! - non-finite floats render as `null`, like JSON.
+
+use reaper_core::ReaperError;
+use serde_json::Value;
+
+/// Render one hole value (already in JSON form) as text.
+pub(crate) fn render(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => canonical(other).to_string(),
+    }
+}
+
+/// Sort a set's JSON elements by their rendered text.
+pub(crate) fn sorted_set(items: Vec<Value>) -> Value {
+    let mut items: Vec<Value> = items.iter().map(canonical).collect();
+    items.sort_by_cached_key(|v| v.to_string());
+    Value::Array(items)
+}
+
+/// `value` with every object's keys in sorted order, whatever order the
+/// map kept them in.
+fn canonical(value: &Value) -> Value {
+    match value {
+        Value::Array(items) => Value::Array(items.iter().map(canonical).collect()),
+        Value::Object(map) => {
+            let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k.clone(), canonical(v)))
+                    .collect(),
+            )
+        }
+        scalar => scalar.clone(),
+    }
+}
+
+/// Split a `strings::format` template at its `{}` placeholders, resolving
+/// `{{` and `}}` to literal braces: `n` placeholders give `n + 1` pieces.
+/// Any other brace is an error.
+pub(crate) fn split(template: &str) -> Result<Vec<String>, ReaperError> {
+    let mut pieces = Vec::new();
+    let mut piece = String::new();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('{', Some('{')) | ('}', Some('}')) => {
+                chars.next();
+                piece.push(c);
+            }
+            ('{', Some('}')) => {
+                chars.next();
+                pieces.push(std::mem::take(&mut piece));
+            }
+            ('{' | '}', _) => {
+                return Err(ReaperError::InvalidPolicy {
+                    reason: format!(
+                        "strings::format() template has an unmatched '{c}' (use '{c}{c}' for a literal brace)"
+                    ),
+                })
+            }
+            _ => piece.push(c),
+        }
+    }
+    pieces.push(piece);
+    Ok(pieces)
+}
+
+/// Fill the `{}` placeholders of a `strings::format` template positionally.
+/// The placeholder count must equal the argument count.
+pub(crate) fn fill(template: &str, args: &[String]) -> Result<String, ReaperError> {
+    let pieces = split(template)?;
+    if pieces.len() - 1 != args.len() {
+        return Err(ReaperError::InvalidPolicy {
+            reason: format!(
+                "strings::format() template has {} placeholder(s) but {} argument(s) were given",
+                pieces.len() - 1,
+                args.len()
+            ),
+        });
+    }
+    let mut out = pieces[0].clone();
+    for (arg, piece) in args.iter().zip(&pieces[1..]) {
+        out.push_str(arg);
+        out.push_str(piece);
+    }
+    Ok(out)
+}
@@ -3,10 +3,11 @@
 //!
 //! An authorization language must not be able to stack-overflow the parser,
 //! compiler, or evaluator on crafted input. The grammar is directly recursive
-//! at two points — parenthesised sub-expressions (`primary_expr = "(" ~
-//! condition_expr ~ ")"`) and prefix negation (`not_expr = "!" ~ not_expr`) —
-//! and pest builds the parse tree by recursive descent, so `"(".repeat(100k)`
-//! or `"!".repeat(100k)` recurse at *parse* time before any of our code runs.
+//! at three points — parenthesised sub-expressions (`primary_expr = "(" ~
+//! condition_expr ~ ")"`), prefix negation (`not_expr = "!" ~ not_expr`) and
+//! braces (`some`/`every` blocks, set and object literals) — and pest builds
+//! the parse tree by recursive descent, so `"(".repeat(100k)` or
+//! `"!".repeat(100k)` recurse at *parse* time before any of our code runs.
 //! The `&&`/`||` chains are grammar repetitions, not recursion, so they don't
 //! contribute to depth.
 //!
@@ -21,7 +22,8 @@
 //!    itself overflow even on a pathologically deep tree.
 
 use super::ast::{
-    AssignmentValue, ComparisonLeft, ComparisonRight, Comprehension, Condition, Expr, Policy,
+    AssignmentValue, ComparisonLeft, ComparisonRight, Comprehension, Condition, Expr,
+    InterpolationPart, Policy,
 };
 use reaper_core::ReaperError;
 
@@ -52,20 +54,31 @@ fn too_deep(limit: usize) -> ReaperError {
     }
 }
 
-/// Reject source whose parenthesis nesting or prefix-negation run exceeds
-/// `limit`, scanning without invoking pest so the parser cannot overflow first.
+/// Reject source whose parenthesis or brace nesting, or prefix-negation run,
+/// exceeds `limit`, scanning without invoking pest so the parser cannot
+/// overflow first.
 ///
-/// String literals are skipped so a `"("` *inside* a string never counts. `!=`
+/// String literals are skipped so a `"("` *inside* a string never counts; raw
+/// strings (`r"..."`) have no escapes, and an f-string's `{...}` holes are
+/// code again, each counting as a brace level. `!=`
 /// is the not-equal operator, not a negation, so it does not count either.
-/// Parens and negation runs are tracked independently; the worst real recursion
-/// is bounded by their sum, which stays comfortably within the stack for any
-/// input this accepts.
+/// Parens, braces and negation runs are tracked independently; the worst real
+/// recursion is bounded by their sum, which stays comfortably within the stack
+/// for any input this accepts. Braces include the policy's and rule's own,
+/// a constant few levels.
 pub fn source_nesting_exceeds(input: &str, limit: usize) -> bool {
     let bytes = input.as_bytes();
     let mut in_string = false;
+    let mut raw = false;
     let mut escaped = false;
+    // Inside an f-string's literal text; `holes` holds the brace depth each
+    // open hole started at, so its closing `}` returns to the text.
+    let mut in_fstring = false;
+    let mut holes: Vec<usize> = Vec::new();
     let mut paren_depth: usize = 0;
     let mut max_paren: usize = 0;
+    let mut brace_depth: usize = 0;
+    let mut max_brace: usize = 0;
     let mut bang_run: usize = 0;
     let mut i = 0;
 
@@ -74,7 +87,7 @@ pub fn source_nesting_exceeds(input: &str, limit: usize) -> bool {
         if in_string {
             if escaped {
                 escaped = false;
-            } else if c == b'\\' {
+            } else if c == b'\\' && !raw {
                 escaped = true;
             } else if c == b'"' {
                 in_string = false;
@@ -82,9 +95,32 @@ pub fn source_nesting_exceeds(input: &str, limit: usize) -> bool {
             i += 1;
             continue;
         }
+        if in_fstring {
+            match (c, bytes.get(i + 1)) {
+                (b'\\', _) | (b'{', Some(b'{')) | (b'}', Some(b'}')) => i += 1,
+                (b'{', _) => {
+                    holes.push(brace_depth);
+                    brace_depth += 1;
+                    max_brace = max_brace.max(brace_depth);
+                    in_fstring = false;
+                }
+                (b'"', _) => in_fstring = false,
+                _ => {}
+            }
+            i += 1;
+            continue;
+        }
+        let prefix = if i > 0 { bytes[i - 1] } else { b' ' };
+        let prefixed = matches!(prefix, b'r' | b'f')
+            && !(i > 1 && (bytes[i - 2].is_ascii_alphanumeric() || bytes[i - 2] == b'_'));
         match c {
+            b'"' if prefixed && prefix == b'f' => {
+                in_fstring = true;
+                bang_run = 0;
+            }
             b'"' => {
                 in_string = true;
+                raw = prefixed;
                 bang_run = 0;
             }
             b'(' => {
@@ -96,6 +132,19 @@ pub fn source_nesting_exceeds(input: &str, limit: usize) -> bool {
                 paren_depth = paren_depth.saturating_sub(1);
                 bang_run = 0;
             }
+            b'{' => {
+                brace_depth += 1;
+                max_brace = max_brace.max(brace_depth);
+                bang_run = 0;
+            }
+            b'}' => {
+                brace_depth = brace_depth.saturating_sub(1);
+                if holes.last() == Some(&brace_depth) {
+                    holes.pop();
+                    in_fstring = true;
+                }
+                bang_run = 0;
+            }
             b'!' => {
                 // `!=` is a comparison operator, not a negation — skip it.
                 if bytes.get(i + 1) == Some(&b'=') {
@@ -113,7 +162,7 @@ pub fn source_nesting_exceeds(input: &str, limit: usize) -> bool {
             b' ' | b'\t' | b'\r' | b'\n' => {}
             _ => bang_run = 0,
         }
-        if max_paren > limit {
+        if max_paren > limit || max_brace > limit {
             return true;
         }
         i += 1;
@@ -173,6 +222,7 @@ fn check_condition_depth(cond: &Condition, depth: usize, limit: usize) -> Result
         }
         Condition::Not(inner) => check_condition_depth(inner, depth + 1, limit),
         Condition::Expr(expr) => check_expr_depth(expr, depth + 1, limit),
+        Condition::Quantifier { body, .. } => check_condition_depth(body, depth + 1, limit),
     }
 }
 
@@ -277,6 +327,16 @@ fn check_expr_depth(expr: &Expr, depth: usize, limit: usize) -> Result<(), Reape
             }
             Ok(())
         }
+        Expr::BinaryOp { left, right, .. } => {
+            check_expr_depth(left, depth + 1, limit)?;
+            check_expr_depth(right, depth + 1, limit)
+        }
+        Expr::Interpolation(parts) => {
+            for e in parts.iter().filter_map(InterpolationPart::expr) {
+                check_expr_depth(e, depth + 1, limit)?;
+            }
+            Ok(())
+        }
         Expr::Literal(_)
         | Expr::Variable(_)
         | Expr::AttributeAccess { .. }
@@ -309,6 +369,22 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn source_scan_understands_raw_and_interpolated_strings() {
+        // A raw string ending in a backslash still ends at its quote: the
+        // parens after it are code.
+        let s = format!("r\"\\\" {}", "(".repeat(65));
+        assert!(source_nesting_exceeds(&s, 64));
+        // f-string text is skipped, but each hole is a brace level.
+        assert!(!source_nesting_exceeds(
+            &format!("f\"{}\"", "(".repeat(1000)),
+            64
+        ));
+        let nested = format!("{}x{}", "f\"{".repeat(65), "}\"".repeat(65));
+        assert!(source_nesting_exceeds(&nested, 64));
+        assert!(!source_nesting_exceeds("f\"{{{a} {{b}}\\\"}\"", 64));
+    }
+
     #[test]
     fn source_scan_allows_realistic_policy() {
         let policy = r#"
@@ -0,0 +1,603 @@
+//! Static analysis of `.reap` policies (`reaper-cli lint`, and the
+//! management service's validation warnings).
+//!
+//! Everything here reads the AST only; nothing evaluates. Findings:
+//!
+//! - **unsatisfiable**: a rule (or the policy's own `func`) whose condition
+//!   can never hold — `x == "a" && x == "b"`, `n > 5 && n < 3`,
+//!   `c && !c`, a literal `false`;
+//! - **dead condition**: an `||` alternative that can never hold inside a
+//!   condition that otherwise can;
+//! - **shadowed**: an `allow` rule that can never decide, because whenever
+//!   it matches, a `deny` rule whose conjuncts are a subset of its own
+//!   (an unconditional deny included) matches too — deny overrides;
+//! - **duplicate**: a rule with the same effect, condition, message and
+//!   obligations as an earlier rule in the same package (across policies
+//!   when linting a package);
+//! - **unused func / import**: a `func` or import alias never reached from
+//!   any rule.
+//!
+//! The checks are syntactic and conservative: conjuncts are compared in
+//! their canonical ([`format`](super::format)) form, and only comparisons of
+//! one operand against literals are reasoned about. A finding is always
+//! real; a clean result is not a proof.
+
+use super::analysis::{self, SourceOutline};
+use super::ast::{
+    AssignmentValue, ComparisonLeft, ComparisonRight, Comprehension, Condition, Decision, Expr,
+    FuncDef, InterpolationPart, Operator, Policy, Rule, Value,
+};
+use super::format;
+use super::functions::{
+    collect_bound_vars_condition, collect_referenced_vars_condition, is_entity_rooted_name,
+};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintKind {
+    Unsatisfiable,
+    DeadCondition,
+    Shadowed,
+    Duplicate,
+    UnusedFunc,
+    UnusedImport,
+}
+
+impl LintKind {
+    /// The kind as printed in reports (`unused-func`).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LintKind::Unsatisfiable => "unsatisfiable",
+            LintKind::DeadCondition => "dead-condition",
+            LintKind::Shadowed => "shadowed",
+            LintKind::Duplicate => "duplicate",
+            LintKind::UnusedFunc => "unused-func",
+            LintKind::UnusedImport => "unused-import",
+        }
+    }
+}
+
+/// One finding, located by policy and declaration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LintFinding {
+    pub kind: LintKind,
+    pub policy: String,
+    /// `rule <name>`, `func <name>` or `import <alias>`.
+    pub location: String,
+    /// 1-based source line of the declaration, when the policy was linted
+    /// with its `.reap` text.
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for LintFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} [{}]",
+            self.location,
+            self.message,
+            self.kind.as_str()
+        )
+    }
+}
+
+/// Every finding in one policy. With `source` (the policy's `.reap` text)
+/// findings carry line numbers.
+pub fn lint_policy(policy: &Policy, source: Option<&str>) -> Vec<LintFinding> {
+    lint_package(&[(policy, source)]).concat()
+}
+
+/// Every finding in a set of policies: each policy's own findings, plus
+/// rules duplicated across policies of the same package (the `package`
+/// metadata key, `default` when absent). One list per policy, in the order
+/// given.
+pub fn lint_package(policies: &[(&Policy, Option<&str>)]) -> Vec<Vec<LintFinding>> {
+    let mut all = Vec::new();
+    // package -> duplicate key -> (policy index, rule index) of the first rule
+    let mut seen: HashMap<&str, HashMap<String, (usize, usize)>> = HashMap::new();
+
+    for (p, (policy, source)) in policies.iter().enumerate() {
+        let outline = source.and_then(|s| analysis::outline(s).ok());
+        let lines = Lines {
+            source: source.unwrap_or_default(),
+            outline: outline.as_ref(),
+        };
+        let package = policy
+            .metadata
+            .get("package")
+            .map(String::as_str)
+            .unwrap_or("default");
+        let seen = seen.entry(package).or_default();
+        let mut out = Vec::new();
+        let mut push = |kind, location: String, line, message: String| {
+            out.push(LintFinding {
+                kind,
+                policy: policy.name.clone(),
+                location,
+                line,
+                message,
+            })
+        };
+
+        for (r, rule) in policy.rules.iter().enumerate() {
+            let location = format!("rule {}", rule.name);
+            let line = lines.rule(policy, r);
+            match unsatisfiable(&rule.condition) {
+                Some(reason) => push(
+                    LintKind::Unsatisfiable,
+                    location.clone(),
+                    line,
+                    format!("condition can never hold: {reason}"),
+                ),
+                None => {
+                    for reason in dead_alternatives(&rule.condition) {
+                        push(
+                            LintKind::DeadCondition,
+                            location.clone(),
+                            line,
+                            format!("`||` alternative can never hold: {reason}"),
+                        );
+                    }
+                    if let Some(message) = shadowing_deny(policy, rule) {
+                        push(LintKind::Shadowed, location.clone(), line, message);
+                    }
+                }
+            }
+            match seen.get(duplicate_key(rule).as_str()) {
+                Some(&(first_policy, first_rule)) => {
+                    let first = &policies[first_policy].0;
+                    let name = &first.rules[first_rule].name;
+                    let message = if first_policy == p {
+                        format!("same effect and condition as rule {name}")
+                    } else {
+                        format!(
+                            "same effect and condition as rule {name} in policy {}",
+                            first.name
+                        )
+                    };
+                    push(LintKind::Duplicate, location, line, message);
+                }
+                None => {
+                    seen.insert(duplicate_key(rule), (p, r));
+                }
+            }
+        }
+
+        let own: Vec<&FuncDef> = policy
+            .functions
+            .iter()
+            .filter(|f| f.namespace.is_none())
+            .collect();
+        for func in &own {
+            if let Some(reason) = unsatisfiable(&func.body) {
+                push(
+                    LintKind::Unsatisfiable,
+                    format!("func {}", func.name),
+                    lines.function(&func.name),
+                    format!("body can never hold: {reason}"),
+                );
+            }
+        }
+
+        let reached = reached_calls(policy);
+        for func in &own {
+            if !reached.contains(&(None, func.name.as_str())) {
+                push(
+                    LintKind::UnusedFunc,
+                    format!("func {}", func.name),
+                    lines.function(&func.name),
+                    "never called from any rule".to_string(),
+                );
+            }
+        }
+        for import in &policy.imports {
+            if !reached
+                .iter()
+                .any(|(ns, _)| *ns == Some(import.alias.as_str()))
+            {
+                push(
+                    LintKind::UnusedImport,
+                    format!("import {}", import.alias),
+                    lines.import(&import.alias),
+                    format!("no rule calls into \"{}\"", import.path),
+                );
+            }
+        }
+        all.push(out);
+    }
+    all
+}
+
+// ---------------------------------------------------------------------------
+// Satisfiability
+// ---------------------------------------------------------------------------
+
+/// Why `cond` can never hold, or `None` when nothing says so.
+fn unsatisfiable(cond: &Condition) -> Option<String> {
+    match cond {
+        Condition::False => Some("`false`".to_string()),
+        Condition::Not(inner) if matches!(inner.as_ref(), Condition::True) => {
+            Some("`!true`".to_string())
+        }
+        Condition::Or(items) => {
+            let reasons: Option<Vec<String>> = items.iter().map(unsatisfiable).collect();
+            reasons.map(|r| r.join("; "))
+        }
+        Condition::And(_) => contradiction(&conjuncts(cond)),
+        _ => None,
+    }
+}
+
+/// The `||` alternatives inside a satisfiable `cond` that can never hold,
+/// as reasons.
+fn dead_alternatives(cond: &Condition) -> Vec<String> {
+    let mut out = Vec::new();
+    match cond {
+        Condition::Or(items) => {
+            for item in items {
+                match unsatisfiable(item) {
+                    Some(reason) => out.push(reason),
+                    None => out.extend(dead_alternatives(item)),
+                }
+            }
+        }
+        Condition::And(items) => {
+            for item in items {
+                out.extend(dead_alternatives(item));
+            }
+        }
+        Condition::Not(inner) => out.extend(dead_alternatives(inner)),
+        _ => {}
+    }
+    out
+}
+
+/// `cond` as a flat list of conjuncts (nested `&&` flattened, `true`
+/// dropped).
+fn conjuncts(cond: &Condition) -> Vec<&Condition> {
+    match cond {
+        Condition::And(items) => items.iter().flat_map(conjuncts).collect(),
+        Condition::True => Vec::new(),
+        _ => vec![cond],
+    }
+}
+
+/// A conjunct comparing one operand against a literal: `user.level >= 3`.
+struct Bound<'a> {
+    text: String,
+    op: Operator,
+    value: &'a Value,
+}
+
+/// Why the conjunction of `items` can never hold.
+pub(crate) fn contradiction(items: &[&Condition]) -> Option<String> {
+    let texts: Vec<String> = items.iter().map(|c| format::condition(c)).collect();
+    let mut bounds: HashMap<String, Vec<Bound<'_>>> = HashMap::new();
+
+    for (item, text) in items.iter().zip(&texts) {
+        if let Some(reason) = unsatisfiable(item) {
+            return Some(reason);
+        }
+        if let Condition::Not(inner) = item {
+            let negated = format::condition(inner);
+            if texts.contains(&negated) {
+                return Some(format!("`{negated}` and `{text}`"));
+            }
+        }
+        if let Condition::Comparison { left, op, right } = item {
+            let value = match right {
+                ComparisonRight::Value(v) | ComparisonRight::Expr(Expr::Literal(v)) => v,
+                _ => continue,
+            };
+            if *op == Operator::In || matches!(left, ComparisonLeft::Expr(Expr::Literal(_))) {
+                continue;
+            }
+            let operand = format::comparison_left(left);
+            let earlier = bounds.entry(operand).or_default();
+            for other in earlier.iter() {
+                if conflicts(other.op, other.value, *op, value) {
+                    return Some(format!("`{}` and `{text}`", other.text));
+                }
+            }
+            earlier.push(Bound {
+                text: text.clone(),
+                op: *op,
+                value,
+            });
+        }
+    }
+    None
+}
+
+/// Whether `x <a_op> a && x <b_op> b` can never hold.
+fn conflicts(a_op: Operator, a: &Value, b_op: Operator, b: &Value) -> bool {
+    use Operator::*;
+    match (a_op, b_op) {
+        (Equal, Equal) => literal_eq(a, b) == Some(false),
+        (Equal, NotEqual) | (NotEqual, Equal) => literal_eq(a, b) == Some(true),
+        (Equal, _) => number(a).is_some_and(|x| !holds(x, b_op, b)),
+        (_, Equal) => number(b).is_some_and(|x| !holds(x, a_op, a)),
+        _ => match (
+            lower(a_op, a).or(lower(b_op, b)),
+            upper(a_op, a).or(upper(b_op, b)),
+        ) {
+            (Some((lo, lo_strict)), Some((hi, hi_strict))) => {
+                lo > hi || (lo == hi && (lo_strict || hi_strict))
+            }
+            _ => false,
+        },
+    }
+}
+
+/// Whether the number `x` satisfies `x <op> bound` (false when `bound` is
+/// not a number).
+pub(crate) fn holds(x: f64, op: Operator, bound: &Value) -> bool {
+    let Some(y) = number(bound) else {
+        return false;
+    };
+    match op {
+        Operator::GreaterThan => x > y,
+        Operator::GreaterEqual => x >= y,
+        Operator::LessThan => x < y,
+        Operator::LessEqual => x <= y,
+        Operator::NotEqual => x != y,
+        Operator::Equal => x == y,
+        Operator::In => true,
+    }
+}
+
+/// `(bound, strict)` when `x <op> value` bounds `x` from below.
+pub(crate) fn lower(op: Operator, value: &Value) -> Option<(f64, bool)> {
+    match op {
+        Operator::GreaterThan => number(value).map(|n| (n, true)),
+        Operator::GreaterEqual => number(value).map(|n| (n, false)),
+        _ => None,
+    }
+}
+
+/// `(bound, strict)` when `x <op> value` bounds `x` from above.
+pub(crate) fn upper(op: Operator, value: &Value) -> Option<(f64, bool)> {
+    match op {
+        Operator::LessThan => number(value).map(|n| (n, true)),
+        Operator::LessEqual => number(value).map(|n| (n, false)),
+        _ => None,
+    }
+}
+
+pub(crate) fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Integer(n) => Some(*n as f64),
+        Value::Float(f) => Some(*f),
+        _ => None,
+    }
+}
+
+/// Scalar literal equality; `None` for collections (not reasoned about).
+pub(crate) fn literal_eq(a: &Value, b: &Value) -> Option<bool> {
+    if let (Some(x), Some(y)) = (number(a), number(b)) {
+        return Some(x == y);
+    }
+    match (a, b) {
+        (Value::String(x), Value::String(y)) => Some(x == y),
+        (Value::Boolean(x), Value::Boolean(y)) => Some(x == y),
+        (Value::Null, Value::Null) => Some(true),
+        (Value::Array(_) | Value::Object(_) | Value::Set(_), _)
+        | (_, Value::Array(_) | Value::Object(_) | Value::Set(_)) => None,
+        _ => Some(false),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Shadowing and duplicates
+// ---------------------------------------------------------------------------
+
+/// For an `allow` rule: the message naming a `deny` rule that matches
+/// whenever it does. Only denies that bind and read no variables qualify, so
+/// equal text means the same check.
+fn shadowing_deny(policy: &Policy, allow: &Rule) -> Option<String> {
+    if allow.decision != Decision::Allow {
+        return None;
+    }
+    let own: HashSet<String> = conjuncts(&allow.condition)
+        .into_iter()
+        .map(format::condition)
+        .collect();
+    policy.rules.iter().find_map(|deny| {
+        if deny.decision != Decision::Deny || unsatisfiable(&deny.condition).is_some() {
+            return None;
+        }
+        let mut bound = HashSet::new();
+        collect_bound_vars_condition(&deny.condition, &mut bound);
+        let mut referenced = HashSet::new();
+        collect_referenced_vars_condition(&deny.condition, &mut referenced);
+        referenced.retain(|name| !is_entity_rooted_name(name));
+        if !bound.is_empty() || !referenced.is_empty() {
+            return None;
+        }
+        let required = conjuncts(&deny.condition);
+        if required.is_empty() {
+            Some(format!(
+                "never decides: rule {} denies every request",
+                deny.name
+            ))
+        } else if required.iter().all(|c| own.contains(&format::condition(c))) {
+            Some(format!(
+                "never decides: rule {} denies every request it matches",
+                deny.name
+            ))
+        } else {
+            None
+        }
+    })
+}
+
+/// Rules with equal keys behave identically.
+fn duplicate_key(rule: &Rule) -> String {
+    let extras = serde_json::to_string(&(&rule.message, &rule.obligations)).unwrap_or_default();
+    format!(
+        "{} {}\n{extras}",
+        rule.decision.as_str(),
+        format::condition(&rule.condition)
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Reachability
+// ---------------------------------------------------------------------------
+
+/// Every `(namespace, function)` called from a rule, directly or through
+/// the policy's own funcs.
+fn reached_calls(policy: &Policy) -> HashSet<(Option<&str>, &str)> {
+    let mut reached = HashSet::new();
+    let mut pending = Vec::new();
+    for rule in &policy.rules {
+        calls_condition(&rule.condition, &mut pending);
+    }
+    while let Some(call) = pending.pop() {
+        if !reached.insert(call) {
+            continue;
+        }
+        if let (None, name) = call {
+            if let Some(func) = policy
+                .functions
+                .iter()
+                .find(|f| f.namespace.is_none() && f.name == name)
+            {
+                calls_condition(&func.body, &mut pending);
+            }
+        }
+    }
+    reached
+}
+
+pub(crate) fn calls_condition<'a>(cond: &'a Condition, out: &mut Vec<(Option<&'a str>, &'a str)>) {
+    match cond {
+        Condition::Comparison { left, right, .. } => {
+            calls_left(left, out);
+            calls_right(right, out);
+        }
+        Condition::Assignment { value, .. } => match value {
+            AssignmentValue::Expr(e) => calls_expr(e, out),
+            AssignmentValue::Comparison { left, right, .. } => {
+                calls_left(left, out);
+                calls_right(right, out);
+            }
+            AssignmentValue::Comprehension(comp) => {
+                let (exprs, filters): (Vec<&Expr>, _) = match comp {
+                    Comprehension::Set {
+                        output, filters, ..
+                    }
+                    | Comprehension::Array {
+                        output, filters, ..
+                    } => (vec![output], filters),
+                    Comprehension::Object {
+                        key,
+                        value,
+                        filters,
+                        ..
+                    } => (vec![key, value], filters),
+                };
+                for e in exprs {
+                    calls_expr(e, out);
+                }
+                for f in filters {
+                    calls_condition(f, out);
+                }
+            }
+            AssignmentValue::EntityAttr(_)
+            | AssignmentValue::Value(_)
+            | AssignmentValue::Variable(_) => {}
+        },
+        Condition::And(items) | Condition::Or(items) => {
+            for c in items {
+                calls_condition(c, out);
+            }
+        }
+        Condition::Not(inner) | Condition::Quantifier { body: inner, .. } => {
+            calls_condition(inner, out)
+        }
+        Condition::Expr(e) => calls_expr(e, out),
+        Condition::True | Condition::False => {}
+    }
+}
+
+fn calls_left<'a>(left: &'a ComparisonLeft, out: &mut Vec<(Option<&'a str>, &'a str)>) {
+    if let ComparisonLeft::Expr(e) = left {
+        calls_expr(e, out);
+    }
+}
+
+fn calls_right<'a>(right: &'a ComparisonRight, out: &mut Vec<(Option<&'a str>, &'a str)>) {
+    if let ComparisonRight::Expr(e) = right {
+        calls_expr(e, out);
+    }
+}
+
+fn calls_expr<'a>(e: &'a Expr, out: &mut Vec<(Option<&'a str>, &'a str)>) {
+    match e {
+        Expr::FunctionCall {
+            namespace,
+            function,
+            args,
+        } => {
+            out.push((namespace.as_deref(), function.as_str()));
+            for a in args {
+                calls_expr(a, out);
+            }
+        }
+        Expr::MethodCall { receiver, args, .. } => {
+            calls_expr(receiver, out);
+            for a in args {
+                calls_expr(a, out);
+            }
+        }
+        Expr::Interpolation(parts) => {
+            for e in parts.iter().filter_map(InterpolationPart::expr) {
+                calls_expr(e, out);
+            }
+        }
+        Expr::BinaryOp { left, right, .. } => {
+            calls_expr(left, out);
+            calls_expr(right, out);
+        }
+        Expr::Literal(_)
+        | Expr::Variable(_)
+        | Expr::AttributeAccess { .. }
+        | Expr::IndexedAccess { .. } => {}
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Source lines
+// ---------------------------------------------------------------------------
+
+struct Lines<'a> {
+    source: &'a str,
+    outline: Option<&'a SourceOutline>,
+}
+
+impl Lines<'_> {
+    fn rule(&self, policy: &Policy, index: usize) -> Option<usize> {
+        // Outlined in declaration order, same as the AST.
+        let decl = self.outline?.rules.get(index)?;
+        (decl.name == policy.rules[index].name).then(|| self.line(decl.span.start))
+    }
+
+    fn function(&self, name: &str) -> Option<usize> {
+        let decl = self.outline?.functions.iter().find(|d| d.name == name)?;
+        Some(self.line(decl.span.start))
+    }
+
+    fn import(&self, alias: &str) -> Option<usize> {
+        let import = self.outline?.imports.iter().find(|i| i.alias == alias)?;
+        Some(self.line(import.path_span.start))
+    }
+
+    fn line(&self, offset: usize) -> usize {
+        self.source[..offset].matches('\n').count() + 1
+    }
+}
@@ -77,6 +77,8 @@ enum UnitEval {
 struct RuleUnit {
     /// The rule's name, owned here so `evaluate_named` can borrow from self.
     name: String,
+    /// The rule's obligations, lowered once and borrowed the same way.
+    obligations: Option<crate::DecisionObligations>,
     eval: UnitEval,
 }
 
@@ -94,6 +96,16 @@ impl RuleUnit {
         };
         Ok(outcome.rule_name.map(|_| outcome.decision))
     }
+
+    /// The mixed evaluator's outcome when this unit decided.
+    fn decided(&self, decision: PolicyAction) -> NamedOutcome<'_> {
+        NamedOutcome {
+            decision,
+            matched: true,
+            rule_name: Some(self.name.as_str()),
+            obligations: self.obligations.as_ref(),
+        }
+    }
 }
 
 /// Per-rule mixed compiled/AST evaluator. Built by
@@ -171,6 +183,11 @@ impl MixedReapEvaluator {
             };
             let unit = RuleUnit {
                 name: rule.name.clone(),
+                obligations: rule
+                    .obligations
+                    .as_ref()
+                    .map(|o| o.to_decision())
+                    .filter(|o| !o.is_empty()),
                 eval,
             };
             match rule.decision {
@@ -203,8 +220,7 @@ impl MixedReapEvaluator {
     /// per-request principal must not be pinned in the shared interner).
     fn principal_is_loaded(&self, request: &crate::PolicyRequest) -> bool {
         request
-            .context
-            .get("principal")
+            .context_str("principal")
             .and_then(|p| self.store.interner().lookup(p))
             .and_then(|id| self.store.get(id))
             .is_some()
@@ -302,34 +318,35 @@ impl MixedReapEvaluator {
     }
 
     /// The shared deny-overrides / first-allow-wins / default loop.
-    fn decide(
-        &self,
-        request: &crate::PolicyRequest,
-    ) -> Result<(PolicyAction, Option<&str>), ReaperError> {
+    fn decide(&self, request: &crate::PolicyRequest) -> Result<NamedOutcome<'_>, ReaperError> {
         if !self.principal_is_loaded(request) {
             // Unknown-principal edge: reproduce the whole-AST outcome this
             // policy had before mixed mode (see the `whole_ast` field docs).
-            let (action, name) = self.whole_ast.evaluate_with_input_named(request, None)?;
-            return Ok((action, name));
+            return self.whole_ast.evaluate_named(request);
         }
         for unit in &self.deny_units {
             if let Some(decision) = unit.matched_decision(request)? {
                 debug_assert!(matches!(decision, PolicyAction::Deny));
-                return Ok((PolicyAction::Deny, Some(unit.name.as_str())));
+                return Ok(unit.decided(PolicyAction::Deny));
             }
         }
         for unit in &self.allow_units {
             if let Some(decision) = unit.matched_decision(request)? {
-                return Ok((decision, Some(unit.name.as_str())));
+                return Ok(unit.decided(decision));
             }
         }
-        Ok((self.default_decision.clone(), None))
+        Ok(NamedOutcome {
+            decision: self.default_decision.clone(),
+            matched: true,
+            rule_name: None,
+            obligations: None,
+        })
     }
 }
 
 impl PolicyEvaluator for MixedReapEvaluator {
     fn evaluate(&self, request: &crate::PolicyRequest) -> Result<PolicyAction, ReaperError> {
-        self.decide(request).map(|(action, _)| action)
+        self.decide(request).map(|named| named.decision)
     }
 
     /// Always-decisive, mirroring the AST fallback this replaces (see module
@@ -339,19 +356,14 @@ impl PolicyEvaluator for MixedReapEvaluator {
         &self,
         request: &crate::PolicyRequest,
     ) -> Result<(PolicyAction, bool), ReaperError> {
-        self.decide(request).map(|(action, _)| (action, true))
+        self.decide(request).map(|named| (named.decision, true))
     }
 
     fn evaluate_named(
         &self,
         request: &crate::PolicyRequest,
     ) -> Result<NamedOutcome<'_>, ReaperError> {
-        let (decision, rule_name) = self.decide(request)?;
-        Ok(NamedOutcome {
-            decision,
-            matched: true,
-            rule_name,
-        })
+        self.decide(request)
     }
 
     fn check_with_input(
@@ -362,6 +374,17 @@ impl PolicyEvaluator for MixedReapEvaluator {
         MixedReapEvaluator::check_with_input(self, request, input)
     }
 
+    /// The whole-policy interpreter already holds the source AST; the
+    /// per-rule decision is checked against it like the compiled path's.
+    fn explain(
+        &self,
+        request: &crate::PolicyRequest,
+    ) -> Result<crate::reap::EvaluationTrace, ReaperError> {
+        let served = self.decide(request)?;
+        let trace = self.whole_ast.explain_with_input(request, None)?;
+        crate::reap::trace::ensure_consistent(trace, self.evaluator_type(), served)
+    }
+
     fn validate(&self) -> Result<(), ReaperError> {
         if self.total_rules == 0 {
             return Err(ReaperError::InvalidPolicy {
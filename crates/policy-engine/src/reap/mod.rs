@@ -2,22 +2,35 @@
 //!
 //! Parses .reap files into AST and compiles to ReaperDSLEvaluator for sub-microsecond evaluation.
 
+pub mod analysis;
+pub(crate) mod arith;
 mod ast;
 mod ast_evaluator;
+pub(crate) mod builtins;
 mod bundle;
 mod compiler;
+pub mod coverage;
+pub mod diff;
+pub mod format;
 mod functions;
+pub(crate) mod interpolate;
 mod limits;
+pub mod lint;
 mod mixed_evaluator;
+pub mod mutate;
 mod parser;
+pub mod rego;
+pub mod trace;
+pub mod typecheck;
 mod yaml_parser;
 
 pub use ast::{
-    AssignmentValue, ComparisonLeft, ComparisonRight, Condition as ReapCondition, Decision, Entity,
-    EntityAttr, Expr, FuncDef, ImportDecl, Index, Operator, Policy, Rule as ReapRule,
-    Value as ReapValue, VarAttr,
+    ArithOp, AssignmentValue, ComparisonLeft, ComparisonRight, Condition as ReapCondition,
+    Decision, Entity, EntityAttr, Expr, FuncDef, ImportDecl, Index, InterpolationPart, Operator,
+    Policy, QuantifierKind, Rule as ReapRule, RuleObligations, Value as ReapValue, VarAttr,
 };
-pub use ast_evaluator::{CheckResult, ReapAstEvaluator, Violation};
+pub(crate) use ast_evaluator::Caveat;
+pub use ast_evaluator::{CheckResult, ReapAstEvaluator, SnippetScope, Violation};
 pub use bundle::{
     stable_policy_id, BundleFormat, PackageMetadata, PolicyBundle, PolicyEntry, PolicyPackage,
     PrecompilationHints,
@@ -29,9 +42,10 @@ pub use limits::{
 };
 pub use mixed_evaluator::{MixedReapEvaluator, PerRuleBuild};
 pub use parser::ReapParser;
+pub use trace::{ConditionTrace, EvaluationTrace, FuncCallTrace, RuleTrace};
 pub use yaml_parser::YamlPolicy;
 
-use crate::data::DataStore;
+use crate::data::{DataStore, EntitySchema};
 use crate::evaluators::reaper_dsl::ReaperDSLEvaluator;
 use reaper_core::ReaperError;
 use std::fs;
@@ -233,6 +247,37 @@ impl ReaperPolicy {
         bundle::compile_to_bundle(&self.ast)
     }
 
+    /// Static analysis: unsatisfiable, shadowed and duplicate rules, unused
+    /// funcs and imports. With `source` findings carry lines; see [`lint`].
+    pub fn lint(&self, source: Option<&str>) -> Vec<lint::LintFinding> {
+        lint::lint_policy(&self.ast, source)
+    }
+
+    /// The parsed policy, for analyses over several policies at once
+    /// ([`lint::lint_package`]).
+    pub fn ast(&self) -> &Policy {
+        &self.ast
+    }
+
+    /// Type-check the policy against a declared entity schema: every
+    /// attribute path must be declared and every comparison satisfiable.
+    /// Empty when the policy is well-typed; see [`typecheck`].
+    pub fn type_check(&self, schema: &EntitySchema) -> Vec<typecheck::TypeDiagnostic> {
+        typecheck::check_policy(&self.ast, schema)
+    }
+
+    /// Type-check against `schema`, then compile to a bundle that carries
+    /// it: deploys re-check the policy, and the schema travels with the
+    /// bundle for validating data loads.
+    pub fn compile_to_bundle_with_schema(
+        &self,
+        schema: &EntitySchema,
+    ) -> Result<Vec<u8>, ReaperError> {
+        PolicyBundle::new(self.ast.clone())
+            .with_schema(schema.clone())?
+            .to_bytes()
+    }
+
     /// Load from a binary bundle
     pub fn from_bundle(
         bytes: &[u8],
@@ -354,17 +399,7 @@ fn resolve_imports(policy: &mut Policy, base_dir: &Path) -> Result<(), ReaperErr
             ),
         })?;
         let (_lib_name, mut lib_funcs) = ReapParser::parse_library(&content)?;
-
-        // Rewrite library-internal calls (`helper(x)` referring to a sibling
-        // in the same library) to the alias namespace, then tag the
-        // functions themselves. After this, the merged set has no ambiguous
-        // un-namespaced references into the library.
-        let local_names: std::collections::HashSet<String> =
-            lib_funcs.iter().map(|f| f.name.clone()).collect();
-        for f in &mut lib_funcs {
-            namespace_local_calls(&mut f.body, &local_names, &import.alias);
-            f.namespace = Some(import.alias.clone());
-        }
+        namespace_library(&mut lib_funcs, &import.alias);
         policy.functions.extend(lib_funcs);
     }
 
@@ -374,6 +409,38 @@ fn resolve_imports(policy: &mut Policy, base_dir: &Path) -> Result<(), ReaperErr
     functions::verify_resolved_calls(policy, limits::configured_max_nesting_depth())
 }
 
+/// Load a `.reap` library file directly rather than through an `import`
+/// (`reaper-cli repl`): its funcs come back namespaced under the library's
+/// own name, exactly as `import "..." as <name>` would merge them. Returns
+/// that name and the funcs.
+pub fn load_library<P: AsRef<Path>>(path: P) -> Result<(String, Vec<FuncDef>), ReaperError> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path).map_err(|e| ReaperError::InvalidPolicy {
+        reason: format!("failed to read library \"{}\": {}", path.display(), e),
+    })?;
+    let (name, mut funcs) = ReapParser::parse_library(&content)?;
+    if functions::BUILTIN_NAMESPACES.contains(&name.as_str()) {
+        return Err(ReaperError::InvalidPolicy {
+            reason: format!("library name '{name}' collides with a builtin namespace"),
+        });
+    }
+    namespace_library(&mut funcs, &name);
+    Ok((name, funcs))
+}
+
+/// Rewrite library-internal calls (`helper(x)` referring to a sibling in the
+/// same library) to the alias namespace, then tag the functions themselves.
+/// After this, the merged set has no ambiguous un-namespaced references into
+/// the library.
+fn namespace_library(funcs: &mut [FuncDef], alias: &str) {
+    let local_names: std::collections::HashSet<String> =
+        funcs.iter().map(|f| f.name.clone()).collect();
+    for f in funcs {
+        namespace_local_calls(&mut f.body, &local_names, alias);
+        f.namespace = Some(alias.to_string());
+    }
+}
+
 /// Rewrite un-namespaced calls to library-local functions into the import
 /// alias namespace, recursively through the condition tree.
 fn namespace_local_calls(
@@ -401,6 +468,17 @@ fn namespace_local_calls(
                     rewrite_expr(a, local_names, alias);
                 }
             }
+            Expr::BinaryOp { left, right, .. } => {
+                rewrite_expr(left, local_names, alias);
+                rewrite_expr(right, local_names, alias);
+            }
+            Expr::Interpolation(parts) => {
+                for part in parts {
+                    if let ast::InterpolationPart::Expr(e) = part {
+                        rewrite_expr(e, local_names, alias);
+                    }
+                }
+            }
             Expr::Literal(_)
             | Expr::Variable(_)
             | Expr::AttributeAccess { .. }
@@ -465,7 +543,9 @@ fn namespace_local_calls(
                 namespace_local_calls(c, local_names, alias);
             }
         }
-        ast::Condition::Not(inner) => namespace_local_calls(inner, local_names, alias),
+        ast::Condition::Not(inner) | ast::Condition::Quantifier { body: inner, .. } => {
+            namespace_local_calls(inner, local_names, alias)
+        }
         ast::Condition::True | ast::Condition::False => {}
     }
 }
@@ -0,0 +1,343 @@
+//! Mutation testing: single-point rewrites of a policy's AST, for checking
+//! whether a test suite notices them (`reaper-cli mutate`).
+//!
+//! Each [`Mutant`] is the policy with exactly one change:
+//!
+//! - operator: `==` ↔ `!=`, `>` ↔ `>=`, `<` ↔ `<=`, `some` ↔ `every`;
+//! - literal: integers and floats ± 1, strings emptied (or, when empty,
+//!   filled), booleans negated;
+//! - drop conjunct: one operand of an `&&` removed (assignments are kept,
+//!   since dropping one only unbinds a variable);
+//! - flip effect: a rule's `allow` becomes `deny` and vice versa.
+//!
+//! Rule conditions and the policy's own `func` bodies are mutated; helpers
+//! merged from imports belong to their library and are left alone. A test
+//! suite that still passes against a mutant has a gap: the mutant
+//! "survives".
+
+use super::analysis::{self, SourceOutline};
+use super::ast::{
+    ComparisonLeft, ComparisonRight, Condition, Decision, Expr, Operator, Policy, QuantifierKind,
+    Value,
+};
+use super::format;
+use super::ReaperPolicy;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MutationKind {
+    Operator,
+    Literal,
+    DropConjunct,
+    FlipEffect,
+}
+
+/// One mutated copy of a policy.
+#[derive(Debug, Clone, Serialize)]
+pub struct Mutant {
+    /// 1-based, in generation order (declaration order, then source order
+    /// within a declaration).
+    pub id: usize,
+    pub kind: MutationKind,
+    /// The mutated declaration: `rule <name>` or `func <name>`.
+    pub declaration: String,
+    /// 1-based source line of the mutated code, when the policy was read
+    /// from `.reap` source.
+    pub line: Option<usize>,
+    /// The code as written (canonical form).
+    pub original: String,
+    /// What it became; empty for a dropped conjunct.
+    pub replacement: String,
+    #[serde(skip)]
+    ast: Policy,
+}
+
+impl Mutant {
+    /// The mutated policy, ready to build.
+    pub fn policy(&self) -> ReaperPolicy {
+        ReaperPolicy {
+            ast: self.ast.clone(),
+        }
+    }
+}
+
+/// A change inside one condition: the rewritten condition plus what changed.
+struct Site {
+    kind: MutationKind,
+    original: String,
+    replacement: String,
+    condition: Condition,
+}
+
+/// Every single-point mutant of `policy`. With `source` (the policy's
+/// `.reap` text) mutants carry line numbers.
+pub fn mutants(policy: &ReaperPolicy, source: Option<&str>) -> Vec<Mutant> {
+    let ast = &policy.ast;
+    let outline = source.and_then(|s| analysis::outline(s).ok());
+    let lines = Locator {
+        source: source.unwrap_or_default(),
+        outline: outline.as_ref(),
+    };
+    let mut out = Vec::new();
+
+    for (index, rule) in ast.rules.iter().enumerate() {
+        let declaration = format!("rule {}", rule.name);
+        let mut cursor = lines.rule_start(ast, index);
+
+        let (original, replacement, flipped) = match rule.decision {
+            Decision::Allow => ("allow", "deny", Decision::Deny),
+            Decision::Deny => ("deny", "allow", Decision::Allow),
+        };
+        let mut mutated = ast.clone();
+        mutated.rules[index].decision = flipped;
+        out.push(Mutant {
+            id: 0,
+            kind: MutationKind::FlipEffect,
+            declaration: declaration.clone(),
+            line: lines.find(&mut cursor, original),
+            original: original.to_string(),
+            replacement: replacement.to_string(),
+            ast: mutated,
+        });
+
+        let mut sites = Vec::new();
+        condition_sites(&rule.condition, &mut sites);
+        for site in sites {
+            let mut mutated = ast.clone();
+            mutated.rules[index].condition = site.condition;
+            out.push(Mutant {
+                id: 0,
+                kind: site.kind,
+                declaration: declaration.clone(),
+                line: lines.find(&mut cursor, &site.original),
+                original: site.original,
+                replacement: site.replacement,
+                ast: mutated,
+            });
+        }
+    }
+
+    for (index, def) in ast.functions.iter().enumerate() {
+        if def.namespace.is_some() {
+            continue;
+        }
+        let declaration = format!("func {}", def.name);
+        let mut cursor = lines.function_start(&def.name);
+        let mut sites = Vec::new();
+        condition_sites(&def.body, &mut sites);
+        for site in sites {
+            let mut mutated = ast.clone();
+            mutated.functions[index].body = site.condition;
+            out.push(Mutant {
+                id: 0,
+                kind: site.kind,
+                declaration: declaration.clone(),
+                line: lines.find(&mut cursor, &site.original),
+                original: site.original,
+                replacement: site.replacement,
+                ast: mutated,
+            });
+        }
+    }
+
+    for (n, mutant) in out.iter_mut().enumerate() {
+        mutant.id = n + 1;
+    }
+    out
+}
+
+/// Collect the mutations inside `cond`, each as a full rewrite of `cond`,
+/// in source order.
+fn condition_sites(cond: &Condition, out: &mut Vec<Site>) {
+    match cond {
+        Condition::And(items) | Condition::Or(items) => {
+            let rebuild = |items: Vec<Condition>| match cond {
+                Condition::And(_) => Condition::And(items),
+                _ => Condition::Or(items),
+            };
+            let droppable = matches!(cond, Condition::And(_)) && items.len() > 1;
+            for (i, item) in items.iter().enumerate() {
+                if droppable && !matches!(item, Condition::Assignment { .. }) {
+                    let mut rest = items.clone();
+                    rest.remove(i);
+                    out.push(Site {
+                        kind: MutationKind::DropConjunct,
+                        original: format::condition(item),
+                        replacement: String::new(),
+                        condition: rebuild(rest),
+                    });
+                }
+                let mut inner = Vec::new();
+                condition_sites(item, &mut inner);
+                for site in inner {
+                    let mut rewritten = items.clone();
+                    rewritten[i] = site.condition;
+                    out.push(Site {
+                        condition: rebuild(rewritten),
+                        ..site
+                    });
+                }
+            }
+        }
+        Condition::Not(inner) => {
+            let mut sites = Vec::new();
+            condition_sites(inner, &mut sites);
+            out.extend(sites.into_iter().map(|site| Site {
+                condition: Condition::Not(Box::new(site.condition)),
+                ..site
+            }));
+        }
+        Condition::Comparison { left, op, right } => {
+            let original = format::condition(cond);
+            let mut push = |kind, condition: Condition| {
+                out.push(Site {
+                    kind,
+                    original: original.clone(),
+                    replacement: format::condition(&condition),
+                    condition,
+                });
+            };
+            if let Some(swapped) = swap(*op) {
+                push(
+                    MutationKind::Operator,
+                    Condition::Comparison {
+                        left: left.clone(),
+                        op: swapped,
+                        right: right.clone(),
+                    },
+                );
+            }
+            if let ComparisonLeft::Expr(Expr::Literal(value)) = left {
+                for changed in literal_changes(value) {
+                    push(
+                        MutationKind::Literal,
+                        Condition::Comparison {
+                            left: ComparisonLeft::Expr(Expr::Literal(changed)),
+                            op: *op,
+                            right: right.clone(),
+                        },
+                    );
+                }
+            }
+            let right_literal = match right {
+                ComparisonRight::Value(value) | ComparisonRight::Expr(Expr::Literal(value)) => {
+                    Some(value)
+                }
+                _ => None,
+            };
+            for changed in right_literal.map(literal_changes).unwrap_or_default() {
+                push(
+                    MutationKind::Literal,
+                    Condition::Comparison {
+                        left: left.clone(),
+                        op: *op,
+                        right: ComparisonRight::Value(changed),
+                    },
+                );
+            }
+        }
+        Condition::Quantifier {
+            kind,
+            index,
+            variable,
+            collection,
+            body,
+        } => {
+            let flipped = match kind {
+                QuantifierKind::Some => QuantifierKind::Every,
+                QuantifierKind::Every => QuantifierKind::Some,
+            };
+            let head = |kind| format::quantifier_head(kind, index.as_deref(), variable, collection);
+            let rebuild = |kind, body| Condition::Quantifier {
+                kind,
+                index: index.clone(),
+                variable: variable.clone(),
+                collection: collection.clone(),
+                body: Box::new(body),
+            };
+            out.push(Site {
+                kind: MutationKind::Operator,
+                original: head(*kind),
+                replacement: head(flipped),
+                condition: rebuild(flipped, (**body).clone()),
+            });
+            let mut sites = Vec::new();
+            condition_sites(body, &mut sites);
+            out.extend(sites.into_iter().map(|site| Site {
+                condition: rebuild(*kind, site.condition),
+                ..site
+            }));
+        }
+        Condition::True | Condition::False | Condition::Assignment { .. } | Condition::Expr(_) => {}
+    }
+}
+
+/// The operator an off-by-one or inverted check would have used.
+fn swap(op: Operator) -> Option<Operator> {
+    Some(match op {
+        Operator::Equal => Operator::NotEqual,
+        Operator::NotEqual => Operator::Equal,
+        Operator::GreaterThan => Operator::GreaterEqual,
+        Operator::GreaterEqual => Operator::GreaterThan,
+        Operator::LessThan => Operator::LessEqual,
+        Operator::LessEqual => Operator::LessThan,
+        Operator::In => return None,
+    })
+}
+
+fn literal_changes(value: &Value) -> Vec<Value> {
+    match value {
+        Value::Integer(n) => [n.checked_add(1), n.checked_sub(1)]
+            .into_iter()
+            .flatten()
+            .map(Value::Integer)
+            .collect(),
+        Value::Float(f) => vec![Value::Float(f + 1.0), Value::Float(f - 1.0)],
+        Value::String(s) if s.is_empty() => vec![Value::String("mutant".to_string())],
+        Value::String(_) => vec![Value::String(String::new())],
+        Value::Boolean(b) => vec![Value::Boolean(!b)],
+        Value::Null | Value::Array(_) | Value::Object(_) | Value::Set(_) => Vec::new(),
+    }
+}
+
+/// Maps mutated code back to source lines: the canonical text of each
+/// mutation site is searched for inside its declaration, moving forward
+/// through it in step with the (source-ordered) sites. Code written in
+/// another shape than the formatter's falls back to the declaration line.
+struct Locator<'a> {
+    source: &'a str,
+    outline: Option<&'a SourceOutline>,
+}
+
+/// Search position inside one declaration: `(from, declaration start, end)`.
+type Cursor = Option<(usize, usize, usize)>;
+
+impl Locator<'_> {
+    fn rule_start(&self, ast: &Policy, index: usize) -> Cursor {
+        // Rules are outlined in declaration order, same as the AST (bare
+        // indexing stays right with duplicate names).
+        let decl = self.outline?.rules.get(index)?;
+        if decl.name != ast.rules[index].name {
+            return None;
+        }
+        Some((decl.name_span.end, decl.span.start, decl.span.end))
+    }
+
+    fn function_start(&self, name: &str) -> Cursor {
+        let decl = self.outline?.functions.iter().find(|d| d.name == name)?;
+        Some((decl.name_span.end, decl.span.start, decl.span.end))
+    }
+
+    fn find(&self, cursor: &mut Cursor, needle: &str) -> Option<usize> {
+        let (from, start, end) = (*cursor)?;
+        let at = match self.source[from..end].find(needle) {
+            Some(offset) => {
+                *cursor = Some((from + offset, start, end));
+                from + offset
+            }
+            None => start,
+        };
+        Some(self.source[..at].matches('\n').count() + 1)
+    }
+}
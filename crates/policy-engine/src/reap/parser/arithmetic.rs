@@ -0,0 +1,150 @@
+//! Infix arithmetic parsing for .reap files.
+//!
+//! The grammar gives a flat `operand (op operand)+` sequence; precedence
+//! (`*` `/` `%` over `+` `-`) and left-associativity are applied here so
+//! `a - b - c` is `(a - b) - c` and `a + b * c` is `a + (b * c)`.
+
+use super::expression::{
+    parse_comp_function_call, parse_entity_method_call, parse_var_method_call,
+};
+use super::value::{parse_entity_attr, parse_var_attr};
+use super::Rule;
+use crate::reap::ast::*;
+use reaper_core::ReaperError;
+
+/// Parse `arith_comparison`: an arithmetic expression on at least one side.
+pub(super) fn parse_arith_comparison(
+    pair: pest::iterators::Pair<Rule>,
+) -> Result<Condition, ReaperError> {
+    let (left, op, right) = parse_arith_comparison_parts(pair)?;
+    Ok(Condition::Comparison { left, op, right })
+}
+
+/// The three parts of an `arith_comparison`, for callers that build their
+/// own node (`x := a + b > c` becomes an `AssignmentValue::Comparison`).
+pub(super) fn parse_arith_comparison_parts(
+    pair: pest::iterators::Pair<Rule>,
+) -> Result<(ComparisonLeft, Operator, ComparisonRight), ReaperError> {
+    let mut inner = pair.into_inner();
+    let left = parse_arith_expr(inner.next().unwrap())?;
+    let op = Operator::from(inner.next().unwrap().as_str());
+    let right = parse_arith_expr(inner.next().unwrap())?;
+    Ok((ComparisonLeft::Expr(left), op, ComparisonRight::Expr(right)))
+}
+
+/// Parse `arith_expr`, `arith_binary` or `arith_operand` into an [`Expr`].
+pub(super) fn parse_arith_expr(pair: pest::iterators::Pair<Rule>) -> Result<Expr, ReaperError> {
+    match pair.as_rule() {
+        Rule::arith_expr => parse_arith_expr(pair.into_inner().next().unwrap()),
+        Rule::arith_binary => parse_arith_binary(pair),
+        Rule::arith_operand => parse_arith_operand(pair),
+        other => Err(ReaperError::InvalidPolicy {
+            reason: format!("Unexpected rule in arithmetic expression: {:?}", other),
+        }),
+    }
+}
+
+/// Fold `operand (op operand)+` with precedence climbing.
+fn parse_arith_binary(pair: pest::iterators::Pair<Rule>) -> Result<Expr, ReaperError> {
+    let mut operands = Vec::new();
+    let mut ops = Vec::new();
+    for item in pair.into_inner() {
+        match item.as_rule() {
+            Rule::arith_op => ops.push(ArithOp::from(item.as_str())),
+            _ => operands.push(parse_arith_operand(item)?),
+        }
+    }
+
+    let mut operands = operands.into_iter();
+    let mut ops = ops.into_iter().peekable();
+    let first = operands.next().unwrap();
+    Ok(climb(first, 1, &mut operands, &mut ops))
+}
+
+fn climb(
+    mut left: Expr,
+    min_precedence: u8,
+    operands: &mut impl Iterator<Item = Expr>,
+    ops: &mut std::iter::Peekable<impl Iterator<Item = ArithOp>>,
+) -> Expr {
+    while let Some(&op) = ops.peek() {
+        if op.precedence() < min_precedence {
+            break;
+        }
+        ops.next();
+        let mut right = operands.next().unwrap();
+        while let Some(&next) = ops.peek() {
+            if next.precedence() <= op.precedence() {
+                break;
+            }
+            right = climb(right, next.precedence(), operands, ops);
+        }
+        left = Expr::BinaryOp {
+            op,
+            left: Box::new(left),
+            right: Box::new(right),
+        };
+    }
+    left
+}
+
+fn parse_arith_operand(pair: pest::iterators::Pair<Rule>) -> Result<Expr, ReaperError> {
+    let inner = pair.into_inner().next().unwrap();
+    match inner.as_rule() {
+        Rule::arith_expr => parse_arith_expr(inner),
+        Rule::comp_function_call => parse_comp_function_call(inner),
+        Rule::entity_method_call => parse_entity_method_call(inner),
+        Rule::var_method_call => parse_var_method_call(inner),
+        Rule::entity_attr => {
+            // Unindexed entity attributes use the pseudo-entity variable form
+            // ("user.quota.limit"), which resolves dotted paths like an
+            // EntityAttr comparison does.
+            let attr = parse_entity_attr(inner)?;
+            let entity = attr.entity.as_str().to_string();
+            Ok(match attr.index {
+                Some(index) => Expr::IndexedAccess {
+                    variable: entity,
+                    attribute: attr.attribute,
+                    index,
+                },
+                None => Expr::Variable(format!("{}.{}", entity, attr.attribute)),
+            })
+        }
+        Rule::var_attr => {
+            let var_attr = parse_var_attr(inner)?;
+            Ok(match var_attr.index {
+                Some(index) => Expr::IndexedAccess {
+                    variable: var_attr.variable,
+                    attribute: var_attr.attribute,
+                    index,
+                },
+                None => Expr::AttributeAccess {
+                    variable: var_attr.variable,
+                    attribute: var_attr.attribute,
+                },
+            })
+        }
+        Rule::integer => {
+            let val = inner
+                .as_str()
+                .parse::<i64>()
+                .map_err(|e| ReaperError::InvalidPolicy {
+                    reason: format!("Invalid integer: {}", e),
+                })?;
+            Ok(Expr::Literal(Value::Integer(val)))
+        }
+        Rule::float => {
+            let val = inner
+                .as_str()
+                .parse::<f64>()
+                .map_err(|e| ReaperError::InvalidPolicy {
+                    reason: format!("Invalid float: {}", e),
+                })?;
+            Ok(Expr::Literal(Value::Float(val)))
+        }
+        Rule::ident => Ok(Expr::Variable(inner.as_str().to_string())),
+        other => Err(ReaperError::InvalidPolicy {
+            reason: format!("Unexpected arithmetic operand: {:?}", other),
+        }),
+    }
+}
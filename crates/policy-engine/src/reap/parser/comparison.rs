@@ -5,9 +5,10 @@
 //! - Comparisons: left op right
 //! - Membership tests: value in collection
 
+use super::arithmetic::{parse_arith_comparison_parts, parse_arith_expr};
 use super::comprehension::parse_comprehension;
 use super::expression::{
-    parse_comp_function_call, parse_entity_method_call, parse_var_method_call,
+    parse_comp_function_call, parse_entity_method_call, parse_fstring, parse_var_method_call,
 };
 use super::value::{parse_bracket_index, parse_entity_attr, parse_value, parse_var_attr};
 use super::Rule;
@@ -64,9 +65,15 @@ pub(super) fn parse_assignment_value(
 
     match inner.as_rule() {
         Rule::comprehension => Ok(AssignmentValue::Comprehension(parse_comprehension(inner)?)),
+        Rule::arith_comparison => {
+            let (left, op, right) = parse_arith_comparison_parts(inner)?;
+            Ok(AssignmentValue::Comparison { left, op, right })
+        }
+        Rule::arith_binary => Ok(AssignmentValue::Expr(parse_arith_expr(inner)?)),
         Rule::comp_function_call => Ok(AssignmentValue::Expr(parse_comp_function_call(inner)?)),
         Rule::entity_method_call => Ok(AssignmentValue::Expr(parse_entity_method_call(inner)?)),
         Rule::var_method_call => Ok(AssignmentValue::Expr(parse_var_method_call(inner)?)),
+        Rule::fstring => Ok(AssignmentValue::Expr(parse_fstring(inner)?)),
         Rule::comparison => {
             // Parse comparison and extract the comparison fields
             let mut inner_iter = inner.into_inner();
@@ -194,9 +201,10 @@ pub(super) fn parse_comparison(
     let op = Operator::from(op_pair.as_str());
     let right_pair = inner.next().unwrap();
 
-    // Parse the left side (entity_method_call, entity_attr, var_attr, var_method_call, or simple ident)
+    // Parse the left side (entity_method_call, comp_function_call, entity_attr, var_attr, var_method_call, or simple ident)
     let left = match first_pair.as_rule() {
         Rule::entity_method_call => ComparisonLeft::Expr(parse_entity_method_call(first_pair)?),
+        Rule::comp_function_call => ComparisonLeft::Expr(parse_comp_function_call(first_pair)?),
         Rule::entity_attr => ComparisonLeft::EntityAttr(parse_entity_attr(first_pair)?),
         Rule::var_attr => ComparisonLeft::VarAttr(parse_var_attr(first_pair)?),
         Rule::var_method_call => ComparisonLeft::Expr(parse_var_method_call(first_pair)?),
@@ -221,7 +229,7 @@ pub(super) fn parse_comparison(
 pub(super) fn parse_comparison_right(
     pair: pest::iterators::Pair<Rule>,
 ) -> Result<ComparisonRight, ReaperError> {
-    // The pair is comparison_right, which contains var_method_call, entity_attr, var_attr, value, or ident
+    // The pair is comparison_right, which contains fstring, var_method_call, entity_attr, var_attr, value, or ident
     let inner = pair
         .into_inner()
         .next()
@@ -230,6 +238,7 @@ pub(super) fn parse_comparison_right(
         })?;
 
     match inner.as_rule() {
+        Rule::fstring => Ok(ComparisonRight::Expr(parse_fstring(inner)?)),
         Rule::var_method_call => Ok(ComparisonRight::Expr(parse_var_method_call(inner)?)),
         Rule::entity_attr => Ok(ComparisonRight::EntityAttr(parse_entity_attr(inner)?)),
         Rule::var_attr => Ok(ComparisonRight::VarAttr(parse_var_attr(inner)?)),
@@ -3,11 +3,14 @@
 //! This module handles parsing of condition expressions:
 //! - Boolean logic: AND, OR, NOT
 //! - Primary expressions: comparisons, assignments, function calls
+//! - Quantifier blocks: `some i, x in coll { ... }`, `every x in coll { ... }`
 
+use super::arithmetic::parse_arith_comparison;
 use super::comparison::{parse_assignment, parse_comparison};
 use super::expression::{
     parse_comp_function_call, parse_entity_method_call, parse_var_method_call,
 };
+use super::value::{parse_entity_attr, parse_var_attr};
 use super::Rule;
 use crate::reap::ast::*;
 use reaper_core::ReaperError;
@@ -94,7 +97,9 @@ pub(super) fn parse_primary_expr(
 
     match inner.as_rule() {
         Rule::condition_expr => parse_condition_expr(inner),
+        Rule::quantifier => parse_quantifier(inner),
         Rule::assignment => parse_assignment(inner),
+        Rule::arith_comparison => parse_arith_comparison(inner),
         Rule::comparison => parse_comparison(inner),
         Rule::entity_method_call => {
             // Parse entity method call and wrap it as an expression condition
@@ -127,3 +132,62 @@ pub(super) fn parse_primary_expr(
         }),
     }
 }
+
+/// Parse a quantifier block: `some i, x in coll { body }`. The collection is
+/// written without `[_]`; a trailing `[_]` is accepted and dropped.
+fn parse_quantifier(pair: pest::iterators::Pair<Rule>) -> Result<Condition, ReaperError> {
+    let mut inner = pair.into_inner();
+    let kind = match inner.next().unwrap().as_str() {
+        "some" => QuantifierKind::Some,
+        _ => QuantifierKind::Every,
+    };
+    let mut names = Vec::new();
+    let mut collection = None;
+    let mut body = None;
+    for part in inner {
+        match part.as_rule() {
+            Rule::ident => names.push(part.as_str().to_string()),
+            Rule::quantifier_source => {
+                let source = part.into_inner().next().unwrap();
+                collection = Some(match source.as_rule() {
+                    Rule::entity_attr => {
+                        let mut attr = parse_entity_attr(source)?;
+                        if matches!(attr.index, Some(Index::Wildcard)) {
+                            attr.index = None;
+                        }
+                        IterationSource::EntityAttr(attr)
+                    }
+                    Rule::var_attr => {
+                        let mut attr = parse_var_attr(source)?;
+                        if matches!(attr.index, Some(Index::Wildcard)) {
+                            attr.index = None;
+                        }
+                        IterationSource::VarAttr(attr)
+                    }
+                    _ => IterationSource::IndexedVariable {
+                        variable: source.as_str().to_string(),
+                        index: Index::Wildcard,
+                    },
+                });
+            }
+            Rule::condition_expr => body = Some(parse_condition_expr(part)?),
+            _ => {}
+        }
+    }
+    let (index, variable) = match names.len() {
+        2 => (Some(names.remove(0)), names.remove(0)),
+        _ => (None, names.remove(0)),
+    };
+    if index.as_ref() == Some(&variable) {
+        return Err(ReaperError::InvalidPolicy {
+            reason: format!("{} binds '{variable}' twice", kind.keyword()),
+        });
+    }
+    Ok(Condition::Quantifier {
+        kind,
+        index,
+        variable,
+        collection: collection.unwrap(),
+        body: Box::new(body.unwrap()),
+    })
+}
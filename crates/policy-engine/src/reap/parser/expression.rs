@@ -5,6 +5,7 @@
 //! - Method calls (variable and entity)
 //! - Function calls
 //! - Method chains
+//! - Interpolated strings
 
 use super::value::{parse_bracket_index, parse_value};
 use super::Rule;
@@ -21,6 +22,8 @@ pub(super) fn parse_comp_expr(pair: pest::iterators::Pair<Rule>) -> Result<Expr,
         })?;
 
     match first.as_rule() {
+        Rule::fstring => parse_fstring(first),
+        Rule::arith_binary => super::arithmetic::parse_arith_expr(first),
         Rule::comp_function_call => parse_comp_function_call(first),
 
         // Method calls or attribute access: perms.count(), u.name.lower(), or just u.name
@@ -97,8 +100,14 @@ pub(super) fn parse_comp_expr(pair: pest::iterators::Pair<Rule>) -> Result<Expr,
 
                 // var or var.method() - simple variable with optional method chain
                 Rule::ident => {
-                    let variable = first_inner.as_str().to_string();
-                    let base = Expr::Variable(variable);
+                    // `ident` wins the ordered choice over `value`, so the
+                    // reserved literal keywords land here.
+                    let base = match first_inner.as_str() {
+                        "null" => Expr::Literal(Value::Null),
+                        "true" => Expr::Literal(Value::Boolean(true)),
+                        "false" => Expr::Literal(Value::Boolean(false)),
+                        variable => Expr::Variable(variable.to_string()),
+                    };
 
                     // Check if there's an optional method chain
                     if let Some(method_chain) = inner.next() {
@@ -141,6 +150,53 @@ pub(super) fn parse_comp_expr(pair: pest::iterators::Pair<Rule>) -> Result<Expr,
     }
 }
 
+/// Parse interpolated string: f"bucket {input.name} is public"
+pub(super) fn parse_fstring(pair: pest::iterators::Pair<Rule>) -> Result<Expr, ReaperError> {
+    let mut parts = Vec::new();
+    for part in pair.into_inner() {
+        match part.as_rule() {
+            Rule::fstring_text => parts.push(InterpolationPart::Literal(unescape_fstring_text(
+                part.as_str(),
+            ))),
+            Rule::fstring_hole => {
+                let inner = part
+                    .into_inner()
+                    .next()
+                    .ok_or_else(|| ReaperError::InvalidPolicy {
+                        reason: "Empty interpolation hole".to_string(),
+                    })?;
+                parts.push(InterpolationPart::Expr(parse_comp_expr(inner)?));
+            }
+            _ => {}
+        }
+    }
+    Ok(Expr::Interpolation(parts))
+}
+
+/// Resolve `{{`, `}}`, `\"` and `\\` in a run of f-string text.
+fn unescape_fstring_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' | '}' => {
+                chars.next();
+                out.push(c);
+            }
+            '\\' => match chars.next() {
+                Some(next @ ('"' | '\\')) => out.push(next),
+                Some(next) => {
+                    out.push('\\');
+                    out.push(next);
+                }
+                None => out.push('\\'),
+            },
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
 /// Parse base expression that can be a receiver for method calls
 pub(super) fn parse_comp_base_expr(pair: pest::iterators::Pair<Rule>) -> Result<Expr, ReaperError> {
     let inner = pair
@@ -4,12 +4,14 @@
 //!
 //! ## Module Structure
 //!
+//! - `arithmetic`: Infix arithmetic (`+ - * / %`) with precedence
 //! - `condition`: Boolean condition parsing (AND, OR, NOT)
 //! - `comparison`: Comparison and assignment parsing
 //! - `comprehension`: Comprehension parsing (set, array, object)
 //! - `expression`: Expression and method call parsing
 //! - `value`: Value and attribute parsing
 
+mod arithmetic;
 mod comparison;
 mod comprehension;
 mod condition;
@@ -203,24 +205,74 @@ fn parse_rule(pair: pest::iterators::Pair<Rule>) -> Result<crate::reap::ast::Rul
     let decision_pair = inner.next().unwrap();
     let decision = Decision::from(decision_pair.as_str());
 
+    let mut message = None;
+    let mut obligations: Option<RuleObligations> = None;
     let mut next = inner.next().unwrap();
-    let message = if next.as_rule() == Rule::message_clause {
-        let expr_pair = next.into_inner().next().unwrap();
-        let expr = super::parser::expression::parse_comp_expr(expr_pair)?;
+    loop {
+        let clause = next.as_rule();
+        if !matches!(
+            clause,
+            Rule::message_clause | Rule::obligations_clause | Rule::advice_clause
+        ) {
+            break;
+        }
+        let body = next.into_inner().next().unwrap();
+        match clause {
+            Rule::message_clause => {
+                if message.is_some() {
+                    return Err(duplicate_clause(&name, "message"));
+                }
+                message = Some(super::parser::expression::parse_comp_expr(body)?);
+            }
+            _ => {
+                let slot = obligations.get_or_insert_with(RuleObligations::default);
+                let (kind, target) = if clause == Rule::obligations_clause {
+                    ("obligations", &mut slot.obligations)
+                } else {
+                    ("advice", &mut slot.advice)
+                };
+                if !target.is_empty() {
+                    return Err(duplicate_clause(&name, kind));
+                }
+                *target = parse_object_clause(body, &name, kind)?;
+            }
+        }
         next = inner.next().unwrap();
-        Some(expr)
-    } else {
-        None
-    };
+    }
     let condition = parse_condition(next)?;
 
     Ok(crate::reap::ast::Rule {
         message,
+        obligations,
         name,
         decision,
         condition,
     })
 }
 
+fn duplicate_clause(rule: &str, kind: &str) -> ReaperError {
+    ReaperError::InvalidPolicy {
+        reason: format!("rule '{rule}' declares `with {kind}` more than once"),
+    }
+}
+
+/// The `{...}` of a `with obligations` / `with advice` clause: must be an
+/// object literal (`{}` is accepted as empty).
+fn parse_object_clause(
+    pair: pest::iterators::Pair<Rule>,
+    rule: &str,
+    kind: &str,
+) -> Result<Vec<(String, Value)>, ReaperError> {
+    match value::parse_braced_expr(pair)? {
+        Value::Object(pairs) => Ok(pairs),
+        Value::Set(items) if items.is_empty() => Ok(Vec::new()),
+        _ => Err(ReaperError::InvalidPolicy {
+            reason: format!(
+                "rule '{rule}': `with {kind}` takes an object literal like {{\"key\": value}}"
+            ),
+        }),
+    }
+}
+
 #[cfg(test)]
 mod tests;
@@ -1794,3 +1794,73 @@ fn test_parse_json_is_valid() {
     let policy = ReapParser::parse(input).unwrap();
     assert_eq!(policy.rules.len(), 1);
 }
+
+#[test]
+fn test_parse_arithmetic_precedence_and_associativity() {
+    fn render(e: &Expr) -> String {
+        match e {
+            Expr::BinaryOp { op, left, right } => {
+                format!("({} {} {})", render(left), op.symbol(), render(right))
+            }
+            Expr::Variable(v) => v.clone(),
+            Expr::Literal(Value::Integer(i)) => i.to_string(),
+            other => format!("{other:?}"),
+        }
+    }
+
+    for (src, left, right) in [
+        (
+            "user.used + input.requested <= user.quota",
+            "(user.used + input.requested)",
+            "user.quota",
+        ),
+        ("user.a + user.b * 2 > 10", "(user.a + (user.b * 2))", "10"),
+        ("user.a - 1 - 2 == 0", "((user.a - 1) - 2)", "0"),
+        ("(user.a + 1) * 2 == 8", "((user.a + 1) * 2)", "8"),
+        (
+            "user.limit >= user.used % 7",
+            "user.limit",
+            "(user.used % 7)",
+        ),
+    ] {
+        let input = format!("policy t {{ default: deny, rule r {{ allow if {src} }} }}");
+        let policy = ReapParser::parse(&input).unwrap();
+        let Condition::Comparison {
+            left: ComparisonLeft::Expr(l),
+            right: ComparisonRight::Expr(r),
+            ..
+        } = &policy.rules[0].condition
+        else {
+            panic!("{src}: expected an arithmetic comparison");
+        };
+        assert_eq!(
+            (render(l).as_str(), render(r).as_str()),
+            (left, right),
+            "{src}"
+        );
+    }
+}
+
+#[test]
+fn test_parse_arithmetic_leaves_plain_comparisons_alone() {
+    let input = r#"
+            policy test {
+                default: deny,
+                rule r { allow if user.age >= 18 && user.name == "a-b" }
+            }
+        "#;
+
+    let policy = ReapParser::parse(input).unwrap();
+    let Condition::And(parts) = &policy.rules[0].condition else {
+        panic!("expected a conjunction");
+    };
+    for part in parts {
+        assert!(matches!(
+            part,
+            Condition::Comparison {
+                left: ComparisonLeft::EntityAttr(_),
+                ..
+            }
+        ));
+    }
+}
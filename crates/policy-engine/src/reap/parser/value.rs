@@ -224,12 +224,16 @@ pub(super) fn parse_braced_expr(pair: pest::iterators::Pair<Rule>) -> Result<Val
     }
 }
 
-/// Parse string literal, removing quotes and handling escapes
+/// Parse string literal, removing quotes and handling escapes. Raw
+/// strings (`r"..."`) are taken verbatim.
 pub(super) fn parse_string_literal(
     pair: pest::iterators::Pair<Rule>,
 ) -> Result<String, ReaperError> {
     // String is atomic (@), so we get the full string with quotes
     let s = pair.as_str();
+    if let Some(raw) = s.strip_prefix('r') {
+        return Ok(raw[1..raw.len() - 1].to_string());
+    }
     // Remove surrounding quotes
     let trimmed = &s[1..s.len() - 1];
     // Unescape if needed (simple implementation)
@@ -0,0 +1,110 @@
+//! Differential check of an import against recorded OPA decisions.
+
+use super::RegoImport;
+use crate::data::{DataLoader, DataStore};
+use crate::PolicyAction;
+use reaper_core::ReaperError;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+
+/// One recorded OPA decision: the `input` and the entrypoint's `result`
+/// (the shape of OPA decision-log entries; an undefined result is `null` or
+/// absent).
+#[derive(Debug, Clone, Deserialize)]
+pub struct CorpusRecord {
+    pub input: Value,
+    #[serde(default)]
+    pub result: Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CorpusReport {
+    pub checked: usize,
+    pub mismatches: Vec<CorpusMismatch>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CorpusMismatch {
+    /// Position of the record in the corpus.
+    pub index: usize,
+    pub input: Value,
+    pub expected: PolicyAction,
+    pub actual: PolicyAction,
+    /// The evaluation error, when the translated policy failed (and so
+    /// denied).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Read a corpus: a JSON array of records, or one record per line.
+pub fn parse_corpus(text: &str) -> Result<Vec<CorpusRecord>, ReaperError> {
+    let invalid = |line: Option<usize>, e: serde_json::Error| ReaperError::InvalidPolicy {
+        reason: match line {
+            Some(line) => format!("corpus line {line}: {e}"),
+            None => format!("corpus: {e}"),
+        },
+    };
+    if text.trim_start().starts_with('[') {
+        return serde_json::from_str(text).map_err(|e| invalid(None, e));
+    }
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| serde_json::from_str(line).map_err(|e| invalid(Some(i + 1), e)))
+        .collect()
+}
+
+/// Evaluate every record against the imported policy, with `data` (the OPA
+/// data document) converted through the import's [`super::DataMapping`],
+/// and report the records whose decision differs from OPA's. An evaluation
+/// error counts as deny, as it does when the policy is served — notably a
+/// principal missing from `data`, which OPA tolerates and Reaper does not.
+pub fn verify_corpus(
+    import: &RegoImport,
+    data: Option<&Value>,
+    records: &[CorpusRecord],
+) -> Result<CorpusReport, ReaperError> {
+    let store = DataStore::new();
+    if let Some(data) = data {
+        let entities = import.mapping.entity_document(data)?;
+        DataLoader::new(store.clone()).load_json(&entities.to_string())?;
+    }
+    let evaluator = import
+        .source
+        .parse::<super::ReaperPolicy>()?
+        .build_preferred(Arc::new(store))?;
+
+    let mut mismatches = Vec::new();
+    for (index, record) in records.iter().enumerate() {
+        let holds = match &record.result {
+            Value::Bool(b) => *b,
+            Value::Array(items) => !items.is_empty(),
+            Value::Object(fields) => !fields.is_empty(),
+            Value::Null => false,
+            _ => true,
+        };
+        let expected = if holds != import.denies() {
+            PolicyAction::Allow
+        } else {
+            PolicyAction::Deny
+        };
+        let (actual, error) = match evaluator.evaluate(&import.mapping.request(&record.input)) {
+            Ok(action) => (action, None),
+            Err(e) => (PolicyAction::Deny, Some(e.to_string())),
+        };
+        if actual != expected {
+            mismatches.push(CorpusMismatch {
+                index,
+                input: record.input.clone(),
+                expected,
+                actual,
+                error,
+            });
+        }
+    }
+    Ok(CorpusReport {
+        checked: records.len(),
+        mismatches,
+    })
+}
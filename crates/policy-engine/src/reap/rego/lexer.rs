@@ -0,0 +1,177 @@
+//! Tokenizer for the Rego subset [`super::parser`] understands.
+//!
+//! Newlines are tokens: inside rule bodies they separate expressions, and
+//! the parser decides where they are insignificant (inside brackets and
+//! literals). Comments are kept aside with their line so the translator can
+//! name rules after the `# rule_name` comment above them.
+
+use reaper_core::ReaperError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Tok {
+    Ident(String),
+    Number(String),
+    Str(String),
+    Newline,
+    /// Punctuation and operators, as written (`:=`, `==`, `{`, ...).
+    Sym(&'static str),
+    Eof,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Token {
+    pub tok: Tok,
+    pub line: usize,
+}
+
+/// A `#` comment: its line and text without the `#`.
+#[derive(Debug, Clone)]
+pub(super) struct Comment {
+    pub line: usize,
+    pub text: String,
+}
+
+// Longest first, so `:=` wins over `:` and `==` over `=`.
+const SYMBOLS: &[&str] = &[
+    ":=", "==", "!=", "<=", ">=", "{", "}", "[", "]", "(", ")", ",", ";", ".", ":", "=", "<", ">",
+    "+", "-", "*", "/", "%", "|", "&",
+];
+
+pub(super) fn tokenize(source: &str) -> Result<(Vec<Token>, Vec<Comment>), ReaperError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut comments = Vec::new();
+    let (mut i, mut line) = (0, 1);
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\n' => {
+                tokens.push(Token {
+                    tok: Tok::Newline,
+                    line,
+                });
+                line += 1;
+                i += 1;
+            }
+            c if c.is_whitespace() => i += 1,
+            '#' => {
+                let start = i + 1;
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                comments.push(Comment {
+                    line,
+                    text: chars[start..i].iter().collect(),
+                });
+            }
+            '"' => {
+                let (text, end) = quoted(&chars, i, line)?;
+                tokens.push(Token {
+                    tok: Tok::Str(text),
+                    line,
+                });
+                i = end;
+            }
+            '`' => {
+                let start = i + 1;
+                let Some(len) = chars[start..].iter().position(|&c| c == '`') else {
+                    return Err(lex_error(line, "unterminated raw string"));
+                };
+                let text: String = chars[start..start + len].iter().collect();
+                line += text.matches('\n').count();
+                tokens.push(Token {
+                    tok: Tok::Str(text),
+                    line,
+                });
+                i = start + len + 1;
+            }
+            c if c.is_ascii_digit() => {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_ascii_digit()
+                        || (chars[i] == '.' && chars.get(i + 1).is_some_and(char::is_ascii_digit)))
+                {
+                    i += 1;
+                }
+                tokens.push(Token {
+                    tok: Tok::Number(chars[start..i].iter().collect()),
+                    line,
+                });
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                tokens.push(Token {
+                    tok: Tok::Ident(chars[start..i].iter().collect()),
+                    line,
+                });
+            }
+            _ => {
+                let Some(sym) = SYMBOLS.iter().find(|s| {
+                    s.chars()
+                        .enumerate()
+                        .all(|(k, sc)| chars.get(i + k) == Some(&sc))
+                }) else {
+                    return Err(lex_error(line, &format!("unexpected character '{c}'")));
+                };
+                tokens.push(Token {
+                    tok: Tok::Sym(sym),
+                    line,
+                });
+                i += sym.len();
+            }
+        }
+    }
+    tokens.push(Token {
+        tok: Tok::Eof,
+        line,
+    });
+    Ok((tokens, comments))
+}
+
+/// Decode a `"..."` string starting at `start`; returns the text and the
+/// index just past the closing quote.
+fn quoted(chars: &[char], start: usize, line: usize) -> Result<(String, usize), ReaperError> {
+    let mut out = String::new();
+    let mut i = start + 1;
+    loop {
+        match chars.get(i) {
+            None | Some('\n') => return Err(lex_error(line, "unterminated string")),
+            Some('"') => return Ok((out, i + 1)),
+            Some('\\') => {
+                let escaped = match chars.get(i + 1) {
+                    Some('n') => '\n',
+                    Some('t') => '\t',
+                    Some('r') => '\r',
+                    Some('u') => {
+                        let hex: String = chars.get(i + 2..i + 6).unwrap_or(&[]).iter().collect();
+                        let decoded = u32::from_str_radix(&hex, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .ok_or_else(|| lex_error(line, "invalid \\u escape"))?;
+                        out.push(decoded);
+                        i += 6;
+                        continue;
+                    }
+                    Some(&c) => c,
+                    None => return Err(lex_error(line, "unterminated string")),
+                };
+                out.push(escaped);
+                i += 2;
+            }
+            Some(&c) => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+}
+
+fn lex_error(line: usize, reason: &str) -> ReaperError {
+    ReaperError::InvalidPolicy {
+        reason: format!("Rego line {line}: {reason}"),
+    }
+}
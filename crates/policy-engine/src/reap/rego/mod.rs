@@ -0,0 +1,234 @@
+//! Rego → `.reap` import, for migrating OPA policies (`reaper-cli
+//! import-rego`).
+//!
+//! [`import_rego`] parses a practical subset of Rego — boolean `allow`/`deny`
+//! rules, `deny contains msg` sets, helper rules and functions, `input`
+//! references, entity lookups in `data`, comprehensions, `some`/`every` and
+//! the common string/collection/regex builtins — and emits one `.reap`
+//! policy. Every construct without a faithful translation is listed in
+//! [`RegoImport::issues`] and the rule it appears in is left out; nothing is
+//! approximated.
+//!
+//! OPA and Reaper shape requests and data differently, so the import also
+//! returns a [`DataMapping`]: which `input` fields carry the principal,
+//! resource and action, and which `data` documents hold entity attributes.
+//! [`verify_corpus`] uses it to replay recorded OPA decisions against the
+//! translation.
+
+mod corpus;
+mod lexer;
+mod parser;
+mod translate;
+
+pub use corpus::{parse_corpus, verify_corpus, CorpusMismatch, CorpusRecord, CorpusReport};
+
+use super::format::format_source;
+use super::ReaperPolicy;
+use crate::PolicyRequest;
+use reaper_core::ReaperError;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::fmt;
+
+/// Knobs for [`import_rego`].
+#[derive(Debug, Clone, Default)]
+pub struct ImportOptions {
+    /// Name of the emitted policy (default: the last package segment).
+    pub name: Option<String>,
+    /// The rule whose value is the decision (default: `allow`, else `deny`,
+    /// else `violation`). `deny*` / `violation*` entrypoints deny when true
+    /// or non-empty.
+    pub entrypoint: Option<String>,
+}
+
+/// The result of importing one Rego module.
+#[derive(Debug, Clone)]
+pub struct RegoImport {
+    /// Formatted `.reap` source. Untranslated constructs are listed in a
+    /// comment header as well as in `issues`.
+    pub source: String,
+    pub entrypoint: String,
+    pub mapping: DataMapping,
+    pub issues: Vec<ImportIssue>,
+}
+
+impl RegoImport {
+    /// Whether the entrypoint denies when it holds (`deny`, `violation`).
+    pub fn denies(&self) -> bool {
+        denies(&self.entrypoint)
+    }
+}
+
+fn denies(entrypoint: &str) -> bool {
+    entrypoint.starts_with("deny") || entrypoint.starts_with("violation")
+}
+
+/// A Rego construct the importer left out.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImportIssue {
+    /// 1-based line in the Rego source.
+    pub line: usize,
+    pub construct: String,
+    pub reason: String,
+}
+
+impl fmt::Display for ImportIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}: {}", self.line, self.construct, self.reason)
+    }
+}
+
+/// How the OPA request and data documents map onto Reaper's.
+///
+/// Rego reads the principal's attributes through a lookup such as
+/// `data.entities[input.principal]`; Reaper resolves `user.*` against the
+/// entity whose id is the request principal. The mapping records the input
+/// field holding each id and the `data` documents to load as entities.
+/// Every other `input` field becomes a request context key (`input.context`
+/// is merged into the context as is).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataMapping {
+    /// Dotted `input` path of the principal id.
+    pub principal: String,
+    /// Dotted `input` path of the resource id.
+    pub resource: String,
+    /// Dotted `input` path of the action.
+    pub action: String,
+    /// `data` documents holding `{id: attributes}` maps.
+    pub entities: Vec<EntityCollection>,
+}
+
+/// One `data` document of entity attributes keyed by id.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntityCollection {
+    /// Dotted path under `data` (`entities` for `data.entities`).
+    pub path: String,
+    /// `user`, `resource`, or `entity` when looked up as both.
+    pub entity_type: String,
+}
+
+impl Default for DataMapping {
+    fn default() -> Self {
+        DataMapping {
+            principal: "principal".to_string(),
+            resource: "resource".to_string(),
+            action: "action".to_string(),
+            entities: Vec::new(),
+        }
+    }
+}
+
+impl DataMapping {
+    fn principal_key(&self) -> &str {
+        &self.principal
+    }
+
+    fn resource_key(&self) -> &str {
+        &self.resource
+    }
+
+    fn action_key(&self) -> &str {
+        &self.action
+    }
+
+    /// Convert an OPA data document into a Reaper entity document
+    /// (`{"entities": [{id, type, attributes}]}`, as `DataLoader` reads it).
+    /// Collections missing from `data` are skipped; an id present in several
+    /// collections keeps its first occurrence.
+    pub fn entity_document(&self, data: &Value) -> Result<Value, ReaperError> {
+        let mut entities = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+        for collection in &self.entities {
+            let Some(doc) = lookup(data, &collection.path) else {
+                continue;
+            };
+            let Value::Object(by_id) = doc else {
+                return Err(ReaperError::InvalidPolicy {
+                    reason: format!(
+                        "data.{} is not an object of entities keyed by id",
+                        collection.path
+                    ),
+                });
+            };
+            for (id, attributes) in by_id {
+                if !seen.insert(id.clone()) {
+                    continue;
+                }
+                let attributes = match attributes {
+                    Value::Object(_) => attributes.clone(),
+                    _ => Value::Object(Map::new()),
+                };
+                entities.push(json!({
+                    "id": id,
+                    "type": collection.entity_type,
+                    "attributes": attributes,
+                }));
+            }
+        }
+        Ok(json!({ "entities": entities }))
+    }
+
+    /// The Reaper request for an OPA `input` document.
+    pub fn request(&self, input: &Value) -> PolicyRequest {
+        let id = |path: &str| match lookup(input, path) {
+            Some(Value::String(s)) => s.clone(),
+            Some(Value::Null) | None => String::new(),
+            Some(other) => other.to_string(),
+        };
+        let mut request = PolicyRequest {
+            resource: id(&self.resource),
+            action: id(&self.action),
+            ..Default::default()
+        };
+        // Top-level id fields are consumed; nested ones (`input.user.id`)
+        // leave the rest of their object readable as context.
+        let mapped: Vec<&str> = [&self.principal, &self.resource, &self.action]
+            .into_iter()
+            .map(String::as_str)
+            .filter(|p| !p.contains('.'))
+            .collect();
+        if let Value::Object(fields) = input {
+            for (key, value) in fields {
+                if key == "context" {
+                    if let Value::Object(context) = value {
+                        request
+                            .context
+                            .extend(context.iter().map(|(k, v)| (k.clone(), v.clone())));
+                    }
+                } else if !mapped.contains(&key.as_str()) {
+                    request.context.insert(key.clone(), value.clone());
+                }
+            }
+        }
+        request
+            .context
+            .insert("principal".to_string(), Value::String(id(&self.principal)));
+        request
+    }
+}
+
+fn lookup<'v>(doc: &'v Value, path: &str) -> Option<&'v Value> {
+    path.split('.').try_fold(doc, |v, key| v.get(key))
+}
+
+/// Translate a Rego module into a `.reap` policy.
+///
+/// Fails only when the Rego source does not parse; untranslatable rules are
+/// reported in [`RegoImport::issues`] and left out of the policy.
+pub fn import_rego(source: &str, options: &ImportOptions) -> Result<RegoImport, ReaperError> {
+    let module = parser::parse_module(source)?;
+    let translation = translate::translate(&module, options);
+    let source = format_source(&translation.source).map_err(|e| ReaperError::InvalidPolicy {
+        reason: format!(
+            "import-rego produced invalid .reap ({e}):\n{}",
+            translation.source
+        ),
+    })?;
+    source.parse::<ReaperPolicy>()?;
+    Ok(RegoImport {
+        source,
+        entrypoint: translation.entrypoint,
+        mapping: translation.mapping,
+        issues: translation.issues,
+    })
+}
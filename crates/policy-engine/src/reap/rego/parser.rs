@@ -0,0 +1,638 @@
+//! Recursive-descent parser for the Rego subset the importer translates.
+//!
+//! The parser is deliberately wider than the translator: it accepts `with`,
+//! `else`, partial rules and the like so that the translator can name them
+//! in its report instead of failing on the whole module.
+
+use super::lexer::{tokenize, Comment, Tok, Token};
+use reaper_core::ReaperError;
+
+#[derive(Debug, Clone)]
+pub(super) struct Module {
+    pub package: Vec<String>,
+    /// `import data.x.y as z`: alias → imported reference.
+    pub imports: Vec<(String, Term)>,
+    pub rules: Vec<RuleDef>,
+    pub comments: Vec<Comment>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct RuleDef {
+    pub line: usize,
+    pub name: String,
+    pub kind: RuleKind,
+}
+
+#[derive(Debug, Clone)]
+pub(super) enum RuleKind {
+    /// `default name := value`
+    Default(Term),
+    /// `name := value` / `name if { ... }` / `name(x) if { ... }` /
+    /// `name contains x if { ... }`.
+    Rule {
+        args: Option<Vec<Term>>,
+        /// The element of a partial set rule (`contains x` / `name[x]`).
+        key: Option<Term>,
+        value: Option<Term>,
+        body: Option<Vec<Literal>>,
+        has_else: bool,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Literal {
+    pub line: usize,
+    pub expr: LitExpr,
+    /// `... with input.x as y` modifiers (never translated).
+    pub with: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum LitExpr {
+    Expr(Term),
+    Not(Term),
+    /// `some x, y` — declares local variables.
+    SomeDecl(Vec<String>),
+    /// `some [k,] v in domain`
+    SomeIn {
+        key: Option<Term>,
+        value: Term,
+        domain: Term,
+    },
+    /// `every [k,] v in domain { body }`
+    Every {
+        key: Option<Term>,
+        value: Term,
+        domain: Term,
+        body: Vec<Literal>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Term {
+    Null,
+    Bool(bool),
+    Number(String),
+    Str(String),
+    Var(String),
+    Ref(Box<Term>, Vec<RefArg>),
+    Array(Vec<Term>),
+    Object(Vec<(Term, Term)>),
+    Set(Vec<Term>),
+    ArrayCompr(Box<Term>, Vec<Literal>),
+    SetCompr(Box<Term>, Vec<Literal>),
+    ObjectCompr(Box<Term>, Box<Term>, Vec<Literal>),
+    /// `name(args)` with a dotted name (`regex.match`).
+    Call(String, Vec<Term>),
+    Binary(Box<Term>, &'static str, Box<Term>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum RefArg {
+    Dot(String),
+    Index(Term),
+}
+
+// Literals compare by expression only; line numbers are bookkeeping.
+impl PartialEq for Literal {
+    fn eq(&self, other: &Self) -> bool {
+        self.expr == other.expr && self.with == other.with
+    }
+}
+
+pub(super) fn parse_module(source: &str) -> Result<Module, ReaperError> {
+    let (tokens, comments) = tokenize(source)?;
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        newlines: true,
+    };
+    let mut module = parser.module()?;
+    module.comments = comments;
+    Ok(module)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// Whether newlines are significant at this point (rule bodies and the
+    /// module level) or whitespace (inside brackets and collection literals).
+    newlines: bool,
+}
+
+// Binary operators by precedence, loosest first.
+const LEVELS: &[&[&str]] = &[
+    &[":=", "="],
+    &["in"],
+    &["==", "!=", "<", "<=", ">", ">="],
+    &["|"],
+    &["&"],
+    &["+", "-"],
+    &["*", "/", "%"],
+];
+
+impl Parser {
+    fn peek(&mut self) -> &Tok {
+        if !self.newlines {
+            while self.tokens[self.pos].tok == Tok::Newline {
+                self.pos += 1;
+            }
+        }
+        &self.tokens[self.pos].tok
+    }
+
+    fn line(&mut self) -> usize {
+        self.peek();
+        self.tokens[self.pos].line
+    }
+
+    fn next(&mut self) -> Tok {
+        let tok = self.peek().clone();
+        if tok != Tok::Eof {
+            self.pos += 1;
+        }
+        tok
+    }
+
+    fn at_sym(&mut self, sym: &str) -> bool {
+        matches!(self.peek(), Tok::Sym(s) if *s == sym)
+    }
+
+    fn at_keyword(&mut self, word: &str) -> bool {
+        matches!(self.peek(), Tok::Ident(s) if s == word)
+    }
+
+    fn eat_sym(&mut self, sym: &str) -> bool {
+        let found = self.at_sym(sym);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn eat_keyword(&mut self, word: &str) -> bool {
+        let found = self.at_keyword(word);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect_sym(&mut self, sym: &str) -> Result<(), ReaperError> {
+        if self.eat_sym(sym) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{sym}'")))
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, ReaperError> {
+        match self.next() {
+            Tok::Ident(name) => Ok(name),
+            _ => {
+                self.pos -= 1;
+                Err(self.error("expected an identifier"))
+            }
+        }
+    }
+
+    fn skip_newlines(&mut self) {
+        while self.tokens[self.pos].tok == Tok::Newline {
+            self.pos += 1;
+        }
+    }
+
+    fn error(&mut self, reason: &str) -> ReaperError {
+        let line = self.line();
+        let found = match self.peek().clone() {
+            Tok::Ident(s) | Tok::Number(s) => format!("'{s}'"),
+            Tok::Str(s) => format!("\"{s}\""),
+            Tok::Sym(s) => format!("'{s}'"),
+            Tok::Newline => "end of line".to_string(),
+            Tok::Eof => "end of file".to_string(),
+        };
+        ReaperError::InvalidPolicy {
+            reason: format!("Rego line {line}: {reason}, found {found}"),
+        }
+    }
+
+    /// Run `f` with newline significance set to `newlines`.
+    fn with_newlines<T>(
+        &mut self,
+        newlines: bool,
+        f: impl FnOnce(&mut Self) -> Result<T, ReaperError>,
+    ) -> Result<T, ReaperError> {
+        let saved = std::mem::replace(&mut self.newlines, newlines);
+        let result = f(self);
+        self.newlines = saved;
+        result
+    }
+
+    fn module(&mut self) -> Result<Module, ReaperError> {
+        self.skip_newlines();
+        if !self.eat_keyword("package") {
+            return Err(self.error("expected 'package'"));
+        }
+        let mut package = vec![self.ident()?];
+        while self.eat_sym(".") {
+            package.push(self.ident()?);
+        }
+
+        let mut module = Module {
+            package,
+            imports: Vec::new(),
+            rules: Vec::new(),
+            comments: Vec::new(),
+        };
+        loop {
+            self.skip_newlines();
+            if *self.peek() == Tok::Eof {
+                return Ok(module);
+            }
+            if self.eat_keyword("import") {
+                let path = self.term()?;
+                let alias = if self.eat_keyword("as") {
+                    self.ident()?
+                } else {
+                    match &path {
+                        Term::Ref(_, args) => match args.last() {
+                            Some(RefArg::Dot(last)) => last.clone(),
+                            _ => String::new(),
+                        },
+                        Term::Var(v) => v.clone(),
+                        _ => String::new(),
+                    }
+                };
+                module.imports.push((alias, path));
+            } else {
+                module.rules.push(self.rule()?);
+            }
+        }
+    }
+
+    fn rule(&mut self) -> Result<RuleDef, ReaperError> {
+        let line = self.line();
+        if self.eat_keyword("default") {
+            let name = self.ident()?;
+            if !self.eat_sym(":=") {
+                self.expect_sym("=")?;
+            }
+            let value = self.term()?;
+            return Ok(RuleDef {
+                line,
+                name,
+                kind: RuleKind::Default(value),
+            });
+        }
+
+        let name = self.ident()?;
+        let mut args = None;
+        let mut key = None;
+        if self.at_sym("(") {
+            self.pos += 1;
+            args = Some(self.with_newlines(false, |p| p.term_list(")"))?);
+        } else if self.at_sym("[") {
+            self.pos += 1;
+            key = Some(self.with_newlines(false, |p| {
+                let key = p.term()?;
+                p.expect_sym("]")?;
+                Ok(key)
+            })?);
+        } else if self.eat_keyword("contains") {
+            key = Some(self.term()?);
+        }
+        let value = if self.eat_sym(":=") || self.eat_sym("=") {
+            Some(self.term()?)
+        } else {
+            None
+        };
+
+        let body = self.rule_body()?;
+        let mut has_else = false;
+        loop {
+            self.skip_newlines();
+            if !self.eat_keyword("else") {
+                break;
+            }
+            has_else = true;
+            if self.eat_sym(":=") || self.eat_sym("=") {
+                self.term()?;
+            }
+            self.rule_body()?;
+        }
+
+        Ok(RuleDef {
+            line,
+            name,
+            kind: RuleKind::Rule {
+                args,
+                key,
+                value,
+                body,
+                has_else,
+            },
+        })
+    }
+
+    /// `if { ... }`, `if expr`, `{ ... }` or nothing.
+    fn rule_body(&mut self) -> Result<Option<Vec<Literal>>, ReaperError> {
+        if self.eat_keyword("if") {
+            if self.at_sym("{") && !self.brace_is_collection() {
+                self.pos += 1;
+                return self.body("}").map(Some);
+            }
+            return Ok(Some(vec![self.literal()?]));
+        }
+        if self.at_sym("{") {
+            self.pos += 1;
+            return self.body("}").map(Some);
+        }
+        Ok(None)
+    }
+
+    /// After `if`, `{` opens a body unless it is an object/set literal
+    /// (`if {"a": 1}[x]` is not worth supporting; this looks one token
+    /// ahead for `"key":` or `}`).
+    fn brace_is_collection(&mut self) -> bool {
+        let mut i = self.pos + 1;
+        while self.tokens[i].tok == Tok::Newline {
+            i += 1;
+        }
+        matches!(
+            (&self.tokens[i].tok, &self.tokens.get(i + 1).map(|t| &t.tok)),
+            (Tok::Str(_), Some(Tok::Sym(":")))
+        )
+    }
+
+    /// Literals up to `close`, separated by newlines or `;`.
+    fn body(&mut self, close: &'static str) -> Result<Vec<Literal>, ReaperError> {
+        self.with_newlines(true, |p| {
+            let mut literals = Vec::new();
+            loop {
+                while p.eat_sym(";") || *p.peek() == Tok::Newline {
+                    if *p.peek() == Tok::Newline {
+                        p.pos += 1;
+                    }
+                }
+                if p.eat_sym(close) {
+                    return Ok(literals);
+                }
+                literals.push(p.literal()?);
+                if !(p.at_sym(";") || p.at_sym(close) || *p.peek() == Tok::Newline) {
+                    return Err(p.error("expected end of expression"));
+                }
+            }
+        })
+    }
+
+    fn literal(&mut self) -> Result<Literal, ReaperError> {
+        let line = self.line();
+        let expr = if self.eat_keyword("some") {
+            let first = self.term_below_in()?;
+            let mut vars = vec![first];
+            while self.eat_sym(",") {
+                vars.push(self.term_below_in()?);
+            }
+            if self.eat_keyword("in") {
+                let domain = self.term_below_in()?;
+                let value = vars.pop().unwrap_or(Term::Null);
+                if vars.len() > 1 {
+                    return Err(self.error("`some` binds at most a key and a value"));
+                }
+                LitExpr::SomeIn {
+                    key: vars.pop(),
+                    value,
+                    domain,
+                }
+            } else {
+                let names = vars
+                    .into_iter()
+                    .map(|v| match v {
+                        Term::Var(name) => Ok(name),
+                        _ => Err(ReaperError::InvalidPolicy {
+                            reason: format!("Rego line {line}: `some` declares variables"),
+                        }),
+                    })
+                    .collect::<Result<_, _>>()?;
+                LitExpr::SomeDecl(names)
+            }
+        } else if self.eat_keyword("every") {
+            let mut vars = vec![self.term_below_in()?];
+            if self.eat_sym(",") {
+                vars.push(self.term_below_in()?);
+            }
+            if !self.eat_keyword("in") {
+                return Err(self.error("expected 'in'"));
+            }
+            let domain = self.term_below_in()?;
+            self.expect_sym("{")?;
+            let body = self.body("}")?;
+            let value = vars.pop().unwrap_or(Term::Null);
+            LitExpr::Every {
+                key: vars.pop(),
+                value,
+                domain,
+                body,
+            }
+        } else if self.eat_keyword("not") {
+            LitExpr::Not(self.term()?)
+        } else {
+            LitExpr::Expr(self.term()?)
+        };
+
+        let mut with = false;
+        while self.eat_keyword("with") {
+            with = true;
+            self.term_below_in()?;
+            if !self.eat_keyword("as") {
+                return Err(self.error("expected 'as'"));
+            }
+            self.term_below_in()?;
+        }
+        Ok(Literal { line, expr, with })
+    }
+
+    fn term(&mut self) -> Result<Term, ReaperError> {
+        self.binary(0)
+    }
+
+    /// A term that stops before `in` (the operand of `some`/`every`).
+    fn term_below_in(&mut self) -> Result<Term, ReaperError> {
+        self.binary(2)
+    }
+
+    fn binary(&mut self, level: usize) -> Result<Term, ReaperError> {
+        if level == LEVELS.len() {
+            return self.unary();
+        }
+        let mut left = self.binary(level + 1)?;
+        loop {
+            let op = match self.peek() {
+                Tok::Sym(s) if LEVELS[level].contains(s) => *s,
+                Tok::Ident(s) if LEVELS[level].contains(&s.as_str()) => "in",
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            let right = self.binary(level + 1)?;
+            left = Term::Binary(Box::new(left), op, Box::new(right));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Term, ReaperError> {
+        if self.at_sym("-") {
+            self.pos += 1;
+            return match self.next() {
+                Tok::Number(n) => Ok(Term::Number(format!("-{n}"))),
+                _ => {
+                    self.pos -= 1;
+                    Err(self.error("expected a number after '-'"))
+                }
+            };
+        }
+        let head = self.primary()?;
+        self.postfix(head)
+    }
+
+    /// `.field`, `[index]` and `(args)` suffixes. Whitespace-separated
+    /// brackets still index (`x [0]` is rare enough not to matter), but a
+    /// newline ends the reference.
+    fn postfix(&mut self, head: Term) -> Result<Term, ReaperError> {
+        let mut args = Vec::new();
+        let mut head = head;
+        loop {
+            if self.tokens[self.pos].tok == Tok::Newline {
+                break;
+            }
+            if self.eat_sym(".") {
+                args.push(RefArg::Dot(self.ident()?));
+            } else if self.at_sym("[") {
+                self.pos += 1;
+                let index = self.with_newlines(false, |p| {
+                    let index = p.term()?;
+                    p.expect_sym("]")?;
+                    Ok(index)
+                })?;
+                args.push(RefArg::Index(index));
+            } else if self.at_sym("(") && matches!(head, Term::Var(_)) {
+                // A call: the callee is the dotted name collected so far.
+                let Term::Var(root) = &head else {
+                    unreachable!()
+                };
+                let mut name = root.clone();
+                for arg in &args {
+                    match arg {
+                        RefArg::Dot(field) => {
+                            name.push('.');
+                            name.push_str(field);
+                        }
+                        RefArg::Index(_) => return Err(self.error("unsupported call target")),
+                    }
+                }
+                self.pos += 1;
+                let call_args = self.with_newlines(false, |p| p.term_list(")"))?;
+                head = Term::Call(name, call_args);
+                args.clear();
+            } else {
+                break;
+            }
+        }
+        Ok(if args.is_empty() {
+            head
+        } else {
+            Term::Ref(Box::new(head), args)
+        })
+    }
+
+    fn primary(&mut self) -> Result<Term, ReaperError> {
+        match self.next() {
+            Tok::Number(n) => Ok(Term::Number(n)),
+            Tok::Str(s) => Ok(Term::Str(s)),
+            Tok::Ident(word) => Ok(match word.as_str() {
+                "true" => Term::Bool(true),
+                "false" => Term::Bool(false),
+                "null" => Term::Null,
+                _ => Term::Var(word),
+            }),
+            Tok::Sym("(") => self.with_newlines(false, |p| {
+                let inner = p.term()?;
+                p.expect_sym(")")?;
+                Ok(inner)
+            }),
+            Tok::Sym("[") => self.with_newlines(false, |p| {
+                if p.eat_sym("]") {
+                    return Ok(Term::Array(Vec::new()));
+                }
+                let first = p.binary(4)?;
+                if p.eat_sym("|") {
+                    let body = p.body("]")?;
+                    return Ok(Term::ArrayCompr(Box::new(first), body));
+                }
+                let mut items = vec![first];
+                if p.eat_sym(",") {
+                    items.extend(p.term_list("]")?);
+                } else {
+                    p.expect_sym("]")?;
+                }
+                Ok(Term::Array(items))
+            }),
+            Tok::Sym("{") => self.with_newlines(false, |p| p.braced()),
+            _ => {
+                self.pos -= 1;
+                Err(self.error("expected an expression"))
+            }
+        }
+    }
+
+    /// After `{`: an object, a set, or a set/object comprehension.
+    fn braced(&mut self) -> Result<Term, ReaperError> {
+        if self.eat_sym("}") {
+            return Ok(Term::Object(Vec::new()));
+        }
+        // Parse above `|` so the comprehension bar is not a set union.
+        let first = self.binary(4)?;
+        if self.eat_sym("|") {
+            let body = self.body("}")?;
+            return Ok(Term::SetCompr(Box::new(first), body));
+        }
+        if self.eat_sym(":") {
+            let value = self.binary(4)?;
+            if self.eat_sym("|") {
+                let body = self.body("}")?;
+                return Ok(Term::ObjectCompr(Box::new(first), Box::new(value), body));
+            }
+            let mut pairs = vec![(first, value)];
+            while self.eat_sym(",") {
+                if self.at_sym("}") {
+                    break;
+                }
+                let key = self.term()?;
+                self.expect_sym(":")?;
+                pairs.push((key, self.term()?));
+            }
+            self.expect_sym("}")?;
+            return Ok(Term::Object(pairs));
+        }
+        let mut items = vec![first];
+        if self.eat_sym(",") {
+            items.extend(self.term_list("}")?);
+        } else {
+            self.expect_sym("}")?;
+        }
+        Ok(Term::Set(items))
+    }
+
+    /// Comma-separated terms up to `close` (trailing comma allowed).
+    fn term_list(&mut self, close: &'static str) -> Result<Vec<Term>, ReaperError> {
+        let mut items = Vec::new();
+        loop {
+            if self.eat_sym(close) {
+                return Ok(items);
+            }
+            items.push(self.term()?);
+            if !self.eat_sym(",") {
+                self.expect_sym(close)?;
+                return Ok(items);
+            }
+        }
+    }
+}
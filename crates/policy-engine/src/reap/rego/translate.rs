@@ -0,0 +1,2039 @@
+//! Rego module → `.reap` source text.
+//!
+//! Rules are lowered one by one into `.reap` conditions; a construct with no
+//! faithful translation drops the rule (or helper) it appears in and is
+//! reported, never approximated. The shapes emitted stay inside what the
+//! `.reap` grammar accepts in each position (comparison operands, method
+//! receivers, function arguments); anything else is first bound to a
+//! `tmpN` variable.
+//!
+//! Semantics carried across:
+//! - Iteration (`some x in xs`, `xs[_]`) is existential over the rest of the
+//!   body: `tmpN := [x | x := xs[_]; <rest>] && tmpN.count() > 0`.
+//! - `every x in xs { b }` holds when no element fails `b`.
+//! - Rego fails a body at the first undefined reference; `.reap` reads a
+//!   missing attribute as `null`, which every comparison rejects. The two
+//!   only diverge under `not`, so variables that may be undefined get an
+//!   explicit `!= null` guard before a negation that reads them.
+
+use super::parser::{LitExpr, Literal, Module, RefArg, RuleKind, Term};
+use super::{DataMapping, EntityCollection, ImportIssue, ImportOptions};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Pseudo-entities of `.reap`; a variable may not start with one of these.
+const ENTITIES: &[&str] = &["user", "actor", "resource", "context", "input"];
+
+/// Names `.reap` keeps for itself (see `reap::functions::RESERVED_NAMES`).
+const RESERVED: &[&str] = &[
+    "user", "actor", "resource", "context", "input", "true", "false", "null", "func", "rule",
+    "policy", "library", "import", "default", "allow", "deny", "if", "in", "with",
+];
+
+pub(super) struct Translation {
+    pub source: String,
+    pub entrypoint: String,
+    pub mapping: DataMapping,
+    pub issues: Vec<ImportIssue>,
+}
+
+/// Why a construct has no translation.
+#[derive(Debug)]
+struct Unsupported {
+    line: Option<usize>,
+    construct: String,
+    reason: String,
+}
+
+type Lowered<T> = Result<T, Unsupported>;
+
+fn unsupported<T>(construct: impl Into<String>, reason: impl Into<String>) -> Lowered<T> {
+    Err(Unsupported {
+        line: None,
+        construct: construct.into(),
+        reason: reason.into(),
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Role {
+    User,
+    Resource,
+}
+
+/// What a module-level name means inside rule bodies.
+#[derive(Debug, Clone)]
+enum Global {
+    /// `user := data.entities[input.principal]`
+    Entity(Role),
+    /// `admins := {"alice"}` or `import data.x as y`: inlined at each use.
+    Const(Term),
+    /// A boolean helper rule (`arity: None`) or a function, emitted as a
+    /// `func`.
+    Func {
+        name: String,
+        arity: Option<usize>,
+        line: usize,
+    },
+    /// Defined, but not translatable; uses fail with this reason.
+    Broken(String),
+}
+
+pub(super) fn translate(module: &Module, options: &ImportOptions) -> Translation {
+    let mut tr = Translator {
+        globals: HashMap::new(),
+        mapping: DataMapping::default(),
+        issues: Vec::new(),
+        lookups: Vec::new(),
+    };
+    tr.run(module, options)
+}
+
+struct Translator {
+    globals: HashMap<String, Global>,
+    mapping: DataMapping,
+    issues: Vec<ImportIssue>,
+    /// `(collection, input key, role)` of each entity lookup.
+    lookups: Vec<(Vec<String>, Vec<String>, Role)>,
+}
+
+/// One emitted `.reap` rule.
+struct ReapRule {
+    name: String,
+    effect: &'static str,
+    message: Option<String>,
+    condition: String,
+}
+
+impl Translator {
+    fn issue(&mut self, line: usize, construct: impl Into<String>, reason: impl Into<String>) {
+        self.issues.push(ImportIssue {
+            line,
+            construct: construct.into(),
+            reason: reason.into(),
+        });
+    }
+
+    fn report(&mut self, line: usize, err: Unsupported) {
+        self.issue(err.line.unwrap_or(line), err.construct, err.reason);
+    }
+
+    fn run(&mut self, module: &Module, options: &ImportOptions) -> Translation {
+        let policy_name = options
+            .name
+            .clone()
+            .or_else(|| module.package.last().cloned())
+            .map(|n| sanitize(&n, "policy"))
+            .unwrap_or_else(|| "imported".to_string());
+
+        // Group definitions by name, in order of first appearance.
+        let mut order: Vec<&str> = Vec::new();
+        let mut defs: HashMap<&str, Vec<&super::parser::RuleDef>> = HashMap::new();
+        for rule in &module.rules {
+            if !defs.contains_key(rule.name.as_str()) {
+                order.push(&rule.name);
+            }
+            defs.entry(&rule.name).or_default().push(rule);
+        }
+
+        let entry = match &options.entrypoint {
+            Some(entry) => entry.clone(),
+            None => ["allow", "deny", "violation"]
+                .into_iter()
+                .find(|n| defs.contains_key(n))
+                .unwrap_or("allow")
+                .to_string(),
+        };
+        let negative = super::denies(&entry);
+
+        for (alias, path) in &module.imports {
+            // `import rego.v1` / `import future.keywords.*` only switch syntax.
+            if !matches!(head_of(path), Term::Var(v) if v == "rego" || v == "future") {
+                self.globals
+                    .insert(alias.clone(), Global::Const(path.clone()));
+            }
+        }
+
+        // Module-level names other than the entrypoint.
+        let mut helpers: Vec<(&str, Vec<&super::parser::RuleDef>)> = Vec::new();
+        for name in &order {
+            if *name == entry {
+                continue;
+            }
+            let rules = &defs[name];
+            let bodies: Vec<_> = rules
+                .iter()
+                .filter(|r| !matches!(r.kind, RuleKind::Default(_)))
+                .copied()
+                .collect();
+            let line = rules[0].line;
+            if let Some(default) = rules.iter().find_map(|r| match &r.kind {
+                RuleKind::Default(v) => Some(v),
+                _ => None,
+            }) {
+                if *default != Term::Bool(false) {
+                    self.issue(
+                        line,
+                        format!("default {name}"),
+                        "only `false` defaults of helper rules are translated",
+                    );
+                    self.globals.insert(
+                        name.to_string(),
+                        Global::Broken(format!("`{name}` has a non-false default")),
+                    );
+                    continue;
+                }
+            }
+            if let [single] = bodies.as_slice() {
+                if let RuleKind::Rule {
+                    args: None,
+                    key: None,
+                    value: Some(value),
+                    body: None,
+                    has_else: false,
+                } = &single.kind
+                {
+                    let global = self.constant(name, value);
+                    if let Global::Broken(reason) = &global {
+                        self.issue(line, format!("{name} := ..."), reason.clone());
+                    }
+                    self.globals.insert(name.to_string(), global);
+                    continue;
+                }
+            }
+            match helper_arity(&bodies) {
+                Ok(arity) => {
+                    self.globals.insert(
+                        name.to_string(),
+                        Global::Func {
+                            name: func_name(name),
+                            arity,
+                            line,
+                        },
+                    );
+                    helpers.push((name, bodies));
+                }
+                Err(reason) => {
+                    self.issue(line, name.to_string(), reason.clone());
+                    self.globals
+                        .insert(name.to_string(), Global::Broken(reason));
+                }
+            }
+        }
+        self.record_mapping();
+
+        // Entry rules.
+        let entry_rules: Vec<_> = defs.get(entry.as_str()).cloned().unwrap_or_default();
+        let default_true = entry_rules
+            .iter()
+            .any(|r| matches!(&r.kind, RuleKind::Default(v) if *v == Term::Bool(true)));
+        let default = match (negative, default_true) {
+            (false, true) | (true, false) => "allow",
+            _ => "deny",
+        };
+        if entry_rules.is_empty() {
+            self.issue(1, entry.clone(), "the entrypoint rule is not defined");
+        }
+
+        // A helper negated at the top of every allow body (`not deny`) is a
+        // deny rule in `.reap` terms: emit its bodies as deny rules.
+        let mut absorbed: HashSet<String> = HashSet::new();
+        if !negative && default == "deny" {
+            let bodies: Vec<&Vec<Literal>> = entry_rules
+                .iter()
+                .filter_map(|r| match &r.kind {
+                    RuleKind::Rule { body: Some(b), .. } => Some(b),
+                    _ => None,
+                })
+                .collect();
+            if !bodies.is_empty()
+                && bodies.len()
+                    == entry_rules
+                        .iter()
+                        .filter(|r| !matches!(r.kind, RuleKind::Default(_)))
+                        .count()
+            {
+                for (name, _) in &helpers {
+                    let negated = |b: &&Vec<Literal>| {
+                        b.iter()
+                            .any(|l| !l.with && l.expr == LitExpr::Not(Term::Var(name.to_string())))
+                    };
+                    let is_flag = matches!(
+                        self.globals.get(*name),
+                        Some(Global::Func { arity: None, .. })
+                    );
+                    if is_flag && bodies.iter().all(negated) {
+                        absorbed.insert(name.to_string());
+                    }
+                }
+            }
+        }
+
+        // Helpers become funcs; a helper that fails breaks its callers, so
+        // lower until nothing new breaks.
+        let mut funcs: BTreeMap<usize, (String, String, HashSet<String>)> = BTreeMap::new();
+        loop {
+            let mut broke = false;
+            funcs.clear();
+            for (index, (name, bodies)) in helpers.iter().enumerate() {
+                let Some(Global::Func {
+                    name: reap_name,
+                    line,
+                    ..
+                }) = self.globals.get(*name).cloned()
+                else {
+                    continue;
+                };
+                match self.lower_func(bodies) {
+                    Ok((text, uses)) => {
+                        funcs.insert(
+                            index,
+                            (name.to_string(), format!("func {reap_name}{text}"), uses),
+                        );
+                    }
+                    Err(err) => {
+                        let reason = format!("`{name}` could not be translated");
+                        self.report(line, err);
+                        self.globals
+                            .insert(name.to_string(), Global::Broken(reason));
+                        broke = true;
+                    }
+                }
+            }
+            if !broke {
+                break;
+            }
+        }
+
+        let mut rules: Vec<ReapRule> = Vec::new();
+        let mut used: HashSet<String> = HashSet::new();
+        let mut names = Names::default();
+        for helper in helpers.iter().filter(|(n, _)| absorbed.contains(*n)) {
+            for (i, def) in helper.1.iter().enumerate() {
+                let RuleKind::Rule { body, .. } = &def.kind else {
+                    continue;
+                };
+                let name = names.rule(module, def.line, &format!("{}_{}", helper.0, i + 1));
+                let mut cx = Cx::new(self);
+                let lowered = cx.rule_body(body.as_deref().unwrap_or(&[]));
+                let uses = cx.uses;
+                match lowered {
+                    Ok(condition) => {
+                        used.extend(uses);
+                        rules.push(ReapRule {
+                            name,
+                            effect: "deny",
+                            message: None,
+                            condition,
+                        });
+                    }
+                    Err(err) => self.report(def.line, err),
+                }
+            }
+        }
+
+        let mut n = 0;
+        for def in &entry_rules {
+            let RuleKind::Rule {
+                args,
+                key,
+                value,
+                body,
+                has_else,
+            } = &def.kind
+            else {
+                continue;
+            };
+            n += 1;
+            if args.is_some() {
+                self.issue(
+                    def.line,
+                    entry.clone(),
+                    "the entrypoint cannot be a function",
+                );
+                continue;
+            }
+            if *has_else {
+                self.issue(
+                    def.line,
+                    format!("{entry} ... else"),
+                    "`else` chains are not translated",
+                );
+                continue;
+            }
+            let positive = match (value, key) {
+                (None, _) | (Some(Term::Bool(true)), None) => true,
+                (Some(Term::Bool(false)), None) => false,
+                _ => {
+                    self.issue(
+                        def.line,
+                        format!("{entry} := ..."),
+                        "only boolean entrypoint values are translated",
+                    );
+                    continue;
+                }
+            };
+            if key.is_some() && !negative {
+                self.issue(
+                    def.line,
+                    format!("{entry} contains ..."),
+                    "a set-valued entrypoint must be a deny/violation set",
+                );
+                continue;
+            }
+            let effect = if positive != negative {
+                "allow"
+            } else {
+                "deny"
+            };
+            let name = names.rule(module, def.line, &format!("{entry}_{n}"));
+            let body: Vec<Literal> = body
+                .iter()
+                .flatten()
+                .filter(|l| {
+                    !matches!(&l.expr, LitExpr::Not(Term::Var(v)) if absorbed.contains(v) && !l.with)
+                })
+                .cloned()
+                .collect();
+            let mut cx = Cx::new(self);
+            let lowered = match key {
+                Some(key) => cx.set_rule(&body, key),
+                None => cx.rule_body(&body).map(|c| (c, Ok(None))),
+            };
+            let uses = cx.uses;
+            match lowered {
+                Ok((condition, message)) => {
+                    used.extend(uses);
+                    let message = match message {
+                        Ok(message) => message,
+                        Err(err) => {
+                            self.report(def.line, err);
+                            None
+                        }
+                    };
+                    rules.push(ReapRule {
+                        name,
+                        effect,
+                        message,
+                        condition,
+                    });
+                }
+                Err(err) => self.report(def.line, err),
+            }
+        }
+
+        // Funcs reachable from the emitted rules.
+        let mut queue: Vec<String> = used.into_iter().collect();
+        let mut emitted: HashSet<String> = HashSet::new();
+        while let Some(name) = queue.pop() {
+            if !emitted.insert(name.clone()) {
+                continue;
+            }
+            if let Some((_, _, uses)) = funcs.values().find(|(n, _, _)| *n == name) {
+                queue.extend(uses.iter().cloned());
+            }
+        }
+
+        let mut source = String::new();
+        source.push_str(&format!(
+            "// Imported from Rego package {} (entrypoint `{entry}`).\n",
+            module.package.join(".")
+        ));
+        let mut issues = self.issues.clone();
+        issues.sort_by_key(|i| i.line);
+        for issue in &issues {
+            source.push_str(&format!("// not translated: {issue}\n"));
+        }
+        source.push_str(&format!(
+            "policy {policy_name} {{\n    default: {default},\n"
+        ));
+        for rule in rules
+            .iter()
+            .filter(|r| r.effect == "deny")
+            .chain(rules.iter().filter(|r| r.effect == "allow"))
+        {
+            let message = rule
+                .message
+                .as_ref()
+                .map(|m| format!(" with message {m}"))
+                .unwrap_or_default();
+            source.push_str(&format!(
+                "    rule {} {{ {}{message} if {{ {} }} }}\n",
+                rule.name, rule.effect, rule.condition
+            ));
+        }
+        for (name, text, _) in funcs.values() {
+            if emitted.contains(name) {
+                source.push_str(&format!("    {text}\n"));
+            }
+        }
+        source.push_str("}\n");
+
+        Translation {
+            source,
+            entrypoint: entry,
+            mapping: std::mem::take(&mut self.mapping),
+            issues,
+        }
+    }
+
+    /// Classify `name := value` at module level.
+    fn constant(&mut self, name: &str, value: &Term) -> Global {
+        if let Some((collection, key)) = entity_lookup(value) {
+            let role = if name == "resource"
+                || matches!(
+                    key.last().map(String::as_str),
+                    Some("resource" | "object" | "target")
+                ) {
+                Role::Resource
+            } else {
+                Role::User
+            };
+            if let Some((_, other, _)) = self.lookups.iter().find(|(_, _, r)| *r == role) {
+                if *other != key {
+                    return Global::Broken(format!(
+                        "the {} is already looked up by `input.{}`",
+                        if role == Role::User {
+                            "principal"
+                        } else {
+                            "resource"
+                        },
+                        other.join(".")
+                    ));
+                }
+            }
+            self.lookups.push((collection, key, role));
+            return Global::Entity(role);
+        }
+        if literal(value).is_some() || matches!(head_of(value), Term::Var(v) if v == "input") {
+            return Global::Const(value.clone());
+        }
+        Global::Broken(format!(
+            "`{name}` is computed; only entity lookups, literals and input references are translated at module level"
+        ))
+    }
+
+    fn record_mapping(&mut self) {
+        for (collection, key, role) in &self.lookups {
+            match role {
+                Role::User => self.mapping.principal = key.join("."),
+                Role::Resource => self.mapping.resource = key.join("."),
+            }
+            let path = collection.join(".");
+            let entity_type = if *role == Role::User {
+                "user"
+            } else {
+                "resource"
+            };
+            match self.mapping.entities.iter_mut().find(|e| e.path == path) {
+                Some(existing) if existing.entity_type != entity_type => {
+                    existing.entity_type = "entity".to_string();
+                }
+                Some(_) => {}
+                None => self.mapping.entities.push(EntityCollection {
+                    path,
+                    entity_type: entity_type.to_string(),
+                }),
+            }
+        }
+    }
+
+    /// `(params) := body` for a helper; returns the text after the name and
+    /// the helpers it calls.
+    fn lower_func(&self, bodies: &[&super::parser::RuleDef]) -> Lowered<(String, HashSet<String>)> {
+        let mut cx = Cx::new(self);
+        let mut params: Vec<String> = Vec::new();
+        let mut alternatives = Vec::new();
+        for def in bodies {
+            let RuleKind::Rule { args, body, .. } = &def.kind else {
+                continue;
+            };
+            let mut scope = Scope::default();
+            for (i, arg) in args.iter().flatten().enumerate() {
+                let Term::Var(v) = arg else {
+                    return unsupported(
+                        "function arguments",
+                        "only plain variables are translated as parameters",
+                    );
+                };
+                let reap = reap_var(v);
+                if params.len() <= i {
+                    params.push(reap.clone());
+                } else if params[i] != reap {
+                    return unsupported(
+                        "function arguments",
+                        "every definition must name its parameters the same way",
+                    );
+                }
+                scope.vars.insert(v.clone(), reap.clone());
+                cx.taken.insert(reap);
+            }
+            let condition = cx
+                .body(body.as_deref().unwrap_or(&[]), &mut scope, None)
+                .map_err(|e| with_line(e, def.line))?;
+            alternatives.push(conjunction(&condition));
+        }
+        let condition = match alternatives.as_slice() {
+            // Only a `default name := false`.
+            [] => "false".to_string(),
+            [single] => single.clone(),
+            _ => alternatives
+                .iter()
+                .map(|a| format!("({a})"))
+                .collect::<Vec<_>>()
+                .join(" || "),
+        };
+        Ok((format!("({}) := {condition}", params.join(", ")), cx.uses))
+    }
+}
+
+fn with_line(mut err: Unsupported, line: usize) -> Unsupported {
+    err.line.get_or_insert(line);
+    err
+}
+
+fn head_of(term: &Term) -> &Term {
+    match term {
+        Term::Ref(head, _) => head_of(head),
+        _ => term,
+    }
+}
+
+/// `data.a.b[input.k.l]` → `(["a", "b"], ["k", "l"])`.
+fn entity_lookup(term: &Term) -> Option<(Vec<String>, Vec<String>)> {
+    let Term::Ref(head, args) = term else {
+        return None;
+    };
+    if **head != Term::Var("data".to_string()) {
+        return None;
+    }
+    let (last, collection) = args.split_last()?;
+    let collection = dotted(collection)?;
+    let RefArg::Index(Term::Ref(key_head, key)) = last else {
+        return None;
+    };
+    if **key_head != Term::Var("input".to_string()) || collection.is_empty() {
+        return None;
+    }
+    Some((collection, dotted(key)?))
+}
+
+fn dotted(args: &[RefArg]) -> Option<Vec<String>> {
+    args.iter()
+        .map(|a| match a {
+            RefArg::Dot(s) => Some(s.clone()),
+            RefArg::Index(Term::Str(s)) => Some(s.clone()),
+            RefArg::Index(_) => None,
+        })
+        .collect()
+}
+
+/// Helper definitions translate when every one is a boolean body (or
+/// `:= true` body) with the same arity.
+fn helper_arity(defs: &[&super::parser::RuleDef]) -> Result<Option<usize>, String> {
+    let mut arity = None;
+    for (i, def) in defs.iter().enumerate() {
+        let RuleKind::Rule {
+            args,
+            key,
+            value,
+            body,
+            has_else,
+        } = &def.kind
+        else {
+            continue;
+        };
+        if *has_else {
+            return Err("`else` chains are not translated".to_string());
+        }
+        if key.is_some() {
+            return Err(
+                "partial set rules are only translated as a deny/violation entrypoint".to_string(),
+            );
+        }
+        if !matches!(value, None | Some(Term::Bool(true))) || body.is_none() {
+            return Err("rules that produce values are not translated; only boolean rules and functions become `func`s".to_string());
+        }
+        let this = args.as_ref().map(Vec::len);
+        if i > 0 && this != arity {
+            return Err("definitions disagree on the number of arguments".to_string());
+        }
+        arity = this;
+    }
+    Ok(arity)
+}
+
+/// A `.reap` func name for a Rego rule name.
+fn func_name(name: &str) -> String {
+    sanitize(name, "rego")
+}
+
+/// `name` if it is a usable `.reap` identifier, else `{prefix}_{name}`.
+fn sanitize(name: &str, prefix: &str) -> String {
+    let clean: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let starts_alpha = clean
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic());
+    let clashes = RESERVED.contains(&clean.as_str())
+        || ENTITIES.iter().any(|e| clean.starts_with(e))
+        || is_temp(&clean);
+    if starts_alpha && !clashes {
+        clean
+    } else {
+        format!("{prefix}_{}", clean.trim_start_matches('_'))
+    }
+}
+
+fn reap_var(name: &str) -> String {
+    sanitize(name, "v")
+}
+
+fn is_temp(name: &str) -> bool {
+    name.strip_prefix("tmp")
+        .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+}
+
+fn is_ident(s: &str) -> bool {
+    s.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+        && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// A constant term as `.reap` value text.
+fn literal(term: &Term) -> Option<String> {
+    Some(match term {
+        Term::Null => "null".to_string(),
+        Term::Bool(b) => b.to_string(),
+        Term::Number(n) => n.clone(),
+        Term::Str(s) => quote(s),
+        Term::Array(items) => format!(
+            "[{}]",
+            items
+                .iter()
+                .map(literal)
+                .collect::<Option<Vec<_>>>()?
+                .join(", ")
+        ),
+        Term::Set(items) => format!(
+            "{{{}}}",
+            items
+                .iter()
+                .map(literal)
+                .collect::<Option<Vec<_>>>()?
+                .join(", ")
+        ),
+        Term::Object(pairs) => format!(
+            "{{{}}}",
+            pairs
+                .iter()
+                .map(|(k, v)| match k {
+                    Term::Str(k) => Some(format!("{}: {}", quote(k), literal(v)?)),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?
+                .join(", ")
+        ),
+        _ => return None,
+    })
+}
+
+fn conjunction(conds: &[String]) -> String {
+    if conds.is_empty() {
+        "true".to_string()
+    } else {
+        conds.join(" && ")
+    }
+}
+
+/// Rule names: the `# name` comment right above a rule, else a numbered
+/// fallback; made unique.
+#[derive(Default)]
+struct Names {
+    taken: HashSet<String>,
+}
+
+impl Names {
+    fn rule(&mut self, module: &Module, line: usize, fallback: &str) -> String {
+        let commented = module
+            .comments
+            .iter()
+            .find(|c| c.line + 1 == line)
+            .and_then(|c| {
+                let text = c.text.trim();
+                let first = text.split_whitespace().next()?;
+                let first = first.trim_end_matches(':');
+                let single = text.split_whitespace().count() == 1;
+                (is_ident(first) && (single || first.contains('_'))).then(|| first.to_string())
+            });
+        let base = sanitize(commented.as_deref().unwrap_or(fallback), "rule");
+        let mut name = base.clone();
+        let mut n = 2;
+        while !self.taken.insert(name.clone()) {
+            name = format!("{base}_{n}");
+            n += 1;
+        }
+        name
+    }
+}
+
+/// Rego variables in scope: Rego name → `.reap` name.
+#[derive(Clone, Default)]
+struct Scope {
+    vars: HashMap<String, String>,
+    /// `.reap` variables bound to something Rego might find undefined.
+    nullable: HashSet<String>,
+}
+
+/// A lowered expression, tagged with the grammar positions it may take.
+#[derive(Debug, Clone)]
+enum Out {
+    /// A `.reap` value literal (string, bool, null, array, object, set).
+    Lit(String),
+    Num(String),
+    /// `root.a.b[index]`; an index implies at least one segment.
+    Path {
+        root: String,
+        segs: Vec<String>,
+        index: Option<String>,
+    },
+    /// `root.a.b.m1(args).m2(args)`
+    Method {
+        root: String,
+        segs: Vec<String>,
+        chain: Vec<(&'static str, Vec<String>)>,
+    },
+    /// `ns::name(args)` / `name(args)`
+    Call(String, Vec<String>),
+    Arith(String),
+    /// Array/set/object comprehension (assignment-only).
+    Compr(String),
+}
+
+impl Out {
+    fn var(name: impl Into<String>) -> Self {
+        Out::Path {
+            root: name.into(),
+            segs: Vec::new(),
+            index: None,
+        }
+    }
+
+    fn render(&self) -> String {
+        match self {
+            Out::Lit(s) | Out::Num(s) | Out::Arith(s) | Out::Compr(s) => s.clone(),
+            Out::Path { root, segs, index } => {
+                let mut s = root.clone();
+                for seg in segs {
+                    s.push('.');
+                    s.push_str(seg);
+                }
+                if let Some(index) = index {
+                    s.push_str(&format!("[{index}]"));
+                }
+                s
+            }
+            Out::Method { root, segs, chain } => {
+                let mut s = Out::Path {
+                    root: root.clone(),
+                    segs: segs.clone(),
+                    index: None,
+                }
+                .render();
+                for (method, args) in chain {
+                    s.push_str(&format!(".{method}({})", args.join(", ")));
+                }
+                s
+            }
+            Out::Call(name, args) => format!("{name}({})", args.join(", ")),
+        }
+    }
+
+    fn entity_rooted(&self) -> bool {
+        matches!(self, Out::Path { root, .. } | Out::Method { root, .. } if ENTITIES.contains(&root.as_str()))
+    }
+}
+
+/// A builtin's `.reap` spelling: a method on one argument, or a function
+/// taking the arguments in the given order.
+enum Builtin {
+    Method {
+        receiver: usize,
+        method: &'static str,
+        arity: usize,
+    },
+    Func {
+        name: &'static str,
+        order: &'static [usize],
+    },
+}
+
+fn builtin(name: &str) -> Option<Builtin> {
+    let method = |method, arity| Builtin::Method {
+        receiver: 0,
+        method,
+        arity,
+    };
+    Some(match name {
+        "count" => method("count", 1),
+        "sum" => method("sum", 1),
+        "max" => method("max", 1),
+        "min" => method("min", 1),
+        "sort" => method("sort", 1),
+        "lower" => method("lower", 1),
+        "upper" => method("upper", 1),
+        "trim_space" => method("trim", 1),
+        "object.keys" => method("keys", 1),
+        "contains" => method("contains", 2),
+        "startswith" => method("startswith", 2),
+        "endswith" => method("endswith", 2),
+        "split" => method("split", 2),
+        "regex.match" | "re_match" => Builtin::Func {
+            name: "regex::matches",
+            order: &[1, 0],
+        },
+        "net.cidr_contains" => Builtin::Func {
+            name: "net::cidr_contains",
+            order: &[0, 1],
+        },
+        "time.now_ns" => Builtin::Func {
+            name: "time::now_ns",
+            order: &[],
+        },
+        "abs" | "round" | "ceil" | "floor" => Builtin::Func {
+            name: match name {
+                "abs" => "math::abs",
+                "round" => "math::round",
+                "ceil" => "math::ceil",
+                _ => "math::floor",
+            },
+            order: &[0],
+        },
+        "is_string" | "is_number" | "is_array" | "is_set" | "is_object" | "is_null" => {
+            Builtin::Func {
+                name: match name {
+                    "is_string" => "is_string",
+                    "is_number" => "is_number",
+                    "is_array" => "is_array",
+                    "is_set" => "is_set",
+                    "is_object" => "is_object",
+                    _ => "is_null",
+                },
+                order: &[0],
+            }
+        }
+        "is_boolean" => Builtin::Func {
+            name: "is_bool",
+            order: &[0],
+        },
+        _ => return None,
+    })
+}
+
+/// Where the first iteration of a comprehension body lands: it becomes the
+/// comprehension's iterator instead of a nested existential.
+struct IterSlot<'p> {
+    bound: Option<(String, String)>,
+    /// Conditions that must run before the comprehension (computing its
+    /// source).
+    prelude: &'p mut Vec<String>,
+}
+
+/// Lowering state for one rule or func.
+struct Cx<'t> {
+    tr: &'t Translator,
+    tmp: usize,
+    taken: HashSet<String>,
+    /// Helpers (by Rego name) the lowered code calls.
+    uses: HashSet<String>,
+}
+
+impl<'t> Cx<'t> {
+    fn new(tr: &'t Translator) -> Self {
+        Cx {
+            tr,
+            tmp: 0,
+            taken: HashSet::new(),
+            uses: HashSet::new(),
+        }
+    }
+
+    fn fresh(&mut self) -> String {
+        self.tmp += 1;
+        format!("tmp{}", self.tmp)
+    }
+
+    fn bind(&mut self, rego: &str, scope: &mut Scope) -> String {
+        let base = match rego.strip_prefix('$') {
+            Some(synthetic) => synthetic.to_string(),
+            None => reap_var(rego),
+        };
+        let mut name = base.clone();
+        let mut n = 2;
+        while !self.taken.insert(name.clone()) {
+            name = format!("{base}_{n}");
+            n += 1;
+        }
+        scope.vars.insert(rego.to_string(), name.clone());
+        name
+    }
+
+    fn is_bound(&self, name: &str, scope: &Scope) -> bool {
+        scope.vars.contains_key(name)
+            || self.tr.globals.contains_key(name)
+            || name == "input"
+            || name == "data"
+    }
+
+    // ---- rule bodies ------------------------------------------------------
+
+    fn rule_body(&mut self, body: &[Literal]) -> Lowered<String> {
+        let mut scope = Scope::default();
+        Ok(conjunction(&self.body(body, &mut scope, None)?))
+    }
+
+    /// A deny/violation set rule: the condition plus the message the set
+    /// element carries (a failed message keeps the rule).
+    fn set_rule(
+        &mut self,
+        body: &[Literal],
+        key: &Term,
+    ) -> Lowered<(String, Lowered<Option<String>>)> {
+        let mut scope = Scope::default();
+        let mut conds = self.body(body, &mut scope, None)?;
+        let message = match key {
+            Term::Object(pairs) => pairs
+                .iter()
+                .find(|(k, _)| *k == Term::Str("msg".to_string()))
+                .map(|(_, v)| v),
+            other => Some(other),
+        };
+        let message = match message {
+            None => Ok(None),
+            // Assigned after a `some`/`[_]`, so it lives inside the
+            // comprehension the iteration lowered to.
+            Some(Term::Var(v))
+                if !scope.vars.contains_key(v) && body.iter().any(|l| lit_mentions(l, v)) =>
+            {
+                unsupported(
+                    v.clone(),
+                    "the message is built from an iteration variable; the rule is kept without it",
+                )
+            }
+            Some(term) => {
+                let mut extra = Vec::new();
+                let rendered = self
+                    .value(term, &scope, &mut extra)
+                    .map(|out| self.arg(out, &mut extra));
+                rendered.map(|m| {
+                    conds.extend(extra);
+                    Some(m)
+                })
+            }
+        };
+        Ok((conjunction(&conds), message))
+    }
+
+    /// Lower `lits` into `.reap` conjuncts. With a slot, the first iteration
+    /// fills it (comprehension bodies).
+    fn body(
+        &mut self,
+        lits: &[Literal],
+        scope: &mut Scope,
+        mut slot: Option<&mut IterSlot<'_>>,
+    ) -> Lowered<Vec<String>> {
+        let mut conds = Vec::new();
+        let mut i = 0;
+        while i < lits.len() {
+            let lit = &lits[i];
+            let step = self.literal(lit, &lits[i + 1..], scope, &mut conds, slot.as_deref_mut());
+            match step.map_err(|e| with_line(e, lit.line))? {
+                Step::Next => i += 1,
+                Step::Done => break,
+            }
+        }
+        Ok(conds)
+    }
+
+    fn literal(
+        &mut self,
+        lit: &Literal,
+        rest: &[Literal],
+        scope: &mut Scope,
+        conds: &mut Vec<String>,
+        slot: Option<&mut IterSlot<'_>>,
+    ) -> Lowered<Step> {
+        if lit.with {
+            return unsupported("with", "`with` modifiers are not translated");
+        }
+        match &lit.expr {
+            LitExpr::SomeDecl(_) => Ok(Step::Next),
+            LitExpr::SomeIn { key, value, domain } => {
+                if !matches!(key, None | Some(Term::Var(_)))
+                    || matches!(key, Some(Term::Var(k)) if k != "_")
+                {
+                    return unsupported(
+                        "some k, v in",
+                        "iterating keys with `some k, v in` is not translated",
+                    );
+                }
+                let Term::Var(var) = value else {
+                    return unsupported(
+                        "some ... in",
+                        "only a plain variable can be bound by `some ... in`",
+                    );
+                };
+                self.existential(var, domain, rest, scope, conds, slot)
+            }
+            LitExpr::Every {
+                key,
+                value,
+                domain,
+                body,
+            } => {
+                if matches!(key, Some(k) if *k != Term::Var("_".to_string())) {
+                    return unsupported(
+                        "every k, v in",
+                        "iterating keys with `every k, v in` is not translated",
+                    );
+                }
+                let Term::Var(var) = value else {
+                    return unsupported("every", "only a plain variable can be bound by `every`");
+                };
+                let dom = self.value(domain, scope, conds)?;
+                let dom = self.iterable(dom, conds);
+                let mut read = Vec::new();
+                body.iter().for_each(|l| lit_vars(l, &mut read));
+                self.guard(read, scope, conds);
+                let mut inner = scope.clone();
+                let x = self.bind(var, &mut inner);
+                let filters = self.body(body, &mut inner, None)?;
+                let t = self.fresh();
+                conds.push(format!("{dom} != null"));
+                conds.push(format!(
+                    "{t} := [{x} | {x} := {dom}[_]; !({})]",
+                    conjunction(&filters)
+                ));
+                conds.push(format!("{t}.count() == 0"));
+                Ok(Step::Next)
+            }
+            LitExpr::Not(term) => {
+                let mut inner = scope.clone();
+                let negated = Literal {
+                    line: lit.line,
+                    expr: LitExpr::Expr(term.clone()),
+                    with: false,
+                };
+                let sub = self.body(std::slice::from_ref(&negated), &mut inner, None)?;
+                let mut read = Vec::new();
+                vars_in(term, &mut read);
+                self.guard(read, scope, conds);
+                conds.push(format!("!({})", conjunction(&sub)));
+                Ok(Step::Next)
+            }
+            LitExpr::Expr(term) => {
+                if let Some((collection, index_var)) = self.find_iteration(term, scope) {
+                    // `xs[_] == "a"`: bind the element, test the rewritten
+                    // literal against it.
+                    // `$` keeps the synthetic name out of Rego's namespace.
+                    let element = "$item".to_string();
+                    let rewritten = replace_ref(term, &collection, &element);
+                    if let Some(v) = index_var {
+                        if mentions(&rewritten, &v) || rest.iter().any(|l| lit_mentions(l, &v)) {
+                            return unsupported(
+                                format!("[{v}]"),
+                                format!("the iteration index `{v}` is used as a value"),
+                            );
+                        }
+                    }
+                    let mut tail = vec![Literal {
+                        line: lit.line,
+                        expr: LitExpr::Expr(rewritten),
+                        with: false,
+                    }];
+                    tail.extend(rest.iter().cloned());
+                    let Term::Ref(head, args) = &collection else {
+                        unreachable!("find_iteration returns a reference")
+                    };
+                    let domain = if args.len() == 1 {
+                        (**head).clone()
+                    } else {
+                        Term::Ref(head.clone(), args[..args.len() - 1].to_vec())
+                    };
+                    return self.existential(&element, &domain, &tail, scope, conds, slot);
+                }
+                self.expression(term, scope, conds)?;
+                Ok(Step::Next)
+            }
+        }
+    }
+
+    /// `some var in domain` followed by `rest`.
+    fn existential(
+        &mut self,
+        var: &str,
+        domain: &Term,
+        rest: &[Literal],
+        scope: &mut Scope,
+        conds: &mut Vec<String>,
+        slot: Option<&mut IterSlot<'_>>,
+    ) -> Lowered<Step> {
+        if let Some(slot) = slot {
+            if slot.bound.is_none() {
+                let mut prelude = Vec::new();
+                let dom = self.value(domain, scope, &mut prelude)?;
+                let dom = self.iterable(dom, &mut prelude);
+                slot.prelude.extend(prelude);
+                let x = self.bind(var, scope);
+                slot.bound = Some((x, dom));
+                conds.extend(self.body(rest, scope, None)?);
+                return Ok(Step::Done);
+            }
+        }
+
+        let dom = self.value(domain, scope, conds)?;
+        let dom = self.iterable(dom, conds);
+        let mut inner = scope.clone();
+        let x = self.bind(var, &mut inner);
+
+        // `xs[_] == "a"` reads best as membership.
+        if let [Literal {
+            expr: LitExpr::Expr(Term::Binary(l, "==", r)),
+            with: false,
+            ..
+        }] = rest
+        {
+            let element = Term::Var(var.to_string());
+            let other = if **l == element {
+                Some(r)
+            } else if **r == element {
+                Some(l)
+            } else {
+                None
+            };
+            if let Some(value) = other.and_then(|t| literal(t).filter(|_| is_scalar(t))) {
+                conds.push(format!("{value} in {dom}"));
+                return Ok(Step::Done);
+            }
+        }
+
+        let filters = self.body(rest, &mut inner, None)?;
+        let t = self.fresh();
+        let filters: String = filters.iter().map(|f| format!("; {f}")).collect();
+        conds.push(format!("{t} := [{x} | {x} := {dom}[_]{filters}]"));
+        conds.push(format!("{t}.count() > 0"));
+        Ok(Step::Done)
+    }
+
+    /// `v != null` for every possibly-undefined variable among `read`.
+    fn guard(&mut self, read: Vec<String>, scope: &Scope, conds: &mut Vec<String>) {
+        for name in read {
+            if let Some(reap) = scope
+                .vars
+                .get(&name)
+                .filter(|r| scope.nullable.contains(*r))
+            {
+                let guard = format!("{reap} != null");
+                if !conds.contains(&guard) {
+                    conds.push(guard);
+                }
+            }
+        }
+    }
+
+    /// A reference in `term` that iterates (`xs[_]`, `xs[i]` with `i`
+    /// unbound): the reference up to and including that index, and the index
+    /// variable if named.
+    fn find_iteration(&self, term: &Term, scope: &Scope) -> Option<(Term, Option<String>)> {
+        match term {
+            Term::Ref(head, args) => {
+                if let Some(found) = self.find_iteration(head, scope) {
+                    return Some(found);
+                }
+                for (k, arg) in args.iter().enumerate() {
+                    if let RefArg::Index(index) = arg {
+                        if let Term::Var(v) = index {
+                            if v == "_" || !self.is_bound(v, scope) {
+                                let named = (v != "_").then(|| v.clone());
+                                return Some((Term::Ref(head.clone(), args[..=k].to_vec()), named));
+                            }
+                        }
+                        if let Some(found) = self.find_iteration(index, scope) {
+                            return Some(found);
+                        }
+                    }
+                }
+                None
+            }
+            Term::Call(_, args) | Term::Array(args) | Term::Set(args) => {
+                args.iter().find_map(|a| self.find_iteration(a, scope))
+            }
+            Term::Object(pairs) => pairs.iter().find_map(|(k, v)| {
+                self.find_iteration(k, scope)
+                    .or_else(|| self.find_iteration(v, scope))
+            }),
+            Term::Binary(l, _, r) => self
+                .find_iteration(l, scope)
+                .or_else(|| self.find_iteration(r, scope)),
+            _ => None,
+        }
+    }
+
+    /// A bare body expression.
+    fn expression(
+        &mut self,
+        term: &Term,
+        scope: &mut Scope,
+        conds: &mut Vec<String>,
+    ) -> Lowered<()> {
+        match term {
+            Term::Binary(l, op @ (":=" | "="), r) => {
+                let (target, value) = match (&**l, &**r) {
+                    (Term::Var(v), value) if *op == ":=" || !self.is_bound(v, scope) => (v, value),
+                    (value, Term::Var(v)) if *op == "=" && !self.is_bound(v, scope) => (v, value),
+                    _ if *op == "=" => return self.comparison(l, "==", r, scope, conds),
+                    _ => {
+                        return unsupported(
+                            format!("{op} with a pattern"),
+                            "destructuring assignments are not translated",
+                        )
+                    }
+                };
+                let out = self.value(value, scope, conds)?;
+                let nullable = !matches!(out, Out::Lit(_) | Out::Num(_) | Out::Compr(_));
+                let name = self.bind(target, scope);
+                if nullable {
+                    scope.nullable.insert(name.clone());
+                }
+                conds.push(format!("{name} := {}", out.render()));
+                Ok(())
+            }
+            Term::Binary(l, op @ ("==" | "!=" | "<" | "<=" | ">" | ">="), r) => {
+                self.comparison(l, op, r, scope, conds)
+            }
+            Term::Binary(member, "in", collection) => {
+                let x = self.value(member, scope, conds)?;
+                let c = self.value(collection, scope, conds)?;
+                if matches!(x, Out::Lit(_) | Out::Num(_)) && matches!(c, Out::Path { .. }) {
+                    conds.push(format!("{} in {}", x.render(), c.render()));
+                } else {
+                    let arg = self.arg(x, conds);
+                    let contains = self.method(c, "contains", vec![arg], conds);
+                    conds.push(contains.render());
+                }
+                Ok(())
+            }
+            Term::Bool(true) => {
+                conds.push("true".to_string());
+                Ok(())
+            }
+            Term::Bool(false) => {
+                conds.push("false".to_string());
+                Ok(())
+            }
+            Term::Var(name)
+                if matches!(
+                    self.tr.globals.get(name),
+                    Some(Global::Func { arity: None, .. })
+                ) =>
+            {
+                let Some(Global::Func { name: func, .. }) = self.tr.globals.get(name) else {
+                    unreachable!()
+                };
+                self.uses.insert(name.clone());
+                conds.push(format!("{func}()"));
+                Ok(())
+            }
+            Term::Call(name, args)
+                if matches!(self.tr.globals.get(name), Some(Global::Func { .. })) =>
+            {
+                let Some(Global::Func {
+                    name: func, arity, ..
+                }) = self.tr.globals.get(name).cloned()
+                else {
+                    unreachable!()
+                };
+                if arity != Some(args.len()) {
+                    return unsupported(format!("{name}(...)"), "wrong number of arguments");
+                }
+                self.uses.insert(name.clone());
+                let mut rendered = Vec::new();
+                for arg in args {
+                    let out = self.value(arg, scope, conds)?;
+                    rendered.push(self.arg(out, conds));
+                }
+                conds.push(format!("{func}({})", rendered.join(", ")));
+                Ok(())
+            }
+            _ => {
+                let out = self.value(term, scope, conds)?;
+                match out {
+                    // Predicates stand as conditions.
+                    Out::Method { .. } | Out::Call(..) => conds.push(out.render()),
+                    Out::Lit(ref s) if s == "false" => conds.push(out.render()),
+                    // Any other defined constant is true.
+                    Out::Lit(_) | Out::Num(_) => {}
+                    _ => {
+                        let left = self.cmp_left(out, conds);
+                        conds.push(format!("{left} != false"));
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn comparison(
+        &mut self,
+        l: &Term,
+        op: &str,
+        r: &Term,
+        scope: &Scope,
+        conds: &mut Vec<String>,
+    ) -> Lowered<()> {
+        let left = self.value(l, scope, conds)?;
+        let right = self.value(r, scope, conds)?;
+        let text = self.compare(left, op, right, conds);
+        conds.push(text);
+        Ok(())
+    }
+
+    // ---- values -----------------------------------------------------------
+
+    fn value(&mut self, term: &Term, scope: &Scope, conds: &mut Vec<String>) -> Lowered<Out> {
+        if let Some(text) = literal(term) {
+            return Ok(match term {
+                Term::Number(_) => Out::Num(text),
+                _ => Out::Lit(text),
+            });
+        }
+        match term {
+            Term::Var(name) => self.variable(name, scope, conds),
+            Term::Ref(head, args) => self.reference(head, args, scope, conds),
+            Term::Array(_) | Term::Set(_) | Term::Object(_) => unsupported(
+                "collection literal",
+                "collections with computed elements are not translated",
+            ),
+            Term::ArrayCompr(head, body) => {
+                self.comprehension(&[head], body, "[", "]", scope, conds)
+            }
+            Term::SetCompr(head, body) => self.comprehension(&[head], body, "{", "}", scope, conds),
+            Term::ObjectCompr(key, value, body) => {
+                self.comprehension(&[key, value], body, "{", "}", scope, conds)
+            }
+            Term::Call(name, args) => self.call(name, args, scope, conds),
+            Term::Binary(l, op @ ("+" | "-" | "*" | "/" | "%"), r) => {
+                let left = self.value(l, scope, conds)?;
+                let right = self.value(r, scope, conds)?;
+                let set_op = [&left, &right]
+                    .iter()
+                    .any(|o| matches!(o, Out::Lit(s) if s.starts_with('{')));
+                if *op == "-" && set_op {
+                    let arg = self.arg(right, conds);
+                    return Ok(self.method(left, "difference", vec![arg], conds));
+                }
+                let l = self.arith(left, conds);
+                let r = self.arith(right, conds);
+                Ok(Out::Arith(format!("{l} {op} {r}")))
+            }
+            Term::Binary(l, op @ ("|" | "&"), r) => {
+                let left = self.value(l, scope, conds)?;
+                let right = self.value(r, scope, conds)?;
+                let arg = self.arg(right, conds);
+                let method = if *op == "|" { "union" } else { "intersection" };
+                Ok(self.method(left, method, vec![arg], conds))
+            }
+            Term::Binary(_, op, _) => unsupported(
+                format!("`{op}` as a value"),
+                "comparisons and assignments are only translated as body expressions",
+            ),
+            _ => unsupported("expression", "not translated"),
+        }
+    }
+
+    fn variable(&mut self, name: &str, scope: &Scope, conds: &mut Vec<String>) -> Lowered<Out> {
+        if let Some(reap) = scope.vars.get(name) {
+            return Ok(Out::var(reap.clone()));
+        }
+        match self.tr.globals.get(name) {
+            Some(Global::Const(term)) => {
+                let term = term.clone();
+                self.value(&term, &Scope::default(), conds)
+            }
+            Some(Global::Entity(_)) => unsupported(
+                name.to_string(),
+                "an entity is only translated through its attributes (`user.role`)",
+            ),
+            Some(Global::Func { .. }) => unsupported(
+                name.to_string(),
+                "a helper rule is only translated as a condition, not as a value",
+            ),
+            Some(Global::Broken(reason)) => {
+                unsupported(name.to_string(), format!("depends on {reason}"))
+            }
+            None if name == "input" || name == "data" => unsupported(
+                name.to_string(),
+                "the whole document cannot be used as a value",
+            ),
+            None => unsupported(name.to_string(), format!("`{name}` is unbound")),
+        }
+    }
+
+    fn reference(
+        &mut self,
+        head: &Term,
+        args: &[RefArg],
+        scope: &Scope,
+        conds: &mut Vec<String>,
+    ) -> Lowered<Out> {
+        let name = match head {
+            Term::Var(name) if !scope.vars.contains_key(name) => name.as_str(),
+            _ => {
+                let base = self.value(head, scope, conds)?;
+                let base = self.as_var(base, conds);
+                return self.path(base, args, conds);
+            }
+        };
+        match (name, self.tr.globals.get(name)) {
+            ("input", _) => {
+                let Some(keys) = dotted(args) else {
+                    let prefix: Vec<RefArg> = args
+                        .iter()
+                        .take_while(
+                            |a| !matches!(a, RefArg::Index(t) if !matches!(t, Term::Str(_))),
+                        )
+                        .cloned()
+                        .collect();
+                    let base = self.reference(head, &prefix, scope, conds)?;
+                    return self.path(base, &args[prefix.len()..], conds);
+                };
+                self.input(&keys)
+            }
+            ("data", _) => {
+                for split in (1..=args.len()).rev() {
+                    let candidate = Term::Ref(Box::new(head.clone()), args[..split].to_vec());
+                    if let Some((collection, key)) = entity_lookup(&candidate) {
+                        if let Some((_, _, role)) = self
+                            .tr
+                            .lookups
+                            .iter()
+                            .find(|(c, k, _)| *c == collection && *k == key)
+                        {
+                            return self.path(Out::var(role_name(*role)), &args[split..], conds);
+                        }
+                    }
+                }
+                unsupported(
+                    "data",
+                    "only entity lookups bound at module level (`user := data.<collection>[input.<key>]`) are translated",
+                )
+            }
+            (_, Some(Global::Entity(role))) => self.path(Out::var(role_name(*role)), args, conds),
+            (_, Some(Global::Const(term))) => {
+                let term = term.clone();
+                match term {
+                    Term::Ref(inner_head, mut inner_args) => {
+                        inner_args.extend(args.iter().cloned());
+                        self.reference(&inner_head, &inner_args, &Scope::default(), conds)
+                    }
+                    Term::Var(v) => self.reference(&Term::Var(v), args, &Scope::default(), conds),
+                    _ => {
+                        let base = self.value(&term, &Scope::default(), conds)?;
+                        let base = self.as_var(base, conds);
+                        self.path(base, args, conds)
+                    }
+                }
+            }
+            _ => {
+                let base = self.variable(name, scope, conds)?;
+                self.path(base, args, conds)
+            }
+        }
+    }
+
+    /// An `input` path under the request mapping.
+    fn input(&mut self, keys: &[String]) -> Lowered<Out> {
+        let joined = keys.join(".");
+        let mapping = &self.tr.mapping;
+        if joined == mapping.principal_key() {
+            return Ok(Out::Path {
+                root: "context".to_string(),
+                segs: vec!["principal".to_string()],
+                index: None,
+            });
+        }
+        if joined == mapping.resource_key() {
+            return Ok(Out::var("resource"));
+        }
+        if joined == mapping.action_key() {
+            return Ok(Out::Path {
+                root: "context".to_string(),
+                segs: vec!["action".to_string()],
+                index: None,
+            });
+        }
+        let segs: Vec<String> = match keys {
+            [] => {
+                return unsupported(
+                    "input",
+                    "the whole input document cannot be used as a value",
+                )
+            }
+            [context] if context == "context" => {
+                return unsupported(
+                    "input.context",
+                    "the whole context cannot be used as a value",
+                )
+            }
+            [context, rest @ ..] if context == "context" => rest.to_vec(),
+            _ => keys.to_vec(),
+        };
+        if let Some(bad) = segs.iter().find(|s| !is_ident(s)) {
+            return unsupported(
+                format!("input.{joined}"),
+                format!("`{bad}` is not a valid attribute name"),
+            );
+        }
+        Ok(Out::Path {
+            root: "context".to_string(),
+            segs,
+            index: None,
+        })
+    }
+
+    /// Apply `.field` / `[index]` accessors to `base`.
+    fn path(&mut self, base: Out, args: &[RefArg], conds: &mut Vec<String>) -> Lowered<Out> {
+        let mut out = base;
+        for arg in args {
+            let (root, mut segs) = match out {
+                Out::Path {
+                    root,
+                    segs,
+                    index: None,
+                } => (root, segs),
+                other => {
+                    let Out::Path { root, .. } = self.as_var(other, conds) else {
+                        unreachable!()
+                    };
+                    (root, Vec::new())
+                }
+            };
+            let next_index = match arg {
+                RefArg::Dot(field) => {
+                    if !is_ident(field) {
+                        return unsupported(format!(".{field}"), "not a valid attribute name");
+                    }
+                    segs.push(field.clone());
+                    None
+                }
+                RefArg::Index(Term::Str(field)) if is_ident(field) => {
+                    segs.push(field.clone());
+                    None
+                }
+                RefArg::Index(Term::Number(n)) if n.bytes().all(|b| b.is_ascii_digit()) => {
+                    Some(n.clone())
+                }
+                RefArg::Index(_) => {
+                    return unsupported("[index]", "only constant indexes are translated")
+                }
+            };
+            out = match next_index {
+                None => Out::Path {
+                    root,
+                    segs,
+                    index: None,
+                },
+                Some(index) if segs.is_empty() => {
+                    // `x[0]` has no `.reap` comparison form; bind it.
+                    let t = self.fresh();
+                    conds.push(format!("{t} := {root}[{index}]"));
+                    Out::var(t)
+                }
+                Some(index) => Out::Path {
+                    root,
+                    segs,
+                    index: Some(index),
+                },
+            };
+        }
+        Ok(out)
+    }
+
+    fn call(
+        &mut self,
+        name: &str,
+        args: &[Term],
+        scope: &Scope,
+        conds: &mut Vec<String>,
+    ) -> Lowered<Out> {
+        if name == "sprintf" {
+            return self.sprintf(args, scope, conds);
+        }
+        if let Some(Global::Func { .. } | Global::Broken(_)) = self.tr.globals.get(name) {
+            return unsupported(
+                format!("{name}(...)"),
+                "user functions are only translated as conditions, not as values",
+            );
+        }
+        let Some(spec) = builtin(name) else {
+            return unsupported(
+                format!("{name}(...)"),
+                "builtin not supported by the importer",
+            );
+        };
+        let mut outs = Vec::new();
+        for arg in args {
+            outs.push(self.value(arg, scope, conds)?);
+        }
+        match spec {
+            Builtin::Method {
+                receiver,
+                method,
+                arity,
+            } => {
+                if outs.len() != arity {
+                    return unsupported(format!("{name}(...)"), "wrong number of arguments");
+                }
+                let recv = outs.remove(receiver);
+                let args = outs.into_iter().map(|o| self.arg(o, conds)).collect();
+                Ok(self.method(recv, method, args, conds))
+            }
+            Builtin::Func { name: func, order } => {
+                if outs.len() != order.len() {
+                    return unsupported(format!("{name}(...)"), "wrong number of arguments");
+                }
+                let mut rendered = Vec::new();
+                for &i in order {
+                    let out = outs[i].clone();
+                    rendered.push(self.arg(out, conds));
+                }
+                Ok(Out::Call(func.to_string(), rendered))
+            }
+        }
+    }
+
+    /// `sprintf("%s is public", [name])` → `concat("", name, " is public")`
+    /// — `.reap` `concat` joins strings, so only `%s`/`%v` translate.
+    fn sprintf(&mut self, args: &[Term], scope: &Scope, conds: &mut Vec<String>) -> Lowered<Out> {
+        let [Term::Str(format), Term::Array(values)] = args else {
+            return unsupported(
+                "sprintf",
+                "only a literal format and a literal argument array are translated",
+            );
+        };
+        let mut parts = Vec::new();
+        let mut values = values.iter();
+        let mut text = String::new();
+        let mut chars = format.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                text.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('%') => text.push('%'),
+                Some('s' | 'v') => {
+                    if !text.is_empty() {
+                        parts.push(quote(&std::mem::take(&mut text)));
+                    }
+                    let Some(value) = values.next() else {
+                        return unsupported("sprintf", "more verbs than arguments");
+                    };
+                    let out = self.value(value, scope, conds)?;
+                    parts.push(self.arg(out, conds));
+                }
+                _ => return unsupported("sprintf", "only %s and %v verbs are translated"),
+            }
+        }
+        if !text.is_empty() {
+            parts.push(quote(&text));
+        }
+        Ok(Out::Call("concat".to_string(), parts))
+    }
+
+    fn comprehension(
+        &mut self,
+        heads: &[&Term],
+        body: &[Literal],
+        open: &str,
+        close: &str,
+        scope: &Scope,
+        conds: &mut Vec<String>,
+    ) -> Lowered<Out> {
+        let mut inner = scope.clone();
+        let mut prelude = Vec::new();
+        let mut slot = IterSlot {
+            bound: None,
+            prelude: &mut prelude,
+        };
+        let mut filters = self.body(body, &mut inner, Some(&mut slot))?;
+        let Some((x, dom)) = slot.bound else {
+            return unsupported(
+                "comprehension",
+                "the body must iterate over a collection (`some x in xs` or `xs[_]`)",
+            );
+        };
+        conds.extend(prelude);
+        let mut rendered = Vec::new();
+        for head in heads {
+            let out = self.value(head, &inner, &mut filters)?;
+            rendered.push(self.arg(out, &mut filters));
+        }
+        let filters: String = filters.iter().map(|f| format!("; {f}")).collect();
+        Ok(Out::Compr(format!(
+            "{open}{} | {x} := {dom}[_]{filters}{close}",
+            rendered.join(": ")
+        )))
+    }
+
+    // ---- grammar positions -------------------------------------------------
+
+    fn as_var(&mut self, out: Out, conds: &mut Vec<String>) -> Out {
+        match out {
+            Out::Path {
+                ref segs,
+                index: None,
+                ..
+            } if segs.is_empty() => out,
+            other => {
+                let t = self.fresh();
+                conds.push(format!("{t} := {}", other.render()));
+                Out::var(t)
+            }
+        }
+    }
+
+    /// A comprehension source: `path` in `x := path[_]`.
+    fn iterable(&mut self, out: Out, conds: &mut Vec<String>) -> String {
+        match &out {
+            Out::Path {
+                root,
+                segs,
+                index: None,
+            } if !segs.is_empty() || !ENTITIES.contains(&root.as_str()) => out.render(),
+            _ => self.as_var(out, conds).render(),
+        }
+    }
+
+    fn method(
+        &mut self,
+        recv: Out,
+        method: &'static str,
+        args: Vec<String>,
+        conds: &mut Vec<String>,
+    ) -> Out {
+        let (root, segs, mut chain) = match recv {
+            Out::Path {
+                root,
+                segs,
+                index: None,
+            } if !segs.is_empty() || !ENTITIES.contains(&root.as_str()) => (root, segs, Vec::new()),
+            Out::Method { root, segs, chain } => (root, segs, chain),
+            other => {
+                let Out::Path { root, .. } = self.as_var(other, conds) else {
+                    unreachable!()
+                };
+                (root, Vec::new(), Vec::new())
+            }
+        };
+        chain.push((method, args));
+        Out::Method { root, segs, chain }
+    }
+
+    /// A function/method argument (`comp_expr`).
+    fn arg(&mut self, out: Out, conds: &mut Vec<String>) -> String {
+        match &out {
+            Out::Lit(_) | Out::Num(_) | Out::Call(..) | Out::Arith(_) => out.render(),
+            Out::Path { segs, .. } | Out::Method { segs, .. } if segs.len() <= 1 => out.render(),
+            _ => self.as_var(out, conds).render(),
+        }
+    }
+
+    /// An operand inside arithmetic.
+    fn arith(&mut self, out: Out, conds: &mut Vec<String>) -> String {
+        match &out {
+            Out::Num(_) | Out::Path { .. } | Out::Method { .. } | Out::Call(..) => out.render(),
+            Out::Arith(s) => format!("({s})"),
+            _ => self.as_var(out, conds).render(),
+        }
+    }
+
+    fn cmp_left(&mut self, out: Out, conds: &mut Vec<String>) -> String {
+        match &out {
+            Out::Path { .. } | Out::Method { .. } => out.render(),
+            _ => self.as_var(out, conds).render(),
+        }
+    }
+
+    fn cmp_right(&mut self, out: Out, conds: &mut Vec<String>) -> String {
+        match &out {
+            Out::Path { .. } | Out::Lit(_) | Out::Num(_) => out.render(),
+            Out::Method { .. } if !out.entity_rooted() => out.render(),
+            _ => self.as_var(out, conds).render(),
+        }
+    }
+
+    fn compare(&mut self, left: Out, op: &str, right: Out, conds: &mut Vec<String>) -> String {
+        let op = if op == "=" { "==" } else { op };
+        if matches!(left, Out::Arith(_)) || matches!(right, Out::Arith(_)) {
+            let top = |cx: &mut Self, o: Out, conds: &mut Vec<String>| match o {
+                Out::Arith(s) => s,
+                other => cx.arith(other, conds),
+            };
+            let l = top(self, left, conds);
+            let r = top(self, right, conds);
+            return format!("{l} {op} {r}");
+        }
+        let constant = |o: &Out| matches!(o, Out::Lit(_) | Out::Num(_));
+        if constant(&left) && !constant(&right) {
+            let flipped = match op {
+                "<" => ">",
+                "<=" => ">=",
+                ">" => "<",
+                ">=" => "<=",
+                other => other,
+            };
+            return self.compare(right, flipped, left, conds);
+        }
+        let l = self.cmp_left(left, conds);
+        let r = self.cmp_right(right, conds);
+        format!("{l} {op} {r}")
+    }
+}
+
+enum Step {
+    Next,
+    /// The literal consumed the rest of the body.
+    Done,
+}
+
+fn role_name(role: Role) -> &'static str {
+    match role {
+        Role::User => "user",
+        Role::Resource => "resource",
+    }
+}
+
+fn is_scalar(term: &Term) -> bool {
+    matches!(
+        term,
+        Term::Null | Term::Bool(_) | Term::Number(_) | Term::Str(_)
+    )
+}
+
+/// Replace the reference `target` inside `term` by `element`, keeping any
+/// accessors that followed it.
+fn replace_ref(term: &Term, target: &Term, element: &str) -> Term {
+    let Term::Ref(t_head, t_args) = target else {
+        return term.clone();
+    };
+    let replace = |t: &Term| replace_ref(t, target, element);
+    match term {
+        Term::Ref(head, args)
+            if **head == **t_head
+                && args.len() >= t_args.len()
+                && args[..t_args.len()] == t_args[..] =>
+        {
+            let rest = &args[t_args.len()..];
+            if rest.is_empty() {
+                Term::Var(element.to_string())
+            } else {
+                Term::Ref(Box::new(Term::Var(element.to_string())), rest.to_vec())
+            }
+        }
+        Term::Ref(head, args) => Term::Ref(
+            Box::new(replace(head)),
+            args.iter()
+                .map(|a| match a {
+                    RefArg::Index(t) => RefArg::Index(replace(t)),
+                    dot => dot.clone(),
+                })
+                .collect(),
+        ),
+        Term::Call(name, args) => Term::Call(name.clone(), args.iter().map(replace).collect()),
+        Term::Array(items) => Term::Array(items.iter().map(replace).collect()),
+        Term::Set(items) => Term::Set(items.iter().map(replace).collect()),
+        Term::Object(pairs) => Term::Object(
+            pairs
+                .iter()
+                .map(|(k, v)| (replace(k), replace(v)))
+                .collect(),
+        ),
+        Term::Binary(l, op, r) => Term::Binary(Box::new(replace(l)), op, Box::new(replace(r))),
+        other => other.clone(),
+    }
+}
+
+fn vars_in(term: &Term, out: &mut Vec<String>) {
+    match term {
+        Term::Var(v) => out.push(v.clone()),
+        Term::Ref(head, args) => {
+            vars_in(head, out);
+            for arg in args {
+                if let RefArg::Index(t) = arg {
+                    vars_in(t, out);
+                }
+            }
+        }
+        Term::Call(_, items) | Term::Array(items) | Term::Set(items) => {
+            items.iter().for_each(|t| vars_in(t, out))
+        }
+        Term::Object(pairs) => pairs.iter().for_each(|(k, v)| {
+            vars_in(k, out);
+            vars_in(v, out);
+        }),
+        Term::Binary(l, _, r) => {
+            vars_in(l, out);
+            vars_in(r, out);
+        }
+        Term::ArrayCompr(head, body) | Term::SetCompr(head, body) => {
+            vars_in(head, out);
+            body.iter().for_each(|l| lit_vars(l, out));
+        }
+        Term::ObjectCompr(k, v, body) => {
+            vars_in(k, out);
+            vars_in(v, out);
+            body.iter().for_each(|l| lit_vars(l, out));
+        }
+        _ => {}
+    }
+}
+
+fn lit_vars(lit: &Literal, out: &mut Vec<String>) {
+    match &lit.expr {
+        LitExpr::Expr(t) | LitExpr::Not(t) => vars_in(t, out),
+        LitExpr::SomeDecl(_) => {}
+        LitExpr::SomeIn { key, value, domain } => key
+            .iter()
+            .chain([value, domain])
+            .for_each(|t| vars_in(t, out)),
+        LitExpr::Every {
+            key,
+            value,
+            domain,
+            body,
+        } => {
+            key.iter()
+                .chain([value, domain])
+                .for_each(|t| vars_in(t, out));
+            body.iter().for_each(|l| lit_vars(l, out));
+        }
+    }
+}
+
+fn mentions(term: &Term, var: &str) -> bool {
+    let mut vars = Vec::new();
+    vars_in(term, &mut vars);
+    vars.iter().any(|v| v == var)
+}
+
+fn lit_mentions(lit: &Literal, var: &str) -> bool {
+    let mut vars = Vec::new();
+    lit_vars(lit, &mut vars);
+    vars.iter().any(|v| v == var)
+}
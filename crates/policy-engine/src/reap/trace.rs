@@ -0,0 +1,171 @@
+//! Evaluation traces: "why was this denied?" for `.reap` policies.
+//!
+//! A decision names at most the rule that matched, which says nothing when
+//! the policy default answered. An [`EvaluationTrace`] records every rule
+//! the evaluation visited, in evaluation order (deny rules, then allow
+//! rules), with the tree of conditions each one evaluated: the resolved
+//! operand values of every comparison, the values bound by assignments,
+//! and where `&&` / `||` stopped early.
+//!
+//! The trace is produced by the AST interpreter, which is the reference
+//! semantics of the language. The compiled and mixed evaluators keep the
+//! policy AST for this and replay the request through the interpreter, then
+//! check that the replay reached the decision they serve (the two paths are
+//! differentially tested, so a mismatch is an engine bug and is reported as
+//! an error rather than as a misleading trace). Tracing is opt-in and off
+//! the serving path: nothing here runs unless a caller asks for a trace.
+
+use super::ast::{Decision, Policy};
+use super::ast_evaluator::ReapAstEvaluator;
+use crate::data::DataStore;
+use crate::evaluators::NamedOutcome;
+use crate::{PolicyAction, PolicyRequest};
+use reaper_core::ReaperError;
+use serde::{Serialize, Serializer};
+use serde_json::Value as JsonValue;
+use std::sync::Arc;
+
+/// How one policy reached its decision for one request.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EvaluationTrace {
+    pub policy: String,
+    /// The evaluator that serves the policy (`reaper_dsl`, `mixed`, ...).
+    pub evaluator: String,
+    #[serde(serialize_with = "lowercase")]
+    pub decision: PolicyAction,
+    /// The rule that decided; `None` when no rule matched and the policy's
+    /// default answered.
+    pub decided_by: Option<String>,
+    /// Every rule, in evaluation order. Rules after the deciding one are
+    /// listed with no condition trace.
+    pub rules: Vec<RuleTrace>,
+    /// Every `func` call the evaluation made, in call order (including
+    /// calls made from other funcs' bodies).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub calls: Vec<FuncCallTrace>,
+}
+
+/// One call of a `func` helper and what it returned.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FuncCallTrace {
+    /// Qualified name (`ns::name` for imported helpers).
+    pub function: String,
+    pub result: bool,
+}
+
+/// One rule's part in an evaluation.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RuleTrace {
+    pub name: String,
+    #[serde(serialize_with = "lowercase")]
+    pub effect: Decision,
+    pub matched: bool,
+    /// `None` when evaluation had already decided before reaching the rule.
+    pub condition: Option<ConditionTrace>,
+}
+
+/// One evaluated node of a rule's condition.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ConditionTrace {
+    /// `true` / `false`.
+    Literal { result: bool },
+    /// A comparison with both operands resolved. For `in`, `left` is the
+    /// member and `right` the collection, as written.
+    Comparison {
+        source: String,
+        left: JsonValue,
+        op: String,
+        right: JsonValue,
+        result: bool,
+    },
+    /// `x := <value>`; always holds.
+    Assignment { variable: String, value: JsonValue },
+    /// A bare boolean expression (function call, method, `rebac::` check)
+    /// or a `some`/`every` block, traced as a whole.
+    Predicate {
+        source: String,
+        value: JsonValue,
+        result: bool,
+    },
+    /// `a && b && ...`. `operands` holds the ones evaluated; `skipped` is
+    /// how many were not, because an earlier one was false.
+    All {
+        result: bool,
+        operands: Vec<ConditionTrace>,
+        skipped: usize,
+    },
+    /// `a || b || ...`; `skipped` counts the operands after the first true.
+    Any {
+        result: bool,
+        operands: Vec<ConditionTrace>,
+        skipped: usize,
+    },
+    Not {
+        result: bool,
+        operand: Box<ConditionTrace>,
+    },
+}
+
+impl ConditionTrace {
+    /// Whether this node held.
+    pub fn result(&self) -> bool {
+        match self {
+            ConditionTrace::Assignment { .. } => true,
+            ConditionTrace::Literal { result }
+            | ConditionTrace::Comparison { result, .. }
+            | ConditionTrace::Predicate { result, .. }
+            | ConditionTrace::All { result, .. }
+            | ConditionTrace::Any { result, .. }
+            | ConditionTrace::Not { result, .. } => *result,
+        }
+    }
+}
+
+impl EvaluationTrace {
+    /// The trace of the rule that decided, if one did.
+    pub fn deciding_rule(&self) -> Option<&RuleTrace> {
+        let name = self.decided_by.as_deref()?;
+        self.rules.iter().find(|r| r.matched && r.name == name)
+    }
+}
+
+pub(super) fn lowercase<T: std::fmt::Debug, S: Serializer>(
+    value: &T,
+    s: S,
+) -> Result<S::Ok, S::Error> {
+    s.serialize_str(&format!("{value:?}").to_lowercase())
+}
+
+/// Trace `request` through the interpreter over `policy` on behalf of an
+/// evaluator that served it as `served`, failing if the two disagree.
+pub(crate) fn replay(
+    store: Arc<DataStore>,
+    policy: &Policy,
+    evaluator: &str,
+    served: NamedOutcome<'_>,
+    request: &PolicyRequest,
+) -> Result<EvaluationTrace, ReaperError> {
+    let trace = ReapAstEvaluator::new(store, policy.clone()).explain_with_input(request, None)?;
+    ensure_consistent(trace, evaluator, served)
+}
+
+/// Stamp `trace` with the serving `evaluator`, after checking it reached
+/// the decision and rule the evaluator served.
+pub(crate) fn ensure_consistent(
+    mut trace: EvaluationTrace,
+    evaluator: &str,
+    served: NamedOutcome<'_>,
+) -> Result<EvaluationTrace, ReaperError> {
+    if trace.decision != served.decision || trace.decided_by.as_deref() != served.rule_name {
+        return Err(ReaperError::EvaluationError {
+            reason: format!(
+                "explain: the traced evaluation of policy '{}' reached {:?} (rule {:?}) but the \
+                 {evaluator} evaluator decided {:?} (rule {:?})",
+                trace.policy, trace.decision, trace.decided_by, served.decision, served.rule_name
+            ),
+        });
+    }
+    trace.evaluator = evaluator.to_string();
+    Ok(trace)
+}
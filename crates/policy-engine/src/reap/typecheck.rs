@@ -0,0 +1,746 @@
+//! Static type checking of `.reap` policies against a declared
+//! [`EntitySchema`].
+//!
+//! Without a schema an attribute the data does not carry reads `null`, and a
+//! rule guarded by it silently never matches. With one, [`check_policy`]
+//! walks every rule, message and `func` body and reports:
+//!
+//! - attribute paths (`user.departmnet`, `context.ipp`, `resource.meta.x`)
+//!   that no type bound to that request slot declares, with the closest
+//!   declared name as a suggestion;
+//! - comparisons whose operand types can never satisfy them — `==` between
+//!   different scalar types (always false; `!=` always true), ordering on a
+//!   non-number, membership of a value in an array of another element type;
+//! - `rebac::*` relation names no entity type declares, and relations whose
+//!   declared subject types exclude every type bound to the subject.
+//!
+//! Types flow through `x := <expr>` assignments and comprehension iterators;
+//! anything the checker cannot type (function parameters, `input.*`, `any`
+//! attributes, most function results) is unknown and never reported.
+
+use super::ast::{
+    AssignmentValue, ComparisonLeft, ComparisonRight, Comprehension, Condition, Entity, EntityAttr,
+    Expr, Index, InterpolationPart, IterationSource, MethodName, Operator, Policy, Value,
+};
+use super::functions::qualified;
+use crate::data::schema::{AttributeSchema, AttributeType, EntitySchema};
+use reaper_core::ReaperError;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// One type error, located by the rule or `func` it appears in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeDiagnostic {
+    /// `rule <name>` or `func <qualified name>`.
+    pub location: String,
+    pub message: String,
+}
+
+impl fmt::Display for TypeDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+/// Every type error in `policy` against `schema`, in source order.
+pub fn check_policy(policy: &Policy, schema: &EntitySchema) -> Vec<TypeDiagnostic> {
+    let mut checker = Checker {
+        schema,
+        location: String::new(),
+        diagnostics: Vec::new(),
+    };
+    for func in &policy.functions {
+        checker.location = format!("func {}", qualified(func));
+        checker.condition(&func.body, &mut Scope::new());
+    }
+    for rule in &policy.rules {
+        checker.location = format!("rule {}", rule.name);
+        let mut scope = Scope::new();
+        checker.condition(&rule.condition, &mut scope);
+        if let Some(message) = &rule.message {
+            checker.expr(message, &scope);
+        }
+    }
+    checker.diagnostics
+}
+
+/// [`check_policy`] as a gate: `Err` listing every diagnostic if there are
+/// any.
+pub fn ensure_well_typed(policy: &Policy, schema: &EntitySchema) -> Result<(), ReaperError> {
+    let diagnostics = check_policy(policy, schema);
+    if diagnostics.is_empty() {
+        return Ok(());
+    }
+    let listed: Vec<String> = diagnostics.iter().map(ToString::to_string).collect();
+    Err(ReaperError::InvalidPolicy {
+        reason: format!(
+            "policy '{}' does not type-check against the entity schema:\n  {}",
+            policy.name,
+            listed.join("\n  ")
+        ),
+    })
+}
+
+/// A static type. `Unknown` is never reported on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Ty {
+    Unknown,
+    Null,
+    /// Never `AttributeType::Any` (that is `Unknown`); `items` only for arrays.
+    Known {
+        ty: AttributeType,
+        items: Option<AttributeType>,
+    },
+}
+
+impl Ty {
+    fn of(ty: AttributeType) -> Self {
+        Self::from_decl(ty, None)
+    }
+
+    fn from_decl(ty: AttributeType, items: Option<AttributeType>) -> Self {
+        match ty {
+            AttributeType::Any => Ty::Unknown,
+            _ => Ty::Known {
+                ty,
+                items: items.filter(|i| *i != AttributeType::Any),
+            },
+        }
+    }
+
+    fn literal(value: &Value) -> Self {
+        match value {
+            Value::String(_) => Ty::of(AttributeType::String),
+            Value::Integer(_) | Value::Float(_) => Ty::of(AttributeType::Number),
+            Value::Boolean(_) => Ty::of(AttributeType::Bool),
+            Value::Null => Ty::Null,
+            Value::Object(_) => Ty::of(AttributeType::Object),
+            Value::Array(items) | Value::Set(items) => {
+                let mut kinds = items.iter().map(|v| match Ty::literal(v) {
+                    Ty::Known { ty, .. } => Some(ty),
+                    _ => None,
+                });
+                let first = kinds.next().flatten();
+                let uniform = first.filter(|f| kinds.all(|k| k == Some(*f)));
+                Ty::from_decl(AttributeType::Array, uniform)
+            }
+        }
+    }
+
+    /// The element type of an array; `Unknown` for anything else.
+    fn element(self) -> Ty {
+        match self {
+            Ty::Known {
+                ty: AttributeType::Array,
+                items: Some(items),
+            } => Ty::of(items),
+            _ => Ty::Unknown,
+        }
+    }
+
+    fn scalar(self) -> Option<AttributeType> {
+        match self {
+            Ty::Known { ty, .. } if ty != AttributeType::Array => Some(ty),
+            _ => None,
+        }
+    }
+
+    /// What an `==` compares on this side: arrays compare existentially
+    /// (any element), so an array contributes its element type.
+    fn equality_operand(self) -> Option<AttributeType> {
+        match self {
+            Ty::Known {
+                ty: AttributeType::Array,
+                items,
+            } => items,
+            _ => self.scalar(),
+        }
+    }
+}
+
+type Scope = HashMap<String, Ty>;
+
+/// How one attribute path resolves against one entity type.
+enum Resolved<'s> {
+    Found(&'s AttributeSchema),
+    /// The path leaves the declared structure (an open object or an `any`).
+    Open,
+    /// `segment` is not declared where the path expects it; `declared` are
+    /// the names that are.
+    Missing {
+        segment: String,
+        declared: Vec<&'s str>,
+    },
+}
+
+fn resolve<'s>(attributes: &'s BTreeMap<String, AttributeSchema>, path: &str) -> Resolved<'s> {
+    let mut current = attributes;
+    let mut segments = path.split('.').peekable();
+    while let Some(segment) = segments.next() {
+        let Some(attr) = current.get(segment) else {
+            return Resolved::Missing {
+                segment: segment.to_string(),
+                declared: current.keys().map(String::as_str).collect(),
+            };
+        };
+        if segments.peek().is_none() {
+            return Resolved::Found(attr);
+        }
+        if attr.ty != AttributeType::Object || attr.attributes.is_empty() {
+            return Resolved::Open;
+        }
+        current = &attr.attributes;
+    }
+    Resolved::Open
+}
+
+struct Checker<'s> {
+    schema: &'s EntitySchema,
+    location: String,
+    diagnostics: Vec<TypeDiagnostic>,
+}
+
+impl Checker<'_> {
+    fn report(&mut self, message: String) {
+        let diagnostic = TypeDiagnostic {
+            location: self.location.clone(),
+            message,
+        };
+        if !self.diagnostics.contains(&diagnostic) {
+            self.diagnostics.push(diagnostic);
+        }
+    }
+
+    fn condition(&mut self, condition: &Condition, scope: &mut Scope) {
+        match condition {
+            Condition::True | Condition::False => {}
+            Condition::Comparison { left, op, right } => {
+                self.comparison(left, *op, right, scope);
+            }
+            Condition::Assignment { variable, value } => {
+                let ty = self.assignment(value, scope);
+                scope.insert(variable.clone(), ty);
+            }
+            Condition::And(conditions) => {
+                for c in conditions {
+                    self.condition(c, scope);
+                }
+            }
+            Condition::Or(conditions) => {
+                for c in conditions {
+                    self.condition(c, &mut scope.clone());
+                }
+            }
+            Condition::Not(inner) => self.condition(inner, &mut scope.clone()),
+            Condition::Expr(expr) => {
+                self.expr(expr, scope);
+            }
+            Condition::Quantifier {
+                index,
+                variable,
+                collection,
+                body,
+                ..
+            } => {
+                let element = match collection {
+                    IterationSource::EntityAttr(attr) => self.entity_attr(attr).element(),
+                    IterationSource::VarAttr(_) => Ty::Unknown,
+                    IterationSource::IndexedVariable { variable, index } => {
+                        index_into(scope.get(variable).copied().unwrap_or(Ty::Unknown), index)
+                    }
+                };
+                let mut inner = scope.clone();
+                if let Some(index) = index {
+                    inner.insert(index.clone(), Ty::Unknown);
+                }
+                inner.insert(variable.clone(), element);
+                self.condition(body, &mut inner);
+            }
+        }
+    }
+
+    fn assignment(&mut self, value: &AssignmentValue, scope: &Scope) -> Ty {
+        match value {
+            AssignmentValue::EntityAttr(attr) => self.entity_attr(attr),
+            AssignmentValue::Value(v) => Ty::literal(v),
+            AssignmentValue::Variable(name) => scope.get(name).copied().unwrap_or(Ty::Unknown),
+            AssignmentValue::Comprehension(c) => self.comprehension(c, scope),
+            AssignmentValue::Expr(e) => self.expr(e, scope),
+            AssignmentValue::Comparison { left, op, right } => {
+                self.comparison(left, *op, right, scope);
+                Ty::of(AttributeType::Bool)
+            }
+        }
+    }
+
+    fn comprehension(&mut self, comprehension: &Comprehension, scope: &Scope) -> Ty {
+        let (iterator, filters, outputs, ty) = match comprehension {
+            Comprehension::Set {
+                output,
+                iterator,
+                filters,
+            }
+            | Comprehension::Array {
+                output,
+                iterator,
+                filters,
+            } => (iterator, filters, vec![output], AttributeType::Array),
+            Comprehension::Object {
+                key,
+                value,
+                iterator,
+                filters,
+            } => (iterator, filters, vec![key, value], AttributeType::Object),
+        };
+        let element = match &iterator.collection {
+            IterationSource::EntityAttr(attr) => self.entity_attr(attr),
+            IterationSource::VarAttr(_) => Ty::Unknown,
+            IterationSource::IndexedVariable { variable, index } => {
+                index_into(scope.get(variable).copied().unwrap_or(Ty::Unknown), index)
+            }
+        };
+        let mut inner = scope.clone();
+        inner.insert(iterator.variable.clone(), element);
+        for filter in filters {
+            self.condition(filter, &mut inner);
+        }
+        for output in outputs {
+            self.expr(output, &inner);
+        }
+        Ty::of(ty)
+    }
+
+    fn comparison(
+        &mut self,
+        left: &ComparisonLeft,
+        op: Operator,
+        right: &ComparisonRight,
+        scope: &Scope,
+    ) {
+        let (left_ty, left_text) = match left {
+            ComparisonLeft::EntityAttr(attr) => (self.entity_attr(attr), entity_attr_text(attr)),
+            ComparisonLeft::VarAttr(_) => (Ty::Unknown, String::new()),
+            ComparisonLeft::Expr(e) => (self.expr(e, scope), expr_text(e)),
+        };
+        let (right_ty, right_text) = match right {
+            ComparisonRight::Value(v) => (Ty::literal(v), String::new()),
+            ComparisonRight::EntityAttr(attr) => (self.entity_attr(attr), entity_attr_text(attr)),
+            ComparisonRight::Variable(name) => (
+                scope.get(name).copied().unwrap_or(Ty::Unknown),
+                name.clone(),
+            ),
+            ComparisonRight::VarAttr(_) => (Ty::Unknown, String::new()),
+            ComparisonRight::Expr(e) => (self.expr(e, scope), expr_text(e)),
+        };
+        let subject = if left_text.is_empty() {
+            right_text
+        } else {
+            left_text
+        };
+        if let Some(problem) = incompatibility(left_ty, op, right_ty) {
+            let subject = if subject.is_empty() {
+                "comparison".to_string()
+            } else {
+                format!("comparison on `{subject}`")
+            };
+            self.report(format!("{subject} {problem}"));
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr, scope: &Scope) -> Ty {
+        match expr {
+            Expr::Literal(v) => Ty::literal(v),
+            Expr::Variable(name) => match entity_path(name) {
+                Some((entity, path)) => self.attribute(entity, path, None),
+                None => scope.get(name).copied().unwrap_or(Ty::Unknown),
+            },
+            Expr::AttributeAccess {
+                variable,
+                attribute,
+            } => match entity_root(variable) {
+                Some((entity, base)) => self.attribute(entity, &join(base, attribute), None),
+                None => Ty::Unknown,
+            },
+            Expr::IndexedAccess {
+                variable,
+                attribute,
+                index,
+            } => match entity_root(variable) {
+                Some((entity, base)) => self.attribute(entity, &join(base, attribute), Some(index)),
+                None => Ty::Unknown,
+            },
+            Expr::MethodCall {
+                receiver,
+                method,
+                args,
+            } => {
+                let receiver = self.expr(receiver, scope);
+                for arg in args {
+                    self.expr(arg, scope);
+                }
+                method_result(method, receiver)
+            }
+            Expr::FunctionCall {
+                namespace,
+                function,
+                args,
+            } => {
+                for arg in args {
+                    self.expr(arg, scope);
+                }
+                if namespace.as_deref() == Some("rebac") {
+                    self.rebac_call(function, args);
+                }
+                function_result(namespace.as_deref(), function)
+            }
+            Expr::BinaryOp { left, right, .. } => {
+                self.expr(left, scope);
+                self.expr(right, scope);
+                Ty::of(AttributeType::Number)
+            }
+            Expr::Interpolation(parts) => {
+                for e in parts.iter().filter_map(InterpolationPart::expr) {
+                    self.expr(e, scope);
+                }
+                Ty::of(AttributeType::String)
+            }
+        }
+    }
+
+    fn entity_attr(&mut self, attr: &EntityAttr) -> Ty {
+        self.attribute(attr.entity, &attr.attribute, attr.index.as_ref())
+    }
+
+    /// Resolve `entity.path[index]`, reporting undeclared paths.
+    fn attribute(&mut self, entity: Entity, path: &str, index: Option<&Index>) -> Ty {
+        let ty = match entity {
+            Entity::Input => return Ty::Unknown,
+            Entity::Context => match &self.schema.context {
+                Some(context) => {
+                    let resolved = [("context", resolve(context, path))];
+                    self.resolved(entity, path, &resolved)
+                }
+                None => return Ty::Unknown,
+            },
+            Entity::User | Entity::Resource | Entity::Actor => {
+                let schema = self.schema;
+                let resolved: Vec<(&str, Resolved<'_>)> = schema
+                    .request
+                    .types_for(entity.as_str())
+                    .iter()
+                    .filter_map(|name| {
+                        let entity_type = schema.entity_type(name)?;
+                        Some((name.as_str(), resolve(&entity_type.attributes, path)))
+                    })
+                    .collect();
+                if resolved.is_empty() {
+                    return Ty::Unknown;
+                }
+                self.resolved(entity, path, &resolved)
+            }
+        };
+        match index {
+            Some(index) => index_into(ty, index),
+            None => ty,
+        }
+    }
+
+    /// Combine one path's resolution across the slot's bound types: found in
+    /// any type is fine (the entity may be of that type), missing from all
+    /// is an error.
+    fn resolved(&mut self, entity: Entity, path: &str, resolved: &[(&str, Resolved<'_>)]) -> Ty {
+        let mut found = Vec::new();
+        for (_, r) in resolved {
+            match r {
+                Resolved::Found(attr) => found.push(Ty::from_decl(attr.ty, attr.items)),
+                Resolved::Open => return Ty::Unknown,
+                Resolved::Missing { .. } => {}
+            }
+        }
+        if let Some(first) = found.first().copied() {
+            return if found.iter().all(|t| *t == first) {
+                first
+            } else {
+                Ty::Unknown
+            };
+        }
+        let mut owners = Vec::new();
+        let mut best: Option<(usize, &str)> = None;
+        for (owner, r) in resolved {
+            if let Resolved::Missing { segment, declared } = r {
+                owners.push(*owner);
+                for candidate in declared {
+                    let distance = edit_distance(segment, candidate);
+                    if distance <= segment.len().max(3) / 3 && best.is_none_or(|b| distance < b.0) {
+                        best = Some((distance, candidate));
+                    }
+                }
+            }
+        }
+        let owners = if entity == Entity::Context {
+            "in the request context".to_string()
+        } else {
+            format!("by {}", owners.join(" or "))
+        };
+        let hint = best.map_or(String::new(), |(_, name)| {
+            format!("; did you mean `{name}`?")
+        });
+        self.report(format!(
+            "unknown attribute `{}.{path}` (not declared {owners}{hint})",
+            entity.as_str()
+        ));
+        Ty::Unknown
+    }
+
+    /// `rebac::related|reachable|inherited|check(subject, relation, object,
+    /// ...)`: literal relation names must be declared (for `check`, stored or
+    /// rewritten), and the relation must admit the subject slot's types.
+    fn rebac_call(&mut self, function: &str, args: &[Expr]) {
+        let mut all_relations: BTreeMap<&str, Vec<&String>> = self
+            .schema
+            .entity_types
+            .values()
+            .flat_map(|t| &t.relations)
+            .fold(BTreeMap::new(), |mut acc, (name, subjects)| {
+                acc.entry(name.as_str()).or_default().extend(subjects);
+                acc
+            });
+        if function == "check" {
+            for entity_type in self.schema.entity_types.values() {
+                for name in entity_type.rewrites.keys() {
+                    all_relations.entry(name.as_str()).or_default();
+                }
+            }
+        }
+        if all_relations.is_empty() {
+            return;
+        }
+        let literal = |i: usize| match args.get(i) {
+            Some(Expr::Literal(Value::String(s))) => Some(s.as_str()),
+            _ => None,
+        };
+        let mut names = vec![literal(1)];
+        if matches!(function, "reachable" | "inherited") {
+            names.push(literal(3));
+        }
+        for name in names.into_iter().flatten() {
+            if !all_relations.contains_key(name) {
+                self.report(format!(
+                    "rebac::{function} uses relation \"{name}\", which no entity type declares"
+                ));
+            }
+        }
+
+        // The subject-type check only holds for direct edges.
+        let (Some(relation), "related") = (literal(1), function) else {
+            return;
+        };
+        let Some(Expr::Variable(subject)) = args.first() else {
+            return;
+        };
+        let Some(Expr::Variable(object)) = args.get(2) else {
+            return;
+        };
+        let object_types = self.schema.request.types_for(object);
+        let subject_types = self.schema.request.types_for(subject);
+        if object_types.is_empty() || subject_types.is_empty() {
+            return;
+        }
+        let admits_subject = object_types.iter().any(|o| {
+            self.schema
+                .entity_type(o)
+                .and_then(|t| t.relations.get(relation))
+                .is_some_and(|allowed| {
+                    allowed.is_empty() || subject_types.iter().any(|s| allowed.contains(s))
+                })
+        });
+        if !admits_subject {
+            self.report(format!(
+                "rebac::related({subject}, \"{relation}\", {object}) is always false: no {} \
+                 declares \"{relation}\" with a {} subject",
+                object_types.join(" or "),
+                subject_types.join(" or ")
+            ));
+        }
+    }
+}
+
+/// Why `left op right` can never hold (or, for `!=`, always holds), if it
+/// statically cannot.
+fn incompatibility(left: Ty, op: Operator, right: Ty) -> Option<String> {
+    match op {
+        Operator::Equal | Operator::NotEqual => {
+            let (l, r) = (left.equality_operand()?, right.equality_operand()?);
+            if l == r {
+                return None;
+            }
+            let outcome = if op == Operator::Equal {
+                "always false"
+            } else {
+                "always true"
+            };
+            Some(format!(
+                "compares {} with {} and is {outcome}",
+                l.as_str(),
+                r.as_str()
+            ))
+        }
+        Operator::GreaterThan
+        | Operator::LessThan
+        | Operator::GreaterEqual
+        | Operator::LessEqual => [left, right].into_iter().find_map(|side| match side {
+            Ty::Known { ty, .. } if ty != AttributeType::Number => Some(format!(
+                "orders a {} value and is always false",
+                ty.as_str()
+            )),
+            _ => None,
+        }),
+        // `x in xs` keeps no record of which side is the collection; only an
+        // array on one side with a mismatched scalar on the other is certain.
+        Operator::In => {
+            let (element, other) = match (left.element(), right.element()) {
+                (Ty::Unknown, Ty::Unknown) => return None,
+                (element, Ty::Unknown) => (element, right),
+                (Ty::Unknown, element) => (element, left),
+                _ => return None,
+            };
+            let (e, o) = (element.scalar()?, other.scalar()?);
+            (e != o).then(|| {
+                format!(
+                    "tests a {} for membership among {} elements and is always false",
+                    o.as_str(),
+                    e.as_str()
+                )
+            })
+        }
+    }
+}
+
+fn index_into(ty: Ty, index: &Index) -> Ty {
+    match index {
+        Index::Number(_) | Index::Wildcard => ty.element(),
+        Index::String(_) => Ty::Unknown,
+    }
+}
+
+fn method_result(method: &MethodName, receiver: Ty) -> Ty {
+    use MethodName as M;
+    let string = Ty::of(AttributeType::String);
+    match method {
+        M::Count | M::Sum => Ty::of(AttributeType::Number),
+        M::Lower | M::Upper | M::Trim | M::Replace => string,
+        M::Contains | M::Startswith | M::Endswith | M::Matches | M::Any | M::All | M::HasKey => {
+            Ty::of(AttributeType::Bool)
+        }
+        M::Split | M::FindAll | M::Keys => {
+            Ty::from_decl(AttributeType::Array, Some(AttributeType::String))
+        }
+        M::First | M::Last | M::Max | M::Min => receiver.element(),
+        M::Slice | M::Reverse | M::Sort | M::Unique => receiver,
+        _ => Ty::Unknown,
+    }
+}
+
+/// A builtin's result type, read off its documented signature; user
+/// functions are predicates.
+fn function_result(namespace: Option<&str>, function: &str) -> Ty {
+    let Some(builtin) = super::analysis::builtin_function(namespace, function) else {
+        return Ty::of(AttributeType::Bool);
+    };
+    match builtin.signature.rsplit("-> ").next() {
+        Some("bool") => Ty::of(AttributeType::Bool),
+        Some("string") => Ty::of(AttributeType::String),
+        Some("number" | "float" | "int") => Ty::of(AttributeType::Number),
+        Some("array") => Ty::of(AttributeType::Array),
+        Some("object") => Ty::of(AttributeType::Object),
+        _ => Ty::Unknown,
+    }
+}
+
+/// `user.a.b` (the parser's pseudo-variable form) -> (User, "a.b").
+fn entity_path(name: &str) -> Option<(Entity, &str)> {
+    match entity_root(name)? {
+        (entity, Some(path)) => Some((entity, path)),
+        (_, None) => None,
+    }
+}
+
+/// `user` -> (User, None); `user.a` -> (User, Some("a")).
+fn entity_root(name: &str) -> Option<(Entity, Option<&str>)> {
+    let (root, rest) = match name.split_once('.') {
+        Some((root, rest)) => (root, Some(rest)),
+        None => (name, None),
+    };
+    let entity = match root {
+        "user" => Entity::User,
+        "actor" => Entity::Actor,
+        "resource" => Entity::Resource,
+        "context" => Entity::Context,
+        "input" => Entity::Input,
+        _ => return None,
+    };
+    Some((entity, rest))
+}
+
+fn join(base: Option<&str>, attribute: &str) -> String {
+    match base {
+        Some(base) => format!("{base}.{attribute}"),
+        None => attribute.to_string(),
+    }
+}
+
+fn entity_attr_text(attr: &EntityAttr) -> String {
+    format!("{}.{}", attr.entity.as_str(), attr.attribute)
+}
+
+fn expr_text(expr: &Expr) -> String {
+    match expr {
+        Expr::Variable(name) => name.clone(),
+        Expr::AttributeAccess {
+            variable,
+            attribute,
+        }
+        | Expr::IndexedAccess {
+            variable,
+            attribute,
+            ..
+        } => format!("{variable}.{attribute}"),
+        Expr::MethodCall {
+            receiver, method, ..
+        } => format!("{}.{}()", expr_text(receiver), method.as_str()),
+        Expr::FunctionCall {
+            namespace,
+            function,
+            ..
+        } => match namespace {
+            Some(ns) => format!("{ns}::{function}()"),
+            None => format!("{function}()"),
+        },
+        Expr::Literal(_) | Expr::BinaryOp { .. } | Expr::Interpolation(_) => String::new(),
+    }
+}
+
+/// Edit distance counting an adjacent transposition as one edit (optimal
+/// string alignment), for "did you mean" suggestions.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let mut d = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
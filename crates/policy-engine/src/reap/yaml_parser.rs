@@ -120,6 +120,7 @@ impl YamlRule {
 
         Ok(Rule {
             message: None,
+            obligations: None,
             name: self.name,
             decision,
             condition,
@@ -0,0 +1,242 @@
+//! Infix-arithmetic differential tests.
+//!
+//! Contract: every arithmetic comparison shape the compiler lowers to
+//! `ArithCompare` must return the same decision and deciding rule name as
+//! the interpreter, over an attribute × document matrix covering the
+//! fail-closed cases: non-numeric and missing operands, JSON null, division
+//! and remainder by zero, integer overflow, float `%`, and int/float
+//! strictness under `==`. Plus an independent oracle for the quota shape
+//! and fallback pins for operands the compiled path does not read.
+
+use policy_engine::data::{DataLoader, DataStore};
+use policy_engine::reap::ReaperPolicy;
+use policy_engine::{PolicyAction, PolicyRequest};
+use proptest::prelude::*;
+use serde_json::json;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+fn store(attrs: &serde_json::Value) -> Arc<DataStore> {
+    let store = Arc::new(DataStore::new());
+    DataLoader::new((*store).clone())
+        .load_json(
+            &json!({"entities": [
+                {"id": "alice", "type": "user", "attributes": attrs},
+                {"id": "res", "type": "resource", "attributes": {"size": 4}}
+            ]})
+            .to_string(),
+        )
+        .expect("load");
+    store
+}
+
+fn request() -> PolicyRequest {
+    let mut context = HashMap::new();
+    context.insert("principal".to_string(), "alice".into());
+    PolicyRequest {
+        resource: "res".to_string(),
+        action: "write".to_string(),
+        context,
+        ..Default::default()
+    }
+}
+
+fn policy(cond: &str) -> String {
+    format!("policy quota {{\n    default: deny,\n    rule within {{ allow if {cond} }}\n}}")
+}
+
+/// Assert the policy COMPILES whole and both paths agree (decision + rule
+/// name) for (attrs, doc). Returns the shared decision.
+fn assert_equivalent(
+    cond: &str,
+    attrs: &serde_json::Value,
+    doc: Option<&serde_json::Value>,
+) -> PolicyAction {
+    let text = policy(cond);
+    let parsed = ReaperPolicy::from_str(&text).expect("parse");
+    let compiled = parsed
+        .clone()
+        .build(store(attrs))
+        .expect("arithmetic comparison must compile whole");
+    let ast = parsed.build_ast_evaluator(store(attrs));
+
+    let req = request();
+    let (c_dec, c_name) = compiled
+        .evaluate_with_input_named(&req, doc)
+        .expect("compiled evaluate");
+    let (a_dec, a_name) = ast
+        .evaluate_with_input_named(&req, doc)
+        .expect("ast evaluate");
+
+    assert_eq!(
+        c_dec, a_dec,
+        "decision diverged\nattrs: {attrs}\ndoc: {doc:?}\ncondition: {cond}"
+    );
+    assert_eq!(c_name, a_name, "rule name diverged: {cond}");
+    c_dec
+}
+
+#[test]
+fn quota_shape_matches_ast_and_pinned_decisions() {
+    let cond = "user.used + input.requested <= user.quota";
+    let attrs = json!({"used": 7, "quota": 10});
+    for (doc, expected) in [
+        (Some(json!({"requested": 3})), PolicyAction::Allow),
+        (Some(json!({"requested": 4})), PolicyAction::Deny),
+        (Some(json!({"requested": 2.5})), PolicyAction::Allow),
+        (Some(json!({"requested": "3"})), PolicyAction::Deny),
+        (Some(json!({"requested": null})), PolicyAction::Deny),
+        (Some(json!({"other": 1})), PolicyAction::Deny),
+        (None, PolicyAction::Deny),
+    ] {
+        assert_eq!(
+            assert_equivalent(cond, &attrs, doc.as_ref()),
+            expected,
+            "doc: {doc:?}"
+        );
+    }
+
+    // Non-numeric or missing attributes fail closed.
+    let doc = json!({"requested": 1});
+    for attrs in [
+        json!({"used": "7", "quota": 10}),
+        json!({"used": true, "quota": 10}),
+        json!({"used": [7], "quota": 10}),
+        json!({"quota": 10}),
+    ] {
+        assert_eq!(
+            assert_equivalent(cond, &attrs, Some(&doc)),
+            PolicyAction::Deny,
+            "attrs: {attrs}"
+        );
+    }
+}
+
+#[test]
+fn operator_truth_table_matches_ast() {
+    let attrs_matrix = [
+        json!({"a": 10, "b": 4}),
+        json!({"a": 10, "b": 5}),
+        json!({"a": 10, "b": 0}),
+        json!({"a": -7, "b": 3}),
+        json!({"a": 2.5, "b": 2}),
+        json!({"a": 9223372036854775807i64, "b": 1}),
+        json!({"a": "10", "b": 4}),
+        json!({"b": 4}),
+    ];
+    let conditions = [
+        "user.a + user.b == 14",
+        "user.a - user.b != 6",
+        "user.a * user.b >= 40",
+        "user.a / user.b == 2",
+        "user.a / user.b == 2.5",
+        "user.a / user.b > 2",
+        "user.a % user.b == 1",
+        "user.a % user.b != 0",
+        "user.a + 1 > user.a",
+        "user.a * 2 + user.b < 100",
+        "(user.a + user.b) * 2 <= 28",
+        "user.a - user.b - 1 == 5",
+        "resource.size * 2 == user.b + 4",
+        "actor.n + 1 != 0",
+    ];
+    for cond in conditions {
+        for attrs in &attrs_matrix {
+            assert_equivalent(cond, attrs, None);
+        }
+    }
+}
+
+#[test]
+fn fail_closed_cases_deny_on_both_paths() {
+    let attrs = json!({"a": 10, "zero": 0, "f": 2.5, "big": 9223372036854775807i64});
+    for cond in [
+        // Division / remainder by zero: the side is undefined, so even the
+        // negated comparisons do not match.
+        "user.a / user.zero == 0",
+        "user.a / user.zero != 0",
+        "user.a % user.zero != 1",
+        // Overflow is undefined rather than wrapping.
+        "user.big + 1 < 0",
+        "user.big + 1 != 0",
+        // `%` is integer-only.
+        "user.f % 2 != 7",
+        // Type-strict equality: an integer result never equals a float.
+        "user.a + 0 == 10.0",
+    ] {
+        assert_eq!(
+            assert_equivalent(cond, &attrs, None),
+            PolicyAction::Deny,
+            "{cond}"
+        );
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(200))]
+
+    /// Oracle: checked i64 arithmetic by hand.
+    #[test]
+    fn quota_check_agrees_with_checked_integer_oracle(
+        used in -1000i64..1000,
+        requested in -1000i64..1000,
+        quota in -1000i64..1000,
+    ) {
+        let attrs = json!({"used": used, "quota": quota});
+        let doc = json!({"requested": requested});
+        let expected = if used + requested <= quota {
+            PolicyAction::Allow
+        } else {
+            PolicyAction::Deny
+        };
+        prop_assert_eq!(
+            assert_equivalent("user.used + input.requested <= user.quota", &attrs, Some(&doc)),
+            expected
+        );
+    }
+}
+
+#[test]
+fn unsupported_operands_still_fall_back() {
+    // Operands the compiled path does not read the way the interpreter does
+    // must not compile whole; per-rule fallback serves them.
+    for (label, cond) in [
+        ("dotted entity attribute", "user.quota.limit - 1 > 0"),
+        ("method call operand", "user.tags.count() + 1 > 1"),
+        ("function call operand", "math::abs(user.a) + 1 > 1"),
+        ("bound variable", "n := user.a && n + 1 > 1"),
+    ] {
+        let parsed = ReaperPolicy::from_str(&policy(cond)).expect("parse");
+        assert!(
+            parsed.build(store(&json!({}))).is_err(),
+            "{label} unexpectedly compiled — extend the differential first"
+        );
+    }
+}
+
+#[test]
+fn arithmetic_assignment_runs_on_ast_fallback() {
+    // `x := a + b` binds the sum; an undefined sum binds null.
+    let text = r#"
+policy quota {
+    default: deny,
+    rule within {
+        allow if total := user.used + input.requested && total <= 10
+    }
+}
+"#;
+    let parsed = ReaperPolicy::from_str(text).expect("parse");
+    let ast = parsed.build_ast_evaluator(store(&json!({"used": 4})));
+    let req = request();
+    for (doc, expected) in [
+        (json!({"requested": 6}), PolicyAction::Allow),
+        (json!({"requested": 7}), PolicyAction::Deny),
+        (json!({"requested": "6"}), PolicyAction::Deny),
+    ] {
+        let (decision, _) = ast
+            .evaluate_with_input_named(&req, Some(&doc))
+            .expect("ast evaluate");
+        assert_eq!(decision, expected, "doc: {doc}");
+    }
+}
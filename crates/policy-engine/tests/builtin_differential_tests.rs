@@ -36,6 +36,17 @@ fn eval_both(
     attrs: serde_json::Value,
     action: &str,
     resource: &str,
+) -> (Option<PolicyAction>, Option<PolicyAction>) {
+    eval_both_with_context(policy_src, attrs, action, resource, &[])
+}
+
+/// [`eval_both`] with extra request-context entries (`context.*`).
+fn eval_both_with_context(
+    policy_src: &str,
+    attrs: serde_json::Value,
+    action: &str,
+    resource: &str,
+    extra_context: &[(&str, &str)],
 ) -> (Option<PolicyAction>, Option<PolicyAction>) {
     let data = serde_json::json!({
         "entities": [
@@ -53,7 +64,10 @@ fn eval_both(
 
     let mut context = std::collections::HashMap::new();
     // The evaluator resolves `user.*` from the principal named in the context.
-    context.insert("principal".to_string(), "alice".to_string());
+    context.insert("principal".to_string(), "alice".into());
+    for (k, v) in extra_context {
+        context.insert(k.to_string(), (*v).into());
+    }
     let request = PolicyRequest {
         resource: resource.to_string(),
         action: action.to_string(),
@@ -290,6 +304,171 @@ fn jwt_expiry_boundary_denies_expired_tokens_under_pinned_clock() {
     clock::clear_injected_now();
 }
 
+// ===========================================================================
+// net — oracle: hand masking over `std::net::Ipv4Addr` as a u32.
+// ===========================================================================
+
+fn v4_in(addr: std::net::Ipv4Addr, net: std::net::Ipv4Addr, prefix: u32) -> bool {
+    let mask = if prefix == 0 {
+        0
+    } else {
+        u32::MAX << (32 - prefix)
+    };
+    (u32::from(addr) & mask) == (u32::from(net) & mask)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(300))]
+
+    /// `net::cidr_contains("N/P", user.ip)` and `net::ip_in_any(user.ip,
+    /// ["N/P"])` on both evaluators must equal the u32 mask comparison. The
+    /// policy is inside the compiled subset, so the compiled path must exist.
+    #[test]
+    fn cidr_contains_agrees_with_u32_masking(
+        addr in any::<u32>(),
+        net in any::<u32>(),
+        prefix in 0u32..=32,
+        // Share a high octet half the time so containment is often true.
+        share in any::<bool>(),
+    ) {
+        let net = std::net::Ipv4Addr::from(net);
+        let addr = std::net::Ipv4Addr::from(if share {
+            (u32::from(net) & 0xff00_0000) | (addr & 0x00ff_ffff)
+        } else {
+            addr
+        });
+        let expected = if v4_in(addr, net, prefix) { PolicyAction::Allow } else { PolicyAction::Deny };
+
+        for body in [
+            format!(r#"net::cidr_contains("{net}/{prefix}", user.ip)"#),
+            format!(r#"net::ip_in_any(user.ip, ["{net}/{prefix}"])"#),
+        ] {
+            let policy_src = format!("policy p {{ default: deny, rule r {{ allow if {body} }} }}");
+            let (ast, compiled) = eval_both(
+                &policy_src,
+                serde_json::json!({ "ip": addr.to_string() }),
+                "read",
+                "res",
+            );
+            prop_assert!(compiled.is_some(), "{body} must compile");
+            assert_both_are(ast, compiled, expected.clone(), &format!("{body} ip={addr}"));
+        }
+    }
+}
+
+#[test]
+fn net_malformed_and_non_string_operands_deny_not_error() {
+    for (attrs, body) in [
+        (
+            serde_json::json!({ "ip": "10.0.0.256" }),
+            r#"net::cidr_contains("10.0.0.0/8", user.ip)"#,
+        ),
+        (
+            serde_json::json!({ "ip": 167772161 }),
+            r#"net::cidr_contains("10.0.0.0/8", user.ip)"#,
+        ),
+        (
+            serde_json::json!({}),
+            r#"net::cidr_contains("10.0.0.0/8", user.ip)"#,
+        ),
+        (
+            serde_json::json!({ "ip": "fe80::1%eth0" }),
+            r#"net::is_ip(user.ip)"#,
+        ),
+        (
+            serde_json::json!({ "ip": "10.0.0.1" }),
+            r#"net::is_ipv6(user.ip)"#,
+        ),
+        (
+            serde_json::json!({ "ip": "10.0.0.1" }),
+            r#"net::cidr_contains("2001:db8::/32", user.ip)"#,
+        ),
+        // A malformed range LITERAL keeps the rule on the AST path, where it
+        // is a non-match like any other malformed range.
+        (
+            serde_json::json!({ "ip": "10.0.0.1" }),
+            r#"net::cidr_contains("10.0.0.0/33", user.ip)"#,
+        ),
+    ] {
+        let policy_src = format!("policy p {{ default: deny, rule r {{ allow if {body} }} }}");
+        let (ast, compiled) = eval_both(&policy_src, attrs.clone(), "read", "res");
+        assert_both_are(
+            ast,
+            compiled,
+            PolicyAction::Deny,
+            &format!("{body} attrs={attrs}"),
+        );
+    }
+}
+
+#[test]
+fn net_attribute_ranges_and_overlap_agree() {
+    let attrs = serde_json::json!({
+        "ip": "::ffff:192.168.4.20",
+        "subnet": "192.168.4.0/26",
+        "ranges": ["not-a-range", 7, "10.0.0.0/8", "192.168.0.0/16"],
+        "home": "192.168.0.0/16",
+    });
+    for (body, expect) in [
+        (
+            r#"net::ip_in_any(user.ip, user.ranges)"#,
+            PolicyAction::Allow,
+        ),
+        (
+            r#"net::cidr_contains(user.home, user.ip)"#,
+            PolicyAction::Allow,
+        ),
+        (
+            r#"net::cidr_contains(user.subnet, user.home)"#,
+            PolicyAction::Deny,
+        ),
+        (
+            r#"net::cidr_overlaps(user.subnet, "192.168.4.32/27")"#,
+            PolicyAction::Allow,
+        ),
+        (
+            r#"net::cidr_overlaps("192.168.5.0/24", user.subnet)"#,
+            PolicyAction::Deny,
+        ),
+        (
+            r#"net::is_ipv4(user.ip) && net::is_cidr(user.subnet)"#,
+            PolicyAction::Allow,
+        ),
+        // ip_in_any needs a collection; a single-range string never matches.
+        (r#"net::ip_in_any(user.ip, user.home)"#, PolicyAction::Deny),
+    ] {
+        let policy_src = format!("policy p {{ default: deny, rule r {{ allow if {body} }} }}");
+        let (ast, compiled) = eval_both(&policy_src, attrs.clone(), "read", "res");
+        assert!(compiled.is_some(), "{body} must compile");
+        assert_both_are(ast, compiled, expect, body);
+    }
+}
+
+#[test]
+fn net_reads_request_context_on_both_paths() {
+    let policy_src = r#"policy p { default: deny,
+        rule r { allow if net::ip_in_any(context.source_ip, ["10.0.0.0/8", "2001:db8::/32"]) } }"#;
+    for (ip, expect) in [
+        ("10.20.30.40", PolicyAction::Allow),
+        ("2001:db8::7", PolicyAction::Allow),
+        ("11.0.0.1", PolicyAction::Deny),
+        ("10.0.0.1:443", PolicyAction::Deny),
+    ] {
+        let (ast, compiled) = eval_both_with_context(
+            policy_src,
+            serde_json::json!({}),
+            "read",
+            "res",
+            &[("source_ip", ip)],
+        );
+        assert!(compiled.is_some(), "context operand must compile");
+        assert_both_are(ast, compiled, expect, &format!("source_ip={ip}"));
+    }
+    // No source_ip on the request at all: fail closed.
+    let (ast, compiled) = eval_both(policy_src, serde_json::json!({}), "read", "res");
+    assert_both_are(ast, compiled, PolicyAction::Deny, "missing source_ip");
+}
+
 // ===========================================================================
 // Self-test: the oracle actually drives the parser/evaluators (teeth).
 // ===========================================================================
@@ -0,0 +1,301 @@
+//! `base64::`/`hex::`/`url::` codecs, `glob::match`, `semver::` and the
+//! `to_number`/`to_string` conversions.
+//!
+//! Contract: every shape the compiler lowers (bare boolean calls, calls
+//! compared with a literal, assignments) decides exactly like the
+//! interpreter, including the fail-closed cases — undecodable text, bad
+//! globs and versions, missing and wrong-typed arguments. Plus fallback pins
+//! for arguments the compiled path does not read.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use policy_engine::data::{DataLoader, DataStore};
+use policy_engine::reap::ReaperPolicy;
+use policy_engine::{PolicyAction, PolicyRequest};
+use serde_json::json;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+fn store() -> Arc<DataStore> {
+    let store = Arc::new(DataStore::new());
+    DataLoader::new((*store).clone())
+        .load_json(
+            &json!({"entities": [
+                {"id": "alice", "type": "user", "attributes": {
+                    "email": "alice@example.com",
+                    "token": "YWxpY2U=",
+                    "age": "42",
+                    "tags": ["b", "a"]
+                }},
+                {"id": "res", "type": "resource", "attributes": {"path": "repos/core/main"}}
+            ]})
+            .to_string(),
+        )
+        .expect("load");
+    store
+}
+
+fn request() -> PolicyRequest {
+    let mut context = HashMap::new();
+    context.insert("principal".to_string(), "alice".into());
+    context.insert("auth".to_string(), "Ym9iOnNlY3JldA".into());
+    context.insert("client".to_string(), "v1.4.2".into());
+    PolicyRequest {
+        resource: "res".to_string(),
+        action: "read".to_string(),
+        context,
+        ..Default::default()
+    }
+}
+
+fn policy(cond: &str) -> String {
+    format!("policy b {{\n    default: deny,\n    rule r {{ allow if {cond} }}\n}}")
+}
+
+/// Decide `cond` on both evaluators, asserting the policy compiles whole
+/// and the decisions agree.
+fn decide(cond: &str, doc: &serde_json::Value) -> bool {
+    let parsed = ReaperPolicy::from_str(&policy(cond)).expect("parse");
+    let compiled = parsed
+        .clone()
+        .build(store())
+        .unwrap_or_else(|e| panic!("{cond} must compile: {e:?}"));
+    let ast = parsed.build_ast_evaluator(store());
+    let (c, _) = compiled
+        .evaluate_with_input_named(&request(), Some(doc))
+        .expect("compiled evaluate");
+    let (a, _) = ast
+        .evaluate_with_input_named(&request(), Some(doc))
+        .expect("ast evaluate");
+    assert_eq!(c, a, "evaluators diverged on {cond} over {doc}");
+    c == PolicyAction::Allow
+}
+
+/// Decide `cond` on the interpreter only, asserting it does not compile.
+fn decide_ast(cond: &str, doc: &serde_json::Value) -> Result<bool, String> {
+    let parsed = ReaperPolicy::from_str(&policy(cond)).expect("parse");
+    assert!(
+        parsed.clone().build(store()).is_err(),
+        "expected AST fallback for {cond}"
+    );
+    parsed
+        .build_ast_evaluator(store())
+        .evaluate_with_input_named(&request(), Some(doc))
+        .map(|(d, _)| d == PolicyAction::Allow)
+        .map_err(|e| format!("{e:?}"))
+}
+
+#[test]
+fn codecs_round_trip_and_fail_closed() {
+    let doc = json!({
+        "b64": "aGVsbG8gd29ybGQ=",
+        "b64_unpadded": "aGVsbG8gd29ybGQ",
+        "binary": "/w==",
+        "garbage": "***",
+        "hex": "48656C6C6F",
+        "query": "a b&c=d/é",
+        "escaped": "a%20b%26c%3Dd%2F%C3%A9",
+        "bad_escape": "100%",
+        "signed_escape": "%+f",
+        "number": 7
+    });
+    let truths = [
+        r#"base64::decode(input.b64) == "hello world""#,
+        r#"base64::decode(input.b64_unpadded) == "hello world""#,
+        r#"base64::encode("hello world") == "aGVsbG8gd29ybGQ=""#,
+        r#"base64::url_encode("??>") == "Pz8-""#,
+        r#"base64::url_decode("Pz8-") == "??>""#,
+        r#"base64::decode(user.token) == "alice""#,
+        r#"base64::url_decode(context.auth) == "bob:secret""#,
+        r#"hex::decode(input.hex) == "Hello""#,
+        r#"hex::encode("Hello") == "48656c6c6f""#,
+        r#"url::encode(input.query) == "a%20b%26c%3Dd%2F%C3%A9""#,
+        r#"url::decode(input.escaped) == "a b&c=d/é""#,
+        r#"url::decode("a+b") == "a+b""#,
+        // Undecodable, non-UTF-8, malformed, wrong-typed or missing: null.
+        "base64::decode(input.binary) == null",
+        "base64::decode(input.garbage) == null",
+        "hex::decode(\"zz\") == null",
+        "url::decode(input.bad_escape) == null",
+        "url::decode(input.signed_escape) == null",
+        "base64::encode(input.number) == null",
+        "hex::encode(input.missing) == null",
+    ];
+    for cond in truths {
+        assert!(decide(cond, &doc), "{cond}");
+    }
+    // A null result satisfies no comparison but a presence check.
+    for cond in [
+        r#"base64::decode(input.garbage) != "x""#,
+        r#"base64::decode(input.garbage) == "x""#,
+        "base64::decode(input.b64) == null",
+    ] {
+        assert!(!decide(cond, &doc), "{cond}");
+    }
+}
+
+#[test]
+fn glob_match_honours_delimiters() {
+    let cases = [
+        ("*.example.com", "api.example.com", r#"["."]"#, true),
+        ("*.example.com", "a.b.example.com", r#"["."]"#, false),
+        ("**.example.com", "a.b.example.com", r#"["."]"#, true),
+        ("*.example.com", "a.b.example.com", "[]", false),
+        ("*.example.com", "a.b.example.com", "null", true),
+        ("repos/*/main", "repos/core/main", r#"["/"]"#, true),
+        ("repos/*/main", "repos/a/b/main", r#"["/"]"#, false),
+        ("repos/**", "repos/a/b/main", r#"["/"]"#, true),
+        ("file-?.txt", "file-1.txt", "[]", true),
+        ("file-?.txt", "file-10.txt", "[]", false),
+        ("[a-c]at", "bat", "null", true),
+        ("[!a-c]at", "bat", "null", false),
+        ("[!a-c]at", "rat", "null", true),
+        ("{api,www}.example.com", "www.example.com", r#"["."]"#, true),
+        (
+            "{api,*-cdn}.example.com",
+            "eu-cdn.example.com",
+            r#"["."]"#,
+            true,
+        ),
+        (
+            "{api,www}.example.com",
+            "web.example.com",
+            r#"["."]"#,
+            false,
+        ),
+        (r"\*.txt", "*.txt", "null", true),
+        (r"\*.txt", "a.txt", "null", false),
+        ("a.b", "aXb", "null", false),
+        ("(x|y)+", "(x|y)+", "null", true),
+        // Malformed patterns and delimiter lists never match.
+        ("[abc", "a", "null", false),
+        ("{a,b", "a", "null", false),
+        ("[]", "]", "null", false),
+        ("*", "a", r#"["ab"]"#, false),
+        ("*", "a", r#""/""#, false),
+    ];
+    for (pattern, path, delimiters, expected) in cases {
+        let doc = json!({ "path": path });
+        let pattern = pattern.replace('\\', "\\\\");
+        let cond = format!(r#"glob::match("{pattern}", input.path, {delimiters})"#);
+        assert_eq!(decide(&cond, &doc), expected, "{cond} on {path}");
+    }
+    // Pattern from request data: compiled per call, same answer.
+    let doc = json!({"pattern": "repos/*/main", "bad": "[x"});
+    assert!(decide(
+        r#"glob::match(input.pattern, resource.path, ["/"])"#,
+        &doc
+    ));
+    assert!(!decide(
+        r#"glob::match(input.bad, resource.path, ["/"])"#,
+        &doc
+    ));
+    assert!(decide(
+        r#"!glob::match(input.bad, resource.path, ["/"])"#,
+        &doc
+    ));
+}
+
+#[test]
+fn semver_orders_by_precedence() {
+    let doc = json!({"version": "2.1.0-rc.1", "bad": "2.1", "build": "1.0.0+abc"});
+    for (cond, expected) in [
+        (r#"semver::compare(input.version, "2.1.0") == -1"#, true),
+        (r#"semver::compare(input.version, "2.0.9") == 1"#, true),
+        (r#"semver::compare(context.client, "1.4.2") == 0"#, true),
+        (r#"semver::compare(input.build, "1.0.0+xyz") == 0"#, true),
+        (r#"semver::compare("1.10.0", "1.9.0") > 0"#, true),
+        (r#"semver::compare(input.bad, "2.1.0") == null"#, true),
+        (r#"semver::compare(input.bad, "2.1.0") < 1"#, false),
+        (r#"semver::satisfies(context.client, "^1.2")"#, true),
+        (r#"semver::satisfies(context.client, ">=1.0, <1.4")"#, false),
+        (r#"semver::satisfies(input.version, ">=2.0.0")"#, false),
+        (r#"semver::satisfies(input.version, ">=2.1.0-rc.0")"#, true),
+        (r#"semver::satisfies(input.bad, "*")"#, false),
+        (r#"semver::satisfies(context.client, "not a range")"#, false),
+    ] {
+        assert_eq!(decide(cond, &doc), expected, "{cond}");
+    }
+}
+
+#[test]
+fn conversions_are_explicit_and_type_strict() {
+    let doc = json!({
+        "replicas": "3",
+        "ratio": "2.5",
+        "count": 3,
+        "flag": true,
+        "word": "three",
+        "inf": "inf",
+        "owner": {"team": "core", "id": 7}
+    });
+    for (cond, expected) in [
+        ("to_number(input.replicas) == 3", true),
+        ("to_number(input.replicas) == 3.0", false),
+        ("to_number(input.ratio) > 2", true),
+        ("to_number(input.count) >= 3", true),
+        ("to_number(input.flag) == 1", true),
+        ("to_number(input.word) == null", true),
+        ("to_number(input.inf) == null", true),
+        ("to_number(input.word) < 100", false),
+        ("to_number(user.age) == 42", true),
+        (r#"to_string(input.count) == "3""#, true),
+        (r#"to_string(input.flag) == "true""#, true),
+        (r#"to_string(input.missing) == "null""#, true),
+        (
+            r#"to_string(input.owner) == "{\"id\":7,\"team\":\"core\"}""#,
+            true,
+        ),
+        (r#"to_string(user.tags) == "[\"b\",\"a\"]""#, true),
+        (r#"to_string(user.email) == "alice@example.com""#, true),
+    ] {
+        assert_eq!(decide(cond, &doc), expected, "{cond}");
+    }
+    // Without a conversion the string is not a number.
+    assert!(!decide("input.replicas == 3", &doc));
+}
+
+#[test]
+fn assignments_bind_results_and_null() {
+    let doc = json!({"auth": "YWRtaW46aHVudGVyMg==", "garbage": "***"});
+    assert!(decide(
+        r#"creds := base64::decode(input.auth) && creds.startswith("admin:")"#,
+        &doc
+    ));
+    // A null result binds and the assignment succeeds.
+    assert!(decide(
+        "creds := base64::decode(input.garbage) && creds == null",
+        &doc
+    ));
+}
+
+#[test]
+fn unread_arguments_fall_back_and_arity_errors_match() {
+    let doc = json!({"name": "logs", "host": "api.example.com"});
+    // Bound variables and nested calls run on the interpreter.
+    assert_eq!(
+        decide_ast(
+            r#"h := input.host && glob::match("*.example.com", h, ["."])"#,
+            &doc
+        ),
+        Ok(true)
+    );
+    assert_eq!(
+        decide_ast(
+            r#"base64::decode(base64::encode(input.name)) == "logs""#,
+            &doc
+        ),
+        Ok(true)
+    );
+    // A non-boolean builtin as a bare condition is an evaluation error.
+    assert!(decide_ast(r#"to_number("3")"#, &doc)
+        .unwrap_err()
+        .contains("must evaluate to boolean"));
+    // Wrong argument counts: rejected at build, an error on the interpreter.
+    let err = decide_ast(r#"glob::match("*", input.host)"#, &doc).unwrap_err();
+    assert!(
+        err.contains("glob::match() requires exactly 3 arguments"),
+        "{err}"
+    );
+}
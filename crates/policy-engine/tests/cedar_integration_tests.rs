@@ -27,7 +27,7 @@ fn test_cedar_basic_permit() {
     let evaluator = CedarPolicyEvaluator::new(policy.to_string()).unwrap();
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "alice".to_string());
+    context.insert("principal".to_string(), "alice".into());
 
     let request = PolicyRequest {
         resource: "document".to_string(),
@@ -56,7 +56,7 @@ fn test_cedar_basic_forbid() {
     let evaluator = CedarPolicyEvaluator::new(policy.to_string()).unwrap();
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "alice".to_string());
+    context.insert("principal".to_string(), "alice".into());
 
     let request = PolicyRequest {
         resource: "document".to_string(),
@@ -85,7 +85,7 @@ fn test_cedar_default_deny() {
     let evaluator = CedarPolicyEvaluator::new(policy.to_string()).unwrap();
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "guest".to_string());
+    context.insert("principal".to_string(), "guest".into());
 
     let request = PolicyRequest {
         resource: "document".to_string(),
@@ -121,8 +121,8 @@ fn test_cedar_when_string_condition() {
 
     // Should allow - department matches
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "alice".to_string());
-    context.insert("department".to_string(), "engineering".to_string());
+    context.insert("principal".to_string(), "alice".into());
+    context.insert("department".to_string(), "engineering".into());
 
     let request = PolicyRequest {
         resource: "code".to_string(),
@@ -138,8 +138,8 @@ fn test_cedar_when_string_condition() {
 
     // Should deny - department doesn't match
     let mut context2 = HashMap::new();
-    context2.insert("principal".to_string(), "bob".to_string());
-    context2.insert("department".to_string(), "marketing".to_string());
+    context2.insert("principal".to_string(), "bob".into());
+    context2.insert("department".to_string(), "marketing".into());
 
     let request2 = PolicyRequest {
         resource: "code".to_string(),
@@ -171,8 +171,8 @@ fn test_cedar_when_numeric_condition() {
 
     // Should allow - amount under limit
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "alice".to_string());
-    context.insert("amount".to_string(), "500".to_string());
+    context.insert("principal".to_string(), "alice".into());
+    context.insert("amount".to_string(), "500".into());
 
     let request = PolicyRequest {
         resource: "item".to_string(),
@@ -204,8 +204,8 @@ fn test_cedar_unless_clause() {
 
     // Should deny - user is suspended
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "alice".to_string());
-    context.insert("suspended".to_string(), "true".to_string());
+    context.insert("principal".to_string(), "alice".into());
+    context.insert("suspended".to_string(), "true".into());
 
     let request = PolicyRequest {
         resource: "document".to_string(),
@@ -221,8 +221,8 @@ fn test_cedar_unless_clause() {
 
     // Should allow - user is not suspended
     let mut context2 = HashMap::new();
-    context2.insert("principal".to_string(), "bob".to_string());
-    context2.insert("suspended".to_string(), "false".to_string());
+    context2.insert("principal".to_string(), "bob".into());
+    context2.insert("suspended".to_string(), "false".into());
 
     let request2 = PolicyRequest {
         resource: "document".to_string(),
@@ -262,7 +262,7 @@ fn test_cedar_multiple_permits() {
 
     // Admin can do anything
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "admin".to_string());
+    context.insert("principal".to_string(), "admin".into());
 
     let request = PolicyRequest {
         resource: "document".to_string(),
@@ -278,7 +278,7 @@ fn test_cedar_multiple_permits() {
 
     // Anyone can read
     let mut context2 = HashMap::new();
-    context2.insert("principal".to_string(), "guest".to_string());
+    context2.insert("principal".to_string(), "guest".into());
 
     let request2 = PolicyRequest {
         resource: "document".to_string(),
@@ -314,7 +314,7 @@ fn test_cedar_forbid_overrides_permit() {
 
     // Should allow - delete on non-protected resource
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "alice".to_string());
+    context.insert("principal".to_string(), "alice".into());
 
     let request = PolicyRequest {
         resource: "normal".to_string(),
@@ -330,7 +330,7 @@ fn test_cedar_forbid_overrides_permit() {
 
     // Should deny - delete on protected resource (forbid overrides)
     let mut context2 = HashMap::new();
-    context2.insert("principal".to_string(), "alice".to_string());
+    context2.insert("principal".to_string(), "alice".into());
 
     let request2 = PolicyRequest {
         resource: "protected".to_string(),
@@ -408,7 +408,7 @@ fn test_cedar_entity_type_matching() {
 
     // Should allow - exact match
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "alice".to_string());
+    context.insert("principal".to_string(), "alice".into());
 
     let request = PolicyRequest {
         resource: "secret-doc".to_string(),
@@ -424,7 +424,7 @@ fn test_cedar_entity_type_matching() {
 
     // Should deny - different user
     let mut context2 = HashMap::new();
-    context2.insert("principal".to_string(), "bob".to_string());
+    context2.insert("principal".to_string(), "bob".into());
 
     let request2 = PolicyRequest {
         resource: "secret-doc".to_string(),
@@ -459,7 +459,7 @@ fn test_cedar_policy_annotations() {
     let evaluator = CedarPolicyEvaluator::new(policy.to_string()).unwrap();
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "anyone".to_string());
+    context.insert("principal".to_string(), "anyone".into());
 
     let request = PolicyRequest {
         resource: "document".to_string(),
@@ -512,8 +512,8 @@ fn test_cedar_evaluation_performance() {
     let evaluator = CedarPolicyEvaluator::new(policy.to_string()).unwrap();
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "user".to_string());
-    context.insert("level".to_string(), "public".to_string());
+    context.insert("principal".to_string(), "user".into());
+    context.insert("level".to_string(), "public".into());
 
     let request = PolicyRequest {
         resource: "doc".to_string(),
@@ -571,9 +571,9 @@ fn test_cedar_abac_multi_attribute() {
 
     // Should allow - both attributes match
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "alice".to_string());
-    context.insert("clearance".to_string(), "secret".to_string());
-    context.insert("department".to_string(), "research".to_string());
+    context.insert("principal".to_string(), "alice".into());
+    context.insert("clearance".to_string(), "secret".into());
+    context.insert("department".to_string(), "research".into());
 
     let request = PolicyRequest {
         resource: "classified".to_string(),
@@ -589,9 +589,9 @@ fn test_cedar_abac_multi_attribute() {
 
     // Should deny - clearance doesn't match
     let mut context2 = HashMap::new();
-    context2.insert("principal".to_string(), "bob".to_string());
-    context2.insert("clearance".to_string(), "public".to_string());
-    context2.insert("department".to_string(), "research".to_string());
+    context2.insert("principal".to_string(), "bob".into());
+    context2.insert("clearance".to_string(), "public".into());
+    context2.insert("department".to_string(), "research".into());
 
     let request2 = PolicyRequest {
         resource: "classified".to_string(),
@@ -605,3 +605,42 @@ fn test_cedar_abac_multi_attribute() {
     assert!(result2.is_ok());
     assert_eq!(result2.unwrap(), PolicyAction::Deny);
 }
+
+/// Test typed context values: longs, bools and sets reach Cedar as such
+#[test]
+fn test_cedar_typed_context_values() {
+    let policy = r#"
+        permit(
+            principal,
+            action == Action::"pay",
+            resource
+        ) when {
+            context.amount > 1000 && context.verified && context.tags.contains("expedite")
+        };
+    "#;
+
+    let evaluator = CedarPolicyEvaluator::new(policy.to_string()).unwrap();
+
+    let request = |amount: serde_json::Value| {
+        let mut context = HashMap::new();
+        context.insert("principal".to_string(), "alice".into());
+        context.insert("amount".to_string(), amount);
+        context.insert("verified".to_string(), true.into());
+        context.insert("tags".to_string(), serde_json::json!(["expedite"]));
+        PolicyRequest {
+            resource: "txn".to_string(),
+            action: "pay".to_string(),
+            context,
+            ..Default::default()
+        }
+    };
+
+    assert_eq!(
+        evaluator.evaluate(&request(1500.into())).unwrap(),
+        PolicyAction::Allow
+    );
+    assert_eq!(
+        evaluator.evaluate(&request(500.into())).unwrap(),
+        PolicyAction::Deny
+    );
+}
@@ -132,7 +132,10 @@ pub fn run_comparison_reaper_only(
     for test_case in &suite.test_cases {
         // Build request with principal in context
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), test_case.input.principal.clone());
+        context.insert(
+            "principal".to_string(),
+            test_case.input.principal.clone().into(),
+        );
 
         let request = PolicyRequest {
             resource: test_case.input.resource.clone(),
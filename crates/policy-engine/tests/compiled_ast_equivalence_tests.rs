@@ -94,7 +94,7 @@ fn store_with_data() -> Arc<DataStore> {
 
 fn request(principal: &str, resource: &str) -> PolicyRequest {
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), principal.to_string());
+    context.insert("principal".to_string(), principal.into());
     PolicyRequest {
         resource: resource.to_string(),
         action: "read".to_string(),
@@ -1064,7 +1064,7 @@ fn taint_request(
 ) -> PolicyRequest {
     let mut req = request(principal, resource);
     for (k, v) in context {
-        req.context.insert(k.to_string(), v.to_string());
+        req.context.insert(k.to_string(), (*v).into());
     }
     req.context_provenance =
         provenance.map(|p| p.iter().map(|(k, t)| (k.to_string(), *t)).collect());
@@ -82,7 +82,7 @@ fn run_policy_test(
 
     for (principal, action, resource, expected) in scenarios {
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), principal.to_string());
+        context.insert("principal".to_string(), principal.into());
 
         let request = PolicyRequest {
             resource: resource.to_string(),
@@ -179,7 +179,7 @@ fn test_string_email_contains() {
     let ast_evaluator = policy.clone().build_ast_evaluator(Arc::clone(&store));
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "str_user3".to_string());
+    context.insert("principal".to_string(), "str_user3".into());
 
     let request = PolicyRequest {
         resource: "str_docs".to_string(),
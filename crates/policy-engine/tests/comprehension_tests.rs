@@ -172,7 +172,7 @@ fn test_set_comprehension_basic() {
     let evaluator = policy.build_ast_evaluator(store);
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "alice".to_string());
+    context.insert("principal".to_string(), "alice".into());
 
     let request = PolicyRequest {
         resource: "prod_db".to_string(),
@@ -210,7 +210,7 @@ fn test_set_comprehension_with_filters() {
 
     // Diana meets criteria: developer, 7 years, active
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "alice".to_string());
+    context.insert("principal".to_string(), "alice".into());
 
     let request = PolicyRequest {
         resource: "prod_db".to_string(),
@@ -242,7 +242,7 @@ fn test_array_comprehension_preserves_order() {
     let evaluator = policy.build_ast_evaluator(store);
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "alice".to_string());
+    context.insert("principal".to_string(), "alice".into());
 
     let request = PolicyRequest {
         resource: "prod_db".to_string(),
@@ -278,7 +278,7 @@ fn test_array_comprehension_with_multiple_filters() {
     let evaluator = policy.build_ast_evaluator(store);
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "alice".to_string());
+    context.insert("principal".to_string(), "alice".into());
 
     let request = PolicyRequest {
         resource: "prod_db".to_string(),
@@ -310,7 +310,7 @@ fn test_object_comprehension_creates_map() {
     let evaluator = policy.build_ast_evaluator(store);
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "alice".to_string());
+    context.insert("principal".to_string(), "alice".into());
 
     let request = PolicyRequest {
         resource: "prod_db".to_string(),
@@ -345,7 +345,7 @@ fn test_object_comprehension_with_filter() {
     let evaluator = policy.build_ast_evaluator(store);
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "alice".to_string());
+    context.insert("principal".to_string(), "alice".into());
 
     let request = PolicyRequest {
         resource: "prod_db".to_string(),
@@ -395,7 +395,7 @@ fn test_empty_collection() {
     let evaluator = policy.build_ast_evaluator(store);
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "test_user".to_string());
+    context.insert("principal".to_string(), "test_user".into());
 
     let request = PolicyRequest {
         resource: "resource1".to_string(),
@@ -458,7 +458,7 @@ fn test_comprehension_deduplication() {
     let evaluator = policy.build_ast_evaluator(store);
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "test_user".to_string());
+    context.insert("principal".to_string(), "test_user".into());
 
     let request = PolicyRequest {
         resource: "resource1".to_string(),
@@ -490,7 +490,7 @@ fn assert_both_evaluators(
 
     let policy = ReaperPolicy::from_str(policy_text).unwrap();
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "test_user".to_string());
+    context.insert("principal".to_string(), "test_user".into());
     let request = PolicyRequest {
         resource: "resource1".to_string(),
         action: "read".to_string(),
@@ -43,8 +43,8 @@ fn store() -> Arc<DataStore> {
 /// attribute — the high-cardinality value the policy compares.
 fn request(token: &str) -> PolicyRequest {
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "alice".to_string());
-    context.insert("token".to_string(), token.to_string());
+    context.insert("principal".to_string(), "alice".into());
+    context.insert("token".to_string(), token.into());
     PolicyRequest {
         resource: "doc".to_string(),
         action: "read".to_string(),
@@ -0,0 +1,262 @@
+//! Policy test coverage (`reap::coverage`): rules matched, branch outcomes
+//! and func calls accumulated from evaluation traces, on every evaluator
+//! shape, and the LCOV / Cobertura / JSON renderings CI consumes.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use policy_engine::data::{DataLoader, DataStore};
+use policy_engine::reap::coverage::{CoverageReport, PolicyCoverage};
+use policy_engine::reap::ReaperPolicy;
+use policy_engine::{PolicyEvaluator, PolicyRequest};
+use serde_json::json;
+
+const POLICY: &str = r#"policy docs {
+    default: deny,
+
+    func senior(level) := level >= 5,
+
+    func unused_helper() := user.level == 100,
+
+    rule block_suspended {
+        deny if user.status == "suspended"
+    }
+
+    rule engineers {
+        allow if {
+            user.department == "eng" &&
+            senior(user.level)
+        }
+    }
+
+    rule reviewers {
+        allow if "reviewer" in user.roles || user.level > 8
+    }
+}
+"#;
+
+fn store() -> Arc<DataStore> {
+    let s = Arc::new(DataStore::new());
+    let data = json!({
+        "entities": [
+            {"id": "alice", "type": "user",
+             "attributes": {"status": "active", "department": "eng", "level": 5, "roles": []}},
+            {"id": "bob", "type": "user",
+             "attributes": {"status": "active", "department": "sales", "level": 2, "roles": ["reviewer"]}},
+            {"id": "dave", "type": "user",
+             "attributes": {"status": "suspended", "department": "eng", "level": 9, "roles": []}},
+            {"id": "doc1", "type": "resource", "attributes": {}}
+        ]
+    });
+    DataLoader::new((*s).clone())
+        .load_json(&data.to_string())
+        .unwrap();
+    s
+}
+
+fn req(principal: &str) -> PolicyRequest {
+    let mut context = HashMap::new();
+    context.insert("principal".to_string(), principal.into());
+    PolicyRequest {
+        resource: "doc1".to_string(),
+        action: "read".to_string(),
+        context,
+        ..Default::default()
+    }
+}
+
+fn covered(evaluator: &dyn PolicyEvaluator, principals: &[&str]) -> PolicyCoverage {
+    let policy: ReaperPolicy = POLICY.parse().unwrap();
+    let mut coverage = PolicyCoverage::new(&policy).with_source(POLICY);
+    for principal in principals {
+        coverage.record(&evaluator.explain(&req(principal)).unwrap());
+    }
+    coverage
+}
+
+/// A rule's name, evaluated and matched counts, and (true, false) counts per
+/// branch.
+type RuleCounts = (String, usize, usize, Vec<(usize, usize)>);
+
+fn counts(coverage: &PolicyCoverage) -> Vec<RuleCounts> {
+    coverage
+        .rules
+        .iter()
+        .map(|r| {
+            let branches = r
+                .branches
+                .iter()
+                .map(|b| (b.true_count, b.false_count))
+                .collect();
+            (r.name.clone(), r.evaluated, r.matched, branches)
+        })
+        .collect()
+}
+
+#[test]
+fn rules_branches_and_funcs_are_counted() {
+    let policy: ReaperPolicy = POLICY.parse().unwrap();
+    let evaluator = policy.build_preferred(store()).unwrap();
+    let coverage = covered(evaluator.as_ref(), &["alice", "bob", "dave"]);
+
+    assert_eq!(coverage.evaluations, 3);
+    assert_eq!(
+        counts(&coverage),
+        vec![
+            // Every request reaches the deny rule; only dave matches it.
+            ("block_suspended".to_string(), 3, 1, vec![(1, 2)]),
+            // dave was decided before reaching the allow rules; bob's
+            // department failed, so `senior(...)` was skipped for him.
+            ("engineers".to_string(), 2, 1, vec![(1, 1), (1, 0)]),
+            // Only bob got here: `||` stopped after the first operand.
+            ("reviewers".to_string(), 1, 1, vec![(1, 0), (0, 0)]),
+        ]
+    );
+
+    let senior = &coverage.functions[0];
+    assert_eq!(
+        (senior.name.as_str(), senior.calls, senior.true_count),
+        ("senior", 1, 1)
+    );
+    assert_eq!(coverage.functions[1].calls, 0);
+
+    let summary = coverage.summary();
+    assert_eq!((summary.rules, summary.rules_matched), (3, 3));
+    assert_eq!(
+        (summary.branch_outcomes, summary.branch_outcomes_covered),
+        (10, 6)
+    );
+    assert_eq!((summary.functions, summary.functions_called), (2, 1));
+    assert_eq!(summary.min_percent(), 50.0);
+}
+
+/// Coverage comes from the interpreter replay, so the compiled evaluator
+/// reports the same numbers as the interpreter itself.
+#[test]
+fn every_evaluator_shape_reports_the_same_coverage() {
+    let principals = ["alice", "bob", "dave"];
+    let compiled = POLICY
+        .parse::<ReaperPolicy>()
+        .unwrap()
+        .build(store())
+        .unwrap();
+    let ast = POLICY
+        .parse::<ReaperPolicy>()
+        .unwrap()
+        .build_ast_evaluator(store());
+    let expected = covered(&compiled, &principals);
+    assert_eq!(covered(&ast, &principals), expected);
+}
+
+#[test]
+fn source_lines_are_attached_by_declaration() {
+    let policy: ReaperPolicy = POLICY.parse().unwrap();
+    let coverage = PolicyCoverage::new(&policy).with_source(POLICY);
+    let lines: Vec<Option<usize>> = coverage.rules.iter().map(|r| r.line).collect();
+    assert_eq!(lines, vec![Some(8), Some(12), Some(19)]);
+    assert_eq!(coverage.functions[0].line, Some(4));
+
+    // Without source (YAML / JSON policies) there are no lines.
+    assert!(PolicyCoverage::new(&policy)
+        .rules
+        .iter()
+        .all(|r| r.line.is_none()));
+}
+
+fn report(principals: &[&str]) -> CoverageReport {
+    let policy: ReaperPolicy = POLICY.parse().unwrap();
+    let evaluator = POLICY
+        .parse::<ReaperPolicy>()
+        .unwrap()
+        .build_preferred(store())
+        .unwrap();
+    let mut report = CoverageReport::default();
+    for principal in principals {
+        let trace = evaluator.explain(&req(principal)).unwrap();
+        report
+            .file("policies/docs.reap", &policy, Some(POLICY))
+            .record(&trace);
+    }
+    report
+}
+
+#[test]
+fn lcov_lists_rules_as_lines_and_branch_pairs() {
+    let lcov = report(&["alice", "dave"]).to_lcov();
+    let lines: Vec<&str> = lcov.lines().collect();
+    assert_eq!(lines[0], "TN:");
+    assert_eq!(lines[1], "SF:policies/docs.reap");
+    assert!(lines.contains(&"FN:4,senior"));
+    assert!(lines.contains(&"FNDA:1,senior"));
+    assert!(lines.contains(&"FNDA:0,unused_helper"));
+    assert!(lines.contains(&"FNF:2") && lines.contains(&"FNH:1"));
+    // block_suspended: true once (dave), false once (alice).
+    assert!(lines.contains(&"BRDA:8,0,0,1") && lines.contains(&"BRDA:8,0,1,1"));
+    // reviewers was never reached: its branches were not executed.
+    assert!(lines.contains(&"BRDA:19,2,0,-") && lines.contains(&"BRDA:19,2,3,-"));
+    assert!(lines.contains(&"DA:12,1"));
+    assert!(lines.contains(&"DA:19,0"));
+    assert!(lines.contains(&"LF:3") && lines.contains(&"LH:2"));
+    assert_eq!(lines.last(), Some(&"end_of_record"));
+}
+
+#[test]
+fn cobertura_reports_rates_and_condition_coverage() {
+    let xml = report(&["alice", "dave"]).to_cobertura();
+    assert!(xml.starts_with("<?xml"));
+    assert!(
+        xml.contains(r#"lines-covered="2" lines-valid="3""#),
+        "{xml}"
+    );
+    assert!(xml.contains(r#"filename="policies/docs.reap""#));
+    assert!(xml
+        .contains(r#"<line number="8" hits="1" branch="true" condition-coverage="100% (2/2)"/>"#));
+    assert!(
+        xml.contains(r#"<line number="19" hits="0" branch="true" condition-coverage="0% (0/4)"/>"#)
+    );
+    assert!(xml.contains(r#"<method name="senior""#));
+    assert!(xml.trim_end().ends_with("</coverage>"));
+}
+
+#[test]
+fn json_and_text_name_what_was_never_exercised() {
+    let report = report(&["alice"]);
+    let json = report.to_json();
+    assert_eq!(json["files"][0]["path"], "policies/docs.reap");
+    assert_eq!(json["files"][0]["rules"][0]["effect"], "deny");
+    assert_eq!(json["summary"]["rules_matched"], 1);
+
+    let text = report.to_text();
+    assert!(
+        text.contains("never matched: rule block_suspended, line 8"),
+        "{text}"
+    );
+    assert!(
+        text.contains("never true: user.status == \"suspended\""),
+        "{text}"
+    );
+    assert!(text.contains("never evaluated: user.level > 8"), "{text}");
+    assert!(
+        text.contains("never called: func unused_helper, line 6"),
+        "{text}"
+    );
+}
+
+#[test]
+fn traces_list_func_calls() {
+    let evaluator = POLICY
+        .parse::<ReaperPolicy>()
+        .unwrap()
+        .build_preferred(store())
+        .unwrap();
+    let trace = evaluator.explain(&req("alice")).unwrap();
+    assert_eq!(trace.calls.len(), 1);
+    assert_eq!(trace.calls[0].function, "senior");
+    assert!(trace.calls[0].result);
+    // No calls, no `calls` key: explain output is unchanged for func-free
+    // evaluations.
+    let trace = evaluator.explain(&req("dave")).unwrap();
+    assert!(serde_json::to_value(&trace).unwrap().get("calls").is_none());
+}
@@ -56,7 +56,7 @@ fn test_type_checking_bdd_scenario() {
     eprintln!("Testing AST evaluator first...");
 
     let mut context_ast = HashMap::new();
-    context_ast.insert("principal".to_string(), "user_string_field".to_string());
+    context_ast.insert("principal".to_string(), "user_string_field".into());
     let request_ast = PolicyRequest {
         resource: "string_check".to_string(),
         action: "validate".to_string(),
@@ -80,7 +80,7 @@ fn test_type_checking_bdd_scenario() {
     };
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "user_string_field".to_string());
+    context.insert("principal".to_string(), "user_string_field".into());
 
     let request = PolicyRequest {
         resource: "string_check".to_string(),
@@ -127,7 +127,7 @@ policy test_string {
     let ast_evaluator = policy.build_ast_evaluator(store_arc.clone());
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "user_string".to_string());
+    context.insert("principal".to_string(), "user_string".into());
 
     let request = PolicyRequest {
         resource: "string_check".to_string(),
@@ -186,7 +186,7 @@ policy test_two_level {
     let ast_evaluator = policy.build_ast_evaluator(store_arc.clone());
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "user_json".to_string());
+    context.insert("principal".to_string(), "user_json".into());
 
     let request = PolicyRequest {
         resource: "api_endpoint".to_string(),
@@ -252,7 +252,7 @@ policy test_nested {
     let ast_evaluator = policy.build_ast_evaluator(store_arc.clone());
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "user_obj".to_string());
+    context.insert("principal".to_string(), "user_obj".into());
 
     let request = PolicyRequest {
         resource: "object_check".to_string(),
@@ -302,7 +302,7 @@ policy test_nested {
         Ok(compiled) => {
             eprintln!("Compiled successfully");
             let mut context = HashMap::new();
-            context.insert("principal".to_string(), "user_obj".to_string());
+            context.insert("principal".to_string(), "user_obj".into());
 
             let request = PolicyRequest {
                 resource: "object_check".to_string(),
@@ -362,7 +362,7 @@ policy test_null_safety {
     eprintln!("Testing AST evaluator...");
     let ast_eval = policy.clone().build_ast_evaluator(store_arc.clone());
     let mut context_ast = HashMap::new();
-    context_ast.insert("principal".to_string(), "user_non_null".to_string());
+    context_ast.insert("principal".to_string(), "user_non_null".into());
     let request_ast = PolicyRequest {
         resource: "nullable_field".to_string(),
         action: "access".to_string(),
@@ -379,7 +379,7 @@ policy test_null_safety {
         Ok(compiled) => {
             eprintln!("Compiled successfully");
             let mut context = HashMap::new();
-            context.insert("principal".to_string(), "user_non_null".to_string());
+            context.insert("principal".to_string(), "user_non_null".into());
 
             let request = PolicyRequest {
                 resource: "nullable_field".to_string(),
@@ -443,7 +443,7 @@ policy test_not_empty {
     // Test AST first
     let ast_eval = policy.clone().build_ast_evaluator(store_arc.clone());
     let mut context_ast = HashMap::new();
-    context_ast.insert("principal".to_string(), "test_user".to_string());
+    context_ast.insert("principal".to_string(), "test_user".into());
     let request_ast = PolicyRequest {
         resource: "test_resource".to_string(),
         action: "test".to_string(),
@@ -458,7 +458,7 @@ policy test_not_empty {
     match policy.clone().build(store_arc.clone()) {
         Ok(compiled) => {
             let mut context = HashMap::new();
-            context.insert("principal".to_string(), "test_user".to_string());
+            context.insert("principal".to_string(), "test_user".into());
             let request = PolicyRequest {
                 resource: "test_resource".to_string(),
                 action: "test".to_string(),
@@ -514,7 +514,7 @@ policy test_assignment {
     // Test AST first
     let ast_eval = policy.clone().build_ast_evaluator(store_arc.clone());
     let mut context_ast = HashMap::new();
-    context_ast.insert("principal".to_string(), "test_user".to_string());
+    context_ast.insert("principal".to_string(), "test_user".into());
     let request_ast = PolicyRequest {
         resource: "test_resource".to_string(),
         action: "test".to_string(),
@@ -529,7 +529,7 @@ policy test_assignment {
     match policy.clone().build(store_arc.clone()) {
         Ok(compiled) => {
             let mut context = HashMap::new();
-            context.insert("principal".to_string(), "test_user".to_string());
+            context.insert("principal".to_string(), "test_user".into());
             let request = PolicyRequest {
                 resource: "test_resource".to_string(),
                 action: "test".to_string(),
@@ -578,7 +578,7 @@ fn test_bdd_object_validation() {
     // Test AST first
     let ast_eval = policy.clone().build_ast_evaluator(store_arc.clone());
     let mut context_ast = HashMap::new();
-    context_ast.insert("principal".to_string(), "user_object_value".to_string());
+    context_ast.insert("principal".to_string(), "user_object_value".into());
     let request_ast = PolicyRequest {
         resource: "object_check".to_string(),
         action: "validate".to_string(),
@@ -594,7 +594,7 @@ fn test_bdd_object_validation() {
         Ok(compiled) => {
             eprintln!("Compiled successfully");
             let mut context = HashMap::new();
-            context.insert("principal".to_string(), "user_object_value".to_string());
+            context.insert("principal".to_string(), "user_object_value".into());
             let request = PolicyRequest {
                 resource: "object_check".to_string(),
                 action: "validate".to_string(),
@@ -670,7 +670,7 @@ policy test_first {
     let compiled = result.expect("should compile successfully");
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "user_priority_tasks".to_string());
+    context.insert("principal".to_string(), "user_priority_tasks".into());
 
     let request = PolicyRequest {
         resource: "task_queue".to_string(),
@@ -746,7 +746,7 @@ policy test_first {
     let ast_evaluator = policy.build_ast_evaluator(store_arc.clone());
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "user_priority_tasks".to_string());
+    context.insert("principal".to_string(), "user_priority_tasks".into());
 
     let request = PolicyRequest {
         resource: "task_queue".to_string(),
@@ -210,7 +210,7 @@ fn decisions(store: Arc<DataStore>) -> Vec<String> {
                 let request = PolicyRequest {
                     resource: doc.to_string(),
                     action: action.to_string(),
-                    context: HashMap::from([("principal".to_string(), user.to_string())]),
+                    context: HashMap::from([("principal".to_string(), (*user).into())]),
 
                     ..Default::default()
                 };
@@ -0,0 +1,216 @@
+//! Semantic policy diff (`reap::diff`): which request classes change
+//! outcome between two versions of a policy.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use policy_engine::reap::diff::{diff_policies, diff_sets, DiffStatus, PolicyDiff};
+use policy_engine::reap::{Policy, ReapParser};
+
+fn parse(source: &str) -> Policy {
+    ReapParser::parse(source).unwrap()
+}
+
+fn diff(old: &str, new: &str) -> PolicyDiff {
+    diff_policies(&parse(old), &parse(new))
+}
+
+/// Each change as printed.
+fn lines(diff: &PolicyDiff) -> Vec<String> {
+    diff.changes.iter().map(ToString::to_string).collect()
+}
+
+const BASE: &str = r#"policy docs {
+    default: deny,
+
+    rule suspended {
+        deny if user.status == "suspended"
+    }
+
+    rule employees {
+        allow if user.role == "employee"
+    }
+}
+"#;
+
+#[test]
+fn reordered_renamed_and_reformatted_rules_are_unchanged() {
+    let same = r#"policy docs {
+    default: deny,
+    rule staff { allow if user.role == "employee" }
+    rule blocked { deny if (user.status == "suspended") }
+}
+"#;
+    assert_eq!(diff(BASE, same).status, DiffStatus::Unchanged);
+    assert_eq!(diff(BASE, same).changes, Vec::new());
+}
+
+#[test]
+fn new_allow_rule_grants_a_request_class() {
+    let new = r#"policy docs {
+    default: deny,
+
+    rule suspended {
+        deny if user.status == "suspended"
+    }
+
+    rule employees {
+        allow if user.role == "employee"
+    }
+
+    rule contractors_internal {
+        allow if user.role == "contractor" && resource.classification == "internal"
+    }
+}
+"#;
+    let d = diff(BASE, new);
+    assert_eq!(d.status, DiffStatus::Changed);
+    assert_eq!(
+        lines(&d),
+        vec![
+            r#"deny → allow when !(user.status == "suspended") && user.role == "contractor" && resource.classification == "internal""#
+        ]
+    );
+    assert_eq!(d.changes[0].from, policy_engine::reap::Decision::Deny);
+    assert!(!d.truncated);
+}
+
+#[test]
+fn narrowed_allow_revokes_and_pinned_values_drop_implied_literals() {
+    let old = r#"policy docs {
+    default: deny,
+    rule staff { allow if user.role == "employee" || user.role == "admin" }
+}
+"#;
+    let new = r#"policy docs {
+    default: deny,
+    rule staff { allow if user.role == "employee" }
+}
+"#;
+    // `user.role == "admin"` already rules out `user.role == "employee"`.
+    assert_eq!(
+        lines(&diff(old, new)),
+        vec![r#"allow → deny when user.role == "admin""#]
+    );
+    assert_eq!(
+        lines(&diff(new, old)),
+        vec![r#"deny → allow when user.role == "admin""#]
+    );
+}
+
+#[test]
+fn new_deny_and_threshold_change_are_both_reported() {
+    let old = r#"policy docs {
+    default: deny,
+    rule seniors { allow if user.level >= 5 }
+}
+"#;
+    let new = r#"policy docs {
+    default: deny,
+    rule locked { deny if user.locked == true }
+    rule seniors { allow if user.level >= 3 }
+}
+"#;
+    assert_eq!(
+        lines(&diff(old, new)),
+        vec![
+            "deny → allow when !(user.locked == true) && user.level >= 3 && !(user.level >= 5)",
+            "allow → deny when user.locked == true && user.level >= 5",
+        ]
+    );
+}
+
+#[test]
+fn default_flip_without_rules_changes_every_request() {
+    let old = "policy open {\n    default: deny,\n}\n";
+    let new = "policy open {\n    default: allow,\n}\n";
+    assert_eq!(
+        lines(&diff(old, new)),
+        vec!["deny → allow for every request"]
+    );
+}
+
+#[test]
+fn changed_func_body_is_a_different_condition() {
+    let old = r#"policy docs {
+    default: deny,
+    func senior(level) := level >= 5,
+    rule seniors { allow if senior(user.level) }
+}
+"#;
+    let new = r#"policy docs {
+    default: deny,
+    func senior(level) := level >= 7,
+    rule seniors { allow if senior(user.level) }
+}
+"#;
+    let d = diff(old, new);
+    assert_eq!(d.status, DiffStatus::Changed);
+    assert_eq!(d.changes.len(), 2);
+    assert_eq!(diff(old, old).status, DiffStatus::Unchanged);
+}
+
+#[test]
+fn rules_binding_variables_compare_as_a_whole() {
+    let old = r#"policy docs {
+    default: deny,
+    rule regional {
+        allow if {
+            r := user.region &&
+            r == "eu"
+        }
+    }
+}
+"#;
+    let new = r#"policy docs {
+    default: deny,
+    rule regional {
+        allow if {
+            r := user.region &&
+            r == "us"
+        }
+    }
+}
+"#;
+    assert_eq!(diff(old, old).status, DiffStatus::Unchanged);
+    let d = diff(old, new);
+    assert_eq!(d.changes.len(), 2);
+    assert!(d.changes[0].when[0].contains(r#"r == "us""#));
+}
+
+#[test]
+fn sets_match_policies_by_name() {
+    let a = parse("policy a {\n    default: deny,\n}\n");
+    let a2 = parse("policy a {\n    default: allow,\n}\n");
+    let b = parse("policy b {\n    default: deny,\n}\n");
+    let c = parse("policy c {\n    default: deny,\n}\n");
+    let diffs = diff_sets(&[&a, &b], &[&c, &a2]);
+    let summary: Vec<(&str, DiffStatus, usize)> = diffs
+        .iter()
+        .map(|d| (d.policy.as_str(), d.status, d.changes.len()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("a", DiffStatus::Changed, 1),
+            ("c", DiffStatus::Added, 0),
+            ("b", DiffStatus::Removed, 0),
+        ]
+    );
+
+    // One policy on each side: compared whatever the names.
+    let renamed = diff_sets(&[&b], &[&c]);
+    assert_eq!(renamed.len(), 1);
+    assert_eq!(renamed[0].policy, "c");
+    assert_eq!(renamed[0].status, DiffStatus::Unchanged);
+}
+
+#[test]
+fn json_report_uses_lowercase_decisions() {
+    let old = "policy open {\n    default: deny,\n}\n";
+    let new = "policy open {\n    default: allow,\n}\n";
+    let value = serde_json::to_value(diff(old, new)).unwrap();
+    assert_eq!(value["status"], "changed");
+    assert_eq!(value["changes"][0]["from"], "deny");
+    assert_eq!(value["changes"][0]["to"], "allow");
+    assert_eq!(value["changes"][0]["when"], serde_json::json!([]));
+}
@@ -397,9 +397,9 @@ fn all_requests() -> Vec<PolicyRequest> {
                 // no extra context / with ticket context
                 for ctx_extra in [None, Some(("ticket", "INC-1")), Some(("channel", "web"))] {
                     let mut context = HashMap::new();
-                    context.insert("principal".to_string(), user.to_string());
+                    context.insert("principal".to_string(), (*user).into());
                     if let Some((k, v)) = ctx_extra {
-                        context.insert(k.to_string(), v.to_string());
+                        context.insert(k.to_string(), v.into());
                     }
                     requests.push(PolicyRequest {
                         resource: resource.to_string(),
@@ -451,7 +451,7 @@ fn oracle_atom(atom: &Atom, world: &World, request: &PolicyRequest) -> bool {
     let principal = request
         .context
         .get("principal")
-        .map(String::as_str)
+        .and_then(serde_json::Value::as_str)
         .unwrap_or("");
     let u = USERS.iter().position(|x| *x == principal);
     let r = RESOURCES
@@ -506,7 +506,13 @@ fn oracle_atom(atom: &Atom, world: &World, request: &PolicyRequest) -> bool {
             _ => false,
         },
         Atom::ActionEq { val } => request.action == *val,
-        Atom::CtxEq { key, val } => request.context.get(*key).map(String::as_str) == Some(*val),
+        Atom::CtxEq { key, val } => {
+            request
+                .context
+                .get(*key)
+                .and_then(serde_json::Value::as_str)
+                == Some(*val)
+        }
         Atom::CtxNotNull { key } => request.context.contains_key(*key),
         Atom::InArray { val } => u.is_some_and(|u| ROLES[world.user_attrs[u].3] == *val),
         // Type-strict TOTAL comparisons over the mixed-type badge:
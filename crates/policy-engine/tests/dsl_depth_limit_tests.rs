@@ -78,6 +78,7 @@ fn compile_rejects_deep_ast() {
             decision: Decision::Allow,
             condition,
             message: None,
+            obligations: None,
         }],
         functions: vec![],
         imports: vec![],
@@ -0,0 +1,239 @@
+//! Evaluation traces (`PolicyEvaluator::explain`): every evaluator that
+//! serves a `.reap` policy traces it to the decision it serves, a default
+//! deny shows the operand values each rule's comparisons failed on, and
+//! `&&` / `||` record where they stopped.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use policy_engine::data::{DataLoader, DataStore};
+use policy_engine::reap::{ConditionTrace, MixedReapEvaluator, PerRuleBuild, ReapParser};
+use policy_engine::reap::{EvaluationTrace, ReaperPolicy};
+use policy_engine::{
+    EnhancedPolicy, PolicyAction, PolicyEngine, PolicyEvaluator, PolicyLanguage, PolicyRequest,
+};
+use serde_json::json;
+
+const POLICY: &str = r#"
+policy docs {
+    default: deny,
+    rule block_suspended {
+        deny if user.status == "suspended"
+    }
+    rule engineers {
+        allow if user.department == "eng" && user.level >= 3 && resource.public == false
+    }
+    rule reviewers {
+        allow if "reviewer" in user.roles || user.level > 8
+    }
+}
+"#;
+
+fn store() -> Arc<DataStore> {
+    let s = Arc::new(DataStore::new());
+    let data = json!({
+        "entities": [
+            {"id": "alice", "type": "user",
+             "attributes": {"status": "active", "department": "eng", "level": 5, "roles": []}},
+            {"id": "bob", "type": "user",
+             "attributes": {"status": "active", "department": "sales", "level": 2, "roles": ["reviewer"]}},
+            {"id": "carol", "type": "user",
+             "attributes": {"status": "active", "department": "ops", "level": 1, "roles": []}},
+            {"id": "dave", "type": "user",
+             "attributes": {"status": "suspended", "department": "eng", "level": 9, "roles": []}},
+            {"id": "doc1", "type": "resource", "attributes": {"public": false}}
+        ]
+    });
+    DataLoader::new((*s).clone())
+        .load_json(&data.to_string())
+        .unwrap();
+    s
+}
+
+fn req(principal: &str) -> PolicyRequest {
+    let mut context = HashMap::new();
+    context.insert("principal".to_string(), principal.into());
+    PolicyRequest {
+        resource: "doc1".to_string(),
+        action: "read".to_string(),
+        context,
+        ..Default::default()
+    }
+}
+
+fn rule<'a>(trace: &'a EvaluationTrace, name: &str) -> &'a policy_engine::reap::RuleTrace {
+    trace.rules.iter().find(|r| r.name == name).unwrap()
+}
+
+#[test]
+fn every_evaluator_traces_the_decision_it_serves() {
+    let policy: ReaperPolicy = POLICY.parse().unwrap();
+    let ast = policy.clone().build_ast_evaluator(store());
+    let compiled = policy.build_preferred(store()).unwrap();
+    assert_eq!(compiled.evaluator_type(), "reaper_dsl");
+
+    for (principal, decision, rule) in [
+        ("alice", PolicyAction::Allow, Some("engineers")),
+        ("bob", PolicyAction::Allow, Some("reviewers")),
+        ("carol", PolicyAction::Deny, None),
+        ("dave", PolicyAction::Deny, Some("block_suspended")),
+    ] {
+        let request = req(principal);
+        let served = compiled.evaluate_named(&request).unwrap();
+        let traced = compiled.explain(&request).unwrap();
+        assert_eq!(traced.evaluator, "reaper_dsl");
+        assert_eq!(
+            (&traced.decision, traced.decided_by.as_deref()),
+            (&served.decision, served.rule_name),
+            "{principal}"
+        );
+        assert_eq!(
+            (traced.decision, traced.decided_by.as_deref()),
+            (decision, rule)
+        );
+
+        let interpreted = ast.explain(&request).unwrap();
+        assert_eq!(interpreted.evaluator, "ReapAstEvaluator");
+        assert_eq!(interpreted.rules, traced.rules, "{principal}");
+    }
+}
+
+#[test]
+fn default_deny_shows_why_each_rule_failed() {
+    let policy: ReaperPolicy = POLICY.parse().unwrap();
+    let trace = policy
+        .build_preferred(store())
+        .unwrap()
+        .explain(&req("carol"))
+        .unwrap();
+    assert_eq!(trace.decision, PolicyAction::Deny);
+    assert_eq!(trace.decided_by, None);
+    assert!(trace.deciding_rule().is_none());
+    // Deny rules first, then allow rules, each in source order.
+    let order: Vec<_> = trace.rules.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(order, ["block_suspended", "engineers", "reviewers"]);
+    assert!(trace.rules.iter().all(|r| !r.matched));
+
+    let Some(ConditionTrace::All {
+        result: false,
+        operands,
+        skipped: 2,
+    }) = &rule(&trace, "engineers").condition
+    else {
+        panic!("{:?}", rule(&trace, "engineers").condition);
+    };
+    assert_eq!(
+        operands[0],
+        ConditionTrace::Comparison {
+            source: r#"user.department == "eng""#.to_string(),
+            left: json!("ops"),
+            op: "==".to_string(),
+            right: json!("eng"),
+            result: false,
+        }
+    );
+
+    // `in` is traced member-first, as written.
+    let Some(ConditionTrace::Any {
+        operands,
+        skipped: 0,
+        ..
+    }) = &rule(&trace, "reviewers").condition
+    else {
+        panic!()
+    };
+    let ConditionTrace::Comparison {
+        left, op, right, ..
+    } = &operands[0]
+    else {
+        panic!()
+    };
+    assert_eq!(
+        (left, op.as_str(), right),
+        (&json!("reviewer"), "in", &json!([]))
+    );
+
+    let rendered = serde_json::to_value(&trace).unwrap();
+    assert_eq!(rendered["decision"], "deny");
+    assert_eq!(rendered["rules"][1]["effect"], "allow");
+    assert_eq!(rendered["rules"][1]["condition"]["kind"], "all");
+}
+
+#[test]
+fn rules_after_the_decision_are_not_evaluated() {
+    let policy: ReaperPolicy = POLICY.parse().unwrap();
+    let evaluator = policy.build_preferred(store()).unwrap();
+    let trace = evaluator.explain(&req("dave")).unwrap();
+    assert_eq!(trace.decided_by.as_deref(), Some("block_suspended"));
+    assert!(trace.deciding_rule().unwrap().matched);
+    assert!(rule(&trace, "engineers").condition.is_none());
+    assert!(rule(&trace, "reviewers").condition.is_none());
+
+    // `||` stops at the first true operand.
+    let trace = evaluator.explain(&req("bob")).unwrap();
+    let Some(ConditionTrace::Any {
+        result: true,
+        skipped: 1,
+        ..
+    }) = &rule(&trace, "reviewers").condition
+    else {
+        panic!("{:?}", rule(&trace, "reviewers").condition);
+    };
+}
+
+#[test]
+fn mixed_evaluators_trace_through_the_whole_policy() {
+    let source = r#"
+policy mixed {
+    default: deny,
+    rule cap { deny if { cap := 8.5 && user.level > cap } }
+    rule engineers { allow if user.department == "eng" }
+}
+"#;
+    let mixed =
+        match MixedReapEvaluator::build(ReapParser::parse(source).unwrap(), store()).unwrap() {
+            PerRuleBuild::Mixed(m) => m,
+            PerRuleBuild::AllAst(_) => panic!("expected a mixed build"),
+        };
+    let trace = mixed.explain(&req("dave")).unwrap();
+    assert_eq!(trace.evaluator, "reaper_dsl_mixed");
+    assert_eq!(trace.decided_by.as_deref(), Some("cap"));
+    let Some(ConditionTrace::All { operands, .. }) = &rule(&trace, "cap").condition else {
+        panic!()
+    };
+    assert_eq!(
+        operands[0],
+        ConditionTrace::Assignment {
+            variable: "cap".to_string(),
+            value: json!(8.5),
+        }
+    );
+
+    let trace = mixed.explain(&req("alice")).unwrap();
+    assert_eq!(trace.decided_by.as_deref(), Some("engineers"));
+}
+
+#[test]
+fn engine_explain_uses_the_deployed_policy() {
+    let engine = PolicyEngine::new();
+    let mut policy = EnhancedPolicy::new_with_language(
+        "docs".to_string(),
+        String::new(),
+        PolicyLanguage::ReaperDsl,
+        POLICY.to_string(),
+    )
+    .unwrap();
+    policy.build_evaluator_with_data(Some(store())).unwrap();
+    let id = policy.id;
+    engine.deploy_policy(policy).unwrap();
+
+    let trace = engine.explain(&id, &req("alice")).unwrap();
+    let served = engine.evaluate(&id, &req("alice")).unwrap();
+    assert_eq!(trace.decision, served.decision);
+    assert_eq!(trace.decided_by, served.matched_rule_name);
+    assert!(engine
+        .explain(&policy_engine::PolicyId::new_v4(), &req("alice"))
+        .is_err());
+}
@@ -18,7 +18,7 @@ fn request() -> PolicyRequest {
         action: "read".to_string(),
         context: {
             let mut c = HashMap::new();
-            c.insert("principal".to_string(), "alice".to_string());
+            c.insert("principal".to_string(), "alice".into());
             c
         },
 
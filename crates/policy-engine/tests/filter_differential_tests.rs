@@ -0,0 +1,355 @@
+//! List authorization (FILTER_COMPILATION.md) — differential against the
+//! point path.
+//!
+//! The contract of `filter_ids` is that paging through it yields exactly the
+//! resources a brute-force `evaluate_set` over every entity of the type
+//! allows. These tests pin that for residualizable rules, deny precedence,
+//! opaque rules (narrowing falls back to the whole type, verification still
+//! decides), evaluate-all scope, and the epoch bookkeeping; plus the strict
+//! residual/SQL outputs for the same corpus.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use policy_engine::data::{DataLoader, DataStore};
+use policy_engine::filter::{
+    filter_ids, filter_sql, residualize, FilterError, FilterQuery, FilterScope, IdsOptions,
+    ParamStyle, SqlOptions,
+};
+use policy_engine::{EnhancedPolicy, PolicyAction, PolicyEngine, PolicyId, PolicyLanguage};
+
+const DOCS: &str = r#"
+policy docs {
+    default: deny,
+    rule archived {
+        deny if resource.status == "archived"
+    }
+    rule owner {
+        allow if resource.owner == user.handle
+    }
+    rule team {
+        allow if resource.team == user.team && user.level >= 3
+    }
+    rule public {
+        allow if resource.visibility == "public"
+    }
+    rule shared {
+        allow if rebac::related(user, "viewer", resource)
+    }
+}
+"#;
+
+const SIZED: &str = r#"
+policy sized {
+    default: deny,
+    rule small {
+        allow if resource.size * 2 < 10
+    }
+}
+"#;
+
+const PRINCIPALS: [&str; 4] = ["alice", "bob", "carol", "mallory"];
+
+fn store() -> Arc<DataStore> {
+    let mut entities = vec![
+        serde_json::json!({"id": "alice", "type": "user",
+            "attributes": {"handle": "alice", "team": "eng", "level": 5}}),
+        serde_json::json!({"id": "bob", "type": "user",
+            "attributes": {"handle": "bob", "team": "eng", "level": 1}}),
+        serde_json::json!({"id": "carol", "type": "user",
+            "attributes": {"handle": "carol", "team": "sales", "level": 4}}),
+        serde_json::json!({"id": "mallory", "type": "user",
+            "attributes": {"handle": "mallory", "team": "none", "level": 0}}),
+    ];
+    let owners = ["alice", "bob", "carol", "nobody"];
+    let teams = ["eng", "sales", "ops"];
+    let statuses = ["active", "active", "archived"];
+    let visibility = ["private", "private", "private", "public"];
+    for i in 0..60 {
+        let mut doc = serde_json::json!({
+            "id": format!("doc-{i:03}"),
+            "type": "document",
+            "attributes": {
+                "owner": owners[i % owners.len()],
+                "team": teams[i % teams.len()],
+                "status": statuses[i % statuses.len()],
+                "visibility": visibility[i % visibility.len()],
+                "size": (i % 9) as i64,
+            }
+        });
+        if i % 7 == 0 {
+            doc["relationships"] = serde_json::json!({"viewer": ["mallory", "bob"]});
+        }
+        entities.push(doc);
+    }
+    // Same attributes, different type: must never be listed.
+    entities.push(serde_json::json!({"id": "note-1", "type": "note",
+        "attributes": {"owner": "alice", "visibility": "public"}}));
+
+    let s = Arc::new(DataStore::new());
+    DataLoader::new((*s).clone())
+        .load_json(&serde_json::json!({ "entities": entities }).to_string())
+        .unwrap();
+    s
+}
+
+fn deploy(engine: &PolicyEngine, s: &Arc<DataStore>, name: &str, source: &str) -> PolicyId {
+    let mut p = EnhancedPolicy::new_with_language(
+        name.to_string(),
+        String::new(),
+        PolicyLanguage::ReaperDsl,
+        source.to_string(),
+    )
+    .unwrap();
+    p.build_evaluator_with_data(Some(s.clone())).unwrap();
+    let id = p.id;
+    engine.deploy_policy(p).unwrap();
+    id
+}
+
+fn query(principal: &str, scope: FilterScope) -> FilterQuery {
+    FilterQuery {
+        principal: principal.to_string(),
+        action: "read".to_string(),
+        resource_type: "document".to_string(),
+        context: HashMap::new(),
+        actor: None,
+        context_provenance: None,
+        scope,
+    }
+}
+
+/// Every document the served path allows, in id order.
+fn brute_force(engine: &PolicyEngine, ids: &[PolicyId], q: &FilterQuery) -> Vec<String> {
+    let mut out: Vec<String> = (0..60)
+        .map(|i| format!("doc-{i:03}"))
+        .filter(|doc| engine.evaluate_set(ids, &q.request_for(doc)).decision == PolicyAction::Allow)
+        .collect();
+    out.sort();
+    out
+}
+
+/// Page through `filter_ids` to the end.
+fn paged(engine: &PolicyEngine, s: &DataStore, q: &FilterQuery, limit: usize) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut after = None;
+    loop {
+        let page = filter_ids(
+            engine,
+            s,
+            q,
+            &IdsOptions {
+                after: after.take(),
+                limit,
+                max_candidates: 10_000,
+            },
+        )
+        .unwrap();
+        assert!(page.ids.len() <= limit);
+        out.extend(page.ids);
+        match page.next_cursor {
+            Some(cursor) => after = Some(cursor),
+            None => return out,
+        }
+    }
+}
+
+#[test]
+fn ids_match_brute_force_for_every_principal_and_page_size() {
+    let s = store();
+    let engine = PolicyEngine::new();
+    let docs = deploy(&engine, &s, "docs", DOCS);
+
+    for principal in PRINCIPALS {
+        let q = query(principal, FilterScope::Policies(vec![docs]));
+        let expected = brute_force(&engine, &[docs], &q);
+        for limit in [1, 4, 7, 100] {
+            assert_eq!(
+                paged(&engine, &s, &q, limit),
+                expected,
+                "principal {principal}, limit {limit}"
+            );
+        }
+    }
+}
+
+#[test]
+fn deny_rules_win_over_every_allow() {
+    let s = store();
+    let engine = PolicyEngine::new();
+    let docs = deploy(&engine, &s, "docs", DOCS);
+
+    let q = query("alice", FilterScope::Policies(vec![docs]));
+    let ids = paged(&engine, &s, &q, 100);
+    assert!(!ids.is_empty());
+    for id in &ids {
+        let n: usize = id.trim_start_matches("doc-").parse().unwrap();
+        assert_ne!(n % 3, 2, "{id} is archived and must be denied");
+    }
+}
+
+#[test]
+fn opaque_rules_fall_back_to_verification() {
+    let s = store();
+    let engine = PolicyEngine::new();
+    let sized = deploy(&engine, &s, "sized", SIZED);
+
+    let q = query("bob", FilterScope::Policies(vec![sized]));
+    let residual = residualize(&engine, &q).unwrap();
+    assert!(
+        serde_json::to_string(&residual).unwrap().contains("opaque"),
+        "arithmetic over the resource is outside the residual IR: {residual:?}"
+    );
+
+    // No narrowing: the whole type is the candidate set, and verification
+    // still returns exactly the served answer.
+    let page = filter_ids(
+        &engine,
+        &s,
+        &q,
+        &IdsOptions {
+            after: None,
+            limit: 100,
+            max_candidates: 10_000,
+        },
+    )
+    .unwrap();
+    assert_eq!(page.candidates, 60);
+    assert_eq!(page.ids, brute_force(&engine, &[sized], &q));
+}
+
+#[test]
+fn evaluate_all_composes_deployed_policies() {
+    let s = store();
+    let engine = PolicyEngine::new();
+    let docs = deploy(&engine, &s, "docs", DOCS);
+    let sized = deploy(&engine, &s, "sized", SIZED);
+
+    for principal in PRINCIPALS {
+        let q = query(
+            principal,
+            FilterScope::All {
+                max_candidate_policies: 100,
+                use_pruning_index: true,
+            },
+        );
+        assert_eq!(
+            paged(&engine, &s, &q, 5),
+            brute_force(&engine, &[docs, sized], &q),
+            "principal {principal}"
+        );
+    }
+}
+
+#[test]
+fn narrowing_bounds_the_verify_budget() {
+    let s = store();
+    let engine = PolicyEngine::new();
+    let sized = deploy(&engine, &s, "sized", SIZED);
+    let docs = deploy(&engine, &s, "docs", DOCS);
+
+    // The docs residual narrows through the indexes well below the type.
+    let q = query("carol", FilterScope::Policies(vec![docs]));
+    let options = IdsOptions {
+        after: None,
+        limit: 100,
+        max_candidates: 40,
+    };
+    let page = filter_ids(&engine, &s, &q, &options).unwrap();
+    assert!(page.candidates < 60, "narrowed to {}", page.candidates);
+
+    // The opaque policy cannot narrow, so the same budget is exceeded.
+    let q = query("carol", FilterScope::Policies(vec![sized]));
+    assert_eq!(
+        filter_ids(&engine, &s, &q, &options).unwrap_err(),
+        FilterError::CandidatesExceeded {
+            candidates: 60,
+            max: 40
+        }
+    );
+}
+
+#[test]
+fn page_reports_the_store_epoch() {
+    let s = store();
+    let engine = PolicyEngine::new();
+    let docs = deploy(&engine, &s, "docs", DOCS);
+    let q = query("alice", FilterScope::Policies(vec![docs]));
+    let options = IdsOptions {
+        after: None,
+        limit: 10,
+        max_candidates: 10_000,
+    };
+
+    let page = filter_ids(&engine, &s, &q, &options).unwrap();
+    assert_eq!(page.data_epoch, s.data_epoch());
+    assert!(!page.epoch_moved);
+}
+
+#[test]
+fn unknown_type_lists_nothing() {
+    let s = store();
+    let engine = PolicyEngine::new();
+    let docs = deploy(&engine, &s, "docs", DOCS);
+    let mut q = query("alice", FilterScope::Policies(vec![docs]));
+    q.resource_type = "never_loaded".to_string();
+    assert!(paged(&engine, &s, &q, 10).is_empty());
+}
+
+#[test]
+fn sql_is_exact_when_every_attribute_is_mapped() {
+    let s = store();
+    let engine = PolicyEngine::new();
+    let docs = deploy(&engine, &s, "docs", DOCS);
+    let q = query("alice", FilterScope::Policies(vec![docs]));
+
+    let columns: HashMap<String, String> = ["owner", "team", "status", "visibility"]
+        .into_iter()
+        .map(|c| (c.to_string(), c.to_string()))
+        .collect();
+    let mut options = SqlOptions {
+        id_column: "id".to_string(),
+        columns,
+        param_style: ParamStyle::Dollar,
+        max_in_list: 1000,
+    };
+    let filter = filter_sql(&engine, &s, &q, &options).unwrap();
+    assert!(filter.exact, "{filter:?}");
+    assert!(filter.sql.contains("\"status\"") || filter.sql.contains("status"));
+    assert!(filter.params.contains(&serde_json::json!("alice")));
+
+    // Drop a column: that atom widens and the filter is only a superset.
+    options.columns.remove("visibility");
+    let filter = filter_sql(&engine, &s, &q, &options).unwrap();
+    assert!(!filter.exact);
+    assert!(filter.unverified_atoms > 0);
+}
+
+#[test]
+fn sql_refuses_policies_without_a_residual() {
+    let s = store();
+    let engine = PolicyEngine::new();
+    let mut simple = EnhancedPolicy::new(
+        "simple".to_string(),
+        String::new(),
+        vec![policy_engine::PolicyRule {
+            action: PolicyAction::Allow,
+            resource: "*".to_string(),
+            conditions: vec![],
+        }],
+    );
+    simple.build_evaluator_with_data(Some(s.clone())).unwrap();
+    let id = simple.id;
+    engine.deploy_policy(simple).unwrap();
+
+    let q = query("alice", FilterScope::Policies(vec![id]));
+    assert!(matches!(
+        residualize(&engine, &q),
+        Err(FilterError::NotResidualizable { .. })
+    ));
+
+    // The ids backend still answers, by verification.
+    assert_eq!(paged(&engine, &s, &q, 25).len(), 60);
+}
@@ -0,0 +1,198 @@
+//! `reap::format` — the canonical `.reap` printer behind `reaper-cli fmt`.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use policy_engine::reap::format::{format_library, format_policy, format_source};
+use policy_engine::reap::ReapParser;
+use std::path::{Path, PathBuf};
+
+fn repo_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../..")
+}
+
+fn reap_files(dir: &Path, out: &mut Vec<PathBuf>) {
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        let name = path.file_name().unwrap().to_string_lossy();
+        if path.is_dir() {
+            if !name.starts_with('.') && name != "target" {
+                reap_files(&path, out);
+            }
+        } else if name.ends_with(".reap") {
+            out.push(path);
+        }
+    }
+}
+
+/// Every `.reap` file in the repository that parses formats, keeps its AST
+/// (checked inside `format_source`) and is a fixed point of the formatter.
+#[test]
+fn every_repo_policy_formats_idempotently() {
+    let mut files = Vec::new();
+    reap_files(&repo_root(), &mut files);
+    assert!(files.len() > 20, "found only {} .reap files", files.len());
+
+    let mut formatted_count = 0;
+    for path in &files {
+        let source = std::fs::read_to_string(path).unwrap();
+        let parses =
+            ReapParser::parse(&source).is_ok() || ReapParser::parse_library(&source).is_ok();
+        if !parses {
+            continue;
+        }
+        let once = format_source(&source).unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+        let twice = format_source(&once).unwrap();
+        assert_eq!(once, twice, "{} is not idempotent", path.display());
+        formatted_count += 1;
+    }
+    assert!(formatted_count > 20);
+}
+
+const MESSY: &str = r#"// header comment
+
+import "lib/predicates.reap"   as   preds  // the helpers
+policy   messy{  // opening
+  // rule first, out of canonical order
+  rule b { deny with message concat("no: ", user.name) if user.banned==true }   // trailing rule
+    version:"2",
+  default:deny, // default note
+  func big(r):=r=="admin"||r=="root" ,
+  author : "ops",
+  rule a {
+     allow with obligations {"mask": ["ssn"]} if {
+       // why the role
+       user.role=="x" &&   // inline op note
+       (user.a>1||user.b<2.5) &&
+       !(user.c=="d") &&
+       preds::is_admin(user.role)
+       /* closing note */
+     }
+  }
+  // dangling before close
+}
+// trailer
+"#;
+
+const MESSY_FORMATTED: &str = r#"// header comment
+
+import "lib/predicates.reap" as preds  // the helpers
+
+policy messy {  // opening
+    author: "ops",
+    version: "2",
+    default: deny,  // default note
+
+    func big(r) := r == "admin" || r == "root",
+
+    // rule first, out of canonical order
+    rule b {
+        deny with message concat("no: ", user.name) if user.banned == true
+    }  // trailing rule
+
+    rule a {
+        allow with obligations {"mask": ["ssn"]} if {
+            // why the role
+            user.role == "x" &&  // inline op note
+            (user.a > 1 || user.b < 2.5) &&
+            !(user.c == "d") &&
+            preds::is_admin(user.role)
+            /* closing note */
+        }
+    }
+    // dangling before close
+}
+// trailer
+"#;
+
+#[test]
+fn comments_follow_the_items_they_annotate() {
+    assert_eq!(format_source(MESSY).unwrap(), MESSY_FORMATTED);
+    assert_eq!(format_source(MESSY_FORMATTED).unwrap(), MESSY_FORMATTED);
+}
+
+#[test]
+fn format_policy_prints_the_ast_without_comments() {
+    let policy = ReapParser::parse(MESSY).unwrap();
+    let printed = format_policy(&policy);
+    assert!(!printed.contains("//") && !printed.contains("/*"));
+    assert!(printed.starts_with("import \"lib/predicates.reap\" as preds\n\npolicy messy {\n"));
+    assert!(printed.contains("    default: deny,\n\n    func big(r)"));
+    assert_eq!(format_source(&printed).unwrap(), printed);
+}
+
+#[test]
+fn long_chains_break_and_nested_groups_wrap() {
+    let source = r#"policy wide { default: deny,
+    func long_one(role, level) := role == "administrator" && level >= 10 && level <= 1000 && role != "suspended",
+    rule r { allow if user.a == 1 && (context.action == "read-the-very-long-action-name" || context.action == "write-the-other-long-action") }
+}"#;
+    assert_eq!(
+        format_source(source).unwrap(),
+        r#"policy wide {
+    default: deny,
+
+    func long_one(role, level) := {
+        role == "administrator" &&
+        level >= 10 &&
+        level <= 1000 &&
+        role != "suspended"
+    },
+
+    rule r {
+        allow if {
+            user.a == 1 &&
+            (
+                context.action == "read-the-very-long-action-name" ||
+                context.action == "write-the-other-long-action"
+            )
+        }
+    }
+}
+"#
+    );
+}
+
+#[test]
+fn literals_and_arithmetic_round_trip() {
+    let source = r#"policy lits {
+    default: deny,
+    rule r {
+        allow if {
+            d := user.x - (user.y - user.z) &&
+            e := (user.x + user.y) * 2 &&
+            user.v == 100.50 &&
+            user.pattern == "^\\d+\"$" &&
+            user.k in {"a", "b"} &&
+            c := {u.id: u.name | u := user.all[_]; u.active == true}
+        }
+    }
+}"#;
+    let formatted = format_source(source).unwrap();
+    assert!(formatted.contains("d := user.x - (user.y - user.z) &&"));
+    assert!(formatted.contains("e := (user.x + user.y) * 2 &&"));
+    assert!(formatted.contains("user.v == 100.5 &&"));
+    assert!(formatted.contains(r#"user.pattern == "^\\d+\"$" &&"#));
+    assert!(formatted.contains("c := {u.id: u.name | u := user.all[_]; u.active == true}"));
+}
+
+#[test]
+fn libraries_format_with_their_metadata() {
+    let source =
+        "// shared\nlibrary preds { version: \"1\", func is_admin(r) := r == \"admin\" }\n";
+    let formatted = format_source(source).unwrap();
+    assert_eq!(
+        formatted,
+        "// shared\nlibrary preds {\n    version: \"1\",\n\n    func is_admin(r) := r == \"admin\",\n}\n"
+    );
+    let (name, functions) = ReapParser::parse_library(&formatted).unwrap();
+    assert_eq!(
+        format_library(&name, &functions),
+        "library preds {\n    func is_admin(r) := r == \"admin\",\n}\n"
+    );
+}
+
+#[test]
+fn invalid_source_reports_the_parse_error() {
+    let err = format_source("policy p { default: deny, rule r { allow if } }").unwrap_err();
+    assert!(err.to_string().contains("Parse error"), "{err}");
+}
@@ -48,7 +48,7 @@ struct Case {
     resource: Option<String>,
     expect: String,
     #[serde(default)]
-    context: Option<HashMap<String, String>>,
+    context: Option<HashMap<String, serde_json::Value>>,
 }
 
 fn frozen_root() -> PathBuf {
@@ -180,7 +180,7 @@ fn frozen_decisions_never_change() {
             let label = format!("[{}] {}", manifest.name, case.name);
 
             let mut context = case.context.clone().unwrap_or_default();
-            context.insert("principal".to_string(), principal.clone());
+            context.insert("principal".to_string(), principal.clone().into());
             let request = PolicyRequest {
                 resource: resource.clone(),
                 action: action.clone(),
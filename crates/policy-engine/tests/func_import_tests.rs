@@ -47,7 +47,7 @@ fn store_with_data() -> Arc<DataStore> {
 
 fn request(principal: &str, resource: &str) -> PolicyRequest {
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), principal.to_string());
+    context.insert("principal".to_string(), principal.into());
     PolicyRequest {
         resource: resource.to_string(),
         action: "read".to_string(),
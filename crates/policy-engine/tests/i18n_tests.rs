@@ -50,7 +50,7 @@ policy unicode_test {
 
     // Should allow - department matches Japanese text
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "tanaka".to_string());
+    context.insert("principal".to_string(), "tanaka".into());
 
     let request = PolicyRequest {
         resource: "test".to_string(),
@@ -65,7 +65,7 @@ policy unicode_test {
 
     // Should deny - different department
     let mut context2 = HashMap::new();
-    context2.insert("principal".to_string(), "sato".to_string());
+    context2.insert("principal".to_string(), "sato".into());
 
     let request2 = PolicyRequest {
         resource: "test".to_string(),
@@ -112,7 +112,7 @@ policy chinese_test {
     let evaluator = policy.build(Arc::clone(&store)).unwrap();
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "wang".to_string());
+    context.insert("principal".to_string(), "wang".into());
 
     let request = PolicyRequest {
         resource: "test".to_string(),
@@ -158,7 +158,7 @@ policy korean_test {
     let evaluator = policy.build(Arc::clone(&store)).unwrap();
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "kim".to_string());
+    context.insert("principal".to_string(), "kim".into());
 
     let request = PolicyRequest {
         resource: "test".to_string(),
@@ -210,7 +210,7 @@ policy emoji_test {
 
     // Should allow - status matches
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "user1".to_string());
+    context.insert("principal".to_string(), "user1".into());
 
     let request = PolicyRequest {
         resource: "test".to_string(),
@@ -225,7 +225,7 @@ policy emoji_test {
 
     // Should deny - status doesn't match
     let mut context2 = HashMap::new();
-    context2.insert("principal".to_string(), "user2".to_string());
+    context2.insert("principal".to_string(), "user2".into());
 
     let request2 = PolicyRequest {
         resource: "test".to_string(),
@@ -273,7 +273,7 @@ policy complex_emoji_test {
     let evaluator = policy.build(Arc::clone(&store)).unwrap();
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "jp_user".to_string());
+    context.insert("principal".to_string(), "jp_user".into());
 
     let request = PolicyRequest {
         resource: "test".to_string(),
@@ -320,7 +320,7 @@ policy zero_width_test {
     let evaluator = policy.build(Arc::clone(&store)).unwrap();
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "zw_user".to_string());
+    context.insert("principal".to_string(), "zw_user".into());
 
     let request = PolicyRequest {
         resource: "test".to_string(),
@@ -367,7 +367,7 @@ policy combining_test {
     let evaluator = policy.build(Arc::clone(&store)).unwrap();
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "jose".to_string());
+    context.insert("principal".to_string(), "jose".into());
 
     let request = PolicyRequest {
         resource: "test".to_string(),
@@ -417,7 +417,7 @@ policy arabic_test {
     let evaluator = policy.build(Arc::clone(&store)).unwrap();
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "ahmed".to_string());
+    context.insert("principal".to_string(), "ahmed".into());
 
     let request = PolicyRequest {
         resource: "test".to_string(),
@@ -463,7 +463,7 @@ policy hebrew_test {
     let evaluator = policy.build(Arc::clone(&store)).unwrap();
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "david".to_string());
+    context.insert("principal".to_string(), "david".into());
 
     let request = PolicyRequest {
         resource: "test".to_string(),
@@ -514,7 +514,7 @@ policy contains_unicode_test {
     let evaluator = policy.build(Arc::clone(&store)).unwrap();
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "user1".to_string());
+    context.insert("principal".to_string(), "user1".into());
 
     let request = PolicyRequest {
         resource: "test".to_string(),
@@ -561,7 +561,7 @@ policy startswith_unicode_test {
     let evaluator = policy.build(Arc::clone(&store)).unwrap();
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "admin1".to_string());
+    context.insert("principal".to_string(), "admin1".into());
 
     let request = PolicyRequest {
         resource: "test".to_string(),
@@ -612,7 +612,7 @@ policy mixed_script_test {
     let evaluator = policy.build(Arc::clone(&store)).unwrap();
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "emp1".to_string());
+    context.insert("principal".to_string(), "emp1".into());
 
     let request = PolicyRequest {
         resource: "test".to_string(),
@@ -658,7 +658,7 @@ policy cyrillic_test {
     let evaluator = policy.build(Arc::clone(&store)).unwrap();
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "ivan".to_string());
+    context.insert("principal".to_string(), "ivan".into());
 
     let request = PolicyRequest {
         resource: "test".to_string(),
@@ -704,7 +704,7 @@ policy greek_test {
     let evaluator = policy.build(Arc::clone(&store)).unwrap();
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "nikos".to_string());
+    context.insert("principal".to_string(), "nikos".into());
 
     let request = PolicyRequest {
         resource: "test".to_string(),
@@ -760,7 +760,7 @@ policy long_unicode_test {
     let evaluator = policy.build(Arc::clone(&store)).unwrap();
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "user1".to_string());
+    context.insert("principal".to_string(), "user1".into());
 
     let request = PolicyRequest {
         resource: "test".to_string(),
@@ -814,7 +814,7 @@ policy normalization_test {
     // Both should work
     for user in &["user_nfc", "user_nfd"] {
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), user.to_string());
+        context.insert("principal".to_string(), (*user).into());
 
         let request = PolicyRequest {
             resource: "test".to_string(),
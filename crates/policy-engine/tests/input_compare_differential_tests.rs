@@ -34,7 +34,7 @@ fn store() -> Arc<DataStore> {
 fn request(principal: Option<&str>) -> PolicyRequest {
     let mut context = HashMap::new();
     if let Some(p) = principal {
-        context.insert("principal".to_string(), p.to_string());
+        context.insert("principal".to_string(), p.into());
     }
     PolicyRequest {
         resource: "res".to_string(),
@@ -45,7 +45,7 @@ pub struct TestCase {
     pub action: String,
     pub resource: String,
     #[serde(default)]
-    pub context: HashMap<String, String>,
+    pub context: HashMap<String, serde_json::Value>,
     pub expected: String,
     #[serde(default)]
     pub tags: Vec<String>,
@@ -176,7 +176,7 @@ fn run_test_case(evaluators: &[Box<dyn PolicyEvaluator>], case: &TestCase) -> Te
 
     // Build the request with principal in context
     let mut context = case.context.clone();
-    context.insert("principal".to_string(), case.principal.clone());
+    context.insert("principal".to_string(), case.principal.clone().into());
 
     let request = PolicyRequest {
         resource: case.resource.clone(),
@@ -77,7 +77,7 @@ policy large_scale_test {
     for i in 0..num_evals {
         let user_idx = i % 100_000;
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), format!("user_{}", user_idx));
+        context.insert("principal".to_string(), format!("user_{}", user_idx).into());
 
         let request = PolicyRequest {
             resource: "test".to_string(),
@@ -237,7 +237,7 @@ policy throughput_test {
     // Warm up
     for _ in 0..1000 {
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), "user_0".to_string());
+        context.insert("principal".to_string(), "user_0".into());
         let request = PolicyRequest {
             resource: "doc_0".to_string(),
             action: "read".to_string(),
@@ -257,7 +257,7 @@ policy throughput_test {
         let doc_idx = i % 100;
 
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), format!("user_{}", user_idx));
+        context.insert("principal".to_string(), format!("user_{}", user_idx).into());
 
         let request = PolicyRequest {
             resource: format!("doc_{}", doc_idx),
@@ -347,7 +347,7 @@ policy latency_test {
         let doc_idx = i % 100;
 
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), format!("user_{}", user_idx));
+        context.insert("principal".to_string(), format!("user_{}", user_idx).into());
 
         let request = PolicyRequest {
             resource: format!("doc_{}", doc_idx),
@@ -443,7 +443,7 @@ policy concurrent_test {
                     let user_idx = (thread_id * evals_per_thread + i) % 10_000;
 
                     let mut context = HashMap::new();
-                    context.insert("principal".to_string(), format!("user_{}", user_idx));
+                    context.insert("principal".to_string(), format!("user_{}", user_idx).into());
 
                     let request = PolicyRequest {
                         resource: "test".to_string(),
@@ -533,7 +533,7 @@ policy sustained_test {
     while start.elapsed() < duration {
         for i in 0..1000 {
             let mut context = HashMap::new();
-            context.insert("principal".to_string(), format!("user_{}", i % 1000));
+            context.insert("principal".to_string(), format!("user_{}", i % 1000).into());
 
             let request = PolicyRequest {
                 resource: "test".to_string(),
@@ -688,7 +688,7 @@ policy complex_large_test {
         let resource_idx = i % 1000;
 
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), format!("user_{}", user_idx));
+        context.insert("principal".to_string(), format!("user_{}", user_idx).into());
 
         let request = PolicyRequest {
             resource: format!("resource_{}", resource_idx),
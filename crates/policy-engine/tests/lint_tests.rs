@@ -0,0 +1,358 @@
+//! Static policy analysis (`reap::lint`): unsatisfiable and dead
+//! conditions, allow rules shadowed by a deny, duplicate rules within and
+//! across the policies of a package, and unused funcs and imports.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use policy_engine::reap::lint::{lint_package, lint_policy, LintFinding, LintKind};
+use policy_engine::reap::{Policy, ReapParser, ReaperPolicy};
+
+fn parse(source: &str) -> Policy {
+    ReapParser::parse(source).unwrap()
+}
+
+fn lint(source: &str) -> Vec<LintFinding> {
+    lint_policy(&parse(source), Some(source))
+}
+
+/// `(kind, location, line)` per finding.
+fn summary(findings: &[LintFinding]) -> Vec<(LintKind, String, Option<usize>)> {
+    findings
+        .iter()
+        .map(|f| (f.kind, f.location.clone(), f.line))
+        .collect()
+}
+
+#[test]
+fn clean_policy_has_no_findings() {
+    let source = r#"policy clean {
+    default: deny,
+
+    func senior(level) := level >= 5,
+
+    rule suspended {
+        deny if user.status == "suspended"
+    }
+
+    rule seniors {
+        allow if user.role == "engineer" && senior(user.level)
+    }
+
+    rule band {
+        allow if user.level >= 2 && user.level <= 4
+    }
+}
+"#;
+    assert_eq!(lint(source), Vec::new());
+}
+
+#[test]
+fn contradictory_conjuncts_are_unsatisfiable() {
+    let source = r#"policy contradictions {
+    default: deny,
+
+    rule two_roles {
+        allow if user.role == "admin" && user.role == "owner"
+    }
+
+    rule empty_range {
+        allow if user.level > 5 && user.level < 3
+    }
+
+    rule open_point {
+        allow if user.level >= 3 && user.level < 3
+    }
+
+    rule equal_and_not {
+        allow if user.team == "a" && user.team != "a"
+    }
+
+    rule negated {
+        allow if user.active == true && !(user.active == true)
+    }
+
+    rule nested {
+        allow if {
+            user.region == "eu" &&
+            (user.level == 1 && user.level > 4)
+        }
+    }
+}
+"#;
+    let findings = lint(source);
+    assert_eq!(
+        summary(&findings),
+        vec![
+            (LintKind::Unsatisfiable, "rule two_roles".into(), Some(4)),
+            (LintKind::Unsatisfiable, "rule empty_range".into(), Some(8)),
+            (LintKind::Unsatisfiable, "rule open_point".into(), Some(12)),
+            (
+                LintKind::Unsatisfiable,
+                "rule equal_and_not".into(),
+                Some(16)
+            ),
+            (LintKind::Unsatisfiable, "rule negated".into(), Some(20)),
+            (LintKind::Unsatisfiable, "rule nested".into(), Some(24)),
+        ]
+    );
+    assert_eq!(
+        findings[0].message,
+        r#"condition can never hold: `user.role == "admin"` and `user.role == "owner"`"#
+    );
+    assert_eq!(
+        findings[0].to_string(),
+        r#"rule two_roles: condition can never hold: `user.role == "admin"` and `user.role == "owner"` [unsatisfiable]"#
+    );
+}
+
+#[test]
+fn dead_or_alternative_is_reported_without_failing_the_rule() {
+    let source = r#"policy dead {
+    default: deny,
+
+    rule either {
+        allow if user.role == "admin" || (user.tier == 1 && user.tier == 2)
+    }
+}
+"#;
+    let findings = lint(source);
+    assert_eq!(
+        summary(&findings),
+        vec![(LintKind::DeadCondition, "rule either".into(), Some(4))]
+    );
+    assert!(findings[0]
+        .message
+        .contains("`user.tier == 1` and `user.tier == 2`"));
+}
+
+#[test]
+fn allow_covered_by_a_deny_is_shadowed() {
+    let source = r#"policy shadowing {
+    default: deny,
+
+    rule contractors_out {
+        deny if user.kind == "contractor"
+    }
+
+    rule contractor_reads {
+        allow if user.kind == "contractor" && resource.public == true
+    }
+
+    rule employees {
+        allow if user.kind == "employee"
+    }
+}
+"#;
+    let findings = lint(source);
+    assert_eq!(
+        summary(&findings),
+        vec![(LintKind::Shadowed, "rule contractor_reads".into(), Some(8))]
+    );
+    assert_eq!(
+        findings[0].message,
+        "never decides: rule contractors_out denies every request it matches"
+    );
+}
+
+#[test]
+fn unconditional_deny_shadows_every_allow() {
+    let source = r#"policy lockdown {
+    default: deny,
+
+    rule freeze {
+        deny if true
+    }
+
+    rule admins {
+        allow if user.role == "admin"
+    }
+}
+"#;
+    let findings = lint(source);
+    assert_eq!(
+        summary(&findings),
+        vec![(LintKind::Shadowed, "rule admins".into(), Some(8))]
+    );
+    assert_eq!(
+        findings[0].message,
+        "never decides: rule freeze denies every request"
+    );
+}
+
+#[test]
+fn deny_reading_variables_does_not_shadow() {
+    // The deny's `r` and the allow's `r` are different bindings; equal text
+    // is not the same check.
+    let source = r#"policy scoped {
+    default: deny,
+
+    rule blocked {
+        deny if {
+            r := user.region &&
+            r == "embargoed"
+        }
+    }
+
+    rule regional {
+        allow if {
+            r := resource.region &&
+            r == "embargoed"
+        }
+    }
+}
+"#;
+    assert_eq!(lint(source), Vec::new());
+}
+
+#[test]
+fn duplicate_rules_within_a_policy() {
+    let source = r#"policy dupes {
+    default: deny,
+
+    rule admins {
+        allow if user.role == "admin"
+    }
+
+    rule admins_again {
+        allow if user.role == "admin"
+    }
+
+    rule admins_denied {
+        deny if user.role == "admin" && user.locked == true
+    }
+}
+"#;
+    let findings = lint(source);
+    assert_eq!(
+        summary(&findings),
+        vec![(LintKind::Duplicate, "rule admins_again".into(), Some(8))]
+    );
+    assert_eq!(
+        findings[0].message,
+        "same effect and condition as rule admins"
+    );
+}
+
+#[test]
+fn duplicate_rules_across_policies_of_one_package() {
+    let billing = r#"policy billing {
+    package: "finance",
+    default: deny,
+
+    rule auditors {
+        allow if user.role == "auditor"
+    }
+}
+"#;
+    let payroll = r#"policy payroll {
+    package: "finance",
+    default: deny,
+
+    rule auditors_read {
+        allow if user.role == "auditor"
+    }
+}
+"#;
+    let elsewhere = r#"policy wiki {
+    default: deny,
+
+    rule auditors {
+        allow if user.role == "auditor"
+    }
+}
+"#;
+    let (b, p, w) = (parse(billing), parse(payroll), parse(elsewhere));
+    let findings = lint_package(&[
+        (&b, Some(billing)),
+        (&p, Some(payroll)),
+        (&w, Some(elsewhere)),
+    ]);
+    assert_eq!(findings.len(), 3);
+    assert!(findings[0].is_empty());
+    assert!(findings[2].is_empty(), "wiki is in another package");
+    assert_eq!(
+        summary(&findings[1]),
+        vec![(LintKind::Duplicate, "rule auditors_read".into(), Some(5))]
+    );
+    assert_eq!(findings[1][0].policy, "payroll");
+    assert_eq!(
+        findings[1][0].message,
+        "same effect and condition as rule auditors in policy billing"
+    );
+}
+
+#[test]
+fn funcs_unreached_from_rules_are_unused() {
+    let source = r#"policy helpers {
+    default: deny,
+
+    func staff(u) := u == "staff",
+
+    func orphan_leaf(x) := x > 1,
+
+    func orphan(x) := orphan_leaf(x),
+
+    func empty(x) := x == 1 && x == 2,
+
+    rule staffers {
+        allow if staff(user.kind) || empty(user.level)
+    }
+}
+"#;
+    let findings = lint(source);
+    assert_eq!(
+        summary(&findings),
+        vec![
+            (LintKind::Unsatisfiable, "func empty".into(), Some(10)),
+            (LintKind::UnusedFunc, "func orphan_leaf".into(), Some(6)),
+            (LintKind::UnusedFunc, "func orphan".into(), Some(8)),
+        ]
+    );
+}
+
+#[test]
+fn unused_import_is_reported_and_used_one_is_not() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("lib.reap"),
+        "library helpers {\n    func senior(level) := level >= 5,\n}\n",
+    )
+    .unwrap();
+    let source = r#"import "lib.reap" as h
+import "lib.reap" as spare
+
+policy uses_lib {
+    default: deny,
+
+    rule seniors {
+        allow if h::senior(user.level)
+    }
+}
+"#;
+    let path = dir.path().join("policy.reap");
+    std::fs::write(&path, source).unwrap();
+    let policy = ReaperPolicy::from_file(&path).unwrap();
+    let findings = policy.lint(Some(source));
+    assert_eq!(
+        summary(&findings),
+        vec![(LintKind::UnusedImport, "import spare".into(), Some(2))]
+    );
+    assert_eq!(findings[0].message, r#"no rule calls into "lib.reap""#);
+}
+
+#[test]
+fn without_source_there_are_no_lines() {
+    let source = r#"policy p {
+    default: deny,
+
+    rule r {
+        allow if user.a == 1 && user.a == 2
+    }
+}
+"#;
+    let findings = lint_policy(&parse(source), None);
+    assert_eq!(
+        summary(&findings),
+        vec![(LintKind::Unsatisfiable, "rule r".into(), None)]
+    );
+}
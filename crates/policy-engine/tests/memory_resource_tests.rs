@@ -219,7 +219,7 @@ policy regex_cache_test {
     let start_cold = Instant::now();
     for i in 0..100 {
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), format!("user_{}", i));
+        context.insert("principal".to_string(), format!("user_{}", i).into());
 
         let request = policy_engine::PolicyRequest {
             resource: "test".to_string(),
@@ -237,7 +237,7 @@ policy regex_cache_test {
     let start_warm = Instant::now();
     for i in 0..100 {
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), format!("user_{}", i));
+        context.insert("principal".to_string(), format!("user_{}", i).into());
 
         let request = policy_engine::PolicyRequest {
             resource: "test".to_string(),
@@ -424,7 +424,7 @@ policy eval_stability_test {
     let start = Instant::now();
     for _ in 0..100_000 {
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), "user_1".to_string());
+        context.insert("principal".to_string(), "user_1".into());
 
         let request = policy_engine::PolicyRequest {
             resource: "test".to_string(),
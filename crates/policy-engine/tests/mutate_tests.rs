@@ -0,0 +1,221 @@
+//! Mutation testing (`reap::mutate`): which single-point mutants a policy
+//! yields, where they point in the source, and that each one builds and
+//! changes behaviour the way its description says.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use policy_engine::data::{DataLoader, DataStore};
+use policy_engine::reap::mutate::{mutants, Mutant, MutationKind};
+use policy_engine::reap::ReaperPolicy;
+use policy_engine::{PolicyAction, PolicyEvaluator, PolicyRequest};
+use serde_json::json;
+
+const POLICY: &str = r#"policy clearance {
+    default: deny,
+
+    func trusted(level) := level > 2,
+
+    rule block_suspended {
+        deny if user.status == "suspended"
+    }
+
+    rule cleared {
+        allow if {
+            user.clearance >= 3 &&
+            trusted(user.clearance)
+        }
+    }
+}
+"#;
+
+fn all() -> Vec<Mutant> {
+    let policy: ReaperPolicy = POLICY.parse().unwrap();
+    mutants(&policy, Some(POLICY))
+}
+
+/// `(declaration, kind, original, replacement, line)` per mutant.
+type Summary = (String, MutationKind, String, String, Option<usize>);
+
+fn summary(mutants: &[Mutant]) -> Vec<Summary> {
+    mutants
+        .iter()
+        .map(|m| {
+            (
+                m.declaration.clone(),
+                m.kind,
+                m.original.clone(),
+                m.replacement.clone(),
+                m.line,
+            )
+        })
+        .collect()
+}
+
+fn s(
+    declaration: &str,
+    kind: MutationKind,
+    original: &str,
+    replacement: &str,
+    line: usize,
+) -> Summary {
+    (
+        declaration.to_string(),
+        kind,
+        original.to_string(),
+        replacement.to_string(),
+        Some(line),
+    )
+}
+
+#[test]
+fn every_operator_literal_conjunct_and_effect_is_mutated_once() {
+    use MutationKind::*;
+    let rule = "rule block_suspended";
+    let cleared = "rule cleared";
+    let func = "func trusted";
+    assert_eq!(
+        summary(&all()),
+        vec![
+            s(rule, FlipEffect, "deny", "allow", 7),
+            s(
+                rule,
+                Operator,
+                r#"user.status == "suspended""#,
+                r#"user.status != "suspended""#,
+                7
+            ),
+            s(
+                rule,
+                Literal,
+                r#"user.status == "suspended""#,
+                r#"user.status == """#,
+                7
+            ),
+            s(cleared, FlipEffect, "allow", "deny", 11),
+            s(cleared, DropConjunct, "user.clearance >= 3", "", 12),
+            s(
+                cleared,
+                Operator,
+                "user.clearance >= 3",
+                "user.clearance > 3",
+                12
+            ),
+            s(
+                cleared,
+                Literal,
+                "user.clearance >= 3",
+                "user.clearance >= 4",
+                12
+            ),
+            s(
+                cleared,
+                Literal,
+                "user.clearance >= 3",
+                "user.clearance >= 2",
+                12
+            ),
+            s(cleared, DropConjunct, "trusted(user.clearance)", "", 13),
+            s(func, Operator, "level > 2", "level >= 2", 4),
+            s(func, Literal, "level > 2", "level > 3", 4),
+            s(func, Literal, "level > 2", "level > 1", 4),
+        ]
+    );
+    let ids: Vec<usize> = all().iter().map(|m| m.id).collect();
+    assert_eq!(ids, (1..=12).collect::<Vec<_>>());
+}
+
+#[test]
+fn without_source_there_are_no_lines() {
+    let policy: ReaperPolicy = POLICY.parse().unwrap();
+    let mutants = mutants(&policy, None);
+    assert_eq!(mutants.len(), 12);
+    assert!(mutants.iter().all(|m| m.line.is_none()));
+}
+
+fn store() -> Arc<DataStore> {
+    let s = Arc::new(DataStore::new());
+    let data = json!({
+        "entities": [
+            {"id": "ann", "type": "user", "attributes": {"status": "active", "clearance": 3}},
+            {"id": "doc", "type": "resource", "attributes": {}}
+        ]
+    });
+    DataLoader::new((*s).clone())
+        .load_json(&data.to_string())
+        .unwrap();
+    s
+}
+
+fn decide(policy: ReaperPolicy) -> PolicyAction {
+    let mut context = HashMap::new();
+    context.insert("principal".to_string(), "ann".into());
+    let request = PolicyRequest {
+        resource: "doc".to_string(),
+        action: "read".to_string(),
+        context,
+        ..Default::default()
+    };
+    policy.build(store()).unwrap().evaluate(&request).unwrap()
+}
+
+/// ann sits exactly on the boundary (clearance 3), so the `>=` → `>`,
+/// `3` → `4` and `trusted`'s `2` → `3` mutants deny her; the others leave
+/// her allowed, i.e. a suite made of only this request lets them survive.
+#[test]
+fn mutants_build_and_change_the_decision() {
+    let original: ReaperPolicy = POLICY.parse().unwrap();
+    assert_eq!(decide(original), PolicyAction::Allow);
+
+    let denied: Vec<String> = all()
+        .iter()
+        .filter(|m| decide(m.policy()) == PolicyAction::Deny)
+        .map(|m| m.replacement.clone())
+        .collect();
+    assert_eq!(
+        denied,
+        vec![
+            r#"user.status != "suspended""#,
+            "deny",
+            "user.clearance > 3",
+            "user.clearance >= 4",
+            "level > 3",
+        ]
+    );
+}
+
+#[test]
+fn imported_helpers_are_left_to_their_library() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("lib.reap"),
+        "library helpers {\n    func senior(level) := level >= 5,\n}\n",
+    )
+    .unwrap();
+    let source = r#"import "lib.reap" as h
+
+policy uses_lib {
+    default: deny,
+
+    rule seniors {
+        allow if h::senior(user.level)
+    }
+}
+"#;
+    let path = dir.path().join("policy.reap");
+    std::fs::write(&path, source).unwrap();
+    let policy = ReaperPolicy::from_file(&path).unwrap();
+    let mutants = mutants(&policy, Some(source));
+    assert_eq!(
+        summary(&mutants),
+        vec![s(
+            "rule seniors",
+            MutationKind::FlipEffect,
+            "allow",
+            "deny",
+            7
+        )]
+    );
+}
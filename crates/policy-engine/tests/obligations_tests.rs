@@ -0,0 +1,274 @@
+//! Rule obligations and advice (`allow with obligations {...}`).
+//!
+//! A matching rule may hand the PEP structured instructions alongside the
+//! decision. These tests pin the grammar, that every evaluator shape surfaces
+//! the deciding rule's obligations identically, the `evaluate_set` combination
+//! (deny carries only its own; allows union, attributed policy wins a key
+//! clash), and the bundle wire format (obligation-free policies keep their old
+//! version so older engines still load them).
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use policy_engine::data::{DataLoader, DataStore};
+use policy_engine::reap::{PolicyBundle, ReapParser, ReaperPolicy};
+use policy_engine::{
+    EnhancedPolicy, PolicyAction, PolicyEngine, PolicyEvaluator, PolicyLanguage, PolicyRequest,
+};
+use serde_json::json;
+
+const POLICY: &str = r#"
+policy obligations_test {
+    default: deny,
+    rule block_guests {
+        deny with obligations {"reason_code": "guest"} if user.role == "guest"
+    }
+    rule analysts_masked {
+        allow
+            with obligations {"mask": ["ssn", "dob"], "log_level": "high"}
+            with advice {"banner": "PII redacted"}
+        if user.role == "analyst"
+    }
+    rule admins_allowed {
+        allow if user.role == "admin"
+    }
+}
+"#;
+
+fn store() -> Arc<DataStore> {
+    let s = Arc::new(DataStore::new());
+    let data = json!({
+        "entities": [
+            {"id": "alice", "type": "user", "attributes": {"role": "admin"}},
+            {"id": "ann", "type": "user", "attributes": {"role": "analyst"}},
+            {"id": "bob", "type": "user", "attributes": {"role": "guest"}},
+            {"id": "carol", "type": "user", "attributes": {"role": "viewer"}},
+            {"id": "res-1", "type": "resource", "attributes": {}}
+        ]
+    });
+    DataLoader::new((*s).clone())
+        .load_json(&data.to_string())
+        .unwrap();
+    s
+}
+
+fn req(principal: &str) -> PolicyRequest {
+    let mut context = HashMap::new();
+    context.insert("principal".to_string(), principal.into());
+    PolicyRequest {
+        resource: "res-1".to_string(),
+        action: "read".to_string(),
+        context,
+        ..Default::default()
+    }
+}
+
+fn deploy(engine: &PolicyEngine, name: &str, source: &str) -> policy_engine::PolicyId {
+    let mut p = EnhancedPolicy::new_with_language(
+        name.to_string(),
+        String::new(),
+        PolicyLanguage::ReaperDsl,
+        source.to_string(),
+    )
+    .unwrap();
+    p.build_evaluator_with_data(Some(store())).unwrap();
+    let id = p.id;
+    engine.deploy_policy(p).unwrap();
+    id
+}
+
+#[test]
+fn parses_obligations_and_advice_clauses() {
+    let policy = ReapParser::parse(POLICY).unwrap();
+    let rule = &policy.rules[1];
+    let o = rule.obligations.as_ref().expect("obligations parsed");
+    let keys: Vec<&str> = o.obligations.iter().map(|(k, _)| k.as_str()).collect();
+    assert_eq!(keys, ["mask", "log_level"]);
+    assert_eq!(o.advice.len(), 1);
+    assert!(policy.rules[2].obligations.is_none());
+}
+
+#[test]
+fn rejects_non_object_and_duplicate_clauses() {
+    let non_object = r#"
+policy p {
+    default: deny,
+    rule r { allow with obligations {"mask", "ssn"} if user.role == "admin" }
+}
+"#;
+    let err = ReaperPolicy::from_str(non_object).unwrap_err().to_string();
+    assert!(err.contains("object"), "unexpected error: {err}");
+
+    let duplicate = r#"
+policy p {
+    default: deny,
+    rule r {
+        allow with obligations {"a": 1} with obligations {"b": 2}
+        if user.role == "admin"
+    }
+}
+"#;
+    let err = ReaperPolicy::from_str(duplicate).unwrap_err().to_string();
+    assert!(err.contains("obligations"), "unexpected error: {err}");
+}
+
+#[test]
+fn compiled_and_ast_evaluators_agree_on_obligations() {
+    let policy = ReaperPolicy::from_str(POLICY).unwrap();
+    let compiled = policy.clone().build(store()).unwrap();
+    let ast = policy.build_ast_evaluator(store());
+
+    for principal in ["alice", "ann", "bob", "carol"] {
+        let c = compiled.evaluate_named(&req(principal)).unwrap();
+        let a = ast.evaluate_named(&req(principal)).unwrap();
+        assert_eq!(c.decision, a.decision, "decision parity for {principal}");
+        assert_eq!(
+            c.obligations, a.obligations,
+            "obligation parity for {principal}"
+        );
+    }
+
+    let ann = compiled.evaluate_named(&req("ann")).unwrap();
+    let o = ann.obligations.unwrap();
+    assert_eq!(o.obligations["mask"], json!(["ssn", "dob"]));
+    assert_eq!(o.obligations["log_level"], json!("high"));
+    assert_eq!(o.advice["banner"], json!("PII redacted"));
+
+    // A rule without clauses, and the default, carry nothing.
+    assert!(compiled
+        .evaluate_named(&req("alice"))
+        .unwrap()
+        .obligations
+        .is_none());
+    assert!(compiled
+        .evaluate_named(&req("carol"))
+        .unwrap()
+        .obligations
+        .is_none());
+}
+
+#[test]
+fn mixed_evaluator_surfaces_obligations_from_both_halves() {
+    // The float-threshold assignment forces the first rule onto the AST path.
+    let source = r#"
+policy mixed_obligations {
+    default: deny,
+    rule analysts_ast {
+        allow with obligations {"path": "ast"} if {
+            t := 0.5 &&
+            user.role == "analyst"
+        }
+    }
+    rule admins_compiled {
+        allow with obligations {"path": "compiled"} if user.role == "admin"
+    }
+}
+"#;
+    let policy = ReaperPolicy::from_str(source).unwrap();
+    let eval = policy.build_preferred(store()).unwrap();
+    assert_eq!(eval.evaluator_type(), "reaper_dsl_mixed");
+
+    let ann = eval.evaluate_named(&req("ann")).unwrap();
+    assert_eq!(ann.obligations.unwrap().obligations["path"], json!("ast"));
+    let alice = eval.evaluate_named(&req("alice")).unwrap();
+    assert_eq!(
+        alice.obligations.unwrap().obligations["path"],
+        json!("compiled")
+    );
+}
+
+#[test]
+fn single_policy_evaluate_carries_obligations() {
+    let engine = PolicyEngine::new();
+    let id = deploy(&engine, "obligations_test", POLICY);
+
+    let d = engine.evaluate(&id, &req("ann")).unwrap();
+    assert_eq!(d.decision, PolicyAction::Allow);
+    let o = d.obligations.unwrap();
+    assert_eq!(o.obligations["log_level"], json!("high"));
+
+    let d = engine.evaluate(&id, &req("bob")).unwrap();
+    assert_eq!(d.decision, PolicyAction::Deny);
+    assert_eq!(
+        d.obligations.unwrap().obligations["reason_code"],
+        json!("guest")
+    );
+}
+
+#[test]
+fn evaluate_set_unions_allow_obligations_and_isolates_deny() {
+    let engine = PolicyEngine::new();
+    let first = deploy(&engine, "obligations_test", POLICY);
+    let second = deploy(
+        &engine,
+        "audit_overlay",
+        r#"
+policy audit_overlay {
+    default: deny,
+    rule analysts_audited {
+        allow with obligations {"log_level": "debug", "audit": true}
+        if user.role == "analyst"
+    }
+    rule guests_throttled {
+        deny with obligations {"throttle": 10} if user.role == "guest"
+    }
+}
+"#,
+    );
+
+    // Both allow: keys union, and the attributed (first) policy wins a clash.
+    let outcome = engine.evaluate_set(&[first, second], &req("ann"));
+    assert_eq!(outcome.decision, PolicyAction::Allow);
+    assert_eq!(outcome.policy_id, first);
+    let o = outcome.obligations.unwrap();
+    assert_eq!(o.obligations["log_level"], json!("high"));
+    assert_eq!(o.obligations["audit"], json!(true));
+    assert_eq!(o.obligations["mask"], json!(["ssn", "dob"]));
+
+    // Deny: only the denying rule's obligations, never an allow's.
+    let outcome = engine.evaluate_set(&[first, second], &req("bob"));
+    assert_eq!(outcome.decision, PolicyAction::Deny);
+    let o = outcome.obligations.unwrap();
+    assert_eq!(o.obligations.len(), 1);
+    assert!(o.obligations.contains_key("reason_code"));
+
+    // Nothing matched: no obligations.
+    let outcome = engine.evaluate_set(&[first, second], &req("carol"));
+    assert!(outcome.obligations.is_none());
+}
+
+#[test]
+fn bundle_roundtrips_obligations_and_keeps_old_version_without_them() {
+    let policy = ReaperPolicy::from_str(POLICY).unwrap();
+    let bytes = policy.compile_to_bundle().unwrap();
+    assert_eq!(
+        PolicyBundle::from_bytes(&bytes).unwrap().metadata.version,
+        4
+    );
+
+    let eval = ReaperPolicy::from_bundle(&bytes, store()).unwrap();
+    let ann = eval.evaluate_named(&req("ann")).unwrap();
+    assert_eq!(
+        ann.obligations.unwrap().advice["banner"],
+        json!("PII redacted")
+    );
+
+    let plain = ReaperPolicy::from_str(
+        r#"
+policy plain {
+    default: deny,
+    rule admins { allow if user.role == "admin" }
+}
+"#,
+    )
+    .unwrap();
+    let bytes = plain.compile_to_bundle().unwrap();
+    assert_eq!(
+        PolicyBundle::from_bytes(&bytes).unwrap().metadata.version,
+        2,
+        "obligation-free policies must stay loadable by older engines"
+    );
+}
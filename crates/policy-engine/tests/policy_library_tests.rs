@@ -41,7 +41,7 @@ struct Case {
     violations: Option<Vec<String>>,
     /// Extra request context entries (e.g. a support ticket id).
     #[serde(default)]
-    context: Option<HashMap<String, String>>,
+    context: Option<HashMap<String, serde_json::Value>>,
 }
 
 fn library_root() -> PathBuf {
@@ -137,7 +137,10 @@ fn every_library_scenario_meets_its_manifest() {
                 let mut context = case.context.clone().unwrap_or_default();
                 context.insert(
                     "principal".to_string(),
-                    case.principal.clone().expect("authz case needs principal"),
+                    case.principal
+                        .clone()
+                        .expect("authz case needs principal")
+                        .into(),
                 );
                 let request = PolicyRequest {
                     resource: case.resource.clone().expect("authz case needs resource"),
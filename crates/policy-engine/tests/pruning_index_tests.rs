@@ -351,7 +351,7 @@ fn dsl_policy_with_store(name: &str, rule_body: &str, store: &Arc<DataStore>) ->
 /// evaluator to resolve the principal) plus the given action.
 fn dsl_request(resource: &str, action: &str) -> PolicyRequest {
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "alice".to_string());
+    context.insert("principal".to_string(), "alice".into());
     PolicyRequest {
         resource: resource.to_string(),
         action: action.to_string(),
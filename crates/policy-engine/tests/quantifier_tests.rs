@@ -0,0 +1,254 @@
+//! `some`/`every` quantifier blocks: parsing and formatting, named index
+//! bindings, block scoping, the empty/missing collection contract, the
+//! nesting limit, and decision parity between the interpreter and the
+//! compiled evaluator.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use policy_engine::reap::format::format_source;
+use policy_engine::reap::{ReapParser, ReaperPolicy};
+use policy_engine::{DataLoader, DataStore, PolicyAction, PolicyRequest};
+use reaper_core::ReaperError;
+use serde_json::json;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+fn store() -> Arc<DataStore> {
+    let s = Arc::new(DataStore::new());
+    let data = json!({
+        "entities": [
+            {"id": "alice", "type": "user",
+             "attributes": {"roles": ["dev", "oncall"], "clearance": 3}},
+            {"id": "site-doc", "type": "resource",
+             "attributes": {
+                 "sites": ["eu-1", "eu-2"],
+                 "labels": {"env": "prod", "team": "core"},
+                 "tags": []
+             }}
+        ]
+    });
+    DataLoader::new((*s).clone())
+        .load_json(&data.to_string())
+        .expect("load");
+    s
+}
+
+fn request() -> PolicyRequest {
+    let mut context = HashMap::new();
+    context.insert("principal".to_string(), "alice".into());
+    PolicyRequest {
+        resource: "site-doc".to_string(),
+        action: "read".to_string(),
+        context,
+        ..Default::default()
+    }
+}
+
+fn policy(condition: &str) -> String {
+    format!(
+        "policy q {{\n    default: deny,\n    rule r {{\n        allow if {{\n            {condition}\n        }}\n    }}\n}}\n"
+    )
+}
+
+/// Decide `condition` on the interpreter and, when `compiled`, on the
+/// compiled evaluator too, asserting both agree.
+fn decide(condition: &str, doc: Option<&serde_json::Value>, compiled: bool) -> PolicyAction {
+    let text = policy(condition);
+    let parsed = ReaperPolicy::from_str(&text).expect("parse");
+    let (ast, _) = parsed
+        .clone()
+        .build_ast_evaluator(store())
+        .evaluate_with_input_named(&request(), doc)
+        .expect("ast evaluate");
+    let built = parsed.build(store());
+    if compiled {
+        let (dec, _) = built
+            .expect("rule compiles")
+            .evaluate_with_input_named(&request(), doc)
+            .expect("compiled evaluate");
+        assert_eq!(dec, ast, "evaluators diverged on {condition} / {doc:?}");
+    } else {
+        assert!(built.is_err(), "expected AST fallback for {condition}");
+    }
+    ast
+}
+
+#[test]
+fn quantifiers_parse_and_format_canonically() {
+    let src = policy(
+        r#"some i,s in resource.sites[_] { s.startswith("eu") && i >= 0 } && every x in input.items{x.ok == true}"#,
+    );
+    let formatted = format_source(&src).unwrap();
+    assert!(formatted.contains(r#"some i, s in resource.sites { s.startswith("eu") && i >= 0 }"#));
+    assert!(formatted.contains("every x in input.items { x.ok == true }"));
+    assert_eq!(format_source(&formatted).unwrap(), formatted);
+
+    // The keywords are only keywords before a binding: `something` and
+    // `everyone` stay identifiers.
+    assert!(ReapParser::parse(&policy("something := 1 && everyone := something")).is_ok());
+
+    let err = ReapParser::parse(&policy("some x, x in resource.sites { true }")).unwrap_err();
+    assert!(err.to_string().contains("x"), "{err}");
+}
+
+#[test]
+fn some_and_every_agree_across_evaluators() {
+    let cases = [
+        (
+            r#"some s in resource.sites { s == "eu-2" }"#,
+            PolicyAction::Allow,
+        ),
+        (
+            r#"some s in resource.sites { s == "us-1" }"#,
+            PolicyAction::Deny,
+        ),
+        (
+            r#"every s in resource.sites { s.startswith("eu") }"#,
+            PolicyAction::Allow,
+        ),
+        (
+            r#"every s in resource.sites { s == "eu-1" }"#,
+            PolicyAction::Deny,
+        ),
+        (
+            r#"some r in user.roles { r == "oncall" } && user.clearance > 2"#,
+            PolicyAction::Allow,
+        ),
+        (
+            r#"!(every r in user.roles { r == "dev" })"#,
+            PolicyAction::Allow,
+        ),
+    ];
+    for (condition, expected) in cases {
+        assert_eq!(decide(condition, None, true), expected, "{condition}");
+    }
+}
+
+#[test]
+fn index_binds_position_key_or_element() {
+    assert_eq!(
+        decide(
+            r#"some i, s in resource.sites { i == 1 && s == "eu-2" }"#,
+            None,
+            true
+        ),
+        PolicyAction::Allow
+    );
+    assert_eq!(
+        decide(
+            r#"some i, s in resource.sites { i == 0 && s == "eu-2" }"#,
+            None,
+            true
+        ),
+        PolicyAction::Deny
+    );
+    assert_eq!(
+        decide(
+            r#"some k, v in resource.labels { k == "env" && v == "prod" }"#,
+            None,
+            true
+        ),
+        PolicyAction::Allow
+    );
+
+    let doc = json!({"containers": [
+        {"name": "app", "image": "registry.local/app"},
+        {"name": "sidecar", "image": "docker.io/proxy"}
+    ]});
+    assert_eq!(
+        decide(
+            r#"every i, c in input.containers { c.image.startswith("registry.local/") || i > 0 }"#,
+            Some(&doc),
+            true
+        ),
+        PolicyAction::Allow
+    );
+    assert_eq!(
+        decide(
+            r#"every c in input.containers { c.image.startswith("registry.local/") }"#,
+            Some(&doc),
+            true
+        ),
+        PolicyAction::Deny
+    );
+}
+
+#[test]
+fn empty_and_missing_collections() {
+    // Empty: `every` holds vacuously, `some` does not.
+    assert_eq!(
+        decide(r#"every t in resource.tags { t == "x" }"#, None, true),
+        PolicyAction::Allow
+    );
+    assert_eq!(
+        decide(r#"some t in resource.tags { t == "x" }"#, None, true),
+        PolicyAction::Deny
+    );
+    // Missing or not a collection: both fail, so `every` never passes on
+    // absent input.
+    for condition in [
+        r#"every t in resource.nothing { t == "x" }"#,
+        r#"every c in input.containers { c.ok == true }"#,
+        r#"every c in input.name { c.ok == true }"#,
+    ] {
+        assert_eq!(
+            decide(condition, Some(&json!({"name": "n"})), true),
+            PolicyAction::Deny
+        );
+        assert_eq!(decide(condition, None, true), PolicyAction::Deny);
+    }
+}
+
+#[test]
+fn bindings_are_scoped_to_the_block() {
+    // Variables over a bound collection compile; nested blocks see the
+    // outer binding.
+    assert_eq!(
+        decide(
+            r#"sites := resource.sites && every s in sites { some r in user.roles { r == "dev" } && s != "" }"#,
+            None,
+            true
+        ),
+        PolicyAction::Allow
+    );
+    // Nothing bound inside the block is visible after it.
+    let text = policy(r#"(some s in resource.sites { inner := s }) && inner == "eu-1""#);
+    let evaluator = ReaperPolicy::from_str(&text)
+        .unwrap()
+        .build_ast_evaluator(store());
+    let result = evaluator.evaluate_with_input_named(&request(), None);
+    assert!(
+        result.is_err(),
+        "`inner` must not escape the block: {result:?}"
+    );
+}
+
+#[test]
+fn unsupported_sources_fall_back_to_the_interpreter() {
+    assert_eq!(
+        decide(r#"some v in context.values { v == 1 }"#, None, false),
+        PolicyAction::Deny
+    );
+    // A variable that may be unbound: the interpreter errors, so the rule
+    // must not compile to a shape that reads it as absent.
+    let text =
+        policy(r#"(user.clearance > 5 || sites := resource.sites) && some s in sites { true }"#);
+    assert!(ReaperPolicy::from_str(&text)
+        .unwrap()
+        .build(store())
+        .is_err());
+}
+
+#[test]
+fn nested_blocks_count_toward_the_depth_limit() {
+    let mut condition = "true".to_string();
+    for _ in 0..200 {
+        condition = format!("some x in resource.sites {{ {condition} }}");
+    }
+    let result = ReapParser::parse(&policy(&condition));
+    assert!(
+        matches!(result, Err(ReaperError::InvalidPolicy { .. })),
+        "expected typed InvalidPolicy, got {result:?}"
+    );
+}
@@ -0,0 +1,296 @@
+//! Conditional relationship edges: an expiry and/or a caveat over the request
+//! context, honoured by `rebac::related`/`reachable`/`inherited`/`check` on
+//! both evaluators without a data republish.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use policy_engine::data::{lookup_resources, EntitySchema, LookupOptions};
+use policy_engine::filter::{filter_ids, FilterQuery, FilterScope, IdsOptions};
+use policy_engine::reap::ReaperPolicy;
+use policy_engine::{
+    DataLoader, DataStore, EnhancedPolicy, PolicyAction, PolicyEngine, PolicyEvaluator,
+    PolicyLanguage, PolicyRequest,
+};
+use serde_json::json;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+const PAST: &str = "2020-01-01T00:00:00Z";
+const FUTURE: &str = "2999-01-01T00:00:00Z";
+
+const POLICY: &str = r#"
+policy incident {
+    default: deny,
+    rule direct { allow if action == "read" && rebac::related(user, "responder", resource) }
+    rule via_team {
+        allow if action == "write" && rebac::reachable(user, "responder", resource, "member_of", 3)
+    }
+    rule via_folder {
+        allow if action == "delete" && rebac::inherited(user, "owner", resource, "parent", 3)
+    }
+    rule schema { allow if action == "view" && rebac::check(user, "viewer", resource) }
+}
+"#;
+
+fn store() -> Arc<DataStore> {
+    let data = json!({"entities": [
+        {"id": "alice", "type": "User", "attributes": {}},
+        {"id": "bob", "type": "User", "attributes": {},
+         "relationships": {"member_of": [{"subject": "oncall", "expires_at": PAST}]}},
+        {"id": "carol", "type": "User", "attributes": {},
+         "relationships": {"member_of": [{"subject": "oncall", "expires_at": FUTURE}]}},
+        {"id": "dave", "type": "User", "attributes": {}},
+        {"id": "erin", "type": "User", "attributes": {}},
+        {"id": "frank", "type": "User", "attributes": {}},
+        {"id": "oncall", "type": "Group", "attributes": {}},
+        {"id": "root", "type": "Folder", "attributes": {}, "relationships": {"owner": [
+            {"subject": "alice", "caveat": "context.ip_in_office == true"},
+            {"subject": "bob", "expires_at": PAST}
+        ]}},
+        {"id": "db-prod", "type": "Document", "attributes": {}, "relationships": {
+            "parent": ["root"],
+            "responder": [
+                "oncall",
+                {"subject": "alice", "expires_at": FUTURE, "caveat": "context.ip_in_office == true"},
+                {"subject": "bob", "expires_at": PAST},
+                {"subject": "dave", "caveat": "context.ticket.startswith(\"INC-\")"}
+            ],
+            "viewer": [{"subject": "erin", "expires_at": PAST}, {"subject": "frank", "expires_at": FUTURE}]
+        }}
+    ]});
+    let store = Arc::new(DataStore::new());
+    DataLoader::new((*store).clone())
+        .load_json(&data.to_string())
+        .expect("load");
+    store
+        .relationships()
+        .set_schema(Some(
+            &EntitySchema::from_json(
+                r#"{"entity_types": {"User": {}, "Group": {}, "Folder": {}, "Document": {
+                    "relations": {"responder": [], "viewer": [], "parent": []},
+                    "rewrites": {"viewer": "viewer + responder"}
+                }}}"#,
+            )
+            .unwrap(),
+        ))
+        .unwrap();
+    store
+}
+
+fn request(principal: &str, action: &str, context: serde_json::Value) -> PolicyRequest {
+    let mut map: HashMap<String, serde_json::Value> =
+        serde_json::from_value(context).unwrap_or_default();
+    map.insert("principal".to_string(), principal.into());
+    PolicyRequest {
+        resource: "db-prod".to_string(),
+        action: action.to_string(),
+        context: map,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn expired_edges_and_failed_caveats_grant_nothing_on_either_evaluator() {
+    let store = store();
+    let parsed = ReaperPolicy::from_str(POLICY).unwrap();
+    let compiled = parsed.clone().build(store.clone()).expect("compiled path");
+    let ast = parsed.build_ast_evaluator(store);
+
+    let office = json!({"ip_in_office": true});
+    let home = json!({"ip_in_office": false});
+    for (principal, action, context, allowed) in [
+        // Caveat holds, expiry in the future.
+        ("alice", "read", office.clone(), true),
+        ("alice", "read", home.clone(), false),
+        ("alice", "read", json!({}), false),
+        // Expired.
+        ("bob", "read", office.clone(), false),
+        // Caveat over a context string.
+        ("dave", "read", json!({"ticket": "INC-42"}), true),
+        ("dave", "read", json!({"ticket": "CHG-7"}), false),
+        ("dave", "read", json!({"ticket": 42}), false),
+        // Reachable: the membership edge expired for bob, not for carol.
+        ("carol", "write", json!({}), true),
+        ("bob", "write", json!({}), false),
+        // Inherited: a caveated owner on the parent folder.
+        ("alice", "delete", office.clone(), true),
+        ("alice", "delete", home.clone(), false),
+        ("bob", "delete", office.clone(), false),
+        // Rewrites: viewer includes responder; erin's viewer edge expired.
+        ("frank", "view", json!({}), true),
+        ("erin", "view", json!({}), false),
+        ("alice", "view", office, true),
+        ("alice", "view", home, false),
+    ] {
+        let req = request(principal, action, context.clone());
+        let c = compiled.evaluate(&req).unwrap();
+        let a = ast.evaluate(&req).unwrap();
+        assert_eq!(c, a, "evaluators diverged: {principal} {action} {context}");
+        assert_eq!(
+            c == PolicyAction::Allow,
+            allowed,
+            "{principal} {action} {context}"
+        );
+    }
+}
+
+#[test]
+fn caveats_fail_closed_outside_an_evaluation() {
+    let store = store();
+    let interner = store.interner();
+    let (db, responder) = (interner.intern("db-prod"), interner.intern("responder"));
+    let graph = store.relationships();
+    // Stored, but neither expired nor context-free.
+    assert!(!graph.has_relation(db, responder, interner.intern("alice")));
+    assert!(!graph.has_relation(db, responder, interner.intern("bob")));
+    assert!(graph.has_relation(db, responder, interner.intern("oncall")));
+    assert_eq!(graph.conditional_len(), 9);
+
+    // With a context installed, the caveat decides.
+    let context = HashMap::from([("ip_in_office".to_string(), json!(true))]);
+    {
+        let _scope = graph.caveat_scope(&context, None);
+        assert!(graph.has_relation(db, responder, interner.intern("alice")));
+    }
+    assert!(!graph.has_relation(db, responder, interner.intern("alice")));
+
+    // Lookups run without a request context: only unconditional and
+    // unexpired edges.
+    let all = LookupOptions {
+        after: None,
+        limit: 10,
+    };
+    assert_eq!(
+        lookup_resources(&store, "frank", "viewer", "Document", &all).ids,
+        ["db-prod"]
+    );
+    assert!(lookup_resources(&store, "erin", "viewer", "Document", &all)
+        .ids
+        .is_empty());
+}
+
+#[test]
+fn filter_lists_caveated_grants_the_point_check_allows() {
+    let store = store();
+    let engine = PolicyEngine::new();
+    let mut policy = EnhancedPolicy::new_with_language(
+        "incident".to_string(),
+        String::new(),
+        PolicyLanguage::ReaperDsl,
+        POLICY.to_string(),
+    )
+    .unwrap();
+    policy
+        .build_evaluator_with_data(Some(store.clone()))
+        .unwrap();
+    let id = policy.id;
+    engine.deploy_policy(policy).unwrap();
+
+    for (principal, context, listed) in [
+        ("dave", json!({"ticket": "INC-42"}), true),
+        ("dave", json!({"ticket": "CHG-7"}), false),
+        ("alice", json!({"ip_in_office": true}), true),
+        ("alice", json!({}), false),
+        ("bob", json!({"ip_in_office": true}), false),
+    ] {
+        let query = FilterQuery {
+            principal: principal.to_string(),
+            action: "read".to_string(),
+            resource_type: "Document".to_string(),
+            context: serde_json::from_value(context.clone()).unwrap(),
+            actor: None,
+            context_provenance: None,
+            scope: FilterScope::Policies(vec![id]),
+        };
+        let page = filter_ids(
+            &engine,
+            &store,
+            &query,
+            &IdsOptions {
+                after: None,
+                limit: 10,
+                max_candidates: 100,
+            },
+        )
+        .unwrap();
+        let allowed = engine
+            .evaluate_set(&[id], &query.request_for("db-prod"))
+            .decision
+            == PolicyAction::Allow;
+        assert_eq!(allowed, listed, "{principal} {context}");
+        assert_eq!(
+            page.ids == ["db-prod"],
+            listed,
+            "{principal} {context}: {:?}",
+            page.ids
+        );
+    }
+}
+
+#[test]
+fn re_adding_an_edge_replaces_its_condition() {
+    let store = store();
+    let interner = store.interner();
+    let (db, responder, bob) = (
+        interner.intern("db-prod"),
+        interner.intern("responder"),
+        interner.intern("bob"),
+    );
+    let graph = store.relationships();
+    assert!(!graph.has_relation(db, responder, bob));
+    // Extending an expired grant: the edge is visible again.
+    graph
+        .add_conditional_edge(db, responder, bob, Some(i64::MAX), None)
+        .unwrap();
+    assert!(graph.has_relation(db, responder, bob));
+    graph
+        .add_conditional_edge(db, responder, bob, Some(0), None)
+        .unwrap();
+    assert!(!graph.has_relation(db, responder, bob));
+    // An unconditional add lifts the condition entirely.
+    graph.add_edge(db, responder, bob);
+    assert!(graph.has_relation(db, responder, bob));
+    let before = graph.conditional_len();
+    graph.remove_edge(db, responder, interner.intern("alice"));
+    assert_eq!(graph.conditional_len(), before - 1);
+}
+
+#[test]
+fn malformed_caveats_and_subjects_reject_the_document() {
+    for subjects in [
+        json!([{"subject": "alice", "caveat": "context.x =="}]),
+        json!([{"subject": "alice", "expires_at": "tomorrow"}]),
+        json!([{"subject": "alice", "until": FUTURE}]),
+    ] {
+        let store = DataStore::new();
+        let data = json!({"entities": [
+            {"id": "doc", "type": "Document", "attributes": {},
+             "relationships": {"viewer": subjects}},
+            {"id": "alice", "type": "User", "attributes": {}}
+        ]});
+        let loaded = DataLoader::new(store.clone()).load_json(&data.to_string());
+        assert!(loaded.is_err(), "{subjects} must not load");
+        assert!(store.relationships().is_empty(), "{subjects} left edges");
+        assert_eq!(store.stats().total_entities, 0, "{subjects} left entities");
+    }
+}
+
+#[test]
+fn upserts_replace_the_carried_conditions() {
+    let store = store();
+    let loader = DataLoader::new((*store).clone());
+    loader
+        .upsert_entity_doc(&json!({
+            "id": "db-prod", "type": "Document", "attributes": {},
+            "relationships": {"responder": [{"subject": "bob", "expires_at": FUTURE}]}
+        }))
+        .unwrap();
+    let interner = store.interner();
+    let graph = store.relationships();
+    let (db, responder) = (interner.intern("db-prod"), interner.intern("responder"));
+    assert!(graph.has_relation(db, responder, interner.intern("bob")));
+    assert!(!graph.has_relation(db, responder, interner.intern("oncall")));
+    // db-prod's five other conditional edges went with its old document.
+    assert_eq!(graph.conditional_len(), 5);
+}
@@ -0,0 +1,219 @@
+//! ReBAC lookups outside a policy: lookup-resources, lookup-subjects and
+//! expand agree with `rebac::check`, page by keyset cursor and stay within
+//! the traversal budget.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use policy_engine::data::{
+    expand, lookup_resources, lookup_subjects, DataLoader, DataStore, EntitySchema, ExpandNode,
+    LookupOptions,
+};
+use std::sync::Arc;
+
+const SCHEMA: &str = r#"{
+    "entity_types": {
+        "User": {},
+        "Group": {
+            "relations": {"member": ["User", "Group"]},
+            "rewrites": {"member": "member + member from member"}
+        },
+        "Folder": {
+            "relations": {"owner": ["User"], "viewer": ["User", "Group"], "parent": ["Folder"]},
+            "rewrites": {
+                "viewer": "viewer + owner + viewer from parent + member from viewer"
+            }
+        },
+        "Document": {
+            "relations": {
+                "owner": ["User"], "editor": ["User", "Group"], "viewer": ["User", "Group"],
+                "blocked": ["User"], "parent": ["Folder"]
+            },
+            "rewrites": {
+                "editor": "editor + owner + member from editor",
+                "viewer": "(viewer + editor + viewer from parent + member from viewer) - blocked"
+            }
+        }
+    }
+}"#;
+
+const DATA: &str = r#"{"entities": [
+    {"id": "alice", "type": "User", "attributes": {}},
+    {"id": "bob", "type": "User", "attributes": {}},
+    {"id": "carol", "type": "User", "attributes": {}},
+    {"id": "dave", "type": "User", "attributes": {}},
+    {"id": "erin", "type": "User", "attributes": {}},
+    {"id": "eng", "type": "Group", "attributes": {}, "relationships": {"member": ["carol", "platform"]}},
+    {"id": "platform", "type": "Group", "attributes": {}, "relationships": {"member": ["erin"]}},
+    {"id": "root", "type": "Folder", "attributes": {}, "relationships": {"owner": ["alice"]}},
+    {"id": "specs", "type": "Folder", "attributes": {}, "relationships": {"parent": ["root"], "viewer": ["eng"]}},
+    {"id": "design", "type": "Document", "attributes": {}, "relationships": {
+        "parent": ["specs"], "editor": ["bob", "dave"], "blocked": ["dave"]
+    }},
+    {"id": "notes", "type": "Document", "attributes": {}, "relationships": {"owner": ["bob"]}},
+    {"id": "roadmap", "type": "Document", "attributes": {}, "relationships": {"parent": ["root"]}}
+]}"#;
+
+fn store(with_rewrites: bool) -> Arc<DataStore> {
+    let store = Arc::new(DataStore::new());
+    let schema = EntitySchema::from_json(SCHEMA).unwrap();
+    DataLoader::new((*store).clone())
+        .with_schema(Arc::new(schema.clone()))
+        .load_json(DATA)
+        .expect("load");
+    if with_rewrites {
+        store.relationships().set_schema(Some(&schema)).unwrap();
+    }
+    store
+}
+
+fn all() -> LookupOptions {
+    LookupOptions {
+        after: None,
+        limit: 100,
+    }
+}
+
+#[test]
+fn lookup_resources_follows_the_rewrites() {
+    let store = store(true);
+    let documents =
+        |subject, relation| lookup_resources(&store, subject, relation, "Document", &all()).ids;
+    assert_eq!(documents("alice", "viewer"), ["design", "roadmap"]);
+    assert_eq!(documents("bob", "viewer"), ["design", "notes"]);
+    assert_eq!(documents("bob", "editor"), ["design", "notes"]);
+    // Two groups deep, through the parent folder's group viewer.
+    assert_eq!(documents("erin", "viewer"), ["design"]);
+    // Blocked from viewing, still an editor.
+    assert!(documents("dave", "viewer").is_empty());
+    assert_eq!(documents("dave", "editor"), ["design"]);
+    assert!(documents("nobody", "viewer").is_empty());
+
+    let folders = lookup_resources(&store, "erin", "viewer", "Folder", &all());
+    assert_eq!(folders.ids, ["specs"]);
+    assert!(!folders.truncated);
+}
+
+#[test]
+fn lookup_subjects_follows_the_rewrites() {
+    let store = store(true);
+    let users =
+        |object, relation| lookup_subjects(&store, object, relation, Some("User"), &all()).ids;
+    assert_eq!(users("design", "viewer"), ["alice", "bob", "carol", "erin"]);
+    assert_eq!(users("design", "editor"), ["bob", "dave"]);
+    assert_eq!(users("roadmap", "viewer"), ["alice"]);
+    assert!(users("missing", "viewer").is_empty());
+
+    // Without a type filter, the group viewer of the parent folder is a
+    // subject too.
+    let any = lookup_subjects(&store, "design", "viewer", None, &all());
+    assert_eq!(
+        any.ids,
+        ["alice", "bob", "carol", "eng", "erin", "platform"]
+    );
+}
+
+#[test]
+fn without_rewrites_lookups_are_the_stored_edges() {
+    let store = store(false);
+    let documents = lookup_resources(&store, "bob", "editor", "Document", &all());
+    assert_eq!(documents.ids, ["design"]);
+    let subjects = lookup_subjects(&store, "design", "editor", None, &all());
+    assert_eq!(subjects.ids, ["bob", "dave"]);
+    assert!(lookup_subjects(&store, "design", "viewer", None, &all())
+        .ids
+        .is_empty());
+}
+
+#[test]
+fn lookups_page_by_cursor() {
+    let store = store(true);
+    let page = |after: Option<String>| {
+        lookup_subjects(
+            &store,
+            "design",
+            "viewer",
+            Some("User"),
+            &LookupOptions { after, limit: 3 },
+        )
+    };
+    let first = page(None);
+    assert_eq!(first.ids, ["alice", "bob", "carol"]);
+    assert_eq!(first.next_cursor.as_deref(), Some("carol"));
+    let second = page(first.next_cursor);
+    assert_eq!(second.ids, ["erin"]);
+    assert_eq!(second.next_cursor, None);
+}
+
+#[test]
+fn expand_shows_the_granting_path() {
+    let store = store(true);
+    let full = expand(&store, "design", "viewer", None);
+    assert_eq!(full.granted, None);
+    assert!(!full.truncated);
+    let ExpandNode::Rewrite { definition, .. } = &full.tree else {
+        panic!("viewer is rewritten: {:?}", full.tree);
+    };
+    assert!(matches!(**definition, ExpandNode::Exclusion { .. }));
+
+    // erin: design → parent specs → viewer eng → member platform → erin.
+    let path = expand(&store, "design", "viewer", Some("erin"));
+    assert_eq!(path.granted, Some(true));
+    let json = serde_json::to_string(&path.tree).unwrap();
+    for step in ["\"specs\"", "\"eng\"", "\"platform\"", "\"erin\""] {
+        assert!(json.contains(step), "{step} missing from {json}");
+    }
+    assert!(!json.contains("\"bob\""), "{json}");
+
+    // dave is an editor but blocked: the path is there, the verdict is not.
+    let blocked = expand(&store, "design", "viewer", Some("dave"));
+    assert_eq!(blocked.granted, Some(false));
+    assert!(matches!(
+        blocked.tree,
+        ExpandNode::Rewrite { ref definition, .. }
+            if matches!(**definition, ExpandNode::Exclusion { .. })
+    ));
+
+    let direct = expand(&store, "design", "blocked", None);
+    assert_eq!(
+        direct.tree,
+        ExpandNode::Direct {
+            object: "design".to_string(),
+            relation: "blocked".to_string(),
+            subjects: vec!["dave".to_string()],
+        }
+    );
+}
+
+#[test]
+fn lookups_are_bounded_by_the_traversal_budget() {
+    // A group chain longer than one evaluation may walk.
+    let store = Arc::new(DataStore::new());
+    let schema = EntitySchema::from_json(
+        r#"{"entity_types": {"User": {}, "Group": {
+            "relations": {"member": []},
+            "rewrites": {"member": "member + member from member"}
+        }}}"#,
+    )
+    .unwrap();
+    let mut entities = vec![r#"{"id": "u", "type": "User", "attributes": {}}"#.to_string()];
+    entities.push(
+        r#"{"id": "g0", "type": "Group", "attributes": {}, "relationships": {"member": ["u"]}}"#
+            .to_string(),
+    );
+    for i in 1..20_000 {
+        entities.push(format!(
+            r#"{{"id": "g{i}", "type": "Group", "attributes": {{}}, "relationships": {{"member": ["g{}"]}}}}"#,
+            i - 1
+        ));
+    }
+    DataLoader::new((*store).clone())
+        .load_json(&format!(r#"{{"entities": [{}]}}"#, entities.join(",")))
+        .unwrap();
+    store.relationships().set_schema(Some(&schema)).unwrap();
+
+    let page = lookup_resources(&store, "u", "member", "Group", &all());
+    assert!(page.truncated);
+    assert!(page.candidates < 20_000);
+    // Near groups are found; each is verified within its own budget.
+    assert!(page.ids.iter().any(|id| id == "g0"));
+}
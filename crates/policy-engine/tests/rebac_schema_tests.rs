@@ -0,0 +1,230 @@
+//! Declarative ReBAC: relation rewrites in the entity schema, expanded by
+//! `rebac::check` on both evaluators, carried by bundles (wire version 6)
+//! and type-checked like the other `rebac::` functions.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use policy_engine::data::{DataLoader, DataStore, EntitySchema};
+use policy_engine::reap::typecheck::check_policy;
+use policy_engine::reap::{PolicyBundle, ReapParser, ReaperPolicy};
+use policy_engine::{PolicyAction, PolicyEvaluator, PolicyRequest};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Google-Drive-style sharing, declared once.
+const SCHEMA: &str = r#"{
+    "entity_types": {
+        "User": {},
+        "Group": {
+            "relations": {"member": ["User", "Group"]},
+            "rewrites": {"member": "member + member from member"}
+        },
+        "Folder": {
+            "relations": {"owner": ["User"], "viewer": ["User", "Group"], "parent": ["Folder"]},
+            "rewrites": {
+                "viewer": "viewer + owner + viewer from parent + member from viewer"
+            }
+        },
+        "Document": {
+            "relations": {
+                "owner": ["User"], "editor": ["User", "Group"], "viewer": ["User", "Group"],
+                "blocked": ["User"], "parent": ["Folder"]
+            },
+            "rewrites": {
+                "editor": "editor + owner + member from editor",
+                "viewer": "(viewer + editor + viewer from parent + member from viewer) - blocked",
+                "comment": "viewer & editor"
+            }
+        }
+    },
+    "request": {"user": ["User"], "resource": ["Document", "Folder"]}
+}"#;
+
+const DATA: &str = r#"{"entities": [
+    {"id": "alice", "type": "User", "attributes": {}},
+    {"id": "bob", "type": "User", "attributes": {}},
+    {"id": "carol", "type": "User", "attributes": {}},
+    {"id": "dave", "type": "User", "attributes": {}},
+    {"id": "erin", "type": "User", "attributes": {}},
+    {"id": "eng", "type": "Group", "attributes": {}, "relationships": {"member": ["carol", "platform"]}},
+    {"id": "platform", "type": "Group", "attributes": {}, "relationships": {"member": ["erin"]}},
+    {"id": "root", "type": "Folder", "attributes": {}, "relationships": {"owner": ["alice"]}},
+    {"id": "specs", "type": "Folder", "attributes": {}, "relationships": {"parent": ["root"], "viewer": ["eng"]}},
+    {"id": "design", "type": "Document", "attributes": {}, "relationships": {
+        "parent": ["specs"], "editor": ["bob", "dave"], "blocked": ["dave"]
+    }}
+]}"#;
+
+const POLICY: &str = r#"
+policy drive {
+    default: deny,
+    rule view { allow if action == "read" && rebac::check(user, "viewer", resource) }
+    rule edit { allow if action == "write" && rebac::check(user, "editor", resource) }
+    rule comment { allow if action == "comment" && rebac::check(user, "comment", resource) }
+}
+"#;
+
+fn schema() -> EntitySchema {
+    EntitySchema::from_json(SCHEMA).unwrap()
+}
+
+fn store() -> Arc<DataStore> {
+    let store = Arc::new(DataStore::new());
+    let schema = schema();
+    DataLoader::new((*store).clone())
+        .with_schema(Arc::new(schema.clone()))
+        .load_json(DATA)
+        .expect("load");
+    store.relationships().set_schema(Some(&schema)).unwrap();
+    store
+}
+
+fn request(principal: &str, action: &str, resource: &str) -> PolicyRequest {
+    let mut context = HashMap::new();
+    context.insert("principal".to_string(), principal.into());
+    PolicyRequest {
+        resource: resource.to_string(),
+        action: action.to_string(),
+        context,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn check_expands_the_schema_on_both_evaluators() {
+    let store = store();
+    let parsed = ReaperPolicy::from_str(POLICY).unwrap();
+    let compiled = parsed
+        .clone()
+        .build(store.clone())
+        .expect("rebac::check must take the compiled path");
+    let ast = parsed.build_ast_evaluator(store);
+
+    for (principal, action, resource, allowed) in [
+        // Owner of the root folder sees everything below it.
+        ("alice", "read", "design", true),
+        ("alice", "read", "specs", true),
+        ("alice", "write", "design", false),
+        // Editors view and edit.
+        ("bob", "read", "design", true),
+        ("bob", "write", "design", true),
+        ("bob", "comment", "design", true),
+        // Group viewer of the parent folder, one and two groups deep.
+        ("carol", "read", "design", true),
+        ("erin", "read", "design", true),
+        ("carol", "comment", "design", false),
+        // Blocked is subtracted from viewer, but editor is its own relation.
+        ("dave", "read", "design", false),
+        ("dave", "write", "design", true),
+        ("dave", "comment", "design", false),
+        ("bob", "read", "root", false),
+    ] {
+        let req = request(principal, action, resource);
+        let c = compiled.evaluate(&req).unwrap();
+        let a = ast.evaluate(&req).unwrap();
+        assert_eq!(c, a, "evaluators diverged: {principal} {action} {resource}");
+        assert_eq!(
+            c == PolicyAction::Allow,
+            allowed,
+            "{principal} {action} {resource}"
+        );
+    }
+}
+
+#[test]
+fn without_rewrites_check_is_the_direct_relation() {
+    let store = store();
+    store.relationships().set_schema(None).unwrap();
+    let evaluator = ReaperPolicy::from_str(POLICY)
+        .unwrap()
+        .build(store)
+        .unwrap();
+    let decide = |principal, action| evaluator.evaluate(&request(principal, action, "design"));
+    assert_eq!(decide("bob", "write").unwrap(), PolicyAction::Allow);
+    assert_eq!(decide("alice", "read").unwrap(), PolicyAction::Deny);
+    assert_eq!(decide("carol", "read").unwrap(), PolicyAction::Deny);
+}
+
+#[test]
+fn dynamic_ids_run_on_the_interpreter_with_the_same_answer() {
+    let policy = ReaperPolicy::from_str(
+        r#"policy p {
+            default: deny,
+            rule r { allow if doc := "design" && rebac::check(user, "viewer", doc) }
+        }"#,
+    )
+    .unwrap();
+    assert!(policy.clone().build(store()).is_err());
+    let ast = policy.build_ast_evaluator(store());
+    let decide = |principal| ast.evaluate(&request(principal, "read", "x")).unwrap();
+    assert_eq!(decide("erin"), PolicyAction::Allow);
+    assert_eq!(decide("dave"), PolicyAction::Deny);
+
+    let err = ReaperPolicy::from_str(
+        r#"policy p { default: deny, rule r { allow if rebac::check(user, "viewer") } }"#,
+    )
+    .unwrap()
+    .build_ast_evaluator(store())
+    .evaluate(&request("bob", "read", "design"))
+    .unwrap_err();
+    assert!(err.to_string().contains("rebac::check requires"), "{err}");
+}
+
+#[test]
+fn rewrites_ride_in_bundles_from_version_6() {
+    let policy = ReaperPolicy::from_str(POLICY).unwrap();
+    let bytes = policy.compile_to_bundle_with_schema(&schema()).unwrap();
+    let bundle = PolicyBundle::from_bytes(&bytes).unwrap();
+    assert_eq!(bundle.metadata.version, 6);
+    assert_eq!(bundle.schema.as_ref(), Some(&schema()));
+
+    // A schema without rewrites keeps the version-5 encoding.
+    let mut plain = schema();
+    for entity_type in plain.entity_types.values_mut() {
+        entity_type.rewrites.clear();
+    }
+    let direct = ReaperPolicy::from_str(
+        r#"policy p { default: deny, rule r { allow if rebac::check(user, "viewer", resource) } }"#,
+    )
+    .unwrap();
+    let bytes = direct.compile_to_bundle_with_schema(&plain).unwrap();
+    let bundle = PolicyBundle::from_bytes(&bytes).unwrap();
+    assert_eq!(bundle.metadata.version, 5);
+    assert_eq!(bundle.schema.as_ref(), Some(&plain));
+
+    // A malformed rewrite rejects the bundle when it is read.
+    let mut broken = schema();
+    broken
+        .entity_types
+        .get_mut("Document")
+        .unwrap()
+        .rewrites
+        .insert("viewer".to_string(), "viewer +".to_string());
+    let smuggled = PolicyBundle {
+        schema: Some(broken),
+        ..PolicyBundle::from_bytes(&policy.compile_to_bundle().unwrap()).unwrap()
+    };
+    let err = PolicyBundle::from_bytes(&smuggled.to_bytes().unwrap()).unwrap_err();
+    assert!(err.to_string().contains("rewrite Document.viewer"), "{err}");
+}
+
+#[test]
+fn rewritten_relations_type_check() {
+    let diagnostics = |condition: &str| {
+        let source = format!("policy p {{ default: deny, rule r {{ allow if {condition} }} }}");
+        check_policy(&ReapParser::parse(&source).unwrap(), &schema())
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+    };
+    assert!(diagnostics(r#"rebac::check(user, "comment", resource)"#).is_empty());
+    let found = diagnostics(r#"rebac::check(user, "commenter", resource)"#);
+    assert!(
+        found[0].contains("rebac::check uses relation \"commenter\""),
+        "{found:?}"
+    );
+    // Only `check` expands rewrites; a direct lookup of one is flagged.
+    let found = diagnostics(r#"rebac::related(user, "comment", resource)"#);
+    assert!(found[0].contains("relation \"comment\""), "{found:?}");
+}
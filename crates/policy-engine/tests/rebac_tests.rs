@@ -38,7 +38,7 @@ fn store() -> Arc<DataStore> {
 
 fn request(principal: &str, action: &str, resource: &str) -> PolicyRequest {
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), principal.to_string());
+    context.insert("principal".to_string(), principal.into());
     PolicyRequest {
         resource: resource.to_string(),
         action: action.to_string(),
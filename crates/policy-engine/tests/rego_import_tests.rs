@@ -0,0 +1,457 @@
+//! `reap::rego` — the Rego importer behind `reaper-cli import-rego`.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use policy_engine::data::{DataLoader, DataStore};
+use policy_engine::reap::rego::{
+    import_rego, parse_corpus, verify_corpus, CorpusRecord, ImportOptions,
+};
+use policy_engine::reap::ReaperPolicy;
+use policy_engine::{PolicyAction, PolicyRequest};
+use serde_json::{json, Map, Value};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+fn repo_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../..")
+}
+
+fn read(relative: &str) -> String {
+    std::fs::read_to_string(repo_root().join(relative)).unwrap()
+}
+
+fn import(source: &str) -> policy_engine::reap::rego::RegoImport {
+    import_rego(source, &ImportOptions::default()).unwrap()
+}
+
+fn records(cases: &[(Value, Value)]) -> Vec<CorpusRecord> {
+    let lines: Vec<String> = cases
+        .iter()
+        .map(|(input, result)| json!({ "input": input, "result": result }).to_string())
+        .collect();
+    parse_corpus(&lines.join("\n")).unwrap()
+}
+
+/// The benchmark suite keeps an OPA twin of each Reaper policy; all of them
+/// are inside the supported subset.
+#[test]
+fn benchmark_policies_import_without_issues() {
+    let dir = repo_root().join("benchmarks/reaper-vs-opa/policies/opa");
+    let mut count = 0;
+    for entry in std::fs::read_dir(&dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|e| e != "rego") {
+            continue;
+        }
+        let imported = import(&std::fs::read_to_string(&path).unwrap());
+        assert!(
+            imported.issues.is_empty(),
+            "{}: {:?}",
+            path.display(),
+            imported.issues
+        );
+        assert_eq!(imported.entrypoint, "allow");
+        imported.source.parse::<ReaperPolicy>().unwrap();
+        count += 1;
+    }
+    assert_eq!(count, 12);
+}
+
+/// `{"entities": [{id, type, attributes}]}` → `{id: attributes}`, the shape
+/// `deploy-opa.sh` loads into OPA.
+fn opa_data(reaper_document: &Value) -> Value {
+    let mut by_id = Map::new();
+    for entity in reaper_document["entities"].as_array().unwrap() {
+        by_id.insert(
+            entity["id"].as_str().unwrap().to_string(),
+            entity["attributes"].clone(),
+        );
+    }
+    json!({ "entities": by_id })
+}
+
+fn evaluator(policy: &str, document: &Value) -> Box<dyn policy_engine::PolicyEvaluator> {
+    let store = DataStore::new();
+    DataLoader::new(store.clone())
+        .load_json(&document.to_string())
+        .unwrap();
+    policy
+        .parse::<ReaperPolicy>()
+        .unwrap()
+        .build_preferred(Arc::new(store))
+        .unwrap()
+}
+
+/// The imported OPA benchmark policy decides every sampled request the same
+/// way as the hand-written `.reap` policy it mirrors.
+#[test]
+fn imported_benchmarks_decide_like_their_reap_twins() {
+    for name in ["rbac", "abac"] {
+        let data: Value = serde_json::from_str(&read(&format!(
+            "benchmarks/reaper-vs-opa/data/10k/{name}.json"
+        )))
+        .unwrap();
+        let imported = import(&read(&format!(
+            "benchmarks/reaper-vs-opa/policies/opa/{name}.rego"
+        )));
+        let converted = imported.mapping.entity_document(&opa_data(&data)).unwrap();
+        let translated = evaluator(&imported.source, &converted);
+        let twin = evaluator(
+            &read(&format!(
+                "benchmarks/reaper-vs-opa/policies/reaper/{name}.reap"
+            )),
+            &data,
+        );
+
+        let ids: Vec<&str> = data["entities"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["id"].as_str().unwrap())
+            .collect();
+        let users: Vec<&str> = ids
+            .iter()
+            .copied()
+            .filter(|id| id.starts_with("user"))
+            .step_by(97)
+            .collect();
+        let resources: Vec<&str> = ids
+            .iter()
+            .copied()
+            .filter(|id| !id.starts_with("user"))
+            .step_by(89)
+            .collect();
+        let mut allowed = 0;
+        let mut checked = 0;
+        for user in &users {
+            for resource in resources.iter().chain(["missing_resource"].iter()) {
+                for action in ["read", "write", "delete"] {
+                    let input =
+                        json!({ "principal": user, "resource": resource, "action": action });
+                    let request = imported.mapping.request(&input);
+                    let expected = twin
+                        .evaluate(&PolicyRequest {
+                            resource: resource.to_string(),
+                            action: action.to_string(),
+                            context: [("principal".to_string(), json!(user))].into(),
+                            ..Default::default()
+                        })
+                        .unwrap();
+                    assert_eq!(
+                        translated.evaluate(&request).unwrap(),
+                        expected,
+                        "{name}: {input}"
+                    );
+                    checked += 1;
+                    allowed += usize::from(expected == PolicyAction::Allow);
+                }
+            }
+        }
+        assert!(checked > 500, "{name}: only {checked} requests");
+        assert!(
+            allowed > 0 && allowed < checked,
+            "{name}: {allowed} of {checked} allowed"
+        );
+    }
+}
+
+/// `test-fixtures/rego-import` covers helper rules and functions, `not`,
+/// `some … in`, `every`, set literals and membership, with decisions
+/// recorded from OPA.
+#[test]
+fn fixture_corpus_matches_recorded_opa_decisions() {
+    let imported = import(&read("test-fixtures/rego-import/policy.rego"));
+    assert!(imported.issues.is_empty(), "{:?}", imported.issues);
+    let data: Value = serde_json::from_str(&read("test-fixtures/rego-import/data.json")).unwrap();
+    let corpus = parse_corpus(&read("test-fixtures/rego-import/corpus.jsonl")).unwrap();
+
+    let report = verify_corpus(&imported, Some(&data), &corpus).unwrap();
+    assert_eq!(report.checked, 12);
+    assert!(report.mismatches.is_empty(), "{:?}", report.mismatches);
+
+    // Rule names come from the comment above each Rego rule.
+    for rule in ["rule admins", "rule owners", "rule editors", "rule readers"] {
+        assert!(imported.source.contains(rule), "{}", imported.source);
+    }
+    // `not blocked` under default deny becomes a deny rule.
+    assert!(imported.source.contains("rule suspended_users"));
+}
+
+#[test]
+fn corpus_reports_records_that_decide_differently() {
+    let imported = import(&read("test-fixtures/rego-import/policy.rego"));
+    let data: Value = serde_json::from_str(&read("test-fixtures/rego-import/data.json")).unwrap();
+    let corpus = records(&[
+        (
+            json!({"principal": "alice", "action": "read", "resource": "ledger"}),
+            json!(true),
+        ),
+        (
+            json!({"principal": "bob", "action": "write", "resource": "ledger"}),
+            json!(true),
+        ),
+    ]);
+
+    let report = verify_corpus(&imported, Some(&data), &corpus).unwrap();
+    assert_eq!(report.checked, 2);
+    assert_eq!(report.mismatches.len(), 1);
+    let mismatch = &report.mismatches[0];
+    assert_eq!(mismatch.index, 1);
+    assert_eq!(mismatch.expected, PolicyAction::Allow);
+    assert_eq!(mismatch.actual, PolicyAction::Deny);
+    assert!(mismatch.error.is_none());
+}
+
+#[test]
+fn corpus_reads_json_arrays_and_rejects_bad_lines() {
+    let array =
+        parse_corpus(r#"[{"input": {"action": "read"}, "result": true}, {"input": {}}]"#).unwrap();
+    assert_eq!(array.len(), 2);
+    assert_eq!(array[1].result, Value::Null);
+
+    let err = parse_corpus("{\"input\": {}}\n\nnot json\n").unwrap_err();
+    assert!(err.to_string().contains("corpus line 3"), "{err}");
+}
+
+/// Constructs outside the subset are listed with their line, and only the
+/// rules containing them are dropped.
+#[test]
+fn untranslatable_constructs_are_reported() {
+    let imported = import(
+        r#"package example
+
+import rego.v1
+
+default allow := false
+
+allow if {
+    input.action == "read"
+}
+
+allow if {
+    input.action == "write"
+} else := true if {
+    input.action == "admin"
+}
+
+allow if {
+    input.action == "mocked" with input.action as "read"
+}
+
+allow if {
+    http.send({"method": "GET", "url": input.url}).status_code == 200
+}
+"#,
+    );
+    assert_eq!(imported.issues.len(), 3, "{:?}", imported.issues);
+    assert!(
+        imported.issues.iter().any(|i| i.construct.contains("else")),
+        "{:?}",
+        imported.issues
+    );
+    assert!(
+        imported.issues.iter().any(|i| i.construct.contains("with")),
+        "{:?}",
+        imported.issues
+    );
+    assert!(
+        imported
+            .issues
+            .iter()
+            .any(|i| i.construct.contains("http.send")),
+        "{:?}",
+        imported.issues
+    );
+
+    // The surviving rule still decides, and the header lists what was lost.
+    assert!(
+        imported.source.contains("context.action == \"read\""),
+        "{}",
+        imported.source
+    );
+    assert_eq!(imported.source.matches("// not translated:").count(), 3);
+}
+
+/// Reaper evaluates a request against a loaded principal entity; OPA has no
+/// such requirement, so the corpus surfaces records whose principal is
+/// missing from `data` instead of hiding them.
+#[test]
+fn records_without_a_loaded_principal_deny_with_the_error() {
+    let imported = import("package p\n\nimport rego.v1\n\nallow if input.action == \"read\"\n");
+    let report = verify_corpus(
+        &imported,
+        None,
+        &records(&[
+            (json!({"principal": "ann", "action": "read"}), json!(true)),
+            (json!({"principal": "ann", "action": "write"}), json!(false)),
+        ]),
+    )
+    .unwrap();
+    assert_eq!(report.mismatches.len(), 1, "{:?}", report.mismatches);
+    let error = report.mismatches[0].error.as_deref().unwrap();
+    assert!(error.contains("User entity not found"), "{error}");
+}
+
+#[test]
+fn unparseable_rego_is_an_error() {
+    let err = import_rego("package p\n\nallow if {\n", &ImportOptions::default()).unwrap_err();
+    assert!(err.to_string().contains("line"), "{err}");
+}
+
+/// conftest-style `deny contains msg` sets become deny rules carrying the
+/// message; a non-empty set is a deny.
+#[test]
+fn deny_sets_become_deny_rules_with_messages() {
+    let imported = import(
+        r#"package main
+
+import rego.v1
+
+team := data.teams[input.principal]
+
+deny contains msg if {
+    team.frozen == true
+    msg := "deploys are frozen for this team"
+}
+
+deny contains msg if {
+    input.kind == "Deployment"
+    not input.spec.replicas
+    msg := "deployments must set replicas"
+}
+
+deny contains msg if {
+    some container in input.spec.containers
+    endswith(container.image, ":latest")
+    msg := sprintf("container %s uses a latest tag", [container.name])
+}
+
+deny contains msg if {
+    input.kind in {"Job", "CronJob"}
+    msg := sprintf("%s is not allowed here", [input.kind])
+}
+"#,
+    );
+    // A message computed per iterated element has no `.reap` equivalent; its
+    // rule still denies, without the message.
+    assert_eq!(imported.issues.len(), 1, "{:?}", imported.issues);
+    assert_eq!(imported.issues[0].line, 18);
+    assert!(imported.issues[0].reason.contains("iteration variable"));
+    assert_eq!(imported.entrypoint, "deny");
+    assert!(imported.denies());
+    assert_eq!(
+        imported.source.matches("deny with message msg if").count(),
+        3
+    );
+    assert!(
+        imported
+            .source
+            .contains("msg := \"deployments must set replicas\""),
+        "{}",
+        imported.source
+    );
+
+    let data = json!({"teams": {"ops": {"frozen": false}, "web": {"frozen": true}}});
+    let report = verify_corpus(
+        &imported,
+        Some(&data),
+        &records(&[
+            (
+                json!({"principal": "ops", "kind": "Deployment", "spec": {"containers": [{"name": "app", "image": "app:1.2"}]}}),
+                json!(["deployments must set replicas"]),
+            ),
+            (
+                json!({"principal": "ops", "kind": "Deployment", "spec": {"replicas": 2, "containers": [{"name": "app", "image": "app:1.2"}]}}),
+                json!([]),
+            ),
+            (
+                json!({"principal": "ops", "kind": "Pod", "spec": {"containers": [{"name": "web", "image": "nginx:latest"}]}}),
+                json!(["container web uses a latest tag"]),
+            ),
+            (
+                json!({"principal": "ops", "kind": "Job", "spec": {"replicas": 1, "containers": []}}),
+                json!(["Job is not allowed here"]),
+            ),
+            (
+                json!({"principal": "web", "kind": "Pod", "spec": {"containers": []}}),
+                json!(["deploys are frozen for this team"]),
+            ),
+        ]),
+    )
+    .unwrap();
+    assert!(report.mismatches.is_empty(), "{:?}", report.mismatches);
+    assert!(
+        imported
+            .source
+            .contains("msg := concat(context.kind, \" is not allowed here\")"),
+        "{}",
+        imported.source
+    );
+}
+
+#[test]
+fn options_pick_the_name_and_entrypoint() {
+    let imported = import_rego(
+        "package a.b.c\n\nimport rego.v1\n\nallow if input.x == 1\n\nviolation if input.x == 2\n",
+        &ImportOptions {
+            name: Some("renamed".to_string()),
+            entrypoint: Some("violation".to_string()),
+        },
+    )
+    .unwrap();
+    assert!(
+        imported.source.contains("policy renamed {"),
+        "{}",
+        imported.source
+    );
+    assert_eq!(imported.entrypoint, "violation");
+    assert!(imported.denies());
+    assert!(!imported.source.contains("== 1"), "{}", imported.source);
+}
+
+/// The mapping follows the `data` lookups: ids come from the input fields
+/// used as keys, and every other input field is request context.
+#[test]
+fn data_mapping_follows_the_entity_lookups() {
+    let imported = import(
+        r#"package p
+
+import rego.v1
+
+default allow := false
+
+subject := data.people[input.who]
+
+allow if {
+    subject.level >= input.required
+}
+"#,
+    );
+    assert!(imported.issues.is_empty(), "{:?}", imported.issues);
+    assert_eq!(imported.mapping.principal, "who");
+    assert_eq!(imported.mapping.entities.len(), 1);
+    assert_eq!(imported.mapping.entities[0].path, "people");
+
+    let request = imported.mapping.request(&json!({
+        "who": "ann",
+        "required": 3,
+        "context": {"ip": "10.0.0.1"},
+    }));
+    assert_eq!(request.context["principal"], json!("ann"));
+    assert_eq!(request.context["required"], json!(3));
+    assert_eq!(request.context["ip"], json!("10.0.0.1"));
+    assert!(!request.context.contains_key("who"));
+
+    let document = imported
+        .mapping
+        .entity_document(&json!({"people": {"ann": {"level": 4}, "bo": {"level": 1}}}))
+        .unwrap();
+    assert_eq!(document["entities"].as_array().unwrap().len(), 2);
+    assert_eq!(document["entities"][0]["attributes"]["level"], json!(4));
+
+    let err = imported
+        .mapping
+        .entity_document(&json!({"people": ["ann"]}))
+        .unwrap_err();
+    assert!(err.to_string().contains("data.people"), "{err}");
+}
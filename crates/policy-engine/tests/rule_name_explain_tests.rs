@@ -61,7 +61,7 @@ policy explain_test {
 
 fn req(principal: &str) -> PolicyRequest {
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), principal.to_string());
+    context.insert("principal".to_string(), principal.into());
     PolicyRequest {
         resource: "res-1".to_string(),
         action: "read".to_string(),
@@ -0,0 +1,267 @@
+//! Declared entity schemas: static type checking of `.reap` policies
+//! (`reap::typecheck`), schema-carrying bundles (wire version 5) and data
+//! loads validated against the same schema.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use policy_engine::data::{DataLoader, DataStore, EntitySchema};
+use policy_engine::reap::typecheck::check_policy;
+use policy_engine::reap::{PolicyBundle, ReapParser, ReaperPolicy};
+
+const SCHEMA: &str = r#"{
+    "entity_types": {
+        "User": {
+            "attributes": {
+                "department": {"type": "string"},
+                "level": {"type": "number"},
+                "active": {"type": "bool", "required": false},
+                "roles": {"type": "array", "items": "string"},
+                "profile": {
+                    "type": "object",
+                    "required": false,
+                    "attributes": {"team": {"type": "string"}}
+                },
+                "extra": {"type": "object", "required": false},
+                "anything": {"type": "any", "required": false}
+            },
+            "relations": {"member_of": ["Group"]}
+        },
+        "Group": {},
+        "Document": {
+            "attributes": {"classification": {"type": "string"}},
+            "relations": {"viewer": ["User", "Group"], "parent": ["Document"]}
+        }
+    },
+    "request": {"user": ["User"], "resource": ["Document"]},
+    "context": {"ip": {"type": "string"}, "hour": {"type": "number"}}
+}"#;
+
+fn schema() -> EntitySchema {
+    EntitySchema::from_json(SCHEMA).unwrap()
+}
+
+/// Diagnostics for a single-rule policy with the given condition.
+fn diagnostics(condition: &str) -> Vec<String> {
+    let source = format!("policy p {{ default: deny, rule r {{ allow if {condition} }} }}");
+    let policy = ReapParser::parse(&source).unwrap();
+    check_policy(&policy, &schema())
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[test]
+fn well_typed_conditions_pass() {
+    for condition in [
+        r#"user.department == "eng""#,
+        "user.level >= 3 && user.level < 10",
+        r#""admin" in user.roles"#,
+        r#"user.roles[_] == "admin""#,
+        r#"user.profile.team == "core""#,
+        r#"user.extra.anything.goes == 1"#,
+        r#"user.anything == "x""#,
+        "user.active == null",
+        r#"resource.classification != "secret""#,
+        r#"context.ip == "10.0.0.1" && context.hour > 8"#,
+        r#"input.whatever.nested == 3"#,
+        "lvl := user.level && lvl > 2",
+        r#"user.department.lower() == "eng""#,
+        "user.roles.count() > 0",
+        r#"rebac::related(user, "viewer", resource)"#,
+        r#"rebac::reachable(user, "viewer", resource, "member_of", 3)"#,
+    ] {
+        assert_eq!(diagnostics(condition), Vec::<String>::new(), "{condition}");
+    }
+}
+
+#[test]
+fn typos_are_rejected_with_a_suggestion() {
+    let found = diagnostics(r#"user.departmnet == "eng""#);
+    assert_eq!(
+        found,
+        ["rule r: unknown attribute `user.departmnet` (not declared by User; did you mean `department`?)"]
+    );
+
+    let found = diagnostics(r#"user.profile.taem == "core""#);
+    assert_eq!(
+        found,
+        ["rule r: unknown attribute `user.profile.taem` (not declared by User; did you mean `team`?)"]
+    );
+
+    let found = diagnostics(r#"context.ipp == "10.0.0.1""#);
+    assert!(found[0].contains("(not declared in the request context; did you mean `ip`?)"));
+
+    // Typos inside method receivers, function arguments and messages too.
+    assert_eq!(diagnostics(r#"user.deparment.lower() == "x""#).len(), 1);
+    assert_eq!(
+        diagnostics(r#"startswith(resource.clasification, "s")"#).len(),
+        1
+    );
+}
+
+#[test]
+fn comparisons_that_can_never_hold_are_flagged() {
+    for (condition, expected) in [
+        (
+            r#"user.level == "3""#,
+            "compares number with string and is always false",
+        ),
+        (
+            r#"user.department != 3"#,
+            "compares string with number and is always true",
+        ),
+        (
+            r#"user.department > 3"#,
+            "orders a string value and is always false",
+        ),
+        ("user.roles[_] == 1", "compares string with number"),
+        (
+            "1 in user.roles",
+            "tests a number for membership among string elements",
+        ),
+        (
+            "user.level == resource.classification",
+            "compares number with string",
+        ),
+        (
+            "lvl := user.level && lvl == true",
+            "compares number with bool",
+        ),
+        (
+            r#"user.department.count() == "x""#,
+            "compares number with string",
+        ),
+    ] {
+        let found = diagnostics(condition);
+        assert_eq!(found.len(), 1, "{condition}: {found:?}");
+        assert!(found[0].contains(expected), "{condition}: {}", found[0]);
+    }
+}
+
+#[test]
+fn rebac_relations_must_be_declared_and_admit_the_subject() {
+    let found = diagnostics(r#"rebac::related(user, "owner", resource)"#);
+    assert!(found[0].contains("relation \"owner\", which no entity type declares"));
+
+    let found = diagnostics(r#"rebac::reachable(user, "viewer", resource, "memberof", 2)"#);
+    assert!(found[0].contains("relation \"memberof\""));
+
+    let found = diagnostics(r#"rebac::related(user, "parent", resource)"#);
+    assert!(
+        found[0].contains("is always false: no Document declares \"parent\" with a User subject")
+    );
+}
+
+#[test]
+fn functions_and_messages_are_checked() {
+    let policy = ReapParser::parse(
+        r#"policy p {
+    default: deny,
+    func senior(u) := user.levl > 5,
+    rule r { deny with message concat("bad ", user.departmen) if senior(user) }
+}"#,
+    )
+    .unwrap();
+    let found: Vec<String> = check_policy(&policy, &schema())
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(found.len(), 2, "{found:?}");
+    assert!(found[0].starts_with("func senior: unknown attribute `user.levl`"));
+    assert!(found[1].starts_with("rule r: unknown attribute `user.departmen`"));
+}
+
+#[test]
+fn unbound_slots_are_not_checked() {
+    let schema = EntitySchema::from_json(r#"{"entity_types": {"User": {}}}"#).unwrap();
+    let policy = ReapParser::parse(
+        r#"policy p { default: deny, rule r { allow if user.anything == 1 && context.x == "y" } }"#,
+    )
+    .unwrap();
+    assert!(check_policy(&policy, &schema).is_empty());
+}
+
+const POLICY: &str = r#"policy docs {
+    default: deny,
+    rule engineers { allow if user.department == "eng" && user.level >= 2 }
+}"#;
+
+#[test]
+fn bundles_carry_the_schema_and_recheck_it_on_deploy() {
+    let policy = ReaperPolicy::from_str(POLICY).unwrap();
+    let bytes = policy.compile_to_bundle_with_schema(&schema()).unwrap();
+    let bundle = PolicyBundle::from_bytes(&bytes).unwrap();
+    assert_eq!(bundle.metadata.version, 5);
+    assert_eq!(bundle.schema.as_ref(), Some(&schema()));
+    assert_eq!(bundle.policy.rules.len(), 1);
+    bundle
+        .to_enhanced_policy_with_store(Arc::new(DataStore::new()))
+        .unwrap();
+
+    // Without a schema the bundle keeps its older wire version.
+    let plain = PolicyBundle::from_bytes(&policy.compile_to_bundle().unwrap()).unwrap();
+    assert_eq!(plain.metadata.version, 2);
+    assert!(plain.schema.is_none());
+
+    // Building rejects an ill-typed policy...
+    let typo = ReaperPolicy::from_str(&POLICY.replace("user.level", "user.lvl")).unwrap();
+    let err = typo.compile_to_bundle_with_schema(&schema()).unwrap_err();
+    assert!(
+        err.to_string().contains("unknown attribute `user.lvl`"),
+        "{err}"
+    );
+
+    // ...and so does deploying one whose schema was attached without a check.
+    let smuggled = PolicyBundle {
+        schema: Some(schema()),
+        ..PolicyBundle::from_bytes(&typo.compile_to_bundle().unwrap()).unwrap()
+    };
+    let reloaded = PolicyBundle::from_bytes(&smuggled.to_bytes().unwrap()).unwrap();
+    assert!(reloaded
+        .to_enhanced_policy_with_store(Arc::new(DataStore::new()))
+        .is_err());
+}
+
+#[test]
+fn data_loads_are_validated_against_the_schema() {
+    let store = DataStore::new();
+    let loader = DataLoader::new(store.clone()).with_schema(Arc::new(schema()));
+
+    let good = r#"{"entities": [
+        {"id": "alice", "type": "User", "attributes": {"department": "eng", "level": 3, "roles": ["admin"]},
+         "relationships": {"member_of": ["eng"]}},
+        {"id": "doc1", "type": "Document", "attributes": {"classification": "internal"}}
+    ]}"#;
+    assert_eq!(loader.load_json(good).unwrap(), 2);
+
+    // A bad entity after a good one: nothing from the document is inserted,
+    // on the streaming and the batch path alike.
+    let bad = r#"{"entities": [
+        {"id": "bob", "type": "User", "attributes": {"department": "ops", "level": 1, "roles": []}},
+        {"id": "carol", "type": "User", "attributes": {"department": "ops", "level": "high", "roles": []}}
+    ]}"#;
+    let before = store.all().len();
+    let err = loader.load_json(bad).unwrap_err();
+    assert!(
+        err.to_string().contains("entity 'carol' (User) does not match the schema: attribute 'level' must be number, got string"),
+        "{err}"
+    );
+    assert!(loader.load_json_batch(bad).is_err());
+    assert_eq!(store.all().len(), before);
+
+    let err = loader
+        .upsert_entity_doc(&serde_json::json!({
+            "id": "alice", "type": "User",
+            "attributes": {"department": "eng", "level": 3, "roles": [], "departmnet": "x"}
+        }))
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("undeclared attribute 'departmnet'"));
+
+    // Without a schema the same document loads as before.
+    assert_eq!(DataLoader::new(DataStore::new()).load_json(bad).unwrap(), 2);
+}
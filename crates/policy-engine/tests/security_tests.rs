@@ -103,7 +103,7 @@ policy null_byte_test {
 
     // Create request with null bytes
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "user\0injected".to_string());
+    context.insert("principal".to_string(), "user\0injected".into());
 
     let request = PolicyRequest {
         resource: "resource\0with\0nulls".to_string(),
@@ -152,7 +152,7 @@ policy unicode_test {
     let evaluator = policy.build(Arc::clone(&store)).unwrap();
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "user_unicode".to_string());
+    context.insert("principal".to_string(), "user_unicode".into());
 
     let request = PolicyRequest {
         resource: "test".to_string(),
@@ -197,7 +197,7 @@ policy injection_test {
         // The key is that injected rules don't execute
         if let Ok(eval) = evaluator {
             let mut context = HashMap::new();
-            context.insert("principal".to_string(), "nonexistent".to_string());
+            context.insert("principal".to_string(), "nonexistent".into());
 
             let request = PolicyRequest {
                 resource: "test".to_string(),
@@ -257,7 +257,7 @@ policy attr_injection_test {
         let evaluator = policy.build(Arc::clone(&store)).unwrap();
 
         let mut context = HashMap::new();
-        context.insert("principal".to_string(), "evil_user".to_string());
+        context.insert("principal".to_string(), "evil_user".into());
 
         let request = PolicyRequest {
             resource: "test".to_string(),
@@ -325,7 +325,7 @@ policy regex_dos_test {
     let evaluator = policy.build(Arc::clone(&store)).unwrap();
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "regex_user".to_string());
+    context.insert("principal".to_string(), "regex_user".into());
 
     let request = PolicyRequest {
         resource: "test".to_string(),
@@ -377,7 +377,7 @@ policy long_regex_test {{
         // Building may fail due to regex complexity, which is acceptable
         if let Ok(evaluator) = build_result {
             let mut context = HashMap::new();
-            context.insert("principal".to_string(), "test".to_string());
+            context.insert("principal".to_string(), "test".into());
 
             let request = PolicyRequest {
                 resource: "test".to_string(),
@@ -441,7 +441,7 @@ policy nested_quantifier_test {
     let evaluator = policy.build(Arc::clone(&store)).unwrap();
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "regex_user2".to_string());
+    context.insert("principal".to_string(), "regex_user2".into());
 
     let request = PolicyRequest {
         resource: "test".to_string(),
@@ -508,7 +508,7 @@ policy large_array_test {
     let evaluator = policy.build(Arc::clone(&store)).unwrap();
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "large_array_user".to_string());
+    context.insert("principal".to_string(), "large_array_user".into());
 
     let request = PolicyRequest {
         resource: "test".to_string(),
@@ -579,7 +579,7 @@ policy many_entities_test {
 
     // Evaluate against a specific user
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "user_5000".to_string());
+    context.insert("principal".to_string(), "user_5000".into());
 
     let request = PolicyRequest {
         resource: "test".to_string(),
@@ -644,7 +644,7 @@ policy integer_boundary_test {
 
     // Test max value
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "max_user".to_string());
+    context.insert("principal".to_string(), "max_user".into());
     let request = PolicyRequest {
         resource: "test".to_string(),
         action: "read".to_string(),
@@ -658,7 +658,7 @@ policy integer_boundary_test {
 
     // Test min value (0)
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "min_user".to_string());
+    context.insert("principal".to_string(), "min_user".into());
     let request = PolicyRequest {
         resource: "test".to_string(),
         action: "read".to_string(),
@@ -672,7 +672,7 @@ policy integer_boundary_test {
 
     // Test negative value (should deny)
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "neg_user".to_string());
+    context.insert("principal".to_string(), "neg_user".into());
     let request = PolicyRequest {
         resource: "test".to_string(),
         action: "read".to_string(),
@@ -719,7 +719,7 @@ policy empty_string_test {
 
     // Empty name should deny
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "empty_user".to_string());
+    context.insert("principal".to_string(), "empty_user".into());
     let request = PolicyRequest {
         resource: "test".to_string(),
         action: "read".to_string(),
@@ -733,7 +733,7 @@ policy empty_string_test {
 
     // Valid name should allow
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "valid_user".to_string());
+    context.insert("principal".to_string(), "valid_user".into());
     let request = PolicyRequest {
         resource: "test".to_string(),
         action: "read".to_string(),
@@ -772,7 +772,7 @@ policy missing_entity_test {
     let evaluator = policy.build(Arc::clone(&store)).unwrap();
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "nonexistent_user".to_string());
+    context.insert("principal".to_string(), "nonexistent_user".into());
 
     let request = PolicyRequest {
         resource: "test".to_string(),
@@ -858,7 +858,7 @@ policy type_mismatch_test {
     let evaluator = policy.build(Arc::clone(&store)).unwrap();
 
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), "mismatch_user".to_string());
+    context.insert("principal".to_string(), "mismatch_user".into());
 
     let request = PolicyRequest {
         resource: "test".to_string(),
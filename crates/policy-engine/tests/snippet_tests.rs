@@ -0,0 +1,120 @@
+//! Standalone snippets (`ReapAstEvaluator::evaluate_snippet`, the engine side
+//! of `reaper-cli repl`): values, conditions and bindings evaluated outside
+//! any rule.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use policy_engine::reap::{load_library, ReapAstEvaluator, ReapParser, SnippetScope};
+use policy_engine::{DataLoader, DataStore, PolicyRequest};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+fn store() -> Arc<DataStore> {
+    let s = Arc::new(DataStore::new());
+    let data = json!({
+        "entities": [
+            {"id": "alice", "type": "user",
+             "attributes": {"roles": ["admin", "dev"], "level": 6}},
+            {"id": "doc-1", "type": "resource", "attributes": {"owner": "alice"}}
+        ]
+    });
+    DataLoader::new((*s).clone())
+        .load_json(&data.to_string())
+        .expect("load");
+    s
+}
+
+fn req() -> PolicyRequest {
+    let mut context = HashMap::new();
+    context.insert("principal".to_string(), "alice".into());
+    PolicyRequest {
+        resource: "doc-1".to_string(),
+        action: "read".to_string(),
+        context,
+        ..Default::default()
+    }
+}
+
+fn evaluator() -> ReapAstEvaluator {
+    let policy = ReapParser::parse(
+        r#"policy p {
+    default: deny,
+    func senior(lvl) := lvl >= 5,
+}"#,
+    )
+    .unwrap();
+    ReapAstEvaluator::new(store(), policy)
+}
+
+#[test]
+fn values_conditions_and_funcs_evaluate_against_the_request() {
+    let ev = evaluator();
+    let mut scope = SnippetScope::default();
+    let mut eval = |src: &str| ev.evaluate_snippet(src, &req(), None, &mut scope).unwrap();
+
+    assert_eq!(eval("user.roles"), json!(["admin", "dev"]));
+    assert_eq!(eval("user.level * 2"), json!(12));
+    assert_eq!(eval(r#"resource.owner == "alice""#), json!(true));
+    assert_eq!(eval(r#""admin" in user.roles && !senior(3)"#), json!(true));
+    assert_eq!(eval("senior(user.level)"), json!(true));
+}
+
+#[test]
+fn bindings_persist_across_snippets_until_cleared() {
+    let ev = evaluator();
+    let mut scope = SnippetScope::default();
+
+    let bound = ev
+        .evaluate_snippet("rs := {r | r := user.roles[_]}", &req(), None, &mut scope)
+        .unwrap();
+    assert_eq!(bound.as_array().map(Vec::len), Some(2));
+    assert_eq!(scope.names(), vec!["rs"]);
+
+    let count = ev
+        .evaluate_snippet("rs.count()", &req(), None, &mut scope)
+        .unwrap();
+    assert_eq!(count, json!(2));
+    // Pseudo-variables (`user`, `resource`, ...) never leak into the scope.
+    assert_eq!(scope.names(), vec!["rs"]);
+
+    scope.clear();
+    assert!(ev
+        .evaluate_snippet("rs.count()", &req(), None, &mut scope)
+        .is_err());
+}
+
+#[test]
+fn input_is_visible_and_garbage_is_rejected() {
+    let ev = evaluator();
+    let mut scope = SnippetScope::default();
+    let input = json!({"amount": 40});
+    assert_eq!(
+        ev.evaluate_snippet("input.amount + 2", &req(), Some(&input), &mut scope)
+            .unwrap(),
+        json!(42)
+    );
+    let err = ev
+        .evaluate_snippet("user.roles ((", &req(), None, &mut scope)
+        .unwrap_err();
+    assert!(err.to_string().contains("not an expression or condition"));
+}
+
+#[test]
+fn load_library_namespaces_funcs_under_the_library_name() {
+    let dir = std::env::temp_dir().join(format!("reap-lib-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("preds.reap");
+    std::fs::write(
+        &path,
+        "library preds {\n    func adult(age) := age >= 18,\n}\n",
+    )
+    .unwrap();
+
+    let (name, funcs) = load_library(&path).unwrap();
+    assert_eq!(name, "preds");
+    assert_eq!(funcs.len(), 1);
+    assert_eq!(funcs[0].namespace.as_deref(), Some("preds"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
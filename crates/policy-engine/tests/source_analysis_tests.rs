@@ -0,0 +1,172 @@
+//! `reap::analysis` — the span-carrying view of `.reap` source that editor
+//! tooling (reaper-lsp) is built on.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use policy_engine::reap::analysis::{
+    builtin_function, builtin_method, check_source, outline, rule_compile_modes,
+};
+
+const POLICY: &str = r#"import "roles.reap" as roles
+
+policy docs {
+    default: deny,
+    func is_owner(u) := u == "alice",
+    rule owners {
+        allow if is_owner(user.name) && time::is_before(user.created_at, time::now())
+    }
+    rule tenants {
+        allow if context.http.headers["x-tenant"] == "acme"
+    }
+    rule admins {
+        allow if roles::is_admin(user.role) && user.profile.team == "ops" && user.tags.contains("staff")
+    }
+}
+"#;
+
+const LIBRARY: &str = r#"library roles {
+    func is_admin(r) := r == "admin"
+}
+"#;
+
+fn span_text(source: &str, start: usize, end: usize) -> &str {
+    &source[start..end]
+}
+
+#[test]
+fn outline_locates_declarations_and_references() {
+    let o = outline(POLICY).unwrap();
+    assert!(!o.is_library);
+    assert_eq!(o.name.as_ref().unwrap().name, "docs");
+
+    let import = &o.imports[0];
+    assert_eq!(
+        (import.path.as_str(), import.alias.as_str()),
+        ("roles.reap", "roles")
+    );
+    assert_eq!(
+        span_text(POLICY, import.alias_span.start, import.alias_span.end),
+        "roles"
+    );
+
+    let rules: Vec<&str> = o.rules.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(rules, ["owners", "tenants", "admins"]);
+    let owners = &o.rules[0];
+    assert_eq!(
+        span_text(POLICY, owners.name_span.start, owners.name_span.end),
+        "owners"
+    );
+    assert_eq!(o.functions[0].name, "is_owner");
+
+    let is_before = o.calls.iter().find(|c| c.function == "is_before").unwrap();
+    assert_eq!(is_before.namespace.as_deref(), Some("time"));
+    let ns = is_before.namespace_span.unwrap();
+    assert_eq!(span_text(POLICY, ns.start, ns.end), "time");
+    assert!(o
+        .calls
+        .iter()
+        .any(|c| c.namespace.as_deref() == Some("roles") && c.function == "is_admin"));
+    assert!(o
+        .calls
+        .iter()
+        .any(|c| c.namespace.is_none() && c.function == "is_owner"));
+
+    let method = o.methods.iter().find(|m| m.name == "contains").unwrap();
+    assert_eq!(
+        span_text(POLICY, method.span.start, method.span.end),
+        "contains"
+    );
+
+    assert!(o
+        .attributes
+        .iter()
+        .any(|a| a.entity == "user" && a.path == ["profile", "team"]));
+}
+
+#[test]
+fn outline_recognizes_libraries() {
+    let o = outline(LIBRARY).unwrap();
+    assert!(o.is_library);
+    assert_eq!(o.name.unwrap().name, "roles");
+    assert_eq!(o.functions[0].name, "is_admin");
+}
+
+#[test]
+fn syntax_errors_carry_the_parser_position() {
+    let source = "policy p {\n    default: deny,\n    rule r { allow if user.role == }\n}\n";
+    let diagnostics = check_source(source, None);
+    assert_eq!(diagnostics.len(), 1);
+    let line = source[..diagnostics[0].span.start].lines().count();
+    assert_eq!(line, 3, "{diagnostics:?}");
+    assert!(diagnostics[0].message.starts_with("syntax error"));
+}
+
+#[test]
+fn unknown_builtins_are_flagged_at_the_call() {
+    let source = "policy p {\n    default: deny,\n    rule r { allow if time::tomorrow() }\n}\n";
+    let diagnostics = check_source(source, None);
+    let d = diagnostics
+        .iter()
+        .find(|d| d.message.contains("tomorrow"))
+        .unwrap_or_else(|| panic!("{diagnostics:?}"));
+    assert_eq!(
+        span_text(source, d.span.start, d.span.end),
+        "time::tomorrow"
+    );
+}
+
+#[test]
+fn semantic_errors_are_anchored_on_the_named_identifier() {
+    let source =
+        "policy p {\n    default: deny,\n    rule r { allow if missing_helper(user.role) }\n}\n";
+    let diagnostics = check_source(source, None);
+    assert!(!diagnostics.is_empty());
+    assert!(
+        diagnostics
+            .iter()
+            .any(|d| span_text(source, d.span.start, d.span.end) == "missing_helper"),
+        "{diagnostics:?}"
+    );
+}
+
+#[test]
+fn imports_resolve_against_the_base_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("roles.reap"), LIBRARY).unwrap();
+    assert!(check_source(POLICY, Some(dir.path())).is_empty());
+
+    let empty = tempfile::tempdir().unwrap();
+    let diagnostics = check_source(POLICY, Some(empty.path()));
+    assert!(!diagnostics.is_empty());
+}
+
+#[test]
+fn clean_library_has_no_diagnostics() {
+    assert!(check_source(LIBRARY, None).is_empty());
+}
+
+#[test]
+fn compile_modes_report_the_interpreted_rules() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("roles.reap"), LIBRARY).unwrap();
+    let modes = rule_compile_modes(POLICY, Some(dir.path())).unwrap();
+    let tenants = modes.iter().find(|m| m.rule == "tenants").unwrap();
+    assert!(tenants.fallback.as_deref().unwrap().contains("context"));
+
+    let simple =
+        "policy p {\n    default: deny,\n    rule r { allow if user.role == \"admin\" }\n}\n";
+    let modes = rule_compile_modes(simple, None).unwrap();
+    assert_eq!(modes.len(), 1);
+    assert!(modes[0].fallback.is_none());
+}
+
+#[test]
+fn builtin_reference_lookups() {
+    let now = builtin_function(Some("time"), "now").unwrap();
+    assert!(now.signature.starts_with("time::now("));
+    assert!(builtin_function(Some("rebac"), "related").is_some());
+    assert!(builtin_function(Some("time"), "tomorrow").is_none());
+    assert!(builtin_function(None, "concat").is_some());
+    assert!(builtin_method("startswith").is_some());
+    assert!(builtin_method("frobnicate").is_none());
+}
@@ -0,0 +1,207 @@
+//! f-strings, raw strings and `strings::format`: parsing and canonical
+//! formatting, hole rendering, check-mode messages (compiled ≡ interpreter,
+//! byte for byte), comparisons against an interpolated right side, and the
+//! template/argument mismatch error.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use policy_engine::reap::format::format_source;
+use policy_engine::reap::{ReapParser, ReaperPolicy};
+use policy_engine::{DataStore, PolicyAction, PolicyRequest};
+use reaper_core::ReaperError;
+use serde_json::json;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+fn store() -> Arc<DataStore> {
+    Arc::new(DataStore::new())
+}
+
+fn request() -> PolicyRequest {
+    PolicyRequest {
+        resource: "res".to_string(),
+        action: "check".to_string(),
+        context: HashMap::new(),
+        ..Default::default()
+    }
+}
+
+fn policy(rule: &str) -> String {
+    format!("policy s {{\n    default: allow,\n    rule r {{\n        {rule}\n    }}\n}}\n")
+}
+
+type CheckOutcome = Result<(bool, Vec<Option<String>>), String>;
+
+fn outcome(r: Result<policy_engine::reap::CheckResult, ReaperError>) -> CheckOutcome {
+    r.map(|c| {
+        (
+            c.allowed,
+            c.violations.into_iter().map(|v| v.message).collect(),
+        )
+    })
+    .map_err(|e| format!("{e:?}"))
+}
+
+/// Check `text` on the interpreter and, when `compiled`, on the compiled
+/// evaluator too, asserting the outcomes are identical.
+fn check(text: &str, doc: Option<&serde_json::Value>, compiled: bool) -> CheckOutcome {
+    let parsed = ReaperPolicy::from_str(text).expect("parse");
+    let ast = outcome(
+        parsed
+            .clone()
+            .build_ast_evaluator(store())
+            .check_with_input(&request(), doc),
+    );
+    let built = parsed.build(store());
+    if compiled {
+        let c = outcome(
+            built
+                .expect("rule compiles")
+                .check_with_input(&request(), doc),
+        );
+        assert_eq!(c, ast, "evaluators diverged on {text}");
+    } else {
+        assert!(built.is_err(), "expected AST fallback for {text}");
+    }
+    ast
+}
+
+fn message(text: &str, doc: Option<&serde_json::Value>, compiled: bool) -> String {
+    let (_, messages) = check(text, doc, compiled).expect("check");
+    messages
+        .into_iter()
+        .next()
+        .flatten()
+        .expect("one violation with a message")
+}
+
+#[test]
+fn fstrings_and_raw_strings_format_canonically() {
+    let src = policy(
+        r#"deny with message f"bucket {input.name} has {{braces}} and \"quotes\"" if input.pattern.matches("^\\d+$")"#,
+    );
+    let formatted = format_source(&src).unwrap();
+    assert!(
+        formatted.contains(r#"f"bucket {input.name} has {{braces}} and \"quotes\"""#),
+        "{formatted}"
+    );
+    // A backslash and no quote: printed raw, not double-escaped.
+    assert!(formatted.contains(r#"matches(r"^\d+$")"#), "{formatted}");
+    assert_eq!(format_source(&formatted).unwrap(), formatted);
+
+    // `r` and `f` are only prefixes directly before a quote.
+    assert!(ReapParser::parse(&policy("allow if r := 1 && f := r")).is_ok());
+}
+
+#[test]
+fn raw_strings_take_backslashes_verbatim() {
+    let doc = json!({"id": "42", "path": "C:\\temp"});
+    let text = policy(
+        r#"deny with message "matched" if input.id.matches(r"^\d+$") && input.path == r"C:\temp""#,
+    );
+    assert_eq!(message(&text, Some(&doc), false), "matched");
+    // Escaped and raw spellings are the same string.
+    let escaped = policy(
+        r#"deny with message "matched" if input.id.matches("^\\d+$") && input.path == "C:\\temp""#,
+    );
+    assert_eq!(message(&escaped, Some(&doc), false), "matched");
+}
+
+#[test]
+fn holes_render_strings_bare_and_other_values_as_json() {
+    let doc = json!({
+        "name": "logs",
+        "replicas": 3,
+        "ratio": 2.5,
+        "public": true,
+        "tags": ["a", "b"],
+        "owner": {"team": "core", "id": 7}
+    });
+    let text = policy(
+        r#"deny with message f"{input.name}:{input.replicas}:{input.ratio}:{input.public}:{input.tags}:{input.owner}:{input.missing}" if input.public == true"#,
+    );
+    assert_eq!(
+        message(&text, Some(&doc), false),
+        r#"logs:3:2.5:true:["a","b"]:{"id":7,"team":"core"}:null"#
+    );
+    // Arithmetic and method calls are holes like any other expression.
+    let text = policy(
+        r#"deny with message f"{input.name.upper()} x{input.replicas * 2}" if input.public == true"#,
+    );
+    assert_eq!(message(&text, Some(&doc), false), "LOGS x6");
+}
+
+#[test]
+fn messages_over_rule_variables_compile() {
+    let doc = json!({"name": "logs", "acl": "public-read", "size": 12});
+    let cases = [
+        (
+            r#"deny with message f"bucket {name} is {acl} ({size} GiB)" if name := input.name && acl := input.acl && size := input.size && acl != "private""#,
+            "bucket logs is public-read (12 GiB)",
+        ),
+        (
+            r#"deny with message strings::format("bucket {} is {}, {{sic}}", name, acl) if name := input.name && acl := input.acl && acl != "private""#,
+            "bucket logs is public-read, {sic}",
+        ),
+    ];
+    for (rule, expected) in cases {
+        assert_eq!(message(&policy(rule), Some(&doc), true), expected);
+    }
+    // Unbound at render on both paths: identical errors, not empty text.
+    let text = policy(
+        r#"deny with message f"acl {acl}" if (input.size > 100 || acl := input.acl) && input.name == "logs""#,
+    );
+    let err = check(&text, Some(&json!({"name": "logs", "size": 500})), true).unwrap_err();
+    assert!(err.contains("Undefined variable: acl"), "{err}");
+}
+
+#[test]
+fn strings_format_is_strict_about_its_template() {
+    let doc = json!({"name": "logs"});
+    for rule in [
+        r#"deny with message strings::format("{} and {}", input.name) if true"#,
+        r#"deny with message strings::format("{}", input.name, input.name) if true"#,
+        r#"deny with message strings::format("{oops}", input.name) if true"#,
+        r#"deny with message strings::format(3, input.name) if true"#,
+    ] {
+        let err = check(&policy(rule), Some(&doc), false).unwrap_err();
+        assert!(err.contains("strings::format()"), "{rule}: {err}");
+    }
+    assert_eq!(
+        message(
+            &policy(r#"deny with message strings::format("{}/{}", input.name, 3) if true"#),
+            Some(&doc),
+            false
+        ),
+        "logs/3"
+    );
+}
+
+#[test]
+fn fstrings_compare_and_assign() {
+    let doc = json!({"env": "prod", "bucket": "prod-logs", "team": "core"});
+    let text = policy(
+        r#"deny with message "naming" if input.bucket != f"{input.env}-logs" || label := f"{input.team}/{input.env}" && label != "core/prod""#,
+    );
+    let (allowed, _) = check(&text, Some(&doc), false).unwrap();
+    assert!(allowed);
+    let bad = json!({"env": "dev", "bucket": "prod-logs", "team": "core"});
+    let (allowed, messages) = check(&text, Some(&bad), false).unwrap();
+    assert!(!allowed);
+    assert_eq!(messages, vec![Some("naming".to_string())]);
+
+    let allow = "policy s {\n    default: deny,\n    rule r {\n        allow if context.path == f\"/teams/{user}\"\n    }\n}\n";
+    let mut context = HashMap::new();
+    context.insert("principal".to_string(), "alice".into());
+    context.insert("path".to_string(), "/teams/alice".into());
+    let req = PolicyRequest {
+        context,
+        ..request()
+    };
+    let evaluator = ReaperPolicy::from_str(allow)
+        .unwrap()
+        .build_ast_evaluator(store());
+    let (decision, _) = evaluator.evaluate_with_input_named(&req, None).unwrap();
+    assert_eq!(decision, PolicyAction::Allow);
+}
@@ -27,9 +27,9 @@ fn store() -> Arc<DataStore> {
 
 fn req(context: &[(&str, &str)], prov: Option<&[(&str, TrustLevel)]>) -> PolicyRequest {
     let mut ctx = HashMap::new();
-    ctx.insert("principal".to_string(), "alice".to_string());
+    ctx.insert("principal".to_string(), "alice".into());
     for (k, v) in context {
-        ctx.insert(k.to_string(), v.to_string());
+        ctx.insert(k.to_string(), (*v).into());
     }
     PolicyRequest {
         resource: "r".to_string(),
@@ -0,0 +1,286 @@
+//! Typed request-context differential tests.
+//!
+//! Contract: `PolicyRequest::context` carries full JSON, and every context
+//! shape the compiler lowers — literal comparisons, `in` membership,
+//! arithmetic operands, cross-entity comparisons and `net::` range lists —
+//! returns the same decision and deciding rule as the interpreter, over a
+//! context matrix mixing numbers, bools, arrays, objects, null, and the
+//! pre-typed string encodings of the same values. Plus taint provenance
+//! for dotted paths, keyed by the top-level context key.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use policy_engine::data::{DataLoader, DataStore};
+use policy_engine::reap::ReaperPolicy;
+use policy_engine::{PolicyAction, PolicyRequest, TrustLevel};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+fn store() -> Arc<DataStore> {
+    let store = Arc::new(DataStore::new());
+    DataLoader::new((*store).clone())
+        .load_json(
+            &json!({"entities": [
+                {"id": "alice", "type": "user",
+                 "attributes": {"limit": 1000, "region": "eu", "level": 3}},
+                {"id": "doc", "type": "resource", "attributes": {"owner": "alice"}}
+            ]})
+            .to_string(),
+        )
+        .expect("load");
+    store
+}
+
+fn request(context: &Value) -> PolicyRequest {
+    let mut map: HashMap<String, Value> = context
+        .as_object()
+        .expect("context object")
+        .iter()
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+    map.insert("principal".to_string(), "alice".into());
+    PolicyRequest {
+        resource: "doc".to_string(),
+        action: "approve".to_string(),
+        context: map,
+        ..Default::default()
+    }
+}
+
+fn policy(cond: &str) -> String {
+    format!("policy typed {{\n    default: deny,\n    rule r {{ allow if {cond} }}\n}}")
+}
+
+/// Assert the policy COMPILES whole and both paths agree (decision + rule
+/// name) for `context`. Returns the shared decision.
+fn assert_equivalent(cond: &str, context: &Value) -> PolicyAction {
+    let parsed = ReaperPolicy::from_str(&policy(cond)).expect("parse");
+    let compiled = parsed
+        .clone()
+        .build(store())
+        .expect("context condition must compile whole");
+    let ast = parsed.build_ast_evaluator(store());
+
+    let req = request(context);
+    let (c_dec, c_name) = compiled
+        .evaluate_with_input_named(&req, None)
+        .expect("compiled evaluate");
+    let (a_dec, a_name) = ast
+        .evaluate_with_input_named(&req, None)
+        .expect("ast evaluate");
+    assert_eq!(
+        c_dec, a_dec,
+        "decision diverged\ncontext: {context}\ncondition: {cond}"
+    );
+    assert_eq!(c_name, a_name, "rule name diverged: {cond}");
+    c_dec
+}
+
+fn contexts() -> Vec<Value> {
+    vec![
+        json!({"amount": 1500, "tags": ["x", "y"], "urgent": true, "order": {"total": 2.5, "cur": "eur"}}),
+        json!({"amount": 999.5, "tags": [], "urgent": false, "order": {"total": 0}}),
+        json!({"amount": 1000, "tags": ["x", 7, true], "urgent": "true", "order": "flat"}),
+        json!({"amount": "1500", "tags": "x", "urgent": null, "order": null}),
+        json!({"amount": [1500], "tags": {"x": 1}, "urgent": 1}),
+        json!({}),
+    ]
+}
+
+#[test]
+fn literal_comparisons_match_ast() {
+    for cond in [
+        "context.amount > 1000",
+        "context.amount >= 1000",
+        "context.amount < 1000",
+        "context.amount == 1500",
+        "context.amount != 1500",
+        "context.amount == \"1500\"",
+        "context.urgent == true",
+        "context.urgent != false",
+        "context.tags == \"x\"",
+        "context.tags != \"x\"",
+        "context.order.total > 1",
+        "context.order.cur == \"eur\"",
+        "context.order.total == null",
+        "context.urgent != null",
+    ] {
+        for context in contexts() {
+            assert_equivalent(cond, &context);
+        }
+    }
+}
+
+#[test]
+fn membership_matches_ast() {
+    for cond in [
+        "\"x\" in context.tags",
+        "\"z\" in context.tags",
+        "7 in context.tags",
+        "true in context.tags",
+    ] {
+        for context in contexts() {
+            assert_equivalent(cond, &context);
+        }
+    }
+}
+
+#[test]
+fn arithmetic_and_cross_entity_match_ast() {
+    for cond in [
+        "context.amount + 1 > user.limit",
+        "context.order.total * 2 == 5.0",
+        "context.amount - user.limit >= 0",
+        "context.amount > user.limit",
+        "context.amount == user.limit",
+        "context.urgent == true && context.amount <= user.limit",
+    ] {
+        for context in contexts() {
+            assert_equivalent(cond, &context);
+        }
+    }
+}
+
+#[test]
+fn net_ranges_from_context_match_ast() {
+    let contexts = [
+        json!({"ip": "10.1.2.3", "ranges": ["192.168.0.0/16", "10.0.0.0/8"]}),
+        json!({"ip": "10.1.2.3", "ranges": [7, "bad", "10.0.0.0/8"]}),
+        json!({"ip": "10.1.2.3", "ranges": ["192.168.0.0/16"]}),
+        json!({"ip": "10.1.2.3", "ranges": "10.0.0.0/8"}),
+        json!({"ip": 7, "ranges": ["10.0.0.0/8"]}),
+        json!({"ip": "10.1.2.3"}),
+    ];
+    for cond in [
+        "net::ip_in_any(context.ip, context.ranges)",
+        "net::cidr_contains(\"10.0.0.0/8\", context.ip)",
+    ] {
+        for context in &contexts {
+            assert_equivalent(cond, context);
+        }
+    }
+    assert_eq!(
+        assert_equivalent("net::ip_in_any(context.ip, context.ranges)", &contexts[0]),
+        PolicyAction::Allow
+    );
+}
+
+#[test]
+fn pinned_typed_decisions() {
+    let typed = json!({"amount": 1500, "tags": ["x"], "urgent": true, "order": {"total": 2.5}});
+    for cond in [
+        "context.amount > 1000",
+        "\"x\" in context.tags",
+        "context.urgent == true",
+        "context.order.total < 3",
+        "context.amount - 500 == user.limit",
+    ] {
+        assert_eq!(
+            assert_equivalent(cond, &typed),
+            PolicyAction::Allow,
+            "{cond}"
+        );
+    }
+
+    // Strings are not coerced: a number never equals its string form.
+    let stringly = json!({"amount": "1500", "urgent": "true"});
+    for cond in [
+        "context.amount == 1500",
+        "context.amount > 1000",
+        "context.urgent == true",
+    ] {
+        assert_eq!(
+            assert_equivalent(cond, &stringly),
+            PolicyAction::Deny,
+            "{cond}"
+        );
+    }
+    assert_eq!(
+        assert_equivalent("context.amount == \"1500\"", &typed),
+        PolicyAction::Deny
+    );
+}
+
+#[test]
+fn message_variables_render_typed_values() {
+    let text = r#"
+policy limits {
+    default: allow,
+    rule too_big {
+        deny with message amount if context.amount > 1000
+    }
+}
+"#;
+    // A bare message variable the rule never binds falls back to the request
+    // context (interpreter-only: the compiler rejects unbound message vars).
+    let ast = ReaperPolicy::from_str(text)
+        .expect("parse")
+        .build_ast_evaluator(store());
+    for (amount, rendered) in [
+        (json!(1500), "1500"),
+        (json!(1500.5), "1500.5"),
+        (json!("1500"), ""),
+    ] {
+        let result = ast
+            .check_with_input(&request(&json!({"amount": amount})), None)
+            .expect("ast check");
+        let messages: Vec<_> = result
+            .violations
+            .iter()
+            .map(|v| v.message.as_deref())
+            .collect();
+        if rendered.is_empty() {
+            assert!(messages.is_empty(), "strings do not order: {messages:?}");
+        } else {
+            assert_eq!(messages, vec![Some(rendered)]);
+        }
+    }
+}
+
+#[test]
+fn taint_labels_apply_to_dotted_paths_by_top_level_key() {
+    let text = r#"
+policy taint {
+    default: deny,
+    rule r { allow if taint::trusted("order.total") && context.order.total > 1 }
+}
+"#;
+    let parsed = ReaperPolicy::from_str(text).expect("parse");
+    let compiled = parsed.clone().build(store()).expect("compiles");
+    let ast = parsed.build_ast_evaluator(store());
+
+    for (label, expected) in [
+        (TrustLevel::Verified, PolicyAction::Allow),
+        (TrustLevel::Llm, PolicyAction::Deny),
+    ] {
+        let mut req = request(&json!({"order": {"total": 2}}));
+        req.context_provenance = Some(HashMap::from([("order".to_string(), label)]));
+        assert_eq!(req.context_trust("order.total"), label);
+        let (c_dec, _) = compiled.evaluate_with_input_named(&req, None).unwrap();
+        let (a_dec, _) = ast.evaluate_with_input_named(&req, None).unwrap();
+        assert_eq!(c_dec, expected, "{label:?}");
+        assert_eq!(a_dec, expected, "{label:?}");
+    }
+}
+
+#[test]
+fn indexed_context_access_falls_back_to_the_interpreter() {
+    // No compiled leaf reads `context` through an index; one that tried
+    // would see no entity and deny. The rule must run on the AST instead.
+    let text = policy("context.headers[\"x-tenant\"] == \"acme\"");
+    let parsed = ReaperPolicy::from_str(&text).expect("parse");
+    assert!(parsed.clone().build(store()).is_err(), "must not compile");
+
+    let preferred = parsed.build_preferred(store()).expect("builds");
+    for (headers, expected) in [
+        (json!({"x-tenant": "acme"}), PolicyAction::Allow),
+        (json!({"x-tenant": "other"}), PolicyAction::Deny),
+        (json!({}), PolicyAction::Deny),
+    ] {
+        let req = request(&json!({"headers": headers}));
+        let decision = preferred.evaluate_named(&req).unwrap().decision;
+        assert_eq!(decision, expected, "headers: {headers}");
+    }
+}
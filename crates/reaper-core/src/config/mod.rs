@@ -23,8 +23,8 @@ mod settings;
 pub use error::ConfigError;
 pub use settings::{
     is_loopback_bind, AgentAuthMode, AgentAuthSettings, AgentSettings, CacheSettings, DataSettings,
-    ManagementSettings, ObservabilitySettings, PerformanceSettings, PolicySettings,
-    RevocationStaleness, TlsSettings, UdsSettings,
+    ExtAuthzSettings, ManagementSettings, ObservabilitySettings, PerformanceSettings,
+    PolicySettings, RevocationStaleness, TlsSettings, UdsSettings,
 };
 
 use serde::{Deserialize, Serialize};
@@ -79,6 +79,10 @@ pub struct ReaperAgentConfig {
     /// Unix Domain Socket settings
     #[serde(default)]
     pub uds: UdsSettings,
+
+    /// Envoy ext_authz gRPC endpoint settings
+    #[serde(default)]
+    pub ext_authz: ExtAuthzSettings,
 }
 
 // ============================================================================
@@ -177,6 +181,10 @@ impl ReaperAgentConfig {
             self.performance.allow_evaluate_all =
                 matches!(val.to_lowercase().as_str(), "true" | "1" | "yes" | "on");
         }
+        if let Ok(val) = std::env::var("REAPER_ALLOW_EXPLAIN") {
+            self.performance.allow_explain =
+                matches!(val.to_lowercase().as_str(), "true" | "1" | "yes" | "on");
+        }
         if let Ok(val) = std::env::var("REAPER_MAX_CANDIDATE_POLICIES") {
             if let Ok(max) = val.parse::<usize>() {
                 if max > 0 {
@@ -188,6 +196,19 @@ impl ReaperAgentConfig {
             self.performance.use_pruning_index =
                 matches!(val.to_lowercase().as_str(), "true" | "1" | "yes" | "on");
         }
+        // List authorization (POST /api/v1/filter) budgets.
+        if let Ok(val) = std::env::var("REAPER_MAX_FILTER_CANDIDATES") {
+            if let Ok(max) = val.parse::<usize>() {
+                if max > 0 {
+                    self.performance.max_filter_candidates = max;
+                }
+            }
+        }
+        if let Ok(val) = std::env::var("REAPER_MAX_FILTER_IN_LIST") {
+            if let Ok(max) = val.parse::<usize>() {
+                self.performance.max_filter_in_list = max;
+            }
+        }
 
         // Observability settings
         if let Ok(val) = std::env::var("REAPER_LOG_LEVEL") {
@@ -305,6 +326,32 @@ impl ReaperAgentConfig {
             self.uds.pin_cores = matches!(val.to_lowercase().as_str(), "true" | "1" | "yes" | "on");
         }
 
+        // Envoy ext_authz settings
+        if let Ok(val) = std::env::var("REAPER_EXT_AUTHZ_ENABLED") {
+            self.ext_authz.enabled =
+                matches!(val.to_lowercase().as_str(), "true" | "1" | "yes" | "on");
+        }
+        if let Ok(val) = std::env::var("REAPER_EXT_AUTHZ_BIND_ADDRESS") {
+            self.ext_authz.bind_address = val;
+        }
+        if let Ok(val) = std::env::var("REAPER_EXT_AUTHZ_PORT") {
+            if let Ok(port) = val.parse::<u16>() {
+                self.ext_authz.port = port;
+            }
+        }
+        if let Ok(val) = std::env::var("REAPER_EXT_AUTHZ_POLICY") {
+            self.ext_authz.policy = Some(val);
+        }
+        if let Ok(val) = std::env::var("REAPER_EXT_AUTHZ_PACKAGE") {
+            self.ext_authz.package = Some(val);
+        }
+        if let Ok(val) = std::env::var("REAPER_EXT_AUTHZ_PRINCIPAL_HEADER") {
+            self.ext_authz.principal_header = Some(val);
+        }
+        if let Ok(val) = std::env::var("REAPER_EXT_AUTHZ_JWT_PAYLOAD_HEADER") {
+            self.ext_authz.jwt_payload_header = Some(val);
+        }
+
         // Inbound auth settings
         if let Ok(val) = std::env::var("REAPER_AGENT_AUTH_ENABLED") {
             self.auth.enabled = matches!(val.to_lowercase().as_str(), "true" | "1" | "yes" | "on");
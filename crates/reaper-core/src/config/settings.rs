@@ -156,8 +156,10 @@ pub struct AgentAuthSettings {
     /// **management plane** (bundle/policy/data deploy + mutation). Lets a
     /// co-located app call the evaluator with zero auth overhead while a
     /// remote or shared caller still needs a credential to push policy.
-    /// Bundle *signing* is independent and stays enforced on the deploy path
-    /// regardless of this flag. Default false (auth gates everything).
+    /// List filtering and relationship queries enumerate what a principal
+    /// can reach, so they stay gated. Bundle *signing* is independent and
+    /// stays enforced on the deploy path regardless of this flag. Default
+    /// false (auth gates everything).
     #[serde(default)]
     pub open_data_plane: bool,
 
@@ -369,6 +371,14 @@ pub struct PerformanceSettings {
     #[serde(default)]
     pub allow_evaluate_all: bool,
 
+    /// Serve `POST /api/v1/messages?explain=full`, which answers with a trace
+    /// of every rule evaluated and the attribute values each condition saw.
+    /// Default **false**: a trace exposes entity data and policy internals to
+    /// the caller and bypasses the decision cache, so it is a debugging aid to
+    /// arm deliberately. Disabled requests are refused with 403.
+    #[serde(default)]
+    pub allow_explain: bool,
+
     /// Hard cap on candidate policies for an evaluate-all request after pruning
     /// (Plan 08 Phase A). If the pruning index yields more than this many
     /// candidates the request is rejected with `candidate_cap_exceeded` rather
@@ -383,6 +393,18 @@ pub struct PerformanceSettings {
     /// which the index ships (ADR-1 / Risk mitigation).
     #[serde(default = "default_true")]
     pub use_pruning_index: bool,
+
+    /// Verify budget for `POST /api/v1/filter` (`mode=ids`): the most
+    /// candidates index narrowing may leave for point evaluation. Over it
+    /// the query fails with `candidates_exceeded` instead of evaluating the
+    /// whole resource type.
+    #[serde(default = "default_max_filter_candidates")]
+    pub max_filter_candidates: usize,
+
+    /// Largest relationship id-set `mode=sql` inlines as `IN (...)`; a
+    /// bigger set widens its atom to `TRUE` and the filter becomes inexact.
+    #[serde(default = "default_max_filter_in_list")]
+    pub max_filter_in_list: usize,
 }
 
 impl Default for PerformanceSettings {
@@ -393,8 +415,11 @@ impl Default for PerformanceSettings {
             enable_simd: true,
             max_batch_requests: default_max_batch_requests(),
             allow_evaluate_all: false,
+            allow_explain: false,
             max_candidate_policies: default_max_candidate_policies(),
             use_pruning_index: true,
+            max_filter_candidates: default_max_filter_candidates(),
+            max_filter_in_list: default_max_filter_in_list(),
         }
     }
 }
@@ -411,6 +436,14 @@ fn default_max_candidate_policies() -> usize {
     256
 }
 
+fn default_max_filter_candidates() -> usize {
+    10_000
+}
+
+fn default_max_filter_in_list() -> usize {
+    1000
+}
+
 // ============================================================================
 // Cache Settings
 // ============================================================================
@@ -789,6 +822,96 @@ fn default_socket_permissions() -> u32 {
     0o660
 }
 
+// ============================================================================
+// Envoy ext_authz Settings
+// ============================================================================
+
+/// Envoy external-authorization gRPC endpoint
+/// (`envoy.service.auth.v3.Authorization/Check`).
+///
+/// Envoy (or an Istio `CUSTOM` authorization provider) calls the agent
+/// directly; each `CheckRequest` is mapped onto a policy request and
+/// evaluated against `policy` or every policy in `package`. A route may
+/// override either with the `reaper_policy` / `reaper_package` context
+/// extensions.
+///
+/// # Security
+///
+/// The gRPC listener has **no application-layer authentication** and trusts
+/// the identity headers named below. Bind it where only the proxy can reach
+/// it (the default is loopback, for the sidecar layout) and make sure the
+/// proxy strips client-supplied copies of those headers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtAuthzSettings {
+    /// Enable the ext_authz gRPC listener (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Address to bind to (default: 127.0.0.1)
+    #[serde(default = "default_ext_authz_bind_address")]
+    pub bind_address: String,
+
+    /// Port to listen on (default: 9191)
+    #[serde(default = "default_ext_authz_port")]
+    pub port: u16,
+
+    /// Policy evaluated for every check, by name or UUID
+    pub policy: Option<String>,
+
+    /// Package whose policies are evaluated for every check (used when
+    /// `policy` is unset)
+    pub package: Option<String>,
+
+    /// Header carrying an already-authenticated principal (e.g. one set by
+    /// Envoy's `jwt_authn` `claim_to_headers`). Used only when neither a JWT
+    /// `sub` nor an mTLS peer principal is present. Envoy passes client
+    /// headers through by default, so the route must strip or overwrite this
+    /// one; otherwise a client picks its own principal.
+    pub principal_header: Option<String>,
+
+    /// Header carrying the verified JWT payload as base64url JSON (Envoy
+    /// `jwt_authn` `forward_payload_header`). Its `sub` claim is the
+    /// principal; all claims are exposed to policies as `context.jwt`.
+    pub jwt_payload_header: Option<String>,
+
+    /// Principal used when no identity is present. Unset = such requests
+    /// are denied without evaluation.
+    pub anonymous_principal: Option<String>,
+
+    /// HTTP status Envoy returns to the client on deny, unless the deciding
+    /// rule supplies a `status` obligation (default: 403)
+    #[serde(default = "default_ext_authz_deny_status")]
+    pub deny_status: u16,
+}
+
+impl Default for ExtAuthzSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: default_ext_authz_bind_address(),
+            port: default_ext_authz_port(),
+            policy: None,
+            package: None,
+            principal_header: None,
+            jwt_payload_header: None,
+            anonymous_principal: None,
+            deny_status: default_ext_authz_deny_status(),
+        }
+    }
+}
+
+fn default_ext_authz_bind_address() -> String {
+    "127.0.0.1".to_string()
+}
+
+fn default_ext_authz_port() -> u16 {
+    9191
+}
+
+fn default_ext_authz_deny_status() -> u16 {
+    403
+}
+
 // ============================================================================
 // TLS Settings
 // ============================================================================
@@ -228,9 +228,9 @@ async fn simulate_traffic(_ebpf_engine: &EbpfPolicyEngine) -> Result<()> {
 
         // Create request
         let mut context = HashMap::new();
-        context.insert("role".to_string(), "user".to_string());
-        context.insert("active".to_string(), "true".to_string());
-        context.insert("department".to_string(), "engineering".to_string());
+        context.insert("role".to_string(), "user".into());
+        context.insert("active".to_string(), "true".into());
+        context.insert("department".to_string(), "engineering".into());
 
         let request = PolicyRequest {
             resource: resource.to_string(),
@@ -175,7 +175,7 @@ fn benchmark_userspace_baseline() -> anyhow::Result<()> {
     let mut latencies = Vec::with_capacity(iterations);
 
     let mut context = HashMap::new();
-    context.insert("role".to_string(), "user".to_string());
+    context.insert("role".to_string(), "user".into());
 
     let request = PolicyRequest {
         resource: "/api/users".to_string(),
@@ -179,6 +179,9 @@ impl ConditionAnalyzer {
                     "Expression conditions not yet supported in eBPF".to_string(),
                 );
             }
+            ReapCondition::Quantifier { .. } => {
+                result.add_blocking_reason("Quantifier blocks not supported in eBPF".to_string());
+            }
         }
 
         result
@@ -146,9 +146,9 @@ impl PolicyEvent {
         use std::collections::HashMap;
 
         let mut context = HashMap::new();
-        context.insert("uid".to_string(), self.uid.to_string());
-        context.insert("gid".to_string(), self.gid.to_string());
-        context.insert("pid".to_string(), self.pid.to_string());
+        context.insert("uid".to_string(), self.uid.into());
+        context.insert("gid".to_string(), self.gid.into());
+        context.insert("pid".to_string(), self.pid.into());
 
         policy_engine::PolicyRequest {
             resource: self.path_str(),
@@ -113,6 +113,7 @@ async fn test_bundle_deployment_routing() {
                 decision: Decision::Allow,
                 condition: Condition::True,
                 message: None,
+                obligations: None,
             },
             // Complex rule - should stay in userspace
             // (using False as a stand-in for more complex conditions that would require
@@ -122,6 +123,7 @@ async fn test_bundle_deployment_routing() {
                 decision: Decision::Deny,
                 condition: Condition::False,
                 message: None,
+                obligations: None,
             },
         ],
         functions: vec![],
@@ -137,6 +139,7 @@ async fn test_bundle_deployment_routing() {
             source_checksum: 0,
         },
         policy,
+        schema: None,
     };
 
     // Note: We can't test actual deployment without a compiled eBPF program
@@ -224,9 +227,9 @@ fn test_policy_event_conversion() {
     let request = event.to_policy_request();
     assert_eq!(request.resource, "comm:test_process");
     assert_eq!(request.action, "open");
-    assert_eq!(request.context.get("uid"), Some(&"1000".to_string()));
-    assert_eq!(request.context.get("gid"), Some(&"1000".to_string()));
-    assert_eq!(request.context.get("pid"), Some(&"1234".to_string()));
+    assert_eq!(request.context.get("uid"), Some(&serde_json::json!(1000)));
+    assert_eq!(request.context.get("gid"), Some(&serde_json::json!(1000)));
+    assert_eq!(request.context.get("pid"), Some(&serde_json::json!(1234)));
 }
 
 #[test]
@@ -47,10 +47,11 @@ async fn main() -> Result<()> {
         resource: "/api/data".to_string(),
         context: {
             let mut ctx = HashMap::new();
-            ctx.insert("department".to_string(), "engineering".to_string());
-            ctx.insert("clearance".to_string(), "level-3".to_string());
+            ctx.insert("department".to_string(), "engineering".into());
+            ctx.insert("clearance".to_string(), "level-3".into());
             ctx
         },
+        min_revision: None,
     };
 
     println!("  Policy ID: {}", request.policy_id);
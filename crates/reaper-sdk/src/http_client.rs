@@ -63,6 +63,7 @@ impl ReaperHttpClient {
     ///     action: "read".to_string(),
     ///     resource: "/api/data".to_string(),
     ///     context: HashMap::new(),
+    ///     min_revision: None,
     /// };
     ///
     /// let response = client.evaluate(request).await?;
@@ -31,6 +31,7 @@
 //!         action: "read".to_string(),
 //!         resource: "/api/data".to_string(),
 //!         context: HashMap::new(),
+//!         min_revision: None,
 //!     };
 //!
 //!     let response = client.evaluate(request).await?;
@@ -61,8 +62,8 @@ pub use error::{ReaperError, Result};
 pub use http_client::ReaperHttpClient;
 pub use transport::Transport;
 pub use types::{
-    Decision, DeployBundleRequest, DeployBundleResponse, EntityData, PolicyRequest, PolicyResponse,
-    Relationship, Source,
+    Decision, DeployBundleRequest, DeployBundleResponse, EntityData, FilterIdsPage, FilterMode,
+    FilterRequest, PolicyRequest, PolicyResponse, Relationship, Source, SqlFilter, SqlMapping,
 };
 pub use uds_client::ReaperUdsClient;
 
@@ -173,6 +174,58 @@ impl ReaperClient {
         }
     }
 
+    /// List the resources of `request.resource_type` the principal may act
+    /// on: one page of permitted ids from the agent's data store.
+    ///
+    /// Replaces fetching a page of rows and point-checking each through
+    /// `/api/v1/batch-messages`. Pass the returned `next_cursor` back as
+    /// `request.cursor` for the next page.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use reaper_sdk::{FilterRequest, ReaperClient};
+    ///
+    /// # async fn example() -> reaper_sdk::Result<()> {
+    /// let client = ReaperClient::http("http://localhost:8080")?;
+    /// let mut request = FilterRequest {
+    ///     principal: "alice".to_string(),
+    ///     action: "read".to_string(),
+    ///     resource_type: "document".to_string(),
+    ///     ..Default::default()
+    /// };
+    /// loop {
+    ///     let page = client.filter_ids(&request).await?;
+    ///     println!("{:?}", page.ids);
+    ///     match page.next_cursor {
+    ///         Some(cursor) => request.cursor = Some(cursor),
+    ///         None => break,
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn filter_ids(&self, request: &FilterRequest) -> Result<FilterIdsPage> {
+        let request = FilterRequest {
+            mode: FilterMode::Ids,
+            ..request.clone()
+        };
+        self.post_json("/api/v1/filter", &request).await
+    }
+
+    /// Compile the same question into a parameterized SQL `WHERE` clause,
+    /// for resources that live in the caller's database.
+    ///
+    /// When the returned filter is not `exact`, the rows it matches are a
+    /// superset and each must still be verified with [`Self::evaluate`].
+    pub async fn filter_sql(&self, request: &FilterRequest) -> Result<SqlFilter> {
+        let request = FilterRequest {
+            mode: FilterMode::Sql,
+            sql: Some(request.sql.clone().unwrap_or_default()),
+            ..request.clone()
+        };
+        self.post_json("/api/v1/filter", &request).await
+    }
+
     /// Check agent health.
     ///
     /// # Returns
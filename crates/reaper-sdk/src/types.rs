@@ -14,9 +14,13 @@ pub struct PolicyRequest {
     pub action: String,
     /// Resource being accessed
     pub resource: String,
-    /// Additional context for evaluation
+    /// Additional context for evaluation; any JSON value
     #[serde(default)]
-    pub context: HashMap<String, String>,
+    pub context: HashMap<String, serde_json::Value>,
+    /// Revision token from a datastore write; the agent waits briefly for
+    /// it and otherwise answers 412 `revision_unavailable` without deciding
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_revision: Option<i64>,
 }
 
 /// Response from policy evaluation
@@ -30,6 +34,14 @@ pub struct PolicyResponse {
     /// Where the policy was evaluated
     #[serde(default)]
     pub source: Source,
+    /// Obligations the deciding rule attached (`with obligations {...}`);
+    /// the PEP must honour these or refuse to act on the decision
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub obligations: serde_json::Map<String, serde_json::Value>,
+    /// Advisory hints the deciding rule attached (`with advice {...}`);
+    /// safe to ignore
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub advice: serde_json::Map<String, serde_json::Value>,
 }
 
 /// Policy decision
@@ -116,3 +128,107 @@ pub struct Relationship {
     /// ID of the entity this relationship points to
     pub target_id: String,
 }
+
+/// What [`FilterRequest`] asks the agent to return
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterMode {
+    /// Permitted resource ids from the agent's data store, one page at a time
+    #[default]
+    Ids,
+    /// A parameterized SQL `WHERE` clause for resources in the caller's database
+    Sql,
+}
+
+/// Column mapping for [`FilterMode::Sql`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SqlMapping {
+    /// Column holding the resource id
+    pub id_column: String,
+    /// Resource attribute name to column name
+    #[serde(default)]
+    pub columns: HashMap<String, String>,
+    /// Placeholder style: `"dollar"` (`$1`) or `"question"` (`?`)
+    pub param_style: String,
+}
+
+impl Default for SqlMapping {
+    fn default() -> Self {
+        Self {
+            id_column: "id".to_string(),
+            columns: HashMap::new(),
+            param_style: "dollar".to_string(),
+        }
+    }
+}
+
+/// Request for the resources of one type a principal may act on
+/// (`POST /api/v1/filter`)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct FilterRequest {
+    /// Policy ID or name to filter by; `None` composes every deployed policy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy_id: Option<String>,
+    /// Principal (user/service) making the request
+    pub principal: String,
+    /// Action being performed
+    pub action: String,
+    /// Entity type of the resources to list
+    pub resource_type: String,
+    /// Additional context for evaluation; any JSON value
+    #[serde(default)]
+    pub context: HashMap<String, serde_json::Value>,
+    /// Ids or SQL
+    #[serde(default)]
+    pub mode: FilterMode,
+    /// Previous page's `next_cursor` (ids mode)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    /// Page size (ids mode; agent default 100, max 1000)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    /// Column mapping (SQL mode)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sql: Option<SqlMapping>,
+    /// Revision token from a datastore write (see [`PolicyRequest::min_revision`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_revision: Option<i64>,
+}
+
+/// One page of permitted resource ids
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilterIdsPage {
+    /// Permitted ids, in ascending order
+    pub ids: Vec<String>,
+    /// Cursor for the next page; `None` on the last page
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+    /// Candidates the agent verified after index narrowing
+    #[serde(default)]
+    pub candidates: usize,
+    /// Agent data epoch the page was computed at
+    #[serde(default)]
+    pub data_epoch: u64,
+    /// The agent's data changed twice while computing the page
+    #[serde(default)]
+    pub epoch_moved: bool,
+}
+
+/// A SQL filter to drop into a `WHERE` clause
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SqlFilter {
+    /// Boolean SQL expression
+    pub sql: String,
+    /// Placeholder values, in order
+    #[serde(default)]
+    pub params: Vec<serde_json::Value>,
+    /// `false` when the filter is a superset and rows must still be
+    /// verified with [`PolicyRequest`]s
+    pub exact: bool,
+    /// Residual atoms the filter widened over
+    #[serde(default)]
+    pub unverified_atoms: usize,
+    /// Agent data epoch the filter was computed at
+    #[serde(default)]
+    pub data_epoch: u64,
+}
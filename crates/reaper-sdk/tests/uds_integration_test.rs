@@ -4,7 +4,9 @@
 //! and verify the SDK's UDS client can communicate with it.
 
 use axum::{routing::get, routing::post, Json, Router};
-use reaper_sdk::{Decision, PolicyRequest, PolicyResponse, ReaperClient, Source};
+use reaper_sdk::{
+    Decision, FilterMode, FilterRequest, PolicyRequest, PolicyResponse, ReaperClient, Source,
+};
 use std::collections::HashMap;
 use tempfile::TempDir;
 use tokio::net::UnixListener;
@@ -14,6 +16,7 @@ fn mock_agent_router() -> Router {
     Router::new()
         .route("/health", get(health_handler))
         .route("/api/v1/messages", post(evaluate_handler))
+        .route("/api/v1/filter", post(filter_handler))
 }
 
 async fn health_handler() -> &'static str {
@@ -25,9 +28,40 @@ async fn evaluate_handler(Json(_request): Json<PolicyRequest>) -> Json<PolicyRes
         decision: Decision::Allow,
         latency_ns: 42,
         source: Source::Userspace,
+        obligations: serde_json::Map::from_iter([("mask".to_string(), serde_json::json!(["ssn"]))]),
+        advice: serde_json::Map::new(),
     })
 }
 
+/// Two-page listing of `doc-1..doc-3`, or a fixed SQL filter.
+async fn filter_handler(Json(request): Json<FilterRequest>) -> Json<serde_json::Value> {
+    match request.mode {
+        FilterMode::Ids => match request.cursor.as_deref() {
+            None => Json(serde_json::json!({
+                "ids": ["doc-1", "doc-2"],
+                "next_cursor": "doc-2",
+                "candidates": 3,
+                "data_epoch": 7,
+                "epoch_moved": false
+            })),
+            Some(_) => Json(serde_json::json!({
+                "ids": ["doc-3"],
+                "next_cursor": null,
+                "candidates": 3,
+                "data_epoch": 7,
+                "epoch_moved": false
+            })),
+        },
+        FilterMode::Sql => Json(serde_json::json!({
+            "sql": format!("({} = $1)", request.sql.map(|m| m.id_column).unwrap_or_default()),
+            "params": ["doc-1"],
+            "exact": true,
+            "unverified_atoms": 0,
+            "data_epoch": 7
+        })),
+    }
+}
+
 #[tokio::test]
 async fn test_uds_health_check() {
     let tmp_dir = TempDir::new().unwrap();
@@ -72,11 +106,14 @@ async fn test_uds_evaluate() {
         action: "read".to_string(),
         resource: "/api/data".to_string(),
         context: HashMap::new(),
+        min_revision: None,
     };
 
     let response = client.evaluate(request).await.unwrap();
     assert_eq!(response.decision, Decision::Allow);
     assert_eq!(response.latency_ns, 42);
+    assert_eq!(response.obligations["mask"], serde_json::json!(["ssn"]));
+    assert!(response.advice.is_empty());
     assert_eq!(response.source, Source::Userspace);
 }
 
@@ -111,6 +148,7 @@ async fn test_uds_post_json_generic() {
         action: "read".to_string(),
         resource: "/api/data".to_string(),
         context: HashMap::new(),
+        min_revision: None,
     };
 
     let response: PolicyResponse = client
@@ -144,9 +182,51 @@ async fn test_uds_multiple_requests() {
             action: "read".to_string(),
             resource: "/api/data".to_string(),
             context: HashMap::new(),
+            min_revision: None,
         };
 
         let response = client.evaluate(request).await.unwrap();
         assert_eq!(response.decision, Decision::Allow);
     }
 }
+
+#[tokio::test]
+async fn test_uds_filter_pages_and_sql() {
+    let tmp_dir = TempDir::new().unwrap();
+    let socket_path = tmp_dir.path().join("test-agent.sock");
+
+    let uds_listener = UnixListener::bind(&socket_path).unwrap();
+    let app = mock_agent_router();
+
+    tokio::spawn(async move {
+        axum::serve(uds_listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+    let client = ReaperClient::unix(&socket_path).unwrap();
+    let mut request = FilterRequest {
+        principal: "alice".to_string(),
+        action: "read".to_string(),
+        resource_type: "document".to_string(),
+        limit: Some(2),
+        ..Default::default()
+    };
+
+    let mut ids = Vec::new();
+    loop {
+        let page = client.filter_ids(&request).await.unwrap();
+        assert_eq!(page.data_epoch, 7);
+        ids.extend(page.ids);
+        match page.next_cursor {
+            Some(cursor) => request.cursor = Some(cursor),
+            None => break,
+        }
+    }
+    assert_eq!(ids, vec!["doc-1", "doc-2", "doc-3"]);
+
+    let filter = client.filter_sql(&request).await.unwrap();
+    assert_eq!(filter.sql, "(id = $1)");
+    assert_eq!(filter.params, vec![serde_json::json!("doc-1")]);
+    assert!(filter.exact);
+}
@@ -9,9 +9,8 @@
 //! - **Parity with the agent's serving semantics.** The wrapper mirrors what
 //!   `services/reaper-agent` does around the engine: the principal is
 //!   injected as `context["principal"]` (and nothing else — `context.action`
-//!   is resolved by the evaluator from the typed request field), and scalar
-//!   context values are coerced to strings exactly like the agent's fast
-//!   path (nested objects/arrays are dropped, not errors). A decision
+//!   is resolved by the evaluator from the typed request field), and context
+//!   values are passed through as typed JSON exactly like the agent. A decision
 //!   produced here must match the decision the agent would have produced for
 //!   the same inputs — enforced by the parity suite: `tests/parity.rs` runs
 //!   the policy-library manifest cases natively through this very wrapper,
@@ -248,8 +247,8 @@ impl ReaperEngine {
     /// Evaluate one request against one policy. Returns the engine's
     /// `PolicyDecision` as a JSON string.
     ///
-    /// `context_json` is an optional JSON object; scalar values are coerced
-    /// to strings (agent fast-path semantics), nested values are dropped.
+    /// `context_json` is an optional JSON object; its values keep their JSON
+    /// types (agent semantics).
     pub fn evaluate(
         &self,
         policy_id: &str,
@@ -335,47 +334,31 @@ impl ReaperEngine {
 }
 
 /// Build a `PolicyRequest` the way the agent does: principal injected as
-/// `context["principal"]`, scalar context values coerced to strings, nested
-/// values dropped (mirrors `services/reaper-agent` fast-path coercion).
+/// `context["principal"]`, context values kept as typed JSON (mirrors
+/// `services/reaper-agent`).
 fn build_request(
     principal: &str,
     action: &str,
     resource: &str,
     context_json: Option<&str>,
 ) -> Result<PolicyRequest, String> {
-    let mut context: HashMap<String, String> = HashMap::new();
+    let mut context: HashMap<String, serde_json::Value> = HashMap::new();
 
     if let Some(raw) = context_json {
         if !raw.trim().is_empty() {
             let value: serde_json::Value =
                 serde_json::from_str(raw).map_err(|e| format!("context is not valid JSON: {e}"))?;
-            let obj = value
-                .as_object()
-                .ok_or_else(|| "context must be a JSON object".to_string())?;
-            for (k, v) in obj {
-                match v {
-                    serde_json::Value::String(s) => {
-                        context.insert(k.clone(), s.clone());
-                    }
-                    serde_json::Value::Number(n) => {
-                        context.insert(k.clone(), n.to_string());
-                    }
-                    serde_json::Value::Bool(b) => {
-                        context.insert(k.clone(), b.to_string());
-                    }
-                    // Agent fast-path semantics: nested objects/arrays and
-                    // nulls are not representable in the flat context and are
-                    // dropped, not errors.
-                    _ => {}
-                }
-            }
+            let serde_json::Value::Object(obj) = value else {
+                return Err("context must be a JSON object".to_string());
+            };
+            context.extend(obj);
         }
     }
 
     // Exactly what the agent injects — principal only. `context.action` is
     // resolved by the evaluator from the typed request field; inserting it
     // here would shadow a caller-supplied value and diverge from the agent.
-    context.insert("principal".to_string(), principal.to_string());
+    context.insert("principal".to_string(), principal.into());
 
     Ok(PolicyRequest {
         resource: resource.to_string(),
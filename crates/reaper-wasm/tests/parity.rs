@@ -46,7 +46,7 @@ struct Case {
     #[serde(default)]
     violations: Option<Vec<String>>,
     #[serde(default)]
-    context: Option<HashMap<String, String>>,
+    context: Option<HashMap<String, serde_json::Value>>,
 }
 
 /// Run one document-mode case through the wrapper's check surface and assert
@@ -349,22 +349,25 @@ fn redeploy_is_atomic_hot_swap_not_static() {
 }
 
 #[test]
-fn context_coercion_matches_agent_fast_path() {
-    // Scalars coerce to strings; nested values drop; caller-supplied
-    // context.principal is overridden by the typed principal — the same
-    // rules the agent applies before the engine sees the request.
+fn context_is_typed_like_the_agent() {
+    // Values keep their JSON types (numbers never equal their string form);
+    // nested objects navigate; caller-supplied context.principal is
+    // overridden by the typed principal — the same rules the agent applies
+    // before the engine sees the request.
     let engine = ReaperEngine::new();
     let policy_id = engine
         .deploy_policy_impl(
-            "ctx-coercion",
+            "ctx-typed",
             r#"
-policy ctx_coercion {
+policy ctx_typed {
     default: deny,
 
     rule tier_gate {
         allow if {
-            context.tier == "3" &&
-            context.beta == "true"
+            context.tier >= 3 &&
+            context.beta == true &&
+            context.org.plan == "pro" &&
+            "eu" in context.regions
         }
     }
 }
@@ -382,15 +385,25 @@ policy ctx_coercion {
             "svc-1",
             "read",
             "thing",
-            Some(r#"{"tier": 3, "beta": true, "nested": {"dropped": 1}}"#),
+            Some(r#"{"tier": 3, "beta": true, "org": {"plan": "pro"}, "regions": ["us", "eu"]}"#),
         )
         .expect("evaluate");
-    assert_eq!(decision_of(&decision), "allow", "number/bool must coerce");
+    assert_eq!(
+        decision_of(&decision),
+        "allow",
+        "typed values compare natively"
+    );
 
-    let denied = engine
-        .evaluate_impl(&policy_id, "svc-1", "read", "thing", Some(r#"{"tier": 2}"#))
+    let stringly = engine
+        .evaluate_impl(
+            &policy_id,
+            "svc-1",
+            "read",
+            "thing",
+            Some(r#"{"tier": "3", "beta": "true", "org": {"plan": "pro"}, "regions": ["eu"]}"#),
+        )
         .expect("evaluate");
-    assert_eq!(decision_of(&denied), "deny");
+    assert_eq!(decision_of(&stringly), "deny", "strings are not coerced");
 
     let err = engine
         .evaluate_impl(&policy_id, "svc-1", "read", "thing", Some("[1,2]"))
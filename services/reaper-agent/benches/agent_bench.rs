@@ -79,6 +79,7 @@ fn build_state_with_buffer(decision_buffer: Option<SharedDecisionBuffer>) -> Arc
                 &reaper_core::config::ManagementSettings::default(),
             ),
         ),
+        shadow: Default::default(),
         capability_gate: std::sync::Arc::new(
             reaper_agent::capability_cache::CapabilityGateRuntime::from_auth(
                 &reaper_core::config::AgentAuthSettings::default(),
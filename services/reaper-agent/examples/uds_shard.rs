@@ -159,6 +159,7 @@ fn build_state() -> anyhow::Result<Arc<AgentState>> {
                 &reaper_core::config::ManagementSettings::default(),
             ),
         ),
+        shadow: Default::default(),
         capability_gate: std::sync::Arc::new(
             reaper_agent::capability_cache::CapabilityGateRuntime::from_auth(
                 &reaper_core::config::AgentAuthSettings::default(),
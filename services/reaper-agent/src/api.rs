@@ -41,6 +41,7 @@ use crate::state::AgentState;
         (name = "policies", description = "Policy and bundle deployment (from the platform)"),
         (name = "data", description = "Managed entity data load and synchronization"),
         (name = "entities", description = "Entity CRUD"),
+        (name = "relations", description = "ReBAC lookups and expansion over the relationship graph"),
         (name = "decisions", description = "OPA-style decision audit log")
     )
 )]
@@ -79,11 +80,16 @@ fn openapi_router() -> OpenApiRouter<Arc<AgentState>> {
         .routes(routes!(handlers::health::liveness_check))
         .routes(routes!(handlers::health::metrics))
         // Evaluation (hot path)
-        .routes(routes!(handlers::evaluate::evaluate_policy))
+        .routes(routes!(handlers::evaluate::evaluate_messages))
         .routes(routes!(handlers::evaluate::fast_evaluate_policy))
         .routes(routes!(handlers::evaluate::batch_evaluate_policy))
         .routes(routes!(handlers::check::check_document))
+        .routes(routes!(handlers::filter::filter_resources))
         .routes(routes!(handlers::admission::admission_review))
+        // Relationship queries
+        .routes(routes!(handlers::relations::lookup_relation_resources))
+        .routes(routes!(handlers::relations::lookup_relation_subjects))
+        .routes(routes!(handlers::relations::expand_relation))
         // Managed data
         .routes(routes!(handlers::data::load_data_handler))
         .routes(routes!(handlers::data::load_data_stream_handler))
@@ -99,6 +105,8 @@ fn openapi_router() -> OpenApiRouter<Arc<AgentState>> {
         .routes(routes!(handlers::policies::get_policy_current_version))
         .routes(routes!(handlers::policies::deploy_bundle))
         .routes(routes!(handlers::policies::load_bundles_atomic))
+        .routes(routes!(handlers::shadow::list_shadows))
+        .routes(routes!(handlers::shadow::remove_shadow))
         // Entity CRUD (GET + DELETE share the {type}/{id} path)
         .routes(routes!(handlers::entities::upsert_entity_handler))
         .routes(routes!(
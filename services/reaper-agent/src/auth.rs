@@ -51,7 +51,10 @@ fn is_exempt(path: &str) -> bool {
 /// The read-only data-plane hot path: policy evaluation. Optionally left open
 /// (sidecar posture) so a co-located caller pays no auth on the hot path while
 /// the management plane stays gated. These endpoints do not mutate policy or
-/// data — they only decide against the already-loaded set.
+/// data — they only decide against the already-loaded set. List filtering
+/// (`/api/v1/filter`) and relationship queries (`/api/v1/relations/*`) are
+/// not here: they enumerate who can reach what, so they stay behind normal
+/// auth even in sidecar posture.
 fn is_data_plane(path: &str) -> bool {
     matches!(
         path,
@@ -322,6 +325,10 @@ mod tests {
             "/api/v1/policies/deploy",
             "/api/v1/data",
             "/api/v1/entities",
+            "/api/v1/filter",
+            "/api/v1/relations/lookup-resources",
+            "/api/v1/relations/lookup-subjects",
+            "/api/v1/relations/expand",
             "/debug/datastore",
         ] {
             assert!(!is_data_plane(p), "{p} is management, not the hot path");
@@ -349,6 +356,7 @@ mod tests {
         }
         let app = axum::Router::new()
             .route("/api/v1/messages", post(ok))
+            .route("/api/v1/filter", post(ok))
             .route("/api/v1/bundles/deploy", post(ok))
             .route("/health", get(ok))
             .layer(axum::middleware::from_fn_with_state(
@@ -383,6 +391,18 @@ mod tests {
                 .status(),
             StatusCode::OK
         );
+        // Filtering lists what a principal can reach: gated like management.
+        assert_eq!(
+            call("/api/v1/filter", None).await.unwrap().status(),
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            call("/api/v1/filter", Some("Bearer tok-secret"))
+                .await
+                .unwrap()
+                .status(),
+            StatusCode::OK
+        );
     }
 
     #[tokio::test]
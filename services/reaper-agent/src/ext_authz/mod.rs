@@ -0,0 +1,590 @@
+//! Envoy external authorization (`envoy.service.auth.v3.Authorization/Check`).
+//!
+//! Envoy's `ext_authz` HTTP filter — and Istio's `CUSTOM` authorization
+//! action — call this gRPC endpoint directly, with no HTTP shim in front of
+//! `/api/v1/messages`. Each `CheckRequest` becomes a [`PolicyRequest`]:
+//!
+//! - **principal**: the `sub` claim of the forwarded JWT payload
+//!   (`jwt_payload_header`), else the mTLS peer principal Envoy validated
+//!   (`source.principal`), else the `principal_header` value, else
+//!   `anonymous_principal`. No identity and no anonymous principal denies
+//!   without evaluating (`unauthenticated`).
+//! - **action**: the lower-cased HTTP method.
+//! - **resource**: the request path without its query string.
+//! - **context**: `http` (method, path, query, host, scheme, protocol, id,
+//!   headers), `source` / `destination` (address, port, principal),
+//!   `context_extensions`, and `jwt` (the forwarded claims) — typed JSON, so
+//!   policies read `context.http.headers["x-tenant"]`.
+//!
+//! The request is evaluated against the configured policy or package (a
+//! route may override either via the `reaper_policy` / `reaper_package`
+//! context extensions) through the same fail-closed `evaluate_set` core as
+//! the HTTP endpoints, and logged to the decision log like them.
+//!
+//! The deciding rule's obligations drive the proxy: on allow, `headers` are
+//! set on the upstream request, `remove_headers` are stripped from it and
+//! `response_headers` are added to the client response; on deny, `status`,
+//! `headers` and `body` shape the denial sent to the client. Every decision
+//! also returns `dynamic_metadata` (decision id, rule, all obligations and
+//! advice) for downstream filters and access logs.
+
+pub mod proto;
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use base64::Engine as _;
+use policy_engine::{DecisionLogEntry, DecisionObligations, PolicyAction, PolicyRequest};
+use reaper_core::config::ExtAuthzSettings;
+use serde_json::{json, Map, Value};
+use tonic::codegen::BoxFuture;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::handlers::evaluate::{
+    audit_gate, caller_context, capture_input_data, observe_early_return, observe_served_deny,
+};
+use crate::observability::ERRORS_TOTAL;
+use crate::state::AgentState;
+use proto::{
+    AttributeContext, AuthorizationServer, CheckHandler, CheckRequest, CheckResponse,
+    DeniedHttpResponse, HeaderValueOption, HttpResponse, HttpStatus, OkHttpResponse, Peer,
+    RpcStatus,
+};
+
+/// Context extension naming the policy for one route.
+pub const POLICY_EXTENSION: &str = "reaper_policy";
+/// Context extension naming the package for one route.
+pub const PACKAGE_EXTENSION: &str = "reaper_package";
+
+/// Header carrying the decision id on both allowed and denied requests.
+const DECISION_ID_HEADER: &str = "x-reaper-decision-id";
+
+/// Request headers never copied into the decision log: credentials the
+/// policy may read but an audit record must not retain.
+const UNLOGGED_HEADERS: [&str; 3] = ["authorization", "proxy-authorization", "cookie"];
+
+// google.rpc.Code values
+const GRPC_OK: i32 = 0;
+const GRPC_PERMISSION_DENIED: i32 = 7;
+const GRPC_UNAVAILABLE: i32 = 14;
+
+/// The ext_authz `Check` handler over the agent's shared state.
+#[derive(Clone)]
+pub struct ExtAuthzService {
+    state: Arc<AgentState>,
+}
+
+impl ExtAuthzService {
+    pub fn new(state: Arc<AgentState>) -> Self {
+        Self { state }
+    }
+}
+
+impl CheckHandler for ExtAuthzService {
+    fn check(
+        &self,
+        request: tonic::Request<CheckRequest>,
+    ) -> BoxFuture<tonic::Response<CheckResponse>, tonic::Status> {
+        let state = Arc::clone(&self.state);
+        Box::pin(async move {
+            Ok(tonic::Response::new(
+                check(&state, request.into_inner()).await,
+            ))
+        })
+    }
+}
+
+/// Spawn the ext_authz gRPC listener on the current runtime. Returns
+/// immediately; a bind or serve failure is logged, not fatal to the agent.
+pub fn spawn_ext_authz_server(
+    settings: &ExtAuthzSettings,
+    state: Arc<AgentState>,
+) -> anyhow::Result<()> {
+    let ip = IpAddr::from_str(&settings.bind_address).map_err(|e| {
+        anyhow::anyhow!(
+            "invalid ext_authz bind_address '{}': {e}",
+            settings.bind_address
+        )
+    })?;
+    let addr = SocketAddr::new(ip, settings.port);
+    if settings.policy.is_none() && settings.package.is_none() {
+        warn!(
+            "ext_authz has no default policy or package; checks from routes without a \
+             reaper_policy/reaper_package context extension will be denied"
+        );
+    }
+    info!(%addr, "Starting Envoy ext_authz gRPC listener");
+    let service = AuthorizationServer::new(ExtAuthzService::new(state));
+    tokio::spawn(async move {
+        if let Err(e) = tonic::transport::Server::builder()
+            .add_service(service)
+            .serve(addr)
+            .await
+        {
+            error!("ext_authz server error: {}", e);
+        }
+    });
+    Ok(())
+}
+
+/// Evaluate one `CheckRequest`. Never errors: every failure is a denial
+/// Envoy can serve.
+pub async fn check(state: &AgentState, request: CheckRequest) -> CheckResponse {
+    let start_time = std::time::Instant::now();
+    let decision_id = Uuid::new_v4().to_string();
+    let settings = &state.agent_config.ext_authz;
+
+    if audit_gate(state).is_err() {
+        observe_served_deny(state, start_time);
+        return unavailable(&decision_id, "audit_unavailable");
+    }
+    if let Some(reason) = state.data_sync.deny_reason() {
+        ERRORS_TOTAL.with_label_values(&["data_stale"]).inc();
+        observe_served_deny(state, start_time);
+        return denied(settings, &decision_id, reason, None);
+    }
+
+    let attributes = request.attributes.unwrap_or_default();
+
+    let policy_ids = match resolve_policies(state, settings, &attributes) {
+        Ok(ids) => ids,
+        Err(reason) => {
+            ERRORS_TOTAL.with_label_values(&["policy_not_found"]).inc();
+            observe_early_return(state, start_time);
+            return denied(settings, &decision_id, reason, None);
+        }
+    };
+
+    let (request, logged_context) = match policy_request(settings, &attributes) {
+        Some(mapped) => mapped,
+        None => {
+            ERRORS_TOTAL
+                .with_label_values(&["ext_authz_unauthenticated"])
+                .inc();
+            observe_served_deny(state, start_time);
+            return denied(settings, &decision_id, "unauthenticated", None);
+        }
+    };
+
+    let outcome = state.policy_engine.evaluate_set(&policy_ids, &request);
+    let matched_rule = if let Some(ref e) = outcome.error {
+        error!("Policy evaluation error: {}", e);
+        ERRORS_TOTAL.with_label_values(&["evaluation_error"]).inc();
+        format!("evaluation_error: {}", e)
+    } else {
+        outcome
+            .matched_rule_name
+            .clone()
+            .or_else(|| outcome.matched_rule.map(|idx| format!("rule_{}", idx)))
+            .unwrap_or_else(|| "default_deny".to_string())
+    };
+
+    state.stats.record_evaluation(outcome.total_eval_time_ns);
+    let allowed = outcome.decision == PolicyAction::Allow;
+    if allowed {
+        state.stats.record_allow();
+    } else {
+        state.stats.record_deny();
+    }
+    let metrics = state.decision_metrics.for_policy(&outcome.policy_name);
+    metrics.counter(&outcome.decision).inc();
+    metrics
+        .engine_duration
+        .observe(outcome.total_eval_time_ns as f64 / 1_000_000_000.0);
+
+    if let Some(ref buffer) = state.decision_buffer {
+        if buffer.should_log(allowed) {
+            let principal = request
+                .context_str("principal")
+                .unwrap_or_default()
+                .to_string();
+            let mut entry = DecisionLogEntry::new(
+                principal.clone(),
+                request.action.clone(),
+                request.resource.clone(),
+                if allowed { "allow" } else { "deny" }.to_string(),
+                outcome.policy_id.to_string(),
+                outcome.policy_name.clone(),
+            )
+            .with_context(logged_context.clone())
+            .with_evaluation_time_ns(outcome.total_eval_time_ns)
+            .with_cache_hit(false)
+            .with_agent_id(state.agent_id.clone())
+            .with_policy_version(outcome.policy_version.to_string())
+            .with_matched_rule(matched_rule.clone());
+            if let Some(ref obligations) = outcome.obligations {
+                entry = entry.with_obligations(obligations.clone());
+            }
+            let (data_version, data_checksum) = state.data_sync.provenance();
+            entry = entry
+                .with_data_sync(data_version, data_checksum, state.data_sync.flag_stale())
+                .with_model_version(state.data_sync.model_provenance());
+            if buffer.should_capture_input(allowed, false) {
+                entry.input_data =
+                    capture_input_data(&state.data_store, &principal, &request.resource, None);
+            }
+            if buffer.should_capture_replay(allowed) {
+                entry.replay_input = Some(json!({
+                    "principal": principal,
+                    "action": request.action,
+                    "resource": request.resource,
+                    "context": logged_context,
+                }));
+            }
+            entry.decision_id = decision_id.clone();
+
+            // Durable-before-serve in mandatory-audit mode, as on the HTTP
+            // endpoints: an un-persisted decision is answered UNAVAILABLE.
+            if buffer.mandatory_durable() {
+                if !buffer.log_durable(entry).await {
+                    ERRORS_TOTAL
+                        .with_label_values(&["audit_persist_unavailable"])
+                        .inc();
+                    metrics.duration.observe(start_time.elapsed().as_secs_f64());
+                    return unavailable(&decision_id, "audit_persist_unavailable");
+                }
+            } else {
+                buffer.log(entry);
+            }
+        }
+    }
+
+    let response = if allowed {
+        allowed_response(&decision_id, &matched_rule, outcome.obligations.as_ref())
+    } else {
+        denied(
+            settings,
+            &decision_id,
+            &matched_rule,
+            outcome.obligations.as_ref(),
+        )
+    };
+    metrics.duration.observe(start_time.elapsed().as_secs_f64());
+    response
+}
+
+/// The policy ids a check evaluates: a route's context extension wins over
+/// the configured default; a policy wins over a package.
+fn resolve_policies(
+    state: &AgentState,
+    settings: &ExtAuthzSettings,
+    attributes: &AttributeContext,
+) -> Result<Vec<Uuid>, &'static str> {
+    let extensions = &attributes.context_extensions;
+    let (policy, package) = match (
+        extensions.get(POLICY_EXTENSION),
+        extensions.get(PACKAGE_EXTENSION),
+    ) {
+        (None, None) => (settings.policy.as_ref(), settings.package.as_ref()),
+        route => route,
+    };
+
+    if let Some(key) = policy {
+        let id = match Uuid::from_str(key) {
+            Ok(id) => state.policy_engine.get_policy(&id).map(|p| p.id),
+            Err(_) => state.policy_engine.get_policy_by_name(key).map(|p| p.id),
+        };
+        return id.map(|id| vec![id]).ok_or("policy_not_found");
+    }
+    if let Some(package) = package {
+        let ids: Vec<Uuid> = state
+            .policy_engine
+            .get_policies_by_package(package)
+            .iter()
+            .map(|p| p.id)
+            .collect();
+        return if ids.is_empty() {
+            Err("package_not_found")
+        } else {
+            Ok(ids)
+        };
+    }
+    Err("ext_authz_no_policy")
+}
+
+/// Map the check attributes onto a policy request, plus the context as it
+/// should be logged. `None` when no principal can be established.
+fn policy_request(
+    settings: &ExtAuthzSettings,
+    attributes: &AttributeContext,
+) -> Option<(PolicyRequest, HashMap<String, Value>)> {
+    let http = attributes
+        .request
+        .as_ref()
+        .and_then(|r| r.http.clone())
+        .unwrap_or_default();
+
+    let claims = settings
+        .jwt_payload_header
+        .as_ref()
+        .and_then(|name| http.headers.get(&name.to_ascii_lowercase()))
+        .and_then(|payload| decode_jwt_payload(payload));
+
+    // Identities Envoy verified win over the configured header, which a
+    // client can set itself unless Envoy strips or overwrites it.
+    let principal = claims
+        .as_ref()
+        .and_then(|c| c.get("sub"))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .or_else(|| {
+            attributes
+                .source
+                .as_ref()
+                .map(|s| s.principal.clone())
+                .filter(|p| !p.is_empty())
+        })
+        .or_else(|| {
+            settings
+                .principal_header
+                .as_ref()
+                .and_then(|name| http.headers.get(&name.to_ascii_lowercase()))
+                .filter(|p| !p.is_empty())
+                .cloned()
+        })
+        .or_else(|| settings.anonymous_principal.clone())?;
+
+    let (path, query) = match http.path.split_once('?') {
+        Some((path, query)) => (path.to_string(), query.to_string()),
+        None => (http.path.clone(), String::new()),
+    };
+
+    let mut context = HashMap::new();
+    context.insert("principal".to_string(), Value::from(principal));
+    context.insert(
+        "http".to_string(),
+        json!({
+            "id": http.id,
+            "method": http.method,
+            "path": path,
+            "query": query,
+            "host": http.host,
+            "scheme": http.scheme,
+            "protocol": http.protocol,
+            "headers": http.headers,
+        }),
+    );
+    if let Some(source) = &attributes.source {
+        context.insert("source".to_string(), peer_json(source));
+    }
+    if let Some(destination) = &attributes.destination {
+        context.insert("destination".to_string(), peer_json(destination));
+    }
+    if !attributes.context_extensions.is_empty() {
+        context.insert(
+            "context_extensions".to_string(),
+            json!(attributes.context_extensions),
+        );
+    }
+    if let Some(claims) = claims {
+        context.insert("jwt".to_string(), Value::Object(claims));
+    }
+
+    let request = PolicyRequest {
+        resource: path,
+        action: http.method.to_ascii_lowercase(),
+        context,
+        ..Default::default()
+    };
+
+    let mut logged = caller_context(&request);
+    if let Some(Value::Object(headers)) = logged.get_mut("http").and_then(|h| h.get_mut("headers"))
+    {
+        headers.retain(|name, _| !UNLOGGED_HEADERS.contains(&name.as_str()));
+    }
+    Some((request, logged))
+}
+
+fn peer_json(peer: &Peer) -> Value {
+    let socket = peer
+        .address
+        .as_ref()
+        .and_then(|a| a.socket_address.as_ref());
+    json!({
+        "address": socket.map(|s| s.address.as_str()).unwrap_or_default(),
+        "port": socket.map(|s| s.port_value).unwrap_or_default(),
+        "principal": peer.principal,
+    })
+}
+
+/// Claims from a JWT payload Envoy already verified and forwarded as
+/// base64url JSON. Padding is tolerated; anything else yields `None`.
+fn decode_jwt_payload(payload: &str) -> Option<Map<String, Value>> {
+    let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(payload.trim_end_matches('='))
+        .ok()?;
+    match serde_json::from_slice(&bytes).ok()? {
+        Value::Object(claims) => Some(claims),
+        _ => None,
+    }
+}
+
+fn allowed_response(
+    decision_id: &str,
+    matched_rule: &str,
+    obligations: Option<&DecisionObligations>,
+) -> CheckResponse {
+    let duties = obligations.map(|o| &o.obligations);
+    let mut headers = vec![HeaderValueOption::overwrite(
+        DECISION_ID_HEADER,
+        decision_id,
+    )];
+    headers.extend(header_options(duties.and_then(|o| o.get("headers"))));
+    let headers_to_remove = duties
+        .and_then(|o| o.get("remove_headers"))
+        .and_then(Value::as_array)
+        .map(|names| {
+            names
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    CheckResponse {
+        status: Some(RpcStatus {
+            code: GRPC_OK,
+            message: String::new(),
+        }),
+        http_response: Some(HttpResponse::OkResponse(OkHttpResponse {
+            headers,
+            headers_to_remove,
+            response_headers_to_add: header_options(duties.and_then(|o| o.get("response_headers"))),
+        })),
+        dynamic_metadata: Some(metadata(decision_id, "allow", matched_rule, obligations)),
+    }
+}
+
+/// A denial, shaped by the deciding rule's `status` / `headers` / `body`
+/// obligations when it has them.
+fn denied(
+    settings: &ExtAuthzSettings,
+    decision_id: &str,
+    reason: &str,
+    obligations: Option<&DecisionObligations>,
+) -> CheckResponse {
+    let duties = obligations.map(|o| &o.obligations);
+    let status = duties
+        .and_then(|o| o.get("status"))
+        .and_then(Value::as_u64)
+        .filter(|code| (100..=599).contains(code))
+        .map_or(i32::from(settings.deny_status), |code| code as i32);
+    let (body, content_type) = match duties.and_then(|o| o.get("body")) {
+        Some(Value::String(text)) => (text.clone(), "text/plain"),
+        Some(other) => (other.to_string(), "application/json"),
+        None => (
+            json!({"decision": "deny", "decision_id": decision_id, "reason": reason}).to_string(),
+            "application/json",
+        ),
+    };
+    let policy_headers = header_options(duties.and_then(|o| o.get("headers")));
+    let mut headers = vec![HeaderValueOption::overwrite(
+        DECISION_ID_HEADER,
+        decision_id,
+    )];
+    if !policy_headers.iter().any(|h| {
+        h.header
+            .as_ref()
+            .is_some_and(|h| h.key.eq_ignore_ascii_case("content-type"))
+    }) {
+        headers.push(HeaderValueOption::overwrite("content-type", content_type));
+    }
+    headers.extend(policy_headers);
+    CheckResponse {
+        status: Some(RpcStatus {
+            code: GRPC_PERMISSION_DENIED,
+            message: reason.to_string(),
+        }),
+        http_response: Some(HttpResponse::DeniedResponse(DeniedHttpResponse {
+            status: Some(HttpStatus { code: status }),
+            headers,
+            body,
+        })),
+        dynamic_metadata: Some(metadata(decision_id, "deny", reason, obligations)),
+    }
+}
+
+/// The agent cannot serve an audited decision right now: Envoy denies with
+/// 503 (never `failure_mode_allow`, since a response was received).
+fn unavailable(decision_id: &str, reason: &str) -> CheckResponse {
+    CheckResponse {
+        status: Some(RpcStatus {
+            code: GRPC_UNAVAILABLE,
+            message: reason.to_string(),
+        }),
+        http_response: Some(HttpResponse::DeniedResponse(DeniedHttpResponse {
+            status: Some(HttpStatus { code: 503 }),
+            headers: vec![HeaderValueOption::overwrite(
+                DECISION_ID_HEADER,
+                decision_id,
+            )],
+            body: String::new(),
+        })),
+        dynamic_metadata: None,
+    }
+}
+
+/// `{"name": "value", ...}` obligations as overwriting header options.
+/// Non-string scalars are rendered; nested values are skipped.
+fn header_options(value: Option<&Value>) -> Vec<HeaderValueOption> {
+    let Some(Value::Object(headers)) = value else {
+        return Vec::new();
+    };
+    headers
+        .iter()
+        .filter_map(|(name, value)| {
+            let value = match value {
+                Value::String(s) => s.clone(),
+                Value::Number(n) => n.to_string(),
+                Value::Bool(b) => b.to_string(),
+                _ => return None,
+            };
+            Some(HeaderValueOption::overwrite(name.clone(), value))
+        })
+        .collect()
+}
+
+fn metadata(
+    decision_id: &str,
+    decision: &str,
+    rule: &str,
+    obligations: Option<&DecisionObligations>,
+) -> prost_types::Struct {
+    let mut fields = json!({
+        "decision_id": decision_id,
+        "decision": decision,
+        "matched_rule": rule,
+    });
+    if let Some(o) = obligations {
+        if !o.obligations.is_empty() {
+            fields["obligations"] = Value::Object(o.obligations.clone());
+        }
+        if !o.advice.is_empty() {
+            fields["advice"] = Value::Object(o.advice.clone());
+        }
+    }
+    match to_prost(fields).kind {
+        Some(prost_types::value::Kind::StructValue(s)) => s,
+        _ => prost_types::Struct::default(),
+    }
+}
+
+fn to_prost(value: Value) -> prost_types::Value {
+    use prost_types::value::Kind;
+    let kind = match value {
+        Value::Null => Kind::NullValue(0),
+        Value::Bool(b) => Kind::BoolValue(b),
+        Value::Number(n) => Kind::NumberValue(n.as_f64().unwrap_or_default()),
+        Value::String(s) => Kind::StringValue(s),
+        Value::Array(items) => Kind::ListValue(prost_types::ListValue {
+            values: items.into_iter().map(to_prost).collect(),
+        }),
+        Value::Object(fields) => Kind::StructValue(prost_types::Struct {
+            fields: fields.into_iter().map(|(k, v)| (k, to_prost(v))).collect(),
+        }),
+    };
+    prost_types::Value { kind: Some(kind) }
+}
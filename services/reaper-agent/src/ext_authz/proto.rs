@@ -0,0 +1,272 @@
+//! Wire types and gRPC plumbing for `envoy.service.auth.v3.Authorization`.
+//!
+//! Hand-declared prost messages mirroring the subset of
+//! `envoy/service/auth/v3/external_auth.proto` (and the messages it pulls in
+//! from `attribute_context.proto`, `config/core/v3`, `type/v3` and
+//! `google/rpc`) the agent reads or writes. Tags match the upstream protos;
+//! fields left out here are skipped as unknown on decode, so a newer Envoy
+//! stays compatible. Declaring them by hand keeps protoc and the Envoy proto
+//! tree out of the build.
+//!
+//! [`AuthorizationServer`] is the service shim `tonic-build` would otherwise
+//! generate: it routes `/envoy.service.auth.v3.Authorization/Check` to a
+//! [`CheckHandler`] and answers every other method `UNIMPLEMENTED`.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use tonic::body::Body;
+use tonic::codegen::{http, BoxFuture, Service, StdError};
+
+/// `envoy.service.auth.v3.CheckRequest`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CheckRequest {
+    #[prost(message, optional, tag = "1")]
+    pub attributes: Option<AttributeContext>,
+}
+
+/// `envoy.service.auth.v3.AttributeContext`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct AttributeContext {
+    #[prost(message, optional, tag = "1")]
+    pub source: Option<Peer>,
+    #[prost(message, optional, tag = "2")]
+    pub destination: Option<Peer>,
+    #[prost(message, optional, tag = "4")]
+    pub request: Option<Request>,
+    #[prost(map = "string, string", tag = "10")]
+    pub context_extensions: HashMap<String, String>,
+}
+
+/// `envoy.service.auth.v3.AttributeContext.Peer`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Peer {
+    #[prost(message, optional, tag = "1")]
+    pub address: Option<Address>,
+    #[prost(string, tag = "2")]
+    pub service: String,
+    #[prost(map = "string, string", tag = "3")]
+    pub labels: HashMap<String, String>,
+    /// mTLS peer identity (URI SAN, else subject) as validated by Envoy.
+    #[prost(string, tag = "4")]
+    pub principal: String,
+}
+
+/// `envoy.config.core.v3.Address` (socket addresses only).
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Address {
+    #[prost(message, optional, tag = "1")]
+    pub socket_address: Option<SocketAddress>,
+}
+
+/// `envoy.config.core.v3.SocketAddress`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SocketAddress {
+    #[prost(string, tag = "2")]
+    pub address: String,
+    #[prost(uint32, tag = "3")]
+    pub port_value: u32,
+}
+
+/// `envoy.service.auth.v3.AttributeContext.Request`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Request {
+    #[prost(message, optional, tag = "2")]
+    pub http: Option<HttpRequest>,
+}
+
+/// `envoy.service.auth.v3.AttributeContext.HttpRequest`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct HttpRequest {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(string, tag = "2")]
+    pub method: String,
+    /// Lower-cased header names; repeated headers are comma-joined by Envoy.
+    #[prost(map = "string, string", tag = "3")]
+    pub headers: HashMap<String, String>,
+    /// Request target including the query string.
+    #[prost(string, tag = "4")]
+    pub path: String,
+    #[prost(string, tag = "5")]
+    pub host: String,
+    #[prost(string, tag = "6")]
+    pub scheme: String,
+    #[prost(string, tag = "10")]
+    pub protocol: String,
+}
+
+/// `envoy.service.auth.v3.CheckResponse`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CheckResponse {
+    #[prost(message, optional, tag = "1")]
+    pub status: Option<RpcStatus>,
+    #[prost(oneof = "HttpResponse", tags = "2, 3")]
+    pub http_response: Option<HttpResponse>,
+    /// Emitted under the filter's namespace for downstream filters and
+    /// access logs.
+    #[prost(message, optional, tag = "4")]
+    pub dynamic_metadata: Option<prost_types::Struct>,
+}
+
+/// `CheckResponse.http_response`
+#[derive(Clone, PartialEq, prost::Oneof)]
+pub enum HttpResponse {
+    #[prost(message, tag = "2")]
+    DeniedResponse(DeniedHttpResponse),
+    #[prost(message, tag = "3")]
+    OkResponse(OkHttpResponse),
+}
+
+/// `envoy.service.auth.v3.DeniedHttpResponse`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct DeniedHttpResponse {
+    #[prost(message, optional, tag = "1")]
+    pub status: Option<HttpStatus>,
+    #[prost(message, repeated, tag = "2")]
+    pub headers: Vec<HeaderValueOption>,
+    #[prost(string, tag = "3")]
+    pub body: String,
+}
+
+/// `envoy.service.auth.v3.OkHttpResponse`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct OkHttpResponse {
+    /// Set on the request forwarded upstream.
+    #[prost(message, repeated, tag = "2")]
+    pub headers: Vec<HeaderValueOption>,
+    /// Removed from the request forwarded upstream.
+    #[prost(string, repeated, tag = "5")]
+    pub headers_to_remove: Vec<String>,
+    /// Added to the response sent back to the client.
+    #[prost(message, repeated, tag = "6")]
+    pub response_headers_to_add: Vec<HeaderValueOption>,
+}
+
+/// `envoy.type.v3.HttpStatus` (the `StatusCode` enum is the HTTP code).
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct HttpStatus {
+    #[prost(int32, tag = "1")]
+    pub code: i32,
+}
+
+/// `envoy.config.core.v3.HeaderValueOption`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct HeaderValueOption {
+    #[prost(message, optional, tag = "1")]
+    pub header: Option<HeaderValue>,
+    /// `HeaderAppendAction`; see [`OVERWRITE_IF_EXISTS_OR_ADD`].
+    #[prost(int32, tag = "3")]
+    pub append_action: i32,
+}
+
+/// `HeaderAppendAction.OVERWRITE_IF_EXISTS_OR_ADD`: a policy-set header
+/// replaces any client-supplied value instead of appending to it.
+pub const OVERWRITE_IF_EXISTS_OR_ADD: i32 = 2;
+
+impl HeaderValueOption {
+    /// A header that overwrites any existing value of the same name.
+    pub fn overwrite(key: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            header: Some(HeaderValue {
+                key: key.into(),
+                value: value.into(),
+            }),
+            append_action: OVERWRITE_IF_EXISTS_OR_ADD,
+        }
+    }
+}
+
+/// `envoy.config.core.v3.HeaderValue`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct HeaderValue {
+    #[prost(string, tag = "1")]
+    pub key: String,
+    #[prost(string, tag = "2")]
+    pub value: String,
+}
+
+/// `google.rpc.Status` (without `details`).
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct RpcStatus {
+    #[prost(int32, tag = "1")]
+    pub code: i32,
+    #[prost(string, tag = "2")]
+    pub message: String,
+}
+
+/// The one RPC the service exposes.
+pub trait CheckHandler: Send + Sync + 'static {
+    fn check(
+        &self,
+        request: tonic::Request<CheckRequest>,
+    ) -> BoxFuture<tonic::Response<CheckResponse>, tonic::Status>;
+}
+
+/// `envoy.service.auth.v3.Authorization` server over a [`CheckHandler`].
+pub struct AuthorizationServer<T> {
+    inner: Arc<T>,
+}
+
+impl<T> AuthorizationServer<T> {
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner: Arc::new(inner),
+        }
+    }
+}
+
+impl<T> Clone for AuthorizationServer<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T> tonic::server::NamedService for AuthorizationServer<T> {
+    const NAME: &'static str = "envoy.service.auth.v3.Authorization";
+}
+
+/// Full gRPC path of the `Check` method.
+pub const CHECK_PATH: &str = "/envoy.service.auth.v3.Authorization/Check";
+
+struct CheckSvc<T>(Arc<T>);
+
+impl<T: CheckHandler> tonic::server::UnaryService<CheckRequest> for CheckSvc<T> {
+    type Response = CheckResponse;
+    type Future = BoxFuture<tonic::Response<CheckResponse>, tonic::Status>;
+
+    fn call(&mut self, request: tonic::Request<CheckRequest>) -> Self::Future {
+        self.0.check(request)
+    }
+}
+
+impl<T, B> Service<http::Request<B>> for AuthorizationServer<T>
+where
+    T: CheckHandler,
+    B: tonic::codegen::Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+{
+    type Response = http::Response<Body>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        if req.uri().path() == CHECK_PATH {
+            let svc = CheckSvc(Arc::clone(&self.inner));
+            Box::pin(async move {
+                let mut grpc = tonic::server::Grpc::new(tonic_prost::ProstCodec::default());
+                Ok(grpc.unary(svc, req).await)
+            })
+        } else {
+            Box::pin(async move { Ok(tonic::Status::unimplemented("unknown method").into_http()) })
+        }
+    }
+}
@@ -108,10 +108,10 @@ pub async fn admission_review(
 
     let mut context = HashMap::new();
     if let Some(username) = request["userInfo"]["username"].as_str() {
-        context.insert("principal".to_string(), username.to_string());
+        context.insert("principal".to_string(), username.into());
     }
     if let Some(namespace) = request["namespace"].as_str() {
-        context.insert("namespace".to_string(), namespace.to_string());
+        context.insert("namespace".to_string(), namespace.into());
     }
 
     let policy_request = PolicyRequest {
@@ -7,7 +7,11 @@
 //! evaluator (compiled / mixed / AST — R4-01 B.3's check driver), so admission
 //! webhooks get the fast path with no per-call parse.
 
-use axum::{extract::State, http::StatusCode, response::Json};
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
 use policy_engine::PolicyRequest;
 use serde::Deserialize;
 use serde_json::{json, Value};
@@ -32,7 +36,11 @@ pub struct CheckRequest {
     #[serde(default)]
     pub resource: Option<String>,
     #[serde(default)]
-    pub context: HashMap<String, String>,
+    pub context: HashMap<String, serde_json::Value>,
+    /// Check against data at least this fresh (a control-plane revision
+    /// token); 412 `revision_unavailable` if it does not arrive in time.
+    #[serde(default)]
+    pub min_revision: Option<i64>,
 }
 
 fn default_action() -> String {
@@ -46,7 +54,8 @@ fn default_action() -> String {
     tag = "evaluation",
     request_body = CheckRequest,
     responses(
-        (status = 200, description = "Document check result with all violations")
+        (status = 200, description = "Document check result with all violations"),
+        (status = 412, description = "`min_revision` not applied in time")
     ),
     security(("bearer_jwt" = []))
 )]
@@ -54,7 +63,13 @@ fn default_action() -> String {
 pub async fn check_document(
     State(state): State<Arc<AgentState>>,
     Json(payload): Json<CheckRequest>,
-) -> Result<Json<Value>, (StatusCode, String)> {
+) -> Result<Json<Value>, Response> {
+    state
+        .data_sync
+        .require_revision(payload.min_revision)
+        .await
+        .map_err(IntoResponse::into_response)?;
+
     let policy = state
         .policy_engine
         .get_policy_by_name(&payload.policy_name)
@@ -63,6 +78,7 @@ pub async fn check_document(
                 StatusCode::NOT_FOUND,
                 format!("policy '{}' not found", payload.policy_name),
             )
+                .into_response()
         })?;
 
     // The cached evaluator was built at deploy time against the live
@@ -74,11 +90,12 @@ pub async fn check_document(
             StatusCode::UNPROCESSABLE_ENTITY,
             format!("policy '{}' has no evaluator: {e}", payload.policy_name),
         )
+            .into_response()
     })?;
 
     let mut context = payload.context.clone();
     if let Some(ref principal) = payload.principal {
-        context.insert("principal".to_string(), principal.clone());
+        context.insert("principal".to_string(), principal.clone().into());
     }
     let request = PolicyRequest {
         resource: payload
@@ -99,6 +116,7 @@ pub async fn check_document(
                 StatusCode::UNPROCESSABLE_ENTITY,
                 format!("check failed: {e}"),
             )
+                .into_response()
         })?;
 
     Ok(Json(json!({
@@ -6,7 +6,7 @@
 //! - `sync_data` - Synchronize entity data from external source
 
 use axum::{body::Bytes, extract::State, http::StatusCode, response::Json};
-use policy_engine::{AttributeValue, DataLoader, EntityBuilder, StreamingLoader, StringInterner};
+use policy_engine::{AttributeValue, EntityBuilder, StreamingLoader, StringInterner};
 use serde::Deserialize;
 use serde_json::{json, Value};
 use std::collections::HashMap;
@@ -101,7 +101,7 @@ pub async fn load_data_handler(
     info!("Loading entity data into DataStore");
 
     // DataStore uses Arc internally, so cloning is cheap and shares data
-    let loader = DataLoader::new((*state.data_store).clone());
+    let loader = state.data_sync.loader(&state.data_store);
     let entity_count = loader.load_json(&payload.data).map_err(|e| {
         error!("Failed to load entity data: {}", e);
         (
@@ -174,7 +174,7 @@ pub async fn load_data_stream_handler(
     let temp_path = temp_file.path();
 
     // Use streaming loader with 10K chunk size
-    let loader = DataLoader::new((*state.data_store).clone());
+    let loader = state.data_sync.loader(&state.data_store);
     let streaming_loader = StreamingLoader::new(loader, 10_000);
 
     let stats = streaming_loader.stream_and_load(temp_path).map_err(|e| {
@@ -517,6 +517,7 @@ pub async fn deploy_data_version(
         state.data_sync.record_heartbeat();
         return Ok(Json(json!({
             "version": current,
+            "revision": state.data_sync.applied_seq.load(std::sync::atomic::Ordering::Acquire),
             "status": "already_current",
         })));
     }
@@ -524,7 +525,7 @@ pub async fn deploy_data_version(
     if payload.replace {
         state.data_store.clear();
     }
-    let loader = DataLoader::new((*state.data_store).clone());
+    let loader = state.data_sync.loader(&state.data_store);
     let entity_count = loader.load_json(&canonical).map_err(|e| {
         error!("failed to load verified data version: {e}");
         (
@@ -538,14 +539,13 @@ pub async fn deploy_data_version(
         payload.checksum.clone(),
         payload.model_version,
     );
-    state
-        .data_sync
-        .applied_seq
-        .store(payload.change_seq, std::sync::atomic::Ordering::Release);
 
     if let Some(ref cache) = state.decision_cache {
         cache.invalidate();
     }
+    // Only now wake `min_revision` waiters: advancing before the cache is
+    // cleared would let one be served a decision cached from the old data.
+    state.data_sync.advance_to(payload.change_seq);
 
     info!(
         version = payload.version,
@@ -555,6 +555,7 @@ pub async fn deploy_data_version(
 
     Ok(Json(json!({
         "version": payload.version,
+        "revision": payload.change_seq,
         "checksum": payload.checksum,
         "entities_loaded": entity_count,
         "status": "deployed",
@@ -644,7 +645,9 @@ pub struct ApplyDeltasRequest {
 /// missing range (self-retrying, gap-proof). Deltas are entity-level
 /// last-state upserts/tombstones — idempotent under at-least-once
 /// delivery, proven equivalent to a fresh rebuild by
-/// delta_sync_differential_tests.
+/// delta_sync_differential_tests. The returned `revision` is the position
+/// this replica now serves; requests carrying a `min_revision` at or below
+/// it stop waiting.
 #[utoipa::path(
     post,
     path = "/api/v1/data/apply-deltas",
@@ -680,7 +683,7 @@ pub async fn apply_data_deltas(
         ));
     }
 
-    let loader = DataLoader::new((*state.data_store).clone());
+    let loader = state.data_sync.loader(&state.data_store);
     let mut upserts = 0usize;
     let mut deletes = 0usize;
     for delta in &payload.deltas {
@@ -711,18 +714,15 @@ pub async fn apply_data_deltas(
         }
     }
 
-    state
-        .data_sync
-        .applied_seq
-        .store(payload.head_seq, Ordering::Release);
-    // Advancing through the change stream IS a successful sync contact.
-    state.data_sync.record_heartbeat();
-
     if (upserts + deletes) > 0 {
         if let Some(ref cache) = state.decision_cache {
             cache.invalidate();
         }
     }
+    // After the invalidation, as in `deploy_data_version`.
+    state.data_sync.advance_to(payload.head_seq);
+    // Advancing through the change stream IS a successful sync contact.
+    state.data_sync.record_heartbeat();
 
     info!(
         from = payload.from_seq,
@@ -733,6 +733,7 @@ pub async fn apply_data_deltas(
     );
     Ok(Json(json!({
         "applied_seq": payload.head_seq,
+        "revision": payload.head_seq,
         "upserts": upserts,
         "deletes": deletes,
         "status": "applied",
@@ -775,9 +776,12 @@ mod deploy_version_tests {
             checksum: parking_lot::RwLock::new(String::new()),
             last_synced_epoch: std::sync::atomic::AtomicU64::new(0),
             applied_seq: std::sync::atomic::AtomicI64::new(0),
+            revision_advanced: tokio::sync::Notify::new(),
+            revision_wait: std::time::Duration::from_millis(500),
             max_staleness_secs: 10,
             mode: StalenessMode::Enforce,
             require_sync: false,
+            schema: parking_lot::RwLock::new(None),
         };
         assert!(!s.is_stale(), "never-synced has no staleness clock");
         assert!(!s.must_deny());
@@ -821,9 +825,12 @@ mod deploy_version_tests {
             checksum: parking_lot::RwLock::new(String::new()),
             last_synced_epoch: std::sync::atomic::AtomicU64::new(0),
             applied_seq: std::sync::atomic::AtomicI64::new(0),
+            revision_advanced: tokio::sync::Notify::new(),
+            revision_wait: std::time::Duration::from_millis(500),
             max_staleness_secs: 0,
             mode: StalenessMode::Monitor,
             require_sync: true,
+            schema: parking_lot::RwLock::new(None),
         };
         assert!(s.awaiting_initial_sync());
         assert!(s.must_deny(), "armed gate fails closed before first sync");
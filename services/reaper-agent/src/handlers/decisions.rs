@@ -38,6 +38,8 @@ pub struct DecisionQueryParams {
     pub resource: Option<String>,
     pub decision: Option<String>,
     pub policy_id: Option<String>,
+    /// `true`: only shadow-divergence records; `false`: only live decisions.
+    pub shadow: Option<bool>,
 }
 
 /// Export request body.
@@ -53,7 +55,8 @@ pub struct ExportRequest {
 
 /// Get recent decisions from the decision buffer.
 ///
-/// Supports filtering by principal, action, resource, decision, and policy_id.
+/// Supports filtering by principal, action, resource, decision, policy_id,
+/// and `shadow` (divergence records from shadow deployments).
 /// Use limit and offset for pagination.
 #[utoipa::path(
     get,
@@ -85,6 +88,7 @@ pub async fn get_decisions(
         || params.resource.is_some()
         || params.decision.is_some()
         || params.policy_id.is_some()
+        || params.shadow.is_some()
     {
         let mut filter = DecisionFilter::new();
         if let Some(p) = params.principal {
@@ -102,6 +106,9 @@ pub async fn get_decisions(
         if let Some(pid) = params.policy_id {
             filter = filter.with_policy_id(pid);
         }
+        if let Some(shadow) = params.shadow {
+            filter = filter.with_shadow(shadow);
+        }
         buffer.query(filter, limit)
     } else if let Some(offset) = params.offset {
         buffer.get_page(offset, limit)
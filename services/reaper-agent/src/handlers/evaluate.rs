@@ -7,14 +7,16 @@
 
 use axum::{
     body::Bytes,
-    extract::State,
+    extract::{Query, State},
     http::{header, StatusCode},
-    response::IntoResponse,
+    response::{IntoResponse, Response},
     Json,
 };
 use opentelemetry::{trace::TraceContextExt, KeyValue};
-use policy_engine::{DecisionLogEntry, PolicyAction, PolicyRequest};
-use serde::Serialize;
+use policy_engine::{
+    DecisionCache, DecisionLogEntry, DecisionObligations, PolicyAction, PolicyRequest,
+};
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use smallvec::{smallvec, SmallVec};
 use std::collections::HashMap;
@@ -27,6 +29,7 @@ use uuid::{fmt::Hyphenated, Uuid};
 use crate::observability::{
     CACHE_HITS, CACHE_MISSES, CONCURRENT_EVALUATIONS, DENIALS_TOTAL, ERRORS_TOTAL,
 };
+use crate::shadow::LiveDecision;
 use crate::state::AgentState;
 use crate::types::EvaluateRequest;
 
@@ -43,13 +46,28 @@ struct EvalResponse<'a> {
     matched_rule: &'a str,
     agent_id: &'a str,
     cache_hit: bool,
+    /// `obligations` / `advice` of the deciding rule, inlined at top level;
+    /// absent when it declared none.
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    obligations: Option<&'a DecisionObligations>,
+    /// Per-policy evaluation traces, present only for `?explain=full`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    explanation: Option<&'a [Value]>,
+}
+
+/// Query parameters of `POST /api/v1/messages`.
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
+pub struct EvaluateQuery {
+    /// `full` returns an evaluation trace with the decision (requires
+    /// `performance.allow_explain`).
+    pub explain: Option<String>,
 }
 
 /// Build the "explain" input-data snapshot: the resolved principal/resource
 /// entity attributes the decision branched on, as
 /// `{"principal": {...}, "resource": {...}}`. Read-only DataStore lookups on the
 /// LOG path (never the eval loop); returns `None` if neither is a known entity.
-fn capture_input_data(
+pub(crate) fn capture_input_data(
     data_store: &policy_engine::DataStore,
     principal: &str,
     resource: &str,
@@ -70,6 +88,17 @@ fn capture_input_data(
     (!input.is_empty()).then_some(Value::Object(input))
 }
 
+/// The caller's context as sent (typed values intact), for the decision log
+/// and replay capture. The principal has its own column.
+pub(crate) fn caller_context(request: &PolicyRequest) -> HashMap<String, Value> {
+    request
+        .context
+        .iter()
+        .filter(|(k, _)| k.as_str() != "principal")
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect()
+}
+
 /// Outcome of evaluating a request against a set of policies — the engine's
 /// [`policy_engine::SetEvalOutcome`], re-exported under the handler-local name
 /// the endpoints already use.
@@ -96,6 +125,31 @@ fn evaluate_policy_set(
     engine.evaluate_set(policy_ids, request)
 }
 
+/// One trace per evaluated policy for `?explain=full`, each tagged with its
+/// `policy_id`. A policy that cannot be traced reports its error in place of
+/// a trace so the rest of the explanation still arrives.
+fn explain_policies(
+    engine: &policy_engine::PolicyEngine,
+    policy_ids: &[Uuid],
+    request: &PolicyRequest,
+) -> Vec<Value> {
+    policy_ids
+        .iter()
+        .map(|id| match engine.explain(id, request) {
+            Ok(trace) => {
+                let mut value = json!({ "policy_id": id });
+                if let (Value::Object(map), Ok(Value::Object(trace))) =
+                    (&mut value, serde_json::to_value(&trace))
+                {
+                    map.extend(trace);
+                }
+                value
+            }
+            Err(e) => json!({ "policy_id": id, "error": e.to_string() }),
+        })
+        .collect()
+}
+
 /// Standard policy evaluation.
 ///
 /// Supports:
@@ -114,7 +168,7 @@ fn evaluate_policy_set(
 /// un-audited. Returns `503` so callers fail closed; readiness also flips
 /// not-ready so load balancers drain the instance.
 #[inline]
-fn audit_gate(state: &AgentState) -> Result<(), StatusCode> {
+pub(crate) fn audit_gate(state: &AgentState) -> Result<(), StatusCode> {
     if let Some(ref buffer) = state.decision_buffer {
         if buffer.audit_required() && !buffer.is_audit_healthy() {
             return Err(StatusCode::SERVICE_UNAVAILABLE);
@@ -123,20 +177,33 @@ fn audit_gate(state: &AgentState) -> Result<(), StatusCode> {
     Ok(())
 }
 
+/// The decision cache, unless the relationship graph holds conditional edges.
+/// An edge that expires on the clock changes no data and bumps no cache
+/// generation, so a cached allow would outlive the grant by up to the TTL
+/// (forever with no TTL).
+#[inline]
+fn decision_cache(state: &AgentState) -> Option<&Arc<DecisionCache>> {
+    state
+        .decision_cache
+        .as_ref()
+        .filter(|_| state.data_store.relationships().conditional_len() == 0)
+}
+
 /// Observe request-total latency for an early-return response (deny or 503)
 /// into the SLA histogram (`reaper_decision_duration_seconds`).
 ///
 /// Early-return denies — `data_stale`, `policy_not_found`,
 /// `evaluate_all_disabled`, `no_policies_loaded`, `candidate_cap_exceeded`,
-/// fast-path `parse_error`, and the audit-gate 503 — are SERVED requests. If
-/// they skip the histogram, a denial storm (stale-data gate tripped,
-/// misconfig, attack) leaves the request-total latency series silent while the
-/// agent answers at line rate, hiding the incident from the SLA dashboard
-/// (PERF R2-P2-3). The reason is already counted by `ERRORS_TOTAL`; here a
-/// single constant policy label keeps the histogram's cardinality bounded (the
-/// same pattern as the "cached" label on the cache-hit path).
+/// fast-path `parse_error`, the `revision_unavailable` 412 and the audit-gate 503 —
+/// are SERVED requests. If they skip the histogram, a denial storm (stale-data
+/// gate tripped, misconfig, attack) leaves the request-total latency series
+/// silent while the agent answers at line rate, hiding the incident from the
+/// SLA dashboard (PERF R2-P2-3). The reason is already counted by
+/// `ERRORS_TOTAL`; here a single constant policy label keeps the histogram's
+/// cardinality bounded (the same pattern as the "cached" label on the
+/// cache-hit path).
 #[inline]
-fn observe_served_deny(state: &AgentState, start_time: std::time::Instant) {
+pub(crate) fn observe_served_deny(state: &AgentState, start_time: std::time::Instant) {
     state
         .decision_metrics
         .for_policy("early_deny")
@@ -149,11 +216,12 @@ fn observe_served_deny(state: &AgentState, start_time: std::time::Instant) {
 /// `no_policies_loaded`, `candidate_cap_exceeded`, fast-path `parse_error`).
 /// Records the decision-quality `eval_errors` signal (round-3 Plan 03) in
 /// addition to the served-latency histogram. The fail-closed operational denies
-/// (`data_stale`, `capability_rejected`, and the audit-gate 503) are intended
-/// outcomes, NOT eval-errors — they call [`observe_served_deny`] instead so they
-/// never inflate the decision-quality signal.
+/// (`data_stale`, `capability_rejected`, the `revision_unavailable` 412 and
+/// the audit-gate 503) are intended outcomes, NOT eval-errors — they call
+/// [`observe_served_deny`] instead so they never inflate the decision-quality
+/// signal.
 #[inline]
-fn observe_early_return(state: &AgentState, start_time: std::time::Instant) {
+pub(crate) fn observe_early_return(state: &AgentState, start_time: std::time::Instant) {
     state.stats.record_eval_error();
     observe_served_deny(state, start_time);
 }
@@ -162,15 +230,42 @@ fn observe_early_return(state: &AgentState, start_time: std::time::Instant) {
     post,
     path = "/api/v1/messages",
     tag = "evaluation",
+    params(EvaluateQuery),
     responses(
-        (status = 200, description = "Policy decision")
+        (status = 200, description = "Policy decision"),
+        (status = 400, description = "Unknown `explain` mode"),
+        (status = 403, description = "`explain` requested but not enabled"),
+        (status = 412, description = "`min_revision` not applied in time")
     ),
     security(("bearer_jwt" = []))
 )]
+pub async fn evaluate_messages(
+    State(state): State<Arc<AgentState>>,
+    Query(query): Query<EvaluateQuery>,
+    payload: Json<EvaluateRequest>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let explain = match query.explain.as_deref() {
+        None => false,
+        Some("full") if state.agent_config.performance.allow_explain => true,
+        Some("full") => return Err(StatusCode::FORBIDDEN),
+        Some(_) => return Err(StatusCode::BAD_REQUEST),
+    };
+    evaluate(state, payload, explain).await
+}
+
+/// [`evaluate_messages`] without query parameters.
 pub async fn evaluate_policy(
     State(state): State<Arc<AgentState>>,
-    Json(mut payload): Json<EvaluateRequest>,
+    payload: Json<EvaluateRequest>,
 ) -> Result<impl IntoResponse, StatusCode> {
+    evaluate(state, payload, false).await
+}
+
+async fn evaluate(
+    state: Arc<AgentState>,
+    Json(mut payload): Json<EvaluateRequest>,
+    explain: bool,
+) -> Result<Response, StatusCode> {
     // Start the request-total clock before the FIRST possible return so every
     // served response — success, deny, or fail-closed 503 — is observed.
     let start_time = std::time::Instant::now();
@@ -208,10 +303,22 @@ pub async fn evaluate_policy(
             matched_rule: reason,
             agent_id: &state.agent_id,
             cache_hit: false,
+            obligations: None,
+            explanation: None,
         })
         .unwrap_or_default();
         observe_served_deny(&state, start_time);
-        return Ok(([(header::CONTENT_TYPE, "application/json")], body));
+        return Ok(([(header::CONTENT_TYPE, "application/json")], body).into_response());
+    }
+
+    // REVISION GATE: the caller wrote through the control plane and holds
+    // its revision token; answering from an older replica could miss the
+    // write (the new-enemy problem). Wait briefly for the delta to land,
+    // else fail CLOSED with 412 `revision_unavailable` — no decision, so the
+    // caller knows a retry can succeed.
+    if let Err(unavailable) = state.data_sync.require_revision(payload.min_revision).await {
+        observe_served_deny(&state, start_time);
+        return Ok(unavailable.into_response());
     }
 
     // CAPABILITY GATE (F1-s3): a presented capability must verify — crypto,
@@ -243,10 +350,12 @@ pub async fn evaluate_policy(
             matched_rule: &reason,
             agent_id: &state.agent_id,
             cache_hit: false,
+            obligations: None,
+            explanation: None,
         })
         .unwrap_or_default();
         observe_served_deny(&state, start_time);
-        return Ok(([(header::CONTENT_TYPE, "application/json")], body));
+        return Ok(([(header::CONTENT_TYPE, "application/json")], body).into_response());
     }
 
     // Determine which policy/policies to evaluate
@@ -276,10 +385,14 @@ pub async fn evaluate_policy(
                             matched_rule: "policy_not_found",
                             agent_id: &state.agent_id,
                             cache_hit: false,
+                            obligations: None,
+                            explanation: None,
                         })
                         .unwrap_or_default();
                         observe_early_return(&state, start_time);
-                        return Ok(([(header::CONTENT_TYPE, "application/json")], body));
+                        return Ok(
+                            ([(header::CONTENT_TYPE, "application/json")], body).into_response()
+                        );
                     }
                 }
             }
@@ -307,10 +420,12 @@ pub async fn evaluate_policy(
                     matched_rule: "policy_not_found",
                     agent_id: &state.agent_id,
                     cache_hit: false,
+                    obligations: None,
+                    explanation: None,
                 })
                 .unwrap_or_default();
                 observe_early_return(&state, start_time);
-                return Ok(([(header::CONTENT_TYPE, "application/json")], body));
+                return Ok(([(header::CONTENT_TYPE, "application/json")], body).into_response());
             }
         }
     } else {
@@ -333,10 +448,12 @@ pub async fn evaluate_policy(
                 matched_rule: "evaluate_all_disabled",
                 agent_id: &state.agent_id,
                 cache_hit: false,
+                obligations: None,
+                explanation: None,
             })
             .unwrap_or_default();
             observe_early_return(&state, start_time);
-            return Ok(([(header::CONTENT_TYPE, "application/json")], body));
+            return Ok(([(header::CONTENT_TYPE, "application/json")], body).into_response());
         }
 
         // Prune to candidate policies for this resource instead of cloning the
@@ -370,10 +487,12 @@ pub async fn evaluate_policy(
                 matched_rule: "no_policies_loaded",
                 agent_id: &state.agent_id,
                 cache_hit: false,
+                obligations: None,
+                explanation: None,
             })
             .unwrap_or_default();
             observe_early_return(&state, start_time);
-            return Ok(([(header::CONTENT_TYPE, "application/json")], body));
+            return Ok(([(header::CONTENT_TYPE, "application/json")], body).into_response());
         }
 
         // Hard cap post-pruning: reject rather than fan out to an N-eval.
@@ -391,10 +510,12 @@ pub async fn evaluate_policy(
                 matched_rule: "candidate_cap_exceeded",
                 agent_id: &state.agent_id,
                 cache_hit: false,
+                obligations: None,
+                explanation: None,
             })
             .unwrap_or_default();
             observe_early_return(&state, start_time);
-            return Ok(([(header::CONTENT_TYPE, "application/json")], body));
+            return Ok(([(header::CONTENT_TYPE, "application/json")], body).into_response());
         }
 
         candidate_ids.into()
@@ -404,7 +525,7 @@ pub async fn evaluate_policy(
     // Only allocate context HashMap entries beyond the standard principal key
     // when there are actual extra context fields.
     let mut context = payload.context.take().unwrap_or_default();
-    context.insert("principal".to_string(), payload.principal.clone());
+    context.insert("principal".to_string(), payload.principal.clone().into());
 
     let request = PolicyRequest {
         resource: payload.resource.clone(),
@@ -431,8 +552,9 @@ pub async fn evaluate_policy(
         .map(|c| c.generation())
         .unwrap_or(0);
 
-    // Check decision cache first (if enabled)
-    if let Some(ref cache) = state.decision_cache {
+    // Check decision cache first (if enabled). An explained request must be
+    // evaluated to be traced, so it never answers from the cache.
+    if let Some(cache) = decision_cache(&state).filter(|_| !explain) {
         if let Some(cached_decision) = cache.get(&request, cache_scope) {
             // Cache hit - return cached decision immediately
             state.stats.record_decision_cache_hit();
@@ -462,6 +584,8 @@ pub async fn evaluate_policy(
                 matched_rule: "cached_decision",
                 agent_id: &state.agent_id,
                 cache_hit: true,
+                obligations: None,
+                explanation: None,
             })
             .unwrap_or_default();
 
@@ -474,7 +598,16 @@ pub async fn evaluate_policy(
                 .duration
                 .observe(start_time.elapsed().as_secs_f64());
 
-            return Ok(([(header::CONTENT_TYPE, "application/json")], body));
+            crate::shadow::spawn(&state, &policy_ids, &request, || LiveDecision {
+                decision_id: decision_id.to_string(),
+                decision: cached_decision.clone(),
+                policy_id: "cached".to_string(),
+                policy_name: String::new(),
+                policy_version: 0,
+                matched_rule: "cached_decision".to_string(),
+            });
+
+            return Ok(([(header::CONTENT_TYPE, "application/json")], body).into_response());
         }
         state.stats.record_decision_cache_miss();
         CACHE_MISSES.with_label_values(&["decision"]).inc();
@@ -590,13 +723,6 @@ pub async fn evaluate_policy(
                 String::new()
             };
 
-            let mut context_values: HashMap<String, serde_json::Value> = HashMap::new();
-            if let Some(ref ctx) = payload.context {
-                for (k, v) in ctx {
-                    context_values.insert(k.clone(), serde_json::Value::String(v.clone()));
-                }
-            }
-
             let mut entry = DecisionLogEntry::new(
                 payload.principal.clone(),
                 payload.action.clone(),
@@ -606,12 +732,15 @@ pub async fn evaluate_policy(
                 matched_policy_name.clone(),
             )
             .with_trace_id(trace_id)
-            .with_context(context_values)
+            .with_context(caller_context(&request))
             .with_evaluation_time_ns(total_eval_time_ns)
             .with_cache_hit(false)
             .with_agent_id(state.agent_id.clone())
             .with_policy_version(matched_policy_version.to_string())
             .with_matched_rule(matched_rule.clone());
+            if let Some(ref obligations) = outcome.obligations {
+                entry = entry.with_obligations(obligations.clone());
+            }
             // Data-plane provenance: which datastore version/checksum this
             // decision saw, and whether it ran past the staleness budget.
             let (data_version, data_checksum) = state.data_sync.provenance();
@@ -640,7 +769,7 @@ pub async fn evaluate_policy(
                     "principal": payload.principal,
                     "action": payload.action,
                     "resource": payload.resource,
-                    "context": payload.context.clone().unwrap_or_default(),
+                    "context": caller_context(&request),
                 });
                 // Agentic fields ride along so a counterfactual replay sees
                 // the same actor bindings and taint labels (F1-s4).
@@ -679,8 +808,10 @@ pub async fn evaluate_policy(
         }
     }
 
-    // Cache the decision for future requests (if caching enabled)
-    if let Some(ref cache) = state.decision_cache {
+    // Cache the decision for future requests (if caching enabled). The cache
+    // holds only the action, so a decision carrying obligations is never
+    // cached — a hit would silently drop them.
+    if let (Some(cache), None) = (decision_cache(&state), &outcome.obligations) {
         cache.insert(
             &request,
             cache_scope,
@@ -689,9 +820,22 @@ pub async fn evaluate_policy(
         );
     }
 
+    // Shadow candidates (if any) see the same request off the response path.
+    crate::shadow::spawn(&state, &policy_ids, &request, || LiveDecision {
+        decision_id: decision_id.to_string(),
+        decision: final_decision.clone(),
+        policy_id: matched_policy_id.to_string(),
+        policy_name: matched_policy_name.clone(),
+        policy_version: matched_policy_version,
+        matched_rule: matched_rule.clone(),
+    });
+
     // Pre-format the policy_id string to avoid allocation in the response struct
     let policy_id_str = matched_policy_id.to_string();
 
+    let explanation: Option<Vec<Value>> =
+        explain.then(|| explain_policies(&state.policy_engine, &policy_ids, &request));
+
     let body = sonic_rs::to_vec(&EvalResponse {
         decision_id,
         decision: decision_str,
@@ -702,6 +846,8 @@ pub async fn evaluate_policy(
         matched_rule: &matched_rule,
         agent_id: &state.agent_id,
         cache_hit: false,
+        obligations: outcome.obligations.as_ref(),
+        explanation: explanation.as_deref(),
     })
     .unwrap_or_default();
 
@@ -709,7 +855,7 @@ pub async fn evaluate_policy(
     // series reports what a client experiences, not just the engine slice.
     metrics.duration.observe(start_time.elapsed().as_secs_f64());
 
-    Ok(([(header::CONTENT_TYPE, "application/json")], body))
+    Ok(([(header::CONTENT_TYPE, "application/json")], body).into_response())
 }
 
 /// Fast policy evaluation using SIMD-accelerated JSON parsing (sonic-rs).
@@ -728,7 +874,8 @@ pub async fn evaluate_policy(
     path = "/api/v1/fast-messages",
     tag = "evaluation",
     responses(
-        (status = 200, description = "Policy decision (SIMD-accelerated path)")
+        (status = 200, description = "Policy decision (SIMD-accelerated path)"),
+        (status = 412, description = "`min_revision` not applied in time")
     ),
     security(("bearer_jwt" = []))
 )]
@@ -736,21 +883,22 @@ pub async fn fast_evaluate_policy(
     State(state): State<Arc<AgentState>>,
     body: Bytes,
 ) -> Result<axum::response::Response, StatusCode> {
-    // Agentic requests (actor / capability / taint labels) take the STANDARD
-    // path: they need capability enforcement and full typed parsing, and are
-    // a small fraction of fast-path traffic. The dispatch probe is a SIMD
-    // substring scan for the quoted keys — a false positive (the text inside
-    // some value) merely routes that one request through the standard lane,
-    // which parses properly and stays correct; a real key can never be
-    // missed. Plain requests pay three memmem probes and keep the pure
-    // SIMD lane.
-    fn looks_agentic(body: &[u8]) -> bool {
+    // Agentic requests (actor / capability / taint labels) and requests
+    // demanding a data revision take the STANDARD path: they need capability
+    // enforcement, the revision wait and full typed parsing, and are a small
+    // fraction of fast-path traffic. The dispatch probe is a SIMD substring
+    // scan for the quoted keys — a false positive (the text inside some
+    // value) merely routes that one request through the standard lane, which
+    // parses properly and stays correct; a real key can never be missed.
+    // Plain requests pay four memmem probes and keep the pure SIMD lane.
+    fn needs_standard_lane(body: &[u8]) -> bool {
         use memchr::memmem::find;
         find(body, b"\"capability\"").is_some()
             || find(body, b"\"actor\"").is_some()
             || find(body, b"\"context_provenance\"").is_some()
+            || find(body, b"\"min_revision\"").is_some()
     }
-    if looks_agentic(&body) {
+    if needs_standard_lane(&body) {
         return match serde_json::from_slice::<EvaluateRequest>(&body) {
             Ok(payload) => evaluate_policy(State(state), Json(payload))
                 .await
@@ -822,25 +970,20 @@ async fn fast_evaluate_policy_plain(
     let policy_id_opt = value.get("policy_id").and_then(|v| v.as_str());
     let policy_name_opt = value.get("policy_name").and_then(|v| v.as_str());
 
-    // Build context — single pass, minimal allocations
-    let mut context = HashMap::with_capacity(4);
-    context.insert("principal".to_string(), principal.to_string());
+    // Build context — single pass, values kept as full JSON
+    let mut context: HashMap<String, serde_json::Value> = HashMap::with_capacity(4);
     if let Some(ctx) = value.get("context") {
         if let Some(obj) = ctx.as_object() {
             for (k, v) in obj.iter() {
-                let val = if let Some(s) = v.as_str() {
-                    s.to_string()
-                } else if v.is_i64() {
-                    v.as_i64().unwrap().to_string()
-                } else if let Some(b) = v.as_bool() {
-                    b.to_string()
-                } else {
+                // A parsed sonic value always re-encodes; skip defensively.
+                let Ok(val) = serde_json::to_value(v) else {
                     continue;
                 };
                 context.insert(k.to_string(), val);
             }
         }
     }
+    context.insert("principal".to_string(), principal.into());
 
     // Determine policy to evaluate — capture name at lookup time
     let mut policy_name_resolved = String::new();
@@ -1015,10 +1158,9 @@ async fn fast_evaluate_policy_plain(
     if let Some(ref buffer) = state.decision_buffer {
         if buffer.should_log(decision_str == "allow") {
             let principal = request
-                .context
-                .get("principal")
-                .cloned()
-                .unwrap_or_default();
+                .context_str("principal")
+                .unwrap_or_default()
+                .to_string();
             let mut entry = DecisionLogEntry::new(
                 principal,
                 request.action.clone(),
@@ -1027,11 +1169,15 @@ async fn fast_evaluate_policy_plain(
                 matched_policy_id.to_string(),
                 policy_name_resolved.clone(),
             )
+            .with_context(caller_context(&request))
             .with_evaluation_time_ns(total_eval_time_ns)
             .with_cache_hit(false)
             .with_agent_id(state.agent_id.clone())
             .with_policy_version(matched_policy_version.to_string())
             .with_matched_rule(matched_rule_rendered.clone());
+            if let Some(ref obligations) = outcome.obligations {
+                entry = entry.with_obligations(obligations.clone());
+            }
             let (data_version, data_checksum) = state.data_sync.provenance();
             entry = entry
                 .with_data_sync(data_version, data_checksum, state.data_sync.flag_stale())
@@ -1041,11 +1187,7 @@ async fn fast_evaluate_policy_plain(
             if buffer.should_capture_input(decision_str == "allow", false) {
                 entry.input_data = capture_input_data(
                     &state.data_store,
-                    &request
-                        .context
-                        .get("principal")
-                        .cloned()
-                        .unwrap_or_default(),
+                    request.context_str("principal").unwrap_or_default(),
                     &request.resource,
                     None,
                 );
@@ -1054,11 +1196,7 @@ async fn fast_evaluate_policy_plain(
             // canonical handler above). Protection applies in buffer.log().
             if buffer.should_capture_replay(decision_str == "allow") {
                 entry.replay_input = Some(serde_json::json!({
-                    "principal": request
-                        .context
-                        .get("principal")
-                        .cloned()
-                        .unwrap_or_default(),
+                    "principal": request.context_str("principal").unwrap_or_default(),
                     "action": request.action,
                     "resource": request.resource,
                     "context": request.context,
@@ -1102,6 +1240,8 @@ async fn fast_evaluate_policy_plain(
         matched_rule: &matched_rule_str,
         agent_id: &state.agent_id,
         cache_hit: false,
+        obligations: outcome.obligations.as_ref(),
+        explanation: None,
     })
     .unwrap_or_default();
 
@@ -1126,12 +1266,16 @@ async fn fast_evaluate_policy_plain(
 ///   batch finishes in ~batch/cores time instead of running sequentially.
 /// - Optional decision-cache integration; results preserve input order via an
 ///   explicit `index` field.
+/// - `min_revision` waits for that data revision like the single-request
+///   endpoint; if it does not arrive the batch answers 412
+///   `revision_unavailable` with no decisions.
 #[utoipa::path(
     post,
     path = "/api/v1/batch-messages",
     tag = "evaluation",
     responses(
         (status = 200, description = "Batch policy decisions"),
+        (status = 412, description = "`min_revision` not applied in time"),
         (status = 413, description = "Batch request count exceeds configured maximum")
     ),
     security(("bearer_jwt" = []))
@@ -1140,8 +1284,8 @@ async fn fast_evaluate_policy_plain(
 pub async fn batch_evaluate_policy(
     State(state): State<Arc<AgentState>>,
     Json(payload): Json<crate::types::BatchEvaluateRequest>,
-) -> Result<Json<Value>, StatusCode> {
-    audit_gate(&state)?;
+) -> Result<Json<Value>, Response> {
+    audit_gate(&state).map_err(IntoResponse::into_response)?;
     let start_time = std::time::Instant::now();
 
     // Bound the batch BEFORE any work: the 256 MB body limit exists for bulk
@@ -1156,9 +1300,18 @@ pub async fn batch_evaluate_policy(
             max = max_batch,
             "batch evaluation rejected: request count exceeds max_batch_requests"
         );
-        return Err(StatusCode::PAYLOAD_TOO_LARGE);
+        return Err(StatusCode::PAYLOAD_TOO_LARGE.into_response());
     }
 
+    // Same revision gate as the single-request path. The whole batch reads
+    // one replica state, so one wait covers every item; on timeout no item
+    // is decided.
+    state
+        .data_sync
+        .require_revision(payload.min_revision)
+        .await
+        .map_err(IntoResponse::into_response)?;
+
     // Find the policy to evaluate
     let policy = if let Some(ref name) = payload.policy_name {
         match state.policy_engine.get_policy_by_name(name) {
@@ -1213,7 +1366,7 @@ pub async fn batch_evaluate_policy(
         .await;
         requests.push(gated.map(|()| {
             let mut context = r.context.clone().unwrap_or_default();
-            context.insert("principal".to_string(), r.principal.clone());
+            context.insert("principal".to_string(), r.principal.clone().into());
             PolicyRequest {
                 resource: r.resource.clone(),
                 action: r.action.clone(),
@@ -1249,6 +1402,7 @@ pub async fn batch_evaluate_policy(
         let state = eval_state;
         // Resolve the per-policy metric handle once for the whole batch.
         let metrics = state.decision_metrics.for_policy(&eval_policy_name);
+        let cache = decision_cache(&state);
         requests
             .par_iter()
             .with_min_len(32)
@@ -1275,32 +1429,35 @@ pub async fn batch_evaluate_policy(
                 };
 
                 // Check decision cache first
-                let (decision, cache_hit) = if let Some(ref cache) = state.decision_cache {
+                let (decision, obligations, cache_hit) = if let Some(cache) = cache {
                     if let Some(cached) = cache.get(req, cache_scope) {
                         state.stats.record_decision_cache_hit();
                         CACHE_HITS.with_label_values(&["decision"]).inc();
-                        (cached, true)
+                        (cached, None, true)
                     } else {
                         state.stats.record_decision_cache_miss();
                         CACHE_MISSES.with_label_values(&["decision"]).inc();
 
-                        // Evaluate and cache
-                        let result = state.policy_engine.evaluate(&policy_id, req);
-                        let decision = match result {
-                            Ok(d) => d.decision,
-                            Err(_) => PolicyAction::Deny,
-                        };
-                        cache.insert(req, cache_scope, decision.clone(), cache_generation);
-                        (decision, false)
+                        // Evaluate and cache (obligation-bearing decisions
+                        // are not cacheable — see the single endpoint).
+                        let (decision, obligations) =
+                            match state.policy_engine.evaluate(&policy_id, req) {
+                                Ok(d) => (d.decision, d.obligations),
+                                Err(_) => (PolicyAction::Deny, None),
+                            };
+                        if obligations.is_none() {
+                            cache.insert(req, cache_scope, decision.clone(), cache_generation);
+                        }
+                        (decision, obligations, false)
                     }
                 } else {
                     // No cache - evaluate directly
-                    let result = state.policy_engine.evaluate(&policy_id, req);
-                    let decision = match result {
-                        Ok(d) => d.decision,
-                        Err(_) => PolicyAction::Deny,
-                    };
-                    (decision, false)
+                    let (decision, obligations) =
+                        match state.policy_engine.evaluate(&policy_id, req) {
+                            Ok(d) => (d.decision, d.obligations),
+                            Err(_) => (PolicyAction::Deny, None),
+                        };
+                    (decision, obligations, false)
                 };
 
                 let duration = eval_start.elapsed();
@@ -1313,12 +1470,21 @@ pub async fn batch_evaluate_policy(
                 // Record metrics via the cached per-policy handle.
                 metrics.counter(&decision).inc();
 
-                json!({
+                let mut item = json!({
                     "index": i,
                     "decision": decision_str,
                     "evaluation_time_microseconds": duration.as_nanos() as f64 / 1000.0,
                     "cache_hit": cache_hit
-                })
+                });
+                if let (Some(o), Some(fields)) = (obligations, item.as_object_mut()) {
+                    if !o.obligations.is_empty() {
+                        fields.insert("obligations".into(), Value::Object(o.obligations));
+                    }
+                    if !o.advice.is_empty() {
+                        fields.insert("advice".into(), Value::Object(o.advice));
+                    }
+                }
+                item
             })
             .collect()
     })
@@ -1329,7 +1495,7 @@ pub async fn batch_evaluate_policy(
             // The blocking task panicked or was cancelled — fail closed.
             ERRORS_TOTAL.with_label_values(&["batch_eval_join"]).inc();
             error!(error = %join_err, "batch evaluation task failed");
-            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
         }
     };
 
@@ -1403,6 +1569,8 @@ mod tests {
             matched_rule: "rule_0",
             agent_id: "agent-001",
             cache_hit: false,
+            obligations: None,
+            explanation: None,
         };
         let bytes = sonic_rs::to_vec(&resp).unwrap();
         let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
@@ -0,0 +1,254 @@
+//! List authorization (`docs/development/FILTER_COMPILATION.md`).
+//!
+//! `POST /api/v1/filter` answers "which resources of this type may the
+//! principal act on?" in one call, instead of a listing page point-checking
+//! every row it fetched through `/api/v1/batch-messages`. Three modes:
+//!
+//! - `ids` (default): permitted resource ids from the agent's `DataStore`,
+//!   one keyset page at a time. Exact — every id is verified by the same
+//!   set evaluation `/api/v1/messages` serves.
+//! - `sql`: a parameterized `WHERE` clause for resources that live in the
+//!   caller's database, with `exact: false` when rows must still be
+//!   verified.
+//! - `ir`: the composed residual itself, for external translators.
+
+use axum::{extract::State, http::StatusCode, response::Json};
+use policy_engine::filter::{
+    filter_ids, filter_sql, residualize, FilterError, FilterQuery, FilterScope, IdsOptions,
+    ParamStyle, SqlOptions,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::instrument;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::observability::ERRORS_TOTAL;
+use crate::state::AgentState;
+
+/// Largest `ids` page a single call may ask for.
+const MAX_PAGE: usize = 1000;
+
+/// What `POST /api/v1/filter` returns.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterMode {
+    #[default]
+    Ids,
+    Sql,
+    Ir,
+}
+
+/// Column mapping for `mode=sql`.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct SqlMapping {
+    /// Column holding the resource id (default `id`).
+    #[serde(default = "default_id_column")]
+    pub id_column: String,
+    /// Resource attribute → column. Atoms over unmapped attributes widen.
+    #[serde(default)]
+    pub columns: HashMap<String, String>,
+    /// Placeholder style: `dollar` (`$1`, default) or `question` (`?`).
+    #[serde(default)]
+    #[schema(value_type = String)]
+    pub param_style: ParamStyle,
+}
+
+fn default_id_column() -> String {
+    "id".to_string()
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct FilterRequest {
+    /// UUID or name of the policy to filter by. With neither this nor
+    /// `policy_name`, every deployed policy composes (evaluate-all, gated
+    /// by `performance.allow_evaluate_all`).
+    #[serde(default)]
+    pub policy_id: Option<String>,
+    #[serde(default)]
+    pub policy_name: Option<String>,
+    pub principal: String,
+    pub action: String,
+    /// Entity type of the resources to list (the data document's `type`).
+    pub resource_type: String,
+    #[serde(default)]
+    pub context: HashMap<String, serde_json::Value>,
+    #[serde(default)]
+    pub actor: Option<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<Object>)]
+    pub context_provenance: Option<HashMap<String, policy_engine::TrustLevel>>,
+    #[serde(default)]
+    pub mode: FilterMode,
+    /// `mode=ids`: the previous page's `next_cursor`.
+    #[serde(default)]
+    pub cursor: Option<String>,
+    /// `mode=ids`: page size (default 100, max 1000).
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// `mode=sql`: column mapping; defaults to `id` and no attribute
+    /// columns.
+    #[serde(default)]
+    pub sql: Option<SqlMapping>,
+    /// Filter against data at least this fresh (a control-plane revision
+    /// token); 412 `revision_unavailable` if it does not arrive in time.
+    #[serde(default)]
+    pub min_revision: Option<i64>,
+}
+
+type FilterResult = Result<Json<Value>, (StatusCode, Json<Value>)>;
+
+fn reject(status: StatusCode, code: &str, message: impl Into<String>) -> (StatusCode, Json<Value>) {
+    ERRORS_TOTAL.with_label_values(&[code]).inc();
+    (
+        status,
+        Json(json!({ "error": code, "message": message.into() })),
+    )
+}
+
+fn filter_error(e: FilterError) -> (StatusCode, Json<Value>) {
+    let status = match e {
+        FilterError::InvalidColumn { .. } => StatusCode::BAD_REQUEST,
+        FilterError::NotResidualizable { .. }
+        | FilterError::CandidatesExceeded { .. }
+        | FilterError::Evaluation { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+    };
+    reject(status, e.code(), e.to_string())
+}
+
+/// POST /api/v1/filter
+#[utoipa::path(
+    post,
+    path = "/api/v1/filter",
+    tag = "evaluation",
+    request_body = FilterRequest,
+    responses(
+        (status = 200, description = "Permitted resource ids, a SQL filter, or the residual"),
+        (status = 400, description = "Invalid SQL column mapping"),
+        (status = 403, description = "Evaluate-all is disabled"),
+        (status = 404, description = "Policy not found"),
+        (status = 412, description = "`min_revision` not applied in time"),
+        (status = 422, description = "Policy not residualizable, or the verify budget was exceeded"),
+        (status = 503, description = "Data plane gate tripped")
+    ),
+    security(("bearer_jwt" = []))
+)]
+#[instrument(skip(state, payload))]
+pub async fn filter_resources(
+    State(state): State<Arc<AgentState>>,
+    Json(payload): Json<FilterRequest>,
+) -> FilterResult {
+    // Same fail-closed data gate as the point path: a stale or unsynced
+    // replica must not mint allows, one row or a thousand.
+    if let Some(reason) = state.data_sync.deny_reason() {
+        return Err(reject(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "data_stale",
+            format!("data plane gate tripped: {reason}"),
+        ));
+    }
+    state
+        .data_sync
+        .require_revision(payload.min_revision)
+        .await?;
+
+    let perf = &state.agent_config.performance;
+    let named = payload
+        .policy_id
+        .as_deref()
+        .or(payload.policy_name.as_deref());
+    let scope = match named {
+        Some(key) => {
+            let id = match Uuid::from_str(key) {
+                Ok(id) => Some(id).filter(|id| state.policy_engine.get_policy(id).is_some()),
+                Err(_) => state.policy_engine.get_policy_by_name(key).map(|p| p.id),
+            };
+            match id {
+                Some(id) => FilterScope::Policies(vec![id]),
+                None => {
+                    return Err(reject(
+                        StatusCode::NOT_FOUND,
+                        "policy_not_found",
+                        format!("policy '{key}' not found"),
+                    ))
+                }
+            }
+        }
+        None if !perf.allow_evaluate_all => {
+            return Err(reject(
+                StatusCode::FORBIDDEN,
+                "evaluate_all_disabled",
+                "name a policy or enable performance.allow_evaluate_all",
+            ))
+        }
+        None => FilterScope::All {
+            max_candidate_policies: perf.max_candidate_policies,
+            use_pruning_index: perf.use_pruning_index,
+        },
+    };
+
+    let query = FilterQuery {
+        principal: payload.principal,
+        action: payload.action,
+        resource_type: payload.resource_type,
+        context: payload.context,
+        actor: payload.actor,
+        context_provenance: payload.context_provenance,
+        scope,
+    };
+    let mode = payload.mode;
+    let ids_options = IdsOptions {
+        after: payload.cursor,
+        limit: payload.limit.unwrap_or(100).clamp(1, MAX_PAGE),
+        max_candidates: perf.max_filter_candidates,
+    };
+    let mapping = payload.sql.unwrap_or_else(|| SqlMapping {
+        id_column: default_id_column(),
+        columns: HashMap::new(),
+        param_style: ParamStyle::default(),
+    });
+    let sql_options = SqlOptions {
+        id_column: mapping.id_column,
+        columns: mapping.columns,
+        param_style: mapping.param_style,
+        max_in_list: perf.max_filter_in_list,
+    };
+
+    // Verification is up to `max_filter_candidates` point evaluations: keep
+    // it off the reactor, like the batch endpoint.
+    let start = std::time::Instant::now();
+    let state_for_eval = state.clone();
+    let outcome = tokio::task::spawn_blocking(move || {
+        let state = state_for_eval;
+        let engine = &state.policy_engine;
+        let store = &state.data_store;
+        match mode {
+            FilterMode::Ids => filter_ids(engine, store, &query, &ids_options)
+                .map(|page| serde_json::to_value(page).unwrap_or_default()),
+            FilterMode::Sql => filter_sql(engine, store, &query, &sql_options)
+                .map(|filter| serde_json::to_value(filter).unwrap_or_default()),
+            FilterMode::Ir => residualize(engine, &query)
+                .map(|residual| json!({ "residual": residual, "data_epoch": store.data_epoch() })),
+        }
+    })
+    .await
+    .map_err(|e| {
+        reject(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "internal_error",
+            format!("filter task failed: {e}"),
+        )
+    })?;
+
+    let mut body = outcome.map_err(filter_error)?;
+    if let Value::Object(ref mut map) = body {
+        map.insert(
+            "filter_time_us".to_string(),
+            json!(start.elapsed().as_micros() as u64),
+        );
+    }
+    Ok(Json(body))
+}
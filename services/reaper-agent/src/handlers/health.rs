@@ -239,6 +239,7 @@ mod tests {
             bundle_verifier: Arc::new(crate::management::verify::BundleVerifier::from_config(
                 &reaper_core::config::ManagementSettings::default(),
             )),
+            shadow: Default::default(),
             capability_gate: std::sync::Arc::new(
                 crate::capability_cache::CapabilityGateRuntime::from_auth(
                     &reaper_core::config::AgentAuthSettings::default(),
@@ -344,9 +345,12 @@ mod tests {
             checksum: parking_lot::RwLock::new(String::new()),
             last_synced_epoch: std::sync::atomic::AtomicU64::new(0),
             applied_seq: std::sync::atomic::AtomicI64::new(0),
+            revision_advanced: tokio::sync::Notify::new(),
+            revision_wait: std::time::Duration::from_millis(500),
             max_staleness_secs: 0,
             mode: crate::state::StalenessMode::Monitor,
             require_sync: true,
+            schema: parking_lot::RwLock::new(None),
         };
         let state = create_test_state_with_sync(data_sync);
 
@@ -386,9 +390,12 @@ mod tests {
             checksum: parking_lot::RwLock::new(String::new()),
             last_synced_epoch: std::sync::atomic::AtomicU64::new(0),
             applied_seq: std::sync::atomic::AtomicI64::new(0),
+            revision_advanced: tokio::sync::Notify::new(),
+            revision_wait: std::time::Duration::from_millis(500),
             max_staleness_secs: 10,
             mode: crate::state::StalenessMode::Enforce,
             require_sync: false,
+            schema: parking_lot::RwLock::new(None),
         };
         let state = create_test_state_with_sync(data_sync);
         let policy = policy_engine::EnhancedPolicy::new(
@@ -3,7 +3,10 @@
 //! This module organizes handlers by domain:
 //! - `health`: Health checks, readiness, liveness, metrics
 //! - `evaluate`: Policy evaluation endpoints
+//! - `filter`: List authorization (permitted ids / SQL filters)
+//! - `relations`: ReBAC lookup-resources / lookup-subjects / expand
 //! - `policies`: Policy deployment and management
+//! - `shadow`: Shadow-deployed policy candidates
 //! - `entities`: Entity CRUD operations
 //! - `data`: Data loading and synchronization
 //! - `decisions`: Decision logging and analytics
@@ -14,8 +17,11 @@ pub mod data;
 pub mod decisions;
 pub mod entities;
 pub mod evaluate;
+pub mod filter;
 pub mod health;
 pub mod policies;
+pub mod relations;
+pub mod shadow;
 
 // Re-export health handlers
 pub use health::{health_check, liveness_check, metrics, readiness_check};
@@ -23,13 +29,20 @@ pub use health::{health_check, liveness_check, metrics, readiness_check};
 // Re-export evaluation handlers
 pub use admission::admission_review;
 pub use check::check_document;
-pub use evaluate::{batch_evaluate_policy, evaluate_policy, fast_evaluate_policy};
+pub use evaluate::{batch_evaluate_policy, evaluate_messages, fast_evaluate_policy};
+// The query-less form of `evaluate_messages`, for in-process callers (the
+// binary routes `evaluate_messages`).
+#[allow(unused_imports)]
+pub use evaluate::evaluate_policy;
+pub use filter::filter_resources;
+pub use relations::{expand_relation, lookup_relation_resources, lookup_relation_subjects};
 
 // Re-export policy management handlers
 pub use policies::{
     deploy_bundle, deploy_compiled_policy, deploy_policy, get_policy_current_version,
     get_policy_versions, list_policies, load_bundles_atomic,
 };
+pub use shadow::{list_shadows, remove_shadow};
 
 // Re-export data handlers
 pub use data::{
@@ -7,6 +7,9 @@
 //! - `get_policy_current_version` - Get current version of a policy
 //! - `deploy_compiled_policy` - Deploy and compile a .reap policy
 //! - `deploy_bundle` - Deploy a policy bundle (.rbb file)
+//!
+//! `deploy_compiled_policy` and `deploy_bundle` also accept `shadow: true`,
+//! which installs the candidate next to the live policy (see [`crate::shadow`]).
 
 use axum::{
     extract::{Path, State},
@@ -33,6 +36,11 @@ pub struct DeployCompiledPolicyRequest {
     pub policy_content: String,
     /// Policy name
     pub policy_name: String,
+    /// Deploy as a shadow of the live policy with this name instead of
+    /// replacing it: live traffic is also evaluated against this candidate
+    /// and divergences are reported, but it never serves.
+    #[serde(default)]
+    pub shadow: bool,
 }
 
 /// Deploy a policy from JSON rules.
@@ -325,6 +333,19 @@ pub async fn deploy_compiled_policy(
     // Set API source metadata
     enhanced_policy.set_api_source(None, Some("platform".to_string()));
 
+    if payload.shadow {
+        require_live_policy(&state, &payload.policy_name)?;
+        let version = enhanced_policy.version.to_string();
+        state.shadow.install(enhanced_policy, version);
+        info!("Policy {} deployed as shadow", payload.policy_name);
+        return Ok(Json(json!({
+            "status": "shadowed",
+            "policy_name": payload.policy_name,
+            "deployment_time": chrono::Utc::now(),
+            "message": "Policy compiled and deployed as a shadow of the live policy"
+        })));
+    }
+
     let policy_id = enhanced_policy.id;
 
     // Deploy to PolicyEngine
@@ -433,7 +454,12 @@ pub async fn deploy_bundle(
         bundle.policy.rules.len()
     );
 
+    if payload.shadow {
+        return deploy_bundle_shadow(&state, bundle, &payload.bundle);
+    }
+
     // 2. Deploy to PolicyEngine with compiled evaluator using the agent's DataStore
+    let schema = bundle.schema.clone();
     let policy_version = state
         .policy_engine
         .deploy_bundle_with_store(bundle, state.data_store.clone(), payload.force)
@@ -448,6 +474,14 @@ pub async fn deploy_bundle(
             )
         })?;
 
+    // A schema-bearing bundle type-checked against its schema on deploy;
+    // data loads from here on must conform to it as well, and `rebac::check`
+    // expands its relation rewrites.
+    if let Some(schema) = schema {
+        install_relation_schema(&state, Some(&schema));
+        *state.data_sync.schema.write() = Some(Arc::new(schema));
+    }
+
     // 3. Update metrics
     let engine_stats = state.policy_engine.get_stats();
     ACTIVE_POLICIES.set(engine_stats.total_policies as f64);
@@ -480,6 +514,68 @@ pub async fn deploy_bundle(
     Ok(Json(response))
 }
 
+/// Install a verified, parsed bundle as the shadow of its live policy.
+fn deploy_bundle_shadow(
+    state: &AgentState,
+    bundle: PolicyBundle,
+    bytes: &[u8],
+) -> Result<Json<DeployBundleResponse>, (StatusCode, String)> {
+    require_live_policy(state, &bundle.metadata.policy_name)?;
+    let version = bundle
+        .metadata
+        .policy_version
+        .clone()
+        .unwrap_or_else(|| "unknown".to_string());
+    let policy = bundle
+        .to_enhanced_policy_with_store(state.data_store.clone())
+        .map_err(|e| {
+            ERRORS_TOTAL
+                .with_label_values(&["bundle_compile_failed"])
+                .inc();
+            (
+                StatusCode::BAD_REQUEST,
+                format!("Bundle failed to compile: {}", e),
+            )
+        })?;
+    let policy_id = policy.id.to_string();
+    state.shadow.install(policy, version.clone());
+    info!(
+        "Bundle deployed as shadow: policy={}, version={}",
+        bundle.metadata.policy_name, version
+    );
+
+    Ok(Json(DeployBundleResponse {
+        policy_id,
+        version,
+        deployed_at: chrono::Utc::now().to_rfc3339(),
+        bundle_hash: reaper_core::bundle_signing::sha256(bytes)
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect(),
+    }))
+}
+
+/// A shadow is only ever compared against the live policy it names; one
+/// with no live counterpart would never be evaluated.
+fn require_live_policy(state: &AgentState, policy_name: &str) -> Result<(), (StatusCode, String)> {
+    match state.policy_engine.get_policy_by_name(policy_name) {
+        Some(_) => Ok(()),
+        None => Err((
+            StatusCode::NOT_FOUND,
+            format!("No live policy named '{}' to shadow", policy_name),
+        )),
+    }
+}
+
+/// Install (or, with `None`, remove) the relation rewrites `rebac::check`
+/// expands. Bundles validate their schema when parsed, so this only fails on
+/// a schema that bypassed that; it then keeps the previous rewrites.
+fn install_relation_schema(state: &AgentState, schema: Option<&policy_engine::data::EntitySchema>) {
+    if let Err(e) = state.data_store.relationships().set_schema(schema) {
+        error!("Failed to install relation rewrites: {e}");
+    }
+}
+
 /// Atomically load a set of bundles as the ENTIRE active policy set.
 ///
 /// Unlike `deploy_bundle` (which upserts a single policy and leaves the rest
@@ -542,11 +638,15 @@ pub async fn load_bundles_atomic(
     // Parse + compile every bundle BEFORE swapping anything, so a bad bundle
     // fails the whole load and leaves the current set untouched.
     let mut policies = Vec::with_capacity(payload.bundles.len());
+    let mut schema = None;
     for (i, bytes) in payload.bundles.iter().enumerate() {
         let bundle = PolicyBundle::from_bytes(bytes).map_err(|e| {
             ERRORS_TOTAL.with_label_values(&["invalid_bundle"]).inc();
             (StatusCode::BAD_REQUEST, format!("Bundle {i} invalid: {e}"))
         })?;
+        if let Some(declared) = &bundle.schema {
+            schema = Some(Arc::new(declared.clone()));
+        }
         let policy = bundle
             .to_enhanced_policy_with_store(state.data_store.clone())
             .map_err(|e| {
@@ -577,6 +677,11 @@ pub async fn load_bundles_atomic(
             )
         })?;
 
+    // The set is replaced wholesale, and so is the data schema: the last
+    // schema-bearing bundle in the set, or none.
+    install_relation_schema(&state, schema.as_deref());
+    *state.data_sync.schema.write() = schema;
+
     // The whole set changed — drop any cached decisions.
     if let Some(ref cache) = state.decision_cache {
         cache.invalidate();
@@ -0,0 +1,223 @@
+//! Relationship queries: the ReBAC graph outside a policy.
+//!
+//! `POST /api/v1/relations/lookup-resources` ("which documents can alice
+//! view?"), `/lookup-subjects` ("who can edit doc1?") and `/expand` ("why
+//! can erin view doc1?") answer over the agent's `DataStore` with the
+//! semantics of `rebac::check` — the deployed schema's relation rewrites,
+//! the same per-evaluation traversal budget — so a sharing UI lists exactly
+//! what a policy would grant instead of re-walking the graph itself. Lookups
+//! page by keyset cursor like `/api/v1/filter`.
+
+use axum::{extract::State, http::StatusCode, response::Json};
+use policy_engine::data::{expand, lookup_resources, lookup_subjects, LookupOptions};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tracing::instrument;
+use utoipa::ToSchema;
+
+use crate::observability::ERRORS_TOTAL;
+use crate::state::AgentState;
+
+/// Largest lookup page a single call may ask for.
+const MAX_PAGE: usize = 1000;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct LookupResourcesRequest {
+    /// Entity id of the subject (e.g. a user).
+    pub subject: String,
+    pub relation: String,
+    /// Entity type of the objects to list (the data document's `type`).
+    pub resource_type: String,
+    /// The previous page's `next_cursor`.
+    #[serde(default)]
+    pub cursor: Option<String>,
+    /// Page size (default 100, max 1000).
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// Answer from data at least this fresh (a control-plane revision
+    /// token); 412 `revision_unavailable` if it does not arrive in time.
+    #[serde(default)]
+    pub min_revision: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct LookupSubjectsRequest {
+    /// Entity id of the object (e.g. a document).
+    pub object: String,
+    pub relation: String,
+    /// Only list subjects of this entity type; all types when absent.
+    #[serde(default)]
+    pub subject_type: Option<String>,
+    /// The previous page's `next_cursor`.
+    #[serde(default)]
+    pub cursor: Option<String>,
+    /// Page size (default 100, max 1000).
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// Answer from data at least this fresh (a control-plane revision
+    /// token); 412 `revision_unavailable` if it does not arrive in time.
+    #[serde(default)]
+    pub min_revision: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ExpandRequest {
+    pub object: String,
+    pub relation: String,
+    /// Prune the tree to the branches naming this subject and report
+    /// whether it holds the relation.
+    #[serde(default)]
+    pub subject: Option<String>,
+    /// Answer from data at least this fresh (a control-plane revision
+    /// token); 412 `revision_unavailable` if it does not arrive in time.
+    #[serde(default)]
+    pub min_revision: Option<i64>,
+}
+
+type RelationsResult = Result<Json<Value>, (StatusCode, Json<Value>)>;
+
+fn reject(status: StatusCode, code: &str, message: impl Into<String>) -> (StatusCode, Json<Value>) {
+    ERRORS_TOTAL.with_label_values(&[code]).inc();
+    (
+        status,
+        Json(json!({ "error": code, "message": message.into() })),
+    )
+}
+
+fn options(cursor: Option<String>, limit: Option<usize>) -> LookupOptions {
+    LookupOptions {
+        after: cursor,
+        limit: limit.unwrap_or(100).clamp(1, MAX_PAGE),
+    }
+}
+
+/// Run a query off the reactor behind the same fail-closed data gates as the
+/// point path: a stale replica must not list grants it would not decide, and
+/// one behind the caller's `min_revision` must not list grants the caller
+/// just revoked.
+async fn answer(
+    state: Arc<AgentState>,
+    min_revision: Option<i64>,
+    query: impl FnOnce(&policy_engine::data::DataStore) -> Value + Send + 'static,
+) -> RelationsResult {
+    if let Some(reason) = state.data_sync.deny_reason() {
+        return Err(reject(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "data_stale",
+            format!("data plane gate tripped: {reason}"),
+        ));
+    }
+    state.data_sync.require_revision(min_revision).await?;
+    let start = std::time::Instant::now();
+    let mut body = tokio::task::spawn_blocking(move || query(&state.data_store))
+        .await
+        .map_err(|e| {
+            reject(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "internal_error",
+                format!("relations task failed: {e}"),
+            )
+        })?;
+    if let Value::Object(ref mut map) = body {
+        map.insert(
+            "query_time_us".to_string(),
+            json!(start.elapsed().as_micros() as u64),
+        );
+    }
+    Ok(Json(body))
+}
+
+/// POST /api/v1/relations/lookup-resources
+#[utoipa::path(
+    post,
+    path = "/api/v1/relations/lookup-resources",
+    tag = "relations",
+    request_body = LookupResourcesRequest,
+    responses(
+        (status = 200, description = "One page of the objects the subject holds the relation on"),
+        (status = 412, description = "`min_revision` not applied in time"),
+        (status = 503, description = "Data plane gate tripped")
+    ),
+    security(("bearer_jwt" = []))
+)]
+#[instrument(skip(state, payload))]
+pub async fn lookup_relation_resources(
+    State(state): State<Arc<AgentState>>,
+    Json(payload): Json<LookupResourcesRequest>,
+) -> RelationsResult {
+    let options = options(payload.cursor, payload.limit);
+    answer(state, payload.min_revision, move |store| {
+        let page = lookup_resources(
+            store,
+            &payload.subject,
+            &payload.relation,
+            &payload.resource_type,
+            &options,
+        );
+        serde_json::to_value(page).unwrap_or_default()
+    })
+    .await
+}
+
+/// POST /api/v1/relations/lookup-subjects
+#[utoipa::path(
+    post,
+    path = "/api/v1/relations/lookup-subjects",
+    tag = "relations",
+    request_body = LookupSubjectsRequest,
+    responses(
+        (status = 200, description = "One page of the subjects holding the relation on the object"),
+        (status = 412, description = "`min_revision` not applied in time"),
+        (status = 503, description = "Data plane gate tripped")
+    ),
+    security(("bearer_jwt" = []))
+)]
+#[instrument(skip(state, payload))]
+pub async fn lookup_relation_subjects(
+    State(state): State<Arc<AgentState>>,
+    Json(payload): Json<LookupSubjectsRequest>,
+) -> RelationsResult {
+    let options = options(payload.cursor, payload.limit);
+    answer(state, payload.min_revision, move |store| {
+        let page = lookup_subjects(
+            store,
+            &payload.object,
+            &payload.relation,
+            payload.subject_type.as_deref(),
+            &options,
+        );
+        serde_json::to_value(page).unwrap_or_default()
+    })
+    .await
+}
+
+/// POST /api/v1/relations/expand
+#[utoipa::path(
+    post,
+    path = "/api/v1/relations/expand",
+    tag = "relations",
+    request_body = ExpandRequest,
+    responses(
+        (status = 200, description = "The relation's definition tree, optionally pruned to one subject"),
+        (status = 412, description = "`min_revision` not applied in time"),
+        (status = 503, description = "Data plane gate tripped")
+    ),
+    security(("bearer_jwt" = []))
+)]
+#[instrument(skip(state, payload))]
+pub async fn expand_relation(
+    State(state): State<Arc<AgentState>>,
+    Json(payload): Json<ExpandRequest>,
+) -> RelationsResult {
+    answer(state, payload.min_revision, move |store| {
+        let tree = expand(
+            store,
+            &payload.object,
+            &payload.relation,
+            payload.subject.as_deref(),
+        );
+        serde_json::to_value(tree).unwrap_or_default()
+    })
+    .await
+}
@@ -0,0 +1,71 @@
+//! Shadow-policy management handlers.
+//!
+//! Candidates are installed through the deploy endpoints (`shadow: true`);
+//! these list them with their divergence counters and withdraw them:
+//! - `list_shadows` - Deployed shadows and their counters
+//! - `remove_shadow` - Stop shadowing a policy
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tracing::{info, instrument};
+
+use crate::state::AgentState;
+
+/// List shadow-deployed policy candidates.
+#[utoipa::path(
+    get,
+    path = "/api/v1/shadow",
+    tag = "policies",
+    responses(
+        (status = 200, description = "Shadow candidates with evaluation and divergence counts")
+    ),
+    security(("bearer_jwt" = []))
+)]
+#[instrument(skip(state))]
+pub async fn list_shadows(State(state): State<Arc<AgentState>>) -> Json<Value> {
+    let shadows = state.shadow.list();
+    Json(json!({
+        "count": shadows.len(),
+        "shadows": shadows,
+    }))
+}
+
+/// Remove the shadow candidate for a live policy.
+///
+/// The live policy is untouched; the response carries the candidate's final
+/// counters.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/shadow/{policy_name}",
+    tag = "policies",
+    params(("policy_name" = String, Path, description = "Live policy the candidate shadows")),
+    responses(
+        (status = 200, description = "Shadow removed"),
+        (status = 404, description = "No shadow deployed for this policy")
+    ),
+    security(("bearer_jwt" = []))
+)]
+#[instrument(skip(state))]
+pub async fn remove_shadow(
+    State(state): State<Arc<AgentState>>,
+    Path(policy_name): Path<String>,
+) -> Result<Json<Value>, (StatusCode, String)> {
+    match state.shadow.remove(&policy_name) {
+        Some(summary) => {
+            info!("Shadow for policy {} removed", policy_name);
+            Ok(Json(json!({
+                "status": "removed",
+                "shadow": summary,
+            })))
+        }
+        None => Err((
+            StatusCode::NOT_FOUND,
+            format!("No shadow deployed for policy '{}'", policy_name),
+        )),
+    }
+}
@@ -21,12 +21,14 @@ pub mod cache;
 pub mod capability_cache;
 pub mod capability_gate;
 pub mod decision_stream;
+pub mod ext_authz;
 pub mod handlers;
 pub mod http;
 pub mod management;
 pub mod metrics_cache;
 pub mod observability;
 pub mod panic_guard;
+pub mod shadow;
 pub mod state;
 pub mod tls;
 pub mod types;
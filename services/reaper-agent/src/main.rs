@@ -5,12 +5,14 @@ mod cache;
 mod capability_cache;
 mod capability_gate;
 mod decision_stream;
+mod ext_authz;
 mod handlers;
 mod http;
 mod management;
 mod metrics_cache;
 mod observability;
 mod panic_guard;
+mod shadow;
 mod state;
 mod tls;
 mod types;
@@ -59,10 +61,12 @@ use handlers::{
     deploy_compiled_policy,
     deploy_data_version,
     deploy_policy,
-    evaluate_policy,
+    evaluate_messages,
+    expand_relation,
     // Decision handlers
     export_decisions,
     fast_evaluate_policy,
+    filter_resources,
     get_decision_by_id,
     get_decision_stats,
     get_decisions,
@@ -73,12 +77,16 @@ use handlers::{
     health_check,
     list_entities_handler,
     list_policies,
+    list_shadows,
     liveness_check,
     load_bundles_atomic,
     load_data_handler,
     load_data_stream_handler,
+    lookup_relation_resources,
+    lookup_relation_subjects,
     metrics,
     readiness_check,
+    remove_shadow,
     sync_data,
     upsert_entity_handler,
 };
@@ -647,10 +655,13 @@ async fn run(
         decision_metrics: Arc::new(metrics_cache::DecisionMetrics::new()),
         data_sync: data_sync.clone(),
         bundle_verifier: bundle_verifier.clone(),
+        shadow: Arc::new(shadow::ShadowPolicies::new()),
         capability_gate: Arc::new(capability_cache::CapabilityGateRuntime::from_auth(
             &config.auth,
         )),
     });
+    // The ext_authz gRPC listener shares the agent state with the HTTP router.
+    let ext_authz_state = config.ext_authz.enabled.then(|| state.clone());
 
     // Evaluation endpoints accept authorization *requests*, not entity
     // datasets, so they get a far tighter body limit than the 256 MB bulk-data
@@ -662,12 +673,24 @@ async fn run(
     const EVAL_BODY_LIMIT: usize = 16 * 1024 * 1024; // 16 MB
     let eval_routes = Router::new()
         // Policy evaluation - the core agent functionality
-        .route(endpoints::API_V1_MESSAGES, post(evaluate_policy))
+        .route(endpoints::API_V1_MESSAGES, post(evaluate_messages))
         // Fast path with SIMD JSON parsing (3-5x faster parsing)
         .route("/api/v1/fast-messages", post(fast_evaluate_policy))
         // Batch evaluation endpoint (bounded + offloaded)
         .route("/api/v1/batch-messages", post(batch_evaluate_policy))
         .route("/api/v1/check", post(check_document))
+        // List authorization: permitted ids / SQL filter for a resource type
+        .route("/api/v1/filter", post(filter_resources))
+        // ReBAC queries: lookup-resources / lookup-subjects / expand
+        .route(
+            "/api/v1/relations/lookup-resources",
+            post(lookup_relation_resources),
+        )
+        .route(
+            "/api/v1/relations/lookup-subjects",
+            post(lookup_relation_subjects),
+        )
+        .route("/api/v1/relations/expand", post(expand_relation))
         // Kubernetes admission webhook target (AdmissionReview v1 in/out)
         .route("/api/v1/admission/{policy}", post(admission_review))
         .route_layer(axum::extract::DefaultBodyLimit::max(EVAL_BODY_LIMIT));
@@ -702,6 +725,12 @@ async fn run(
         // Bundle deployment (hot-reload with versioning)
         .route("/api/v1/bundles/deploy", post(deploy_bundle))
         .route("/api/v1/bundles/load", post(load_bundles_atomic))
+        // Shadow candidates (installed via the deploy endpoints, shadow=true)
+        .route("/api/v1/shadow", get(list_shadows))
+        .route(
+            "/api/v1/shadow/{policy_name}",
+            axum::routing::delete(remove_shadow),
+        )
         // Entity CRUD operations (requires eBPF integration)
         .route("/api/v1/entities", post(upsert_entity_handler))
         .route("/api/v1/entities/{type}/{id}", get(get_entity_handler))
@@ -772,6 +801,12 @@ async fn run(
     info!("  GET  /api/v1/policies        - List active policies");
     info!("  GET  /metrics                 - Prometheus metrics");
     info!("  GET  /health                  - Health check");
+    if config.ext_authz.enabled {
+        info!(
+            "  gRPC {}:{}           - Envoy ext_authz (Authorization/Check)",
+            config.ext_authz.bind_address, config.ext_authz.port
+        );
+    }
     info!("");
     info!("📊 Observability:");
     info!("  Logs: Structured JSON (Loki-compatible)");
@@ -785,6 +820,11 @@ async fn run(
         uds::spawn_uds_listeners(&config.uds, uds_app);
     }
 
+    // Envoy ext_authz (envoy.service.auth.v3.Authorization/Check) if enabled.
+    if let Some(state) = ext_authz_state {
+        ext_authz::spawn_ext_authz_server(&config.ext_authz, state)?;
+    }
+
     // Run server with TLS if configured
     let result = if config.tls.enabled {
         info!("🔒 TLS enabled - secure mode");
@@ -18,8 +18,8 @@ use opentelemetry_sdk::{
 };
 use opentelemetry_semantic_conventions as semconv;
 use prometheus::{
-    register_counter_vec, register_gauge, register_histogram_vec, CounterVec, Encoder, Gauge,
-    HistogramVec, TextEncoder,
+    register_counter, register_counter_vec, register_gauge, register_histogram_vec, Counter,
+    CounterVec, Encoder, Gauge, HistogramVec, TextEncoder,
 };
 use reaper_core::VERSION;
 use tracing::info;
@@ -149,6 +149,32 @@ lazy_static! {
     )
     .expect("Failed to register ERRORS_TOTAL metric");
 
+    /// Live requests re-evaluated against a shadow candidate, by the live
+    /// policy name the candidate shadows (bounded by deployed shadows).
+    pub static ref SHADOW_EVALUATIONS: CounterVec = register_counter_vec!(
+        "reaper_shadow_evaluations_total",
+        "Live requests evaluated against a shadow policy candidate",
+        &["policy_name"]
+    )
+    .expect("Failed to register SHADOW_EVALUATIONS metric");
+
+    /// Live/shadow disagreements: `direction` is `allow_to_deny` (the
+    /// candidate would deny a request served as allow) or `deny_to_allow`.
+    pub static ref SHADOW_DIVERGENCES: CounterVec = register_counter_vec!(
+        "reaper_shadow_divergences_total",
+        "Shadow policy decisions that differ from the served decision",
+        &["policy_name", "direction"]
+    )
+    .expect("Failed to register SHADOW_DIVERGENCES metric");
+
+    /// Shadow observations skipped because the in-flight cap was reached:
+    /// shadow work is best-effort and never queues behind live traffic.
+    pub static ref SHADOW_DROPPED: Counter = register_counter!(
+        "reaper_shadow_dropped_total",
+        "Shadow policy evaluations dropped at the in-flight cap"
+    )
+    .expect("Failed to register SHADOW_DROPPED metric");
+
     /// Current number of concurrent evaluations.
     pub static ref CONCURRENT_EVALUATIONS: Gauge = register_gauge!(
         "reaper_concurrent_evaluations",
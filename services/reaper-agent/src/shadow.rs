@@ -0,0 +1,376 @@
+//! Shadow-policy evaluation against live traffic.
+//!
+//! A shadow is a candidate version of a LIVE policy, deployed by name
+//! (`shadow: true` on `/api/v1/policies/compile` or `/api/v1/bundles/deploy`).
+//! It never serves: the response to `/api/v1/messages` is always the live
+//! decision. After the response is built, the handler hands the resolved
+//! request to [`spawn`], which re-evaluates the same policy set on a blocking
+//! task with every shadowed policy swapped for its candidate
+//! ([`PolicyEngine::evaluate_set_with`] — identical combination semantics),
+//! and compares the two outcomes:
+//!
+//! - every shadow evaluation bumps `reaper_shadow_evaluations_total`;
+//! - an allow→deny or deny→allow flip bumps
+//!   `reaper_shadow_divergences_total{direction}` and writes a divergence
+//!   record (live outcome + [`ShadowOutcome`]) to the decision buffer.
+//!
+//! That answers "what would promoting this change do to real requests" with
+//! zero user-visible effect — unlike the canary strategy, which serves the
+//! candidate, and the management replay engine, which only sees history.
+//!
+//! Cost when nothing is shadowed: one relaxed atomic load per request.
+//! When shadows are armed, at most [`MAX_IN_FLIGHT`] observations run at
+//! once; past that an observation is dropped (counted in
+//! `reaper_shadow_dropped_total`) rather than queued, so a slow candidate
+//! cannot compete with live traffic for workers.
+//!
+//! [`PolicyEngine::evaluate_set_with`]: policy_engine::PolicyEngine::evaluate_set_with
+
+use dashmap::DashMap;
+use policy_engine::{
+    DecisionLogEntry, EnhancedPolicy, PolicyAction, PolicyEngine, PolicyRequest, SetEvalOutcome,
+    ShadowOutcome,
+};
+use serde::Serialize;
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use uuid::Uuid;
+
+use crate::observability::{ERRORS_TOTAL, SHADOW_DIVERGENCES, SHADOW_DROPPED, SHADOW_EVALUATIONS};
+use crate::state::AgentState;
+
+/// Shadow observations allowed in flight at once.
+pub const MAX_IN_FLIGHT: usize = 64;
+
+/// One shadowed policy: the candidate plus its divergence counters.
+pub struct ShadowSlot {
+    policy: Arc<EnhancedPolicy>,
+    /// Candidate version as deployed (bundle version, or the policy version).
+    version: String,
+    deployed_at: String,
+    evaluations: AtomicU64,
+    allow_to_deny: AtomicU64,
+    deny_to_allow: AtomicU64,
+}
+
+/// Point-in-time view of a [`ShadowSlot`] for `GET /api/v1/shadow`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ShadowSummary {
+    /// Name of the live policy this candidate shadows
+    pub policy_name: String,
+    /// Candidate version
+    pub version: String,
+    /// RFC 3339 time the candidate was deployed
+    pub deployed_at: String,
+    /// Live requests the candidate was evaluated against
+    pub evaluations: u64,
+    /// Requests the live set allowed and the candidate would deny
+    pub allow_to_deny: u64,
+    /// Requests the live set denied and the candidate would allow
+    pub deny_to_allow: u64,
+}
+
+/// Direction of a live/shadow disagreement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Divergence {
+    AllowToDeny,
+    DenyToAllow,
+}
+
+impl Divergence {
+    /// Classify a (live, shadow) pair. Only allow vs not-allow matters: a set
+    /// outcome is never `Log`, and a non-allow is a deny to the caller.
+    pub fn of(live: &PolicyAction, shadow: &PolicyAction) -> Option<Self> {
+        match (live == &PolicyAction::Allow, shadow == &PolicyAction::Allow) {
+            (true, false) => Some(Self::AllowToDeny),
+            (false, true) => Some(Self::DenyToAllow),
+            _ => None,
+        }
+    }
+
+    /// Prometheus `direction` label value.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::AllowToDeny => "allow_to_deny",
+            Self::DenyToAllow => "deny_to_allow",
+        }
+    }
+}
+
+/// The live side of a comparison, as served.
+pub struct LiveDecision {
+    pub decision_id: String,
+    pub decision: PolicyAction,
+    pub policy_id: String,
+    pub policy_name: String,
+    pub policy_version: u64,
+    pub matched_rule: String,
+}
+
+/// Registry of shadow candidates, keyed by the live policy name they shadow.
+pub struct ShadowPolicies {
+    by_name: DashMap<String, Arc<ShadowSlot>>,
+    /// Fast hot-path gate: true while at least one shadow is deployed.
+    armed: AtomicBool,
+    /// Caps concurrent observations; see [`spawn`].
+    in_flight: Arc<Semaphore>,
+}
+
+impl Default for ShadowPolicies {
+    fn default() -> Self {
+        Self::with_capacity(MAX_IN_FLIGHT)
+    }
+}
+
+impl ShadowPolicies {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry allowing `max_in_flight` concurrent observations.
+    pub fn with_capacity(max_in_flight: usize) -> Self {
+        Self {
+            by_name: DashMap::new(),
+            armed: AtomicBool::new(false),
+            in_flight: Arc::new(Semaphore::new(max_in_flight)),
+        }
+    }
+
+    /// Install (or replace) the shadow for `policy.name`. Replacing resets
+    /// the counters: they describe one candidate.
+    pub fn install(&self, policy: EnhancedPolicy, version: String) {
+        let slot = ShadowSlot {
+            version,
+            deployed_at: chrono::Utc::now().to_rfc3339(),
+            policy: Arc::new(policy),
+            evaluations: AtomicU64::new(0),
+            allow_to_deny: AtomicU64::new(0),
+            deny_to_allow: AtomicU64::new(0),
+        };
+        self.by_name
+            .insert(slot.policy.name.clone(), Arc::new(slot));
+        self.armed.store(true, Ordering::Release);
+    }
+
+    /// Drop the shadow for `policy_name`; returns its final counters.
+    pub fn remove(&self, policy_name: &str) -> Option<ShadowSummary> {
+        let removed = self
+            .by_name
+            .remove(policy_name)
+            .map(|(_, slot)| summary(&slot));
+        self.armed
+            .store(!self.by_name.is_empty(), Ordering::Release);
+        removed
+    }
+
+    /// True while any shadow is deployed.
+    #[inline]
+    pub fn is_armed(&self) -> bool {
+        self.armed.load(Ordering::Relaxed)
+    }
+
+    /// All deployed shadows, sorted by policy name.
+    pub fn list(&self) -> Vec<ShadowSummary> {
+        let mut out: Vec<ShadowSummary> = self
+            .by_name
+            .iter()
+            .map(|slot| summary(slot.value()))
+            .collect();
+        out.sort_by(|a, b| a.policy_name.cmp(&b.policy_name));
+        out
+    }
+
+    /// Evaluate `policy_ids` with every shadowed policy swapped for its
+    /// candidate. `None` when no policy in the set is shadowed.
+    pub fn evaluate(
+        &self,
+        engine: &PolicyEngine,
+        policy_ids: &[Uuid],
+        request: &PolicyRequest,
+    ) -> Option<(SetEvalOutcome, Vec<Arc<ShadowSlot>>)> {
+        let used: RefCell<Vec<Arc<ShadowSlot>>> = RefCell::new(Vec::new());
+        let outcome = engine.evaluate_set_with(policy_ids, request, |live| {
+            let slot = self.by_name.get(&live.name)?.value().clone();
+            let candidate = slot.policy.clone();
+            used.borrow_mut().push(slot);
+            Some(candidate)
+        });
+        let used = used.into_inner();
+        (!used.is_empty()).then_some((outcome, used))
+    }
+}
+
+fn summary(slot: &ShadowSlot) -> ShadowSummary {
+    ShadowSummary {
+        policy_name: slot.policy.name.clone(),
+        version: slot.version.clone(),
+        deployed_at: slot.deployed_at.clone(),
+        evaluations: slot.evaluations.load(Ordering::Relaxed),
+        allow_to_deny: slot.allow_to_deny.load(Ordering::Relaxed),
+        deny_to_allow: slot.deny_to_allow.load(Ordering::Relaxed),
+    }
+}
+
+/// Evaluate the shadow side of a served request on a blocking task (the
+/// evaluation is synchronous CPU work). No-op (one atomic load) when nothing
+/// is shadowed; dropped and counted when [`MAX_IN_FLIGHT`] observations are
+/// already running.
+pub fn spawn(
+    state: &Arc<AgentState>,
+    policy_ids: &[Uuid],
+    request: &PolicyRequest,
+    live: impl FnOnce() -> LiveDecision,
+) {
+    if !state.shadow.is_armed() {
+        return;
+    }
+    let Ok(permit) = state.shadow.in_flight.clone().try_acquire_owned() else {
+        SHADOW_DROPPED.inc();
+        return;
+    };
+    let state = state.clone();
+    let policy_ids = policy_ids.to_vec();
+    let request = request.clone();
+    let live = live();
+    tokio::task::spawn_blocking(move || {
+        let _permit = permit;
+        observe(&state, &policy_ids, &request, &live)
+    });
+}
+
+/// Evaluate the shadow side of `request` and record the comparison. Returns
+/// the divergence, if any (`None` also when the set has no shadow).
+pub fn observe(
+    state: &AgentState,
+    policy_ids: &[Uuid],
+    request: &PolicyRequest,
+    live: &LiveDecision,
+) -> Option<Divergence> {
+    let (outcome, slots) = state
+        .shadow
+        .evaluate(&state.policy_engine, policy_ids, request)?;
+    if let Some(ref e) = outcome.error {
+        // Fail closed exactly like the live path: the shadow decision is the
+        // deny the candidate would have served.
+        tracing::warn!(error = %e, "shadow evaluation error");
+        ERRORS_TOTAL
+            .with_label_values(&["shadow_evaluation_error"])
+            .inc();
+    }
+
+    let divergence = Divergence::of(&live.decision, &outcome.decision);
+    for slot in &slots {
+        slot.evaluations.fetch_add(1, Ordering::Relaxed);
+        SHADOW_EVALUATIONS
+            .with_label_values(&[&slot.policy.name])
+            .inc();
+        if let Some(d) = divergence {
+            let counter = match d {
+                Divergence::AllowToDeny => &slot.allow_to_deny,
+                Divergence::DenyToAllow => &slot.deny_to_allow,
+            };
+            counter.fetch_add(1, Ordering::Relaxed);
+            SHADOW_DIVERGENCES
+                .with_label_values(&[&slot.policy.name, d.as_str()])
+                .inc();
+        }
+    }
+
+    if let (Some(_), Some(buffer)) = (divergence, state.decision_buffer.as_ref()) {
+        buffer.log(divergence_entry(state, request, live, &outcome, &slots));
+    }
+    divergence
+}
+
+/// The divergence record: the live decision in the usual columns, the
+/// candidate's in `shadow`, under the served decision id.
+fn divergence_entry(
+    state: &AgentState,
+    request: &PolicyRequest,
+    live: &LiveDecision,
+    outcome: &SetEvalOutcome,
+    slots: &[Arc<ShadowSlot>],
+) -> DecisionLogEntry {
+    // The deciding shadow policy's deployed version when it was a candidate;
+    // otherwise the (unshadowed) live policy's own version.
+    let policy_version = slots
+        .iter()
+        .find(|slot| slot.policy.name == outcome.policy_name)
+        .map(|slot| slot.version.clone())
+        .or_else(|| (outcome.policy_version > 0).then(|| outcome.policy_version.to_string()));
+    let matched_rule = match outcome.error {
+        Some(ref e) => Some(format!("evaluation_error: {e}")),
+        None => outcome.matched_rule_name.clone(),
+    };
+    let shadow = ShadowOutcome {
+        decision: action_str(&outcome.decision).to_string(),
+        policy_name: outcome.policy_name.clone(),
+        policy_version,
+        matched_rule,
+    };
+
+    let mut entry = DecisionLogEntry::new(
+        request
+            .context_str("principal")
+            .unwrap_or_default()
+            .to_string(),
+        request.action.clone(),
+        request.resource.clone(),
+        action_str(&live.decision).to_string(),
+        live.policy_id.clone(),
+        live.policy_name.clone(),
+    )
+    .with_context(
+        request
+            .context
+            .iter()
+            .filter(|(k, _)| k.as_str() != "principal")
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect(),
+    )
+    .with_agent_id(state.agent_id.clone())
+    .with_policy_version(live.policy_version.to_string())
+    .with_matched_rule(live.matched_rule.clone())
+    .with_shadow(shadow);
+    entry.decision_id = live.decision_id.clone();
+    entry
+}
+
+fn action_str(action: &PolicyAction) -> &'static str {
+    match action {
+        PolicyAction::Allow => "allow",
+        PolicyAction::Deny => "deny",
+        PolicyAction::Log => "log",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn divergence_direction() {
+        use PolicyAction::{Allow, Deny, Log};
+        assert_eq!(Divergence::of(&Allow, &Deny), Some(Divergence::AllowToDeny));
+        assert_eq!(Divergence::of(&Deny, &Allow), Some(Divergence::DenyToAllow));
+        assert_eq!(Divergence::of(&Allow, &Allow), None);
+        assert_eq!(Divergence::of(&Deny, &Deny), None);
+        assert_eq!(Divergence::of(&Log, &Deny), None);
+    }
+
+    #[test]
+    fn arming_follows_the_registry() {
+        let shadows = ShadowPolicies::new();
+        assert!(!shadows.is_armed());
+        let policy = EnhancedPolicy::new("p".to_string(), String::new(), vec![]);
+        shadows.install(policy, "2".to_string());
+        assert!(shadows.is_armed());
+        assert_eq!(shadows.list()[0].version, "2");
+        assert!(shadows.remove("missing").is_none());
+        assert!(shadows.is_armed());
+        assert_eq!(shadows.remove("p").map(|s| s.evaluations), Some(0));
+        assert!(!shadows.is_armed());
+    }
+}
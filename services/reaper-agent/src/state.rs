@@ -4,16 +4,61 @@
 
 use parking_lot::Mutex;
 use policy_engine::{
-    cache_config::CacheConfig, decision_cache::DecisionCache, PolicyEngine, SharedDecisionBuffer,
+    cache_config::CacheConfig, data::EntitySchema, decision_cache::DecisionCache, DataLoader,
+    DataStore, PolicyEngine, SharedDecisionBuffer,
 };
 use reaper_core::config::ReaperAgentConfig;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
 
 use crate::cache::PolicyCache;
 use parking_lot::RwLock;
 use std::sync::atomic::AtomicI64;
 
+/// A request's `min_revision` did not arrive within the wait budget. Every
+/// data-plane endpoint (evaluate, fast, batch, check, filter, relations)
+/// answers it the same way: 412 with
+/// `{"error": "revision_unavailable", "message", "min_revision",
+/// "applied_revision"}`. Nothing is decided, and a retry can succeed once the
+/// replica catches up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RevisionUnavailable {
+    pub min_revision: i64,
+    /// The revision this replica had applied when the wait ran out.
+    pub applied_revision: i64,
+}
+
+impl RevisionUnavailable {
+    pub fn body(&self) -> serde_json::Value {
+        serde_json::json!({
+            "error": "revision_unavailable",
+            "message": format!(
+                "revision {} not applied yet (at {})",
+                self.min_revision, self.applied_revision
+            ),
+            "min_revision": self.min_revision,
+            "applied_revision": self.applied_revision,
+        })
+    }
+}
+
+impl axum::response::IntoResponse for RevisionUnavailable {
+    fn into_response(self) -> axum::response::Response {
+        <(axum::http::StatusCode, axum::Json<serde_json::Value>)>::from(self).into_response()
+    }
+}
+
+impl From<RevisionUnavailable> for (axum::http::StatusCode, axum::Json<serde_json::Value>) {
+    fn from(e: RevisionUnavailable) -> Self {
+        (
+            axum::http::StatusCode::PRECONDITION_FAILED,
+            axum::Json(e.body()),
+        )
+    }
+}
+
 /// Shared agent state accessible by all request handlers.
 ///
 /// This struct is wrapped in `Arc` and extracted via Axum's `State` extractor
@@ -49,6 +94,8 @@ pub struct AgentState {
     pub bundle_verifier: Arc<crate::management::verify::BundleVerifier>,
     /// Capability verdict cache + verify rate limiter (Plan 06 Phase D).
     pub capability_gate: Arc<crate::capability_cache::CapabilityGateRuntime>,
+    /// Shadow-deployed policy candidates evaluated against live traffic.
+    pub shadow: Arc<crate::shadow::ShadowPolicies>,
 }
 
 /// What to do when the data-plane staleness budget is exceeded.
@@ -104,6 +151,12 @@ pub struct DataSyncState {
     /// exactly here or the agent 409s with this value so the sync client
     /// self-corrects (pull-based gap repair).
     pub applied_seq: AtomicI64,
+    /// Woken whenever `applied_seq` advances, so requests that demand a
+    /// revision (`min_revision`) park instead of polling.
+    pub revision_advanced: Notify,
+    /// How long such a request waits for a revision this replica has not
+    /// applied yet before failing closed (REAPER_DATA_REVISION_WAIT_MS).
+    pub revision_wait: Duration,
     /// Staleness budget in seconds; 0 = no budget configured.
     pub max_staleness_secs: u64,
     /// Behavior when the budget is exceeded.
@@ -113,6 +166,10 @@ pub struct DataSyncState {
     /// pod out of rotation) and evaluation fails closed. Off by default —
     /// standalone / bootstrap-file agents have no data plane to wait for.
     pub require_sync: bool,
+    /// Entity schema carried by the most recently deployed schema-bearing
+    /// bundle. When set, every data load and delta is validated against it
+    /// before anything reaches the DataStore.
+    pub schema: RwLock<Option<Arc<EntitySchema>>>,
 }
 
 impl DataSyncState {
@@ -123,6 +180,13 @@ impl DataSyncState {
             checksum: RwLock::new(String::new()),
             last_synced_epoch: AtomicU64::new(0),
             applied_seq: AtomicI64::new(0),
+            revision_advanced: Notify::new(),
+            revision_wait: Duration::from_millis(
+                std::env::var("REAPER_DATA_REVISION_WAIT_MS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(500),
+            ),
             max_staleness_secs: std::env::var("REAPER_DATA_MAX_STALENESS_SECS")
                 .ok()
                 .and_then(|v| v.parse().ok())
@@ -131,6 +195,16 @@ impl DataSyncState {
             require_sync: std::env::var("REAPER_DATA_REQUIRE_SYNC")
                 .map(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "yes" | "on"))
                 .unwrap_or(false),
+            schema: RwLock::new(None),
+        }
+    }
+
+    /// A loader over `store` that enforces the current entity schema, if any.
+    pub fn loader(&self, store: &DataStore) -> DataLoader {
+        let loader = DataLoader::new(store.clone());
+        match self.schema.read().clone() {
+            Some(schema) => loader.with_schema(schema),
+            None => loader,
         }
     }
 
@@ -151,6 +225,64 @@ impl DataSyncState {
             .store(Self::now_epoch(), Ordering::Release);
     }
 
+    /// Move the change-stream position (snapshot deploy or delta batch) and
+    /// wake every request waiting on a revision.
+    pub fn advance_to(&self, seq: i64) {
+        self.applied_seq.store(seq, Ordering::Release);
+        self.revision_advanced.notify_waiters();
+    }
+
+    /// Wait until this replica has applied revision `min_revision` — the
+    /// read-your-writes half of the revision token a control-plane write
+    /// returns. Bounded by `revision_wait`; `Err` carries the revision
+    /// actually applied so the caller can fail closed and say how far
+    /// behind it is. Already-fresh replicas return without parking.
+    pub async fn await_revision(&self, min_revision: i64) -> Result<(), i64> {
+        let deadline = tokio::time::Instant::now() + self.revision_wait;
+        loop {
+            // Register BEFORE reading the position: an advance between the
+            // load and the await would otherwise be a lost wakeup.
+            let notified = self.revision_advanced.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            let applied = self.applied_seq.load(Ordering::Acquire);
+            if applied >= min_revision {
+                return Ok(());
+            }
+            if tokio::time::timeout_at(deadline, notified).await.is_err() {
+                let applied = self.applied_seq.load(Ordering::Acquire);
+                return if applied >= min_revision {
+                    Ok(())
+                } else {
+                    Err(applied)
+                };
+            }
+        }
+    }
+
+    /// [`Self::await_revision`] for a request's optional `min_revision`,
+    /// counted under `ERRORS_TOTAL{reason="revision_unavailable"}` when the
+    /// revision does not arrive.
+    pub async fn require_revision(
+        &self,
+        min_revision: Option<i64>,
+    ) -> Result<(), RevisionUnavailable> {
+        let Some(min_revision) = min_revision else {
+            return Ok(());
+        };
+        self.await_revision(min_revision)
+            .await
+            .map_err(|applied_revision| {
+                crate::observability::ERRORS_TOTAL
+                    .with_label_values(&["revision_unavailable"])
+                    .inc();
+                RevisionUnavailable {
+                    min_revision,
+                    applied_revision,
+                }
+            })
+    }
+
     /// Model-shape version of the synced store (0 = unknown).
     #[inline]
     pub fn model_provenance(&self) -> i64 {
@@ -29,8 +29,9 @@ pub struct EvaluateRequest {
     pub resource: String,
     /// Action being performed
     pub action: String,
-    /// Additional context for evaluation (optional)
-    pub context: Option<HashMap<String, String>>,
+    /// Additional context for evaluation (optional). Values are any JSON —
+    /// numbers, bools, arrays and objects compare natively in policies.
+    pub context: Option<HashMap<String, serde_json::Value>>,
     /// Optional non-human actor acting on behalf of the principal (F1
     /// agentic authz). Flows into the engine request's `actor` binding.
     #[serde(default)]
@@ -45,6 +46,12 @@ pub struct EvaluateRequest {
     /// and denies on any failure.
     #[serde(default)]
     pub capability: Option<reaper_core::capability::Capability>,
+    /// Revision token from a control-plane write or publish: evaluate
+    /// against data at least this fresh. The agent waits briefly for the
+    /// revision to arrive and otherwise answers 412 `revision_unavailable`
+    /// without deciding.
+    #[serde(default)]
+    pub min_revision: Option<i64>,
 }
 
 /// Response from policy evaluation.
@@ -69,6 +76,10 @@ pub struct BatchEvaluateRequest {
     /// Policy to evaluate
     pub policy_id: Option<String>,
     pub policy_name: Option<String>,
+    /// Data freshness the whole batch requires — see
+    /// [`EvaluateRequest::min_revision`].
+    #[serde(default)]
+    pub min_revision: Option<i64>,
     /// List of evaluation requests
     pub requests: Vec<BatchRequestItem>,
 }
@@ -81,7 +92,7 @@ pub struct BatchRequestItem {
     pub principal: String,
     pub resource: String,
     pub action: String,
-    pub context: Option<HashMap<String, String>>,
+    pub context: Option<HashMap<String, serde_json::Value>>,
     /// Optional actor (F1 agentic authz) — see [`EvaluateRequest::actor`].
     #[serde(default)]
     pub actor: Option<String>,
@@ -158,6 +169,10 @@ pub struct DeployBundleRequest {
     /// management-connected with `require_signed_bundles=true`).
     #[serde(default)]
     pub signature: Option<reaper_core::bundle_signing::BundleSignature>,
+    /// Deploy as a shadow of the live policy of the same name rather than
+    /// replacing it (see `crate::shadow`). Signature checks apply unchanged.
+    #[serde(default)]
+    pub shadow: bool,
 }
 
 /// Atomic full-replace bundle-load request.
@@ -328,7 +343,7 @@ pub struct PackageEvaluateRequest {
     pub principal: String,
     pub resource: String,
     pub action: String,
-    pub context: Option<HashMap<String, String>>,
+    pub context: Option<HashMap<String, serde_json::Value>>,
 }
 
 // ============================================================================
@@ -387,14 +402,18 @@ mod tests {
             "action": "write",
             "context": {
                 "department": "engineering",
-                "level": "senior"
+                "level": "senior",
+                "amount": 1500,
+                "tags": ["urgent"]
             }
         }"#;
 
         let req: EvaluateRequest = serde_json::from_str(json).unwrap();
         assert!(req.policy_id.is_some());
         let ctx = req.context.unwrap();
-        assert_eq!(ctx.get("department"), Some(&"engineering".to_string()));
+        assert_eq!(ctx.get("department"), Some(&"engineering".into()));
+        assert_eq!(ctx.get("amount"), Some(&serde_json::json!(1500)));
+        assert_eq!(ctx.get("tags"), Some(&serde_json::json!(["urgent"])));
     }
 
     #[test]
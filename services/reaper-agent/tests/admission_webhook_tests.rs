@@ -72,6 +72,7 @@ fn state_with_k8s_policy() -> Arc<AgentState> {
         decision_metrics: Arc::new(reaper_agent::metrics_cache::DecisionMetrics::new()),
         data_sync: Arc::new(DataSyncState::from_env()),
         bundle_verifier: Arc::new(BundleVerifier::from_config(&ManagementSettings::default())),
+        shadow: Default::default(),
         capability_gate: Arc::new(
             reaper_agent::capability_cache::CapabilityGateRuntime::from_auth(
                 &reaper_core::config::AgentAuthSettings::default(),
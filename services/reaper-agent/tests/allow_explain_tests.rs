@@ -83,6 +83,7 @@ fn state_with_buffer(buffer: Option<SharedDecisionBuffer>) -> Arc<AgentState> {
         decision_metrics: Arc::new(reaper_agent::metrics_cache::DecisionMetrics::new()),
         data_sync: Arc::new(DataSyncState::from_env()),
         bundle_verifier: Arc::new(BundleVerifier::from_config(&ManagementSettings::default())),
+        shadow: Default::default(),
         capability_gate: std::sync::Arc::new(
             reaper_agent::capability_cache::CapabilityGateRuntime::from_auth(
                 &reaper_core::config::AgentAuthSettings::default(),
@@ -115,6 +116,7 @@ fn req(principal: &str, actor: Option<&str>, action: &str) -> EvaluateRequest {
         actor: actor.map(str::to_string),
         context_provenance: None,
         capability: None,
+        min_revision: None,
     }
 }
 
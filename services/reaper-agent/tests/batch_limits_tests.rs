@@ -39,6 +39,7 @@ fn state_with_max_batch(max_batch_requests: usize) -> Arc<AgentState> {
         decision_metrics: Arc::new(reaper_agent::metrics_cache::DecisionMetrics::new()),
         data_sync: Arc::new(DataSyncState::from_env()),
         bundle_verifier: Arc::new(BundleVerifier::from_config(&ManagementSettings::default())),
+        shadow: Default::default(),
         capability_gate: std::sync::Arc::new(
             reaper_agent::capability_cache::CapabilityGateRuntime::from_auth(
                 &reaper_core::config::AgentAuthSettings::default(),
@@ -51,6 +52,7 @@ fn batch_of(n: usize) -> BatchEvaluateRequest {
     BatchEvaluateRequest {
         policy_id: None,
         policy_name: None,
+        min_revision: None,
         requests: (0..n)
             .map(|i| BatchRequestItem {
                 id: format!("r{i}"),
@@ -72,7 +74,7 @@ async fn batch_over_cap_is_rejected_with_413() {
     // cap + 1 requests → rejected before evaluation, regardless of policy state.
     let result = batch_evaluate_policy(State(state), Json(batch_of(3))).await;
     match result {
-        Err(status) => assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE),
+        Err(response) => assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE),
         Ok(_) => panic!("expected 413 for an over-cap batch"),
     }
 }
@@ -128,6 +130,7 @@ async fn parallel_batch_preserves_order_and_decisions() {
     let req = BatchEvaluateRequest {
         policy_id: None,
         policy_name: Some("batch-par".to_string()),
+        min_revision: None,
         requests,
     };
 
@@ -178,6 +181,7 @@ async fn single_evals_complete_while_large_batch_runs() {
                         actor: None,
                         context_provenance: None,
                         capability: None,
+                        min_revision: None,
                     }),
                 )
                 .await
@@ -87,6 +87,7 @@ fn state_with_auth(
         decision_metrics: Arc::new(reaper_agent::metrics_cache::DecisionMetrics::new()),
         data_sync: Arc::new(DataSyncState::from_env()),
         bundle_verifier: Arc::new(BundleVerifier::from_config(&mgmt)),
+        shadow: Default::default(),
         capability_gate: std::sync::Arc::new(
             reaper_agent::capability_cache::CapabilityGateRuntime::from_auth(&agent_config.auth),
         ),
@@ -125,6 +126,7 @@ fn req(
         actor: actor.map(str::to_string),
         context_provenance: None,
         capability,
+        min_revision: None,
     }
 }
 
@@ -429,6 +431,7 @@ async fn batch_enforces_capabilities_per_item() {
         Json(BatchEvaluateRequest {
             policy_id: None,
             policy_name: Some("cap-test".to_string()),
+            min_revision: None,
             requests: vec![
                 item("ok", "read", Some(c.clone())),
                 item("bad", "write", Some(c)),
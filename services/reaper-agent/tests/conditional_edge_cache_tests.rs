@@ -0,0 +1,156 @@
+//! Decision cache vs. conditional relationship edges.
+//!
+//! Pins: an edge that expires on the clock changes no data and bumps no
+//! cache generation, so the served routes must not answer from the decision
+//! cache while the graph holds one — an allow that rested on the grant turns
+//! into a deny the moment it lapses, on the single and the batch route.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use axum::{
+    body::Body,
+    extract::{Json, State},
+    http::{Request, StatusCode},
+    routing::post,
+    Router,
+};
+use policy_engine::{cache_config::CacheConfig, decision_cache::DecisionCache, PolicyEngine};
+use reaper_agent::handlers::policies::DeployCompiledPolicyRequest;
+use reaper_agent::handlers::{batch_evaluate_policy, deploy_compiled_policy, evaluate_messages};
+use reaper_agent::management::verify::BundleVerifier;
+use reaper_agent::state::{AgentState, AgentStats, DataSyncState};
+use reaper_core::config::{ManagementSettings, ReaperAgentConfig};
+use serde_json::{json, Value};
+use tower::ServiceExt; // for `oneshot`
+
+const POLICY: &str = r#"
+policy shared_docs {
+    default: deny,
+    rule viewers_read {
+        allow if action == "read" && rebac::related(user, "viewer", resource)
+    }
+}
+"#;
+
+/// A store where bob views doc-1 until `expires_at` (unix seconds).
+async fn state(expires_at: i64) -> Arc<AgentState> {
+    let expiry = chrono::DateTime::from_timestamp(expires_at, 0)
+        .unwrap()
+        .to_rfc3339();
+    let s = Arc::new(policy_engine::DataStore::new());
+    policy_engine::DataLoader::new((*s).clone())
+        .load_json(
+            &json!({"entities": [
+                {"id": "bob", "type": "user", "attributes": {}},
+                {"id": "doc-1", "type": "document", "attributes": {}, "relationships": {
+                    "viewer": [{"subject": "bob", "expires_at": expiry}]
+                }}
+            ]})
+            .to_string(),
+        )
+        .unwrap();
+
+    let state = Arc::new(AgentState {
+        policy_engine: PolicyEngine::new(),
+        data_store: s,
+        stats: Arc::new(AgentStats::new(false)),
+        decision_cache: Some(Arc::new(DecisionCache::new(64))),
+        cache_config: CacheConfig::default(),
+        agent_config: ReaperAgentConfig::default(),
+        policy_cache: None,
+        decision_buffer: None,
+        agent_id: "test-agent".to_string(),
+        decision_metrics: Arc::new(reaper_agent::metrics_cache::DecisionMetrics::new()),
+        data_sync: Arc::new(DataSyncState::from_env()),
+        bundle_verifier: Arc::new(BundleVerifier::from_config(&ManagementSettings::default())),
+        shadow: Default::default(),
+        capability_gate: std::sync::Arc::new(
+            reaper_agent::capability_cache::CapabilityGateRuntime::from_auth(
+                &reaper_core::config::AgentAuthSettings::default(),
+            ),
+        ),
+    });
+    let _ = deploy_compiled_policy(
+        State(state.clone()),
+        Json(DeployCompiledPolicyRequest {
+            policy_content: POLICY.to_string(),
+            policy_name: "shared_docs".to_string(),
+            shadow: false,
+        }),
+    )
+    .await
+    .expect("deploy");
+    state
+}
+
+fn app(state: Arc<AgentState>) -> Router {
+    Router::new()
+        .route("/api/v1/messages", post(evaluate_messages))
+        .route("/api/v1/batch-messages", post(batch_evaluate_policy))
+        .with_state(state)
+}
+
+async fn post_json(app: &Router, uri: &str, body: Value) -> Value {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+fn single() -> Value {
+    json!({"policy_name": "shared_docs", "principal": "bob", "resource": "doc-1",
+           "action": "read"})
+}
+
+fn batch() -> Value {
+    json!({"policy_name": "shared_docs", "requests": [
+        {"id": "r1", "principal": "bob", "resource": "doc-1", "action": "read"}
+    ]})
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+#[tokio::test]
+async fn a_cached_allow_does_not_outlive_an_expiring_grant() {
+    let expires_at = unix_now() + 2;
+    let app = app(state(expires_at).await);
+
+    for _ in 0..2 {
+        let body = post_json(&app, "/api/v1/messages", single()).await;
+        assert_eq!(body["decision"], "allow", "{body}");
+        assert_eq!(body["cache_hit"], false, "{body}");
+        let body = post_json(&app, "/api/v1/batch-messages", batch()).await;
+        assert_eq!(body["results"][0]["decision"], "allow", "{body}");
+        assert_eq!(body["results"][0]["cache_hit"], false, "{body}");
+    }
+
+    while unix_now() < expires_at {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    let body = post_json(&app, "/api/v1/messages", single()).await;
+    assert_eq!(body["decision"], "deny", "{body}");
+    let body = post_json(&app, "/api/v1/batch-messages", batch()).await;
+    assert_eq!(body["results"][0]["decision"], "deny", "{body}");
+}
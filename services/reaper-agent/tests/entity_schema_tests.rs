@@ -0,0 +1,155 @@
+//! Entity schemas on the agent: a schema-bearing bundle is type-checked on
+//! deploy, and from then on data loads that do not conform are rejected
+//! before anything reaches the DataStore.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::sync::Arc;
+
+use axum::extract::{Json, State};
+use axum::http::StatusCode;
+use policy_engine::data::EntitySchema;
+use policy_engine::reap::{PolicyBundle, ReaperPolicy};
+use policy_engine::{cache_config::CacheConfig, PolicyEngine};
+use reaper_agent::handlers::data::LoadDataRequest;
+use reaper_agent::handlers::{deploy_bundle, load_bundles_atomic, load_data_handler};
+use reaper_agent::management::verify::BundleVerifier;
+use reaper_agent::state::{AgentState, AgentStats, DataSyncState};
+use reaper_agent::types::{DeployBundleRequest, LoadBundlesRequest};
+use reaper_core::config::{ManagementSettings, ReaperAgentConfig};
+use serde_json::json;
+
+const SCHEMA: &str = r#"{
+    "entity_types": {
+        "User": {"attributes": {"department": {"type": "string"}}},
+        "Document": {}
+    },
+    "request": {"user": ["User"], "resource": ["Document"]}
+}"#;
+
+const POLICY: &str = r#"policy typed {
+    default: deny,
+    rule engineers { allow if user.department == "eng" }
+}"#;
+
+fn state() -> Arc<AgentState> {
+    Arc::new(AgentState {
+        policy_engine: PolicyEngine::new(),
+        data_store: Arc::new(policy_engine::DataStore::new()),
+        stats: Arc::new(AgentStats::new(false)),
+        decision_cache: None,
+        cache_config: CacheConfig::default(),
+        agent_config: ReaperAgentConfig::default(),
+        policy_cache: None,
+        decision_buffer: None,
+        agent_id: "test-agent".to_string(),
+        decision_metrics: Arc::new(reaper_agent::metrics_cache::DecisionMetrics::new()),
+        data_sync: Arc::new(DataSyncState::from_env()),
+        bundle_verifier: Arc::new(BundleVerifier::from_config(&ManagementSettings::default())),
+        shadow: Default::default(),
+        capability_gate: Arc::new(
+            reaper_agent::capability_cache::CapabilityGateRuntime::from_auth(
+                &reaper_core::config::AgentAuthSettings::default(),
+            ),
+        ),
+    })
+}
+
+fn schema_bundle(source: &str) -> Vec<u8> {
+    let policy: ReaperPolicy = source.parse().unwrap();
+    policy
+        .compile_to_bundle_with_schema(&EntitySchema::from_json(SCHEMA).unwrap())
+        .unwrap()
+}
+
+async fn deploy(state: &Arc<AgentState>, bundle: Vec<u8>) -> Result<(), (StatusCode, String)> {
+    deploy_bundle(
+        State(state.clone()),
+        Json(DeployBundleRequest {
+            bundle,
+            version: "1".to_string(),
+            force: true,
+            signature: None,
+            shadow: false,
+        }),
+    )
+    .await
+    .map(|_| ())
+}
+
+async fn load(state: &Arc<AgentState>, department: serde_json::Value) -> Result<(), StatusCode> {
+    let data = json!({"entities": [
+        {"id": "alice", "type": "User", "attributes": {"department": department}}
+    ]});
+    load_data_handler(
+        State(state.clone()),
+        Json(LoadDataRequest {
+            data: data.to_string(),
+        }),
+    )
+    .await
+    .map(|_| ())
+    .map_err(|(status, _)| status)
+}
+
+#[tokio::test]
+async fn deployed_schema_gates_data_loads() {
+    let state = state();
+
+    // No schema yet: anything loads.
+    load(&state, json!(42)).await.unwrap();
+    state.data_store.clear();
+
+    deploy(&state, schema_bundle(POLICY)).await.unwrap();
+    assert!(state.data_sync.schema.read().is_some());
+
+    assert_eq!(
+        load(&state, json!(42)).await.unwrap_err(),
+        StatusCode::BAD_REQUEST
+    );
+    assert!(state.data_store.entity_attributes_json("alice").is_none());
+    load(&state, json!("eng")).await.unwrap();
+}
+
+#[tokio::test]
+async fn ill_typed_schema_bundles_are_refused() {
+    let state = state();
+    let good = PolicyBundle::from_bytes(&schema_bundle(POLICY)).unwrap();
+    let typo: ReaperPolicy = POLICY.replace("department", "departmnet").parse().unwrap();
+    let smuggled = PolicyBundle {
+        policy: PolicyBundle::from_bytes(&typo.compile_to_bundle().unwrap())
+            .unwrap()
+            .policy,
+        ..good
+    };
+
+    let (status, message) = deploy(&state, smuggled.to_bytes().unwrap())
+        .await
+        .unwrap_err();
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(
+        message.contains("unknown attribute `user.departmnet`"),
+        "{message}"
+    );
+    assert!(state.data_sync.schema.read().is_none());
+}
+
+#[tokio::test]
+async fn atomic_loads_replace_the_schema() {
+    let state = state();
+    deploy(&state, schema_bundle(POLICY)).await.unwrap();
+
+    let plain: ReaperPolicy = POLICY.parse().unwrap();
+    let Json(loaded) = load_bundles_atomic(
+        State(state.clone()),
+        Json(LoadBundlesRequest {
+            bundles: vec![plain.compile_to_bundle().unwrap()],
+            signatures: None,
+        }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(loaded.active_policies, 1);
+    assert!(state.data_sync.schema.read().is_none());
+    load(&state, json!(42)).await.unwrap();
+}
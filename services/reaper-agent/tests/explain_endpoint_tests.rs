@@ -0,0 +1,195 @@
+//! `POST /api/v1/messages?explain=full` on the agent.
+//!
+//! Pins: the trace rides alongside the decision it explains (one entry per
+//! evaluated policy), the request is refused unless
+//! `performance.allow_explain` is armed, unknown modes are a 400, and an
+//! explained request is evaluated even when the decision is cached.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Json, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use policy_engine::{
+    cache_config::CacheConfig, decision_cache::DecisionCache, EnhancedPolicy, PolicyEngine,
+    PolicyLanguage,
+};
+use reaper_agent::handlers::evaluate::EvaluateQuery;
+use reaper_agent::handlers::evaluate_messages;
+use reaper_agent::management::verify::BundleVerifier;
+use reaper_agent::state::{AgentState, AgentStats, DataSyncState};
+use reaper_agent::types::EvaluateRequest;
+use reaper_core::config::{ManagementSettings, ReaperAgentConfig};
+use serde_json::{json, Value};
+
+const POLICY: &str = r#"
+policy docs {
+    default: deny,
+    rule engineers {
+        allow if user.department == "eng" && user.level >= 3
+    }
+}
+"#;
+
+fn state(allow_explain: bool, cache: Option<Arc<DecisionCache>>) -> Arc<AgentState> {
+    let s = Arc::new(policy_engine::DataStore::new());
+    policy_engine::DataLoader::new((*s).clone())
+        .load_json(
+            &json!({"entities": [
+                {"id": "alice", "type": "user", "attributes": {"department": "eng", "level": 5}},
+                {"id": "bob", "type": "user", "attributes": {"department": "sales", "level": 5}},
+                {"id": "doc1", "type": "resource", "attributes": {}}
+            ]})
+            .to_string(),
+        )
+        .unwrap();
+
+    let engine = PolicyEngine::new();
+    let mut p = EnhancedPolicy::new_with_language(
+        "docs".to_string(),
+        String::new(),
+        PolicyLanguage::ReaperDsl,
+        POLICY.to_string(),
+    )
+    .unwrap();
+    p.build_evaluator_with_data(Some(s.clone())).unwrap();
+    engine.deploy_policy(p).unwrap();
+
+    let mut agent_config = ReaperAgentConfig::default();
+    agent_config.performance.allow_explain = allow_explain;
+
+    Arc::new(AgentState {
+        policy_engine: engine,
+        data_store: s,
+        stats: Arc::new(AgentStats::new(false)),
+        decision_cache: cache,
+        cache_config: CacheConfig::default(),
+        agent_config,
+        policy_cache: None,
+        decision_buffer: None,
+        agent_id: "test-agent".to_string(),
+        decision_metrics: Arc::new(reaper_agent::metrics_cache::DecisionMetrics::new()),
+        data_sync: Arc::new(DataSyncState::from_env()),
+        bundle_verifier: Arc::new(BundleVerifier::from_config(&ManagementSettings::default())),
+        shadow: Default::default(),
+        capability_gate: Arc::new(
+            reaper_agent::capability_cache::CapabilityGateRuntime::from_auth(
+                &reaper_core::config::AgentAuthSettings::default(),
+            ),
+        ),
+    })
+}
+
+fn req(principal: &str) -> EvaluateRequest {
+    EvaluateRequest {
+        policy_id: None,
+        policy_name: Some("docs".to_string()),
+        principal: principal.to_string(),
+        resource: "doc1".to_string(),
+        action: "read".to_string(),
+        context: None,
+        actor: None,
+        context_provenance: None,
+        capability: None,
+        min_revision: None,
+    }
+}
+
+async fn decide(
+    state: &Arc<AgentState>,
+    principal: &str,
+    explain: Option<&str>,
+) -> Result<Value, StatusCode> {
+    let query = EvaluateQuery {
+        explain: explain.map(str::to_string),
+    };
+    let resp = evaluate_messages(State(state.clone()), Query(query), Json(req(principal)))
+        .await?
+        .into_response();
+    let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    Ok(serde_json::from_slice(&bytes).unwrap())
+}
+
+#[tokio::test]
+async fn explain_full_returns_the_trace_with_the_decision() {
+    let state = state(true, None);
+
+    let body = decide(&state, "bob", Some("full")).await.unwrap();
+    assert_eq!(body["decision"], "deny");
+    let explanation = body["explanation"].as_array().expect("explanation");
+    assert_eq!(explanation.len(), 1);
+    let trace = &explanation[0];
+    // A default deny is attributed to no policy; the trace still names the
+    // one that was evaluated.
+    assert_eq!(body["policy_id"], "00000000-0000-0000-0000-000000000000");
+    assert_ne!(trace["policy_id"], body["policy_id"]);
+    assert_eq!(trace["policy"], "docs");
+    assert_eq!(trace["evaluator"], "reaper_dsl");
+    assert_eq!(trace["decision"], "deny");
+    assert_eq!(trace["decided_by"], Value::Null);
+
+    let condition = &trace["rules"][0]["condition"];
+    assert_eq!(condition["kind"], "all");
+    assert_eq!(condition["skipped"], 1);
+    assert_eq!(
+        condition["operands"][0],
+        json!({
+            "kind": "comparison",
+            "source": "user.department == \"eng\"",
+            "left": "sales",
+            "op": "==",
+            "right": "eng",
+            "result": false
+        })
+    );
+
+    let body = decide(&state, "alice", Some("full")).await.unwrap();
+    assert_eq!(body["decision"], "allow");
+    assert_eq!(body["explanation"][0]["decided_by"], "engineers");
+
+    // Without the parameter the response is unchanged.
+    let body = decide(&state, "alice", None).await.unwrap();
+    assert!(body.get("explanation").is_none(), "body: {body}");
+}
+
+#[tokio::test]
+async fn explain_must_be_enabled() {
+    let state = state(false, None);
+    assert_eq!(
+        decide(&state, "alice", Some("full")).await.unwrap_err(),
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        decide(&state, "alice", None).await.unwrap()["decision"],
+        "allow"
+    );
+
+    let state = self::state(true, None);
+    assert_eq!(
+        decide(&state, "alice", Some("summary")).await.unwrap_err(),
+        StatusCode::BAD_REQUEST
+    );
+}
+
+#[tokio::test]
+async fn explained_requests_bypass_the_decision_cache() {
+    let state = state(true, Some(Arc::new(DecisionCache::new(64))));
+    assert_eq!(
+        decide(&state, "alice", None).await.unwrap()["cache_hit"],
+        false
+    );
+    assert_eq!(
+        decide(&state, "alice", None).await.unwrap()["cache_hit"],
+        true
+    );
+
+    let body = decide(&state, "alice", Some("full")).await.unwrap();
+    assert_eq!(body["cache_hit"], false);
+    assert_eq!(body["explanation"][0]["decided_by"], "engineers");
+}
@@ -0,0 +1,509 @@
+//! Envoy ext_authz (`envoy.service.auth.v3.Authorization/Check`).
+//!
+//! Pins: CheckRequest attributes reach the policy as principal / action /
+//! resource / typed context; each principal source (JWT payload, mTLS
+//! peer, trusted header, anonymous) resolves in order, and the header never
+//! overrides a verified identity; route context
+//! extensions pick the policy or package; obligations become header
+//! mutations on allow and shape the denial; credentials stay out of the
+//! decision log; and the service answers real gRPC frames.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::sync::Arc;
+
+use base64::Engine as _;
+use http_body_util::BodyExt;
+use policy_engine::{
+    cache_config::CacheConfig, DecisionFilter, DecisionLogConfig, EnhancedPolicy, PolicyEngine,
+    PolicyLanguage, SharedDecisionBuffer,
+};
+use prost::Message;
+use reaper_agent::ext_authz::proto::{
+    Address, AttributeContext, AuthorizationServer, CheckRequest, CheckResponse,
+    DeniedHttpResponse, HttpRequest, HttpResponse, OkHttpResponse, Peer, Request, SocketAddress,
+    CHECK_PATH,
+};
+use reaper_agent::ext_authz::{check, ExtAuthzService};
+use reaper_agent::management::verify::BundleVerifier;
+use reaper_agent::state::{AgentState, AgentStats, DataSyncState};
+use reaper_core::config::{ExtAuthzSettings, ManagementSettings, ReaperAgentConfig};
+use serde_json::{json, Value};
+use tonic::codegen::http;
+use tower::ServiceExt;
+
+const GATEWAY: &str = r#"
+policy gateway {
+    default: deny,
+    rule readonly_tenant {
+        allow
+            with obligations {
+                "headers": {"x-tenant-verified": "acme"},
+                "remove_headers": ["x-debug"],
+                "response_headers": {"cache-control": "no-store"}
+            }
+        if action == "get" && context.http.headers["x-tenant"] == "acme"
+    }
+    rule admins_write {
+        allow if user.role == "admin" && action == "post"
+    }
+    rule locked {
+        deny
+            with obligations {"status": 451, "body": "unavailable for legal reasons"}
+        if resource == "/legal-hold"
+    }
+}
+"#;
+
+const PUBLIC: &str = r#"
+policy public_site {
+    default: deny,
+    rule anyone_reads {
+        allow if action == "get"
+    }
+}
+"#;
+
+fn deploy(engine: &PolicyEngine, store: &Arc<policy_engine::DataStore>, name: &str, src: &str) {
+    let mut p = EnhancedPolicy::new_with_language(
+        name.to_string(),
+        String::new(),
+        PolicyLanguage::ReaperDsl,
+        src.to_string(),
+    )
+    .unwrap();
+    p.build_evaluator_with_data(Some(store.clone())).unwrap();
+    engine.deploy_policy(p).unwrap();
+}
+
+fn state(settings: ExtAuthzSettings, buffer: Option<SharedDecisionBuffer>) -> Arc<AgentState> {
+    let s = Arc::new(policy_engine::DataStore::new());
+    policy_engine::DataLoader::new((*s).clone())
+        .load_json(
+            &json!({"entities": [
+                {"id": "alice", "type": "user", "attributes": {"role": "admin"}},
+                {"id": "bob", "type": "user", "attributes": {"role": "viewer"}},
+                {"id": "spiffe://mesh/ns/web/sa/frontend", "type": "user",
+                 "attributes": {"role": "admin"}},
+                {"id": "anonymous", "type": "user", "attributes": {"role": "none"}}
+            ]})
+            .to_string(),
+        )
+        .unwrap();
+
+    let engine = PolicyEngine::new();
+    deploy(&engine, &s, "gateway", GATEWAY);
+    deploy(&engine, &s, "public_site", PUBLIC);
+
+    let agent_config = ReaperAgentConfig {
+        ext_authz: settings,
+        ..Default::default()
+    };
+    Arc::new(AgentState {
+        policy_engine: engine,
+        data_store: s,
+        stats: Arc::new(AgentStats::new(false)),
+        decision_cache: None,
+        cache_config: CacheConfig::default(),
+        agent_config,
+        policy_cache: None,
+        decision_buffer: buffer,
+        agent_id: "test-agent".to_string(),
+        decision_metrics: Arc::new(reaper_agent::metrics_cache::DecisionMetrics::new()),
+        data_sync: Arc::new(DataSyncState::from_env()),
+        bundle_verifier: Arc::new(BundleVerifier::from_config(&ManagementSettings::default())),
+        shadow: Default::default(),
+        capability_gate: std::sync::Arc::new(
+            reaper_agent::capability_cache::CapabilityGateRuntime::from_auth(
+                &reaper_core::config::AgentAuthSettings::default(),
+            ),
+        ),
+    })
+}
+
+fn settings() -> ExtAuthzSettings {
+    ExtAuthzSettings {
+        policy: Some("gateway".to_string()),
+        principal_header: Some("x-user".to_string()),
+        ..Default::default()
+    }
+}
+
+fn check_request(method: &str, path: &str, headers: &[(&str, &str)]) -> CheckRequest {
+    CheckRequest {
+        attributes: Some(AttributeContext {
+            source: Some(Peer {
+                address: Some(Address {
+                    socket_address: Some(SocketAddress {
+                        address: "10.0.0.7".to_string(),
+                        port_value: 51234,
+                    }),
+                }),
+                ..Default::default()
+            }),
+            request: Some(Request {
+                http: Some(HttpRequest {
+                    id: "req-1".to_string(),
+                    method: method.to_string(),
+                    headers: headers
+                        .iter()
+                        .map(|(k, v)| (k.to_string(), v.to_string()))
+                        .collect(),
+                    path: path.to_string(),
+                    host: "api.example.com".to_string(),
+                    scheme: "https".to_string(),
+                    ..Default::default()
+                }),
+            }),
+            ..Default::default()
+        }),
+    }
+}
+
+fn ok(resp: &CheckResponse) -> &OkHttpResponse {
+    match &resp.http_response {
+        Some(HttpResponse::OkResponse(ok)) => ok,
+        other => panic!("expected ok response, got {other:?}"),
+    }
+}
+
+fn denial(resp: &CheckResponse) -> &DeniedHttpResponse {
+    match &resp.http_response {
+        Some(HttpResponse::DeniedResponse(d)) => d,
+        other => panic!("expected denied response, got {other:?}"),
+    }
+}
+
+fn header<'a>(
+    headers: &'a [reaper_agent::ext_authz::proto::HeaderValueOption],
+    name: &str,
+) -> Option<&'a str> {
+    headers
+        .iter()
+        .filter_map(|h| h.header.as_ref())
+        .find(|h| h.key == name)
+        .map(|h| h.value.as_str())
+}
+
+#[tokio::test]
+async fn allow_applies_header_obligations() {
+    let state = state(settings(), None);
+    let resp = check(
+        &state,
+        check_request(
+            "GET",
+            "/reports?page=2",
+            &[("x-user", "bob"), ("x-tenant", "acme"), ("x-debug", "1")],
+        ),
+    )
+    .await;
+
+    assert_eq!(resp.status.as_ref().unwrap().code, 0);
+    let ok = ok(&resp);
+    assert_eq!(header(&ok.headers, "x-tenant-verified"), Some("acme"));
+    assert!(header(&ok.headers, "x-reaper-decision-id").is_some());
+    assert_eq!(ok.headers_to_remove, ["x-debug"]);
+    assert_eq!(
+        header(&ok.response_headers_to_add, "cache-control"),
+        Some("no-store")
+    );
+
+    let metadata = resp.dynamic_metadata.unwrap();
+    assert!(metadata.fields.contains_key("obligations"));
+    assert!(metadata.fields.contains_key("decision_id"));
+}
+
+#[tokio::test]
+async fn deny_uses_default_status_and_json_body() {
+    let state = state(settings(), None);
+    // Wrong tenant: nothing matches, the policy default denies.
+    let resp = check(
+        &state,
+        check_request(
+            "GET",
+            "/reports",
+            &[("x-user", "bob"), ("x-tenant", "other")],
+        ),
+    )
+    .await;
+
+    assert_eq!(resp.status.as_ref().unwrap().code, 7);
+    let denied = denial(&resp);
+    assert_eq!(denied.status.as_ref().unwrap().code, 403);
+    assert_eq!(
+        header(&denied.headers, "content-type"),
+        Some("application/json")
+    );
+    let body: Value = serde_json::from_str(&denied.body).unwrap();
+    assert_eq!(body["decision"], "deny");
+    assert_eq!(body["reason"], "default_deny");
+    assert_eq!(
+        body["decision_id"],
+        header(&denied.headers, "x-reaper-decision-id").unwrap()
+    );
+}
+
+#[tokio::test]
+async fn deny_obligations_shape_the_denial() {
+    let state = state(settings(), None);
+    let resp = check(
+        &state,
+        check_request("POST", "/legal-hold", &[("x-user", "alice")]),
+    )
+    .await;
+
+    let denied = denial(&resp);
+    assert_eq!(denied.status.as_ref().unwrap().code, 451);
+    assert_eq!(denied.body, "unavailable for legal reasons");
+    assert_eq!(header(&denied.headers, "content-type"), Some("text/plain"));
+}
+
+#[tokio::test]
+async fn principal_sources_resolve_in_order() {
+    let jwt_settings = ExtAuthzSettings {
+        jwt_payload_header: Some("x-jwt-payload".to_string()),
+        ..settings()
+    };
+    let state = state(jwt_settings, None);
+
+    // Trusted header.
+    let resp = check(
+        &state,
+        check_request("POST", "/reports", &[("x-user", "alice")]),
+    )
+    .await;
+    assert_eq!(resp.status.unwrap().code, 0, "header principal is an admin");
+
+    // JWT payload `sub`.
+    let payload = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .encode(json!({"sub": "alice", "scope": "write"}).to_string());
+    let resp = check(
+        &state,
+        check_request("POST", "/reports", &[("x-jwt-payload", &payload)]),
+    )
+    .await;
+    assert_eq!(resp.status.unwrap().code, 0, "jwt sub is an admin");
+
+    // mTLS peer principal.
+    let mut req = check_request("POST", "/reports", &[]);
+    req.attributes
+        .as_mut()
+        .unwrap()
+        .source
+        .as_mut()
+        .unwrap()
+        .principal = "spiffe://mesh/ns/web/sa/frontend".to_string();
+    let resp = check(&state, req).await;
+    assert_eq!(resp.status.unwrap().code, 0, "mTLS principal is an admin");
+
+    // No identity, no anonymous principal: denied before evaluation.
+    let resp = check(&state, check_request("GET", "/reports", &[])).await;
+    let body: Value = serde_json::from_str(&denial(&resp).body).unwrap();
+    assert_eq!(body["reason"], "unauthenticated");
+}
+
+#[tokio::test]
+async fn verified_identities_win_over_the_principal_header() {
+    let jwt_settings = ExtAuthzSettings {
+        jwt_payload_header: Some("x-jwt-payload".to_string()),
+        ..settings()
+    };
+    let state = state(jwt_settings, None);
+
+    // bob (a viewer) names himself in the header: his own identity applies.
+    let resp = check(
+        &state,
+        check_request("POST", "/reports", &[("x-user", "bob")]),
+    )
+    .await;
+    assert_eq!(denial(&resp).status.as_ref().unwrap().code, 403);
+
+    // A client-set header cannot replace the verified JWT `sub` ...
+    let payload =
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(json!({"sub": "bob"}).to_string());
+    let resp = check(
+        &state,
+        check_request(
+            "POST",
+            "/reports",
+            &[("x-user", "alice"), ("x-jwt-payload", &payload)],
+        ),
+    )
+    .await;
+    assert_eq!(
+        denial(&resp).status.as_ref().unwrap().code,
+        403,
+        "jwt sub bob wins over header alice"
+    );
+
+    // ... nor the mTLS peer principal.
+    let mut req = check_request("POST", "/reports", &[("x-user", "bob")]);
+    req.attributes
+        .as_mut()
+        .unwrap()
+        .source
+        .as_mut()
+        .unwrap()
+        .principal = "spiffe://mesh/ns/web/sa/frontend".to_string();
+    let resp = check(&state, req).await;
+    assert_eq!(
+        resp.status.unwrap().code,
+        0,
+        "mTLS admin wins over header bob"
+    );
+}
+
+#[tokio::test]
+async fn anonymous_principal_and_route_extensions() {
+    let state = state(
+        ExtAuthzSettings {
+            anonymous_principal: Some("anonymous".to_string()),
+            ..settings()
+        },
+        None,
+    );
+
+    // Default policy (gateway) denies an anonymous read...
+    let resp = check(&state, check_request("GET", "/", &[])).await;
+    assert_eq!(resp.status.unwrap().code, 7);
+
+    // ...but a route pointing at the public policy allows it.
+    let mut req = check_request("GET", "/", &[]);
+    req.attributes
+        .as_mut()
+        .unwrap()
+        .context_extensions
+        .insert("reaper_policy".to_string(), "public_site".to_string());
+    let resp = check(&state, req).await;
+    assert_eq!(resp.status.unwrap().code, 0);
+
+    // A route naming an unknown policy fails closed.
+    let mut req = check_request("GET", "/", &[]);
+    req.attributes
+        .as_mut()
+        .unwrap()
+        .context_extensions
+        .insert("reaper_policy".to_string(), "missing".to_string());
+    let resp = check(&state, req).await;
+    let body: Value = serde_json::from_str(&denial(&resp).body).unwrap();
+    assert_eq!(body["reason"], "policy_not_found");
+}
+
+#[tokio::test]
+async fn no_configured_policy_denies() {
+    let state = state(ExtAuthzSettings::default(), None);
+    let resp = check(&state, check_request("GET", "/", &[("x-user", "bob")])).await;
+    let body: Value = serde_json::from_str(&denial(&resp).body).unwrap();
+    assert_eq!(body["reason"], "ext_authz_no_policy");
+}
+
+#[tokio::test]
+async fn decision_log_records_request_without_credentials() {
+    let config = DecisionLogConfig {
+        enabled: true,
+        privacy_profile: Some(policy_engine::PrivacyProfile::Raw),
+        ..Default::default()
+    };
+    let buffer = policy_engine::create_shared_buffer(config).unwrap();
+    let state = state(settings(), Some(buffer.clone()));
+
+    let resp = check(
+        &state,
+        check_request(
+            "GET",
+            "/reports",
+            &[
+                ("x-user", "bob"),
+                ("x-tenant", "acme"),
+                ("authorization", "Bearer secret"),
+                ("cookie", "session=secret"),
+            ],
+        ),
+    )
+    .await;
+    let decision_id = header(&ok(&resp).headers, "x-reaper-decision-id")
+        .unwrap()
+        .to_string();
+
+    let entries = buffer.query(DecisionFilter::new(), 10);
+    assert_eq!(entries.len(), 1);
+    let entry = &entries[0];
+    assert_eq!(entry.decision_id, decision_id);
+    assert_eq!(entry.principal, "bob");
+    assert_eq!(entry.action, "get");
+    assert_eq!(entry.resource, "/reports");
+    assert_eq!(entry.matched_rule.as_deref(), Some("readonly_tenant"));
+    let headers = &entry.context["http"]["headers"];
+    assert_eq!(headers["x-tenant"], "acme");
+    assert!(headers.get("authorization").is_none());
+    assert!(headers.get("cookie").is_none());
+}
+
+/// gRPC length-prefixed frame around one message.
+fn frame(message: &impl Message) -> Vec<u8> {
+    let bytes = message.encode_to_vec();
+    let mut framed = vec![0u8];
+    framed.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+    framed.extend_from_slice(&bytes);
+    framed
+}
+
+fn grpc_request(path: &str, body: Vec<u8>) -> http::Request<tonic::body::Body> {
+    http::Request::builder()
+        .method("POST")
+        .uri(format!("http://agent{path}"))
+        .header("content-type", "application/grpc")
+        .header("te", "trailers")
+        .body(tonic::body::Body::new(http_body_util::Full::new(
+            bytes::Bytes::from(body),
+        )))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn serves_check_over_grpc_framing() {
+    let server = AuthorizationServer::new(ExtAuthzService::new(state(settings(), None)));
+    let req = check_request(
+        "GET",
+        "/reports",
+        &[("x-user", "bob"), ("x-tenant", "acme")],
+    );
+
+    let resp = server
+        .clone()
+        .oneshot(grpc_request(CHECK_PATH, frame(&req)))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let collected = resp.into_body().collect().await.unwrap();
+    let trailers = collected.trailers().cloned().unwrap_or_default();
+    assert_eq!(
+        trailers.get("grpc-status").map(|v| v.as_bytes()),
+        Some(&b"0"[..])
+    );
+    let bytes = collected.to_bytes();
+    let decoded = CheckResponse::decode(&bytes[5..]).unwrap();
+    assert_eq!(decoded.status.unwrap().code, 0);
+
+    let resp = server
+        .oneshot(grpc_request(
+            "/envoy.service.auth.v3.Authorization/Other",
+            frame(&req),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(
+        resp.headers().get("grpc-status").map(|v| v.as_bytes()),
+        Some(&b"12"[..]),
+        "unknown methods are UNIMPLEMENTED"
+    );
+}
+
+#[test]
+fn ext_authz_settings_default_to_loopback() {
+    let s = ExtAuthzSettings::default();
+    assert!(!s.enabled);
+    assert_eq!(s.bind_address, "127.0.0.1");
+    assert_eq!(s.port, 9191);
+    assert_eq!(s.deny_status, 403);
+}
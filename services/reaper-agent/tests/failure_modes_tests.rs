@@ -8,6 +8,8 @@
 //! all cross-referenced from `docs/deployment/OPERATIONS_GUIDE.md`.
 
 use std::sync::atomic::{AtomicI64, AtomicU64};
+use std::sync::Arc;
+use std::time::Duration;
 
 use parking_lot::RwLock;
 use reaper_agent::state::{DataSyncState, StalenessMode};
@@ -19,9 +21,12 @@ fn sync_state(mode: StalenessMode, require_sync: bool, max_staleness_secs: u64)
         checksum: RwLock::new(String::new()),
         last_synced_epoch: AtomicU64::new(0),
         applied_seq: AtomicI64::new(0),
+        revision_advanced: tokio::sync::Notify::new(),
+        revision_wait: Duration::from_millis(200),
         max_staleness_secs,
         mode,
         require_sync,
+        schema: RwLock::new(None),
     }
 }
 
@@ -60,3 +65,29 @@ fn data_gate_open_when_unarmed() {
     let state = sync_state(StalenessMode::Monitor, false, 0);
     assert_eq!(state.deny_reason(), None);
 }
+
+/// Row: a request demands a revision (`min_revision`) the replica has not
+/// applied yet, and the delta lands within the wait ⇒ serves normally.
+#[tokio::test]
+async fn revision_gate_waits_for_the_delta() {
+    let state = Arc::new(sync_state(StalenessMode::Monitor, false, 0));
+    state.advance_to(3);
+    assert_eq!(state.await_revision(3).await, Ok(()), "already applied");
+
+    let syncer = Arc::clone(&state);
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        syncer.advance_to(5);
+    });
+    assert_eq!(state.await_revision(5).await, Ok(()));
+}
+
+/// Row: the demanded revision does not arrive within the wait ⇒ fail closed,
+/// reporting how far the replica got (the new-enemy problem must never be
+/// answered from data that predates the caller's write).
+#[tokio::test]
+async fn revision_gate_fails_closed_when_the_delta_is_late() {
+    let state = sync_state(StalenessMode::Monitor, false, 0);
+    state.advance_to(3);
+    assert_eq!(state.await_revision(4).await, Err(3));
+}
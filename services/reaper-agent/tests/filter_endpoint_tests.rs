@@ -0,0 +1,190 @@
+//! `POST /api/v1/filter` on the agent's served path.
+//!
+//! Pins: ids mode pages through exactly the resources `/api/v1/messages`
+//! would allow; sql mode returns a parameterized clause; unknown policies,
+//! disabled evaluate-all and bad column names are rejected with stable
+//! error codes.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::sync::Arc;
+
+use axum::extract::{Json, State};
+use axum::http::StatusCode;
+use policy_engine::{cache_config::CacheConfig, EnhancedPolicy, PolicyEngine, PolicyLanguage};
+use reaper_agent::handlers::filter::FilterRequest;
+use reaper_agent::handlers::filter_resources;
+use reaper_agent::management::verify::BundleVerifier;
+use reaper_agent::state::{AgentState, AgentStats, DataSyncState};
+use reaper_core::config::{ManagementSettings, ReaperAgentConfig};
+use serde_json::{json, Value};
+
+const POLICY: &str = r#"
+policy doc_listing {
+    default: deny,
+    rule archived {
+        deny if resource.status == "archived"
+    }
+    rule owner {
+        allow if resource.owner == user.handle
+    }
+    rule public {
+        allow if resource.visibility == "public"
+    }
+}
+"#;
+
+fn agent_state(config: ReaperAgentConfig) -> Arc<AgentState> {
+    let s = Arc::new(policy_engine::DataStore::new());
+    let mut entities = vec![
+        json!({"id": "alice", "type": "user", "attributes": {"handle": "alice"}}),
+        json!({"id": "bob", "type": "user", "attributes": {"handle": "bob"}}),
+    ];
+    for i in 0..12 {
+        entities.push(json!({
+            "id": format!("doc-{i:02}"),
+            "type": "document",
+            "attributes": {
+                "owner": if i % 2 == 0 { "alice" } else { "bob" },
+                "status": if i % 4 == 0 { "archived" } else { "active" },
+                "visibility": if i == 3 { "public" } else { "private" },
+            }
+        }));
+    }
+    policy_engine::DataLoader::new((*s).clone())
+        .load_json(&json!({ "entities": entities }).to_string())
+        .unwrap();
+
+    let engine = PolicyEngine::new();
+    let mut p = EnhancedPolicy::new_with_language(
+        "doc_listing".to_string(),
+        String::new(),
+        PolicyLanguage::ReaperDsl,
+        POLICY.to_string(),
+    )
+    .unwrap();
+    p.build_evaluator_with_data(Some(s.clone())).unwrap();
+    engine.deploy_policy(p).unwrap();
+
+    Arc::new(AgentState {
+        policy_engine: engine,
+        data_store: s,
+        stats: Arc::new(AgentStats::new(false)),
+        decision_cache: None,
+        cache_config: CacheConfig::default(),
+        agent_config: config,
+        policy_cache: None,
+        decision_buffer: None,
+        agent_id: "test-agent".to_string(),
+        decision_metrics: Arc::new(reaper_agent::metrics_cache::DecisionMetrics::new()),
+        data_sync: Arc::new(DataSyncState::from_env()),
+        bundle_verifier: Arc::new(BundleVerifier::from_config(&ManagementSettings::default())),
+        shadow: Default::default(),
+        capability_gate: Arc::new(
+            reaper_agent::capability_cache::CapabilityGateRuntime::from_auth(
+                &reaper_core::config::AgentAuthSettings::default(),
+            ),
+        ),
+    })
+}
+
+async fn filter(state: Arc<AgentState>, body: Value) -> Result<Value, (StatusCode, Value)> {
+    let request: FilterRequest = serde_json::from_value(body).unwrap();
+    filter_resources(State(state), Json(request))
+        .await
+        .map(|Json(v)| v)
+        .map_err(|(status, Json(v))| (status, v))
+}
+
+#[tokio::test]
+async fn ids_mode_pages_through_the_permitted_documents() {
+    let state = agent_state(ReaperAgentConfig::default());
+    let mut ids = Vec::new();
+    let mut cursor = Value::Null;
+    loop {
+        let page = filter(
+            state.clone(),
+            json!({
+                "policy_name": "doc_listing",
+                "principal": "alice",
+                "action": "read",
+                "resource_type": "document",
+                "limit": 2,
+                "cursor": cursor,
+            }),
+        )
+        .await
+        .unwrap();
+        assert!(page["filter_time_us"].is_u64(), "body: {page}");
+        ids.extend(
+            page["ids"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|v| v.as_str().unwrap().to_string()),
+        );
+        cursor = page["next_cursor"].clone();
+        if cursor.is_null() {
+            break;
+        }
+    }
+    // Alice owns the even docs, minus the archived multiples of four, and
+    // doc-03 is public.
+    assert_eq!(ids, vec!["doc-02", "doc-03", "doc-06", "doc-10"]);
+}
+
+#[tokio::test]
+async fn sql_mode_returns_a_parameterized_clause() {
+    let state = agent_state(ReaperAgentConfig::default());
+    let body = filter(
+        state,
+        json!({
+            "policy_name": "doc_listing",
+            "principal": "bob",
+            "action": "read",
+            "resource_type": "document",
+            "mode": "sql",
+            "sql": {
+                "id_column": "doc_id",
+                "columns": {"owner": "owner", "status": "status", "visibility": "visibility"},
+                "param_style": "question"
+            }
+        }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(body["exact"], true, "body: {body}");
+    let sql = body["sql"].as_str().unwrap();
+    assert!(sql.contains('?') && !sql.contains("bob"), "sql: {sql}");
+    assert!(body["params"].as_array().unwrap().contains(&json!("bob")));
+}
+
+#[tokio::test]
+async fn rejections_carry_stable_codes() {
+    let state = agent_state(ReaperAgentConfig::default());
+    let base = json!({
+        "principal": "alice",
+        "action": "read",
+        "resource_type": "document",
+    });
+
+    let mut unknown = base.clone();
+    unknown["policy_name"] = json!("nope");
+    let (status, body) = filter(state.clone(), unknown).await.unwrap_err();
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"], "policy_not_found");
+
+    let mut bad_column = base.clone();
+    bad_column["policy_name"] = json!("doc_listing");
+    bad_column["mode"] = json!("sql");
+    bad_column["sql"] = json!({"id_column": "id; DROP TABLE docs"});
+    let (status, body) = filter(state.clone(), bad_column).await.unwrap_err();
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "invalid_column");
+
+    let mut config = ReaperAgentConfig::default();
+    config.performance.allow_evaluate_all = false;
+    let (status, body) = filter(agent_state(config), base).await.unwrap_err();
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"], "evaluate_all_disabled");
+}
@@ -49,6 +49,7 @@ fn state() -> Arc<AgentState> {
         decision_metrics: Arc::new(reaper_agent::metrics_cache::DecisionMetrics::new()),
         data_sync: Arc::new(DataSyncState::from_env()),
         bundle_verifier: Arc::new(BundleVerifier::from_config(&ManagementSettings::default())),
+        shadow: Default::default(),
         capability_gate: std::sync::Arc::new(
             reaper_agent::capability_cache::CapabilityGateRuntime::from_auth(
                 &reaper_core::config::AgentAuthSettings::default(),
@@ -90,6 +91,7 @@ async fn standard_endpoint_feeds_total_and_engine_series_and_they_differ() {
             actor: None,
             context_provenance: None,
             capability: None,
+            min_revision: None,
         }),
     )
     .await
@@ -0,0 +1,193 @@
+//! Rule obligations on the agent's served path.
+//!
+//! Pins: the deciding rule's `obligations` / `advice` appear as top-level
+//! response keys (absent when the rule declared none), batch items carry
+//! them too, the decision log records them, and obligation-bearing decisions
+//! are never served from the action-only decision cache.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::{
+    extract::{Json, State},
+    response::IntoResponse,
+};
+use policy_engine::{
+    cache_config::CacheConfig, decision_cache::DecisionCache, DecisionFilter, DecisionLogConfig,
+    EnhancedPolicy, PolicyEngine, PolicyLanguage, SharedDecisionBuffer,
+};
+use reaper_agent::handlers::{batch_evaluate_policy, evaluate_policy};
+use reaper_agent::management::verify::BundleVerifier;
+use reaper_agent::state::{AgentState, AgentStats, DataSyncState};
+use reaper_agent::types::{BatchEvaluateRequest, EvaluateRequest};
+use reaper_core::config::{ManagementSettings, ReaperAgentConfig};
+use serde_json::{json, Value};
+
+const POLICY: &str = r#"
+policy records {
+    default: deny,
+    rule analysts_masked {
+        allow
+            with obligations {"mask": ["ssn"], "log_level": "high"}
+            with advice {"banner": "PII redacted"}
+        if user.role == "analyst"
+    }
+    rule admins {
+        allow if user.role == "admin"
+    }
+}
+"#;
+
+fn state(
+    buffer: Option<SharedDecisionBuffer>,
+    cache: Option<Arc<DecisionCache>>,
+) -> Arc<AgentState> {
+    let s = Arc::new(policy_engine::DataStore::new());
+    policy_engine::DataLoader::new((*s).clone())
+        .load_json(
+            &json!({"entities": [
+                {"id": "ann", "type": "user", "attributes": {"role": "analyst"}},
+                {"id": "alice", "type": "user", "attributes": {"role": "admin"}},
+                {"id": "rec-1", "type": "resource", "attributes": {}}
+            ]})
+            .to_string(),
+        )
+        .unwrap();
+
+    let engine = PolicyEngine::new();
+    let mut p = EnhancedPolicy::new_with_language(
+        "records".to_string(),
+        String::new(),
+        PolicyLanguage::ReaperDsl,
+        POLICY.to_string(),
+    )
+    .unwrap();
+    p.build_evaluator_with_data(Some(s.clone())).unwrap();
+    engine.deploy_policy(p).unwrap();
+
+    Arc::new(AgentState {
+        policy_engine: engine,
+        data_store: s,
+        stats: Arc::new(AgentStats::new(false)),
+        decision_cache: cache,
+        cache_config: CacheConfig::default(),
+        agent_config: ReaperAgentConfig::default(),
+        policy_cache: None,
+        decision_buffer: buffer,
+        agent_id: "test-agent".to_string(),
+        decision_metrics: Arc::new(reaper_agent::metrics_cache::DecisionMetrics::new()),
+        data_sync: Arc::new(DataSyncState::from_env()),
+        bundle_verifier: Arc::new(BundleVerifier::from_config(&ManagementSettings::default())),
+        shadow: Default::default(),
+        capability_gate: std::sync::Arc::new(
+            reaper_agent::capability_cache::CapabilityGateRuntime::from_auth(
+                &reaper_core::config::AgentAuthSettings::default(),
+            ),
+        ),
+    })
+}
+
+fn req(principal: &str) -> EvaluateRequest {
+    EvaluateRequest {
+        policy_id: None,
+        policy_name: Some("records".to_string()),
+        principal: principal.to_string(),
+        resource: "rec-1".to_string(),
+        action: "read".to_string(),
+        context: Some(HashMap::new()),
+        actor: None,
+        context_provenance: None,
+        capability: None,
+        min_revision: None,
+    }
+}
+
+async fn decide(state: Arc<AgentState>, r: EvaluateRequest) -> Value {
+    let resp = evaluate_policy(State(state), Json(r))
+        .await
+        .expect("handler must serve")
+        .into_response();
+    let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+#[tokio::test]
+async fn response_carries_the_deciding_rules_obligations() {
+    let state = state(None, None);
+
+    let body = decide(state.clone(), req("ann")).await;
+    assert_eq!(body["decision"], "allow");
+    assert_eq!(
+        body["obligations"],
+        json!({"mask": ["ssn"], "log_level": "high"})
+    );
+    assert_eq!(body["advice"], json!({"banner": "PII redacted"}));
+
+    // No clauses on the deciding rule: the keys are absent, not empty.
+    let body = decide(state, req("alice")).await;
+    assert_eq!(body["decision"], "allow");
+    assert!(body.get("obligations").is_none(), "body: {body}");
+    assert!(body.get("advice").is_none(), "body: {body}");
+}
+
+#[tokio::test]
+async fn obligation_bearing_decisions_bypass_the_cache() {
+    let cache = Arc::new(DecisionCache::new(64));
+    let state = state(None, Some(cache.clone()));
+
+    for _ in 0..2 {
+        let body = decide(state.clone(), req("ann")).await;
+        assert_eq!(body["cache_hit"], false);
+        assert_eq!(body["obligations"]["log_level"], "high");
+    }
+    assert!(
+        cache.is_empty(),
+        "an action-only hit would drop obligations"
+    );
+
+    decide(state, req("alice")).await;
+    assert_eq!(cache.len(), 1, "plain decisions still cache");
+}
+
+#[tokio::test]
+async fn batch_items_carry_obligations() {
+    let state = state(None, Some(Arc::new(DecisionCache::new(64))));
+    let payload: BatchEvaluateRequest = serde_json::from_value(json!({
+        "policy_name": "records",
+        "requests": [
+            {"id": "1", "principal": "ann", "resource": "rec-1", "action": "read"},
+            {"id": "2", "principal": "alice", "resource": "rec-1", "action": "read"}
+        ]
+    }))
+    .unwrap();
+    let Json(body) = batch_evaluate_policy(State(state), Json(payload))
+        .await
+        .expect("batch must serve");
+    let results = body["results"].as_array().expect("results array");
+    assert_eq!(results[0]["obligations"]["mask"], json!(["ssn"]));
+    assert_eq!(results[0]["advice"]["banner"], "PII redacted");
+    assert!(results[1].get("obligations").is_none(), "body: {body}");
+}
+
+#[tokio::test]
+async fn decision_log_records_obligations() {
+    let config = DecisionLogConfig {
+        enabled: true,
+        privacy_profile: Some(policy_engine::PrivacyProfile::Raw),
+        ..Default::default()
+    };
+    let buffer = policy_engine::create_shared_buffer(config).unwrap();
+    let state = state(Some(buffer.clone()), None);
+
+    decide(state, req("ann")).await;
+
+    let entries = buffer.query(DecisionFilter::new(), 10);
+    assert_eq!(entries.len(), 1);
+    let o = entries[0].obligations.as_ref().expect("obligations logged");
+    assert_eq!(o.obligations["mask"], json!(["ssn"]));
+    assert_eq!(o.advice["banner"], "PII redacted");
+}
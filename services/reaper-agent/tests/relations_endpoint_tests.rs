@@ -0,0 +1,184 @@
+//! `POST /api/v1/relations/{lookup-resources,lookup-subjects,expand}` on the
+//! agent's served path.
+//!
+//! Pins: lookups expand the deployed schema's relation rewrites exactly as
+//! `rebac::check` does and page by keyset cursor; expand returns the path
+//! that grants a subject the relation. A `min_revision` the replica has not
+//! applied fails closed with 412 instead of listing pre-write grants.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::sync::Arc;
+
+use axum::extract::{Json, State};
+use axum::http::StatusCode;
+use policy_engine::cache_config::CacheConfig;
+use policy_engine::data::EntitySchema;
+use policy_engine::PolicyEngine;
+use reaper_agent::handlers::relations::{
+    ExpandRequest, LookupResourcesRequest, LookupSubjectsRequest,
+};
+use reaper_agent::handlers::{
+    expand_relation, lookup_relation_resources, lookup_relation_subjects,
+};
+use reaper_agent::management::verify::BundleVerifier;
+use reaper_agent::state::{AgentState, AgentStats, DataSyncState};
+use reaper_core::config::{ManagementSettings, ReaperAgentConfig};
+use serde_json::{json, Value};
+
+const SCHEMA: &str = r#"{
+    "entity_types": {
+        "User": {},
+        "Group": {"relations": {"member": ["User"]}},
+        "Folder": {
+            "relations": {"viewer": ["User", "Group"]},
+            "rewrites": {"viewer": "viewer + member from viewer"}
+        },
+        "Document": {
+            "relations": {"owner": ["User"], "viewer": ["User"], "parent": ["Folder"]},
+            "rewrites": {"viewer": "viewer + owner + viewer from parent"}
+        }
+    }
+}"#;
+
+fn agent_state() -> Arc<AgentState> {
+    let s = Arc::new(policy_engine::DataStore::new());
+    let mut entities = vec![
+        json!({"id": "alice", "type": "User", "attributes": {}}),
+        json!({"id": "bob", "type": "User", "attributes": {}}),
+        json!({"id": "carol", "type": "User", "attributes": {}}),
+        json!({"id": "eng", "type": "Group", "attributes": {}, "relationships": {"member": ["carol"]}}),
+        json!({"id": "shared", "type": "Folder", "attributes": {}, "relationships": {"viewer": ["eng"]}}),
+    ];
+    for i in 0..5 {
+        entities.push(json!({
+            "id": format!("doc-{i}"),
+            "type": "Document",
+            "attributes": {},
+            "relationships": {"owner": ["alice"], "parent": ["shared"]},
+        }));
+    }
+    entities.push(json!({
+        "id": "memo", "type": "Document", "attributes": {},
+        "relationships": {"viewer": ["bob"]},
+    }));
+    policy_engine::DataLoader::new((*s).clone())
+        .load_json(&json!({ "entities": entities }).to_string())
+        .unwrap();
+    s.relationships()
+        .set_schema(Some(&EntitySchema::from_json(SCHEMA).unwrap()))
+        .unwrap();
+
+    Arc::new(AgentState {
+        policy_engine: PolicyEngine::new(),
+        data_store: s,
+        stats: Arc::new(AgentStats::new(false)),
+        decision_cache: None,
+        cache_config: CacheConfig::default(),
+        agent_config: ReaperAgentConfig::default(),
+        policy_cache: None,
+        decision_buffer: None,
+        agent_id: "test-agent".to_string(),
+        decision_metrics: Arc::new(reaper_agent::metrics_cache::DecisionMetrics::new()),
+        data_sync: Arc::new(DataSyncState::from_env()),
+        bundle_verifier: Arc::new(BundleVerifier::from_config(&ManagementSettings::default())),
+        shadow: Default::default(),
+        capability_gate: Arc::new(
+            reaper_agent::capability_cache::CapabilityGateRuntime::from_auth(
+                &reaper_core::config::AgentAuthSettings::default(),
+            ),
+        ),
+    })
+}
+
+async fn resources(state: Arc<AgentState>, body: Value) -> Value {
+    let request: LookupResourcesRequest = serde_json::from_value(body).unwrap();
+    let Json(v) = lookup_relation_resources(State(state), Json(request))
+        .await
+        .unwrap();
+    v
+}
+
+#[tokio::test]
+async fn lookup_resources_pages_through_what_check_grants() {
+    let state = agent_state();
+    // carol views every document in the folder her group can view.
+    let first = resources(
+        state.clone(),
+        json!({"subject": "carol", "relation": "viewer", "resource_type": "Document", "limit": 3}),
+    )
+    .await;
+    assert_eq!(first["ids"], json!(["doc-0", "doc-1", "doc-2"]));
+    assert_eq!(first["next_cursor"], "doc-2");
+    assert_eq!(first["truncated"], false);
+    assert!(first["query_time_us"].is_u64());
+    let second = resources(
+        state.clone(),
+        json!({"subject": "carol", "relation": "viewer", "resource_type": "Document",
+               "limit": 3, "cursor": "doc-2"}),
+    )
+    .await;
+    assert_eq!(second["ids"], json!(["doc-3", "doc-4"]));
+    assert_eq!(second["next_cursor"], Value::Null);
+
+    let bob = resources(
+        state,
+        json!({"subject": "bob", "relation": "viewer", "resource_type": "Document"}),
+    )
+    .await;
+    assert_eq!(bob["ids"], json!(["memo"]));
+}
+
+#[tokio::test]
+async fn lookup_subjects_lists_who_holds_the_relation() {
+    let state = agent_state();
+    let request: LookupSubjectsRequest = serde_json::from_value(json!({
+        "object": "doc-0", "relation": "viewer", "subject_type": "User"
+    }))
+    .unwrap();
+    let Json(page) = lookup_relation_subjects(State(state), Json(request))
+        .await
+        .unwrap();
+    assert_eq!(page["ids"], json!(["alice", "carol"]));
+}
+
+#[tokio::test]
+async fn expand_returns_the_granting_path() {
+    let state = agent_state();
+    let request: ExpandRequest = serde_json::from_value(json!({
+        "object": "doc-0", "relation": "viewer", "subject": "carol"
+    }))
+    .unwrap();
+    let Json(tree) = expand_relation(State(state), Json(request)).await.unwrap();
+    assert_eq!(tree["granted"], true);
+    assert_eq!(tree["tree"]["kind"], "rewrite");
+    let json = tree["tree"].to_string();
+    for step in ["shared", "eng", "carol"] {
+        assert!(json.contains(step), "{step} missing from {json}");
+    }
+    assert!(!json.contains("alice"), "{json}");
+}
+
+#[tokio::test]
+async fn lookups_wait_for_the_demanded_revision() {
+    let state = agent_state();
+    state.data_sync.advance_to(7);
+    let fresh = resources(
+        state.clone(),
+        json!({"subject": "bob", "relation": "viewer", "resource_type": "Document",
+               "min_revision": 7}),
+    )
+    .await;
+    assert_eq!(fresh["ids"], json!(["memo"]));
+
+    let request: LookupResourcesRequest = serde_json::from_value(json!({
+        "subject": "bob", "relation": "viewer", "resource_type": "Document",
+        "min_revision": 8
+    }))
+    .unwrap();
+    let (status, Json(body)) = lookup_relation_resources(State(state), Json(request))
+        .await
+        .unwrap_err();
+    assert_eq!(status, StatusCode::PRECONDITION_FAILED);
+    assert_eq!(body["error"], "revision_unavailable");
+}
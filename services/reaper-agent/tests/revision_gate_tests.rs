@@ -0,0 +1,205 @@
+//! `min_revision` on the served evaluation routes.
+//!
+//! Pins: the standard, fast and batch routes answer a revision the replica
+//! has not applied with 412 `revision_unavailable` and no decision, the same
+//! contract as check, filter and the relation queries; a delta batch clears
+//! the decision cache before it releases waiters, so a request demanding the
+//! new head is never answered from a decision cached against the old data.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    body::Body,
+    extract::{Json, State},
+    http::{Request, StatusCode},
+    routing::post,
+    Router,
+};
+use policy_engine::{cache_config::CacheConfig, decision_cache::DecisionCache, PolicyEngine};
+use reaper_agent::handlers::policies::DeployCompiledPolicyRequest;
+use reaper_agent::handlers::{
+    apply_data_deltas, batch_evaluate_policy, deploy_compiled_policy, evaluate_messages,
+    fast_evaluate_policy,
+};
+use reaper_agent::management::verify::BundleVerifier;
+use reaper_agent::state::{AgentState, AgentStats, DataSyncState};
+use reaper_core::config::{ManagementSettings, ReaperAgentConfig};
+use serde_json::{json, Value};
+use tower::ServiceExt; // for `oneshot`
+
+const POLICY: &str = r#"
+policy revision_gate {
+    default: deny,
+    rule engineers_read {
+        allow if user.role == "engineer" && context.action == "read"
+    }
+}
+"#;
+
+async fn state(revision_wait: Duration) -> Arc<AgentState> {
+    let s = Arc::new(policy_engine::DataStore::new());
+    policy_engine::DataLoader::new((*s).clone())
+        .load_json(
+            &json!({"entities": [
+                {"id": "alice", "type": "user", "attributes": {"role": "engineer"}},
+                {"id": "doc-1", "type": "resource", "attributes": {}}
+            ]})
+            .to_string(),
+        )
+        .unwrap();
+    let mut data_sync = DataSyncState::from_env();
+    data_sync.revision_wait = revision_wait;
+
+    let state = Arc::new(AgentState {
+        policy_engine: PolicyEngine::new(),
+        data_store: s,
+        stats: Arc::new(AgentStats::new(false)),
+        decision_cache: Some(Arc::new(DecisionCache::new(64))),
+        cache_config: CacheConfig::default(),
+        agent_config: ReaperAgentConfig::default(),
+        policy_cache: None,
+        decision_buffer: None,
+        agent_id: "test-agent".to_string(),
+        decision_metrics: Arc::new(reaper_agent::metrics_cache::DecisionMetrics::new()),
+        data_sync: Arc::new(data_sync),
+        bundle_verifier: Arc::new(BundleVerifier::from_config(&ManagementSettings::default())),
+        shadow: Default::default(),
+        capability_gate: std::sync::Arc::new(
+            reaper_agent::capability_cache::CapabilityGateRuntime::from_auth(
+                &reaper_core::config::AgentAuthSettings::default(),
+            ),
+        ),
+    });
+    let _ = deploy_compiled_policy(
+        State(state.clone()),
+        Json(DeployCompiledPolicyRequest {
+            policy_content: POLICY.to_string(),
+            policy_name: "revision_gate".to_string(),
+            shadow: false,
+        }),
+    )
+    .await
+    .expect("deploy");
+    state
+}
+
+/// The evaluation and delta routes, mounted at their served paths.
+fn app(state: Arc<AgentState>) -> Router {
+    Router::new()
+        .route("/api/v1/messages", post(evaluate_messages))
+        .route("/api/v1/fast-messages", post(fast_evaluate_policy))
+        .route("/api/v1/batch-messages", post(batch_evaluate_policy))
+        .route("/api/v1/data/apply-deltas", post(apply_data_deltas))
+        .with_state(state)
+}
+
+async fn post_json(app: &Router, uri: &str, body: Value) -> (StatusCode, Value) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+fn single(min_revision: i64) -> Value {
+    json!({"policy_name": "revision_gate", "principal": "alice", "resource": "doc-1",
+           "action": "read", "min_revision": min_revision})
+}
+
+fn batch(min_revision: i64) -> Value {
+    json!({"policy_name": "revision_gate", "min_revision": min_revision, "requests": [
+        {"id": "r1", "principal": "alice", "resource": "doc-1", "action": "read"}
+    ]})
+}
+
+#[tokio::test]
+async fn unapplied_revisions_are_412_on_every_evaluation_route() {
+    let state = state(Duration::from_millis(10)).await;
+    state.data_sync.advance_to(3);
+    let app = app(state);
+
+    for (uri, body) in [
+        ("/api/v1/messages", single(4)),
+        ("/api/v1/fast-messages", single(4)),
+        ("/api/v1/batch-messages", batch(4)),
+    ] {
+        let (status, body) = post_json(&app, uri, body).await;
+        assert_eq!(status, StatusCode::PRECONDITION_FAILED, "{uri}: {body}");
+        assert_eq!(body["error"], "revision_unavailable", "{uri}");
+        assert_eq!(body["min_revision"], 4, "{uri}");
+        assert_eq!(body["applied_revision"], 3, "{uri}");
+        assert!(body.get("decision").is_none(), "{uri}: nothing is decided");
+        assert!(body.get("results").is_none(), "{uri}: nothing is decided");
+    }
+
+    // At the applied revision every route decides.
+    let (status, body) = post_json(&app, "/api/v1/messages", single(3)).await;
+    assert_eq!(
+        (status, body["decision"].clone()),
+        (StatusCode::OK, json!("allow"))
+    );
+    let (status, body) = post_json(&app, "/api/v1/fast-messages", single(3)).await;
+    assert_eq!(
+        (status, body["decision"].clone()),
+        (StatusCode::OK, json!("allow"))
+    );
+    let (status, body) = post_json(&app, "/api/v1/batch-messages", batch(3)).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["results"][0]["decision"], "allow");
+}
+
+#[tokio::test]
+async fn a_demanded_revision_is_never_answered_from_the_stale_cache() {
+    let state = state(Duration::from_secs(5)).await;
+    let app = app(state.clone());
+
+    // Warm the cache at revision 0.
+    let (_, body) = post_json(&app, "/api/v1/messages", single(0)).await;
+    assert_eq!(body["decision"], "allow");
+    let (_, body) = post_json(&app, "/api/v1/messages", single(0)).await;
+    assert_eq!(body["cache_hit"], true, "{body}");
+
+    // A caller that just demoted alice waits for revision 1 …
+    let waiter = {
+        let app = app.clone();
+        tokio::spawn(async move { post_json(&app, "/api/v1/messages", single(1)).await })
+    };
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    // … which the delta batch delivers.
+    let (status, body) = post_json(
+        &app,
+        "/api/v1/data/apply-deltas",
+        json!({"from_seq": 0, "head_seq": 1, "deltas": [
+            {"op": "upsert", "entity_id": "alice", "document":
+                {"id": "alice", "type": "user", "attributes": {"role": "intern"}}}
+        ]}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+
+    let (status, body) = waiter.await.unwrap();
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["decision"], "deny", "{body}");
+    assert_eq!(body["cache_hit"], false);
+    let (_, body) = post_json(&app, "/api/v1/messages", single(1)).await;
+    assert_eq!(body["decision"], "deny", "{body}");
+}
@@ -0,0 +1,318 @@
+//! Shadow-policy evaluation on the agent's served path.
+//!
+//! Pins: a shadow candidate never changes the served decision; every live
+//! request naming the shadowed policy is re-evaluated against the candidate;
+//! allow→deny / deny→allow flips are counted (registry + Prometheus) and
+//! recorded in the decision buffer with both outcomes; a shadow needs a live
+//! counterpart; removing it stops the comparison; past the in-flight cap an
+//! observation is dropped and counted, never queued. Bundles shadow the same
+//! way.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use policy_engine::{
+    cache_config::CacheConfig, DecisionFilter, DecisionLogConfig, PolicyEngine,
+    SharedDecisionBuffer,
+};
+use reaper_agent::handlers::policies::DeployCompiledPolicyRequest;
+use reaper_agent::handlers::{
+    deploy_bundle, deploy_compiled_policy, evaluate_policy, list_shadows, remove_shadow,
+};
+use reaper_agent::management::verify::BundleVerifier;
+use reaper_agent::observability::{SHADOW_DIVERGENCES, SHADOW_DROPPED, SHADOW_EVALUATIONS};
+use reaper_agent::shadow::ShadowPolicies;
+use reaper_agent::state::{AgentState, AgentStats, DataSyncState};
+use reaper_agent::types::{DeployBundleRequest, EvaluateRequest};
+use reaper_core::config::{ManagementSettings, ReaperAgentConfig};
+use serde_json::{json, Value};
+
+fn live_policy(name: &str) -> String {
+    format!(
+        r#"
+policy {name} {{
+    default: deny,
+    rule engineers_read {{
+        allow if user.role == "engineer" && context.action == "read"
+    }}
+}}
+"#
+    )
+}
+
+/// The candidate: engineers may also write, but nobody reads prod.
+fn candidate_policy(name: &str) -> String {
+    format!(
+        r#"
+policy {name} {{
+    default: deny,
+    rule no_prod_reads {{
+        deny if resource.env == "prod" && context.action == "read"
+    }}
+    rule engineers {{
+        allow if user.role == "engineer"
+    }}
+}}
+"#
+    )
+}
+
+fn state(buffer: Option<SharedDecisionBuffer>) -> Arc<AgentState> {
+    state_with_shadows(buffer, ShadowPolicies::new())
+}
+
+fn state_with_shadows(
+    buffer: Option<SharedDecisionBuffer>,
+    shadows: ShadowPolicies,
+) -> Arc<AgentState> {
+    let s = Arc::new(policy_engine::DataStore::new());
+    policy_engine::DataLoader::new((*s).clone())
+        .load_json(
+            &json!({"entities": [
+                {"id": "alice", "type": "user", "attributes": {"role": "engineer"}},
+                {"id": "doc-prod", "type": "resource", "attributes": {"env": "prod"}},
+                {"id": "doc-dev", "type": "resource", "attributes": {"env": "dev"}}
+            ]})
+            .to_string(),
+        )
+        .unwrap();
+
+    Arc::new(AgentState {
+        policy_engine: PolicyEngine::new(),
+        data_store: s,
+        stats: Arc::new(AgentStats::new(false)),
+        decision_cache: None,
+        cache_config: CacheConfig::default(),
+        agent_config: ReaperAgentConfig::default(),
+        policy_cache: None,
+        decision_buffer: buffer,
+        agent_id: "test-agent".to_string(),
+        decision_metrics: Arc::new(reaper_agent::metrics_cache::DecisionMetrics::new()),
+        data_sync: Arc::new(DataSyncState::from_env()),
+        bundle_verifier: Arc::new(BundleVerifier::from_config(&ManagementSettings::default())),
+        shadow: Arc::new(shadows),
+        capability_gate: std::sync::Arc::new(
+            reaper_agent::capability_cache::CapabilityGateRuntime::from_auth(
+                &reaper_core::config::AgentAuthSettings::default(),
+            ),
+        ),
+    })
+}
+
+async fn deploy(state: &Arc<AgentState>, name: &str, content: String, shadow: bool) -> StatusCode {
+    match deploy_compiled_policy(
+        State(state.clone()),
+        Json(DeployCompiledPolicyRequest {
+            policy_content: content,
+            policy_name: name.to_string(),
+            shadow,
+        }),
+    )
+    .await
+    {
+        Ok(_) => StatusCode::OK,
+        Err((status, _)) => status,
+    }
+}
+
+async fn decide(state: &Arc<AgentState>, policy: &str, resource: &str, action: &str) -> Value {
+    let r = EvaluateRequest {
+        policy_id: None,
+        policy_name: Some(policy.to_string()),
+        principal: "alice".to_string(),
+        resource: resource.to_string(),
+        action: action.to_string(),
+        context: None,
+        actor: None,
+        context_provenance: None,
+        capability: None,
+        min_revision: None,
+    };
+    let resp = evaluate_policy(State(state.clone()), Json(r))
+        .await
+        .expect("handler must serve")
+        .into_response();
+    let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+/// Shadow work is off the response path: wait for it to land.
+async fn await_evaluations(state: &AgentState, n: u64) {
+    for _ in 0..200 {
+        if state.shadow.list().first().map(|s| s.evaluations) >= Some(n) {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    panic!("shadow evaluations never reached {n}");
+}
+
+#[tokio::test]
+async fn divergences_are_counted_and_logged_without_changing_decisions() {
+    let name = "shadow_divergence";
+    let buffer = policy_engine::create_shared_buffer(DecisionLogConfig {
+        enabled: true,
+        privacy_profile: Some(policy_engine::PrivacyProfile::Raw),
+        // Pristine denies-only posture: divergence records still land.
+        log_allows: false,
+        ..Default::default()
+    })
+    .unwrap();
+    let state = state(Some(buffer.clone()));
+    assert_eq!(
+        deploy(&state, name, live_policy(name), false).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        deploy(&state, name, candidate_policy(name), true).await,
+        StatusCode::OK
+    );
+
+    // Live decisions are unchanged by the candidate.
+    let body = decide(&state, name, "doc-prod", "read").await;
+    assert_eq!(body["decision"], "allow", "body: {body}");
+    assert_eq!(
+        decide(&state, name, "doc-dev", "write").await["decision"],
+        "deny"
+    );
+    assert_eq!(
+        decide(&state, name, "doc-dev", "read").await["decision"],
+        "allow"
+    );
+    await_evaluations(&state, 3).await;
+
+    let summary = &state.shadow.list()[0];
+    assert_eq!(summary.policy_name, name);
+    assert_eq!(summary.allow_to_deny, 1);
+    assert_eq!(summary.deny_to_allow, 1);
+    assert_eq!(SHADOW_EVALUATIONS.with_label_values(&[name]).get(), 3.0);
+    assert_eq!(
+        SHADOW_DIVERGENCES
+            .with_label_values(&[name, "allow_to_deny"])
+            .get(),
+        1.0
+    );
+    assert_eq!(
+        SHADOW_DIVERGENCES
+            .with_label_values(&[name, "deny_to_allow"])
+            .get(),
+        1.0
+    );
+
+    // Both outcomes are recorded, under the served decision id.
+    let records = buffer.query(DecisionFilter::new().with_shadow(true), 10);
+    assert_eq!(records.len(), 2);
+    let flip = records
+        .iter()
+        .find(|e| e.resource == "doc-prod")
+        .expect("allow->deny record");
+    assert_eq!(flip.decision, "allow");
+    assert_eq!(flip.matched_rule.as_deref(), Some("engineers_read"));
+    assert_eq!(flip.decision_id, body["decision_id"].as_str().unwrap());
+    let shadow = flip.shadow.as_ref().unwrap();
+    assert_eq!(shadow.decision, "deny");
+    assert_eq!(shadow.policy_name, name);
+    assert_eq!(shadow.matched_rule.as_deref(), Some("no_prod_reads"));
+
+    // The listing endpoint reports the same counters.
+    let Json(listing) = list_shadows(State(state.clone())).await;
+    assert_eq!(listing["count"], 1);
+    assert_eq!(listing["shadows"][0]["allow_to_deny"], 1);
+}
+
+#[tokio::test]
+async fn shadow_requires_a_live_policy() {
+    let state = state(None);
+    assert_eq!(
+        deploy(
+            &state,
+            "shadow_orphan",
+            candidate_policy("shadow_orphan"),
+            true
+        )
+        .await,
+        StatusCode::NOT_FOUND
+    );
+    assert!(!state.shadow.is_armed());
+}
+
+#[tokio::test]
+async fn removing_the_shadow_stops_the_comparison() {
+    let name = "shadow_removal";
+    let state = state(None);
+    deploy(&state, name, live_policy(name), false).await;
+    deploy(&state, name, candidate_policy(name), true).await;
+    decide(&state, name, "doc-prod", "read").await;
+    await_evaluations(&state, 1).await;
+
+    let Json(removed) = remove_shadow(State(state.clone()), Path(name.to_string()))
+        .await
+        .unwrap();
+    assert_eq!(removed["shadow"]["evaluations"], 1);
+    assert!(!state.shadow.is_armed());
+    assert!(remove_shadow(State(state.clone()), Path(name.to_string()))
+        .await
+        .is_err());
+
+    decide(&state, name, "doc-prod", "read").await;
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(SHADOW_EVALUATIONS.with_label_values(&[name]).get(), 1.0);
+}
+
+#[tokio::test]
+async fn observations_past_the_in_flight_cap_are_dropped() {
+    let name = "shadow_saturated";
+    let state = state_with_shadows(None, ShadowPolicies::with_capacity(0));
+    deploy(&state, name, live_policy(name), false).await;
+    deploy(&state, name, candidate_policy(name), true).await;
+
+    let before = SHADOW_DROPPED.get();
+    let body = decide(&state, name, "doc-prod", "read").await;
+    assert_eq!(
+        body["decision"], "allow",
+        "the live decision is still served"
+    );
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(SHADOW_DROPPED.get(), before + 1.0);
+    assert_eq!(state.shadow.list()[0].evaluations, 0);
+}
+
+#[tokio::test]
+async fn bundles_deploy_as_shadows() {
+    let name = "shadow_bundle";
+    let state = state(None);
+    deploy(&state, name, live_policy(name), false).await;
+
+    let policy: policy_engine::reap::ReaperPolicy = candidate_policy(name).parse().unwrap();
+    let bytes = policy.compile_to_bundle().unwrap();
+    let Json(resp) = deploy_bundle(
+        State(state.clone()),
+        Json(DeployBundleRequest {
+            bundle: bytes,
+            version: "2".to_string(),
+            force: false,
+            signature: None,
+            shadow: true,
+        }),
+    )
+    .await
+    .unwrap();
+    assert!(!resp.bundle_hash.is_empty());
+
+    // The live policy is still the original one.
+    assert_eq!(
+        decide(&state, name, "doc-prod", "read").await["decision"],
+        "allow"
+    );
+    await_evaluations(&state, 1).await;
+    assert_eq!(state.shadow.list()[0].allow_to_deny, 1);
+}
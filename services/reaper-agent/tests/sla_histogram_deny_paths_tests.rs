@@ -1,10 +1,10 @@
 //! PERF R2-P2-3 — the request-total SLA histogram must be fed on EVERY
 //! served return path, not just success/cache-hit. Early-return denies
-//! (`data_stale`, `policy_not_found`, `evaluate_all_disabled`,
-//! `no_policies_loaded`, `candidate_cap_exceeded`, fast-path `parse_error`)
-//! are served requests; if they skip `reaper_decision_duration_seconds`, a
-//! denial storm renders the SLA dashboard silent while the agent answers at
-//! line rate.
+//! (`data_stale`, `revision_unavailable`, `policy_not_found`,
+//! `evaluate_all_disabled`, `no_policies_loaded`, `candidate_cap_exceeded`,
+//! fast-path `parse_error`) are served requests; if they skip
+//! `reaper_decision_duration_seconds`, a denial storm renders the SLA
+//! dashboard silent while the agent answers at line rate.
 //!
 //! Early-return observations land under the constant policy label
 //! `early_deny` (bounded cardinality; the deny *reason* is already counted by
@@ -64,6 +64,7 @@ fn state_with(
         decision_metrics: Arc::new(reaper_agent::metrics_cache::DecisionMetrics::new()),
         data_sync: Arc::new(data_sync),
         bundle_verifier: Arc::new(BundleVerifier::from_config(&ManagementSettings::default())),
+        shadow: Default::default(),
         capability_gate: std::sync::Arc::new(
             reaper_agent::capability_cache::CapabilityGateRuntime::from_auth(
                 &reaper_core::config::AgentAuthSettings::default(),
@@ -91,6 +92,7 @@ fn eval_request(policy_id: Option<&str>, resource: &str) -> EvaluateRequest {
         actor: None,
         context_provenance: None,
         capability: None,
+        min_revision: None,
     }
 }
 
@@ -198,6 +200,32 @@ async fn every_early_return_deny_feeds_the_sla_histogram() {
         "data-sync gate deny must observe request-total"
     );
 
+    // revision gate (min_revision beyond what the replica applied, 412); the
+    // fast endpoint routes such requests through the standard lane.
+    let before = early_deny_samples();
+    let mut data_sync = DataSyncState::from_env();
+    data_sync.revision_wait = std::time::Duration::from_millis(10);
+    let s = state_with(false, 256, data_sync);
+    s.policy_engine
+        .deploy_policy(simple_allow("p", "/doc"))
+        .unwrap();
+    let mut req = eval_request(Some("p"), "/doc");
+    req.min_revision = Some(1);
+    let body = standard(s.clone(), req).await;
+    assert_eq!(body["error"], "revision_unavailable");
+    assert_eq!(body["applied_revision"], 0);
+    let body = fast(
+        s,
+        r#"{"policy_id":"p","principal":"u","resource":"/doc","action":"read","min_revision":1}"#,
+    )
+    .await;
+    assert_eq!(body["error"], "revision_unavailable");
+    assert_eq!(
+        early_deny_samples(),
+        before + 2,
+        "revision gate deny must observe request-total"
+    );
+
     // -------- fast endpoint --------
 
     // parse_error
@@ -0,0 +1,210 @@
+//! Typed request `context` on the agent's served path.
+//!
+//! Pins: numbers, bools, arrays and objects in `context` reach the compiled
+//! evaluator as JSON (`context.amount > 1000`, `"x" in context.tags`,
+//! `context.org.plan`); a stringly payload no longer satisfies a numeric
+//! rule; the batch endpoint accepts the same shapes; the decision log
+//! records the context values exactly as sent.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::{
+    extract::{Json, State},
+    response::IntoResponse,
+};
+use policy_engine::{
+    cache_config::CacheConfig, DecisionFilter, DecisionLogConfig, EnhancedPolicy, PolicyEngine,
+    PolicyLanguage, SharedDecisionBuffer,
+};
+use reaper_agent::handlers::{batch_evaluate_policy, evaluate_policy};
+use reaper_agent::management::verify::BundleVerifier;
+use reaper_agent::state::{AgentState, AgentStats, DataSyncState};
+use reaper_agent::types::{BatchEvaluateRequest, EvaluateRequest};
+use reaper_core::config::{ManagementSettings, ReaperAgentConfig};
+use serde_json::{json, Value};
+
+const POLICY: &str = r#"
+policy payments {
+    default: deny,
+    rule frozen {
+        deny if context.frozen == true
+    }
+    rule large_pro {
+        allow if context.amount > 1000 && context.org.plan == "pro"
+    }
+    rule tagged {
+        allow if "expedite" in context.tags
+    }
+}
+"#;
+
+fn state_with_buffer(buffer: Option<SharedDecisionBuffer>) -> Arc<AgentState> {
+    let s = Arc::new(policy_engine::DataStore::new());
+    policy_engine::DataLoader::new((*s).clone())
+        .load_json(
+            &json!({"entities": [
+                {"id": "alice", "type": "user", "attributes": {}},
+                {"id": "txn-1", "type": "resource", "attributes": {}}
+            ]})
+            .to_string(),
+        )
+        .unwrap();
+
+    let engine = PolicyEngine::new();
+    let mut p = EnhancedPolicy::new_with_language(
+        "payments".to_string(),
+        String::new(),
+        PolicyLanguage::ReaperDsl,
+        POLICY.to_string(),
+    )
+    .unwrap();
+    p.build_evaluator_with_data(Some(s.clone())).unwrap();
+    engine.deploy_policy(p).unwrap();
+
+    Arc::new(AgentState {
+        policy_engine: engine,
+        data_store: s,
+        stats: Arc::new(AgentStats::new(false)),
+        decision_cache: None,
+        cache_config: CacheConfig::default(),
+        agent_config: ReaperAgentConfig::default(),
+        policy_cache: None,
+        decision_buffer: buffer,
+        agent_id: "test-agent".to_string(),
+        decision_metrics: Arc::new(reaper_agent::metrics_cache::DecisionMetrics::new()),
+        data_sync: Arc::new(DataSyncState::from_env()),
+        bundle_verifier: Arc::new(BundleVerifier::from_config(&ManagementSettings::default())),
+        shadow: Default::default(),
+        capability_gate: std::sync::Arc::new(
+            reaper_agent::capability_cache::CapabilityGateRuntime::from_auth(
+                &reaper_core::config::AgentAuthSettings::default(),
+            ),
+        ),
+    })
+}
+
+fn req(context: Value) -> EvaluateRequest {
+    let context: HashMap<String, Value> = serde_json::from_value(context).unwrap();
+    EvaluateRequest {
+        policy_id: None,
+        policy_name: Some("payments".to_string()),
+        principal: "alice".to_string(),
+        resource: "txn-1".to_string(),
+        action: "pay".to_string(),
+        context: Some(context),
+        actor: None,
+        context_provenance: None,
+        capability: None,
+        min_revision: None,
+    }
+}
+
+async fn decide(state: Arc<AgentState>, r: EvaluateRequest) -> Value {
+    let resp = evaluate_policy(State(state), Json(r))
+        .await
+        .expect("handler must serve")
+        .into_response();
+    let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+#[tokio::test]
+async fn typed_values_reach_the_evaluator() {
+    let state = state_with_buffer(None);
+    for (context, decision, rule) in [
+        (
+            json!({"amount": 1500, "org": {"plan": "pro"}}),
+            "allow",
+            "large_pro",
+        ),
+        (
+            json!({"amount": 1000.5, "org": {"plan": "pro"}}),
+            "allow",
+            "large_pro",
+        ),
+        (
+            json!({"amount": 999, "org": {"plan": "pro"}}),
+            "deny",
+            "default_deny",
+        ),
+        (json!({"tags": ["audit", "expedite"]}), "allow", "tagged"),
+        (
+            json!({"amount": 1500, "org": {"plan": "pro"}, "frozen": true}),
+            "deny",
+            "frozen",
+        ),
+    ] {
+        let body = decide(state.clone(), req(context.clone())).await;
+        assert_eq!(
+            body["decision"], decision,
+            "context: {context} body: {body}"
+        );
+        assert_eq!(body["matched_rule"], rule, "context: {context}");
+    }
+}
+
+#[tokio::test]
+async fn stringly_values_are_not_coerced() {
+    let state = state_with_buffer(None);
+    for context in [
+        json!({"amount": "1500", "org": {"plan": "pro"}}),
+        json!({"tags": "expedite"}),
+        json!({"amount": 1500, "org": "pro"}),
+    ] {
+        let body = decide(state.clone(), req(context.clone())).await;
+        assert_eq!(body["decision"], "deny", "context: {context}");
+    }
+    // `"true"` is not `true`: the frozen rule does not fire.
+    let body = decide(state, req(json!({"frozen": "true", "tags": ["expedite"]}))).await;
+    assert_eq!(body["decision"], "allow");
+}
+
+#[tokio::test]
+async fn batch_items_carry_typed_context() {
+    let state = state_with_buffer(None);
+    let payload: BatchEvaluateRequest = serde_json::from_value(json!({
+        "policy_name": "payments",
+        "requests": [
+            {"id": "typed", "principal": "alice", "resource": "txn-1", "action": "pay",
+             "context": {"amount": 2000, "org": {"plan": "pro"}}},
+            {"id": "stringly", "principal": "alice", "resource": "txn-1", "action": "pay",
+             "context": {"amount": "2000", "org": {"plan": "pro"}}}
+        ]
+    }))
+    .unwrap();
+    let Json(body) = batch_evaluate_policy(State(state), Json(payload))
+        .await
+        .expect("batch must serve");
+    let results = body["results"].as_array().expect("results array");
+    assert_eq!(results.len(), 2, "body: {body}");
+    assert_eq!(results[0]["decision"], "allow", "body: {body}");
+    assert_eq!(results[1]["decision"], "deny", "body: {body}");
+}
+
+#[tokio::test]
+async fn decision_log_records_typed_context() {
+    let config = DecisionLogConfig {
+        enabled: true,
+        privacy_profile: Some(policy_engine::PrivacyProfile::Raw),
+        ..Default::default()
+    };
+    let buffer = policy_engine::create_shared_buffer(config).unwrap();
+    let state = state_with_buffer(Some(buffer.clone()));
+
+    let context = json!({"amount": 1500, "org": {"plan": "pro"}, "tags": ["a"]});
+    let body = decide(state, req(context)).await;
+    assert_eq!(body["decision"], "allow");
+
+    let entries = buffer.query(DecisionFilter::new(), 10);
+    assert_eq!(entries.len(), 1);
+    let logged = &entries[0].context;
+    assert_eq!(logged["amount"], json!(1500));
+    assert_eq!(logged["org"], json!({"plan": "pro"}));
+    assert_eq!(logged["tags"], json!(["a"]));
+    assert!(!logged.contains_key("principal"));
+}
@@ -10,6 +10,9 @@
 //!   zero-approver policy the requester's own approve suffices (self-service
 //!   confirmation); stricter envs demand N distinct approvers.
 //! - `GET /orgs/{org}/promotions[/{id}]` is the auditable change-record trail.
+//! - `GET /orgs/{org}/promotions/{id}/diff` shows which requests change
+//!   outcome if the request applies: its bundle's Reaper policies against
+//!   those of the last request applied to the same target environment.
 //!
 //! (The `/promotions` path is distinct from Plan 02's `/change-requests`, which
 //! governs bundle-status promotion — a separate mechanism.)
@@ -34,7 +37,8 @@ use crate::{
     audit::{actions, ActorType, AuditEntry, ResourceType},
     auth::{middleware::RequireAuth, scopes::Scope},
     db::repositories::{
-        ChangeRequestRepository, DatastoreRepository, EnvironmentRepository, OrganizationRepository,
+        BundleRepository, ChangeRequestRepository, DatastoreRepository, EnvironmentRepository,
+        OrganizationRepository, PolicyRepository,
     },
     deployment::service::DeploymentService,
     domain::change_request::{
@@ -42,6 +46,7 @@ use crate::{
     },
     domain::deployment::StartRollout,
     domain::environment::{ApprovalOutcome, Environment, ExternalChangeRecordMode, WindowDecision},
+    domain::policy::PolicyLanguage,
     integrations::{ChangeRecordCheck, ServiceNowClient},
     state::AppState,
 };
@@ -51,6 +56,7 @@ pub fn routes() -> OpenApiRouter<Arc<AppState>> {
         .routes(routes!(promote))
         .routes(routes!(list_promotions))
         .routes(routes!(get_promotion))
+        .routes(routes!(get_promotion_diff))
         .routes(routes!(approve_promotion))
         .routes(routes!(reject_promotion))
 }
@@ -82,6 +88,42 @@ pub struct ChangeRequestDetail {
     pub approvals: Vec<ChangeApproval>,
 }
 
+/// Outcome diff of a change request (`GET /orgs/{org}/promotions/{id}/diff`).
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PromotionDiff {
+    /// Bundle of the last change request applied to the target environment
+    /// before this one; `None` when there is none, and every policy is
+    /// `added`.
+    pub baseline_bundle_id: Option<Uuid>,
+    pub policies: Vec<PolicyOutcomeDiff>,
+    /// Policies not compared: other languages, or Reaper sources that do
+    /// not parse.
+    pub skipped: Vec<String>,
+}
+
+/// How one policy's decisions change.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PolicyOutcomeDiff {
+    pub policy: String,
+    /// `added`, `removed`, `changed` or `unchanged`.
+    pub status: String,
+    pub changes: Vec<OutcomeChangeView>,
+    /// More request classes changed than are listed.
+    pub truncated: bool,
+}
+
+/// One class of requests whose outcome changes.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OutcomeChangeView {
+    /// `allow` or `deny`.
+    pub from: String,
+    pub to: String,
+    /// Conditions describing the class; empty means every request.
+    pub when: Vec<String>,
+    /// e.g. `deny → allow when user.role == "contractor"`.
+    pub summary: String,
+}
+
 /// Decision body for approve/reject.
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct DecisionRequest {
@@ -423,8 +465,108 @@ async fn get_promotion(
     }))
 }
 
+/// Which requests change outcome if a change request applies.
+#[utoipa::path(
+    get,
+    path = "/orgs/{org}/promotions/{id}/diff",
+    tag = "environments",
+    params(
+        ("org" = String, Path, description = "Organization ID or slug"),
+        ("id" = Uuid, Path, description = "Change request ID")
+    ),
+    responses(
+        (status = 200, description = "Outcome diff against the target environment", body = PromotionDiff),
+        (status = 404, description = "Change request not found in this organization")
+    ),
+    security(("bearer_jwt" = []))
+)]
+async fn get_promotion_diff(
+    State(state): State<Arc<AppState>>,
+    RequireAuth(user): RequireAuth,
+    Path((org, id)): Path<(String, Uuid)>,
+) -> ApiResult<Json<PromotionDiff>> {
+    let organization = authorize(&state, &user, &org, Scope::PolicyRead).await?;
+    let cr_repo = ChangeRequestRepository::new(&state.db);
+    let cr = load_scoped(&cr_repo, organization.id, id).await?;
+    let baseline = cr_repo.last_applied_before(&cr).await?;
+
+    let mut skipped = Vec::new();
+    let new = reaper_policies(&state, cr.bundle_id, &mut skipped).await?;
+    let old = match &baseline {
+        Some(applied) => reaper_policies(&state, applied.bundle_id, &mut skipped).await?,
+        None => Vec::new(),
+    };
+    skipped.sort();
+    skipped.dedup();
+
+    let policies = policy_engine::reap::diff::diff_sets(
+        &old.iter().collect::<Vec<_>>(),
+        &new.iter().collect::<Vec<_>>(),
+    )
+    .into_iter()
+    .map(|d| PolicyOutcomeDiff {
+        policy: d.policy,
+        status: d.status.as_str().to_string(),
+        changes: d
+            .changes
+            .iter()
+            .map(|c| OutcomeChangeView {
+                from: decision_name(&c.from).to_string(),
+                to: decision_name(&c.to).to_string(),
+                when: c.when.clone(),
+                summary: c.to_string(),
+            })
+            .collect(),
+        truncated: d.truncated,
+    })
+    .collect();
+
+    Ok(Json(PromotionDiff {
+        baseline_bundle_id: baseline.map(|b| b.bundle_id),
+        policies,
+        skipped,
+    }))
+}
+
 // --- shared helpers --------------------------------------------------------
 
+/// The parsed Reaper policies of a bundle, at the versions the bundle pins.
+/// Anything that cannot be compared is named in `skipped`.
+async fn reaper_policies(
+    state: &AppState,
+    bundle_id: Uuid,
+    skipped: &mut Vec<String>,
+) -> ApiResult<Vec<policy_engine::reap::Policy>> {
+    let bundle_repo = BundleRepository::new(&state.db);
+    let policy_repo = PolicyRepository::new(&state.db);
+    let mut out = Vec::new();
+    for bp in bundle_repo.get_policies(bundle_id).await? {
+        let Some(policy) = policy_repo.get_by_id(bp.policy_id).await? else {
+            continue;
+        };
+        if policy.language != PolicyLanguage::Reaper {
+            skipped.push(policy.name);
+            continue;
+        }
+        let parsed = policy_repo
+            .get_version(bp.policy_id, bp.policy_version)
+            .await?
+            .and_then(|v| policy_engine::reap::ReapParser::parse(&v.content).ok());
+        match parsed {
+            Some(ast) => out.push(ast),
+            None => skipped.push(policy.name),
+        }
+    }
+    Ok(out)
+}
+
+fn decision_name(decision: &policy_engine::reap::Decision) -> &'static str {
+    match decision {
+        policy_engine::reap::Decision::Allow => "allow",
+        policy_engine::reap::Decision::Deny => "deny",
+    }
+}
+
 /// If the target env's approval policy is satisfied by the recorded approvals,
 /// start the rollout and mark the change request `applied`. Otherwise leave it
 /// pending. Idempotent — a request already applied/decided is returned as-is.
@@ -28,7 +28,8 @@ use crate::{
     db::repositories::{DatastoreRepository, NamespaceRepository},
     db::DatabaseError,
     domain::datastore::{
-        materialize, AdmEntity, DatastoreTemplate, ModelDefinition, RelationTuple, RoleBinding,
+        self, materialize, merge_model, validate_grant_condition, AdmEntity, DatastoreTemplate,
+        ImportFormat, ImportIssue, ModelDefinition, RelationTuple, RoleBinding,
     },
     domain::{impact, migration},
     state::{AppState, ServerEvent},
@@ -43,6 +44,7 @@ pub fn routes() -> OpenApiRouter<Arc<AppState>> {
         .routes(routes!(put_attributes))
         .routes(routes!(list_bindings, add_binding, remove_binding))
         .routes(routes!(list_tuples, write_tuple, remove_tuple))
+        .routes(routes!(import_model))
         .routes(routes!(publish))
         .routes(routes!(plan_migration))
         .routes(routes!(apply_migration))
@@ -395,6 +397,9 @@ async fn list_entities(
 struct EntityUpsertedResponse {
     entity_id: String,
     upserted: bool,
+    /// Revision token: pass as `min_revision` to agent evaluate/check calls
+    /// to read at least this fresh.
+    revision: i64,
 }
 
 /// Create or replace an entity (attributes validated against the model).
@@ -426,12 +431,12 @@ async fn upsert_entity(
         .model
         .validate_attributes(&entity.entity_type, &entity.attributes)
         .map_err(ApiError::BadRequest)?;
-    DatastoreRepository::new(&state.db)
-        .upsert_entity(store.id, &entity)
-        .await?;
+    let repo = DatastoreRepository::new(&state.db);
+    repo.upsert_entity(store.id, &entity).await?;
     Ok(Json(EntityUpsertedResponse {
         entity_id: entity.entity_id,
         upserted: true,
+        revision: repo.revision(store.id).await?,
     }))
 }
 
@@ -473,6 +478,9 @@ struct EntityDeletedResponse {
     /// Other entity ids whose materialized documents changed because tuples
     /// or bindings touching the deleted entity died with it.
     cascaded: Vec<String>,
+    /// Revision token: pass as `min_revision` to agent evaluate/check calls
+    /// to read at least this fresh.
+    revision: i64,
 }
 
 /// Delete an entity; tuples and bindings touching it cascade.
@@ -501,12 +509,12 @@ async fn delete_entity(
     // Referential cascade (contract pinned by the delta==rebuild
     // differential): tuples and bindings touching the entity die with it,
     // and the other endpoints' docs are marked dirty in the change log.
-    let (deleted, affected) = DatastoreRepository::new(&state.db)
-        .delete_entity_cascade(store.id, &entity_id)
-        .await?;
+    let repo = DatastoreRepository::new(&state.db);
+    let (deleted, affected) = repo.delete_entity_cascade(store.id, &entity_id).await?;
     Ok(Json(EntityDeletedResponse {
         deleted,
         cascaded: affected,
+        revision: repo.revision(store.id).await?,
     }))
 }
 
@@ -515,6 +523,9 @@ async fn delete_entity(
 struct AttributesUpdatedResponse {
     entity_id: String,
     updated: bool,
+    /// Revision token: pass as `min_revision` to agent evaluate/check calls
+    /// to read at least this fresh.
+    revision: i64,
 }
 
 /// Replace an entity's attribute map (typed, validated). PUT semantics keep
@@ -557,6 +568,7 @@ async fn put_attributes(
     Ok(Json(AttributesUpdatedResponse {
         entity_id,
         updated: true,
+        revision: repo.revision(store.id).await?,
     }))
 }
 
@@ -622,9 +634,14 @@ async fn list_bindings(
 #[derive(Debug, Serialize, ToSchema)]
 struct BindingAddedResponse {
     bound: bool,
+    /// Revision token: pass as `min_revision` to agent evaluate/check calls
+    /// to read at least this fresh.
+    revision: i64,
 }
 
 /// Grant a role to a subject (namespace-wide; the role must exist in the model).
+/// Optionally temporary (`expires_at`) or conditional (`caveat`); granting
+/// an existing binding again replaces its expiry and caveat.
 #[utoipa::path(
     post,
     path = "/orgs/{org}/namespaces/{ns}/datastore/role-bindings",
@@ -636,7 +653,7 @@ struct BindingAddedResponse {
     request_body = RoleBinding,
     responses(
         (status = 200, description = "Role binding added", body = BindingAddedResponse),
-        (status = 400, description = "Role not defined in the model, or scoped binding (unsupported)", body = ProblemDetails),
+        (status = 400, description = "Role not defined in the model, scoped binding (unsupported), expiry in the past, or invalid caveat", body = ProblemDetails),
         (status = 404, description = "No datastore provisioned for this namespace", body = ProblemDetails)
     ),
     security(("bearer_jwt" = []))
@@ -665,16 +682,23 @@ async fn add_binding(
                 .to_string(),
         ));
     }
-    DatastoreRepository::new(&state.db)
-        .add_binding(store.id, &binding)
-        .await?;
-    Ok(Json(BindingAddedResponse { bound: true }))
+    validate_grant_condition(binding.expires_at.as_ref(), binding.caveat.as_deref())
+        .map_err(ApiError::BadRequest)?;
+    let repo = DatastoreRepository::new(&state.db);
+    repo.add_binding(store.id, &binding).await?;
+    Ok(Json(BindingAddedResponse {
+        bound: true,
+        revision: repo.revision(store.id).await?,
+    }))
 }
 
 /// Result of a record delete (`deleted: false` = the record did not exist).
 #[derive(Debug, Serialize, ToSchema)]
 struct RecordDeletedResponse {
     deleted: bool,
+    /// Revision token: pass as `min_revision` to agent evaluate/check calls
+    /// to read at least this fresh.
+    revision: i64,
 }
 
 /// Revoke a role binding (idempotent; `deleted: false` when absent).
@@ -701,10 +725,12 @@ async fn remove_binding(
 ) -> ApiResult<Json<RecordDeletedResponse>> {
     let resolved = authorize(&state, &user, &org, &ns, true).await?;
     let store = require_store(&state, &resolved).await?;
-    let deleted = DatastoreRepository::new(&state.db)
-        .delete_binding(store.id, &binding)
-        .await?;
-    Ok(Json(RecordDeletedResponse { deleted }))
+    let repo = DatastoreRepository::new(&state.db);
+    let deleted = repo.delete_binding(store.id, &binding).await?;
+    Ok(Json(RecordDeletedResponse {
+        deleted,
+        revision: repo.revision(store.id).await?,
+    }))
 }
 
 // ---------------------------------------------------------------------------
@@ -772,9 +798,14 @@ async fn list_tuples(
 #[derive(Debug, Serialize, ToSchema)]
 struct TupleWrittenResponse {
     written: bool,
+    /// Revision token: pass as `min_revision` to agent evaluate/check calls
+    /// to read at least this fresh.
+    revision: i64,
 }
 
 /// Write a relationship tuple (the relation must exist in the model).
+/// Optionally temporary (`expires_at`) or conditional (`caveat`); writing an
+/// existing tuple again replaces its expiry and caveat.
 #[utoipa::path(
     post,
     path = "/orgs/{org}/namespaces/{ns}/datastore/tuples",
@@ -786,7 +817,7 @@ struct TupleWrittenResponse {
     request_body = RelationTuple,
     responses(
         (status = 200, description = "Relationship tuple written", body = TupleWrittenResponse),
-        (status = 400, description = "Relation not defined in the model", body = ProblemDetails),
+        (status = 400, description = "Relation not defined in the model, expiry in the past, or invalid caveat", body = ProblemDetails),
         (status = 404, description = "No datastore provisioned for this namespace", body = ProblemDetails)
     ),
     security(("bearer_jwt" = []))
@@ -805,10 +836,14 @@ async fn write_tuple(
             tuple.relation
         )));
     }
-    DatastoreRepository::new(&state.db)
-        .write_tuple(store.id, &tuple)
-        .await?;
-    Ok(Json(TupleWrittenResponse { written: true }))
+    validate_grant_condition(tuple.expires_at.as_ref(), tuple.caveat.as_deref())
+        .map_err(ApiError::BadRequest)?;
+    let repo = DatastoreRepository::new(&state.db);
+    repo.write_tuple(store.id, &tuple).await?;
+    Ok(Json(TupleWrittenResponse {
+        written: true,
+        revision: repo.revision(store.id).await?,
+    }))
 }
 
 /// Remove a relationship tuple (idempotent; `deleted: false` when absent).
@@ -835,10 +870,114 @@ async fn remove_tuple(
 ) -> ApiResult<Json<RecordDeletedResponse>> {
     let resolved = authorize(&state, &user, &org, &ns, true).await?;
     let store = require_store(&state, &resolved).await?;
-    let deleted = DatastoreRepository::new(&state.db)
-        .delete_tuple(store.id, &tuple)
-        .await?;
-    Ok(Json(RecordDeletedResponse { deleted }))
+    let repo = DatastoreRepository::new(&state.db);
+    let deleted = repo.delete_tuple(store.id, &tuple).await?;
+    Ok(Json(RecordDeletedResponse {
+        deleted,
+        revision: repo.revision(store.id).await?,
+    }))
+}
+
+// ---------------------------------------------------------------------------
+// Import (OpenFGA / SpiceDB)
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Deserialize, ToSchema)]
+struct ImportRequest {
+    /// `openfga` or `spicedb`.
+    format: ImportFormat,
+    /// The authorization model: OpenFGA JSON or DSL, or a SpiceDB schema.
+    schema: String,
+    /// OpenFGA: a JSON array of `{user, relation, object}` tuples.
+    /// SpiceDB: relationships one per line (`document:readme#viewer@user:anne`).
+    #[serde(default)]
+    tuples: Option<String>,
+    /// Convert and report without writing anything.
+    #[serde(default)]
+    dry_run: bool,
+}
+
+/// Records an import wrote (or, on a dry run, would write).
+#[derive(Debug, Serialize, ToSchema)]
+struct ImportCounts {
+    entities: usize,
+    tuples: usize,
+}
+
+/// The converted model and what the conversion could not carry over.
+#[derive(Debug, Serialize, ToSchema)]
+struct ImportResponse {
+    /// The datastore model with the imported types and relations merged in.
+    model: ModelDefinition,
+    counts: ImportCounts,
+    /// Constructs left out or approximated (rewrites, permissions,
+    /// wildcards, conditions, usersets).
+    issues: Vec<ImportIssue>,
+    /// False on a dry run.
+    applied: bool,
+    /// Revision token: pass as `min_revision` to agent evaluate/check calls
+    /// to read at least this fresh.
+    revision: i64,
+}
+
+/// Import an OpenFGA authorization model or SpiceDB schema, with its
+/// tuples, into the datastore. Types and directly assignable relations are
+/// merged into the model additively; tuples and the entities they name are
+/// written in one transaction (existing entities keep their attributes),
+/// and the merged model is recorded as a new model version.
+/// Unsupported constructs are reported in `issues`, never approximated in a
+/// way that widens access. Publish afterwards to ship the data.
+#[utoipa::path(
+    post,
+    path = "/orgs/{org}/namespaces/{ns}/datastore/import",
+    tag = "datastore",
+    params(
+        ("org" = String, Path, description = "Organization ID or slug"),
+        ("ns" = String, Path, description = "Namespace slug")
+    ),
+    request_body = ImportRequest,
+    responses(
+        (status = 200, description = "Model and tuples imported (or converted, on a dry run)", body = ImportResponse),
+        (status = 400, description = "Schema or tuples could not be parsed", body = ProblemDetails),
+        (status = 404, description = "No datastore provisioned for this namespace", body = ProblemDetails),
+        (status = 409, description = "An imported relation conflicts with one already in the model, \
+            or the model changed concurrently (retry)", body = ProblemDetails)
+    ),
+    security(("bearer_jwt" = []))
+)]
+async fn import_model(
+    State(state): State<Arc<AppState>>,
+    RequireAuth(user): RequireAuth,
+    Path((org, ns)): Path<(String, String)>,
+    Json(req): Json<ImportRequest>,
+) -> ApiResult<Json<ImportResponse>> {
+    let resolved = authorize(&state, &user, &org, &ns, true).await?;
+    let store = require_store(&state, &resolved).await?;
+    let import = datastore::import_model(req.format, &req.schema, req.tuples.as_deref())
+        .map_err(ApiError::BadRequest)?;
+    let model = merge_model(&store.model, &import.model).map_err(ApiError::Conflict)?;
+    let repo = DatastoreRepository::new(&state.db);
+    if !req.dry_run {
+        repo.import_records(
+            &store,
+            &model,
+            &import.entities,
+            &import.tuples,
+            &user.id.to_string(),
+        )
+        .await
+        .map_err(model_conflict_to_api)?;
+    }
+    Ok(Json(ImportResponse {
+        model,
+        counts: ImportCounts {
+            entities: import.entities.len(),
+            tuples: import.tuples.len(),
+        },
+        issues: import.issues,
+        applied: !req.dry_run,
+        revision: repo.revision(store.id).await?,
+    }))
 }
 
 // ---------------------------------------------------------------------------
@@ -853,6 +992,9 @@ struct PublishResponse {
     checksum: String,
     counts: RecordCounts,
     published_at: String,
+    /// Revision token of the published snapshot: agents that loaded this
+    /// version satisfy `min_revision` up to it.
+    revision: i64,
 }
 
 /// Cut a new immutable, checksummed data-bundle version and wake the fleet.
@@ -892,6 +1034,7 @@ async fn publish(
             tuples: published.tuple_count,
         },
         published_at: published.published_at.clone(),
+        revision: published.change_seq,
     }))
 }
 
@@ -1068,6 +1211,8 @@ struct PublishedRef {
     version: i64,
     /// `sha256:<hex>` over the materialized document.
     checksum: String,
+    /// Revision token of the published snapshot (see [`PublishResponse`]).
+    revision: i64,
 }
 
 /// Result of an atomically applied migration + the publish it triggered.
@@ -1137,6 +1282,7 @@ async fn apply_migration_inner(
         published: PublishedRef {
             version: published.version,
             checksum: published.checksum,
+            revision: published.change_seq,
         },
     };
     let body = serde_json::to_value(&response)
@@ -1285,6 +1431,7 @@ async fn rollback_migration(
         published: PublishedRef {
             version: published.version,
             checksum: published.checksum,
+            revision: published.change_seq,
         },
     }))
 }
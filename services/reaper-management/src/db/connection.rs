@@ -128,6 +128,11 @@ const PG_MIGRATIONS: &[(i64, &str, &str)] = &[
         "bundle_row_version",
         include_str!("migrations_pg/0022_bundle_row_version.sql"),
     ),
+    (
+        23,
+        "adm_conditional_grants",
+        include_str!("migrations_pg/0023_adm_conditional_grants.sql"),
+    ),
 ];
 
 static INSTALL_DRIVERS: Once = Once::new();
@@ -439,6 +444,7 @@ impl Database {
             include_str!("migrations/027_agent_decision_quality.sql"),
             include_str!("migrations/028_decision_quality_rollback.sql"),
             include_str!("migrations/029_bundle_row_version.sql"),
+            include_str!("migrations/030_adm_conditional_grants.sql"),
         ];
 
         for (idx, migration_sql) in migrations.iter().enumerate() {
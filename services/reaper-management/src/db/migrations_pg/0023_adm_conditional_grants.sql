@@ -0,0 +1,9 @@
+-- Conditional grants: a role binding or relationship tuple may expire and may
+-- carry a caveat (a .reap condition over the request context). Both columns
+-- are materialized into the data bundle; the agent hides an expired edge at
+-- check time, so a temporary grant lapses without a cleanup job or republish.
+-- expires_at is RFC 3339 text like every other ADM timestamp; NULL = never.
+ALTER TABLE adm_role_bindings ADD COLUMN IF NOT EXISTS expires_at TEXT;
+ALTER TABLE adm_role_bindings ADD COLUMN IF NOT EXISTS caveat TEXT;
+ALTER TABLE adm_tuples ADD COLUMN IF NOT EXISTS expires_at TEXT;
+ALTER TABLE adm_tuples ADD COLUMN IF NOT EXISTS caveat TEXT;
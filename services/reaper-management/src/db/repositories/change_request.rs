@@ -142,6 +142,27 @@ impl<'a> ChangeRequestRepository<'a> {
         rows.iter().map(Self::row_to_cr).collect()
     }
 
+    /// The most recent change request applied to `to_env_id` that was
+    /// created before `cr` — what the environment ran before `cr` (the
+    /// baseline of its outcome diff).
+    pub async fn last_applied_before(
+        &self,
+        cr: &ChangeRequest,
+    ) -> Result<Option<ChangeRequest>, DatabaseError> {
+        let pool = self.pool()?;
+        let row = sqlx::query(&format!(
+            "{CR_COLUMNS} WHERE org_id = $1 AND to_env_id = $2 AND status = $3 \
+             AND created_at < $4 ORDER BY created_at DESC LIMIT 1"
+        ))
+        .bind(cr.org_id.to_string())
+        .bind(cr.to_env_id.to_string())
+        .bind(ChangeRequestStatus::Applied.as_str())
+        .bind(cr.created_at.to_rfc3339())
+        .fetch_optional(pool)
+        .await?;
+        row.map(|r| Self::row_to_cr(&r)).transpose()
+    }
+
     /// Record (or update) an approver's decision. One row per approver per
     /// request; a re-vote replaces the prior decision.
     pub async fn record_decision(
@@ -253,6 +253,18 @@ impl<'a> DatastoreRepository<'a> {
         Ok(())
     }
 
+    /// Head of the change stream — the revision token write endpoints hand
+    /// back. Read after the write commits, so it is at least the write's own
+    /// seq; a concurrent writer can only push it higher, which makes the
+    /// token stricter, never weaker.
+    pub async fn revision(&self, datastore_id: Uuid) -> Result<i64, DatabaseError> {
+        let row = sqlx::query("SELECT change_seq FROM datastores WHERE id = $1")
+            .bind(datastore_id.to_string())
+            .fetch_one(self.pool()?)
+            .await?;
+        Ok(row.get("change_seq"))
+    }
+
     /// Dirty markers since `since_seq` (exclusive), deduplicated to the
     /// LATEST mark per entity (an entity churned 50 times syncs once).
     /// Returns (head_seq, min_available_seq, marks).
@@ -336,7 +348,7 @@ impl<'a> DatastoreRepository<'a> {
             .await?;
         let pool = self.pool()?;
         let rows = sqlx::query(
-            "SELECT object, relation, subject FROM adm_tuples              WHERE datastore_id = $1 AND (object = $2 OR subject = $3)              ORDER BY object, relation, subject",
+            "SELECT object, relation, subject, expires_at, caveat FROM adm_tuples              WHERE datastore_id = $1 AND (object = $2 OR subject = $3)              ORDER BY object, relation, subject",
         )
         .bind(datastore_id.to_string())
         .bind(entity_id)
@@ -349,6 +361,8 @@ impl<'a> DatastoreRepository<'a> {
                 object: row.get("object"),
                 relation: row.get("relation"),
                 subject: row.get("subject"),
+                expires_at: parse_expiry(row.get("expires_at")),
+                caveat: row.get("caveat"),
             })
             .collect();
         Ok((entity, bindings, tuples))
@@ -588,15 +602,19 @@ impl<'a> DatastoreRepository<'a> {
         let pool = self.pool()?;
         let mut tx = pool.begin().await?;
         sqlx::query(
-            r#"INSERT INTO adm_role_bindings (id, datastore_id, subject, role, scope, created_at)
-               VALUES ($1, $2, $3, $4, $5, $6)
-               ON CONFLICT(datastore_id, subject, role, scope) DO NOTHING"#,
+            r#"INSERT INTO adm_role_bindings
+                   (id, datastore_id, subject, role, scope, expires_at, caveat, created_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+               ON CONFLICT(datastore_id, subject, role, scope)
+               DO UPDATE SET expires_at = excluded.expires_at, caveat = excluded.caveat"#,
         )
         .bind(Uuid::new_v4().to_string())
         .bind(datastore_id.to_string())
         .bind(&binding.subject)
         .bind(&binding.role)
         .bind(&binding.scope)
+        .bind(binding.expires_at.map(|t| t.to_rfc3339()))
+        .bind(binding.caveat.as_deref())
         .bind(Utc::now().to_rfc3339())
         .execute(&mut *tx)
         .await?;
@@ -641,7 +659,8 @@ impl<'a> DatastoreRepository<'a> {
     ) -> Result<Vec<RoleBinding>, DatabaseError> {
         let pool = self.pool()?;
         let mut sql = String::from(
-            "SELECT subject, role, scope FROM adm_role_bindings WHERE datastore_id = ?",
+            "SELECT subject, role, scope, expires_at, caveat FROM adm_role_bindings \
+             WHERE datastore_id = ?",
         );
         if subject.is_some() {
             sql.push_str(" AND subject = ?");
@@ -666,6 +685,8 @@ impl<'a> DatastoreRepository<'a> {
                 subject: row.get("subject"),
                 role: row.get("role"),
                 scope: row.get("scope"),
+                expires_at: parse_expiry(row.get("expires_at")),
+                caveat: row.get("caveat"),
             })
             .collect())
     }
@@ -683,7 +704,7 @@ impl<'a> DatastoreRepository<'a> {
         let pool = self.pool()?;
 
         let mut sql = String::from(
-            "SELECT id, subject, role, scope, created_at \
+            "SELECT id, subject, role, scope, expires_at, caveat, created_at \
              FROM adm_role_bindings WHERE datastore_id = $1",
         );
         let mut next = 2;
@@ -716,6 +737,8 @@ impl<'a> DatastoreRepository<'a> {
                     subject: row.get("subject"),
                     role: row.get("role"),
                     scope: row.get("scope"),
+                    expires_at: parse_expiry(row.get("expires_at")),
+                    caveat: row.get("caveat"),
                 },
                 created_at: row.get("created_at"),
                 row_id: row.get("id"),
@@ -735,15 +758,19 @@ impl<'a> DatastoreRepository<'a> {
         let pool = self.pool()?;
         let mut tx = pool.begin().await?;
         sqlx::query(
-            r#"INSERT INTO adm_tuples (id, datastore_id, object, relation, subject, created_at)
-               VALUES ($1, $2, $3, $4, $5, $6)
-               ON CONFLICT(datastore_id, object, relation, subject) DO NOTHING"#,
+            r#"INSERT INTO adm_tuples
+                   (id, datastore_id, object, relation, subject, expires_at, caveat, created_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+               ON CONFLICT(datastore_id, object, relation, subject)
+               DO UPDATE SET expires_at = excluded.expires_at, caveat = excluded.caveat"#,
         )
         .bind(Uuid::new_v4().to_string())
         .bind(datastore_id.to_string())
         .bind(&tuple.object)
         .bind(&tuple.relation)
         .bind(&tuple.subject)
+        .bind(tuple.expires_at.map(|t| t.to_rfc3339()))
+        .bind(tuple.caveat.as_deref())
         .bind(Utc::now().to_rfc3339())
         .execute(&mut *tx)
         .await?;
@@ -792,6 +819,81 @@ impl<'a> DatastoreRepository<'a> {
         Ok(result.rows_affected() > 0)
     }
 
+    /// Write an imported model and its records in ONE transaction: the
+    /// (additively merged) model, entity records that do not exist yet
+    /// (existing ones keep their type and attributes), and the tuples
+    /// (re-imported tuples take the new expiry). A failure leaves nothing
+    /// half-imported.
+    ///
+    /// The model is written as a new model version with a history row, like
+    /// a migration with no transforms, and is guarded on
+    /// `store.model_version`: a model change since `store` was read fails
+    /// with [`DatabaseError::VersionConflict`] instead of being overwritten.
+    /// Returns the new model version.
+    pub async fn import_records(
+        &self,
+        store: &DatastoreRecord,
+        model: &ModelDefinition,
+        entities: &[AdmEntity],
+        tuples: &[RelationTuple],
+        author: &str,
+    ) -> Result<i64, DatabaseError> {
+        let pool = self.pool()?;
+        let datastore_id = store.id;
+        let now = Utc::now().to_rfc3339();
+        let mut tx = pool.begin().await?;
+        let model_version =
+            Self::bump_model_in(&mut tx, store, &store.model, model, "[]", author, "import")
+                .await?;
+        for entity in entities {
+            let attrs = serde_json::to_string(&entity.attributes)
+                .map_err(|e| DatabaseError::Config(format!("serialize attributes: {e}")))?;
+            sqlx::query(
+                r#"INSERT INTO adm_entities
+                   (id, datastore_id, entity_id, entity_type, attributes, created_at, updated_at)
+                   VALUES ($1, $2, $3, $4, $5, $6, $7)
+                   ON CONFLICT(datastore_id, entity_id) DO NOTHING"#,
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(datastore_id.to_string())
+            .bind(&entity.entity_id)
+            .bind(&entity.entity_type)
+            .bind(&attrs)
+            .bind(&now)
+            .bind(&now)
+            .execute(&mut *tx)
+            .await?;
+        }
+        for tuple in tuples {
+            sqlx::query(
+                r#"INSERT INTO adm_tuples
+                       (id, datastore_id, object, relation, subject, expires_at, caveat, created_at)
+                   VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                   ON CONFLICT(datastore_id, object, relation, subject)
+                   DO UPDATE SET expires_at = excluded.expires_at, caveat = excluded.caveat"#,
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(datastore_id.to_string())
+            .bind(&tuple.object)
+            .bind(&tuple.relation)
+            .bind(&tuple.subject)
+            .bind(tuple.expires_at.map(|t| t.to_rfc3339()))
+            .bind(tuple.caveat.as_deref())
+            .bind(&now)
+            .execute(&mut *tx)
+            .await?;
+        }
+        // Every entity an imported tuple touches is also an imported
+        // entity, so the entity list is the full dirty set.
+        let marks: Vec<(String, bool)> = entities
+            .iter()
+            .map(|e| (e.entity_id.clone(), false))
+            .collect();
+        Self::record_changes_in(&mut tx, datastore_id, &marks).await?;
+        tx.commit().await?;
+        Ok(model_version)
+    }
+
     /// Full tuple set — INTERNAL callers only (publish materialization and
     /// migration planning). The HTTP list endpoint goes through
     /// [`Self::list_tuples_page`] (R2-01).
@@ -803,8 +905,10 @@ impl<'a> DatastoreRepository<'a> {
         subject: Option<&str>,
     ) -> Result<Vec<RelationTuple>, DatabaseError> {
         let pool = self.pool()?;
-        let mut sql =
-            String::from("SELECT object, relation, subject FROM adm_tuples WHERE datastore_id = ?");
+        let mut sql = String::from(
+            "SELECT object, relation, subject, expires_at, caveat FROM adm_tuples \
+             WHERE datastore_id = ?",
+        );
         if object.is_some() {
             sql.push_str(" AND object = ?");
         }
@@ -828,6 +932,8 @@ impl<'a> DatastoreRepository<'a> {
                 object: row.get("object"),
                 relation: row.get("relation"),
                 subject: row.get("subject"),
+                expires_at: parse_expiry(row.get("expires_at")),
+                caveat: row.get("caveat"),
             })
             .collect())
     }
@@ -846,7 +952,7 @@ impl<'a> DatastoreRepository<'a> {
         let pool = self.pool()?;
 
         let mut sql = String::from(
-            "SELECT id, object, relation, subject, created_at \
+            "SELECT id, object, relation, subject, expires_at, caveat, created_at \
              FROM adm_tuples WHERE datastore_id = $1",
         );
         let mut next = 2;
@@ -883,6 +989,8 @@ impl<'a> DatastoreRepository<'a> {
                     object: row.get("object"),
                     relation: row.get("relation"),
                     subject: row.get("subject"),
+                    expires_at: parse_expiry(row.get("expires_at")),
+                    caveat: row.get("caveat"),
                 },
                 created_at: row.get("created_at"),
                 row_id: row.get("id"),
@@ -921,17 +1029,8 @@ impl<'a> DatastoreRepository<'a> {
 
         let pool = self.pool()?;
         let now = Utc::now().to_rfc3339();
-        let model_json = serde_json::to_string(&plan.model_after)
-            .map_err(|e| DatabaseError::Config(format!("serialize model: {e}")))?;
         let transforms_json = serde_json::to_string(&plan.transforms)
             .map_err(|e| DatabaseError::Config(format!("serialize transforms: {e}")))?;
-        let model_hash = |m: &ModelDefinition| -> Result<String, DatabaseError> {
-            let json = serde_json::to_string(m)
-                .map_err(|e| DatabaseError::Config(format!("hash model: {e}")))?;
-            Ok(format!("sha256:{:x}", Sha256::digest(json.as_bytes())))
-        };
-        let before_hash = model_hash(&plan.model_before)?;
-        let after_hash = model_hash(&plan.model_after)?;
 
         let mut tx = pool.begin().await?;
 
@@ -1016,6 +1115,50 @@ impl<'a> DatastoreRepository<'a> {
         // committed since, this UPDATE matches zero rows and the WHOLE
         // transaction — including the wholesale entity rewrite from the now
         // stale plan snapshot — rolls back instead of silently clobbering.
+        // (d) append-only history row.
+        let model_version = Self::bump_model_in(
+            &mut tx,
+            store,
+            &plan.model_before,
+            &plan.model_after,
+            &transforms_json,
+            author,
+            "migration",
+        )
+        .await?;
+
+        // (e) outbox markers for every changed document — inside the SAME
+        // transaction (the D2 invariant: mutation + log commit together).
+        Self::record_changes_in(&mut tx, store.id, dirty).await?;
+
+        tx.commit().await?;
+        Ok(model_version)
+    }
+
+    /// Write `model_after` as the next model version inside the caller's
+    /// transaction, guarded on `store.model_version` (R2-04), and append its
+    /// history row. `what` names the writer in the conflict error. On
+    /// conflict the caller's transaction must not commit: dropping it rolls
+    /// everything back.
+    async fn bump_model_in(
+        tx: &mut sqlx::Transaction<'_, sqlx::Any>,
+        store: &DatastoreRecord,
+        model_before: &ModelDefinition,
+        model_after: &ModelDefinition,
+        transforms_json: &str,
+        author: &str,
+        what: &str,
+    ) -> Result<i64, DatabaseError> {
+        let now = Utc::now().to_rfc3339();
+        let model_hash = |m: &ModelDefinition| -> Result<(String, String), DatabaseError> {
+            let json = serde_json::to_string(m)
+                .map_err(|e| DatabaseError::Config(format!("serialize model: {e}")))?;
+            let hash = format!("sha256:{:x}", Sha256::digest(json.as_bytes()));
+            Ok((json, hash))
+        };
+        let (model_before_json, before_hash) = model_hash(model_before)?;
+        let (model_json, after_hash) = model_hash(model_after)?;
+
         let expected_model_version = store.model_version;
         let row = sqlx::query(
             "UPDATE datastores SET model = $1, model_version = model_version + 1, \
@@ -1026,28 +1169,24 @@ impl<'a> DatastoreRepository<'a> {
         .bind(&now)
         .bind(store.id.to_string())
         .bind(expected_model_version)
-        .fetch_optional(&mut *tx)
+        .fetch_optional(&mut **tx)
         .await?;
         let Some(row) = row else {
-            tx.rollback().await?;
             // Best-effort read of the winner's version for the error message.
             let actual: i64 = sqlx::query("SELECT model_version FROM datastores WHERE id = $1")
                 .bind(store.id.to_string())
-                .fetch_optional(pool)
+                .fetch_optional(&mut **tx)
                 .await?
                 .map(|r| r.get("model_version"))
                 .unwrap_or(-1);
             return Err(DatabaseError::VersionConflict(format!(
-                "datastore model changed concurrently: migration was planned against \
+                "datastore model changed concurrently: {what} was planned against \
                  model_version {expected_model_version}, but the current model_version \
                  is {actual} — re-plan against the current model and retry"
             )));
         };
         let model_version: i64 = row.get("model_version");
 
-        // (d) append-only history row.
-        let model_before_json = serde_json::to_string(&plan.model_before)
-            .map_err(|e| DatabaseError::Config(format!("serialize model_before: {e}")))?;
         sqlx::query(
             r#"INSERT INTO adm_model_versions
                (id, datastore_id, model_version, transforms, author,
@@ -1057,21 +1196,15 @@ impl<'a> DatastoreRepository<'a> {
         .bind(Uuid::new_v4().to_string())
         .bind(store.id.to_string())
         .bind(model_version)
-        .bind(&transforms_json)
+        .bind(transforms_json)
         .bind(author)
         .bind(&before_hash)
         .bind(&after_hash)
         .bind(&model_before_json)
         .bind(&model_json)
         .bind(&now)
-        .execute(&mut *tx)
+        .execute(&mut **tx)
         .await?;
-
-        // (e) outbox markers for every changed document — inside the SAME
-        // transaction (the D2 invariant: mutation + log commit together).
-        Self::record_changes_in(&mut tx, store.id, dirty).await?;
-
-        tx.commit().await?;
         Ok(model_version)
     }
 
@@ -1392,3 +1525,14 @@ impl<'a> DatastoreRepository<'a> {
         }))
     }
 }
+
+/// Stored grant expiry (RFC 3339 text). Written only by this repository; a
+/// hand-edited row that no longer parses reads as long expired — fail
+/// closed, never as a grant that holds forever.
+fn parse_expiry(raw: Option<String>) -> Option<chrono::DateTime<Utc>> {
+    raw.map(|s| {
+        chrono::DateTime::parse_from_rfc3339(&s)
+            .map(|t| t.with_timezone(&Utc))
+            .unwrap_or(chrono::DateTime::<Utc>::UNIX_EPOCH)
+    })
+}
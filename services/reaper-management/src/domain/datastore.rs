@@ -4,10 +4,12 @@
 //! attributes, roles + permissions, relations) and holds records (entities,
 //! role bindings, relationship tuples). `materialize` compiles everything to
 //! the exact policy-engine DataLoader format, so reapers consume it with
-//! zero engine changes. See docs/development/DATA_PLANE_PLAN.md.
+//! zero engine changes. `import_model` maps an OpenFGA or SpiceDB model and
+//! its tuples onto the same vocabulary. See docs/development/DATA_PLANE_PLAN.md.
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use utoipa::ToSchema;
 
 // ---------------------------------------------------------------------------
@@ -279,6 +281,14 @@ pub struct RoleBinding {
     /// "" = namespace-wide.
     #[serde(default)]
     pub scope: String,
+    /// The binding lapses at this instant (agents stop honouring it without
+    /// a republish). Absent = never.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// A `.reap` condition over `context` the request must meet for the
+    /// binding to hold, e.g. `context.ip_in_office == true`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caveat: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
@@ -286,6 +296,37 @@ pub struct RelationTuple {
     pub object: String,
     pub relation: String,
     pub subject: String,
+    /// The tuple lapses at this instant. Absent = never.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// A `.reap` condition over `context` the request must meet for the
+    /// tuple to hold.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caveat: Option<String>,
+}
+
+/// Validate a grant's expiry and caveat before it is stored: an expiry
+/// already past is almost certainly a mistake, and a caveat the engine
+/// cannot parse would fail the agents' data load.
+pub fn validate_grant_condition(
+    expires_at: Option<&DateTime<Utc>>,
+    caveat: Option<&str>,
+) -> Result<(), String> {
+    if let Some(expires_at) = expires_at {
+        if *expires_at <= Utc::now() {
+            return Err(format!(
+                "expires_at {} is in the past",
+                expires_at.to_rfc3339()
+            ));
+        }
+    }
+    if let Some(caveat) = caveat {
+        if caveat.trim().is_empty() {
+            return Err("caveat must not be empty".to_string());
+        }
+        policy_engine::data::validate_caveat(caveat).map_err(|e| e.to_string())?;
+    }
+    Ok(())
 }
 
 // ---------------------------------------------------------------------------
@@ -300,10 +341,15 @@ pub struct RelationTuple {
 ///   interned lookup at eval). Scoped bindings are rejected at the API
 ///   until the materializer represents them (D2) — a scoped grant must
 ///   never silently widen to a global one.
+/// - a binding with an expiry or caveat cannot be an attribute (attributes
+///   hold unconditionally); it becomes conditional edges the subject
+///   carries instead — `role` to the role name and `permission` to each of
+///   its permissions — read with `rebac::related("admin", "role", user)`
 /// - tuples become graph edges with DIRECTION decided by the relation
 ///   definition: traversal relations (member_of) land on the SUBJECT
 ///   (subject → object, the direction rebac::reachable walks); everything
-///   else lands on the OBJECT (what rebac::related reads)
+///   else lands on the OBJECT (what rebac::related reads). An expiry or
+///   caveat rides along as the loader's object form of the subject
 /// - entities referenced only by tuples get a minimal synthesized record
 ///   (type inferred from the relation definition)
 ///
@@ -317,11 +363,20 @@ pub fn materialize(
     bindings: &[RoleBinding],
     tuples: &[RelationTuple],
 ) -> serde_json::Value {
-    use std::collections::BTreeSet;
-
     // subject -> (roles, permissions); BTreeSet = dedup + stable order.
     let mut roles_by_subject: BTreeMap<&str, (BTreeSet<&str>, BTreeSet<&str>)> = BTreeMap::new();
+    // subject -> the edges its conditional bindings materialize to.
+    let mut conditional_roles: BTreeMap<&str, Edges<'_>> = BTreeMap::new();
     for b in bindings.iter().filter(|b| b.scope.is_empty()) {
+        let grant = Grant::of_binding(b);
+        if !grant.is_unconditional() {
+            add_binding_edges(
+                model,
+                conditional_roles.entry(b.subject.as_str()).or_default(),
+                b,
+            );
+            continue;
+        }
         let entry = roles_by_subject.entry(b.subject.as_str()).or_default();
         entry.0.insert(b.role.as_str());
         if let Some(role) = model.role(&b.role) {
@@ -330,7 +385,7 @@ pub fn materialize(
     }
 
     // carrier entity -> relation -> {targets}, direction per relation def.
-    let mut rels_by_carrier: BTreeMap<&str, BTreeMap<&str, BTreeSet<&str>>> = BTreeMap::new();
+    let mut rels_by_carrier: BTreeMap<&str, Edges<'_>> = BTreeMap::new();
     // carrier -> relation name, for type inference of synthesized entities.
     let mut carrier_relation: BTreeMap<&str, (&str, bool)> = BTreeMap::new();
     for t in tuples {
@@ -340,12 +395,12 @@ pub fn materialize(
         } else {
             (t.object.as_str(), t.subject.as_str())
         };
-        rels_by_carrier
-            .entry(carrier)
-            .or_default()
-            .entry(t.relation.as_str())
-            .or_default()
-            .insert(target);
+        add_edge(
+            rels_by_carrier.entry(carrier).or_default(),
+            t.relation.as_str(),
+            target,
+            Grant::of_tuple(t),
+        );
         carrier_relation
             .entry(carrier)
             .or_insert((t.relation.as_str(), traversal));
@@ -368,8 +423,15 @@ pub fn materialize(
             "type": e.entity_type,
             "attributes": attributes,
         });
-        if let Some(rels) = rels_by_carrier.get(e.entity_id.as_str()) {
-            doc["relationships"] = serde_json::json!(rels);
+        let mut rels = rels_by_carrier
+            .get(e.entity_id.as_str())
+            .cloned()
+            .unwrap_or_default();
+        if let Some(roles) = conditional_roles.get(e.entity_id.as_str()) {
+            merge_edges(&mut rels, roles);
+        }
+        if !rels.is_empty() {
+            doc["relationships"] = edges_json(&rels);
         }
         docs.push(doc);
     }
@@ -398,7 +460,7 @@ pub fn materialize(
             "id": carrier,
             "type": inferred_type,
             "attributes": {},
-            "relationships": rels,
+            "relationships": edges_json(rels),
         }));
     }
 
@@ -419,10 +481,8 @@ pub fn materialize_one(
     bindings: &[RoleBinding],
     touching_tuples: &[RelationTuple],
 ) -> Option<serde_json::Value> {
-    use std::collections::BTreeSet;
-
     // Edges THIS entity carries, direction-resolved.
-    let mut rels: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    let mut rels: Edges<'_> = BTreeMap::new();
     for t in touching_tuples {
         let traversal = model.relation(&t.relation).is_some_and(|d| d.traversal);
         let (carrier, target) = if traversal {
@@ -431,7 +491,7 @@ pub fn materialize_one(
             (t.object.as_str(), t.subject.as_str())
         };
         if carrier == entity_id {
-            rels.entry(t.relation.as_str()).or_default().insert(target);
+            add_edge(&mut rels, t.relation.as_str(), target, Grant::of_tuple(t));
         }
     }
 
@@ -442,7 +502,12 @@ pub fn materialize_one(
     let mut attributes = entity.map(|e| e.attributes.clone()).unwrap_or_default();
     let mut roles: BTreeSet<&str> = BTreeSet::new();
     let mut perms: BTreeSet<&str> = BTreeSet::new();
+    let mut conditional_roles: Edges<'_> = BTreeMap::new();
     for b in bindings.iter().filter(|b| b.scope.is_empty()) {
+        if !Grant::of_binding(b).is_unconditional() {
+            add_binding_edges(model, &mut conditional_roles, b);
+            continue;
+        }
         roles.insert(b.role.as_str());
         if let Some(role) = model.role(&b.role) {
             perms.extend(role.permissions.iter().map(String::as_str));
@@ -453,6 +518,8 @@ pub fn materialize_one(
         attributes.insert("permissions".into(), serde_json::json!(perms));
     }
 
+    // Resolve the type from the tuple edges alone, before the role edges
+    // join them — full materialize() infers it the same way.
     let entity_type = entity.map(|e| e.entity_type.clone()).unwrap_or_else(|| {
         // Synthesized carrier: infer from any carried relation definition.
         rels.keys()
@@ -468,17 +535,1085 @@ pub fn materialize_one(
             .unwrap_or_else(|| "resource".into())
     });
 
+    merge_edges(&mut rels, &conditional_roles);
+
     let mut doc = serde_json::json!({
         "id": entity_id,
         "type": entity_type,
         "attributes": attributes,
     });
     if !rels.is_empty() {
-        doc["relationships"] = serde_json::json!(rels);
+        doc["relationships"] = edges_json(&rels);
     }
     Some(doc)
 }
 
+/// Relation a conditional role binding materializes to (subject → role).
+pub const ROLE_RELATION: &str = "role";
+/// Relation a conditional role binding's permissions materialize to
+/// (subject → permission).
+pub const PERMISSION_RELATION: &str = "permission";
+
+/// When a materialized edge holds: always, or until an expiry / under a
+/// caveat.
+#[derive(Debug, Clone, Copy)]
+struct Grant<'a> {
+    expires_at: Option<&'a DateTime<Utc>>,
+    caveat: Option<&'a str>,
+}
+
+impl<'a> Grant<'a> {
+    fn of_tuple(t: &'a RelationTuple) -> Self {
+        Self {
+            expires_at: t.expires_at.as_ref(),
+            caveat: t.caveat.as_deref(),
+        }
+    }
+
+    fn of_binding(b: &'a RoleBinding) -> Self {
+        Self {
+            expires_at: b.expires_at.as_ref(),
+            caveat: b.caveat.as_deref(),
+        }
+    }
+
+    fn is_unconditional(&self) -> bool {
+        self.expires_at.is_none() && self.caveat.is_none()
+    }
+}
+
+/// relation -> target -> grant; BTreeMaps keep the output deterministic.
+type Edges<'a> = BTreeMap<&'a str, BTreeMap<&'a str, Grant<'a>>>;
+
+/// Record an edge. The same edge granted twice keeps the first grant unless
+/// the newcomer is unconditional — it holds whenever the other would.
+fn add_edge<'a>(edges: &mut Edges<'a>, relation: &'a str, target: &'a str, grant: Grant<'a>) {
+    let slot = edges
+        .entry(relation)
+        .or_default()
+        .entry(target)
+        .or_insert(grant);
+    if grant.is_unconditional() {
+        *slot = grant;
+    }
+}
+
+fn merge_edges<'a>(into: &mut Edges<'a>, from: &Edges<'a>) {
+    for (relation, targets) in from {
+        for (target, grant) in targets {
+            add_edge(into, relation, target, *grant);
+        }
+    }
+}
+
+fn add_binding_edges<'a>(model: &'a ModelDefinition, edges: &mut Edges<'a>, b: &'a RoleBinding) {
+    let grant = Grant::of_binding(b);
+    add_edge(edges, ROLE_RELATION, b.role.as_str(), grant);
+    if let Some(role) = model.role(&b.role) {
+        for permission in &role.permissions {
+            add_edge(edges, PERMISSION_RELATION, permission.as_str(), grant);
+        }
+    }
+}
+
+/// The loader's `relationships` block: bare subject ids for unconditional
+/// edges (so unconditional data materializes byte-identically), the object
+/// form for the rest.
+fn edges_json(edges: &Edges<'_>) -> serde_json::Value {
+    let block: serde_json::Map<String, serde_json::Value> = edges
+        .iter()
+        .map(|(relation, targets)| {
+            let subjects = targets
+                .iter()
+                .map(|(target, grant)| {
+                    if grant.is_unconditional() {
+                        return serde_json::json!(target);
+                    }
+                    let mut subject = serde_json::json!({ "subject": target });
+                    if let Some(expires_at) = grant.expires_at {
+                        subject["expires_at"] = serde_json::json!(
+                            expires_at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+                        );
+                    }
+                    if let Some(caveat) = grant.caveat {
+                        subject["caveat"] = serde_json::json!(caveat);
+                    }
+                    subject
+                })
+                .collect();
+            (relation.to_string(), serde_json::Value::Array(subjects))
+        })
+        .collect();
+    serde_json::Value::Object(block)
+}
+
+// ---------------------------------------------------------------------------
+// Import: OpenFGA / SpiceDB
+// ---------------------------------------------------------------------------
+
+/// Schema language of an external authorization model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportFormat {
+    /// OpenFGA authorization model (JSON or DSL); tuples as a JSON array of
+    /// `{user, relation, object}` (or a Read response's `{"tuples": […]}`).
+    Openfga,
+    /// SpiceDB schema; relationships one per line,
+    /// `document:readme#viewer@user:anne`.
+    Spicedb,
+}
+
+/// A construct the importer left out or could only approximate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct ImportIssue {
+    /// `type#relation` in the schema, or `tuple N` (1-based) in the tuples.
+    pub location: String,
+    pub construct: String,
+    pub reason: String,
+    /// How many tuples hit the same issue (`location` is the first).
+    pub occurrences: usize,
+}
+
+/// An external model mapped onto the ADM.
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct ModelImport {
+    pub model: ModelDefinition,
+    /// One attribute-less record per object and subject the tuples name.
+    pub entities: Vec<AdmEntity>,
+    pub tuples: Vec<RelationTuple>,
+    pub issues: Vec<ImportIssue>,
+}
+
+/// Convert an OpenFGA or SpiceDB model (plus, optionally, its tuples) to a
+/// [`ModelDefinition`] and ADM records.
+///
+/// Types become entity types and every directly assignable relation a
+/// [`RelationDef`]; ids keep their type prefix (`document:readme`), which
+/// is what requests must then name. Relations used as a userset subject
+/// (`[group#member]`) become traversal relations, so
+/// `rebac::reachable(user, "viewer", resource, "member", depth)` grants
+/// through the group; a userset tuple naming any other relation
+/// (`group:eng#admin`) is reported, since its edge would be walked as
+/// membership. A relation name declared on several types is recorded on the
+/// first, and tuples on the others are reported. Everything the ADM has no
+/// place for is reported rather than approximated in a way that could widen
+/// access: rewrites (`viewer or editor`, `parent->view`, intersections,
+/// exclusions) and permissions, wildcards and conditional tuples are left
+/// out. SpiceDB `expiration` tuples keep their expiry.
+///
+/// Errors only when the schema or tuples cannot be read at all.
+pub fn import_model(
+    format: ImportFormat,
+    schema: &str,
+    tuples: Option<&str>,
+) -> Result<ModelImport, String> {
+    let mut issues = Vec::new();
+    let types = match format {
+        ImportFormat::Openfga if schema.trim_start().starts_with('{') => {
+            parse_openfga_json(schema, &mut issues)?
+        }
+        ImportFormat::Openfga => parse_openfga_dsl(schema, &mut issues)?,
+        ImportFormat::Spicedb => parse_spicedb_schema(schema, &mut issues)?,
+    };
+    let model = build_model(&types, &mut issues);
+    let raw = match (format, tuples) {
+        (_, None) => Vec::new(),
+        (ImportFormat::Openfga, Some(src)) => parse_openfga_tuples(src)?,
+        (ImportFormat::Spicedb, Some(src)) => parse_spicedb_tuples(src)?,
+    };
+    let (entities, tuples) = import_tuples(&types, &model, raw, &mut issues);
+    Ok(ModelImport {
+        model,
+        entities,
+        tuples,
+        issues,
+    })
+}
+
+/// Fold an imported model into `current`: new entity types and relations
+/// are added, a relation already present must agree on its object type and
+/// direction (subject types are unioned). Never removes anything, so the
+/// result is always an additive edit.
+pub fn merge_model(
+    current: &ModelDefinition,
+    imported: &ModelDefinition,
+) -> Result<ModelDefinition, String> {
+    let mut merged = current.clone();
+    for t in &imported.entity_types {
+        if merged.entity_type(&t.name).is_none() {
+            merged.entity_types.push(t.clone());
+        }
+    }
+    for r in &imported.relations {
+        match merged.relations.iter_mut().find(|m| m.name == r.name) {
+            None => merged.relations.push(r.clone()),
+            Some(m) if m.object != r.object || m.traversal != r.traversal => {
+                return Err(format!(
+                    "relation '{}' already exists on '{}'{} — rename it or migrate the model first",
+                    r.name,
+                    m.object,
+                    if m.traversal { " (traversal)" } else { "" }
+                ));
+            }
+            Some(m) => {
+                for s in &r.subject {
+                    if !m.subject.contains(s) {
+                        m.subject.push(s.clone());
+                    }
+                }
+            }
+        }
+    }
+    Ok(merged)
+}
+
+/// A type of the external model, format-neutral.
+#[derive(Debug, Default)]
+struct ExternalType {
+    name: String,
+    relations: Vec<ExternalRelation>,
+}
+
+#[derive(Debug, Default)]
+struct ExternalRelation {
+    name: String,
+    /// Tuples written to the relation grant it on their own.
+    direct: bool,
+    subjects: Vec<DirectSubject>,
+    /// The definition, when it is (or includes) more than direct tuples.
+    rewrite: Option<String>,
+}
+
+#[derive(Debug)]
+struct DirectSubject {
+    type_name: String,
+    /// `group#member`
+    relation: Option<String>,
+    /// `user:*`
+    wildcard: bool,
+    /// `user with in_office`
+    condition: Option<String>,
+}
+
+impl DirectSubject {
+    /// `user`, `group#member`, `user:*`, `user with cond` — schema-independent.
+    fn parse(text: &str) -> Option<Self> {
+        let (head, condition) = match text.split_once(" with ") {
+            Some((head, traits)) => {
+                // SpiceDB traits: `with caveat`, `with expiration`,
+                // `with caveat and expiration`. Expiry is supported.
+                let caveat = traits
+                    .split(" and ")
+                    .map(str::trim)
+                    .find(|t| *t != "expiration")
+                    .map(str::to_string);
+                (head.trim(), caveat)
+            }
+            None => (text.trim(), None),
+        };
+        if head.is_empty() {
+            return None;
+        }
+        let (type_name, wildcard) = match head.strip_suffix(":*") {
+            Some(t) => (t, true),
+            None => (head, false),
+        };
+        let (type_name, relation) = match type_name.split_once('#') {
+            Some((t, r)) => (t, Some(r.to_string())),
+            None => (type_name, None),
+        };
+        Some(Self {
+            type_name: type_name.to_string(),
+            relation,
+            wildcard,
+            condition,
+        })
+    }
+
+    fn describe(&self) -> String {
+        let mut s = self.type_name.clone();
+        if let Some(r) = &self.relation {
+            s = format!("{s}#{r}");
+        }
+        if self.wildcard {
+            s.push_str(":*");
+        }
+        if let Some(c) = &self.condition {
+            s = format!("{s} with {c}");
+        }
+        s
+    }
+}
+
+fn schema_issue(location: String, construct: &str, reason: String) -> ImportIssue {
+    ImportIssue {
+        location,
+        construct: construct.to_string(),
+        reason,
+        occurrences: 1,
+    }
+}
+
+/// OpenFGA JSON (`schema_version` 1.1, as `fga model get --format json`
+/// prints it).
+fn parse_openfga_json(
+    src: &str,
+    issues: &mut Vec<ImportIssue>,
+) -> Result<Vec<ExternalType>, String> {
+    let doc: serde_json::Value =
+        serde_json::from_str(src).map_err(|e| format!("OpenFGA model is not valid JSON: {e}"))?;
+    // `fga model get` wraps the model in `authorization_model`.
+    let doc = doc.get("authorization_model").unwrap_or(&doc);
+    let defs = doc
+        .get("type_definitions")
+        .and_then(|d| d.as_array())
+        .ok_or("OpenFGA model has no `type_definitions` array")?;
+    if let Some(conditions) = doc.get("conditions").and_then(|c| c.as_object()) {
+        for name in conditions.keys() {
+            issues.push(condition_issue(name));
+        }
+    }
+
+    let mut types = Vec::with_capacity(defs.len());
+    for def in defs {
+        let name = def
+            .get("type")
+            .and_then(|t| t.as_str())
+            .ok_or("OpenFGA type definition without a `type`")?;
+        let mut ty = ExternalType {
+            name: name.to_string(),
+            relations: Vec::new(),
+        };
+        let metadata = def.pointer("/metadata/relations");
+        for (rel, rewrite) in def
+            .get("relations")
+            .and_then(|r| r.as_object())
+            .into_iter()
+            .flatten()
+        {
+            let mut direct = false;
+            let text = openfga_rewrite(rewrite, true, &mut direct);
+            let subjects = metadata
+                .and_then(|m| m.pointer(&format!("/{rel}/directly_related_user_types")))
+                .and_then(|d| d.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|s| {
+                    let type_name = s.get("type")?.as_str()?.to_string();
+                    Some(DirectSubject {
+                        type_name,
+                        relation: s
+                            .get("relation")
+                            .and_then(|r| r.as_str())
+                            .map(str::to_string),
+                        wildcard: s.get("wildcard").is_some(),
+                        condition: s
+                            .get("condition")
+                            .and_then(|c| c.as_str())
+                            .filter(|c| !c.is_empty())
+                            .map(str::to_string),
+                    })
+                })
+                .collect();
+            ty.relations.push(ExternalRelation {
+                name: rel.clone(),
+                direct,
+                subjects,
+                rewrite: (text != "[direct]").then_some(text),
+            });
+        }
+        types.push(ty);
+    }
+    Ok(types)
+}
+
+/// Render an OpenFGA userset rewrite in DSL terms, noting in `direct`
+/// whether directly written tuples grant the relation on their own (they do
+/// not under an intersection or exclusion).
+fn openfga_rewrite(rewrite: &serde_json::Value, granting: bool, direct: &mut bool) -> String {
+    let relation = |v: Option<&serde_json::Value>| {
+        v.and_then(|v| v.get("relation"))
+            .and_then(|r| r.as_str())
+            .unwrap_or("?")
+            .to_string()
+    };
+    let children = |v: &serde_json::Value| -> Vec<serde_json::Value> {
+        v.get("child")
+            .and_then(|c| c.as_array())
+            .cloned()
+            .unwrap_or_default()
+    };
+    if rewrite.get("this").is_some() {
+        *direct |= granting;
+        "[direct]".to_string()
+    } else if let Some(cu) = rewrite.get("computedUserset") {
+        relation(Some(cu))
+    } else if let Some(ttu) = rewrite.get("tupleToUserset") {
+        format!(
+            "{} from {}",
+            relation(ttu.get("computedUserset")),
+            relation(ttu.get("tupleset"))
+        )
+    } else if let Some(union) = rewrite.get("union") {
+        let parts: Vec<String> = children(union)
+            .iter()
+            .map(|c| openfga_rewrite(c, granting, direct))
+            .collect();
+        format!("({})", parts.join(" or "))
+    } else if let Some(intersection) = rewrite.get("intersection") {
+        let parts: Vec<String> = children(intersection)
+            .iter()
+            .map(|c| openfga_rewrite(c, false, direct))
+            .collect();
+        format!("({})", parts.join(" and "))
+    } else if let Some(difference) = rewrite.get("difference") {
+        let base = difference
+            .get("base")
+            .map(|b| openfga_rewrite(b, false, direct))
+            .unwrap_or_default();
+        let subtract = difference
+            .get("subtract")
+            .map(|s| openfga_rewrite(s, false, direct))
+            .unwrap_or_default();
+        format!("({base} but not {subtract})")
+    } else {
+        rewrite.to_string()
+    }
+}
+
+/// OpenFGA DSL (`model` / `schema 1.1` / `type` / `relations` / `define`).
+fn parse_openfga_dsl(
+    src: &str,
+    issues: &mut Vec<ImportIssue>,
+) -> Result<Vec<ExternalType>, String> {
+    let mut types: Vec<ExternalType> = Vec::new();
+    let mut condition_depth = 0usize;
+    for (index, raw) in src.lines().enumerate() {
+        // `# comment`, but `group#member` is not one.
+        let line = raw.trim();
+        let line = match line.find(" #") {
+            _ if line.starts_with('#') => "",
+            Some(at) => line[..at].trim_end(),
+            None => line,
+        };
+        if condition_depth > 0 {
+            condition_depth += line.matches('{').count();
+            condition_depth = condition_depth.saturating_sub(line.matches('}').count());
+            continue;
+        }
+        let mut words = line.split_whitespace();
+        match words.next() {
+            None | Some("model" | "schema" | "relations" | "module") => {}
+            Some("type") => types.push(ExternalType {
+                name: words.next().unwrap_or_default().to_string(),
+                relations: Vec::new(),
+            }),
+            Some("extend") => {
+                // `extend type x` (modular models): move x last so the
+                // `define`s that follow attach to it.
+                let name = words.nth(1).unwrap_or_default();
+                let ty = match types.iter().position(|t| t.name == name) {
+                    Some(at) => types.remove(at),
+                    None => ExternalType {
+                        name: name.to_string(),
+                        relations: Vec::new(),
+                    },
+                };
+                types.push(ty);
+            }
+            Some("condition") => {
+                let name = line["condition".len()..]
+                    .split('(')
+                    .next()
+                    .unwrap_or_default()
+                    .trim();
+                issues.push(condition_issue(name));
+                condition_depth = line.matches('{').count();
+                condition_depth = condition_depth.saturating_sub(line.matches('}').count());
+            }
+            Some("define") => {
+                let ty = types
+                    .last_mut()
+                    .ok_or_else(|| format!("line {}: `define` outside a type", index + 1))?;
+                let (name, expr) = line["define".len()..]
+                    .split_once(':')
+                    .ok_or_else(|| format!("line {}: expected `define name: …`", index + 1))?;
+                ty.relations
+                    .push(openfga_relation(name.trim(), expr.trim()));
+            }
+            Some(other) => {
+                return Err(format!(
+                    "line {}: unexpected `{other}` in OpenFGA model",
+                    index + 1
+                ))
+            }
+        }
+    }
+    Ok(types)
+}
+
+/// A `define` whose direct part is its `[…]` type restriction. Direct
+/// tuples grant on their own unless the expression also intersects or
+/// excludes.
+fn openfga_relation(name: &str, expr: &str) -> ExternalRelation {
+    let (restriction, rest) = match (expr.find('['), expr.find(']')) {
+        (Some(open), Some(close)) if open < close => (
+            Some(&expr[open + 1..close]),
+            format!("{}{}", &expr[..open], &expr[close + 1..]),
+        ),
+        _ => (None, expr.to_string()),
+    };
+    let narrowed = rest.contains(" and ") || rest.contains(" but not ");
+    let computed = rest.split(" or ").any(|part| !part.trim().is_empty());
+    ExternalRelation {
+        name: name.to_string(),
+        direct: restriction.is_some() && !narrowed,
+        subjects: restriction
+            .into_iter()
+            .flat_map(|r| r.split(','))
+            .filter_map(DirectSubject::parse)
+            .collect(),
+        rewrite: (restriction.is_none() || computed).then(|| expr.to_string()),
+    }
+}
+
+/// SpiceDB schema: `definition`s with `relation`s and `permission`s, plus
+/// `caveat`s. Comments (`//`, `/* */`) are ignored; definition names keep
+/// their prefix (`acme/document`).
+fn parse_spicedb_schema(
+    src: &str,
+    issues: &mut Vec<ImportIssue>,
+) -> Result<Vec<ExternalType>, String> {
+    let src = strip_comments(src);
+    let mut types = Vec::new();
+    let mut rest = src.as_str();
+    while let Some(open) = rest.find('{') {
+        // `use expiration` and other feature directives carry no model.
+        let header = rest[..open]
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with("use "))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let header = header.as_str();
+        let close = matching_brace(rest, open)
+            .ok_or_else(|| format!("unbalanced braces after `{header}`"))?;
+        let body = &rest[open + 1..close];
+        rest = &rest[close + 1..];
+
+        let mut words = header.split_whitespace();
+        match words.next() {
+            Some("definition") => {
+                let name = words.next().ok_or("`definition` without a name")?;
+                types.push(spicedb_definition(name, body)?);
+            }
+            Some("caveat") => {
+                let name = header["caveat".len()..]
+                    .split('(')
+                    .next()
+                    .unwrap_or_default()
+                    .trim();
+                issues.push(condition_issue(name));
+            }
+            _ => return Err(format!("unexpected `{header}` in SpiceDB schema")),
+        }
+    }
+    let trailing: Vec<&str> = rest
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with("use "))
+        .collect();
+    if !trailing.is_empty() {
+        return Err(format!(
+            "unexpected `{}` in SpiceDB schema",
+            trailing.join(" ")
+        ));
+    }
+    Ok(types)
+}
+
+fn spicedb_definition(name: &str, body: &str) -> Result<ExternalType, String> {
+    // Statements start with `relation` / `permission`; a permission's
+    // expression may continue over several lines.
+    let mut statements: Vec<String> = Vec::new();
+    for line in body.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if line.starts_with("relation ") || line.starts_with("permission ") {
+            statements.push(line.to_string());
+        } else if let Some(last) = statements.last_mut() {
+            last.push(' ');
+            last.push_str(line);
+        } else {
+            return Err(format!("definition {name}: unexpected `{line}`"));
+        }
+    }
+
+    let mut ty = ExternalType {
+        name: name.to_string(),
+        relations: Vec::new(),
+    };
+    for statement in statements {
+        if let Some(relation) = statement.strip_prefix("relation ") {
+            let (rel, subjects) = relation
+                .split_once(':')
+                .ok_or_else(|| format!("definition {name}: expected `relation name: types`"))?;
+            ty.relations.push(ExternalRelation {
+                name: rel.trim().to_string(),
+                direct: true,
+                subjects: subjects
+                    .split('|')
+                    .filter_map(DirectSubject::parse)
+                    .collect(),
+                rewrite: None,
+            });
+        } else if let Some(permission) = statement.strip_prefix("permission ") {
+            let (perm, expr) = permission
+                .split_once('=')
+                .ok_or_else(|| format!("definition {name}: expected `permission name = …`"))?;
+            ty.relations.push(ExternalRelation {
+                name: perm.trim().to_string(),
+                direct: false,
+                subjects: Vec::new(),
+                rewrite: Some(format!("permission {}", expr.trim())),
+            });
+        }
+    }
+    Ok(ty)
+}
+
+fn strip_comments(src: &str) -> String {
+    let mut out = String::with_capacity(src.len());
+    let mut rest = src;
+    loop {
+        let line = rest.find("//");
+        let block = rest.find("/*");
+        match (line, block) {
+            (Some(l), b) if b.is_none_or(|b| l < b) => {
+                out.push_str(&rest[..l]);
+                rest = rest[l..].find('\n').map_or("", |nl| &rest[l + nl..]);
+            }
+            (_, Some(b)) => {
+                out.push_str(&rest[..b]);
+                rest = rest[b..].find("*/").map_or("", |end| &rest[b + end + 2..]);
+            }
+            _ => {
+                out.push_str(rest);
+                return out;
+            }
+        }
+    }
+}
+
+fn matching_brace(src: &str, open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, c) in src[open..].char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn condition_issue(name: &str) -> ImportIssue {
+    schema_issue(
+        format!("condition {name}"),
+        "condition",
+        "CEL conditions are not translated to .reap caveats; tuples that use it are left out"
+            .to_string(),
+    )
+}
+
+fn build_model(types: &[ExternalType], issues: &mut Vec<ImportIssue>) -> ModelDefinition {
+    // (object type, relation) pairs some type restriction walks through.
+    let usersets: BTreeSet<(&str, &str)> = types
+        .iter()
+        .flat_map(|t| &t.relations)
+        .flat_map(|r| &r.subjects)
+        .filter_map(|s| Some((s.type_name.as_str(), s.relation.as_deref()?)))
+        .collect();
+
+    let mut model = ModelDefinition::default();
+    for t in types {
+        model.entity_types.push(EntityTypeDef {
+            name: t.name.clone(),
+            attributes: Vec::new(),
+        });
+        for r in &t.relations {
+            let location = format!("{}#{}", t.name, r.name);
+            if let Some(rewrite) = &r.rewrite {
+                let reason = if r.direct {
+                    format!("`{rewrite}`: only directly written tuples were imported")
+                } else {
+                    format!("`{rewrite}`: not imported (write policy rules for it)")
+                };
+                issues.push(schema_issue(location.clone(), "rewrite", reason));
+            }
+            if !r.direct {
+                continue;
+            }
+            let mut subject = Vec::new();
+            for s in &r.subjects {
+                if s.wildcard || s.condition.is_some() || s.relation.is_some() {
+                    let (construct, reason) = if s.wildcard {
+                        ("wildcard", "public access is not imported".to_string())
+                    } else if s.condition.is_some() {
+                        (
+                            "conditional subject",
+                            "tuples carrying the condition are left out".to_string(),
+                        )
+                    } else if traversed_userset(r, &s.type_name).is_none() {
+                        (
+                            "userset",
+                            format!(
+                                "several {} usersets on one relation cannot be told apart \
+                                 once imported; their tuples are left out",
+                                s.type_name
+                            ),
+                        )
+                    } else {
+                        (
+                            "userset",
+                            format!(
+                                "imported as edges to the {} itself; grant through it with \
+                                 rebac::reachable(user, \"{}\", resource, \"{}\", depth)",
+                                s.type_name,
+                                r.name,
+                                s.relation.as_deref().unwrap_or_default()
+                            ),
+                        )
+                    };
+                    issues.push(schema_issue(
+                        format!("{location} [{}]", s.describe()),
+                        construct,
+                        reason,
+                    ));
+                }
+                if !s.wildcard && !subject.contains(&s.type_name) {
+                    subject.push(s.type_name.clone());
+                }
+            }
+            let traversal = usersets.contains(&(t.name.as_str(), r.name.as_str()));
+            match model.relations.iter_mut().find(|d| d.name == r.name) {
+                None => model.relations.push(RelationDef {
+                    name: r.name.clone(),
+                    object: t.name.clone(),
+                    subject,
+                    traversal,
+                }),
+                Some(def) => issues.push(schema_issue(
+                    location,
+                    "shared relation name",
+                    format!(
+                        "ADM relation names are model-wide; recorded once, on `{}`, and \
+                         tuples on `{}` are left out",
+                        def.object, t.name
+                    ),
+                )),
+            }
+        }
+    }
+    model
+}
+
+/// The one userset relation of `subject_type` a direct relation admits —
+/// the relation `rebac::reachable` traverses. `None` when it admits none or
+/// several: the imported edge names the subject alone, so several userset
+/// relations would collapse into one and widen access.
+fn traversed_userset<'a>(relation: &'a ExternalRelation, subject_type: &str) -> Option<&'a str> {
+    let mut usersets = relation
+        .subjects
+        .iter()
+        .filter(|s| s.type_name == subject_type && !s.wildcard)
+        .filter_map(|s| s.relation.as_deref());
+    let first = usersets.next()?;
+    usersets.all(|r| r == first).then_some(first)
+}
+
+/// A tuple as written in the external store.
+#[derive(Debug)]
+struct RawTuple {
+    object: String,
+    relation: String,
+    subject: String,
+    caveat: Option<String>,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+fn parse_openfga_tuples(src: &str) -> Result<Vec<RawTuple>, String> {
+    let doc: serde_json::Value =
+        serde_json::from_str(src).map_err(|e| format!("OpenFGA tuples are not valid JSON: {e}"))?;
+    let items = match &doc {
+        serde_json::Value::Array(items) => items,
+        other => other
+            .get("tuples")
+            .and_then(|t| t.as_array())
+            .ok_or("OpenFGA tuples must be a JSON array or an object with a `tuples` array")?,
+    };
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            // Read responses nest the tuple under `key`.
+            let key = item.get("key").unwrap_or(item);
+            let field = |name: &str| {
+                key.get(name)
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string)
+                    .ok_or_else(|| format!("tuple {}: missing `{name}`", i + 1))
+            };
+            Ok(RawTuple {
+                object: field("object")?,
+                relation: field("relation")?,
+                subject: field("user")?,
+                caveat: key
+                    .pointer("/condition/name")
+                    .and_then(|c| c.as_str())
+                    .map(str::to_string),
+                expires_at: None,
+            })
+        })
+        .collect()
+}
+
+/// `resource:id#relation@subject:id[#relation][[caveat…]|[expiration:…]]`,
+/// one per line (`zed relationship` / validation-file syntax).
+fn parse_spicedb_tuples(src: &str) -> Result<Vec<RawTuple>, String> {
+    let mut tuples = Vec::new();
+    for (index, line) in src.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("//") {
+            continue;
+        }
+        let err = || {
+            format!(
+                "line {}: expected `type:id#relation@type:id`, got `{line}`",
+                index + 1
+            )
+        };
+        let (relationship, traits) = match line.find('[') {
+            Some(open) => (
+                &line[..open],
+                line[open + 1..].strip_suffix(']').ok_or_else(err)?,
+            ),
+            None => (line, ""),
+        };
+        let (resource, subject) = relationship.split_once('@').ok_or_else(err)?;
+        let (object, relation) = resource.split_once('#').ok_or_else(err)?;
+        let mut tuple = RawTuple {
+            object: object.trim().to_string(),
+            relation: relation.trim().to_string(),
+            subject: subject.trim().to_string(),
+            caveat: None,
+            expires_at: None,
+        };
+        for t in traits.split("][").filter(|t| !t.is_empty()) {
+            match t.strip_prefix("expiration:") {
+                Some(at) => {
+                    tuple.expires_at = Some(
+                        DateTime::parse_from_rfc3339(at.trim())
+                            .map_err(|e| format!("line {}: expiration: {e}", index + 1))?
+                            .with_timezone(&Utc),
+                    )
+                }
+                None => tuple.caveat = Some(t.split(':').next().unwrap_or(t).trim().to_string()),
+            }
+        }
+        tuples.push(tuple);
+    }
+    Ok(tuples)
+}
+
+fn import_tuples(
+    types: &[ExternalType],
+    model: &ModelDefinition,
+    raw: Vec<RawTuple>,
+    issues: &mut Vec<ImportIssue>,
+) -> (Vec<AdmEntity>, Vec<RelationTuple>) {
+    // (object type, relation) -> the relation and, per subject type, whether
+    // a plain subject is allowed and which userset relation is traversed.
+    type Subjects<'a> = BTreeMap<&'a str, (bool, Option<&'a str>)>;
+    let mut allowed: HashMap<(&str, &str), (&ExternalRelation, Subjects)> = HashMap::new();
+    for t in types {
+        for r in t.relations.iter().filter(|r| r.direct) {
+            let mut subjects = Subjects::new();
+            for s in r.subjects.iter().filter(|s| !s.wildcard) {
+                let entry = subjects.entry(s.type_name.as_str()).or_default();
+                match s.relation {
+                    None => entry.0 = true,
+                    Some(_) => entry.1 = traversed_userset(r, &s.type_name),
+                }
+            }
+            allowed.insert((t.name.as_str(), r.name.as_str()), (r, subjects));
+        }
+    }
+
+    // (construct, reason) -> index into `issues`, so a million wildcard
+    // tuples are one issue.
+    let mut seen: HashMap<(&'static str, String), usize> = HashMap::new();
+    let mut skip = |index: usize, construct: &'static str, reason: String| {
+        let key = (construct, reason);
+        match seen.get(&key) {
+            Some(&at) => issues[at].occurrences += 1,
+            None => {
+                seen.insert(key.clone(), issues.len());
+                issues.push(ImportIssue {
+                    location: format!("tuple {}", index + 1),
+                    construct: construct.to_string(),
+                    reason: key.1,
+                    occurrences: 1,
+                });
+            }
+        }
+    };
+
+    let now = Utc::now();
+    let mut entities: BTreeMap<String, String> = BTreeMap::new();
+    let mut written: BTreeSet<(String, String, String)> = BTreeSet::new();
+    let mut tuples = Vec::with_capacity(raw.len());
+    for (index, t) in raw.into_iter().enumerate() {
+        let Some((object_type, _)) = t.object.split_once(':') else {
+            skip(
+                index,
+                "object",
+                format!("`{}` has no `type:` prefix", t.object),
+            );
+            continue;
+        };
+        // A userset subject (`group:eng#member`) becomes an edge to the group,
+        // walked through the one relation the schema traverses.
+        let (subject, userset) = match t.subject.split_once('#') {
+            Some((subject, relation)) => (subject.to_string(), Some(relation)),
+            None => (t.subject.clone(), None),
+        };
+        let Some((subject_type, subject_id)) = subject.split_once(':') else {
+            skip(
+                index,
+                "subject",
+                format!("`{}` has no `type:` prefix", t.subject),
+            );
+            continue;
+        };
+        let Some((relation, subject_types)) = allowed.get(&(object_type, t.relation.as_str()))
+        else {
+            skip(
+                index,
+                "relation",
+                format!(
+                    "`{object_type}#{}` is not a directly assignable relation",
+                    t.relation
+                ),
+            );
+            continue;
+        };
+        if model
+            .relation(&t.relation)
+            .is_none_or(|def| def.object != object_type)
+        {
+            skip(
+                index,
+                "shared relation name",
+                format!(
+                    "`{object_type}#{}` shares its name with a relation already recorded",
+                    t.relation
+                ),
+            );
+            continue;
+        }
+        if subject_id == "*" {
+            skip(
+                index,
+                "wildcard",
+                "public access is not imported".to_string(),
+            );
+            continue;
+        }
+        if let Some(caveat) = &t.caveat {
+            skip(
+                index,
+                "conditional tuple",
+                format!("condition `{caveat}` is not translated"),
+            );
+            continue;
+        }
+        let Some(&(plain, traversed)) = subject_types.get(subject_type) else {
+            skip(
+                index,
+                "subject",
+                format!(
+                    "`{object_type}#{}` does not allow `{subject_type}`",
+                    t.relation
+                ),
+            );
+            continue;
+        };
+        match userset {
+            None if !plain => {
+                skip(
+                    index,
+                    "subject",
+                    format!(
+                        "`{object_type}#{}` allows `{subject_type}` only as a userset",
+                        t.relation
+                    ),
+                );
+                continue;
+            }
+            Some(userset) if traversed != Some(userset) => {
+                let declared = relation
+                    .subjects
+                    .iter()
+                    .any(|s| s.type_name == subject_type && s.relation.as_deref() == Some(userset));
+                let reason = if declared {
+                    format!(
+                        "`{object_type}#{}` admits several `{subject_type}` usersets",
+                        t.relation
+                    )
+                } else {
+                    format!(
+                        "`{object_type}#{}` does not allow `{subject_type}#{userset}`",
+                        t.relation
+                    )
+                };
+                skip(index, "userset", reason);
+                continue;
+            }
+            _ => {}
+        }
+        if t.expires_at.is_some_and(|at| at <= now) {
+            skip(index, "expired tuple", "already expired".to_string());
+            continue;
+        }
+        if !written.insert((t.object.clone(), t.relation.clone(), subject.clone())) {
+            continue;
+        }
+        entities.insert(t.object.clone(), object_type.to_string());
+        entities.insert(subject.clone(), subject_type.to_string());
+        tuples.push(RelationTuple {
+            object: t.object,
+            relation: t.relation,
+            subject,
+            expires_at: t.expires_at,
+            caveat: None,
+        });
+    }
+
+    let entities = entities
+        .into_iter()
+        .map(|(entity_id, entity_type)| AdmEntity {
+            entity_id,
+            entity_type,
+            attributes: serde_json::Map::new(),
+        })
+        .collect();
+    (entities, tuples)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -518,11 +1653,15 @@ mod tests {
             subject: "alice".into(),
             role: "editor".into(),
             scope: String::new(),
+            expires_at: None,
+            caveat: None,
         }];
         let tuples = vec![RelationTuple {
             object: "doc-1".into(),
             relation: "owner".into(),
             subject: "alice".into(),
+            expires_at: None,
+            caveat: None,
         }];
 
         let doc = materialize(&m, &entities, &bindings, &tuples);
@@ -537,4 +1676,463 @@ mod tests {
         assert_eq!(doc1["type"], "resource", "type inferred from relation def");
         assert_eq!(doc1["relationships"]["owner"][0], "alice");
     }
+
+    #[test]
+    fn conditional_grants_materialize_to_conditional_edges() {
+        let m = DatastoreTemplate::Combined.seed_model();
+        let expiry: DateTime<Utc> = "2031-05-01T12:00:00Z".parse().unwrap();
+        let entities = vec![AdmEntity {
+            entity_id: "alice".into(),
+            entity_type: "user".into(),
+            attributes: serde_json::Map::new(),
+        }];
+        let bindings = vec![
+            RoleBinding {
+                subject: "alice".into(),
+                role: "viewer".into(),
+                scope: String::new(),
+                expires_at: None,
+                caveat: None,
+            },
+            RoleBinding {
+                subject: "alice".into(),
+                role: "admin".into(),
+                scope: String::new(),
+                expires_at: Some(expiry),
+                caveat: Some("context.ip_in_office == true".into()),
+            },
+        ];
+        let tuples = vec![
+            RelationTuple {
+                object: "doc-1".into(),
+                relation: "owner".into(),
+                subject: "alice".into(),
+                expires_at: Some(expiry),
+                caveat: None,
+            },
+            RelationTuple {
+                object: "doc-1".into(),
+                relation: "owner".into(),
+                subject: "bob".into(),
+                expires_at: None,
+                caveat: None,
+            },
+        ];
+
+        let doc = materialize(&m, &entities, &bindings, &tuples);
+        let ents = doc["entities"].as_array().unwrap();
+        let alice = ents.iter().find(|e| e["id"] == "alice").unwrap();
+        // Only the unconditional binding is an attribute.
+        assert_eq!(alice["attributes"]["roles"], serde_json::json!(["viewer"]));
+        let admin = serde_json::json!({
+            "subject": "admin",
+            "expires_at": "2031-05-01T12:00:00Z",
+            "caveat": "context.ip_in_office == true",
+        });
+        assert_eq!(
+            alice["relationships"][ROLE_RELATION],
+            serde_json::json!([admin])
+        );
+        let admin_permissions = m.role("admin").unwrap().permissions.len();
+        assert_eq!(
+            alice["relationships"][PERMISSION_RELATION]
+                .as_array()
+                .unwrap()
+                .len(),
+            admin_permissions
+        );
+
+        let doc1 = ents.iter().find(|e| e["id"] == "doc-1").unwrap();
+        assert_eq!(
+            doc1["relationships"]["owner"],
+            serde_json::json!([
+                {"subject": "alice", "expires_at": "2031-05-01T12:00:00Z"},
+                "bob"
+            ])
+        );
+
+        // The single-entity delta path emits the same documents.
+        let one = materialize_one(&m, "alice", Some(&entities[0]), &bindings, &[]).unwrap();
+        assert_eq!(&one, alice);
+        let one = materialize_one(&m, "doc-1", None, &[], &tuples).unwrap();
+        assert_eq!(&one, doc1);
+    }
+
+    #[test]
+    fn grant_conditions_are_validated() {
+        let future: DateTime<Utc> = "2999-01-01T00:00:00Z".parse().unwrap();
+        let past: DateTime<Utc> = "2000-01-01T00:00:00Z".parse().unwrap();
+        assert!(validate_grant_condition(Some(&future), Some("context.on_call == true")).is_ok());
+        assert!(validate_grant_condition(None, None).is_ok());
+        let err = validate_grant_condition(Some(&past), None).unwrap_err();
+        assert!(err.contains("in the past"), "{err}");
+        assert!(validate_grant_condition(None, Some("context.on_call ==")).is_err());
+        assert!(validate_grant_condition(None, Some("  ")).is_err());
+    }
+
+    /// Schema-level issues of one kind.
+    fn issue<'a>(import: &'a ModelImport, construct: &str) -> Vec<&'a ImportIssue> {
+        import
+            .issues
+            .iter()
+            .filter(|i| i.construct == construct && !i.location.starts_with("tuple "))
+            .collect()
+    }
+
+    /// Tuple-level issues, (construct, occurrences) in order.
+    fn skipped(import: &ModelImport) -> Vec<(&str, usize)> {
+        import
+            .issues
+            .iter()
+            .filter(|i| i.location.starts_with("tuple "))
+            .map(|i| (i.construct.as_str(), i.occurrences))
+            .collect()
+    }
+
+    const OPENFGA_DSL: &str = r#"
+model
+  schema 1.1
+
+# Teams nest.
+type user
+type group
+  relations
+    define member: [user, group#member]
+type document
+  relations
+    define parent: [folder]
+    define owner: [user]
+    define viewer: [user, group#member, user:*] or owner or viewer from parent
+    define blocked: [user]
+    define can_read: viewer but not blocked
+type folder
+  relations
+    define viewer: [user]
+
+condition in_office(ip: ipaddress) {
+  ip.in_cidr("10.0.0.0/8")
+}
+"#;
+
+    #[test]
+    fn openfga_models_map_to_direct_relations_and_report_the_rest() {
+        let tuples = serde_json::json!({"tuples": [
+            {"key": {"user": "user:anne", "relation": "viewer", "object": "document:readme"}},
+            {"user": "group:eng#member", "relation": "viewer", "object": "document:readme"},
+            {"user": "user:dan", "relation": "member", "object": "group:eng"},
+            {"user": "user:*", "relation": "viewer", "object": "document:readme"},
+            {"user": "user:*", "relation": "viewer", "object": "document:spec"},
+            {"user": "user:bob", "relation": "can_read", "object": "document:readme"},
+            {"user": "user:carl", "relation": "member", "object": "group:eng",
+             "condition": {"name": "in_office"}},
+            {"user": "document:x", "relation": "owner", "object": "document:readme"},
+            {"user": "group:eng#admin", "relation": "viewer", "object": "document:readme"},
+            {"user": "user:erin", "relation": "viewer", "object": "folder:f1"}
+        ]})
+        .to_string();
+        let import = import_model(ImportFormat::Openfga, OPENFGA_DSL, Some(&tuples)).unwrap();
+        let m = &import.model;
+
+        assert_eq!(m.entity_types.len(), 4);
+        let member = m.relation("member").unwrap();
+        assert_eq!((member.object.as_str(), member.traversal), ("group", true));
+        assert_eq!(member.subject, ["user", "group"]);
+        assert!(!m.relation("parent").unwrap().traversal);
+        let viewer = m.relation("viewer").unwrap();
+        assert_eq!(viewer.object, "document", "first declaration wins");
+        assert_eq!(viewer.subject, ["user", "group"], "no wildcard subject");
+        assert!(m.relation("can_read").is_none(), "computed only");
+
+        let rewrites = issue(&import, "rewrite");
+        assert_eq!(rewrites.len(), 2, "{rewrites:?}");
+        assert!(rewrites[1].reason.contains("not imported"), "{rewrites:?}");
+        for construct in ["wildcard", "condition", "shared relation name"] {
+            assert_eq!(issue(&import, construct).len(), 1, "{construct}");
+        }
+        assert_eq!(
+            issue(&import, "wildcard")[0].location,
+            "document#viewer [user:*]"
+        );
+        assert_eq!(
+            issue(&import, "userset").len(),
+            2,
+            "group#member and document#viewer"
+        );
+
+        assert_eq!(
+            import.tuples,
+            [
+                ("document:readme", "viewer", "user:anne"),
+                ("document:readme", "viewer", "group:eng"),
+                ("group:eng", "member", "user:dan"),
+            ]
+            .map(|(object, relation, subject)| RelationTuple {
+                object: object.into(),
+                relation: relation.into(),
+                subject: subject.into(),
+                expires_at: None,
+                caveat: None,
+            })
+        );
+        assert_eq!(
+            skipped(&import),
+            [
+                ("wildcard", 2),
+                ("relation", 1),
+                ("conditional tuple", 1),
+                ("subject", 1),
+                ("userset", 1),
+                ("shared relation name", 1)
+            ]
+        );
+        let ids: Vec<&str> = import
+            .entities
+            .iter()
+            .map(|e| e.entity_id.as_str())
+            .collect();
+        assert_eq!(
+            ids,
+            ["document:readme", "group:eng", "user:anne", "user:dan"]
+        );
+
+        // Group membership lands on the member, the direction
+        // rebac::reachable walks.
+        let doc = materialize(m, &import.entities, &[], &import.tuples);
+        let dan = doc["entities"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|e| e["id"] == "user:dan")
+            .unwrap();
+        assert_eq!(
+            dan["relationships"]["member"],
+            serde_json::json!(["group:eng"])
+        );
+    }
+
+    #[test]
+    fn usersets_import_only_through_the_traversed_relation() {
+        let schema = r#"
+model
+  schema 1.1
+type user
+type group
+  relations
+    define member: [user]
+    define admin: [user]
+type document
+  relations
+    define viewer: [group#member]
+    define editor: [group#member, group#admin]
+"#;
+        let tuples = serde_json::json!([
+            {"user": "group:eng#member", "relation": "viewer", "object": "document:readme"},
+            {"user": "group:eng#admin", "relation": "viewer", "object": "document:readme"},
+            {"user": "group:eng", "relation": "viewer", "object": "document:readme"},
+            {"user": "group:eng#member", "relation": "editor", "object": "document:readme"},
+            {"user": "group:eng#admin", "relation": "editor", "object": "document:readme"}
+        ])
+        .to_string();
+        let import = import_model(ImportFormat::Openfga, schema, Some(&tuples)).unwrap();
+
+        // Only the member userset survives: an `#admin` edge to the group
+        // would be walked as membership and grant every member.
+        assert_eq!(
+            import.tuples,
+            [RelationTuple {
+                object: "document:readme".into(),
+                relation: "viewer".into(),
+                subject: "group:eng".into(),
+                expires_at: None,
+                caveat: None,
+            }]
+        );
+        assert_eq!(
+            skipped(&import),
+            [("userset", 1), ("subject", 1), ("userset", 2)]
+        );
+        let reasons: Vec<&str> = import
+            .issues
+            .iter()
+            .filter(|i| i.location.starts_with("tuple "))
+            .map(|i| i.reason.as_str())
+            .collect();
+        assert_eq!(reasons[0], "`document#viewer` does not allow `group#admin`");
+        assert_eq!(
+            reasons[2],
+            "`document#editor` admits several `group` usersets"
+        );
+        let editor = issue(&import, "userset")
+            .into_iter()
+            .filter(|i| i.location.starts_with("document#editor"))
+            .count();
+        assert_eq!(editor, 2, "both editor usersets are reported");
+    }
+
+    #[test]
+    fn openfga_json_models_match_the_dsl() {
+        let json = serde_json::json!({"authorization_model": {
+            "schema_version": "1.1",
+            "type_definitions": [
+                {"type": "user"},
+                {"type": "document",
+                 "relations": {
+                     "owner": {"this": {}},
+                     "viewer": {"union": {"child": [{"this": {}}, {"computedUserset": {"relation": "owner"}}]}},
+                     "auditor": {"intersection": {"child": [{"this": {}}, {"computedUserset": {"relation": "owner"}}]}}
+                 },
+                 "metadata": {"relations": {
+                     "owner": {"directly_related_user_types": [{"type": "user"}]},
+                     "viewer": {"directly_related_user_types": [{"type": "user"}]},
+                     "auditor": {"directly_related_user_types": [{"type": "user"}]}
+                 }}}
+            ]
+        }})
+        .to_string();
+        let import = import_model(ImportFormat::Openfga, &json, None).unwrap();
+        let names: Vec<&str> = import
+            .model
+            .relations
+            .iter()
+            .map(|r| r.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            ["owner", "viewer"],
+            "an intersection's tuples grant nothing alone"
+        );
+        let mut rewrites: Vec<&str> = issue(&import, "rewrite")
+            .iter()
+            .map(|i| i.reason.as_str())
+            .collect();
+        rewrites.sort_unstable();
+        assert_eq!(
+            rewrites,
+            [
+                "`([direct] and owner)`: not imported (write policy rules for it)",
+                "`([direct] or owner)`: only directly written tuples were imported",
+            ]
+        );
+    }
+
+    #[test]
+    fn spicedb_schemas_import_relations_expiry_and_report_caveats() {
+        use chrono::Datelike;
+        let schema = r#"
+use expiration
+
+/** a user */
+definition user {}
+
+definition team {
+    relation member: user | team#member
+}
+
+caveat on_call(on_call bool) {
+    on_call == true
+}
+
+definition document {
+    // direct grants
+    relation reader: user with expiration | team#member | user:*
+    relation writer: user with on_call
+    permission read = reader + writer
+        + writer
+}
+"#;
+        let tuples = "\
+document:readme#reader@user:anne[expiration:2999-01-01T00:00:00Z]
+document:readme#reader@user:old[expiration:2001-01-01T00:00:00Z]
+document:readme#reader@team:eng#member
+document:readme#writer@user:bob[on_call:{\"on_call\":true}]
+team:eng#member@user:carl
+document:readme#read@user:dan
+";
+        let import = import_model(ImportFormat::Spicedb, schema, Some(tuples)).unwrap();
+        let m = &import.model;
+        let names: Vec<&str> = m.relations.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["member", "reader", "writer"]);
+        assert!(m.relation("member").unwrap().traversal);
+        assert_eq!(
+            issue(&import, "rewrite")[0].reason,
+            "`permission reader + writer + writer`: not imported (write policy rules for it)"
+        );
+        assert_eq!(issue(&import, "condition")[0].location, "condition on_call");
+
+        let imported: Vec<String> = import
+            .tuples
+            .iter()
+            .map(|t| {
+                format!(
+                    "{}#{}@{}{}",
+                    t.object,
+                    t.relation,
+                    t.subject,
+                    t.expires_at
+                        .map(|at| format!(" until {}", at.year()))
+                        .unwrap_or_default()
+                )
+            })
+            .collect();
+        assert_eq!(
+            imported,
+            [
+                "document:readme#reader@user:anne until 2999",
+                "document:readme#reader@team:eng",
+                "team:eng#member@user:carl",
+            ]
+        );
+        assert_eq!(
+            skipped(&import),
+            [
+                ("expired tuple", 1),
+                ("conditional tuple", 1),
+                ("relation", 1)
+            ]
+        );
+
+        let err = import_model(ImportFormat::Spicedb, "definition user {", None).unwrap_err();
+        assert!(err.contains("unbalanced"), "{err}");
+        let err =
+            import_model(ImportFormat::Spicedb, "definition user {}", Some("nope")).unwrap_err();
+        assert!(err.contains("line 1"), "{err}");
+    }
+
+    #[test]
+    fn merging_an_import_is_additive_and_rejects_conflicts() {
+        let current = DatastoreTemplate::Rebac.seed_model();
+        let import = import_model(
+            ImportFormat::Spicedb,
+            "definition user {}\ndefinition resource {\n relation viewer: user | team\n relation editor: user\n}",
+            None,
+        )
+        .unwrap();
+        let merged = merge_model(&current, &import.model).unwrap();
+        assert!(migration_is_additive(&current, &merged));
+        assert_eq!(
+            merged.relation("viewer").unwrap().subject,
+            ["user", "group", "team"]
+        );
+        assert!(merged.relation("editor").is_some());
+        assert!(
+            merged.entity_type("team").is_none(),
+            "only declared types are added"
+        );
+
+        let clash = import_model(
+            ImportFormat::Spicedb,
+            "definition user {}\ndefinition folder {\n relation owner: user\n}",
+            None,
+        )
+        .unwrap();
+        let err = merge_model(&current, &clash.model).unwrap_err();
+        assert!(
+            err.contains("'owner' already exists on 'resource'"),
+            "{err}"
+        );
+    }
+
+    fn migration_is_additive(from: &ModelDefinition, to: &ModelDefinition) -> bool {
+        crate::domain::migration::vocabulary_breaking_changes(from, to).is_empty()
+    }
 }
@@ -783,11 +783,15 @@ mod tests {
                 subject: "alice".into(),
                 role: "editor".into(),
                 scope: String::new(),
+                expires_at: None,
+                caveat: None,
             },
             RoleBinding {
                 subject: "bob".into(),
                 role: "viewer".into(),
                 scope: String::new(),
+                expires_at: None,
+                caveat: None,
             },
         ];
         let plan = plan(&[t], &m, &[], &bindings, &[]).unwrap();
@@ -923,6 +927,8 @@ mod tests {
             object: "doc-1".into(),
             relation: "owner".into(),
             subject: "alice".into(),
+            expires_at: None,
+            caveat: None,
         }];
         let t = ModelTransform::RemoveRelation {
             name: "owner".into(),
@@ -981,6 +987,8 @@ mod tests {
                 object: "d".into(),
                 relation: "owner".into(),
                 subject: "a".into(),
+                expires_at: None,
+                caveat: None,
             }],
         )
         .unwrap();
@@ -446,22 +446,12 @@ fn replay_row(
         .and_then(Value::as_str)
         .unwrap_or(&row.resource)
         .to_string();
-    let mut context: HashMap<String, String> = blob
+    let mut context: HashMap<String, Value> = blob
         .get("context")
         .and_then(Value::as_object)
-        .map(|o| {
-            o.iter()
-                .map(|(k, v)| {
-                    let s = v
-                        .as_str()
-                        .map(str::to_string)
-                        .unwrap_or_else(|| v.to_string());
-                    (k.clone(), s)
-                })
-                .collect()
-        })
+        .map(|o| o.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
         .unwrap_or_default();
-    context.insert("principal".to_string(), principal.clone());
+    context.insert("principal".to_string(), principal.clone().into());
 
     let policy_request = PolicyRequest {
         resource: resource.clone(),
@@ -597,7 +587,7 @@ mod tests {
         };
         let eval = |engine: &PolicyEngine, ids: &[Uuid]| {
             let mut context = HashMap::new();
-            context.insert("principal".to_string(), "user".to_string());
+            context.insert("principal".to_string(), "user".into());
             engine.evaluate_set(
                 ids,
                 &PolicyRequest {
@@ -181,7 +181,7 @@ impl ValidationService {
         errors: &mut Vec<ValidationError>,
         warnings: &mut Vec<ValidationError>,
     ) {
-        use policy_engine::reap::ReapParser;
+        use policy_engine::reap::{lint, ReapParser};
 
         match ReapParser::parse(content) {
             Ok(policy) => {
@@ -209,6 +209,19 @@ impl ValidationService {
                         });
                     }
                 }
+
+                // Static analysis: contradictory, shadowed and duplicate
+                // rules, unused helpers and imports
+                for finding in lint::lint_policy(&policy, Some(content)) {
+                    let line = finding.line.map(|l| l as u32);
+                    warnings.push(ValidationError {
+                        error_type: "semantic".to_string(),
+                        message: finding.to_string(),
+                        line,
+                        column: None,
+                        snippet: self.get_snippet(content, line),
+                    });
+                }
             }
             Err(e) => {
                 let (line, column, snippet) = self.extract_error_location(content, &e.to_string());
@@ -522,6 +535,29 @@ mod tests {
         assert!(errors[0].message.contains("must be 'allow' or 'deny'"));
     }
 
+    #[test]
+    fn test_validate_reaper_syntax_reports_lint_findings() {
+        let content = r#"policy contradictory {
+    default: deny,
+
+    rule never {
+        allow if user.role == "admin" && user.role == "owner"
+    }
+}
+"#;
+        let service = create_test_service();
+
+        let mut errors = Vec::new();
+        let mut warnings = Vec::new();
+        service.validate_reaper_syntax(content, &mut errors, &mut warnings);
+
+        assert!(errors.is_empty(), "Expected no errors: {:?}", errors);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].error_type, "semantic");
+        assert_eq!(warnings[0].line, Some(4));
+        assert!(warnings[0].message.contains("[unsatisfiable]"));
+    }
+
     #[test]
     fn test_extract_line_from_error() {
         let service = create_test_service();
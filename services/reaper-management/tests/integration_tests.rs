@@ -12,8 +12,9 @@ use reaper_management::{
     auth::api_key::{ApiKeyRepository, CreateApiKey},
     auth::jwks::JwksConfigRepository,
     config::{AuthConfig, Config, PromotionApproval},
-    db::repositories::{AgentRepository, OrganizationRepository},
+    db::repositories::{AgentRepository, ChangeRequestRepository, OrganizationRepository},
     db::Database,
+    domain::change_request::ChangeRequestStatus,
     domain::organization::CreateOrganization,
     storage::FilesystemStorage,
     AppState,
@@ -1826,7 +1827,7 @@ async fn test_data_plane_end_to_end() {
     let request = |principal: &str, resource: &str| PolicyRequest {
         resource: resource.to_string(),
         action: "write".to_string(),
-        context: HashMap::from([("principal".to_string(), principal.to_string())]),
+        context: HashMap::from([("principal".to_string(), principal.into())]),
         ..Default::default()
     };
 
@@ -1849,7 +1850,7 @@ async fn test_data_plane_end_to_end() {
     let read = |principal: &str, resource: &str| PolicyRequest {
         resource: resource.to_string(),
         action: "read".to_string(),
-        context: HashMap::from([("principal".to_string(), principal.to_string())]),
+        context: HashMap::from([("principal".to_string(), principal.into())]),
         ..Default::default()
     };
     let d = evaluator.evaluate(&read("carol", "doc-1")).unwrap();
@@ -2040,6 +2041,8 @@ async fn test_data_plane_delta_sync() {
         versions["versions"][0]["change_seq"].as_i64().unwrap()
     };
     assert_eq!(published["version"], 1);
+    // The publish's revision token is the snapshot's stream position.
+    assert_eq!(published["revision"], snapshot_seq);
 
     // Replica: load snapshot v1.
     let (_, v1) = call("GET", format!("{base}/versions/1"), None).await;
@@ -2090,6 +2093,9 @@ async fn test_data_plane_delta_sync() {
     assert_eq!(status, StatusCode::OK, "changes failed: {changes}");
     assert_eq!(changes["snapshot_required"], false);
     assert!(changes["deltas"].as_array().unwrap().len() >= 3);
+    // The last write's revision token is exactly the head a replica must
+    // reach to reflect it.
+    assert_eq!(deleted["revision"], changes["head_seq"]);
     apply_changes(replica.clone(), changes.clone());
 
     // Redelivery (lost ack): applying the SAME pull again must be a no-op.
@@ -3895,7 +3901,7 @@ async fn test_replay_engine_loads_real_bundle_and_api_guards() {
     // the real compiled artifact, with the production set semantics.
     let eval = |principal: &str, action: &str| {
         let mut context = std::collections::HashMap::new();
-        context.insert("principal".to_string(), principal.to_string());
+        context.insert("principal".to_string(), principal.into());
         engine.evaluate_set(
             &policy_ids,
             &policy_engine::PolicyRequest {
@@ -5965,6 +5971,143 @@ async fn promotions_list_is_keyset_paginated() {
     assert!(page2["next_cursor"].is_null());
 }
 
+/// `GET /promotions/{id}/diff` compares the request's bundle with the one
+/// last applied to the same target environment, by decision outcome.
+#[tokio::test]
+async fn promotion_diff_reports_outcome_changes_against_last_applied() {
+    let env = setup_test_env().await;
+    let response = env
+        .app
+        .clone()
+        .oneshot(json_request(
+            "POST",
+            "/orgs",
+            Some(json!({"name": "Diff Org", "slug": "diff-org"})),
+        ))
+        .await
+        .unwrap();
+    let org_id = Uuid::parse_str(parse_body(response).await["id"].as_str().unwrap()).unwrap();
+    let key = create_test_api_key(&env.db, org_id).await;
+
+    let mut ns = Vec::new();
+    for slug in ["staging", "prod"] {
+        let r = env
+            .app
+            .clone()
+            .oneshot(authed_request(
+                "POST",
+                "/orgs/diff-org/namespaces",
+                Some(json!({"slug": slug})),
+                &key,
+            ))
+            .await
+            .unwrap();
+        ns.push(parse_body(r).await["id"].as_str().unwrap().to_string());
+    }
+    // prod requires 2 approvers so promotions stay pending (no agent needed).
+    for (name, tier, nsid, min) in [("staging", 10, &ns[0], 0), ("prod", 20, &ns[1], 2)] {
+        env.app
+            .clone()
+            .oneshot(authed_request(
+                "POST",
+                "/orgs/diff-org/environments",
+                Some(
+                    json!({"name": name, "tier_order": tier, "namespace_id": nsid,
+                            "approval_policy": {"min_approvers": min}}),
+                ),
+                &key,
+            ))
+            .await
+            .unwrap();
+    }
+
+    let employees = "policy docs {\n    default: deny,\n\n    rule employees {\n        allow if user.role == \"employee\"\n    }\n}\n";
+    let widened = "policy docs {\n    default: deny,\n\n    rule employees {\n        allow if user.role == \"employee\"\n    }\n\n    rule contractors {\n        allow if user.role == \"contractor\" && resource.classification == \"internal\"\n    }\n}\n";
+    let mut promotions = Vec::new();
+    for (name, content) in [("docs-v1", employees), ("docs-v2", widened)] {
+        let r = env
+            .app
+            .clone()
+            .oneshot(authed_request(
+                "POST",
+                "/orgs/diff-org/policies",
+                Some(json!({"name": name, "language": "reaper", "content": content})),
+                &key,
+            ))
+            .await
+            .unwrap();
+        let policy_id = parse_body(r).await["id"].as_str().unwrap().to_string();
+        let r = env
+            .app
+            .clone()
+            .oneshot(authed_request(
+                "POST",
+                "/orgs/diff-org/bundles",
+                Some(json!({"name": name, "policy_ids": [policy_id]})),
+                &key,
+            ))
+            .await
+            .unwrap();
+        let bundle_id = parse_body(r).await["id"].as_str().unwrap().to_string();
+        let r = env
+            .app
+            .clone()
+            .oneshot(authed_request(
+                "POST",
+                "/orgs/diff-org/environments/prod/promote",
+                Some(json!({"bundle_id": bundle_id, "from_env": "staging"})),
+                &key,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(r.status(), StatusCode::CREATED);
+        let cr_id = Uuid::parse_str(parse_body(r).await["id"].as_str().unwrap()).unwrap();
+        promotions.push((cr_id, bundle_id));
+    }
+
+    let diff_of = |cr_id: Uuid| {
+        let app = env.app.clone();
+        let key = key.clone();
+        async move {
+            let r = app
+                .oneshot(authed_request(
+                    "GET",
+                    &format!("/orgs/diff-org/promotions/{cr_id}/diff"),
+                    None,
+                    &key,
+                ))
+                .await
+                .unwrap();
+            assert_eq!(r.status(), StatusCode::OK);
+            parse_body(r).await
+        }
+    };
+
+    // Nothing applied to prod yet: every policy is new.
+    let first = diff_of(promotions[0].0).await;
+    assert!(first["baseline_bundle_id"].is_null());
+    assert_eq!(first["policies"][0]["status"], "added");
+
+    // Once the first promotion has applied, the second is diffed against it.
+    ChangeRequestRepository::new(&env.db)
+        .set_status(promotions[0].0, ChangeRequestStatus::Applied, None, None)
+        .await
+        .unwrap();
+    let second = diff_of(promotions[1].0).await;
+    assert_eq!(second["baseline_bundle_id"], promotions[0].1.as_str());
+    assert_eq!(second["policies"][0]["policy"], "docs");
+    assert_eq!(second["policies"][0]["status"], "changed");
+    assert_eq!(
+        second["policies"][0]["changes"][0]["summary"],
+        "deny → allow when user.role == \"contractor\" && resource.classification == \"internal\""
+    );
+    assert_eq!(
+        second["policies"][0]["changes"].as_array().unwrap().len(),
+        1
+    );
+    assert_eq!(second["skipped"], json!([]));
+}
+
 /// An environment that opts into `require_change_record` only accepts
 /// deployments through the promotion path: direct rollouts into its namespace
 /// (and org-wide rollouts, which would sweep it) are rejected with 409, while
@@ -8572,6 +8715,185 @@ async fn migration_apply_idempotency_key_replays() {
     assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
 }
 
+// ============================================================================
+// OpenFGA / SpiceDB model import
+// ============================================================================
+
+const IMPORT_SCHEMA: &str = r#"
+model
+  schema 1.1
+type user
+type team
+  relations
+    define member: [user]
+    define admin: [user]
+type doc
+  relations
+    define reader: [user, team#member]
+"#;
+
+/// `POST …/datastore/import`: a dry run converts and reports but writes
+/// nothing; the real import writes tuples and entities, records the merged
+/// model as a new model version with a history row, and reports (rather
+/// than imports) a userset naming a relation the schema does not traverse.
+#[tokio::test]
+async fn model_import_writes_a_model_version_and_reports_usersets() {
+    let env = setup_test_env().await;
+    let (key, base, _) = seed_datastore(&env, "Import Org", "import-org").await;
+    let call = |method: &'static str, path: String, body: Option<Value>| {
+        let app = env.app.clone();
+        let key = key.clone();
+        async move {
+            let response = app
+                .oneshot(authed_request(method, &path, body, &key))
+                .await
+                .unwrap();
+            let status = response.status();
+            (status, parse_body(response).await)
+        }
+    };
+    let tuples = json!([
+        {"user": "user:anne", "relation": "reader", "object": "doc:readme"},
+        {"user": "team:eng#member", "relation": "reader", "object": "doc:readme"},
+        {"user": "team:eng#admin", "relation": "reader", "object": "doc:spec"},
+        {"user": "user:dan", "relation": "member", "object": "team:eng"}
+    ])
+    .to_string();
+    let request = |dry_run: bool| json!({"format": "openfga", "schema": IMPORT_SCHEMA, "tuples": tuples, "dry_run": dry_run});
+
+    let (status, dry) = call("POST", format!("{base}/import"), Some(request(true))).await;
+    assert_eq!(status, StatusCode::OK, "{dry}");
+    assert_eq!(dry["applied"], json!(false));
+    assert_eq!(dry["counts"]["tuples"], 3);
+    let (_, listed) = call("GET", format!("{base}/tuples"), None).await;
+    assert_eq!(listed["items"], json!([]), "a dry run writes nothing");
+    let (_, history) = call("GET", format!("{base}/migrations"), None).await;
+    assert_eq!(history["model_version"], 0);
+
+    let (status, applied) = call("POST", format!("{base}/import"), Some(request(false))).await;
+    assert_eq!(status, StatusCode::OK, "{applied}");
+    assert_eq!(applied["applied"], json!(true));
+    assert!(applied["revision"].as_i64().unwrap() > 0);
+    let admin = applied["issues"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|i| i["location"] == "tuple 3")
+        .expect("the #admin userset is reported");
+    assert_eq!(admin["construct"], "userset");
+    assert_eq!(admin["reason"], "`doc#reader` does not allow `team#admin`");
+
+    let (_, listed) = call("GET", format!("{base}/tuples?object=doc:spec"), None).await;
+    assert_eq!(
+        listed["items"],
+        json!([]),
+        "the #admin userset is not imported"
+    );
+    let (_, listed) = call("GET", format!("{base}/tuples?object=doc:readme"), None).await;
+    let subjects: Vec<&str> = listed["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["subject"].as_str().unwrap())
+        .collect();
+    assert_eq!(subjects, ["team:eng", "user:anne"]);
+    let (_, model) = call("GET", format!("{base}/model"), None).await;
+    assert!(
+        model["relations"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|r| r["name"] == "reader" && r["object"] == "doc"),
+        "{model}"
+    );
+
+    let (_, history) = call("GET", format!("{base}/migrations"), None).await;
+    assert_eq!(history["model_version"], 1, "the import is a model version");
+    let entry = &history["migrations"][0];
+    assert_eq!(entry["model_version"], 1);
+    assert_eq!(entry["transforms"], json!([]));
+    assert_ne!(entry["model_before_hash"], entry["model_after_hash"]);
+}
+
+/// `import_records` writes the model through the same model-version guard
+/// as migrations: an import computed against a model that has since changed
+/// fails with VersionConflict and writes none of its records.
+#[tokio::test]
+async fn import_records_stale_model_version_conflicts() {
+    use reaper_management::db::repositories::{DatastoreRepository, NamespaceRepository};
+    use reaper_management::domain::datastore::{import_model, merge_model, ImportFormat};
+
+    let env = setup_test_env().await;
+    let (key, base, org_id) = seed_datastore(&env, "Stale Import Org", "stale-import-org").await;
+    let ns = NamespaceRepository::new(&env.db)
+        .get_by_slug(org_id, "main")
+        .await
+        .unwrap()
+        .unwrap();
+    let repo = DatastoreRepository::new(&env.db);
+    let stale = repo.get(org_id, ns.id).await.unwrap().unwrap();
+
+    let tuples =
+        json!([{"user": "user:anne", "relation": "reader", "object": "doc:readme"}]).to_string();
+    let import = import_model(ImportFormat::Openfga, IMPORT_SCHEMA, Some(&tuples)).unwrap();
+    let model = merge_model(&stale.model, &import.model).unwrap();
+    let version = repo
+        .import_records(&stale, &model, &import.entities, &import.tuples, "importer")
+        .await
+        .unwrap();
+    assert_eq!(version, 1);
+
+    // A migration lands between the next importer's read and its write.
+    let read = repo.get(org_id, ns.id).await.unwrap().unwrap();
+    let response = env
+        .app
+        .clone()
+        .oneshot(authed_request(
+            "POST",
+            &format!("{base}/migrations/apply"),
+            Some(json!({"transforms": [
+                {"op": "rename_role", "from": "editor", "to": "author"}
+            ]})),
+            &key,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let tuples =
+        json!([{"user": "user:bob", "relation": "reader", "object": "doc:spec"}]).to_string();
+    let import = import_model(ImportFormat::Openfga, IMPORT_SCHEMA, Some(&tuples)).unwrap();
+    let err = repo
+        .import_records(
+            &read,
+            &read.model,
+            &import.entities,
+            &import.tuples,
+            "importer",
+        )
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            reaper_management::db::DatabaseError::VersionConflict(_)
+        ),
+        "stale import must be a VersionConflict, got: {err:?}"
+    );
+    assert!(
+        err.to_string().contains("import was planned against"),
+        "{err}"
+    );
+
+    let current = repo.get(org_id, ns.id).await.unwrap().unwrap();
+    assert_eq!(current.model_version, 2);
+    let written = repo
+        .list_tuples(current.id, Some("doc:spec"), None, None)
+        .await
+        .unwrap();
+    assert!(written.is_empty(), "a conflicting import writes nothing");
+}
+
 // ============================================================================
 // RFC 9457 problem responses carry `instance` (round-2 C4, finding R2-08)
 // ============================================================================
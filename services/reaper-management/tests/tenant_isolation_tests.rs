@@ -551,6 +551,11 @@ async fn cross_tenant_corpus_refuses_every_resource_family() {
             json!({"entity_type":"User","entity_id":"e1","attributes":{}}),
         ),
         probe("GET", "/orgs/victim/namespaces/default/datastore/tuples"),
+        probe_body(
+            "POST",
+            "/orgs/victim/namespaces/default/datastore/import",
+            json!({"format":"spicedb","schema":"definition user {}","dry_run":true}),
+        ),
         // decisions (the audit read plane)
         probe("GET", "/orgs/victim/decisions"),
         probe("GET", "/orgs/victim/decisions/stats"),
@@ -65,6 +65,7 @@ pub async fn create_bundle(
             },
             condition: ReapCondition::True, // Simplified: all rules unconditional
             message: None,
+            obligations: None,
         })
         .collect();
 
@@ -0,0 +1,59 @@
+package docs.authz
+
+import rego.v1
+
+# Document access for the import-rego fixture. Recorded decisions for this
+# policy are in corpus.jsonl; data.json is the OPA data document.
+
+default allow := false
+
+user := data.users[input.principal]
+
+doc := data.documents[input.resource]
+
+editors := {"bob", "carol"}
+
+is_owner if {
+	doc.owner == input.principal
+}
+
+has_role(role) if {
+	some r in user.roles
+	r == role
+}
+
+# suspended_users
+blocked if {
+	user.suspended == true
+}
+
+# admins
+allow if {
+	not blocked
+	has_role("admin")
+}
+
+# owners
+allow if {
+	not blocked
+	is_owner
+	input.action in ["read", "write", "delete"]
+}
+
+# editors
+allow if {
+	not blocked
+	input.principal in editors
+	input.action == "write"
+	every tag in doc.tags {
+		tag != "locked"
+	}
+}
+
+# readers
+allow if {
+	not blocked
+	input.action == "read"
+	some team in user.teams
+	team == doc.team
+}
@@ -0,0 +1,108 @@
+//! `reaper-cli bundle diff`: which requests change outcome between two
+//! versions of a policy.
+//!
+//! Each side is a compiled bundle (`.rbb`), a package (`.rpp`), a source
+//! policy (`.reap`, `.yaml`, `.json`), or `REV:PATH` — a source file as of
+//! a git revision (`main:policies/docs.reap`). The comparison itself is
+//! [`reap::diff`]; sources read from git are parsed without resolving
+//! imports, so calls into libraries compare by their text.
+//!
+//! [`reap::diff`]: policy_engine::reap::diff
+
+use policy_engine::reap::diff::{self, DiffStatus};
+use policy_engine::reap::{Policy, ReapParser, YamlPolicy};
+use policy_engine::{PolicyBundle, PolicyPackage, ReaperPolicy};
+use std::path::Path;
+use std::process::Command;
+
+/// Print the outcome diff of `old` → `new`. Returns whether any policy was
+/// added, removed or changed.
+pub fn run(old: &str, new: &str, format: &str) -> anyhow::Result<bool> {
+    let json = match format.to_ascii_lowercase().as_str() {
+        "text" => false,
+        "json" => true,
+        other => anyhow::bail!("unknown format '{other}' (expected text or json)"),
+    };
+    let before = load(old)?;
+    let after = load(new)?;
+    let diffs = diff::diff_sets(
+        &before.iter().collect::<Vec<_>>(),
+        &after.iter().collect::<Vec<_>>(),
+    );
+    let differs = diffs.iter().any(|d| d.status != DiffStatus::Unchanged);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&diffs)?);
+        return Ok(differs);
+    }
+    for d in &diffs {
+        println!("policy {}: {}", d.policy, d.status.as_str());
+        for change in &d.changes {
+            println!("  {change}");
+        }
+        if d.truncated {
+            println!(
+                "  ... more than {} classes per direction; the rest are not shown",
+                diff::MAX_CLASSES
+            );
+        }
+    }
+    let changes: usize = diffs.iter().map(|d| d.changes.len()).sum();
+    eprintln!(
+        "{changes} outcome change(s) across {} polic{}",
+        diffs.len(),
+        if diffs.len() == 1 { "y" } else { "ies" }
+    );
+    Ok(differs)
+}
+
+/// The policies in one side of the diff.
+fn load(spec: &str) -> anyhow::Result<Vec<Policy>> {
+    let path = Path::new(spec);
+    if path.exists() {
+        let bytes =
+            std::fs::read(path).map_err(|e| anyhow::anyhow!("failed to read {spec}: {e}"))?;
+        if let Some(policies) = compiled(&bytes) {
+            return Ok(policies);
+        }
+        let policy = ReaperPolicy::from_file_auto(path)
+            .map_err(|e| anyhow::anyhow!("failed to load {spec}: {e}"))?;
+        return Ok(vec![policy.ast().clone()]);
+    }
+
+    let Some((rev, file)) = spec.split_once(':') else {
+        anyhow::bail!("{spec}: no such file (expected a path or REV:PATH)");
+    };
+    let output = Command::new("git")
+        .args(["show", &format!("{rev}:{file}")])
+        .output()
+        .map_err(|e| anyhow::anyhow!("failed to run git: {e}"))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git show {spec}: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    if let Some(policies) = compiled(&output.stdout) {
+        return Ok(policies);
+    }
+    let source = String::from_utf8(output.stdout)
+        .map_err(|_| anyhow::anyhow!("{spec}: not a UTF-8 policy source"))?;
+    let policy = match Path::new(file).extension().and_then(|e| e.to_str()) {
+        Some("yaml" | "yml") => YamlPolicy::from_yaml(&source).and_then(|y| y.to_ast()),
+        Some("json") => YamlPolicy::from_json(&source).and_then(|y| y.to_ast()),
+        _ => ReapParser::parse(&source),
+    }
+    .map_err(|e| anyhow::anyhow!("failed to parse {spec}: {e}"))?;
+    Ok(vec![policy])
+}
+
+/// The policies of a compiled bundle or package, if `bytes` is one.
+fn compiled(bytes: &[u8]) -> Option<Vec<Policy>> {
+    if let Ok(bundle) = PolicyBundle::from_bytes(bytes) {
+        return Some(vec![bundle.policy]);
+    }
+    PolicyPackage::from_bytes(bytes)
+        .ok()
+        .map(|package| package.policies.into_iter().map(|e| e.policy).collect())
+}
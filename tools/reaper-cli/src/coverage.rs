@@ -0,0 +1,77 @@
+//! `reaper-cli test-suite --coverage`: report formats and the CI gate.
+//!
+//! Collection happens in `handle_test`, which traces every test request
+//! into a [`CoverageReport`]; this module renders the report and applies
+//! `--min-coverage`.
+
+use policy_engine::reap::coverage::CoverageReport;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoverageFormat {
+    Text,
+    Json,
+    Lcov,
+    Cobertura,
+}
+
+impl FromStr for CoverageFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(CoverageFormat::Text),
+            "json" => Ok(CoverageFormat::Json),
+            "lcov" => Ok(CoverageFormat::Lcov),
+            "cobertura" | "xml" => Ok(CoverageFormat::Cobertura),
+            other => anyhow::bail!(
+                "unknown coverage format '{other}' (expected text, json, lcov or cobertura)"
+            ),
+        }
+    }
+}
+
+pub struct CoverageArgs<'a> {
+    pub format: CoverageFormat,
+    pub output: Option<&'a str>,
+    pub min_percent: Option<f64>,
+}
+
+/// Render `report`, write it, and check the threshold. Returns `false` when
+/// coverage is below `--min-coverage`.
+pub fn finish(report: &CoverageReport, args: &CoverageArgs<'_>) -> anyhow::Result<bool> {
+    let rendered = match args.format {
+        CoverageFormat::Text => report.to_text(),
+        CoverageFormat::Json => serde_json::to_string_pretty(&report.to_json())? + "\n",
+        CoverageFormat::Lcov => report.to_lcov(),
+        CoverageFormat::Cobertura => report.to_cobertura(),
+    };
+    match args.output {
+        Some(path) => {
+            std::fs::write(path, rendered)
+                .map_err(|e| anyhow::anyhow!("failed to write {path}: {e}"))?;
+            println!("Coverage report written to {path}");
+        }
+        None => {
+            println!("\nCoverage");
+            println!("═══════════════════════════════════════════════════════════════");
+            print!("{rendered}");
+        }
+    }
+
+    let Some(min) = args.min_percent else {
+        return Ok(true);
+    };
+    let summary = report.summary();
+    let lowest = summary.min_percent();
+    if lowest < min {
+        println!(
+            "\nCoverage below {min:.1}%: rules {:.1}%, branches {:.1}%, funcs {:.1}%",
+            summary.rule_percent(),
+            summary.branch_percent(),
+            summary.function_percent()
+        );
+        return Ok(false);
+    }
+    Ok(true)
+}
@@ -0,0 +1,103 @@
+//! `reaper-cli fmt`: canonical formatting of `.reap` files.
+//!
+//! Formatting itself is `policy_engine::reap::format::format_source`, which
+//! keeps comments and refuses output that would parse to a different policy.
+//! This module walks the paths, rewrites files (or, with `--check`, only
+//! reports them) and reports per-file errors without stopping at the first.
+
+use policy_engine::reap::format::format_source;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Format `paths` (files, or directories searched recursively for `*.reap`;
+/// hidden directories and `target` are skipped). `-` formats stdin to
+/// stdout. Returns `false` if any file failed to format or, with `check`,
+/// is not formatted.
+pub fn run(paths: &[String], check: bool) -> anyhow::Result<bool> {
+    if paths.iter().any(|p| p == "-") {
+        if paths.len() > 1 {
+            anyhow::bail!("`-` (stdin) cannot be combined with other paths");
+        }
+        return format_stdin(check);
+    }
+
+    let mut files = Vec::new();
+    let roots: Vec<PathBuf> = if paths.is_empty() {
+        vec![PathBuf::from(".")]
+    } else {
+        paths.iter().map(PathBuf::from).collect()
+    };
+    for root in &roots {
+        if root.is_dir() {
+            collect(root, &mut files)?;
+        } else if root.exists() {
+            files.push(root.clone());
+        } else {
+            anyhow::bail!("{}: no such file or directory", root.display());
+        }
+    }
+    files.sort();
+
+    let (mut changed, mut failed) = (0usize, 0usize);
+    for file in &files {
+        let source = std::fs::read_to_string(file)
+            .map_err(|e| anyhow::anyhow!("failed to read {}: {e}", file.display()))?;
+        let formatted = match format_source(&source) {
+            Ok(formatted) => formatted,
+            Err(e) => {
+                eprintln!("error: {}: {e}", file.display());
+                failed += 1;
+                continue;
+            }
+        };
+        if formatted == source {
+            continue;
+        }
+        changed += 1;
+        if check {
+            println!("would reformat {}", file.display());
+        } else {
+            std::fs::write(file, formatted)
+                .map_err(|e| anyhow::anyhow!("failed to write {}: {e}", file.display()))?;
+            println!("formatted {}", file.display());
+        }
+    }
+
+    let verb = if check {
+        "would be reformatted"
+    } else {
+        "reformatted"
+    };
+    let mut summary = format!("{changed} of {} file(s) {verb}", files.len() - failed);
+    if failed > 0 {
+        summary.push_str(&format!(", {failed} could not be formatted"));
+    }
+    eprintln!("{summary}");
+    Ok(failed == 0 && !(check && changed > 0))
+}
+
+fn format_stdin(check: bool) -> anyhow::Result<bool> {
+    let mut source = String::new();
+    std::io::stdin().read_to_string(&mut source)?;
+    let formatted = format_source(&source).map_err(|e| anyhow::anyhow!("<stdin>: {e}"))?;
+    if check {
+        return Ok(formatted == source);
+    }
+    std::io::stdout().write_all(formatted.as_bytes())?;
+    Ok(true)
+}
+
+pub(crate) fn collect(dir: &Path, out: &mut Vec<PathBuf>) -> anyhow::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        if path.is_dir() {
+            if !name.starts_with('.') && name != "target" {
+                collect(&path, out)?;
+            }
+        } else if path.extension().is_some_and(|ext| ext == "reap") {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
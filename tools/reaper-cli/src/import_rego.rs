@@ -0,0 +1,98 @@
+//! `reaper-cli import-rego`: translate an OPA policy into `.reap`.
+//!
+//! The translation is `policy_engine::reap::rego::import_rego`; this module
+//! handles files, converts OPA data to a Reaper entity document with the
+//! import's mapping, and replays a recorded decision corpus against the
+//! result.
+
+use policy_engine::reap::rego::{
+    import_rego, parse_corpus, verify_corpus, ImportOptions, RegoImport,
+};
+use serde_json::Value;
+
+pub struct ImportArgs<'a> {
+    pub input: &'a str,
+    pub output: Option<&'a str>,
+    pub options: ImportOptions,
+    pub data: Option<&'a str>,
+    pub data_out: Option<&'a str>,
+    pub corpus: Option<&'a str>,
+}
+
+/// Run the import. Returns `false` when a construct was left untranslated
+/// or a corpus record decided differently.
+pub fn run(args: &ImportArgs<'_>) -> anyhow::Result<bool> {
+    let source = read(args.input)?;
+    let import =
+        import_rego(&source, &args.options).map_err(|e| anyhow::anyhow!("{}: {e}", args.input))?;
+
+    match args.output {
+        Some(path) => {
+            std::fs::write(path, &import.source)
+                .map_err(|e| anyhow::anyhow!("failed to write {path}: {e}"))?;
+            eprintln!("wrote {path}");
+        }
+        None => print!("{}", import.source),
+    }
+    for issue in &import.issues {
+        eprintln!("warning: {}:{issue}", args.input);
+    }
+
+    let data = match args.data {
+        Some(path) => Some(
+            serde_json::from_str::<Value>(&read(path)?)
+                .map_err(|e| anyhow::anyhow!("{path}: {e}"))?,
+        ),
+        None => None,
+    };
+    if let Some(path) = args.data_out {
+        let Some(data) = &data else {
+            anyhow::bail!("--data-out needs --data (the OPA data document to convert)");
+        };
+        let entities = import.mapping.entity_document(data)?;
+        std::fs::write(path, serde_json::to_string_pretty(&entities)?)
+            .map_err(|e| anyhow::anyhow!("failed to write {path}: {e}"))?;
+        eprintln!("wrote {path}");
+    }
+
+    let mut ok = import.issues.is_empty();
+    if let Some(path) = args.corpus {
+        ok &= check_corpus(&import, data.as_ref(), path)?;
+    }
+    if !import.issues.is_empty() {
+        eprintln!(
+            "{} construct(s) not translated; the rules containing them were left out",
+            import.issues.len()
+        );
+    }
+    Ok(ok)
+}
+
+fn check_corpus(import: &RegoImport, data: Option<&Value>, path: &str) -> anyhow::Result<bool> {
+    let records = parse_corpus(&read(path)?).map_err(|e| anyhow::anyhow!("{path}: {e}"))?;
+    let report = verify_corpus(import, data, &records)?;
+    for mismatch in &report.mismatches {
+        eprintln!(
+            "mismatch: {path} record {}: OPA {:?}, reap {:?}{} (input {})",
+            mismatch.index,
+            mismatch.expected,
+            mismatch.actual,
+            mismatch
+                .error
+                .as_ref()
+                .map(|e| format!(" [{e}]"))
+                .unwrap_or_default(),
+            mismatch.input
+        );
+    }
+    eprintln!(
+        "corpus: {} of {} decision(s) match",
+        report.checked - report.mismatches.len(),
+        report.checked
+    );
+    Ok(report.mismatches.is_empty())
+}
+
+fn read(path: &str) -> anyhow::Result<String> {
+    std::fs::read_to_string(path).map_err(|e| anyhow::anyhow!("failed to read {path}: {e}"))
+}
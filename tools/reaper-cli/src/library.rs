@@ -37,7 +37,7 @@ struct Case {
     #[serde(default)]
     violations: Option<Vec<String>>,
     #[serde(default)]
-    context: Option<HashMap<String, String>>,
+    context: Option<HashMap<String, serde_json::Value>>,
 }
 
 fn root(path: Option<&str>) -> anyhow::Result<PathBuf> {
@@ -214,7 +214,7 @@ fn run_scenario(dir: &Path, manifest: &Manifest) -> anyhow::Result<Vec<(String,
             let mut context = case.context.clone().unwrap_or_default();
             context.insert(
                 "principal".to_string(),
-                case.principal.clone().unwrap_or_default(),
+                case.principal.clone().unwrap_or_default().into(),
             );
             let request = PolicyRequest {
                 resource: case.resource.clone().unwrap_or_default(),
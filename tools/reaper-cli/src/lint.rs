@@ -0,0 +1,97 @@
+//! `reaper-cli lint`: static analysis of `.reap` policies.
+//!
+//! The checks are `policy_engine::reap::lint`; this module loads the files
+//! (imports resolved, so imported helpers count as used), lints them as one
+//! package set so duplicates across files are found, and reports the
+//! findings with file and line. Library files are skipped: their funcs are
+//! used from the policies that import them.
+
+use policy_engine::reap::analysis;
+use policy_engine::reap::lint::{self, LintFinding};
+use policy_engine::ReaperPolicy;
+use std::path::PathBuf;
+
+/// Lint `paths` (files, or directories searched recursively for `*.reap`).
+/// Returns `false` if any file failed to load or any finding was reported.
+pub fn run(paths: &[String], format: &str) -> anyhow::Result<bool> {
+    let json = match format.to_ascii_lowercase().as_str() {
+        "text" => false,
+        "json" => true,
+        other => anyhow::bail!("unknown format '{other}' (expected text or json)"),
+    };
+
+    let mut files = Vec::new();
+    let roots: Vec<PathBuf> = if paths.is_empty() {
+        vec![PathBuf::from(".")]
+    } else {
+        paths.iter().map(PathBuf::from).collect()
+    };
+    for root in &roots {
+        if root.is_dir() {
+            crate::fmt::collect(root, &mut files)?;
+        } else if root.exists() {
+            files.push(root.clone());
+        } else {
+            anyhow::bail!("{}: no such file or directory", root.display());
+        }
+    }
+    files.sort();
+
+    let mut loaded: Vec<(PathBuf, String, ReaperPolicy)> = Vec::new();
+    let mut failed = 0usize;
+    for file in files {
+        let source = std::fs::read_to_string(&file)
+            .map_err(|e| anyhow::anyhow!("failed to read {}: {e}", file.display()))?;
+        if analysis::outline(&source).is_ok_and(|o| o.is_library) {
+            continue;
+        }
+        match ReaperPolicy::from_file(&file) {
+            Ok(policy) => loaded.push((file, source, policy)),
+            Err(e) => {
+                eprintln!("error: {}: {e}", file.display());
+                failed += 1;
+            }
+        }
+    }
+
+    let inputs: Vec<_> = loaded
+        .iter()
+        .map(|(_, source, policy)| (policy.ast(), Some(source.as_str())))
+        .collect();
+    let findings: Vec<(String, LintFinding)> = lint::lint_package(&inputs)
+        .into_iter()
+        .zip(&loaded)
+        .flat_map(|(found, (path, _, _))| {
+            let file = path.display().to_string();
+            found
+                .into_iter()
+                .map(move |finding| (file.clone(), finding))
+        })
+        .collect();
+
+    if json {
+        let report: Vec<serde_json::Value> = findings
+            .iter()
+            .map(|(file, finding)| {
+                let mut value = serde_json::to_value(finding)?;
+                value["file"] = file.as_str().into();
+                Ok(value)
+            })
+            .collect::<Result<_, serde_json::Error>>()?;
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        for (file, finding) in &findings {
+            let location = match finding.line {
+                Some(line) => format!("{file}:{line}"),
+                None => file.clone(),
+            };
+            println!("{location}: {finding}");
+        }
+        eprintln!(
+            "{} finding(s) in {} policy file(s)",
+            findings.len(),
+            loaded.len()
+        );
+    }
+    Ok(failed == 0 && findings.is_empty())
+}
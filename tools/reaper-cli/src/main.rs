@@ -9,6 +9,9 @@ use std::time::Instant;
 use uuid::Uuid;
 
 // Reap policy imports
+use coverage::CoverageArgs;
+use policy_engine::data::EntitySchema;
+use policy_engine::reap::coverage::CoverageReport;
 use policy_engine::{
     DataLoader, DataStore, PolicyAction as EngineAction, PolicyBundle, PolicyEvaluator,
     PolicyRequest, ReaperPolicy,
@@ -42,7 +45,14 @@ fn handle_analyze_policy(
 }
 
 mod airgap;
+mod bundle_diff;
+mod coverage;
+mod fmt;
+mod import_rego;
 mod library;
+mod lint;
+mod mutate;
+mod repl;
 
 #[derive(Parser)]
 #[command(name = "reaper")]
@@ -107,6 +117,11 @@ enum Commands {
         /// Show bundle metadata
         #[arg(long)]
         info: bool,
+
+        /// Entity schema (JSON) to type-check the policy against and embed
+        /// in the bundle
+        #[arg(long)]
+        schema: Option<String>,
     },
 
     /// Validate policy syntax
@@ -118,6 +133,10 @@ enum Commands {
         #[arg(short, long)]
         data: Option<String>,
 
+        /// Entity schema (JSON): type-check the policy, and the data if given
+        #[arg(long)]
+        schema: Option<String>,
+
         /// Show detailed parse tree
         #[arg(long)]
         verbose: bool,
@@ -156,6 +175,65 @@ enum Commands {
         format: String,
     },
 
+    /// Format .reap policies and libraries in canonical style, keeping
+    /// comments. Rewrites files in place; `--check` only lists the files that
+    /// would change and exits 1 if there are any (for CI).
+    Fmt {
+        /// Files or directories (searched recursively for *.reap); '-' reads
+        /// stdin and writes stdout. Default: the current directory
+        paths: Vec<String>,
+
+        /// Report unformatted files instead of rewriting them
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Statically analyze .reap policies: unsatisfiable conditions, allow
+    /// rules shadowed by a deny, duplicate rules across a package, unused
+    /// funcs and imports. Exits 1 if anything is found.
+    Lint {
+        /// Files or directories (searched recursively for *.reap), linted
+        /// together. Default: the current directory
+        paths: Vec<String>,
+
+        /// Output format: text or json
+        #[arg(long, default_value = "text")]
+        format: String,
+    },
+
+    /// Translate an OPA Rego module into a .reap policy. Constructs without a
+    /// faithful translation are reported and their rules left out; exits 1 if
+    /// any were, or if a `--corpus` decision differs.
+    ImportRego {
+        /// Rego source file
+        input: String,
+
+        /// Write the .reap policy here instead of stdout
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Policy name (default: the last package segment)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Decision rule (default: allow, else deny, else violation)
+        #[arg(long)]
+        entrypoint: Option<String>,
+
+        /// OPA data document (JSON) used by --data-out and --corpus
+        #[arg(long)]
+        data: Option<String>,
+
+        /// Write --data converted to a Reaper entity document
+        #[arg(long)]
+        data_out: Option<String>,
+
+        /// Recorded OPA decisions ({"input", "result"} per line, or a JSON
+        /// array) to replay against the translation
+        #[arg(long)]
+        corpus: Option<String>,
+    },
+
     /// Generate a bundle signing keypair (Ed25519 or ECDSA P-256)
     Keygen {
         /// Signature algorithm: ed25519-sha256 or ecdsa-p256-sha256
@@ -308,6 +386,69 @@ enum Commands {
         /// Stop on first failure
         #[arg(long)]
         fail_fast: bool,
+
+        /// Record which rules matched, which condition branches were true
+        /// and false, and which funcs were called; report as text, json,
+        /// lcov or cobertura
+        #[arg(long, value_name = "FORMAT")]
+        coverage: Option<String>,
+
+        /// Write the coverage report here instead of stdout
+        #[arg(long, value_name = "PATH", requires = "coverage")]
+        coverage_output: Option<String>,
+
+        /// Exit 1 unless rule, branch and func coverage are each at least
+        /// this percentage
+        #[arg(long, value_name = "PERCENT", requires = "coverage")]
+        min_coverage: Option<f64>,
+    },
+
+    /// Mutation-test a .reap policy: rerun the suite's cases for it against
+    /// each single-point mutant (flipped operators and effects, changed
+    /// literals, dropped conjuncts) and report the mutants no test catches
+    Mutate {
+        /// Path to the .reap policy to mutate
+        policy: String,
+
+        /// Test suite YAML; the cases whose `policy` is this file are run
+        #[arg(short, long)]
+        suite: String,
+
+        /// Output format: text or json
+        #[arg(long, default_value = "text")]
+        format: String,
+
+        /// Exit 1 when the mutation score (killed / valid mutants) is below
+        /// this percentage
+        #[arg(long, value_name = "PERCENT", default_value_t = 100.0)]
+        min_score: f64,
+    },
+
+    /// Interactive session: evaluate .reap expressions and requests against
+    /// loaded policies, libraries and entity data (reloaded on change)
+    Repl {
+        /// Policies (.reap/.yaml/.json) or .reap libraries to load
+        files: Vec<String>,
+
+        /// Entity data files (JSON)
+        #[arg(short, long)]
+        data: Vec<String>,
+
+        /// Request principal
+        #[arg(long, default_value = "")]
+        principal: String,
+
+        /// Request action
+        #[arg(long, default_value = "")]
+        action: String,
+
+        /// Request resource
+        #[arg(long, default_value = "")]
+        resource: String,
+
+        /// Request actor (delegation)
+        #[arg(long)]
+        actor: Option<String>,
     },
 }
 
@@ -318,6 +459,22 @@ enum BundleAction {
         /// Path to .rbb bundle file
         file: String,
     },
+    /// Show which requests change outcome between two policy versions
+    Diff {
+        /// Old version: .rbb, .rpp, source policy, or REV:PATH (git revision)
+        old: String,
+
+        /// New version: .rbb, .rpp, source policy, or REV:PATH (git revision)
+        new: String,
+
+        /// Output format: text or json
+        #[arg(long, default_value = "text")]
+        format: String,
+
+        /// Exit with status 1 when any outcome changes
+        #[arg(long = "exit-code")]
+        exit_code: bool,
+    },
     /// Deploy bundle to agent
     Deploy {
         /// Path to .rbb bundle file
@@ -662,6 +819,35 @@ enum ManagementAction {
         #[arg(short, long)]
         description: Option<String>,
     },
+
+    /// Import an OpenFGA model or SpiceDB schema, with its tuples, into a
+    /// namespace's datastore. Constructs the datastore model cannot express
+    /// are reported and left out; exits 1 if any were.
+    ImportModel {
+        /// Organization ID or name
+        #[arg(short, long)]
+        org: String,
+
+        /// Namespace slug
+        #[arg(long, default_value = "default")]
+        namespace: String,
+
+        /// Schema language of the source
+        #[arg(long, value_parser = ["openfga", "spicedb"])]
+        format: String,
+
+        /// Model file: OpenFGA JSON or DSL, or a SpiceDB schema
+        #[arg(long)]
+        schema: String,
+
+        /// Tuple dump: OpenFGA JSON tuples, or SpiceDB relationships one per line
+        #[arg(long)]
+        tuples: Option<String>,
+
+        /// Convert and report without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 // ============================================================================
@@ -762,7 +948,7 @@ fn handle_eval(
     // Evaluate policy
     println!("5️⃣  Evaluating policy...");
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), principal.to_string());
+    context.insert("principal".to_string(), principal.into());
 
     let request = PolicyRequest {
         resource: resource.to_string(),
@@ -823,6 +1009,7 @@ fn handle_compile(
     output_path: &str,
     _optimize: bool,
     show_info: bool,
+    schema_path: Option<&str>,
 ) -> anyhow::Result<()> {
     println!("🔨 Compiling Reaper Policy Bundle\n");
 
@@ -855,14 +1042,20 @@ fn handle_compile(
     if let Some(version) = policy.version() {
         println!("   ✓ Version: {}", version);
     }
+    let schema = schema_path.map(load_schema).transpose()?;
+    if let (Some(path), Some(schema)) = (schema_path, &schema) {
+        print_type_check(&policy, schema, path)?;
+    }
     println!();
 
     // Compile to bundle
     println!("2️⃣  Compiling to binary bundle...");
     let compile_start = Instant::now();
-    let bundle_bytes = policy
-        .compile_to_bundle()
-        .map_err(|e| anyhow::anyhow!("❌ Compilation failed: {:?}", e))?;
+    let bundle_bytes = match &schema {
+        Some(schema) => policy.compile_to_bundle_with_schema(schema),
+        None => policy.compile_to_bundle(),
+    }
+    .map_err(|e| anyhow::anyhow!("❌ Compilation failed: {:?}", e))?;
     let compile_time = compile_start.elapsed();
 
     println!("   ✓ Compiled successfully");
@@ -891,6 +1084,9 @@ fn handle_compile(
                 println!("   • Compiled at: {}", bundle.metadata.compiled_at);
                 println!("   • Checksum: {:x}", bundle.metadata.source_checksum);
                 println!("   • Rules: {}", bundle.policy.rules.len());
+                if let Some(schema) = &bundle.schema {
+                    println!("   • Entity schema: {} type(s)", schema.entity_types.len());
+                }
             }
             Err(e) => {
                 println!("   ⚠️  Could not read bundle metadata: {:?}", e);
@@ -1276,7 +1472,7 @@ fn handle_check(
 
     let mut context = std::collections::HashMap::new();
     if let Some(pr) = principal {
-        context.insert("principal".to_string(), pr.to_string());
+        context.insert("principal".to_string(), pr.into());
     }
     let request = PolicyRequest {
         resource: resource.unwrap_or(input_path).to_string(),
@@ -1317,9 +1513,41 @@ fn handle_check(
     Ok(())
 }
 
+/// Read and validate an entity schema file.
+fn load_schema(path: &str) -> anyhow::Result<EntitySchema> {
+    EntitySchema::from_file(path)
+        .map_err(|e| anyhow::anyhow!("❌ Invalid entity schema {}: {}", path, e))
+}
+
+/// Print the type check of `policy` against `schema`; fails if there are
+/// diagnostics.
+fn print_type_check(
+    policy: &ReaperPolicy,
+    schema: &EntitySchema,
+    schema_path: &str,
+) -> anyhow::Result<()> {
+    let diagnostics = policy.type_check(schema);
+    if diagnostics.is_empty() {
+        println!("   ✓ Type-checks against {}", schema_path);
+        return Ok(());
+    }
+    println!();
+    println!("❌ TYPE ERRORS ({})", schema_path);
+    println!("══════════════════════════════════════════════════════");
+    for diagnostic in &diagnostics {
+        println!("   • {}", diagnostic);
+    }
+    println!("══════════════════════════════════════════════════════");
+    anyhow::bail!(
+        "{} type error(s) against the entity schema",
+        diagnostics.len()
+    )
+}
+
 fn handle_validate(
     policy_path: &str,
     data_path: Option<&str>,
+    schema_path: Option<&str>,
     verbose: bool,
 ) -> anyhow::Result<()> {
     println!("✅ Validating Reaper Policy\n");
@@ -1365,6 +1593,10 @@ fn handle_validate(
     if let Some(version) = policy.version() {
         println!("   • Version: {}", version);
     }
+    let schema = schema_path.map(load_schema).transpose()?.map(Arc::new);
+    if let (Some(path), Some(schema)) = (schema_path, &schema) {
+        print_type_check(&policy, schema, path)?;
+    }
     println!();
 
     // Validate with data if provided
@@ -1379,7 +1611,10 @@ fn handle_validate(
             .map_err(|e| anyhow::anyhow!("❌ Failed to read data file: {}", e))?;
 
         let store = DataStore::new();
-        let loader = DataLoader::new(store.clone());
+        let mut loader = DataLoader::new(store.clone());
+        if let Some(schema) = &schema {
+            loader = loader.with_schema(Arc::clone(schema));
+        }
 
         let entity_count = match loader.load_json(&data_content) {
             Ok(count) => count,
@@ -1452,6 +1687,7 @@ struct TestSuiteDefinition {
 }
 
 /// Handle: reaper test
+#[allow(clippy::too_many_arguments)]
 fn handle_test(
     policy_path: &str,
     data_path: &str,
@@ -1460,6 +1696,7 @@ fn handle_test(
     resource: &str,
     expect: &str,
     verbose: bool,
+    coverage: Option<&mut CoverageReport>,
 ) -> anyhow::Result<bool> {
     // Validate expected value
     let expected_decision = match expect.to_lowercase().as_str() {
@@ -1493,6 +1730,15 @@ fn handle_test(
         .load_json(&data_content)
         .map_err(|e| anyhow::anyhow!("Failed to load data: {:?}", e))?;
 
+    // Coverage needs the policy itself; `build` consumes it.
+    let coverage = coverage.map(|report| {
+        let source = policy_path
+            .ends_with(".reap")
+            .then(|| fs::read_to_string(policy_path).ok())
+            .flatten();
+        report.file(policy_path, &policy, source.as_deref())
+    });
+
     // Build evaluator
     let store = Arc::new(store);
     let evaluator = policy
@@ -1501,7 +1747,7 @@ fn handle_test(
 
     // Evaluate
     let mut context = HashMap::new();
-    context.insert("principal".to_string(), principal.to_string());
+    context.insert("principal".to_string(), principal.into());
 
     let request = PolicyRequest {
         resource: resource.to_string(),
@@ -1517,6 +1763,13 @@ fn handle_test(
         .map_err(|e| anyhow::anyhow!("Evaluation failed: {:?}", e))?;
     let eval_time = eval_start.elapsed();
 
+    if let Some(coverage) = coverage {
+        let trace = evaluator
+            .explain(&request)
+            .map_err(|e| anyhow::anyhow!("Coverage trace failed: {:?}", e))?;
+        coverage.record(&trace);
+    }
+
     // Compare result
     let passed = actual_decision == expected_decision;
 
@@ -1547,7 +1800,12 @@ fn handle_test(
 }
 
 /// Handle: reaper test-suite
-fn handle_test_suite(suite_path: &str, verbose: bool, fail_fast: bool) -> anyhow::Result<bool> {
+fn handle_test_suite(
+    suite_path: &str,
+    verbose: bool,
+    fail_fast: bool,
+    coverage: Option<CoverageArgs<'_>>,
+) -> anyhow::Result<bool> {
     // Load test suite
     if !Path::new(suite_path).exists() {
         anyhow::bail!("Test suite file not found: {}", suite_path);
@@ -1569,6 +1827,7 @@ fn handle_test_suite(suite_path: &str, verbose: bool, fail_fast: bool) -> anyhow
     let mut passed = 0;
     let mut failed = 0;
     let mut failures: Vec<String> = Vec::new();
+    let mut report = coverage.as_ref().map(|_| CoverageReport::default());
     let start_time = Instant::now();
 
     for test in &suite.tests {
@@ -1584,6 +1843,7 @@ fn handle_test_suite(suite_path: &str, verbose: bool, fail_fast: bool) -> anyhow
             &test.resource,
             &test.expect,
             verbose,
+            report.as_mut(),
         ) {
             Ok(true) => {
                 passed += 1;
@@ -1636,7 +1896,12 @@ fn handle_test_suite(suite_path: &str, verbose: bool, fail_fast: bool) -> anyhow
         println!("\nAll tests passed!");
     }
 
-    Ok(failed == 0)
+    let covered = match (&report, &coverage) {
+        (Some(report), Some(args)) => coverage::finish(report, args)?,
+        _ => true,
+    };
+
+    Ok(failed == 0 && covered)
 }
 
 // ============================================================================
@@ -1668,13 +1933,15 @@ async fn main() -> anyhow::Result<()> {
             ref output,
             optimize,
             info,
-        } => handle_compile(input, output, optimize, info)?,
+            ref schema,
+        } => handle_compile(input, output, optimize, info, schema.as_deref())?,
 
         Commands::Validate {
             ref policy,
             ref data,
+            ref schema,
             verbose,
-        } => handle_validate(policy, data.as_deref(), verbose)?,
+        } => handle_validate(policy, data.as_deref(), schema.as_deref(), verbose)?,
 
         Commands::Check {
             ref policy,
@@ -1694,6 +1961,46 @@ async fn main() -> anyhow::Result<()> {
             format,
         )?,
 
+        Commands::Fmt { ref paths, check } => {
+            if !fmt::run(paths, check)? {
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Lint {
+            ref paths,
+            ref format,
+        } => {
+            if !lint::run(paths, format)? {
+                std::process::exit(1);
+            }
+        }
+
+        Commands::ImportRego {
+            ref input,
+            ref output,
+            ref name,
+            ref entrypoint,
+            ref data,
+            ref data_out,
+            ref corpus,
+        } => {
+            let args = import_rego::ImportArgs {
+                input,
+                output: output.as_deref(),
+                options: policy_engine::reap::rego::ImportOptions {
+                    name: name.clone(),
+                    entrypoint: entrypoint.clone(),
+                },
+                data: data.as_deref(),
+                data_out: data_out.as_deref(),
+                corpus: corpus.as_deref(),
+            };
+            if !import_rego::run(&args)? {
+                std::process::exit(1);
+            }
+        }
+
         Commands::Keygen {
             ref algorithm,
             ref key_id,
@@ -1776,7 +2083,9 @@ async fn main() -> anyhow::Result<()> {
             ref expect,
             verbose,
         } => {
-            let passed = handle_test(policy, data, principal, action, resource, expect, verbose)?;
+            let passed = handle_test(
+                policy, data, principal, action, resource, expect, verbose, None,
+            )?;
             if !passed {
                 std::process::exit(1);
             }
@@ -1786,12 +2095,51 @@ async fn main() -> anyhow::Result<()> {
             ref file,
             verbose,
             fail_fast,
+            ref coverage,
+            ref coverage_output,
+            min_coverage,
         } => {
-            let all_passed = handle_test_suite(file, verbose, fail_fast)?;
+            let coverage = match coverage {
+                Some(format) => Some(CoverageArgs {
+                    format: format.parse()?,
+                    output: coverage_output.as_deref(),
+                    min_percent: min_coverage,
+                }),
+                None => None,
+            };
+            let all_passed = handle_test_suite(file, verbose, fail_fast, coverage)?;
             if !all_passed {
                 std::process::exit(1);
             }
         }
+
+        Commands::Mutate {
+            ref policy,
+            ref suite,
+            ref format,
+            min_score,
+        } => {
+            if !mutate::handle_mutate(policy, suite, format, min_score)? {
+                std::process::exit(1);
+            }
+        }
+        Commands::Repl {
+            ref files,
+            ref data,
+            ref principal,
+            ref action,
+            ref resource,
+            ref actor,
+        } => {
+            let request = repl::RequestArgs {
+                principal: principal.clone(),
+                action: action.clone(),
+                resource: resource.clone(),
+                actor: actor.clone(),
+                ..Default::default()
+            };
+            repl::run(files, data, request)?;
+        }
     }
 
     Ok(())
@@ -1804,6 +2152,17 @@ async fn handle_bundle_action(
     client: &Client,
 ) -> anyhow::Result<()> {
     match action {
+        BundleAction::Diff {
+            old,
+            new,
+            format,
+            exit_code,
+        } => {
+            if bundle_diff::run(old, new, format)? && *exit_code {
+                std::process::exit(1);
+            }
+        }
+
         BundleAction::Info { file } => {
             println!("📦 Bundle Information\n");
 
@@ -3614,6 +3973,76 @@ async fn handle_management_action(
                 println!("   ⚠️  Bundle created but promotion failed: {}", error_text);
             }
         }
+
+        ManagementAction::ImportModel {
+            org,
+            namespace,
+            format,
+            schema,
+            tuples,
+            dry_run,
+        } => {
+            let read = |path: &str| {
+                fs::read_to_string(path)
+                    .map_err(|e| anyhow::anyhow!("❌ Failed to read {}: {}", path, e))
+            };
+            let body = json!({
+                "format": format,
+                "schema": read(schema)?,
+                "tuples": tuples.as_deref().map(read).transpose()?,
+                "dry_run": dry_run,
+            });
+            let response = build_post(
+                client,
+                &format!(
+                    "{}/api/v1/orgs/{}/namespaces/{}/datastore/import",
+                    management_url, org, namespace
+                ),
+            )
+            .json(&body)
+            .send()
+            .await?;
+            if !response.status().is_success() {
+                let error_text = response.text().await?;
+                anyhow::bail!("❌ Import failed: {}", error_text);
+            }
+
+            let result: Value = response.json().await?;
+            let count = |key: &str| result["counts"][key].as_u64().unwrap_or(0);
+            println!(
+                "{} {} entities, {} tuples from {}",
+                if *dry_run {
+                    "🔎 Would import"
+                } else {
+                    "✅ Imported"
+                },
+                count("entities"),
+                count("tuples"),
+                schema
+            );
+            let issues = result["issues"].as_array().cloned().unwrap_or_default();
+            for issue in &issues {
+                let occurrences = issue["occurrences"].as_u64().unwrap_or(1);
+                eprintln!(
+                    "warning: {}: {}: {}{}",
+                    issue["location"].as_str().unwrap_or("?"),
+                    issue["construct"].as_str().unwrap_or("?"),
+                    issue["reason"].as_str().unwrap_or(""),
+                    if occurrences > 1 {
+                        format!(" ({occurrences} tuples)")
+                    } else {
+                        String::new()
+                    }
+                );
+            }
+            if !dry_run {
+                println!("   📦 Publish the datastore to ship the imported data");
+            }
+            if !issues.is_empty() {
+                eprintln!("{} construct(s) not imported", issues.len());
+                std::process::exit(1);
+            }
+        }
     }
 
     Ok(())
@@ -0,0 +1,273 @@
+//! `reaper-cli mutate`: mutation testing for policy test suites.
+//!
+//! The policy is rewritten one change at a time ([`reap::mutate`]) and the
+//! suite's cases for that policy are rerun against every mutant. A mutant
+//! is killed when some case now gets the wrong decision (or fails to
+//! evaluate); one that passes every case survives and points at a check
+//! the suite never pins down.
+//!
+//! [`reap::mutate`]: policy_engine::reap::mutate
+
+use crate::TestSuiteDefinition;
+use policy_engine::reap::mutate::{self, Mutant, MutationKind};
+use policy_engine::reap::ReaperPolicy;
+use policy_engine::{
+    DataLoader, DataStore, PolicyAction as EngineAction, PolicyEvaluator, PolicyRequest,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+/// One suite case, loaded.
+struct Case {
+    name: String,
+    request: PolicyRequest,
+    expected: EngineAction,
+}
+
+/// The cases sharing one data file: one evaluator build serves them all.
+struct Group {
+    store: Arc<DataStore>,
+    cases: Vec<Case>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Status {
+    Killed,
+    Survived,
+    /// The mutant does not build (e.g. a literal change a schema rejects);
+    /// not counted in the score.
+    Invalid,
+}
+
+#[derive(Serialize)]
+struct Outcome<'a> {
+    #[serde(flatten)]
+    mutant: &'a Mutant,
+    status: Status,
+    /// The first case that caught the mutant, or the build error.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+}
+
+/// Handle: reaper mutate. Returns `false` when the mutation score is below
+/// `min_score`.
+pub fn handle_mutate(
+    policy_path: &str,
+    suite_path: &str,
+    format: &str,
+    min_score: f64,
+) -> anyhow::Result<bool> {
+    let json = match format.to_ascii_lowercase().as_str() {
+        "text" => false,
+        "json" => true,
+        other => anyhow::bail!("unknown format '{other}' (expected text or json)"),
+    };
+    if !policy_path.ends_with(".reap") {
+        anyhow::bail!("mutate works on .reap policies: {policy_path}");
+    }
+    let source = fs::read_to_string(policy_path)
+        .map_err(|e| anyhow::anyhow!("Failed to read {policy_path}: {e}"))?;
+    let policy = ReaperPolicy::from_file(policy_path)
+        .map_err(|e| anyhow::anyhow!("Failed to parse policy: {:?}", e))?;
+    let groups = load_cases(policy_path, suite_path)?;
+    let tests: usize = groups.iter().map(|g| g.cases.len()).sum();
+
+    // A suite that already fails says nothing about the mutants.
+    if let Some(failure) = first_failure(&policy, &groups)? {
+        anyhow::bail!(
+            "the suite fails against the unmodified policy ({failure}); fix it before mutation testing"
+        );
+    }
+
+    let mutants = mutate::mutants(&policy, Some(&source));
+    let outcomes: Vec<Outcome<'_>> = mutants
+        .iter()
+        .map(|mutant| match first_failure(&mutant.policy(), &groups) {
+            Err(e) => Outcome {
+                mutant,
+                status: Status::Invalid,
+                detail: Some(e.to_string()),
+            },
+            Ok(failure) => match failure {
+                Some(failure) => Outcome {
+                    mutant,
+                    status: Status::Killed,
+                    detail: Some(failure),
+                },
+                None => Outcome {
+                    mutant,
+                    status: Status::Survived,
+                    detail: None,
+                },
+            },
+        })
+        .collect();
+
+    let count = |status: fn(&Status) -> bool| outcomes.iter().filter(|o| status(&o.status)).count();
+    let killed = count(|s| matches!(s, Status::Killed));
+    let survived = count(|s| matches!(s, Status::Survived));
+    let invalid = count(|s| matches!(s, Status::Invalid));
+    let score = if killed + survived == 0 {
+        100.0
+    } else {
+        killed as f64 * 100.0 / (killed + survived) as f64
+    };
+
+    if json {
+        let report = serde_json::json!({
+            "policy": policy_path,
+            "suite": suite_path,
+            "tests": tests,
+            "mutants": outcomes,
+            "summary": {
+                "mutants": outcomes.len(),
+                "killed": killed,
+                "survived": survived,
+                "invalid": invalid,
+                "score": score,
+            },
+        });
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!(
+            "Mutation testing {} against {} test(s) from {}\n",
+            policy_path, tests, suite_path
+        );
+        println!(
+            "Mutants: {} generated, {} killed, {} survived, {} invalid",
+            outcomes.len(),
+            killed,
+            survived,
+            invalid
+        );
+        println!("Mutation score: {score:.1}%");
+        let survivors: Vec<&Outcome<'_>> = outcomes
+            .iter()
+            .filter(|o| matches!(o.status, Status::Survived))
+            .collect();
+        if !survivors.is_empty() {
+            println!("\nSurviving mutants:");
+            for outcome in survivors {
+                println!("  {}", describe(policy_path, outcome.mutant));
+            }
+        }
+    }
+
+    if score < min_score {
+        if !json {
+            println!("\nMutation score below {min_score:.1}%");
+        }
+        return Ok(false);
+    }
+    Ok(true)
+}
+
+/// `path:line: rule name: change (kind)`.
+fn describe(path: &str, mutant: &Mutant) -> String {
+    let location = match mutant.line {
+        Some(line) => format!("{path}:{line}"),
+        None => path.to_string(),
+    };
+    let (change, kind) = match mutant.kind {
+        MutationKind::DropConjunct => (format!("dropped `{}`", mutant.original), "drop conjunct"),
+        kind => (
+            format!("`{}` → `{}`", mutant.original, mutant.replacement),
+            match kind {
+                MutationKind::Operator => "operator",
+                MutationKind::Literal => "literal",
+                _ => "flip effect",
+            },
+        ),
+    };
+    format!("{location}: {}: {change} ({kind})", mutant.declaration)
+}
+
+/// The first case `policy` gets wrong, as `name: reason`. Building the
+/// evaluator can fail (a mutant the compiler rejects); that is the `Err`.
+fn first_failure(policy: &ReaperPolicy, groups: &[Group]) -> anyhow::Result<Option<String>> {
+    for group in groups {
+        let evaluator = policy
+            .clone()
+            .build(group.store.clone())
+            .map_err(|e| anyhow::anyhow!("Failed to build evaluator: {:?}", e))?;
+        for case in &group.cases {
+            let failure = match evaluator.evaluate(&case.request) {
+                Ok(decision) if decision == case.expected => continue,
+                Ok(decision) => format!(
+                    "{}: got {:?}, expected {:?}",
+                    case.name, decision, case.expected
+                ),
+                Err(e) => format!("{}: {:?}", case.name, e),
+            };
+            return Ok(Some(failure));
+        }
+    }
+    Ok(None)
+}
+
+/// The suite's cases whose `policy` is `policy_path`, with their data
+/// grouped by data file.
+fn load_cases(policy_path: &str, suite_path: &str) -> anyhow::Result<Vec<Group>> {
+    let content = fs::read_to_string(suite_path)
+        .map_err(|e| anyhow::anyhow!("Failed to read test suite: {}", e))?;
+    let suite: TestSuiteDefinition = serde_yaml::from_str(&content)
+        .map_err(|e| anyhow::anyhow!("Failed to parse test suite YAML: {}", e))?;
+
+    let target = Path::new(policy_path)
+        .canonicalize()
+        .map_err(|e| anyhow::anyhow!("Policy file not found: {policy_path}: {e}"))?;
+    let mut groups: Vec<Group> = Vec::new();
+    let mut by_data: HashMap<String, usize> = HashMap::new();
+    for test in suite.tests {
+        if Path::new(&test.policy).canonicalize().ok().as_ref() != Some(&target) {
+            continue;
+        }
+        let expected = match test.expect.to_lowercase().as_str() {
+            "allow" => EngineAction::Allow,
+            "deny" => EngineAction::Deny,
+            _ => anyhow::bail!(
+                "{}: invalid expected decision '{}'. Must be 'allow' or 'deny'",
+                test.name,
+                test.expect
+            ),
+        };
+        let group = match by_data.get(&test.data) {
+            Some(&index) => index,
+            None => {
+                let data = fs::read_to_string(&test.data).map_err(|e| {
+                    anyhow::anyhow!("Failed to read data file {}: {}", test.data, e)
+                })?;
+                let store = DataStore::new();
+                DataLoader::new(store.clone())
+                    .load_json(&data)
+                    .map_err(|e| anyhow::anyhow!("Failed to load data: {:?}", e))?;
+                groups.push(Group {
+                    store: Arc::new(store),
+                    cases: Vec::new(),
+                });
+                by_data.insert(test.data.clone(), groups.len() - 1);
+                groups.len() - 1
+            }
+        };
+        let mut context = HashMap::new();
+        context.insert("principal".to_string(), test.principal.as_str().into());
+        groups[group].cases.push(Case {
+            name: test.name,
+            request: PolicyRequest {
+                resource: test.resource,
+                action: test.action,
+                context,
+                ..Default::default()
+            },
+            expected,
+        });
+    }
+    if groups.is_empty() {
+        anyhow::bail!("no test in {suite_path} uses {policy_path}");
+    }
+    Ok(groups)
+}
@@ -0,0 +1,448 @@
+//! `reaper-cli repl`: an interactive session over policies, libraries and
+//! entity data.
+//!
+//! A line is either a `:command` or a `.reap` snippet — an expression, a
+//! condition, or `name := value` — evaluated against the current request
+//! with the focused policy's funcs and every loaded library in scope
+//! ([`ReapAstEvaluator::evaluate_snippet`]). Files are watched by mtime and
+//! reloaded before the next line whenever one changes.
+//!
+//! Lines are read from stdin, so a session can also be piped in:
+//! `reaper-cli repl rbac.reap -d entities.json < session.txt`.
+
+use policy_engine::clock;
+use policy_engine::reap::{
+    analysis, load_library, Decision, FuncDef, Policy, ReapAstEvaluator, ReaperPolicy, SnippetScope,
+};
+use policy_engine::{DataLoader, DataStore, PolicyAction, PolicyRequest};
+use std::collections::HashMap;
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+const HELP: &str = "\
+Type a .reap expression or condition to evaluate it against the current
+request, or `name := value` to bind a variable for later lines.
+
+:load FILE...            load policies (.reap/.yaml/.json) or .reap libraries
+:data FILE...            load entity data (JSON)
+:reload                  reload every file now (changed files reload on their own)
+:request P ACTION R      set the request's principal, action and resource
+:actor ID|none           set or clear the request's actor
+:context [KEY JSON]      show the request context, or set one key
+:input FILE|none         set or clear the request's input document
+:eval                    decide the current request with every loaded policy
+:use POLICY              evaluate snippets with this policy's funcs
+:entity ID               show an entity's attributes
+:rels ID                 show an entity's relationships
+:clock [NS|RFC3339|now]  show, pin or unpin the evaluation clock
+:vars                    show bound variables
+:reset                   forget bound variables
+:help                    this text
+:quit                    leave (or end of input)";
+
+/// Start a session with `files` (policies or libraries) and `data` loaded.
+pub fn run(files: &[String], data: &[String], request: RequestArgs) -> anyhow::Result<()> {
+    let mut session = Session {
+        policies: Vec::new(),
+        libraries: Vec::new(),
+        data: Vec::new(),
+        mtimes: HashMap::new(),
+        store: Arc::new(DataStore::new()),
+        focus: None,
+        scope: SnippetScope::default(),
+        request,
+        pinned: false,
+    };
+    for file in files {
+        session.load(Path::new(file))?;
+    }
+    for file in data {
+        session.data.push(PathBuf::from(file));
+    }
+    session.reload_data()?;
+    session.stamp();
+
+    let interactive = std::io::stdin().is_terminal();
+    if interactive {
+        println!("reaper repl — :help for commands, :quit to leave");
+    }
+    let stdin = std::io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        if interactive {
+            print!("reap> ");
+            std::io::stdout().flush()?;
+        }
+        let Some(line) = lines.next() else {
+            break;
+        };
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("//") {
+            continue;
+        }
+        if let Err(e) = session.reload_changed() {
+            eprintln!("error: reload failed: {e}");
+        }
+        match session.line(line) {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(e) => eprintln!("error: {e}"),
+        }
+    }
+    clock::clear_injected_now();
+    Ok(())
+}
+
+/// The request lines are evaluated against; set from the command line and
+/// changed with `:request`, `:actor`, `:context` and `:input`.
+#[derive(Default)]
+pub struct RequestArgs {
+    pub principal: String,
+    pub action: String,
+    pub resource: String,
+    pub actor: Option<String>,
+    pub context: HashMap<String, serde_json::Value>,
+    pub input: Option<serde_json::Value>,
+}
+
+struct Session {
+    policies: Vec<(PathBuf, ReaperPolicy)>,
+    /// `(path, library name, funcs namespaced under it)`.
+    libraries: Vec<(PathBuf, String, Vec<FuncDef>)>,
+    data: Vec<PathBuf>,
+    mtimes: HashMap<PathBuf, Option<SystemTime>>,
+    store: Arc<DataStore>,
+    /// Policy whose funcs snippets see; the first loaded when `None`.
+    focus: Option<String>,
+    scope: SnippetScope,
+    request: RequestArgs,
+    /// Whether `:clock` pinned the evaluation clock.
+    pinned: bool,
+}
+
+impl Session {
+    /// Handle one line; `false` ends the session.
+    fn line(&mut self, line: &str) -> anyhow::Result<bool> {
+        let Some(command) = line.strip_prefix(':') else {
+            let value = self.snippet_evaluator().evaluate_snippet(
+                line,
+                &self.policy_request(),
+                self.request.input.as_ref(),
+                &mut self.scope,
+            )?;
+            println!("{}", render(&value));
+            return Ok(true);
+        };
+
+        let mut words = command.split_whitespace();
+        let name = words.next().unwrap_or_default();
+        let args: Vec<&str> = words.collect();
+        match (name, args.as_slice()) {
+            ("q" | "quit" | "exit", []) => return Ok(false),
+            ("h" | "help", []) => println!("{HELP}"),
+            ("load", files) if !files.is_empty() => {
+                for file in files {
+                    self.load(Path::new(file))?;
+                }
+                self.stamp();
+            }
+            ("data", files) if !files.is_empty() => {
+                self.data.extend(files.iter().map(PathBuf::from));
+                self.reload_data()?;
+                self.stamp();
+            }
+            ("reload", []) => {
+                self.reload_all()?;
+                println!("reloaded {} file(s)", self.mtimes.len());
+            }
+            ("request", [principal, action, resource]) => {
+                self.request.principal = principal.to_string();
+                self.request.action = action.to_string();
+                self.request.resource = resource.to_string();
+            }
+            ("actor", ["none"]) => self.request.actor = None,
+            ("actor", [id]) => self.request.actor = Some(id.to_string()),
+            ("context", []) => {
+                let context: serde_json::Map<_, _> = self
+                    .request
+                    .context
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect();
+                println!("{}", render(&serde_json::Value::Object(context)));
+            }
+            ("context", [key, ..]) => {
+                let raw = command
+                    .split_once(key)
+                    .map(|(_, rest)| rest.trim())
+                    .unwrap_or_default();
+                let value = serde_json::from_str(raw)
+                    .map_err(|e| anyhow::anyhow!("context value must be JSON: {e}"))?;
+                self.request.context.insert(key.to_string(), value);
+            }
+            ("input", ["none"]) => self.request.input = None,
+            ("input", [file]) => {
+                let text = std::fs::read_to_string(file)
+                    .map_err(|e| anyhow::anyhow!("failed to read {file}: {e}"))?;
+                self.request.input = Some(serde_json::from_str(&text)?);
+            }
+            ("eval", []) => self.eval()?,
+            ("use", [policy]) => {
+                if !self.policies.iter().any(|(_, p)| p.name() == *policy) {
+                    anyhow::bail!("no policy named '{policy}' is loaded");
+                }
+                self.focus = Some(policy.to_string());
+            }
+            ("entity", [id]) => {
+                let attributes = self
+                    .store
+                    .entity_attributes_json(id)
+                    .ok_or_else(|| anyhow::anyhow!("no entity '{id}'"))?;
+                println!("{}", render(&attributes));
+            }
+            ("rels", [id]) => self.relationships(id)?,
+            ("clock", []) => self.show_clock()?,
+            ("clock", ["now"]) => {
+                clock::clear_injected_now();
+                self.pinned = false;
+                self.show_clock()?;
+            }
+            ("clock", [at]) => {
+                let ns = match at.parse::<i64>() {
+                    Ok(ns) => ns,
+                    Err(_) => self
+                        .eval_snippet(&format!(
+                            "time::parse_rfc3339({})",
+                            serde_json::to_string(at)?
+                        ))?
+                        .as_i64()
+                        .ok_or_else(|| anyhow::anyhow!("not a timestamp: {at}"))?,
+                };
+                clock::set_injected_now_unix_ns(ns);
+                self.pinned = true;
+                self.show_clock()?;
+            }
+            ("vars", []) => {
+                for name in self.scope.names() {
+                    let value = self.scope.get(name).unwrap_or_default();
+                    println!("{name} = {}", render(&value));
+                }
+            }
+            ("reset", []) => self.scope.clear(),
+            _ => anyhow::bail!("unknown command ':{command}' (see :help)"),
+        }
+        Ok(true)
+    }
+
+    /// Load a policy or library, replacing an earlier load of the same file.
+    fn load(&mut self, path: &Path) -> anyhow::Result<()> {
+        let source = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("failed to read {}: {e}", path.display()))?;
+        let is_library = path.extension().is_some_and(|e| e == "reap")
+            && analysis::outline(&source).is_ok_and(|o| o.is_library);
+        self.policies.retain(|(p, _)| p != path);
+        self.libraries.retain(|(p, _, _)| p != path);
+        if is_library {
+            let (name, funcs) =
+                load_library(path).map_err(|e| anyhow::anyhow!("{}: {e}", path.display()))?;
+            println!("library {name}: {} func(s) as {name}::", funcs.len());
+            self.libraries.push((path.to_path_buf(), name, funcs));
+        } else {
+            let policy = ReaperPolicy::from_file_auto(path)
+                .map_err(|e| anyhow::anyhow!("{}: {e}", path.display()))?;
+            println!(
+                "policy {}: {} rule(s), {} func(s)",
+                policy.name(),
+                policy.ast().rules.len(),
+                policy.ast().functions.len()
+            );
+            self.policies.push((path.to_path_buf(), policy));
+        }
+        Ok(())
+    }
+
+    /// Rebuild the entity store from every data file.
+    fn reload_data(&mut self) -> anyhow::Result<()> {
+        let store = DataStore::new();
+        let loader = DataLoader::new(store.clone());
+        for path in &self.data {
+            let text = std::fs::read_to_string(path)
+                .map_err(|e| anyhow::anyhow!("failed to read {}: {e}", path.display()))?;
+            let count = loader
+                .load_json(&text)
+                .map_err(|e| anyhow::anyhow!("{}: {e}", path.display()))?;
+            println!("data {}: {count} entities", path.display());
+        }
+        self.store = Arc::new(store);
+        Ok(())
+    }
+
+    fn reload_all(&mut self) -> anyhow::Result<()> {
+        let files: Vec<PathBuf> = self
+            .policies
+            .iter()
+            .map(|(p, _)| p.clone())
+            .chain(self.libraries.iter().map(|(p, _, _)| p.clone()))
+            .collect();
+        for file in files {
+            self.load(&file)?;
+        }
+        self.reload_data()?;
+        self.stamp();
+        Ok(())
+    }
+
+    /// Reload everything when any watched file changed since the last load.
+    fn reload_changed(&mut self) -> anyhow::Result<()> {
+        let changed: Vec<&PathBuf> = self
+            .mtimes
+            .iter()
+            .filter(|(path, seen)| modified(path) != **seen)
+            .map(|(path, _)| path)
+            .collect();
+        if changed.is_empty() {
+            return Ok(());
+        }
+        let names: Vec<String> = changed.iter().map(|p| p.display().to_string()).collect();
+        println!("changed: {}", names.join(", "));
+        self.reload_all()
+    }
+
+    /// Record the mtime of every loaded file.
+    fn stamp(&mut self) {
+        self.mtimes = self
+            .policies
+            .iter()
+            .map(|(p, _)| p)
+            .chain(self.libraries.iter().map(|(p, _, _)| p))
+            .chain(&self.data)
+            .map(|p| (p.clone(), modified(p)))
+            .collect();
+    }
+
+    fn policy_request(&self) -> PolicyRequest {
+        let mut context = self.request.context.clone();
+        context.insert(
+            "principal".to_string(),
+            self.request.principal.clone().into(),
+        );
+        PolicyRequest {
+            resource: self.request.resource.clone(),
+            action: self.request.action.clone(),
+            actor: self.request.actor.clone(),
+            context,
+            ..Default::default()
+        }
+    }
+
+    /// An evaluator with no rules whose funcs are the focused policy's plus
+    /// every library's.
+    fn snippet_evaluator(&self) -> ReapAstEvaluator {
+        let focused = match &self.focus {
+            Some(name) => self.policies.iter().find(|(_, p)| p.name() == name),
+            None => self.policies.first(),
+        };
+        let mut functions: Vec<FuncDef> = focused
+            .map(|(_, p)| p.ast().functions.clone())
+            .unwrap_or_default();
+        for (_, _, funcs) in &self.libraries {
+            functions.extend(funcs.iter().cloned());
+        }
+        let policy = Policy {
+            name: "repl".to_string(),
+            metadata: HashMap::new(),
+            default_decision: Decision::Deny,
+            rules: Vec::new(),
+            functions,
+            imports: Vec::new(),
+        };
+        ReapAstEvaluator::new(self.store.clone(), policy)
+    }
+
+    /// Evaluate a snippet without touching the user's variables.
+    fn eval_snippet(&self, source: &str) -> anyhow::Result<serde_json::Value> {
+        Ok(self.snippet_evaluator().evaluate_snippet(
+            source,
+            &self.policy_request(),
+            None,
+            &mut SnippetScope::default(),
+        )?)
+    }
+
+    fn eval(&self) -> anyhow::Result<()> {
+        if self.policies.is_empty() {
+            anyhow::bail!("no policy loaded (:load FILE)");
+        }
+        let request = self.policy_request();
+        for (_, policy) in &self.policies {
+            let evaluator = ReapAstEvaluator::new(self.store.clone(), policy.ast().clone());
+            let (action, rule) =
+                evaluator.evaluate_with_input_named(&request, self.request.input.as_ref())?;
+            let decision = match action {
+                PolicyAction::Allow => "allow",
+                PolicyAction::Deny => "deny",
+                PolicyAction::Log => "log",
+            };
+            match rule {
+                Some(rule) => println!("{}: {decision} (rule {rule})", policy.name()),
+                None => println!("{}: {decision} (default)", policy.name()),
+            }
+        }
+        Ok(())
+    }
+
+    fn relationships(&self, id: &str) -> anyhow::Result<()> {
+        let interner = self.store.interner();
+        let entity = interner
+            .lookup(id)
+            .ok_or_else(|| anyhow::anyhow!("no entity '{id}'"))?;
+        let graph = self.store.relationships();
+        let name = |i| interner.resolve_str(i).unwrap_or_else(|| "?".to_string());
+        let mut lines = Vec::new();
+        for (relation, subjects) in graph.carried_edges(entity) {
+            for subject in subjects {
+                lines.push(format!("{id} #{} @{}", name(relation), name(subject)));
+            }
+        }
+        for (relation, carriers) in graph.subject_edges(entity) {
+            for carrier in carriers {
+                lines.push(format!("{} #{} @{id}", name(carrier), name(relation)));
+            }
+        }
+        if lines.is_empty() {
+            println!("(no relationships)");
+        }
+        lines.sort();
+        for line in lines {
+            println!("{line}");
+        }
+        Ok(())
+    }
+
+    fn show_clock(&self) -> anyhow::Result<()> {
+        let now = self.eval_snippet("time::format_rfc3339(time::now_ns())")?;
+        println!(
+            "{} ({})",
+            now.as_str().unwrap_or_default(),
+            if self.pinned { "pinned" } else { "wall clock" }
+        );
+        Ok(())
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// JSON on one line when short, pretty-printed otherwise.
+fn render(value: &serde_json::Value) -> String {
+    let compact = value.to_string();
+    if compact.len() <= 80 {
+        compact
+    } else {
+        serde_json::to_string_pretty(value).unwrap_or(compact)
+    }
+}
@@ -219,6 +219,143 @@ fn test_suite_with_failure_exits_nonzero_and_names_the_test() {
     );
 }
 
+#[test]
+fn test_suite_coverage_reports_unmatched_rules() {
+    let out = run(&[
+        "test-suite",
+        "--file",
+        "suite-partial.yaml",
+        "--coverage",
+        "text",
+    ]);
+    assert!(out.status.success(), "stderr: {}", stderr_of(&out));
+    let stdout = stdout_of(&out);
+    assert!(stdout.contains("rules     1/2 matched (50.0%)"), "{stdout}");
+    assert!(
+        stdout.contains("never matched: rule suspended_never, line 7"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn test_suite_min_coverage_fails_a_passing_suite() {
+    let out = run(&[
+        "test-suite",
+        "--file",
+        "suite-partial.yaml",
+        "--coverage",
+        "text",
+        "--min-coverage",
+        "100",
+    ]);
+    assert_eq!(out.status.code(), Some(1), "coverage gate must exit 1");
+    let stdout = stdout_of(&out);
+    assert!(stdout.contains("All tests passed!"));
+    assert!(stdout.contains("Coverage below 100.0%"), "{stdout}");
+
+    let full = run(&[
+        "test-suite",
+        "--file",
+        "suite-pass.yaml",
+        "--coverage",
+        "text",
+        "--min-coverage",
+        "100",
+    ]);
+    assert!(full.status.success(), "stdout: {}", stdout_of(&full));
+}
+
+#[test]
+fn test_suite_writes_lcov_and_cobertura_files() {
+    let dir = std::env::temp_dir().join(format!("reaper-cli-it-cov-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("create temp dir");
+    for (format, file, marker) in [
+        ("lcov", "lcov.info", "SF:rbac.reap"),
+        ("cobertura", "coverage.xml", r#"filename="rbac.reap""#),
+        ("json", "coverage.json", r#""rules_matched": 2"#),
+    ] {
+        let path = dir.join(file);
+        let out = run(&[
+            "test-suite",
+            "--file",
+            "suite-pass.yaml",
+            "--coverage",
+            format,
+            "--coverage-output",
+            path.to_str().expect("utf-8 temp path"),
+        ]);
+        assert!(out.status.success(), "stderr: {}", stderr_of(&out));
+        let written = std::fs::read_to_string(&path).expect("report written");
+        assert!(written.contains(marker), "{format}: {written}");
+    }
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_suite_rejects_unknown_coverage_format() {
+    let out = run(&[
+        "test-suite",
+        "--file",
+        "suite-pass.yaml",
+        "--coverage",
+        "html",
+    ]);
+    assert!(!out.status.success());
+    assert!(stderr_of(&out).contains("unknown coverage format 'html'"));
+}
+
+// ---------------------------------------------------------------------------
+// `mutate` — the full suite kills every mutant of rbac.reap; the partial one
+// (alice only) never looks at the suspended rule, so its mutants survive.
+// ---------------------------------------------------------------------------
+
+#[test]
+fn mutate_full_suite_kills_every_mutant() {
+    let out = run(&["mutate", "rbac.reap", "--suite", "suite-pass.yaml"]);
+    assert!(out.status.success(), "stdout: {}", stdout_of(&out));
+    let stdout = stdout_of(&out);
+    assert!(stdout.contains("Mutation score: 100.0%"), "{stdout}");
+    assert!(!stdout.contains("Surviving mutants"), "{stdout}");
+}
+
+#[test]
+fn mutate_reports_survivors_with_lines_and_exits_nonzero() {
+    let out = run(&["mutate", "rbac.reap", "--suite", "suite-partial.yaml"]);
+    assert_eq!(out.status.code(), Some(1), "survivors must exit 1");
+    let stdout = stdout_of(&out);
+    assert!(
+        stdout.contains("rbac.reap:8: rule suspended_never: `deny` → `allow` (flip effect)"),
+        "{stdout}"
+    );
+
+    let lenient = run(&[
+        "mutate",
+        "rbac.reap",
+        "--suite",
+        "suite-partial.yaml",
+        "--format",
+        "json",
+        "--min-score",
+        "0",
+    ]);
+    assert!(lenient.status.success(), "stderr: {}", stderr_of(&lenient));
+    let report: serde_json::Value =
+        serde_json::from_str(&stdout_of(&lenient)).expect("mutate --format json is JSON");
+    assert_eq!(report["tests"], 1);
+    assert!(report["summary"]["survived"].as_u64().expect("count") > 0);
+}
+
+#[test]
+fn mutate_refuses_a_failing_suite() {
+    let out = run(&["mutate", "rbac.reap", "--suite", "suite-fail.yaml"]);
+    assert!(!out.status.success());
+    assert!(
+        stderr_of(&out).contains("fails against the unmodified policy"),
+        "{}",
+        stderr_of(&out)
+    );
+}
+
 // ---------------------------------------------------------------------------
 // `eval` — reports the decision; exit 0 for BOTH allow and deny (a deny is a
 // successful evaluation — only `test`/`check` turn decisions into exit codes).
@@ -302,6 +439,47 @@ fn validate_bad_syntax_exits_nonzero() {
     assert!(stdout_of(&out).contains("SYNTAX ERROR"));
 }
 
+#[test]
+fn validate_with_schema_checks_policy_and_data() {
+    let out = run(&[
+        "validate",
+        "rbac.reap",
+        "--schema",
+        "schema.json",
+        "--data",
+        "entities.json",
+    ]);
+    assert!(out.status.success(), "stdout: {}", stdout_of(&out));
+    assert!(stdout_of(&out).contains("Type-checks against schema.json"));
+}
+
+#[test]
+fn validate_with_schema_rejects_undeclared_attributes() {
+    let dir = std::env::temp_dir().join(format!("reaper-cli-it-schema-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("create temp dir");
+    let policy = dir.join("typo.reap");
+    std::fs::write(
+        &policy,
+        r#"policy typo { default: deny, rule r { deny if user.stauts == "suspended" } }"#,
+    )
+    .expect("write policy");
+
+    let out = run(&[
+        "validate",
+        policy.to_str().expect("utf-8 temp path"),
+        "--schema",
+        "schema.json",
+    ]);
+    assert!(!out.status.success(), "a type error must exit non-zero");
+    let stdout = stdout_of(&out);
+    assert!(stdout.contains("TYPE ERRORS"), "stdout: {stdout}");
+    assert!(
+        stdout.contains("did you mean `status`?"),
+        "stdout: {stdout}"
+    );
+    std::fs::remove_dir_all(&dir).ok();
+}
+
 // ---------------------------------------------------------------------------
 // `compile` + `bundle info` — the .rbb round-trip, no services needed.
 // ---------------------------------------------------------------------------
@@ -365,3 +543,546 @@ fn compile_bad_syntax_exits_nonzero_and_writes_nothing() {
 
     std::fs::remove_dir_all(&out_dir).ok();
 }
+
+// ---------------------------------------------------------------------------
+// `bundle diff` — outcome changes between versions; exit 1 only with
+// `--exit-code`.
+// ---------------------------------------------------------------------------
+
+#[test]
+fn bundle_diff_reports_request_classes_that_change_outcome() {
+    let dir = std::env::temp_dir().join(format!("reaper-cli-it-diff-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("create temp dir");
+    let bundle = dir.join("old.rbb");
+    let bundle_str = bundle.to_str().expect("utf-8 temp path");
+    let compiled = run(&["compile", "rbac.reap", "--output", bundle_str]);
+    assert!(compiled.status.success(), "{}", stderr_of(&compiled));
+
+    let source = std::fs::read_to_string(fixtures_dir().join("rbac.reap")).expect("read");
+    let widened = source.trim_end().trim_end_matches('}').to_string()
+        + "\n    rule contractors {\n        allow if user.role == \"contractor\"\n    }\n}\n";
+    let new = dir.join("new.reap");
+    std::fs::write(&new, widened).expect("write policy");
+    let new_str = new.to_str().expect("utf-8 temp path");
+
+    let out = run(&["bundle", "diff", bundle_str, new_str]);
+    assert!(out.status.success(), "{}", stderr_of(&out));
+    let stdout = stdout_of(&out);
+    assert!(stdout.contains("policy cli_rbac: changed"), "{stdout}");
+    assert!(
+        stdout.contains(r#"deny → allow when !(user.status == "suspended") && !("admin" in user.roles) && user.role == "contractor""#),
+        "{stdout}"
+    );
+
+    let gated = run(&["bundle", "diff", "--exit-code", bundle_str, new_str]);
+    assert_eq!(gated.status.code(), Some(1));
+
+    let json = run(&["bundle", "diff", "--format", "json", bundle_str, new_str]);
+    let report: serde_json::Value =
+        serde_json::from_str(&stdout_of(&json)).expect("bundle diff --format json is JSON");
+    assert_eq!(report[0]["status"], "changed");
+    assert_eq!(report[0]["changes"][0]["to"], "allow");
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn bundle_diff_against_a_git_revision_of_the_same_source_is_unchanged() {
+    let out = run(&[
+        "bundle",
+        "diff",
+        "--exit-code",
+        "HEAD:./rbac.reap",
+        "rbac.reap",
+    ]);
+    assert!(out.status.success(), "{}", stderr_of(&out));
+    assert!(stdout_of(&out).contains("policy cli_rbac: unchanged"));
+}
+
+// ---------------------------------------------------------------------------
+// `fmt` — canonical formatting; `--check` is the CI gate (exit 1 = unformatted).
+// ---------------------------------------------------------------------------
+
+const UNFORMATTED: &str = r#"// kept comment
+policy  messy{ default:deny,
+  rule a { allow if user.role=="admin"&&context.action=="read" }  // why
+  version : "1",
+}
+"#;
+
+/// A scratch directory holding `nested/messy.reap` (unformatted) and a copy
+/// of the formatted `rbac.reap` fixture.
+fn fmt_workspace(tag: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("reaper-cli-it-fmt-{tag}-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("nested")).expect("create temp dir");
+    std::fs::write(dir.join("nested/messy.reap"), UNFORMATTED).expect("write policy");
+    std::fs::copy(fixtures_dir().join("rbac.reap"), dir.join("rbac.reap")).expect("copy fixture");
+    dir
+}
+
+#[test]
+fn fmt_check_on_formatted_files_exits_zero() {
+    let out = run(&["fmt", "--check", "rbac.reap"]);
+    assert!(
+        out.status.success(),
+        "the rbac fixture is canonical; stdout: {} stderr: {}",
+        stdout_of(&out),
+        stderr_of(&out)
+    );
+}
+
+#[test]
+fn fmt_check_lists_unformatted_files_and_exits_nonzero() {
+    let dir = fmt_workspace("check");
+    let out = run(&["fmt", "--check", dir.to_str().expect("utf-8 temp path")]);
+    assert_eq!(
+        out.status.code(),
+        Some(1),
+        "unformatted file must fail --check"
+    );
+    let stdout = stdout_of(&out);
+    assert!(stdout.contains("would reformat") && stdout.contains("messy.reap"));
+    assert!(
+        !stdout.contains("rbac.reap"),
+        "formatted file must not be listed"
+    );
+    assert_eq!(
+        std::fs::read_to_string(dir.join("nested/messy.reap")).expect("read back"),
+        UNFORMATTED,
+        "--check must not write"
+    );
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn fmt_rewrites_directories_in_place() {
+    let dir = fmt_workspace("write");
+    let dir_arg = dir.to_str().expect("utf-8 temp path");
+    let out = run(&["fmt", dir_arg]);
+    assert!(out.status.success(), "stderr: {}", stderr_of(&out));
+
+    let formatted = std::fs::read_to_string(dir.join("nested/messy.reap")).expect("read back");
+    assert_eq!(
+        formatted,
+        r#"// kept comment
+policy messy {
+    version: "1",
+    default: deny,
+
+    rule a {
+        allow if {
+            user.role == "admin" &&
+            context.action == "read"
+        }
+    }  // why
+}
+"#
+    );
+    let again = run(&["fmt", "--check", dir_arg]);
+    assert!(again.status.success(), "formatted output must pass --check");
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn fmt_bad_syntax_exits_nonzero_and_leaves_file_alone() {
+    let before = std::fs::read_to_string(fixtures_dir().join("bad-syntax.reap")).expect("read");
+    let out = run(&["fmt", "bad-syntax.reap"]);
+    assert!(
+        !out.status.success(),
+        "a file that does not parse cannot be formatted"
+    );
+    assert!(stderr_of(&out).contains("bad-syntax.reap"));
+    let after = std::fs::read_to_string(fixtures_dir().join("bad-syntax.reap")).expect("read");
+    assert_eq!(before, after);
+}
+
+// ---------------------------------------------------------------------------
+// `lint` — static analysis; exit 1 = findings.
+// ---------------------------------------------------------------------------
+
+const LINTED: &str = r#"policy linted {
+    default: deny,
+
+    rule frozen {
+        deny if user.status == "frozen"
+    }
+
+    rule frozen_reads {
+        allow if user.status == "frozen" && resource.public == true
+    }
+
+    rule impossible {
+        allow if user.level > 5 && user.level < 2
+    }
+}
+"#;
+
+#[test]
+fn lint_clean_policy_exits_zero() {
+    let out = run(&["lint", "rbac.reap"]);
+    assert!(out.status.success(), "stdout: {}", stdout_of(&out));
+    assert!(stderr_of(&out).contains("0 finding(s) in 1 policy file(s)"));
+}
+
+#[test]
+fn lint_reports_findings_with_lines_and_exits_nonzero() {
+    let dir = std::env::temp_dir().join(format!("reaper-cli-it-lint-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("create temp dir");
+    std::fs::write(dir.join("linted.reap"), LINTED).expect("write policy");
+    // Same package as rbac.reap ("default"): its admin rule duplicated.
+    std::fs::write(
+        dir.join("copy.reap"),
+        "policy copy {\n    default: deny,\n\n    rule admins {\n        allow if \"admin\" in user.roles\n    }\n}\n",
+    )
+    .expect("write policy");
+    let dir_arg = dir.to_str().expect("utf-8 temp path");
+    let rbac = fixtures_dir().join("rbac.reap");
+
+    let out = run(&["lint", rbac.to_str().expect("utf-8 path"), dir_arg]);
+    assert_eq!(out.status.code(), Some(1), "findings must exit 1");
+    let stdout = stdout_of(&out);
+    assert!(
+        stdout.contains("linted.reap:8: rule frozen_reads: never decides: rule frozen denies every request it matches [shadowed]"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("linted.reap:12: rule impossible: condition can never hold"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("copy.reap:4: rule admins: same effect and condition as rule admins_allowed in policy cli_rbac [duplicate]"),
+        "{stdout}"
+    );
+
+    let json = run(&["lint", "--format", "json", dir_arg]);
+    let report: serde_json::Value =
+        serde_json::from_str(&stdout_of(&json)).expect("lint --format json is JSON");
+    assert_eq!(report.as_array().expect("array").len(), 2);
+    assert_eq!(report[0]["kind"], "shadowed");
+    assert!(report[0]["file"]
+        .as_str()
+        .expect("file")
+        .ends_with("linted.reap"));
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+// ---------------------------------------------------------------------------
+// `import-rego` — OPA migration; exit 1 = something was not carried over.
+// ---------------------------------------------------------------------------
+
+/// A file under the repository's `test-fixtures/rego-import`.
+fn rego_fixture(name: &str) -> String {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../test-fixtures/rego-import")
+        .join(name)
+        .to_str()
+        .expect("utf-8 fixture path")
+        .to_string()
+}
+
+#[test]
+fn import_rego_replays_the_corpus_and_exits_zero() {
+    let out = run(&[
+        "import-rego",
+        &rego_fixture("policy.rego"),
+        "--data",
+        &rego_fixture("data.json"),
+        "--corpus",
+        &rego_fixture("corpus.jsonl"),
+    ]);
+    assert!(out.status.success(), "stderr: {}", stderr_of(&out));
+    assert!(stdout_of(&out).contains("policy authz {"));
+    assert!(stderr_of(&out).contains("corpus: 12 of 12 decision(s) match"));
+}
+
+#[test]
+fn import_rego_writes_the_policy_and_entity_document() {
+    let dir = std::env::temp_dir().join(format!("reaper-cli-it-rego-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("create temp dir");
+    let policy = dir.join("authz.reap");
+    let entities = dir.join("entities.json");
+    let policy_arg = policy.to_str().expect("utf-8 temp path");
+    let entities_arg = entities.to_str().expect("utf-8 temp path");
+
+    let out = run(&[
+        "import-rego",
+        &rego_fixture("policy.rego"),
+        "-o",
+        policy_arg,
+        "--data",
+        &rego_fixture("data.json"),
+        "--data-out",
+        entities_arg,
+    ]);
+    assert!(out.status.success(), "stderr: {}", stderr_of(&out));
+    assert!(stdout_of(&out).is_empty(), "-o must not print the policy");
+
+    let source = std::fs::read_to_string(&policy).expect("read policy");
+    assert!(source.starts_with("// Imported from Rego package docs.authz"));
+    let converted: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&entities).expect("read entities"))
+            .expect("entity document is JSON");
+    assert_eq!(converted["entities"].as_array().map(Vec::len), Some(6));
+    let validated = run(&["validate", policy_arg]);
+    assert!(
+        validated.status.success(),
+        "stdout: {}",
+        stdout_of(&validated)
+    );
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn import_rego_warns_about_untranslated_rules_and_exits_nonzero() {
+    let dir = std::env::temp_dir().join(format!("reaper-cli-it-rego-warn-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("create temp dir");
+    let rego = dir.join("partial.rego");
+    std::fs::write(
+        &rego,
+        "package partial\n\nimport rego.v1\n\nallow if input.action == \"read\"\n\nallow if {\n    input.action == \"x\" with input.action as \"x\"\n}\n",
+    )
+    .expect("write rego");
+
+    let out = run(&["import-rego", rego.to_str().expect("utf-8 temp path")]);
+    assert_eq!(out.status.code(), Some(1));
+    assert!(stdout_of(&out).contains("policy partial {"));
+    let stderr = stderr_of(&out);
+    assert!(
+        stderr.contains("warning:") && stderr.contains("line 8"),
+        "{stderr}"
+    );
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn import_rego_data_out_requires_data() {
+    let out = run(&[
+        "import-rego",
+        &rego_fixture("policy.rego"),
+        "--data-out",
+        "unused.json",
+    ]);
+    assert!(!out.status.success());
+    assert!(stderr_of(&out).contains("--data-out needs --data"));
+    assert!(!fixtures_dir().join("unused.json").exists());
+}
+
+// ---------------------------------------------------------------------------
+// `repl` — lines piped on stdin, one result per line on stdout.
+// ---------------------------------------------------------------------------
+
+/// Run `reaper-cli repl args` with `session` as stdin, cwd = fixtures/.
+fn run_repl(args: &[&str], session: &str) -> Output {
+    use std::io::Write;
+    use std::process::Stdio;
+    let mut child = Command::new(env!("CARGO_BIN_EXE_reaper-cli"))
+        .arg("repl")
+        .args(args)
+        .current_dir(fixtures_dir())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("spawn reaper-cli binary");
+    child
+        .stdin
+        .take()
+        .expect("stdin")
+        .write_all(session.as_bytes())
+        .expect("write session");
+    child.wait_with_output().expect("wait for reaper-cli")
+}
+
+#[test]
+fn repl_evaluates_snippets_and_requests_against_loaded_data() {
+    let out = run_repl(
+        &["rbac.reap", "-d", "entities.json"],
+        ":request alice read doc-1\n\
+         user.roles\n\
+         n := user.roles.count() + 1\n\
+         n\n\
+         \"admin\" in user.roles\n\
+         :eval\n\
+         :request mallory read doc-1\n\
+         :eval\n\
+         :clock 2024-01-01T00:00:00Z\n\
+         time::now_ns()\n\
+         nope((\n",
+    );
+    assert!(out.status.success(), "stderr: {}", stderr_of(&out));
+    let stdout = stdout_of(&out);
+    for expected in [
+        "[\"admin\"]\n2\n2\ntrue\n",
+        "cli_rbac: allow (rule admins_allowed)",
+        "cli_rbac: deny (rule suspended_never)",
+        "2024-01-01T00:00:00+00:00 (pinned)\n1704067200000000000\n",
+    ] {
+        assert!(
+            stdout.contains(expected),
+            "missing {expected:?} in: {stdout}"
+        );
+    }
+    assert!(stderr_of(&out).contains("not an expression or condition"));
+}
+
+#[test]
+fn repl_reloads_a_policy_that_changes_between_lines() {
+    let dir = std::env::temp_dir().join(format!("reaper-cli-it-repl-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("create temp dir");
+    let policy = dir.join("p.reap");
+    let source = "policy p {\n    default: deny,\n    func ok(x) := x > 1,\n}\n";
+    std::fs::write(&policy, source).expect("write policy");
+
+    use std::io::{BufRead, BufReader, Write};
+    use std::process::Stdio;
+    let mut child = Command::new(env!("CARGO_BIN_EXE_reaper-cli"))
+        .args(["repl", policy.to_str().expect("utf-8 temp path")])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("spawn reaper-cli binary");
+    let mut stdin = child.stdin.take().expect("stdin");
+    let mut stdout = BufReader::new(child.stdout.take().expect("stdout"));
+    let mut line = String::new();
+    stdout.read_line(&mut line).expect("read banner");
+    assert!(line.starts_with("policy p:"), "{line}");
+
+    writeln!(stdin, "ok(2)").expect("write");
+    line.clear();
+    stdout.read_line(&mut line).expect("read result");
+    assert_eq!(line, "true\n");
+
+    // Coarse filesystem timestamps: make sure the mtime moves.
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+    std::fs::write(&policy, source.replace("x > 1", "x > 5")).expect("rewrite policy");
+    writeln!(stdin, "ok(2)").expect("write");
+    drop(stdin);
+    let rest: Vec<String> = stdout.lines().map(|l| l.expect("line")).collect();
+    assert_eq!(rest.last().map(String::as_str), Some("false"), "{rest:?}");
+    assert!(rest.iter().any(|l| l.starts_with("changed:")), "{rest:?}");
+    child.wait().expect("wait for reaper-cli");
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+// ---------------------------------------------------------------------------
+// `management import-model` — posts the files to the import endpoint; any
+// reported construct is a non-zero exit so a pipeline notices lost grants.
+// ---------------------------------------------------------------------------
+
+/// Serve ONE canned JSON response on a loopback port; the join handle
+/// yields the raw request (head + body) the CLI sent.
+fn serve_once(body: &'static str) -> (String, std::thread::JoinHandle<String>) {
+    use std::io::{Read, Write};
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind loopback");
+    let url = format!("http://{}", listener.local_addr().expect("local addr"));
+    let handle = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().expect("accept");
+        let mut request = Vec::new();
+        let mut chunk = [0u8; 4096];
+        loop {
+            let n = stream.read(&mut chunk).expect("read request");
+            request.extend_from_slice(&chunk[..n]);
+            let text = String::from_utf8_lossy(&request);
+            if let Some(end) = text.find("\r\n\r\n") {
+                let length = text[..end]
+                    .lines()
+                    .find_map(|l| {
+                        let (name, value) = l.split_once(':')?;
+                        name.eq_ignore_ascii_case("content-length")
+                            .then(|| value.trim().parse::<usize>().ok())?
+                    })
+                    .unwrap_or(0);
+                if request.len() >= end + 4 + length || n == 0 {
+                    break;
+                }
+            }
+        }
+        write!(
+            stream,
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\
+             connection: close\r\n\r\n{body}",
+            body.len()
+        )
+        .expect("write response");
+        String::from_utf8_lossy(&request).into_owned()
+    });
+    (url, handle)
+}
+
+fn import_model(url: &str) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_reaper-cli"))
+        .args([
+            "management",
+            "--management-url",
+            url,
+            "--api-key",
+            "test-key",
+            "import-model",
+            "--org",
+            "acme",
+            "--namespace",
+            "prod",
+            "--format",
+            "openfga",
+            "--schema",
+            "openfga-model.fga",
+            "--tuples",
+            "openfga-tuples.json",
+        ])
+        .env("NO_PROXY", "127.0.0.1")
+        .current_dir(fixtures_dir())
+        .output()
+        .expect("spawn reaper-cli binary")
+}
+
+#[test]
+fn import_model_posts_schema_and_tuples() {
+    let (url, server) = serve_once(
+        r#"{"model":{},"counts":{"entities":2,"tuples":1},"issues":[],"applied":true,"revision":3}"#,
+    );
+    let out = import_model(&url);
+    let request = server.join().expect("server thread");
+    assert!(out.status.success(), "stderr: {}", stderr_of(&out));
+    assert!(
+        stdout_of(&out).contains("Imported 2 entities, 1 tuples"),
+        "{}",
+        stdout_of(&out)
+    );
+
+    assert!(
+        request.starts_with("POST /api/v1/orgs/acme/namespaces/prod/datastore/import "),
+        "{request}"
+    );
+    assert!(
+        request.to_ascii_lowercase().contains("x-api-key: test-key"),
+        "{request}"
+    );
+    let body: serde_json::Value =
+        serde_json::from_str(&request[request.find("\r\n\r\n").unwrap() + 4..]).expect("json");
+    assert_eq!(body["format"], "openfga");
+    assert_eq!(body["dry_run"], false);
+    assert!(body["schema"]
+        .as_str()
+        .unwrap()
+        .contains("define reader: [user]"));
+    assert!(body["tuples"].as_str().unwrap().contains("user:anne"));
+}
+
+#[test]
+fn import_model_with_reported_constructs_exits_nonzero() {
+    let (url, server) = serve_once(
+        r#"{"model":{},"counts":{"entities":2,"tuples":1},"applied":true,"revision":3,
+            "issues":[{"location":"tuple 2","construct":"userset",
+                       "reason":"`doc#reader` does not allow `team#admin`","occurrences":2}]}"#,
+    );
+    let out = import_model(&url);
+    server.join().expect("server thread");
+    assert!(!out.status.success(), "a reported construct must fail CI");
+    let stderr = stderr_of(&out);
+    assert!(
+        stderr.contains(
+            "warning: tuple 2: userset: `doc#reader` does not allow `team#admin` (2 tuples)"
+        ),
+        "{stderr}"
+    );
+    assert!(stderr.contains("1 construct(s) not imported"), "{stderr}");
+}
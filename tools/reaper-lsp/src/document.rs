@@ -0,0 +1,99 @@
+//! Positions and URIs: LSP speaks UTF-16 line/character pairs and `file://`
+//! URIs; the analysis speaks byte offsets and paths.
+
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+use policy_engine::reap::analysis::SourceSpan;
+
+/// Byte offset <-> LSP position conversion for one text.
+pub struct LineIndex<'a> {
+    text: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(text: &'a str) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(text.match_indices('\n').map(|(i, _)| i + 1));
+        Self { text, line_starts }
+    }
+
+    /// LSP `{line, character}` for a byte offset (clamped to the text).
+    pub fn position(&self, offset: usize) -> Value {
+        let offset = offset.min(self.text.len());
+        let line = self.line_starts.partition_point(|&s| s <= offset) - 1;
+        let start = self.line_starts[line];
+        let character: usize = self
+            .text
+            .get(start..offset)
+            .unwrap_or_default()
+            .chars()
+            .map(char::len_utf16)
+            .sum();
+        json!({ "line": line, "character": character })
+    }
+
+    pub fn range(&self, span: SourceSpan) -> Value {
+        json!({ "start": self.position(span.start), "end": self.position(span.end) })
+    }
+
+    /// Byte offset of an LSP position; positions past a line's end clamp to
+    /// it, positions past the last line clamp to the end of the text.
+    pub fn offset(&self, position: &Value) -> usize {
+        let line = position.get("line").and_then(Value::as_u64).unwrap_or(0) as usize;
+        let character = position
+            .get("character")
+            .and_then(Value::as_u64)
+            .unwrap_or(0) as usize;
+        let Some(&start) = self.line_starts.get(line) else {
+            return self.text.len();
+        };
+        let line_text = self.text[start..].split('\n').next().unwrap_or_default();
+        let mut units = 0;
+        for (i, c) in line_text.char_indices() {
+            if units >= character {
+                return start + i;
+            }
+            units += c.len_utf16();
+        }
+        start + line_text.len()
+    }
+}
+
+/// Filesystem path of a `file://` URI (percent-decoded); `None` for other
+/// schemes (unsaved buffers).
+pub fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let rest = uri.strip_prefix("file://")?;
+    // `file://host/path` — only the local (empty or localhost) host is a path.
+    let rest = rest.strip_prefix("localhost").unwrap_or(rest);
+    let bytes = rest.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            if let Some(Ok(byte)) = rest.get(i + 1..i + 3).map(|h| u8::from_str_radix(h, 16)) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    Some(PathBuf::from(String::from_utf8(decoded).ok()?))
+}
+
+/// `file://` URI for a path, percent-encoding everything outside the
+/// unreserved set and `/`.
+pub fn path_to_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for byte in path.to_string_lossy().bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    uri
+}
@@ -0,0 +1,22 @@
+//! Reaper LSP — a stdio language server for `.reap` policies and libraries.
+//!
+//! Everything it reports comes from the engine itself rather than a second
+//! grammar: `policy_engine::reap::analysis` re-walks the pest parse for
+//! spans, runs the same `func`/import validation a file load runs, and asks
+//! the compiler which rules it rejects. The server offers:
+//!
+//! - diagnostics with spans on open/change/save;
+//! - hover docs for builtin functions (`time::`, `math::`, `regex::`,
+//!   `rebac::`, ...), methods, and user `func`s;
+//! - go-to-definition for `func` calls, across `import "..." as ns`
+//!   libraries, and on the import path itself;
+//! - completion of `ns::` functions, methods, and entity attributes (seen in
+//!   open documents or read from an entity data file);
+//! - an inlay hint on every rule that will fall back from compiled to AST
+//!   evaluation, with the compiler's reason.
+
+pub mod document;
+pub mod protocol;
+pub mod server;
+
+pub use server::{EntityAttributes, LspServer};
@@ -0,0 +1,42 @@
+//! reaper-lsp — language server for `.reap` policies over stdio.
+//!
+//! stdout carries the protocol; all diagnostics go to stderr.
+
+use std::io::{self, BufReader};
+use std::path::Path;
+
+use anyhow::Context;
+
+use reaper_lsp::protocol::{read_message, write_message};
+use reaper_lsp::LspServer;
+
+fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
+        )
+        .with_writer(io::stderr)
+        .init();
+
+    let mut server = LspServer::new();
+    if let Ok(path) = std::env::var("REAPER_LSP_DATA") {
+        server
+            .load_data_file(Path::new(&path))
+            .with_context(|| format!("loading REAPER_LSP_DATA {path}"))?;
+    }
+    tracing::info!("reaper-lsp starting");
+
+    let mut stdin = BufReader::new(io::stdin().lock());
+    let mut stdout = io::stdout().lock();
+    while let Some(message) = read_message(&mut stdin).context("reading stdin")? {
+        if message.get("method").and_then(|m| m.as_str()) == Some("exit") {
+            break;
+        }
+        for reply in server.handle_message(&message) {
+            write_message(&mut stdout, &reply).context("writing stdout")?;
+        }
+    }
+    tracing::info!("reaper-lsp shutting down");
+    Ok(())
+}
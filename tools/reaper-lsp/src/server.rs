@@ -0,0 +1,636 @@
+//! Request handling: one [`LspServer`] holds the open documents and answers
+//! each JSON-RPC message with the messages to send back.
+//!
+//! Analysis is recomputed from the document text on every request — a
+//! `.reap` file parses in microseconds, so there is no cache to invalidate.
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+use tracing::{debug, warn};
+
+use policy_engine::reap::analysis::{
+    self, Declaration, DiagnosticSeverity, SourceOutline, SourceSpan,
+};
+
+use crate::document::{path_to_uri, uri_to_path, LineIndex};
+use crate::protocol::{error_response, notification, result_response};
+
+const KEYWORDS: &[&str] = &[
+    "policy",
+    "library",
+    "import",
+    "as",
+    "rule",
+    "func",
+    "default",
+    "allow",
+    "deny",
+    "if",
+    "with",
+    "message",
+    "obligations",
+    "advice",
+    "in",
+    "true",
+    "false",
+    "null",
+    "user",
+    "actor",
+    "resource",
+    "context",
+    "input",
+];
+
+// LSP enum values used below.
+const SEVERITY_ERROR: u8 = 1;
+const SEVERITY_WARNING: u8 = 2;
+const KIND_METHOD: u8 = 2;
+const KIND_FUNCTION: u8 = 3;
+const KIND_FIELD: u8 = 5;
+const KIND_MODULE: u8 = 9;
+const KIND_KEYWORD: u8 = 14;
+const TEXT_DOCUMENT_SYNC_FULL: u8 = 1;
+
+/// Known attribute paths per entity, for completion. `actor` shares the
+/// `user` entry — both name the principal.
+#[derive(Debug, Default, Clone)]
+pub struct EntityAttributes {
+    paths: BTreeSet<(String, Vec<String>)>,
+}
+
+impl EntityAttributes {
+    /// Attributes from an entity data file in the loader's
+    /// `{"entities": [{"id", "type", "attributes"}]}` shape. Entities of type
+    /// `user` (any case) describe the principal; every other type describes
+    /// `resource`. Nested objects contribute their dotted paths.
+    pub fn from_data_json(json: &str) -> anyhow::Result<Self> {
+        let document: Value = serde_json::from_str(json)?;
+        let entities = document
+            .get("entities")
+            .and_then(Value::as_array)
+            .ok_or_else(|| anyhow::anyhow!("data file has no \"entities\" array"))?;
+        let mut attributes = Self::default();
+        for entity in entities {
+            let is_user = entity
+                .get("type")
+                .and_then(Value::as_str)
+                .is_some_and(|t| t.eq_ignore_ascii_case("user"));
+            let target = if is_user { "user" } else { "resource" };
+            if let Some(attrs) = entity.get("attributes") {
+                attributes.add_object(target, &mut Vec::new(), attrs);
+            }
+        }
+        Ok(attributes)
+    }
+
+    fn add_object(&mut self, entity: &str, prefix: &mut Vec<String>, value: &Value) {
+        // Deep enough for real attribute trees; bounded against odd data.
+        const MAX_DEPTH: usize = 4;
+        let Some(object) = value.as_object() else {
+            return;
+        };
+        if prefix.len() >= MAX_DEPTH {
+            return;
+        }
+        for (key, child) in object {
+            prefix.push(key.clone());
+            self.insert(entity, prefix);
+            self.add_object(entity, prefix, child);
+            prefix.pop();
+        }
+    }
+
+    fn insert(&mut self, entity: &str, path: &[String]) {
+        let entity = canonical_entity(entity);
+        for len in 1..=path.len() {
+            self.paths
+                .insert((entity.to_string(), path[..len].to_vec()));
+        }
+    }
+
+    /// Attribute names directly under `entity.prefix...`.
+    pub fn children(&self, entity: &str, prefix: &[String]) -> Vec<&str> {
+        let entity = canonical_entity(entity);
+        self.paths
+            .iter()
+            .filter(|(e, path)| {
+                e == entity && path.len() == prefix.len() + 1 && path.starts_with(prefix)
+            })
+            .filter_map(|(_, path)| path.last().map(String::as_str))
+            .collect()
+    }
+}
+
+fn canonical_entity(entity: &str) -> &str {
+    if entity == "actor" {
+        "user"
+    } else {
+        entity
+    }
+}
+
+/// The language server state: open documents plus attributes loaded from an
+/// entity data file.
+#[derive(Default)]
+pub struct LspServer {
+    documents: HashMap<String, String>,
+    /// The latest version of each open document that parsed, with its
+    /// outline. Right after `user.` or `ns::` is typed the text rarely
+    /// parses, but the imports, functions and attribute paths it had a
+    /// keystroke earlier are what completion needs.
+    parsed: HashMap<String, (String, SourceOutline)>,
+    data_attributes: EntityAttributes,
+}
+
+impl LspServer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load completion attributes from an entity data file, replacing any
+    /// loaded earlier.
+    pub fn load_data_file(&mut self, path: &Path) -> anyhow::Result<()> {
+        let json = std::fs::read_to_string(path)?;
+        self.data_attributes = EntityAttributes::from_data_json(&json)?;
+        Ok(())
+    }
+
+    /// Handle one JSON-RPC message. Returns every message to send in reply:
+    /// the response for a request, `publishDiagnostics` for document
+    /// changes, nothing for other notifications. `exit` is left to the
+    /// caller, which owns the stream.
+    pub fn handle_message(&mut self, message: &Value) -> Vec<Value> {
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            // A response to a server request we never send — ignore.
+            return Vec::new();
+        };
+        let id = message.get("id").cloned();
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        debug!(method, "handling message");
+
+        match (method, id) {
+            ("initialize", Some(id)) => vec![result_response(id, self.initialize(&params))],
+            ("shutdown", Some(id)) => vec![result_response(id, Value::Null)],
+            ("textDocument/hover", Some(id)) => {
+                vec![result_response(
+                    id,
+                    self.hover(&params).unwrap_or(Value::Null),
+                )]
+            }
+            ("textDocument/definition", Some(id)) => vec![result_response(
+                id,
+                self.definition(&params).unwrap_or(Value::Null),
+            )],
+            ("textDocument/completion", Some(id)) => {
+                vec![result_response(id, self.completion(&params))]
+            }
+            ("textDocument/inlayHint", Some(id)) => {
+                vec![result_response(id, self.inlay_hints(&params))]
+            }
+            ("textDocument/didOpen", None) => {
+                let uri = str_at(&params, &["textDocument", "uri"]);
+                let text = str_at(&params, &["textDocument", "text"]);
+                match (uri, text) {
+                    (Some(uri), Some(text)) => {
+                        self.update(uri, text);
+                        vec![self.publish_diagnostics(uri)]
+                    }
+                    _ => Vec::new(),
+                }
+            }
+            ("textDocument/didChange", None) => {
+                // Full sync: the last change carries the whole text.
+                let uri = str_at(&params, &["textDocument", "uri"]);
+                let text = params
+                    .get("contentChanges")
+                    .and_then(Value::as_array)
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change.get("text"))
+                    .and_then(Value::as_str);
+                match (uri, text) {
+                    (Some(uri), Some(text)) => {
+                        self.update(uri, text);
+                        vec![self.publish_diagnostics(uri)]
+                    }
+                    _ => Vec::new(),
+                }
+            }
+            ("textDocument/didSave", None) => {
+                // A save can fix an import this document resolves against.
+                match str_at(&params, &["textDocument", "uri"]) {
+                    Some(uri) if self.documents.contains_key(uri) => {
+                        let uris: Vec<String> = self.documents.keys().cloned().collect();
+                        uris.iter().map(|u| self.publish_diagnostics(u)).collect()
+                    }
+                    _ => Vec::new(),
+                }
+            }
+            ("textDocument/didClose", None) => match str_at(&params, &["textDocument", "uri"]) {
+                Some(uri) => {
+                    self.documents.remove(uri);
+                    self.parsed.remove(uri);
+                    vec![notification(
+                        "textDocument/publishDiagnostics",
+                        json!({ "uri": uri, "diagnostics": [] }),
+                    )]
+                }
+                None => Vec::new(),
+            },
+            (_, None) => Vec::new(),
+            (_, Some(id)) => vec![error_response(id, -32601, "Method not found")],
+        }
+    }
+
+    fn update(&mut self, uri: &str, text: &str) {
+        self.documents.insert(uri.to_string(), text.to_string());
+        if let Ok(outline) = analysis::outline(text) {
+            self.parsed
+                .insert(uri.to_string(), (text.to_string(), outline));
+        }
+    }
+
+    fn initialize(&mut self, params: &Value) -> Value {
+        if let Some(path) = str_at(params, &["initializationOptions", "dataFile"]) {
+            if let Err(e) = self.load_data_file(Path::new(path)) {
+                warn!("could not load data file {path}: {e}");
+            }
+        }
+        json!({
+            "capabilities": {
+                "textDocumentSync": {
+                    "openClose": true,
+                    "change": TEXT_DOCUMENT_SYNC_FULL,
+                    "save": true,
+                },
+                "hoverProvider": true,
+                "definitionProvider": true,
+                "completionProvider": { "triggerCharacters": [".", ":"] },
+                "inlayHintProvider": true,
+            },
+            "serverInfo": {
+                "name": env!("CARGO_PKG_NAME"),
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+        })
+    }
+
+    fn publish_diagnostics(&self, uri: &str) -> Value {
+        let text = self
+            .documents
+            .get(uri)
+            .map(String::as_str)
+            .unwrap_or_default();
+        let base_dir = document_dir(uri);
+        let index = LineIndex::new(text);
+        let diagnostics: Vec<Value> = analysis::check_source(text, base_dir.as_deref())
+            .into_iter()
+            .map(|d| {
+                let severity = match d.severity {
+                    DiagnosticSeverity::Error => SEVERITY_ERROR,
+                    DiagnosticSeverity::Warning => SEVERITY_WARNING,
+                };
+                json!({
+                    "range": index.range(d.span),
+                    "severity": severity,
+                    "source": "reaper",
+                    "message": d.message,
+                })
+            })
+            .collect();
+        notification(
+            "textDocument/publishDiagnostics",
+            json!({ "uri": uri, "diagnostics": diagnostics }),
+        )
+    }
+
+    /// The open document a position request refers to, with the cursor as a
+    /// byte offset.
+    fn document_at<'a>(&'a self, params: &Value) -> Option<(&'a str, &'a str, usize)> {
+        let uri = str_at(params, &["textDocument", "uri"])?;
+        let (uri, text) = self.documents.get_key_value(uri)?;
+        let offset = LineIndex::new(text).offset(params.get("position")?);
+        Some((uri.as_str(), text.as_str(), offset))
+    }
+
+    fn hover(&self, params: &Value) -> Option<Value> {
+        let (uri, text, offset) = self.document_at(params)?;
+        let outline = analysis::outline(text).ok()?;
+        let index = LineIndex::new(text);
+
+        let (span, markdown) = if let Some(call) = outline.calls.iter().find(|c| {
+            c.function_span.contains(offset) || c.namespace_span.is_some_and(|s| s.contains(offset))
+        }) {
+            let span = SourceSpan {
+                start: call.namespace_span.unwrap_or(call.function_span).start,
+                end: call.function_span.end,
+            };
+            let markdown = match call.namespace.as_deref() {
+                Some(ns) if outline.imports.iter().any(|i| i.alias == ns) => {
+                    let (_, lib_text, lib_outline) = self.import_outline(uri, &outline, ns)?;
+                    let decl = lib_outline
+                        .functions
+                        .iter()
+                        .find(|f| f.name == call.function)?;
+                    code_block(declaration_header(&lib_text, decl))
+                }
+                Some(ns) => {
+                    let builtin = analysis::builtin_function(Some(ns), &call.function)?;
+                    format!("{}\n\n{}", code_block(builtin.signature), builtin.doc)
+                }
+                None => match analysis::builtin_function(None, &call.function) {
+                    Some(builtin) => {
+                        format!("{}\n\n{}", code_block(builtin.signature), builtin.doc)
+                    }
+                    None => {
+                        let decl = outline.functions.iter().find(|f| f.name == call.function)?;
+                        code_block(declaration_header(text, decl))
+                    }
+                },
+            };
+            (span, markdown)
+        } else if let Some(method) = outline.methods.iter().find(|m| m.span.contains(offset)) {
+            let builtin = analysis::builtin_method(&method.name)?;
+            (
+                method.span,
+                format!("{}\n\n{}", code_block(builtin.signature), builtin.doc),
+            )
+        } else {
+            return None;
+        };
+
+        Some(json!({
+            "contents": { "kind": "markdown", "value": markdown },
+            "range": index.range(span),
+        }))
+    }
+
+    fn definition(&self, params: &Value) -> Option<Value> {
+        let (uri, text, offset) = self.document_at(params)?;
+        let outline = analysis::outline(text).ok()?;
+
+        if let Some(import) = outline
+            .imports
+            .iter()
+            .find(|i| i.path_span.contains(offset))
+        {
+            let path = document_dir(uri)?.join(&import.path);
+            return Some(location(
+                &path_to_uri(&path),
+                json!({
+                    "start": { "line": 0, "character": 0 },
+                    "end": { "line": 0, "character": 0 },
+                }),
+            ));
+        }
+
+        let call = outline.calls.iter().find(|c| {
+            c.function_span.contains(offset) || c.namespace_span.is_some_and(|s| s.contains(offset))
+        })?;
+        match call.namespace.as_deref() {
+            Some(ns) => {
+                let (lib_uri, lib_text, lib_outline) = self.import_outline(uri, &outline, ns)?;
+                let decl = lib_outline
+                    .functions
+                    .iter()
+                    .find(|f| f.name == call.function)?;
+                Some(location(
+                    &lib_uri,
+                    LineIndex::new(&lib_text).range(decl.name_span),
+                ))
+            }
+            None => {
+                let decl = outline.functions.iter().find(|f| f.name == call.function)?;
+                Some(location(uri, LineIndex::new(text).range(decl.name_span)))
+            }
+        }
+    }
+
+    /// The library imported as `alias`: its URI, text (the open buffer if the
+    /// editor has it, else the file on disk) and outline.
+    fn import_outline(
+        &self,
+        uri: &str,
+        outline: &SourceOutline,
+        alias: &str,
+    ) -> Option<(String, String, SourceOutline)> {
+        let import = outline.imports.iter().find(|i| i.alias == alias)?;
+        let path: PathBuf = document_dir(uri)?.join(&import.path);
+        let lib_uri = path_to_uri(&path);
+        let lib_text = match self.documents.get(&lib_uri) {
+            Some(text) => text.clone(),
+            None => std::fs::read_to_string(&path).ok()?,
+        };
+        let lib_outline = analysis::outline(&lib_text).ok()?;
+        Some((lib_uri, lib_text, lib_outline))
+    }
+
+    fn completion(&self, params: &Value) -> Value {
+        let Some((uri, text, offset)) = self.document_at(params) else {
+            return json!([]);
+        };
+        // Only the text before the cursor matters: the current line, minus
+        // the identifier being typed.
+        let before = &text[..offset];
+        let line = before.rsplit('\n').next().unwrap_or_default();
+        let typed = line.trim_end_matches(|c: char| c.is_ascii_alphanumeric() || c == '_');
+        let parsed = self.parsed.get(uri);
+        let outline = parsed.map(|(_, outline)| outline);
+
+        let items: Vec<Value> =
+            if let Some(ns) = typed
+                .strip_suffix("::")
+                .map(trailing_ident)
+                .filter(|ns| !ns.is_empty())
+            {
+                self.namespace_items(uri, outline, ns)
+            } else if let Some(receiver) = typed.strip_suffix('.') {
+                let chain = trailing_chain(receiver);
+                match chain.split_first() {
+                    Some((entity, prefix)) if is_entity(entity) => self
+                        .attributes_for_completion()
+                        .children(entity, prefix)
+                        .into_iter()
+                        .map(|name| item(name, KIND_FIELD, &format!("{entity} attribute")))
+                        .collect(),
+                    _ => analysis::BUILTIN_METHODS
+                        .iter()
+                        .map(|m| item_with_doc(m.name, KIND_METHOD, m.signature, m.doc))
+                        .collect(),
+                }
+            } else {
+                let mut items: Vec<Value> = KEYWORDS
+                    .iter()
+                    .map(|k| item(k, KIND_KEYWORD, "keyword"))
+                    .collect();
+                let namespaces: BTreeSet<&str> = analysis::BUILTIN_FUNCTIONS
+                    .iter()
+                    .filter_map(|b| b.namespace)
+                    .collect();
+                items.extend(
+                    namespaces
+                        .into_iter()
+                        .map(|ns| item(ns, KIND_MODULE, "builtin namespace")),
+                );
+                items.extend(
+                    analysis::BUILTIN_FUNCTIONS
+                        .iter()
+                        .filter(|b| b.namespace.is_none())
+                        .map(|b| item_with_doc(b.name, KIND_FUNCTION, b.signature, b.doc)),
+                );
+                if let Some((parsed_text, outline)) = parsed {
+                    items.extend(
+                        outline.imports.iter().map(|i| {
+                            item(&i.alias, KIND_MODULE, &format!("import \"{}\"", i.path))
+                        }),
+                    );
+                    items.extend(outline.functions.iter().map(|f| {
+                        item(&f.name, KIND_FUNCTION, &declaration_header(parsed_text, f))
+                    }));
+                }
+                items
+            };
+        json!(items)
+    }
+
+    /// Functions callable as `ns::...`: an import alias's library functions,
+    /// or a builtin namespace.
+    fn namespace_items(&self, uri: &str, outline: Option<&SourceOutline>, ns: &str) -> Vec<Value> {
+        if let Some(outline) = outline.filter(|o| o.imports.iter().any(|i| i.alias == ns)) {
+            return match self.import_outline(uri, outline, ns) {
+                Some((_, lib_text, lib_outline)) => lib_outline
+                    .functions
+                    .iter()
+                    .map(|f| item(&f.name, KIND_FUNCTION, &declaration_header(&lib_text, f)))
+                    .collect(),
+                None => Vec::new(),
+            };
+        }
+        analysis::BUILTIN_FUNCTIONS
+            .iter()
+            .filter(|b| b.namespace == Some(ns))
+            .map(|b| item_with_doc(b.name, KIND_FUNCTION, b.signature, b.doc))
+            .collect()
+    }
+
+    /// Attributes from the data file plus every attribute path referenced in
+    /// an open document.
+    fn attributes_for_completion(&self) -> EntityAttributes {
+        let mut attributes = self.data_attributes.clone();
+        for (_, outline) in self.parsed.values() {
+            for attr in &outline.attributes {
+                attributes.insert(&attr.entity, &attr.path);
+            }
+        }
+        attributes
+    }
+
+    fn inlay_hints(&self, params: &Value) -> Value {
+        let Some(uri) = str_at(params, &["textDocument", "uri"]) else {
+            return json!([]);
+        };
+        let Some(text) = self.documents.get(uri) else {
+            return json!([]);
+        };
+        let Ok(outline) = analysis::outline(text) else {
+            return json!([]);
+        };
+        if outline.is_library {
+            return json!([]);
+        }
+        let modes = match analysis::rule_compile_modes(text, document_dir(uri).as_deref()) {
+            Ok(modes) => modes,
+            // The load error is already a diagnostic.
+            Err(_) => return json!([]),
+        };
+        let index = LineIndex::new(text);
+        let hints: Vec<Value> = modes
+            .into_iter()
+            .filter_map(|mode| {
+                let reason = mode.fallback?;
+                let decl = outline.rules.iter().find(|r| r.name == mode.rule)?;
+                Some(json!({
+                    "position": index.position(decl.name_span.end),
+                    "label": "AST fallback",
+                    "paddingLeft": true,
+                    "tooltip": format!("Not compiled; evaluated by the AST interpreter: {reason}"),
+                }))
+            })
+            .collect();
+        json!(hints)
+    }
+}
+
+fn str_at<'a>(value: &'a Value, path: &[&str]) -> Option<&'a str> {
+    path.iter()
+        .try_fold(value, |v, key| v.get(key))
+        .and_then(Value::as_str)
+}
+
+fn document_dir(uri: &str) -> Option<PathBuf> {
+    uri_to_path(uri)?.parent().map(Path::to_path_buf)
+}
+
+fn is_entity(name: &str) -> bool {
+    matches!(name, "user" | "actor" | "resource" | "context" | "input")
+}
+
+/// The identifier ending `text`.
+fn trailing_ident(text: &str) -> &str {
+    let start = text
+        .rfind(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .map_or(0, |i| i + 1);
+    &text[start..]
+}
+
+/// The dotted identifier chain ending `text`: `"x && user.dept"` gives
+/// `["user", "dept"]`.
+fn trailing_chain(text: &str) -> Vec<String> {
+    let mut chain = Vec::new();
+    let mut rest = text;
+    loop {
+        let ident = trailing_ident(rest);
+        if ident.is_empty() {
+            break;
+        }
+        chain.push(ident.to_string());
+        rest = &rest[..rest.len() - ident.len()];
+        match rest.strip_suffix('.') {
+            Some(r) => rest = r,
+            None => break,
+        }
+    }
+    chain.reverse();
+    chain
+}
+
+/// A declaration's first line up to its body: `func is_admin(role)`,
+/// `rule admins`.
+fn declaration_header(text: &str, decl: &Declaration) -> String {
+    let source = text.get(decl.span.start..decl.span.end).unwrap_or_default();
+    let header = source.split(":=").next().unwrap_or(source);
+    let header = header.split('{').next().unwrap_or(header);
+    header.lines().next().unwrap_or_default().trim().to_string()
+}
+
+fn code_block(code: impl AsRef<str>) -> String {
+    format!("```reap\n{}\n```", code.as_ref())
+}
+
+fn location(uri: &str, range: Value) -> Value {
+    json!({ "uri": uri, "range": range })
+}
+
+fn item(label: &str, kind: u8, detail: &str) -> Value {
+    json!({ "label": label, "kind": kind, "detail": detail })
+}
+
+fn item_with_doc(label: &str, kind: u8, detail: &str, doc: &str) -> Value {
+    json!({
+        "label": label,
+        "kind": kind,
+        "detail": detail,
+        "documentation": { "kind": "markdown", "value": doc },
+    })
+}
@@ -0,0 +1,383 @@
+//! Integration tests: the language server driven message by message, plus a
+//! stdio smoke test that frames requests to the real binary.
+
+// Test-only code: the ergonomic unwrap/expect idiom is fine here (the
+// workspace gate targets reachable production code).
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::io::{BufReader, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use serde_json::{json, Value};
+
+use reaper_lsp::document::{path_to_uri, uri_to_path, LineIndex};
+use reaper_lsp::protocol::{read_message, write_message};
+use reaper_lsp::LspServer;
+
+const POLICY: &str = r#"import "roles.reap" as roles
+
+policy docs {
+    default: deny,
+    func is_owner(u) := u == "alice",
+    rule owners {
+        allow if is_owner(user.name) && time::is_before(user.created_at, time::now())
+    }
+    rule tenants {
+        allow if context.http.headers["x-tenant"] == "acme"
+    }
+    rule admins {
+        allow if roles::is_admin(user.role) && user.profile.team == "ops" && user.tags.contains("staff")
+    }
+}
+"#;
+
+const LIBRARY: &str = r#"library roles {
+    // Administrators of any tenant.
+    func is_admin(r) := r == "admin"
+}
+"#;
+
+/// A server with `policy.reap` open next to `roles.reap` on disk.
+struct Fixture {
+    _dir: tempfile::TempDir,
+    server: LspServer,
+    uri: String,
+    lib_uri: String,
+    opened: Vec<Value>,
+}
+
+fn fixture() -> Fixture {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("roles.reap"), LIBRARY).unwrap();
+    std::fs::write(dir.path().join("policy.reap"), POLICY).unwrap();
+    let uri = path_to_uri(&dir.path().join("policy.reap"));
+    let lib_uri = path_to_uri(&dir.path().join("roles.reap"));
+    let mut server = LspServer::new();
+    server.handle_message(&request(1, "initialize", json!({ "capabilities": {} })));
+    let opened = server.handle_message(&open(&uri, POLICY));
+    Fixture {
+        _dir: dir,
+        server,
+        uri,
+        lib_uri,
+        opened,
+    }
+}
+
+fn request(id: u64, method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+}
+
+fn open(uri: &str, text: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": { "textDocument": { "uri": uri, "languageId": "reap", "version": 1, "text": text } },
+    })
+}
+
+/// LSP position of the first occurrence of `needle` (plus `shift` chars).
+fn position_of(text: &str, needle: &str, shift: usize) -> Value {
+    let offset = text.find(needle).unwrap() + shift;
+    LineIndex::new(text).position(offset)
+}
+
+fn at(f: &mut Fixture, method: &str, text: &str, needle: &str, shift: usize) -> Value {
+    let params = json!({
+        "textDocument": { "uri": f.uri },
+        "position": position_of(text, needle, shift),
+    });
+    let mut replies = f.server.handle_message(&request(7, method, params));
+    assert_eq!(replies.len(), 1);
+    replies.remove(0)["result"].take()
+}
+
+fn labels(items: &Value) -> Vec<&str> {
+    items
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["label"].as_str().unwrap())
+        .collect()
+}
+
+#[test]
+fn initialize_advertises_the_supported_features() {
+    let mut server = LspServer::new();
+    let replies = server.handle_message(&request(1, "initialize", json!({})));
+    let caps = &replies[0]["result"]["capabilities"];
+    assert_eq!(caps["textDocumentSync"]["change"], 1);
+    assert_eq!(caps["hoverProvider"], true);
+    assert_eq!(caps["definitionProvider"], true);
+    assert_eq!(caps["inlayHintProvider"], true);
+    assert_eq!(
+        caps["completionProvider"]["triggerCharacters"],
+        json!([".", ":"])
+    );
+}
+
+#[test]
+fn open_publishes_clean_diagnostics_for_a_valid_policy() {
+    let f = fixture();
+    assert_eq!(f.opened.len(), 1);
+    assert_eq!(f.opened[0]["method"], "textDocument/publishDiagnostics");
+    assert_eq!(f.opened[0]["params"]["uri"], f.uri.as_str());
+    assert_eq!(f.opened[0]["params"]["diagnostics"], json!([]));
+}
+
+#[test]
+fn change_reports_errors_with_ranges() {
+    let mut f = fixture();
+    let broken = POLICY.replace("time::now()", "time::tomorrow()");
+    let replies = f.server.handle_message(&json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didChange",
+        "params": {
+            "textDocument": { "uri": f.uri, "version": 2 },
+            "contentChanges": [{ "text": broken }],
+        },
+    }));
+    let diagnostics = replies[0]["params"]["diagnostics"].as_array().unwrap();
+    assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+    assert_eq!(diagnostics[0]["severity"], 1);
+    assert_eq!(
+        diagnostics[0]["range"]["start"],
+        position_of(&broken, "time::tomorrow", 0)
+    );
+    assert!(diagnostics[0]["message"]
+        .as_str()
+        .unwrap()
+        .contains("tomorrow"));
+}
+
+#[test]
+fn missing_import_is_a_diagnostic() {
+    let dir = tempfile::tempdir().unwrap();
+    let uri = path_to_uri(&dir.path().join("policy.reap"));
+    let mut server = LspServer::new();
+    let replies = server.handle_message(&open(&uri, POLICY));
+    let diagnostics = replies[0]["params"]["diagnostics"].as_array().unwrap();
+    assert!(!diagnostics.is_empty());
+}
+
+#[test]
+fn hover_documents_builtins_methods_and_library_functions() {
+    let mut f = fixture();
+    let builtin = at(&mut f, "textDocument/hover", POLICY, "now()", 1);
+    let value = builtin["contents"]["value"].as_str().unwrap();
+    assert!(value.contains("time::now("), "{value}");
+
+    let method = at(&mut f, "textDocument/hover", POLICY, "contains(", 2);
+    assert!(method["contents"]["value"]
+        .as_str()
+        .unwrap()
+        .contains("contains"));
+
+    let imported = at(&mut f, "textDocument/hover", POLICY, "is_admin", 3);
+    assert!(imported["contents"]["value"]
+        .as_str()
+        .unwrap()
+        .contains("func is_admin(r)"));
+
+    let nothing = at(&mut f, "textDocument/hover", POLICY, "default", 0);
+    assert!(nothing.is_null());
+}
+
+#[test]
+fn definition_crosses_import_boundaries() {
+    let mut f = fixture();
+    let location = at(&mut f, "textDocument/definition", POLICY, "is_admin", 0);
+    assert_eq!(location["uri"], f.lib_uri.as_str());
+    assert_eq!(
+        location["range"]["start"],
+        position_of(LIBRARY, "is_admin", 0)
+    );
+
+    let local = at(
+        &mut f,
+        "textDocument/definition",
+        POLICY,
+        "is_owner(user",
+        0,
+    );
+    assert_eq!(local["uri"], f.uri.as_str());
+    assert_eq!(local["range"]["start"], position_of(POLICY, "is_owner", 0));
+
+    let import = at(&mut f, "textDocument/definition", POLICY, "roles.reap", 0);
+    assert_eq!(import["uri"], f.lib_uri.as_str());
+}
+
+#[test]
+fn completion_after_namespace_lists_its_functions() {
+    let mut f = fixture();
+    let typing = POLICY.replace("time::now()", "math::");
+    f.server.handle_message(&open(&f.uri.clone(), &typing));
+    let items = at(&mut f, "textDocument/completion", &typing, "math::", 6);
+    let names = labels(&items);
+    assert!(
+        names.contains(&"abs") && names.contains(&"clamp"),
+        "{names:?}"
+    );
+    assert!(!names.contains(&"now"));
+
+    let typing = POLICY.replace("roles::is_admin", "roles::");
+    f.server.handle_message(&open(&f.uri.clone(), &typing));
+    let items = at(&mut f, "textDocument/completion", &typing, "roles::", 7);
+    assert_eq!(labels(&items), ["is_admin"]);
+}
+
+#[test]
+fn completion_after_entity_lists_attributes() {
+    let mut f = fixture();
+    let typing = POLICY.replace("user.tags.contains", "user.pro");
+    f.server.handle_message(&open(&f.uri.clone(), &typing));
+    let items = at(&mut f, "textDocument/completion", &typing, "user.pro", 8);
+    let names = labels(&items);
+    for expected in ["name", "created_at", "profile", "tags"] {
+        assert!(
+            names.contains(&expected),
+            "{expected} missing from {names:?}"
+        );
+    }
+
+    let nested = at(&mut f, "textDocument/completion", POLICY, "profile.team", 8);
+    assert_eq!(labels(&nested), ["team"]);
+}
+
+#[test]
+fn completion_reads_attributes_from_a_data_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let data = dir.path().join("entities.json");
+    std::fs::write(
+        &data,
+        json!({
+            "entities": [
+                { "id": "u1", "type": "User", "attributes": { "clearance": 3, "org": { "region": "eu" } } },
+                { "id": "d1", "type": "Document", "attributes": { "classification": "secret" } },
+            ]
+        })
+        .to_string(),
+    )
+    .unwrap();
+    let uri = path_to_uri(&dir.path().join("p.reap"));
+    let text = "policy p {\n    default: deny,\n    rule r { allow if actor. }\n}\n";
+
+    let mut server = LspServer::new();
+    server.handle_message(&request(
+        1,
+        "initialize",
+        json!({ "initializationOptions": { "dataFile": data } }),
+    ));
+    server.handle_message(&open(&uri, text));
+    let complete = |server: &mut LspServer, needle: &str, shift: usize| {
+        let mut replies = server.handle_message(&request(
+            2,
+            "textDocument/completion",
+            json!({ "textDocument": { "uri": uri }, "position": position_of(text, needle, shift) }),
+        ));
+        replies.remove(0)["result"].take()
+    };
+    let actor = complete(&mut server, "actor.", 6);
+    assert_eq!(labels(&actor), ["clearance", "org"]);
+
+    let text_resource = text.replace("actor.", "resource.");
+    server.handle_message(&open(&uri, &text_resource));
+    let mut replies = server.handle_message(&request(
+        3,
+        "textDocument/completion",
+        json!({ "textDocument": { "uri": uri }, "position": position_of(&text_resource, "resource.", 9) }),
+    ));
+    assert_eq!(labels(&replies.remove(0)["result"]), ["classification"]);
+}
+
+#[test]
+fn completion_after_other_receivers_lists_methods() {
+    let mut f = fixture();
+    let typing = POLICY.replace("time::is_before(user.created_at, time::now())", "\"abc\".");
+    f.server.handle_message(&open(&f.uri.clone(), &typing));
+    let items = at(&mut f, "textDocument/completion", &typing, "\"abc\".", 6);
+    let names = labels(&items);
+    assert!(names.contains(&"startswith") && names.contains(&"count"));
+}
+
+#[test]
+fn inlay_hint_marks_rules_falling_back_to_the_interpreter() {
+    let mut f = fixture();
+    let uri = f.uri.clone();
+    let mut replies = f.server.handle_message(&request(
+        9,
+        "textDocument/inlayHint",
+        json!({ "textDocument": { "uri": uri }, "range": {
+            "start": { "line": 0, "character": 0 }, "end": { "line": 99, "character": 0 } } }),
+    ));
+    let hints = replies.remove(0)["result"].take();
+    let hints = hints.as_array().unwrap();
+    // `owners` (a builtin call) and `tenants` (indexed context) fall back;
+    // `admins` compiles.
+    let positions: Vec<&Value> = hints.iter().map(|h| &h["position"]).collect();
+    assert_eq!(
+        positions,
+        [
+            &position_of(POLICY, "owners", 6),
+            &position_of(POLICY, "tenants", 7)
+        ]
+    );
+    assert!(hints[1]["tooltip"].as_str().unwrap().contains("`context`"));
+}
+
+#[test]
+fn unknown_requests_and_notifications() {
+    let mut server = LspServer::new();
+    let replies = server.handle_message(&request(4, "workspace/symbol", json!({})));
+    assert_eq!(replies[0]["error"]["code"], -32601);
+    let none =
+        server.handle_message(&json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }));
+    assert!(none.is_empty());
+}
+
+#[test]
+fn line_index_and_uris_round_trip() {
+    let text = "é😀x\nsecond";
+    let index = LineIndex::new(text);
+    // `x` follows one BMP char (1 unit) and one astral char (2 units).
+    let x = text.find('x').unwrap();
+    assert_eq!(index.position(x), json!({ "line": 0, "character": 3 }));
+    assert_eq!(index.offset(&index.position(x)), x);
+    assert_eq!(
+        index.offset(&json!({ "line": 1, "character": 100 })),
+        text.len()
+    );
+
+    let path = Path::new("/tmp/my policies/p#1.reap");
+    let uri = path_to_uri(path);
+    assert_eq!(uri, "file:///tmp/my%20policies/p%231.reap");
+    assert_eq!(uri_to_path(&uri).unwrap(), path);
+    assert!(uri_to_path("untitled:Untitled-1").is_none());
+}
+
+#[test]
+fn stdio_smoke_test() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_reaper-lsp"))
+        .env_remove("REAPER_LSP_DATA")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    {
+        let stdin = child.stdin.as_mut().unwrap();
+        write_message(stdin, &request(1, "initialize", json!({}))).unwrap();
+        write_message(stdin, &request(2, "shutdown", Value::Null)).unwrap();
+        write_message(stdin, &json!({ "jsonrpc": "2.0", "method": "exit" })).unwrap();
+        stdin.flush().unwrap();
+    }
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let init = read_message(&mut stdout).unwrap().unwrap();
+    assert_eq!(init["id"], 1);
+    assert_eq!(init["result"]["serverInfo"]["name"], "reaper-lsp");
+    let shutdown = read_message(&mut stdout).unwrap().unwrap();
+    assert_eq!(shutdown["id"], 2);
+    assert!(read_message(&mut stdout).unwrap().is_none());
+    assert!(child.wait().unwrap().success());
+}